@@ -6,8 +6,11 @@ pub enum SicCliOpsError {
     #[error("Unable to parse: {0}")]
     ParserError(#[from] SicParserError),
 
-    #[error("Failed to parse value of type `{typ}`, error:\n\t{err}")]
-    UnableToParseValueOfType { err: SicParserError, typ: String },
+    #[error("Unable to parse the arguments of image operation '{operation}', error:\n\t{err}")]
+    UnableToParseArguments {
+        operation: String,
+        err: SicParserError,
+    },
 
     #[error("Internal Error: {0}")]
     InternalError(InternalErrorSource),
@@ -10,9 +10,12 @@ extern crate parameterized;
 use crate::errors::SicCliOpsError;
 use crate::operations::OperationId;
 use sic_image_engine::engine::Instr;
+use sic_parser::macros::Macros;
+use std::iter::Peekable;
 use strum::VariantNames;
 
 pub mod errors;
+pub mod metadata;
 pub mod operations;
 
 pub type TResult<T> = Result<T, SicCliOpsError>;
@@ -24,7 +27,16 @@ pub type TResult<T> = Result<T, SicCliOpsError>;
 /// only the amount of arguments we expect to receive, in this case 0.
 /// Since we can rely on Clap, we left the added complexity out here.  
 pub fn create_image_ops<I: IntoIterator<Item = String>>(iter: I) -> TResult<Vec<Instr>> {
-    let mut iter = iter.into_iter();
+    create_image_ops_with_macros(iter, &Macros::default())
+}
+
+/// Like `create_image_ops`, but also expands the calls of the given macros, which are provided as
+/// `--macro <call>`, e.g. `--macro "thumb(256)"`, in the order of the other operations.
+pub fn create_image_ops_with_macros<I: IntoIterator<Item = String>>(
+    iter: I,
+    macros: &Macros,
+) -> TResult<Vec<Instr>> {
+    let mut iter = iter.into_iter().peekable();
 
     let size = if let Some(size) = iter.size_hint().1 {
         size
@@ -41,7 +53,12 @@ pub fn create_image_ops<I: IntoIterator<Item = String>>(iter: I) -> TResult<Vec<
             let operation = OperationId::try_from_name(&program_argument[2..])?;
             let inputs = take_n(&mut iter, operation)?;
             let inputs = inputs.iter().map(|v| v.as_str()).collect::<Vec<&str>>();
-            ast.push(operation.create_instruction(inputs)?);
+            ast.push(operation.create_instruction(&inputs)?);
+        } else if program_argument == "--macro" {
+            let call = iter.next().ok_or_else(|| {
+                SicCliOpsError::ExpectedArgumentForImageOperation("macro".to_string(), 0)
+            })?;
+            ast.extend(sic_parser::parse_macro_call(&call, macros)?);
         }
         // else: skip
     }
@@ -50,7 +67,7 @@ pub fn create_image_ops<I: IntoIterator<Item = String>>(iter: I) -> TResult<Vec<
 }
 
 fn take_n<I: Iterator<Item = String>>(
-    iter: &mut I,
+    iter: &mut Peekable<I>,
     operation: OperationId,
 ) -> TResult<Vec<String>> {
     let mut operation_arguments: Vec<String> = Vec::new();
@@ -66,6 +83,21 @@ fn take_n<I: Iterator<Item = String>>(
         }
     }
 
+    if operation.takes_additional_arguments() {
+        // negative numbers are values, other arguments starting with a '-' are options
+        while let Some(op_arg) =
+            iter.next_if(|arg| !arg.starts_with('-') || arg.parse::<f32>().is_ok())
+        {
+            operation_arguments.push(op_arg);
+        }
+    }
+
+    if operation.takes_optional_number() {
+        if let Some(op_arg) = iter.next_if(|arg| arg.parse::<f32>().is_ok()) {
+            operation_arguments.push(op_arg);
+        }
+    }
+
     Ok(operation_arguments)
 }
 
@@ -75,9 +107,41 @@ mod tests {
 
     mod individual_args {
         use super::*;
-        use sic_image_engine::engine::EnvItem;
+        use sic_core::image::Rgba;
+        use sic_image_engine::engine::{DeferredOp, EnvItem};
+        use sic_image_engine::wrapper::aspect_ratio::AspectRatio;
+        use sic_image_engine::wrapper::blend_mode::BlendMode;
+        use sic_image_engine::wrapper::blur_algorithm::BlurAlgorithm;
+        use sic_image_engine::wrapper::border::BorderInputs;
+        use sic_image_engine::wrapper::channel::Channel;
+        use sic_image_engine::wrapper::chroma_key::ChromaKeyInputs;
+        use sic_image_engine::wrapper::contours::ContoursInputs;
+        use sic_image_engine::wrapper::dimension::Dimension::{self, Percentage, Pixels};
+        use sic_image_engine::wrapper::extend::ExtendInputs;
         use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
+        use sic_image_engine::wrapper::find::FindInputs;
+        use sic_image_engine::wrapper::gradient::{ColorStop, Gradient};
+        use sic_image_engine::wrapper::gradient_fill::{
+            GradientFillInputs, GradientKind, GRADIENT_DEFAULT_ANGLE,
+        };
+        use sic_image_engine::wrapper::gravity::Gravity;
+        use sic_image_engine::wrapper::histogram::{
+            DrawHistogramInputs, HistogramChannels, HistogramPlacement,
+        };
         use sic_image_engine::wrapper::image_path::ImageFromPath;
+        use sic_image_engine::wrapper::kernel::{Kernel, KernelFromPath};
+        use sic_image_engine::wrapper::lut::LutFromPath;
+        use sic_image_engine::wrapper::mirror::MirrorMode;
+        use sic_image_engine::wrapper::nine_slice::NineSliceInputs;
+        use sic_image_engine::wrapper::overlay::OverlayInputs;
+        use sic_image_engine::wrapper::pad_to_ratio::PadToRatioInputs;
+        use sic_image_engine::wrapper::pixel_upscale::{PixelUpscaleAlgorithm, PixelUpscaleInputs};
+        use sic_image_engine::wrapper::redact::{RedactInputs, RedactStyle};
+        use sic_image_engine::wrapper::region::Region;
+        use sic_image_engine::wrapper::stamp::StampInputs;
+        use sic_image_engine::wrapper::tonemap::{
+            ToneMapInputs, ToneMapOperator, TONEMAP_DEFAULT_EXPOSURE,
+        };
         use sic_image_engine::ImgOp;
         use sic_testing::setup_test_image;
 
@@ -120,52 +184,228 @@ mod tests {
 
         #[parameterized(
             ops = {
+                vec!["--alpha-add"],
+                vec!["--alpha-premultiply"],
+                vec!["--alpha-remove"],
+                vec!["--alpha-threshold", "128"],
+                vec!["--auto-enhance"],
+                vec!["--background", "rgba(255, 255, 255, 255)"],
                 vec!["--blur", "1.0"],
+                vec!["--blur-algorithm", "box"],
+                vec!["--blur-algorithm", "gaussian"],
+                vec!["--border", "4", "rgba(255, 255, 255, 255)"],
+                vec!["--border", "1", "2", "3", "4", "rgba(0, 0, 0, 255)"],
+                vec!["--box-blur", "3"],
                 vec!["--brighten", "-1"],
+                vec!["--brighten", "+10"],
+                vec!["--channel-extract", "g"],
+                vec!["--channel-swap", "r", "b"],
+                vec!["--chroma-key", "rgba(0, 255, 0, 255)", "60", "10"],
+                vec!["--contours", "128"],
+                vec!["--contours", "60", "rgba(255, 0, 0, 255)", "--invert"],
                 vec!["--contrast", "1.0"],
+                vec!["--convolve", "3", "0", "-1", "0", "-1", "5", "-1", "0", "-1", "0"],
+                vec!["--convolve", "1", "2", "--invert"],
+                vec!["--convolve", "1", "-2", "-o", "out.png"],
+                vec!["--convolve-file", "kernel.txt"],
                 vec!["--crop", "0", "1", "2", "3"],
+                vec!["--crop", "10%", "10%", "90%", "90%"],
+                vec!["--crop", "0", "0", "$width", "$height/2"],
+                vec!["--crop-center", "100", "50"],
+                vec!["--crop-gravity", "bottom-left", "100", "50"],
                 vec!["--diff", "▲"],
+                vec!["--dither", "16"],
+                vec!["--draw-histogram"],
+                vec!["--draw-histogram", "overlay"],
+                vec!["--draw-histogram", "luma"],
+                vec!["--draw-histogram", "rgb", "overlay"],
+                vec!["--duotone", "rgba(0, 0, 128, 255)", "rgba(255, 200, 0, 255)"],
+                vec!["--equalize"],
+                vec!["--extend", "640", "480"],
+                vec!["--extend", "640", "480", "top-left", "rgba(0, 0, 0, 255)"],
                 vec!["--filter3x3", "1.0", "1.0", "1.0", "-1.0", "-1.0", "-1.0", "0.0", "0.0", "0.0"],
+                vec!["--find", "▲"],
+                vec!["--find", "▲", "rgba(255, 0, 0, 255)", "--invert"],
+                vec!["--fisheye", "-0.5"],
+                vec!["--flatten-checker", "8"],
+                vec!["--gradient", "linear", "rgba(0, 0, 0, 255)", "rgba(255, 255, 255, 0)", "-45"],
+                vec!["--gradient", "radial", "rgba(0, 0, 0, 0)", "rgba(0, 0, 0, 200)", "--invert"],
+                vec!["--gradient-map", "stop(0, rgba(0, 0, 0, 255))", "stop(1, rgba(255, 255, 255, 255))"],
                 vec!["--flip-horizontal"],
                 vec!["--flip-vertical"],
                 vec!["--grayscale"],
+                vec!["--grayscale-weighted", "0.2126", "0.7152", "0.0722"],
+                vec!["--gravity", "bottom-right"],
+                vec!["--gravity", "north-west"],
+                vec!["--hstack", "▲"],
                 vec!["--hue-rotate", "-1"],
                 vec!["--invert"],
+                vec!["--local-mean", "2"],
+                vec!["--local-variance", "2"],
+                vec!["--lut", "look.cube"],
+                vec!["--mask", "mask.png"],
+                vec!["--mirror", "quad"],
+                vec!["--nine-slice", "8", "8", "8", "8", "200", "100"],
+                vec!["--oil-paint", "3", "20"],
+                vec!["--opacity", "0.5"],
+                vec!["--overlay", "▲", "10", "20"],
+                vec!["--overlay", "▲", "10", "20", "0.5", "multiply"],
+                vec!["--pad-to-ratio", "16:9"],
+                vec!["--pad-to-ratio", "1:1", "rgba(255, 255, 255, 255)"],
+                vec!["--pixel-upscale", "3"],
+                vec!["--pixel-upscale", "2", "scale2x"],
+                vec!["--radial-blur", "10", "20", "0.5"],
+                vec!["--redact", "rect(10, 20, 30, 40)"],
+                vec!["--redact", "rect(10, 20, 30, 40)", "pixelate", "--invert"],
+                vec!["--region", "rect(0, 240, 640, 240)"],
                 vec!["--resize", "1", "1"],
+                vec!["--resize", "50%", "0"],
                 vec!["--preserve-aspect-ratio", "true"],
+                vec!["--sampling-filter", "box"],
                 vec!["--sampling-filter", "catmullrom"],
                 vec!["--sampling-filter", "gaussian"],
+                vec!["--sampling-filter", "hermite"],
                 vec!["--sampling-filter", "lanczos3"],
+                vec!["--sampling-filter", "mitchell"],
                 vec!["--sampling-filter", "nearest"],
                 vec!["--sampling-filter", "triangle"],
                 vec!["--rotate90"],
                 vec!["--rotate180"],
                 vec!["--rotate270"],
+                vec!["--sharpen"],
+                vec!["--sharpen", "0.5"],
+                vec!["--sharpen", "--invert"],
+                vec!["--sharpen", "in.png"],
+                vec!["--smart-crop", "100", "50"],
+                vec!["--stamp", "▲", "bottom-right", "8"],
+                vec!["--tonemap", "reinhard"],
+                vec!["--tonemap", "aces", "-1.5", "--invert"],
+                vec!["--trim"],
+                vec!["--trim", "12.5"],
+                vec!["--trim", "--invert"],
                 vec!["--unsharpen", "-1.0", "-1"],
+                vec!["--vstack", "▲"],
             },
             expected = {
+                op![ImgOp::AlphaAdd],
+                op![ImgOp::AlphaPremultiply],
+                op![ImgOp::AlphaRemove],
+                op![ImgOp::AlphaThreshold(128)],
+                op![ImgOp::AutoEnhance],
+                modifier![EnvItem::BackgroundColor(Rgba([255, 255, 255, 255]))],
                 op![ImgOp::Blur(1.0)],
+                modifier![EnvItem::BlurAlgorithm(BlurAlgorithm::Box)],
+                modifier![EnvItem::BlurAlgorithm(BlurAlgorithm::Gaussian)],
+                op![ImgOp::Border(
+                    BorderInputs::new(&[4], Rgba([255, 255, 255, 255])).unwrap()
+                )],
+                op![ImgOp::Border(
+                    BorderInputs::new(&[1, 2, 3, 4], Rgba([0, 0, 0, 255])).unwrap()
+                )],
+                op![ImgOp::BoxBlur(3)],
                 op![ImgOp::Brighten(-1)],
+                op![ImgOp::Brighten(10)],
+                op![ImgOp::ChannelExtract(Channel::Green)],
+                op![ImgOp::ChannelSwap((Channel::Red, Channel::Blue))],
+                op![ImgOp::ChromaKey(ChromaKeyInputs::new(Rgba([0, 255, 0, 255]), 60.0, 10.0))],
+                op![ImgOp::Contours(ContoursInputs::new(128, Rgba([0, 255, 0, 255])))],
+                ops![ImgOp::Contours(ContoursInputs::new(60, Rgba([255, 0, 0, 255]))), ImgOp::Invert],
                 op![ImgOp::Contrast(1.0)],
+                op![ImgOp::Convolve(Kernel::new(3, vec![0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0]).unwrap())],
+                ops![ImgOp::Convolve(Kernel::new(1, vec![2.0]).unwrap()), ImgOp::Invert],
+                op![ImgOp::Convolve(Kernel::new(1, vec![-2.0]).unwrap())],
+                op![ImgOp::ConvolveFile(KernelFromPath::new("kernel.txt".into()))],
                 op![ImgOp::Crop((0, 1, 2, 3))],
+                vec![Instr::Deferred(DeferredOp::Crop([Percentage(10.0), Percentage(10.0), Percentage(90.0), Percentage(90.0)]))],
+                vec![Instr::Deferred(DeferredOp::Crop([Pixels(0), Pixels(0), Dimension::try_from_str("$width").unwrap(), Dimension::try_from_str("$height/2").unwrap()]))],
+                op![ImgOp::CropGravity((Gravity::Center, 100, 50))],
+                op![ImgOp::CropGravity((Gravity::BottomLeft, 100, 50))],
                 op![ImgOp::Diff(ImageFromPath::new(setup_test_image("aaa.png")))],
+                op![ImgOp::Dither(16)],
+                op![ImgOp::DrawHistogram(DrawHistogramInputs::new(HistogramChannels::Luma, HistogramPlacement::Append))],
+                op![ImgOp::DrawHistogram(DrawHistogramInputs::new(HistogramChannels::Luma, HistogramPlacement::Overlay))],
+                op![ImgOp::DrawHistogram(DrawHistogramInputs::new(HistogramChannels::Luma, HistogramPlacement::Append))],
+                op![ImgOp::DrawHistogram(DrawHistogramInputs::new(HistogramChannels::Rgb, HistogramPlacement::Overlay))],
+                op![ImgOp::Duotone((Rgba([0, 0, 128, 255]), Rgba([255, 200, 0, 255])))],
+                op![ImgOp::Equalize],
+                op![ImgOp::Extend(ExtendInputs::new(
+                    (640, 480),
+                    None,
+                    None
+                ))],
+                op![ImgOp::Extend(ExtendInputs::new(
+                    (640, 480),
+                    Some(Gravity::TopLeft),
+                    Some(Rgba([0, 0, 0, 255]))
+                ))],
                 op![ImgOp::Filter3x3([1.0, 1.0, 1.0, -1.0, -1.0, -1.0, 0.0, 0.0, 0.0])],
+                op![ImgOp::Find(FindInputs::new(ImageFromPath::new(setup_test_image("aaa.png")), None))],
+                ops![ImgOp::Find(FindInputs::new(ImageFromPath::new(setup_test_image("aaa.png")), Some(Rgba([255, 0, 0, 255])))), ImgOp::Invert],
+                op![ImgOp::Fisheye(-0.5)],
+                op![ImgOp::FlattenChecker(8)],
+                op![ImgOp::Gradient(GradientFillInputs::new(GradientKind::Linear, (Rgba([0, 0, 0, 255]), Rgba([255, 255, 255, 0])), -45.0))],
+                ops![ImgOp::Gradient(GradientFillInputs::new(GradientKind::Radial, (Rgba([0, 0, 0, 0]), Rgba([0, 0, 0, 200])), GRADIENT_DEFAULT_ANGLE)), ImgOp::Invert],
+                op![ImgOp::GradientMap(Gradient::new(vec![ColorStop::new(0.0, Rgba([0, 0, 0, 255])), ColorStop::new(1.0, Rgba([255, 255, 255, 255]))]))],
                 op![ImgOp::FlipHorizontal],
                 op![ImgOp::FlipVertical],
                 op![ImgOp::GrayScale],
+                op![ImgOp::GrayscaleWeighted((0.2126, 0.7152, 0.0722))],
+                modifier![EnvItem::Gravity(Gravity::BottomRight)],
+                modifier![EnvItem::Gravity(Gravity::TopLeft)],
+                op![ImgOp::Hstack(ImageFromPath::new(setup_test_image("aaa.png")))],
                 op![ImgOp::HueRotate(-1)],
                 op![ImgOp::Invert],
+                op![ImgOp::LocalMean(2)],
+                op![ImgOp::LocalVariance(2)],
+                op![ImgOp::Lut(LutFromPath::new("look.cube".into()))],
+                modifier![EnvItem::Mask(ImageFromPath::new("mask.png".into()))],
+                op![ImgOp::Mirror(MirrorMode::Quad)],
+                op![ImgOp::NineSlice(NineSliceInputs::new((8, 8, 8, 8), (200, 100)))],
+                op![ImgOp::OilPaint((3, 20))],
+                op![ImgOp::Opacity(0.5)],
+                op![ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new(setup_test_image("aaa.png")), (10, 20)))],
+                op![ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new(setup_test_image("aaa.png")), (10, 20)).with_opacity(0.5).with_blend_mode(BlendMode::Multiply))],
+                op![ImgOp::PadToRatio(PadToRatioInputs::new(
+                    AspectRatio::try_from_str("16:9").unwrap(),
+                    None
+                ))],
+                op![ImgOp::PadToRatio(PadToRatioInputs::new(
+                    AspectRatio::try_from_str("1:1").unwrap(),
+                    Some(Rgba([255, 255, 255, 255]))
+                ))],
+                op![ImgOp::PixelUpscale(PixelUpscaleInputs::new(3, PixelUpscaleAlgorithm::Scale2x))],
+                op![ImgOp::PixelUpscale(PixelUpscaleInputs::new(2, PixelUpscaleAlgorithm::Scale2x))],
+                op![ImgOp::RadialBlur((10, 20, 0.5))],
+                op![ImgOp::Redact(RedactInputs::new(Region::new((10, 20), (30, 40)), RedactStyle::Fill))],
+                ops![ImgOp::Redact(RedactInputs::new(Region::new((10, 20), (30, 40)), RedactStyle::Pixelate)), ImgOp::Invert],
+                modifier![EnvItem::Region(Region::new((0, 240), (640, 240)))],
                 op![ImgOp::Resize((1, 1))],
+                vec![Instr::Deferred(DeferredOp::Resize([Percentage(50.0), Pixels(0)]))],
                 modifier![EnvItem::PreserveAspectRatio(true)],
+                modifier![EnvItem::CustomSamplingFilter(FilterTypeWrap::try_from_str("box").unwrap())],
                 modifier![EnvItem::CustomSamplingFilter(FilterTypeWrap::try_from_str("catmullrom").unwrap())],
                 modifier![EnvItem::CustomSamplingFilter(FilterTypeWrap::try_from_str("gaussian").unwrap())],
+                modifier![EnvItem::CustomSamplingFilter(FilterTypeWrap::try_from_str("hermite").unwrap())],
                 modifier![EnvItem::CustomSamplingFilter(FilterTypeWrap::try_from_str("lanczos3").unwrap())],
+                modifier![EnvItem::CustomSamplingFilter(FilterTypeWrap::try_from_str("mitchell").unwrap())],
                 modifier![EnvItem::CustomSamplingFilter(FilterTypeWrap::try_from_str("nearest").unwrap())],
                 modifier![EnvItem::CustomSamplingFilter(FilterTypeWrap::try_from_str("triangle").unwrap())],
                 op![ImgOp::Rotate90],
                 op![ImgOp::Rotate180],
                 op![ImgOp::Rotate270],
+                op![ImgOp::Sharpen(1.0)],
+                op![ImgOp::Sharpen(0.5)],
+                ops![ImgOp::Sharpen(1.0), ImgOp::Invert],
+                op![ImgOp::Sharpen(1.0)],
+                op![ImgOp::SmartCrop((100, 50))],
+                op![ImgOp::Stamp(StampInputs::new(ImageFromPath::new(setup_test_image("aaa.png")), Gravity::BottomRight, 8))],
+                op![ImgOp::Tonemap(ToneMapInputs::new(ToneMapOperator::Reinhard, TONEMAP_DEFAULT_EXPOSURE))],
+                ops![ImgOp::Tonemap(ToneMapInputs::new(ToneMapOperator::Aces, -1.5)), ImgOp::Invert],
+                op![ImgOp::Trim(0.0)],
+                op![ImgOp::Trim(12.5)],
+                ops![ImgOp::Trim(0.0), ImgOp::Invert],
                 op![ImgOp::Unsharpen((-1.0, -1))],
+                op![ImgOp::Vstack(ImageFromPath::new(setup_test_image("aaa.png")))],
             },
         )]
         fn create_image_ops_t_sunny(ops: Vec<&str>, expected: Vec<Instr>) {
@@ -177,8 +417,17 @@ mod tests {
         mod imageproc_ops_tests {
             use super::*;
             use sic_core::image::Rgba;
+            use sic_image_engine::wrapper::adaptive_threshold_method::AdaptiveThresholdMethod;
+            use sic_image_engine::wrapper::draw_corners::DrawCornersInputs;
+            use sic_image_engine::wrapper::draw_shape::{
+                DrawEllipseInputs, DrawLineInputs, DrawPolygonInputs, DrawRectInputs, ShapeStyle,
+            };
             use sic_image_engine::wrapper::draw_text_inner::DrawTextInner;
-            use sic_image_engine::wrapper::font_options::{FontOptions, FontScale};
+            use sic_image_engine::wrapper::font_options::{
+                FontOptions, FontScale, FontSource, FontStyle, TextAlign, TextOutline, TextShadow,
+            };
+            use sic_image_engine::wrapper::noise::{NoiseInputs, NoiseKind};
+            use sic_image_engine::wrapper::perspective::PerspectiveInputs;
             use std::path::PathBuf;
 
             ide!();
@@ -186,7 +435,11 @@ mod tests {
             #[parameterized(
                 ops = {
                     vec!["--draw-text", "my text", "coord(0, 1)", "rgba(10, 10, 255, 255)", "size(16.0)", r#"font("resources/font/Lato-Regular.ttf")"#],
-                    vec!["--draw-text", "my text", "coord(0, 1)", "rgba(10, 10, 255, 255)", "size(16.0)", r#"font("resources/font/Lato-Regular()".ttf")"#],
+                    vec!["--draw-text", "my text", "coord(0, 1)", "rgba(10, 10, 255, 255)", "size(16.0)", r#"font("resources/font/Lato-Regular()\".ttf")"#],
+                    vec!["--draw-text", "my text", "coord(0, 1)", "rgba(10, 10, 255, 255)", "size(16.0)", r#"font("Lato", "bold")"#],
+                    vec!["--draw-text", "my text", "coord(0, 1)", "rgba(10, 10, 255, 255)", "size(16.0)", r#"font("Lato")"#, "outline(2, rgba(0, 0, 0, 255))", "shadow(1, 2, rgba(0, 0, 0, 128))", "spacing(1, 0.5)"],
+                    vec!["--draw-text", "my text", "coord(0, 1)", "rgba(10, 10, 255, 255)", "size(16.0)", r#"font("Lato")"#, "angle(-45)"],
+                    vec!["--draw-text", r"my\ntext", "coord(0, 1)", "rgba(10, 10, 255, 255)", "size(16.0)", r#"font("Lato")"#, "wrap(120)", "align('right')"],
                 },
                 expected = {
                     op![ImgOp::DrawText(DrawTextInner::new("my text".to_string(),
@@ -200,7 +453,37 @@ mod tests {
                         FontOptions::new(
                         PathBuf::from("resources/font/Lato-Regular()\".ttf".to_string()),
                         Rgba([10, 10, 255, 255]),
-                        FontScale::Uniform(16.0))))]
+                        FontScale::Uniform(16.0))))],
+                    op![ImgOp::DrawText(DrawTextInner::new("my text".to_string(),
+                        (0, 1),
+                        FontOptions::new(
+                        FontSource::Family("Lato".to_string()),
+                        Rgba([10, 10, 255, 255]),
+                        FontScale::Uniform(16.0)).with_style(FontStyle { bold: true, italic: false })))],
+                    op![ImgOp::DrawText(DrawTextInner::new("my text".to_string(),
+                        (0, 1),
+                        FontOptions::new(
+                        FontSource::Family("Lato".to_string()),
+                        Rgba([10, 10, 255, 255]),
+                        FontScale::Uniform(16.0))
+                        .with_outline(TextOutline { width: 2.0, color: Rgba([0, 0, 0, 255]) })
+                        .with_shadow(TextShadow { offset: (1, 2), color: Rgba([0, 0, 0, 128]) })
+                        .with_spacing(1.0, 0.5)))],
+                    op![ImgOp::DrawText(DrawTextInner::new("my text".to_string(),
+                        (0, 1),
+                        FontOptions::new(
+                        FontSource::Family("Lato".to_string()),
+                        Rgba([10, 10, 255, 255]),
+                        FontScale::Uniform(16.0))
+                        .with_angle(-45.0)))],
+                    op![ImgOp::DrawText(DrawTextInner::new("my\ntext".to_string(),
+                        (0, 1),
+                        FontOptions::new(
+                        FontSource::Family("Lato".to_string()),
+                        Rgba([10, 10, 255, 255]),
+                        FontScale::Uniform(16.0))
+                        .with_wrap_width(120.0)
+                        .with_align(TextAlign::Right)))]
                 }
             )]
             fn create_image_ops_t_sunny_imageproc_ops(ops: Vec<&str>, expected: Vec<Instr>) {
@@ -208,6 +491,294 @@ mod tests {
 
                 assert_eq!(result.unwrap(), expected);
             }
+
+            #[parameterized(
+                ops = {
+                    vec!["--adaptive-threshold", "15"],
+                    vec!["--adaptive-threshold-method", "mean"],
+                    vec!["--adaptive-threshold-method", "gaussian"],
+                },
+                expected = {
+                    op![ImgOp::AdaptiveThreshold(15)],
+                    modifier![EnvItem::AdaptiveThresholdMethod(AdaptiveThresholdMethod::Mean)],
+                    modifier![EnvItem::AdaptiveThresholdMethod(AdaptiveThresholdMethod::Gaussian)],
+                }
+            )]
+            fn create_image_ops_t_sunny_adaptive_threshold(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--adaptive-threshold", "-15"],
+                    vec!["--adaptive-threshold-method", "median"],
+                }
+            )]
+            fn create_image_ops_t_expected_failure_adaptive_threshold(ops: Vec<&str>) {
+                let result = create_image_ops(interweave(&ops));
+                assert!(result.is_err());
+            }
+
+            #[test]
+            fn create_image_ops_t_sunny_clahe() {
+                let result = create_image_ops(interweave(&["--clahe", "8", "2.5"]));
+
+                assert_eq!(result.unwrap(), op![ImgOp::Clahe((8, 2.5))]);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-rect", "rect(1, 2, 30, 40)", "rgba(255, 0, 0, 255)"],
+                    vec!["--draw-rect", "rect(1, 2, 30, 40)", "rgba(255, 0, 0, 255)", "filled"],
+                    vec!["--draw-rect", "rect(1, 2, 30, 40)", "rgba(255, 0, 0, 255)", "stroke", "3", "--invert"],
+                },
+                expected = {
+                    op![ImgOp::DrawRect(DrawRectInputs::new((1, 2, 30, 40), Rgba([255, 0, 0, 255]), ShapeStyle::Stroke(1)))],
+                    op![ImgOp::DrawRect(DrawRectInputs::new((1, 2, 30, 40), Rgba([255, 0, 0, 255]), ShapeStyle::Filled))],
+                    ops![ImgOp::DrawRect(DrawRectInputs::new((1, 2, 30, 40), Rgba([255, 0, 0, 255]), ShapeStyle::Stroke(3))), ImgOp::Invert],
+                }
+            )]
+            fn create_image_ops_t_sunny_draw_rect(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-rect", "rect(1, 2, 30, 40)"],
+                    vec!["--draw-rect", "coord(1, 2)", "rgba(255, 0, 0, 255)"],
+                    vec!["--draw-rect", "rect(1, 2, 30, 40)", "rgba(255, 0, 0, 255)", "stroke"],
+                    vec!["--draw-rect", "rect(1, 2, 30, 40)", "rgba(255, 0, 0, 255)", "dashed"],
+                }
+            )]
+            fn create_image_ops_t_expected_failure_draw_rect(ops: Vec<&str>) {
+                let result = create_image_ops(interweave(&ops));
+                assert!(result.is_err());
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-ellipse", "coord(10, 20)", "30", "40", "rgba(255, 0, 0, 255)"],
+                    vec!["--draw-ellipse", "coord(10, 20)", "30", "30", "rgba(255, 0, 0, 255)", "filled", "--invert"],
+                },
+                expected = {
+                    op![ImgOp::DrawEllipse(DrawEllipseInputs::new((10, 20), (30, 40), Rgba([255, 0, 0, 255]), ShapeStyle::Stroke(1)))],
+                    ops![ImgOp::DrawEllipse(DrawEllipseInputs::new((10, 20), (30, 30), Rgba([255, 0, 0, 255]), ShapeStyle::Filled)), ImgOp::Invert],
+                }
+            )]
+            fn create_image_ops_t_sunny_draw_ellipse(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-ellipse", "coord(10, 20)", "30", "rgba(255, 0, 0, 255)"],
+                    vec!["--draw-ellipse", "coord(10, 20)", "30", "40", "rgba(255, 0, 0, 255)", "hollow"],
+                }
+            )]
+            fn create_image_ops_t_expected_failure_draw_ellipse(ops: Vec<&str>) {
+                let result = create_image_ops(interweave(&ops));
+                assert!(result.is_err());
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-polygon", "coord(0, 0)", "coord(10, 0)", "coord(0, 10)", "rgba(255, 0, 0, 255)"],
+                    vec!["--draw-polygon", "coord(0, 0)", "coord(10, 0)", "coord(10, 10)", "coord(0, 10)", "coord(5, 15)", "rgba(255, 0, 0, 255)", "filled", "--invert"],
+                },
+                expected = {
+                    op![ImgOp::DrawPolygon(DrawPolygonInputs::new(vec![(0, 0), (10, 0), (0, 10)], Rgba([255, 0, 0, 255]), ShapeStyle::Stroke(1)).unwrap())],
+                    ops![ImgOp::DrawPolygon(DrawPolygonInputs::new(vec![(0, 0), (10, 0), (10, 10), (0, 10), (5, 15)], Rgba([255, 0, 0, 255]), ShapeStyle::Filled).unwrap()), ImgOp::Invert],
+                }
+            )]
+            fn create_image_ops_t_sunny_draw_polygon(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-polygon", "coord(0, 0)", "coord(10, 0)", "rgba(255, 0, 0, 255)"],
+                    vec!["--draw-polygon", "coord(0, 0)", "coord(10, 0)", "coord(0, 10)", "coord(5, 15)"],
+                }
+            )]
+            fn create_image_ops_t_expected_failure_draw_polygon(ops: Vec<&str>) {
+                let result = create_image_ops(interweave(&ops));
+                assert!(result.is_err());
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-line", "coord(1, 2)", "coord(30, 40)", "rgba(255, 0, 0, 255)", "3"],
+                    vec!["--draw-arrow", "coord(1, 2)", "coord(30, 40)", "rgba(255, 0, 0, 255)", "1", "--invert"],
+                },
+                expected = {
+                    op![ImgOp::DrawLine(DrawLineInputs::new((1, 2), (30, 40), Rgba([255, 0, 0, 255]), 3))],
+                    ops![ImgOp::DrawArrow(DrawLineInputs::new((1, 2), (30, 40), Rgba([255, 0, 0, 255]), 1)), ImgOp::Invert],
+                }
+            )]
+            fn create_image_ops_t_sunny_draw_line(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-line", "coord(1, 2)", "coord(30, 40)", "rgba(255, 0, 0, 255)"],
+                    vec!["--draw-line", "coord(1, 2)", "coord(30, 40)", "rgba(255, 0, 0, 255)", "-3"],
+                    vec!["--draw-arrow", "coord(1, 2)", "rgba(255, 0, 0, 255)", "coord(30, 40)", "3"],
+                }
+            )]
+            fn create_image_ops_t_expected_failure_draw_line(ops: Vec<&str>) {
+                let result = create_image_ops(interweave(&ops));
+                assert!(result.is_err());
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-corners", "40"],
+                    vec!["--draw-corners", "20", "rgba(0, 255, 0, 255)", "--invert"],
+                },
+                expected = {
+                    op![ImgOp::DrawCorners(DrawCornersInputs::new(40, Rgba([255, 0, 0, 255])))],
+                    ops![ImgOp::DrawCorners(DrawCornersInputs::new(20, Rgba([0, 255, 0, 255]))), ImgOp::Invert],
+                }
+            )]
+            fn create_image_ops_t_sunny_draw_corners(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--draw-corners", "256"],
+                    vec!["--draw-corners", "40", "coord(1, 2)"],
+                }
+            )]
+            fn create_image_ops_t_expected_failure_draw_corners(ops: Vec<&str>) {
+                let result = create_image_ops(interweave(&ops));
+                assert!(result.is_err());
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--label-components"],
+                    vec!["--label-components", "4"],
+                    vec!["--label-components", "--invert"],
+                },
+                expected = {
+                    op![ImgOp::LabelComponents(8)],
+                    op![ImgOp::LabelComponents(4)],
+                    ops![ImgOp::LabelComponents(8), ImgOp::Invert],
+                }
+            )]
+            fn create_image_ops_t_sunny_label_components(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[test]
+            fn create_image_ops_t_sunny_distance_transform() {
+                let result = create_image_ops(interweave(&["--distance-transform", "--invert"]));
+
+                assert_eq!(
+                    result.unwrap(),
+                    ops![ImgOp::DistanceTransform, ImgOp::Invert]
+                );
+            }
+
+            #[test]
+            fn create_image_ops_t_sunny_median() {
+                let result = create_image_ops(interweave(&["--median", "2"]));
+
+                assert_eq!(result.unwrap(), op![ImgOp::Median(2)]);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--dilate", "2"],
+                    vec!["--erode", "1"],
+                    vec!["--morph-open", "3"],
+                    vec!["--morph-close", "2"],
+                },
+                expected = {
+                    op![ImgOp::Dilate(2)],
+                    op![ImgOp::Erode(1)],
+                    op![ImgOp::MorphOpen(3)],
+                    op![ImgOp::MorphClose(2)],
+                }
+            )]
+            fn create_image_ops_t_sunny_morphology(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--noise", "gaussian", "0.1"],
+                    vec!["--noise", "salt-pepper", "0.05", "42"],
+                    vec!["--noise", "gaussian", "0.1", "--invert"],
+                },
+                expected = {
+                    op![ImgOp::Noise(NoiseInputs::new(NoiseKind::Gaussian, 0.1, None))],
+                    op![ImgOp::Noise(NoiseInputs::new(NoiseKind::SaltPepper, 0.05, Some(42)))],
+                    ops![ImgOp::Noise(NoiseInputs::new(NoiseKind::Gaussian, 0.1, None)), ImgOp::Invert],
+                }
+            )]
+            fn create_image_ops_t_sunny_noise(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--noise", "perlin", "0.1"],
+                    vec!["--noise", "gaussian"],
+                    vec!["--noise", "gaussian", "0.1", "-1"],
+                }
+            )]
+            fn create_image_ops_t_expected_failure_noise(ops: Vec<&str>) {
+                let result = create_image_ops(interweave(&ops));
+                assert!(result.is_err());
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--perspective", "10", "5", "90", "0", "100", "80", "0", "75"],
+                    vec!["--perspective", "-1.5", "0", "10", "0", "10", "10", "0", "10", "--invert"],
+                },
+                expected = {
+                    op![ImgOp::Perspective(PerspectiveInputs::new([(10.0, 5.0), (90.0, 0.0), (100.0, 80.0), (0.0, 75.0)]))],
+                    ops![ImgOp::Perspective(PerspectiveInputs::new([(-1.5, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)])), ImgOp::Invert],
+                }
+            )]
+            fn create_image_ops_t_sunny_perspective(ops: Vec<&str>, expected: Vec<Instr>) {
+                let result = create_image_ops(interweave(&ops));
+
+                assert_eq!(result.unwrap(), expected);
+            }
+
+            #[parameterized(
+                ops = {
+                    vec!["--perspective", "0", "0", "1", "0", "1", "1", "0"],
+                    vec!["--perspective", "0", "0", "1", "0", "1", "1", "0", "a"],
+                }
+            )]
+            fn create_image_ops_t_expected_failure_perspective(ops: Vec<&str>) {
+                let result = create_image_ops(interweave(&ops));
+                assert!(result.is_err());
+            }
         }
 
         #[test]
@@ -255,18 +826,83 @@ mod tests {
             assert_eq!(create_image_ops(input).unwrap(), expected);
         }
 
+        #[test]
+        fn gradient_map_takes_additional_stops() {
+            let input = [
+                "--gradient-map",
+                "stop(0, rgba(0, 0, 0, 255))",
+                "stop(0.5, rgba(255, 0, 0, 255))",
+                "stop(1, rgba(255, 255, 0, 255))",
+                "--flip-horizontal",
+            ]
+            .iter()
+            .map(|v| (*v).to_string())
+            .collect::<Vec<_>>();
+
+            let expected = ops![
+                ImgOp::GradientMap(Gradient::new(vec![
+                    ColorStop::new(0.0, Rgba([0, 0, 0, 255])),
+                    ColorStop::new(0.5, Rgba([255, 0, 0, 255])),
+                    ColorStop::new(1.0, Rgba([255, 255, 0, 255])),
+                ])),
+                ImgOp::FlipHorizontal
+            ];
+
+            assert_eq!(create_image_ops(input).unwrap(), expected);
+        }
+
         #[parameterized(
             ops = {
                 vec!["--blur", "A"],
+                vec!["--border", "rgba(0, 0, 0, 255)"],
+                vec!["--border", "1", "2", "3", "4", "5", "rgba(0, 0, 0, 255)"],
                 vec!["--brighten", "-1.0"],
                 vec!["--contrast", ""],
+                vec!["--convolve", "3", "1", "1"],
+                vec!["--convolve", "2", "1", "1", "1", "1"],
                 vec!["--crop", "--crop", "0", "1", "2", "3"],
+                vec!["--crop-center", "100"],
+                vec!["--crop-gravity", "up", "100", "50"],
                 vec!["--diff"],
+                vec!["--draw-histogram", "hsv"],
+                vec!["--draw-histogram", "luma", "below"],
+                vec!["--extend", "640"],
+                vec!["--region", "coord(0, 240)"],
+                vec!["--extend", "640", "480", "up"],
+                vec!["--gravity", "middle"],
+                vec!["--gravity"],
+                vec!["--background", "white"],
+                vec!["--background", "rgba(255, 255, 255, 255)", "--gravity"],
+                vec!["--gradient", "conic", "rgba(0, 0, 0, 255)", "rgba(255, 255, 255, 255)"],
+                vec!["--gradient", "linear", "rgba(0, 0, 0, 255)"],
+                vec!["--gradient", "radial", "rgba(0, 0, 0, 255)", "rgba(255, 255, 255, 255)", "45"],
+                vec!["--gradient-map", "stop(0, rgba(0, 0, 0, 255))"],
+                vec!["--gradient-map", "stop(0, rgba(0, 0, 0, 255))", "1"],
                 vec!["--filter3x3", "[", "1.0", "1.0", "1.0", "-1.0", "-1.0", "-1.0", "0.0", "0.0", "0.0", "]"],
+                vec!["--find"],
+                vec!["--find", "▲", "red"],
+                vec!["--contours"],
+                vec!["--contours", "256"],
+                vec!["--contours", "128", "size(1)"],
+                vec!["--fisheye", "strong"],
+                vec!["--hstack"],
                 vec!["--hue-rotate", "-100.8"],
+                vec!["--mirror", "both"],
+                vec!["--tonemap", "filmic"],
+                vec!["--nine-slice", "8", "8", "8", "8", "200"],
+                vec!["--overlay", "▲", "10"],
+                vec!["--overlay", "▲", "10", "20", "add"],
+                vec!["--pad-to-ratio", "16/9"],
+                vec!["--pad-to-ratio", "16:9", "1"],
                 vec!["--resize", "1", "1", "--crop"],
+                vec!["--pixel-upscale", "2x"],
+                vec!["--pixel-upscale", "2", "xbr"],
+                vec!["--redact"],
+                vec!["--redact", "rect(10, 20, 30, 40)", "blur"],
+                vec!["--redact", "coord(10, 20)"],
                 vec!["--preserve-aspect-ratio", "yes"],
                 vec!["--sampling-filter", "tri"],
+                vec!["--smart-crop", "100"],
                 vec!["--sampling-filter", ""],
                 vec!["--unsharpen", "-1.0", "-1.0"],
             }
@@ -275,5 +911,35 @@ mod tests {
             let result = create_image_ops(interweave(&ops));
             assert!(result.is_err());
         }
+
+        #[test]
+        fn create_image_ops_with_macro_calls() {
+            let macros = Macros::from_script("def thumb(w) { resize $w $w; blur 1 }").unwrap();
+            let ops = ["--invert", "--macro", "thumb(8)", "--flip-vertical"];
+            let result = create_image_ops_with_macros(interweave(&ops), &macros);
+
+            assert_eq!(
+                result.unwrap(),
+                vec![
+                    Instr::Operation(ImgOp::Invert),
+                    Instr::Operation(ImgOp::Resize((8, 8))),
+                    Instr::Operation(ImgOp::Blur(1.0)),
+                    Instr::Operation(ImgOp::FlipVertical),
+                ]
+            );
+        }
+
+        #[parameterized(ops = {
+            vec!["--macro"],
+            vec!["--macro", "blur 1"],
+            vec!["--macro", "thumb()"],
+            vec!["--macro", "undefined(8)"],
+        })]
+        fn create_image_ops_with_macro_calls_expected_failure(ops: Vec<&str>) {
+            let macros = Macros::from_script("def thumb(w) { resize $w $w }").unwrap();
+            let result = create_image_ops_with_macros(interweave(&ops), &macros);
+
+            assert!(result.is_err());
+        }
     }
 }
@@ -0,0 +1,756 @@
+use crate::operations::OperationId;
+use serde::Serialize;
+
+/// Whether an operation id names an image operation, or a modifier which adapts the behaviour of
+/// an image operation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OperationKind {
+    Operation,
+    Modifier,
+}
+
+/// Describes an argument of an operation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+pub struct ArgumentMetadata {
+    pub name: &'static str,
+    /// The type of the argument, in the notation of the documentation, e.g. `uint` or `nv:rgba`.
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    /// The accepted values, if only part of the values of the type are accepted, e.g. `0..=1`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<&'static str>,
+    /// Whether the argument may be left out.
+    pub optional: bool,
+}
+
+/// Describes an operation, so it can be introspected by shells, user interfaces and the
+/// documentation generator.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+pub struct OperationMetadata {
+    pub name: &'static str,
+    pub kind: OperationKind,
+    /// A one line description of what the operation does.
+    pub description: &'static str,
+    pub arguments: &'static [ArgumentMetadata],
+    /// Whether more arguments may follow the listed arguments, like further color stops of a
+    /// gradient map.
+    pub variadic: bool,
+}
+
+impl OperationMetadata {
+    /// The syntax of the operation in an image script, e.g. `blur <sigma:fp>`.
+    pub fn syntax(&self) -> String {
+        let mut syntax = match self.kind {
+            OperationKind::Operation => self.name.to_string(),
+            OperationKind::Modifier => format!("set {}", self.name),
+        };
+
+        for argument in self.arguments {
+            if argument.optional {
+                syntax.push_str(&format!(" [{}:{}]", argument.name, argument.ty));
+            } else {
+                syntax.push_str(&format!(" <{}:{}>", argument.name, argument.ty));
+            }
+        }
+
+        if self.variadic {
+            syntax.push_str(" ...");
+        }
+
+        syntax
+    }
+}
+
+macro_rules! args {
+    ($(($name:expr, $ty:expr $(, $range:expr)?)),* $(,)?) => {
+        &[$(ArgumentMetadata { name: $name, ty: $ty, range: args!(@range $($range)?), optional: false }),*]
+    };
+    (@range) => { None };
+    (@range $range:expr) => { Some($range) };
+}
+
+const CHANNELS: &str = "r, g, b, a";
+
+impl OperationId {
+    /// Describes the operation: its name, a one line description and its arguments.
+    pub fn metadata(self) -> OperationMetadata {
+        use OperationKind::{Modifier, Operation};
+
+        let (kind, description, arguments): (_, _, &'static [ArgumentMetadata]) = match self {
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::AdaptiveThreshold => (
+                Operation,
+                "Binarizes the image by comparing each pixel to a threshold computed from its neighbourhood",
+                args![("block-size", "uint", "odd, > 1")],
+            ),
+            OperationId::AlphaAdd => (
+                Operation,
+                "Adds an opaque alpha channel, if the image has none",
+                args![],
+            ),
+            OperationId::AlphaPremultiply => (
+                Operation,
+                "Multiplies the color channels of each pixel by its alpha value",
+                args![],
+            ),
+            OperationId::AlphaRemove => (
+                Operation,
+                "Drops the alpha channel of the image",
+                args![],
+            ),
+            OperationId::AlphaThreshold => (
+                Operation,
+                "Makes pixels with an alpha value below the threshold fully transparent, and all others fully opaque",
+                args![("threshold", "u8")],
+            ),
+            OperationId::AutoEnhance => (
+                Operation,
+                "Stretches the contrast, boosts the saturation and sharpens the image in a single step",
+                args![],
+            ),
+            OperationId::Blur => (
+                Operation,
+                "Performs a Gaussian blur",
+                args![("sigma", "fp")],
+            ),
+            OperationId::Border => (
+                Operation,
+                "Adds a solid border around the image, with 1 to 4 widths assigned to the sides like in CSS",
+                args![("width", "uint"), ("color", "rgba")],
+            ),
+            OperationId::BoxBlur => (
+                Operation,
+                "Replaces each pixel by the mean of the pixels within a radius around it",
+                args![("radius", "uint")],
+            ),
+            OperationId::Brighten => (
+                Operation,
+                "Brightens (or, if negative, darkens) the image",
+                args![("amount", "int")],
+            ),
+            OperationId::ChannelExtract => (
+                Operation,
+                "Creates a grayscale image from a single channel",
+                args![("channel", "channel", CHANNELS)],
+            ),
+            OperationId::ChannelSwap => (
+                Operation,
+                "Swaps two channels of the image",
+                args![("channel", "channel", CHANNELS), ("channel", "channel", CHANNELS)],
+            ),
+            OperationId::ChromaKey => (
+                Operation,
+                "Makes the pixels which are close to the key color transparent",
+                args![
+                    ("key", "nv:rgba"),
+                    ("tolerance", "fp", ">= 0"),
+                    ("feather", "fp", ">= 0"),
+                ],
+            ),
+            OperationId::Contours => (
+                Operation,
+                "Traces the borders of the areas with a luminance of at least the threshold, draws them, and reports their points, optionally followed by an nv:rgba outline color (default: green)",
+                args![("threshold", "u8")],
+            ),
+            OperationId::Contrast => (
+                Operation,
+                "Adjusts the contrast of the image",
+                args![("amount", "fp")],
+            ),
+            OperationId::Convolve => (
+                Operation,
+                "Convolves the image with a square kernel of any odd size, given row by row",
+                args![("size", "uint", "odd, 1..=31"), ("weight", "fp")],
+            ),
+            OperationId::ConvolveFile => (
+                Operation,
+                "Convolves the image with a square kernel loaded from a text file, one row per line",
+                args![("path", "path")],
+            ),
+            OperationId::Crop => (
+                Operation,
+                "Crops the image to the rectangle between the top left and bottom right anchors, in pixels or as percentages of the width and height",
+                args![
+                    ("lx", "dim"),
+                    ("ly", "dim"),
+                    ("rx", "dim", "> lx"),
+                    ("ry", "dim", "> ly"),
+                ],
+            ),
+            OperationId::CropCenter => (
+                Operation,
+                "Crops a selection of the given size from the center of the image",
+                args![("width", "uint", "1..=image width"), ("height", "uint", "1..=image height")],
+            ),
+            OperationId::CropGravity => (
+                Operation,
+                "Crops a selection of the given size from the side or corner of the image given by the gravity",
+                args![
+                    (
+                        "gravity",
+                        "gravity",
+                        "top-left, top, top-right, left, center, right, bottom-left, bottom, bottom-right"
+                    ),
+                    ("width", "uint", "1..=image width"),
+                    ("height", "uint", "1..=image height"),
+                ],
+            ),
+            OperationId::Diff => (
+                Operation,
+                "Shows which pixels are the same as (white) or different from (red) those of another image",
+                args![("path", "path")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Dilate => (
+                Operation,
+                "Grows the bright areas of the grayscale image by the radius",
+                args![("radius", "uint")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DistanceTransform => (
+                Operation,
+                "Replaces each pixel of the bright areas of the binarized image by its normalized distance to the nearest dark pixel",
+                args![],
+            ),
+            OperationId::Dither => (
+                Operation,
+                "Reduces the colors of the image to a palette, with Floyd-Steinberg dithering",
+                args![("colors", "uint", "2..=256")],
+            ),
+            OperationId::DrawHistogram => (
+                Operation,
+                "Renders a histogram of the luminance (luma, default) or the color channels (rgb) of the image, optionally followed by whether it's appended below the image (default) or overlaid on its bottom",
+                args![],
+            ),
+            OperationId::Duotone => (
+                Operation,
+                "Maps the luminance of the image onto a gradient between two colors",
+                args![("shadows", "nv:rgba"), ("highlights", "nv:rgba")],
+            ),
+            OperationId::Equalize => (
+                Operation,
+                "Equalizes the histogram of the luminance of the image",
+                args![],
+            ),
+            OperationId::Extend => (
+                Operation,
+                "Places the image on a larger canvas, optionally followed by a gravity and an nv:rgba canvas color",
+                args![("width", "uint"), ("height", "uint")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Erode => (
+                Operation,
+                "Shrinks the bright areas of the grayscale image by the radius",
+                args![("radius", "uint")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Clahe => (
+                Operation,
+                "Equalizes the histogram of the luminance of each tile of the image, with a limited contrast",
+                args![("tile-size", "uint", "> 0"), ("clip-limit", "fp", "> 0")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawArrow => (
+                Operation,
+                "Draws a line with an arrowhead at its end",
+                args![
+                    ("from", "nv:coord"),
+                    ("to", "nv:coord"),
+                    ("color", "nv:rgba"),
+                    ("width", "uint", "> 0"),
+                ],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawCorners => (
+                Operation,
+                "Marks the FAST corners of the image, at which 9 contiguous pixels around the corner all differ in luminance by more than the threshold, optionally followed by an nv:rgba marker color (default: red)",
+                args![("threshold", "u8")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawEllipse => (
+                Operation,
+                "Draws an ellipse outline, or optionally 'filled' or 'stroke <width>'",
+                args![
+                    ("center", "nv:coord"),
+                    ("radius-x", "uint", "> 0"),
+                    ("radius-y", "uint", "> 0"),
+                    ("color", "nv:rgba"),
+                ],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawLine => (
+                Operation,
+                "Draws a line with rounded ends",
+                args![
+                    ("from", "nv:coord"),
+                    ("to", "nv:coord"),
+                    ("color", "nv:rgba"),
+                    ("width", "uint", "> 0"),
+                ],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawPolygon => (
+                Operation,
+                "Draws a polygon through three or more points, which are followed by its color, and optionally 'filled' or 'stroke <width>'",
+                args![
+                    ("point", "nv:coord"),
+                    ("point", "nv:coord"),
+                    ("point", "nv:coord"),
+                    ("color", "nv:rgba"),
+                ],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawRect => (
+                Operation,
+                "Draws a rectangle outline, or optionally 'filled' or 'stroke <width>'",
+                args![("rect", "nv:rect"), ("color", "nv:rgba")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawText => (
+                Operation,
+                "Draws text, optionally followed by an nv:outline, nv:shadow, nv:spacing, nv:angle, nv:wrap and nv:align",
+                args![
+                    ("text", "string"),
+                    ("coord", "nv:coord"),
+                    ("color", "nv:rgba"),
+                    ("size", "nv:size"),
+                    ("font", "nv:font"),
+                ],
+            ),
+            OperationId::Filter3x3 => (
+                Operation,
+                "Applies a 3 by 3 convolution kernel, given row by row",
+                args![
+                    ("k0", "fp"),
+                    ("k1", "fp"),
+                    ("k2", "fp"),
+                    ("k3", "fp"),
+                    ("k4", "fp"),
+                    ("k5", "fp"),
+                    ("k6", "fp"),
+                    ("k7", "fp"),
+                    ("k8", "fp"),
+                ],
+            ),
+            OperationId::Find => (
+                Operation,
+                "Finds where a template image appears by normalized cross-correlation and reports its position and score, optionally followed by an nv:rgba color in which it's outlined",
+                args![("template", "path")],
+            ),
+            OperationId::Fisheye => (
+                Operation,
+                "Bulges (positive strength) or pinches (negative strength) the center of the image",
+                args![("strength", "fp", "-1 to 1")],
+            ),
+            OperationId::FlattenChecker => (
+                Operation,
+                "Composites the image over a checkerboard and removes the alpha channel",
+                args![("cell-size", "uint", "> 0")],
+            ),
+            OperationId::FlipHorizontal => (
+                Operation,
+                "Flips the image on the horizontal axis",
+                args![],
+            ),
+            OperationId::FlipVertical => (
+                Operation,
+                "Flips the image on the vertical axis",
+                args![],
+            ),
+            OperationId::Gradient => (
+                Operation,
+                "Draws a linear or radial gradient between two colors over the image; a linear gradient runs at an optional angle in degrees, which defaults to 0 (left to right)",
+                &[
+                    ArgumentMetadata {
+                        name: "kind",
+                        ty: "value",
+                        range: Some("linear, radial"),
+                        optional: false,
+                    },
+                    ArgumentMetadata {
+                        name: "from",
+                        ty: "nv:rgba",
+                        range: None,
+                        optional: false,
+                    },
+                    ArgumentMetadata {
+                        name: "to",
+                        ty: "nv:rgba",
+                        range: None,
+                        optional: false,
+                    },
+                    ArgumentMetadata {
+                        name: "angle",
+                        ty: "fp",
+                        range: None,
+                        optional: true,
+                    },
+                ],
+            ),
+            OperationId::GradientMap => (
+                Operation,
+                "Remaps the luminance of the image through a gradient of two or more color stops",
+                args![("stop", "nv:stop"), ("stop", "nv:stop")],
+            ),
+            OperationId::Grayscale => (
+                Operation,
+                "Discards the chrominance of the image",
+                args![],
+            ),
+            OperationId::GrayscaleWeighted => (
+                Operation,
+                "Converts the image to grayscale, weighting the red, green and blue channels",
+                args![("wr", "fp", ">= 0"), ("wg", "fp", ">= 0"), ("wb", "fp", ">= 0")],
+            ),
+            OperationId::Hstack => (
+                Operation,
+                "Appends another image to the right, centered vertically and padded with transparent pixels",
+                args![("path", "path")],
+            ),
+            OperationId::HueRotate => (
+                Operation,
+                "Rotates the hue of the image by a number of degrees",
+                args![("degrees", "int")],
+            ),
+            OperationId::Invert => (
+                Operation,
+                "Inverts the colors of the image",
+                args![],
+            ),
+            OperationId::LocalMean => (
+                Operation,
+                "Replaces each pixel by the mean luminance of the pixels within a radius around it",
+                args![("radius", "uint")],
+            ),
+            OperationId::LocalVariance => (
+                Operation,
+                "Replaces each pixel by the variance of the luminance of the pixels within a radius around it",
+                args![("radius", "uint")],
+            ),
+            OperationId::Lut => (
+                Operation,
+                "Maps the colors of the image through a 3D LUT, loaded from an Adobe .cube file",
+                args![("path", "path")],
+            ),
+            OperationId::Mirror => (
+                Operation,
+                "Appends a reflection of the image to its right, below it, or both, which doubles its dimensions",
+                args![("mode", "value", "horizontal, vertical, quad")],
+            ),
+            OperationId::NineSlice => (
+                Operation,
+                "Resizes the image, but only scales the center and the edges within the insets, keeping the corners crisp",
+                args![
+                    ("left", "uint"),
+                    ("top", "uint"),
+                    ("right", "uint"),
+                    ("bottom", "uint"),
+                    ("width", "uint", ">= left + right"),
+                    ("height", "uint", ">= top + bottom"),
+                ],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::LabelComponents => (
+                Operation,
+                "Draws each connected bright area of the binarized image in a distinct color, by an optional connectivity which defaults to 8",
+                &[ArgumentMetadata {
+                    name: "connectivity",
+                    ty: "uint",
+                    range: Some("4, 8"),
+                    optional: true,
+                }],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Median => (
+                Operation,
+                "Replaces each pixel by the median of the pixels within a radius around it, which removes salt-and-pepper noise",
+                args![("radius", "uint")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::MorphClose => (
+                Operation,
+                "Dilates and then erodes the grayscale image, which fills small dark holes and gaps",
+                args![("radius", "uint")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::MorphOpen => (
+                Operation,
+                "Erodes and then dilates the grayscale image, which removes small bright specks",
+                args![("radius", "uint")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Noise => (
+                Operation,
+                "Adds random noise; with a seed, the same noise is added each time",
+                &[
+                    ArgumentMetadata {
+                        name: "kind",
+                        ty: "value",
+                        range: Some("gaussian, salt-pepper"),
+                        optional: false,
+                    },
+                    ArgumentMetadata {
+                        name: "amount",
+                        ty: "fp",
+                        range: Some("0..=1"),
+                        optional: false,
+                    },
+                    ArgumentMetadata {
+                        name: "seed",
+                        ty: "uint",
+                        range: None,
+                        optional: true,
+                    },
+                ],
+            ),
+            OperationId::OilPaint => (
+                Operation,
+                "Gives the image the look of an oil painting, by replacing each pixel by the mean color of the most common intensity level around it",
+                args![("radius", "uint"), ("levels", "uint", "1..=256")],
+            ),
+            OperationId::Opacity => (
+                Operation,
+                "Multiplies the alpha channel by the opacity",
+                args![("opacity", "fp", "0..=1")],
+            ),
+            OperationId::Overlay => (
+                Operation,
+                "Overlays an image at a position, optionally followed by an opacity (0..=1) and a blend mode",
+                args![("path", "path"), ("x", "uint"), ("y", "uint")],
+            ),
+            OperationId::PadToRatio => (
+                Operation,
+                "Pads the image as little as possible to match an aspect ratio, optionally followed by an nv:rgba padding color",
+                args![("ratio", "ratio", "<w>:<h>, e.g. 16:9")],
+            ),
+            OperationId::PixelUpscale => (
+                Operation,
+                "Upscales pixel art with Scale2x, which keeps sprites crisp and smooths diagonal edges, optionally followed by the algorithm: scale2x (the default and only algorithm)",
+                args![("factor", "uint", "2, 3, 4")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Perspective => (
+                Operation,
+                "Maps the quadrilateral with the given top left, top right, bottom right and bottom left corners onto a rectangle, e.g. for keystone correction",
+                args![
+                    ("x1", "fp"),
+                    ("y1", "fp"),
+                    ("x2", "fp"),
+                    ("y2", "fp"),
+                    ("x3", "fp"),
+                    ("y3", "fp"),
+                    ("x4", "fp"),
+                    ("y4", "fp")
+                ],
+            ),
+            OperationId::RadialBlur => (
+                Operation,
+                "Blurs the image outward from a center point, like a zoom burst",
+                args![("cx", "uint"), ("cy", "uint"), ("strength", "fp")],
+            ),
+            OperationId::Redact => (
+                Operation,
+                "Irreversibly destroys a rectangle of the image, optionally followed by whether it's filled with black (default) or pixelated with noise",
+                args![("region", "nv:rect")],
+            ),
+            OperationId::Resize => (
+                Operation,
+                "Resizes the image, to a number of pixels or to a percentage of the width and height; a dimension of 0 is derived from the aspect ratio",
+                args![("width", "dim"), ("height", "dim")],
+            ),
+            OperationId::Rotate90 => (Operation, "Rotates the image 90 degrees", args![]),
+            OperationId::Rotate180 => (Operation, "Rotates the image 180 degrees", args![]),
+            OperationId::Rotate270 => (Operation, "Rotates the image 270 degrees", args![]),
+            OperationId::Sharpen => (
+                Operation,
+                "Sharpens the image with a Laplacian kernel, by an optional amount which defaults to 1",
+                &[ArgumentMetadata {
+                    name: "amount",
+                    ty: "fp",
+                    range: Some(">= 0"),
+                    optional: true,
+                }],
+            ),
+            OperationId::SmartCrop => (
+                Operation,
+                "Crops a selection of the given size from the area of the image with the most detail",
+                args![("width", "uint", "1..=image width"), ("height", "uint", "1..=image height")],
+            ),
+            OperationId::Stamp => (
+                Operation,
+                "Overlays a stamp, such as a logo, at a side or corner, keeping it legible",
+                args![
+                    ("path", "path"),
+                    (
+                        "gravity",
+                        "gravity",
+                        "top-left, top, top-right, left, center, right, bottom-left, bottom, bottom-right"
+                    ),
+                    ("margin", "uint"),
+                ],
+            ),
+            OperationId::Tonemap => (
+                Operation,
+                "Maps the high dynamic range samples of the image to the displayable range with a tone mapping operator, after scaling them by an optional exposure in stops, which defaults to 0",
+                &[
+                    ArgumentMetadata {
+                        name: "operator",
+                        ty: "value",
+                        range: Some("reinhard, aces"),
+                        optional: false,
+                    },
+                    ArgumentMetadata {
+                        name: "exposure",
+                        ty: "fp",
+                        range: None,
+                        optional: true,
+                    },
+                ],
+            ),
+            OperationId::Trim => (
+                Operation,
+                "Removes the uniform borders of the image, whose color is taken from the top left pixel, by an optional tolerance which defaults to 0",
+                &[ArgumentMetadata {
+                    name: "tolerance",
+                    ty: "fp",
+                    range: Some(">= 0"),
+                    optional: true,
+                }],
+            ),
+            OperationId::Unsharpen => (
+                Operation,
+                "Sharpens the image with an unsharp mask",
+                args![("sigma", "fp"), ("threshold", "int")],
+            ),
+            OperationId::Vstack => (
+                Operation,
+                "Appends another image at the bottom, centered horizontally and padded with transparent pixels",
+                args![("path", "path")],
+            ),
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::AdaptiveThresholdMethod => (
+                Modifier,
+                "Computes the threshold of adaptive-threshold as the mean or gaussian weighted sum of the neighbourhood",
+                args![("method", "value", "mean, gaussian")],
+            ),
+            OperationId::Background => (
+                Modifier,
+                "Fills the canvas of extend and pad-to-ratio and the area of perspective outside of the image, if they are given no color themselves, and flattens transparent pixels onto it by alpha-remove",
+                args![("color", "rgba")],
+            ),
+            OperationId::BlurAlgorithm => (
+                Modifier,
+                "Selects the algorithm used by blur; box is much faster for large sigmas",
+                args![("algorithm", "value", "gaussian, box")],
+            ),
+            OperationId::Gravity => (
+                Modifier,
+                "Measures the coordinates of crop, overlay and draw-text from the given side or corner of the image, and places the image by extend, if extend is given no gravity itself",
+                args![(
+                    "gravity",
+                    "gravity",
+                    "top-left, top, top-right, left, center, right, bottom-left, bottom, bottom-right, or their compass directions, e.g. north-west"
+                )],
+            ),
+            OperationId::Mask => (
+                Modifier,
+                "Blends the results of the operations which follow it with the image by a grayscale mask image of the same size; they apply where the mask is white",
+                args![("mask", "path")],
+            ),
+            OperationId::PreserveAspectRatio => (
+                Modifier,
+                "Preserves the aspect ratio of the image when resizing",
+                args![("enabled", "bool")],
+            ),
+            OperationId::Region => (
+                Modifier,
+                "Restricts the operations which follow it to a rectangle of the image, which keeps its size",
+                args![("region", "nv:rect")],
+            ),
+            OperationId::SamplingFilter => (
+                Modifier,
+                "Selects the sampling filter used by resize and nine-slice",
+                args![(
+                    "filter",
+                    "value",
+                    "box, catmullrom, gaussian, hermite, lanczos3, mitchell, nearest, triangle"
+                )],
+            ),
+        };
+
+        OperationMetadata {
+            name: self.as_str(),
+            kind,
+            description,
+            arguments,
+            variadic: self.takes_additional_arguments(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn arguments_match_number_of_arguments() {
+        for id in OperationId::iter() {
+            let arguments = id.metadata().arguments;
+            let required = arguments.iter().filter(|arg| !arg.optional).count();
+            let optional = arguments.len() - required;
+
+            assert_eq!(required, id.takes_number_of_arguments(), "{}", id.as_str());
+            assert_eq!(optional > 0, id.takes_optional_number(), "{}", id.as_str());
+        }
+    }
+
+    #[test]
+    fn descriptions_are_single_lines() {
+        for id in OperationId::iter() {
+            let description = id.metadata().description;
+
+            assert!(!description.is_empty());
+            assert!(!description.contains('\n'));
+            assert!(!description.ends_with('.'));
+        }
+    }
+
+    #[test]
+    fn syntax() {
+        assert_eq!(
+            OperationId::Overlay.metadata().syntax(),
+            "overlay <path:path> <x:uint> <y:uint> ..."
+        );
+        assert_eq!(
+            OperationId::GradientMap.metadata().syntax(),
+            "gradient-map <stop:nv:stop> <stop:nv:stop> ..."
+        );
+        assert_eq!(
+            OperationId::PreserveAspectRatio.metadata().syntax(),
+            "set preserve-aspect-ratio <enabled:bool>"
+        );
+        assert_eq!(OperationId::Invert.metadata().syntax(), "invert");
+        assert_eq!(
+            OperationId::Sharpen.metadata().syntax(),
+            "sharpen [amount:fp]"
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let json = serde_json::to_value(OperationId::Opacity.metadata()).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "opacity",
+                "kind": "operation",
+                "description": "Multiplies the alpha channel by the opacity",
+                "arguments": [{ "name": "opacity", "type": "fp", "range": "0..=1", "optional": false }],
+                "variadic": false,
+            })
+        );
+    }
+}
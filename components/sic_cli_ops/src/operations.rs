@@ -1,13 +1,8 @@
 use crate::errors::{InternalErrorSource, SicCliOpsError};
+use crate::metadata::OperationKind;
 use crate::TResult;
-use sic_image_engine::engine::{EnvItem, Instr};
-use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
-use sic_image_engine::wrapper::image_path::ImageFromPath;
-use sic_image_engine::wrapper::overlay::OverlayInputs;
-use sic_image_engine::ImgOp;
-use sic_parser::errors::SicParserError;
-use sic_parser::value_parser::{Describable, ParseInputsFromIter};
-use std::fmt::Debug;
+use sic_image_engine::engine::Instr;
+use sic_parser::instruction::{parse_modifier, parse_operation};
 use std::str::FromStr;
 
 /// The enumeration of all supported operations.
@@ -17,30 +12,135 @@ use std::str::FromStr;
 #[strum(serialize_all = "kebab_case")]
 pub enum OperationId {
     // image operations
+    #[cfg(feature = "imageproc-ops")]
+    AdaptiveThreshold,
+
+    AlphaAdd,
+    AlphaPremultiply,
+    AlphaRemove,
+    AlphaThreshold,
+    AutoEnhance,
     Blur,
+    Border,
+    BoxBlur,
     Brighten,
+    ChannelExtract,
+    ChannelSwap,
+    ChromaKey,
+    Contours,
     Contrast,
+    Convolve,
+    ConvolveFile,
     Crop,
+    CropCenter,
+    CropGravity,
     Diff,
 
+    #[cfg(feature = "imageproc-ops")]
+    Dilate,
+    #[cfg(feature = "imageproc-ops")]
+    DistanceTransform,
+
+    Dither,
+    DrawHistogram,
+    Duotone,
+    Equalize,
+    Extend,
+
+    #[cfg(feature = "imageproc-ops")]
+    Erode,
+
+    #[cfg(feature = "imageproc-ops")]
+    Clahe,
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawArrow,
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawCorners,
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawEllipse,
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawLine,
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawPolygon,
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawRect,
+
     #[cfg(feature = "imageproc-ops")]
     DrawText,
 
     Filter3x3,
+    Find,
+    Fisheye,
+    FlattenChecker,
     FlipHorizontal,
     FlipVertical,
+    Gradient,
+    GradientMap,
     Grayscale,
+    GrayscaleWeighted,
+    Hstack,
     HueRotate,
     Invert,
+    LocalMean,
+    LocalVariance,
+    Lut,
+    Mirror,
+    NineSlice,
+
+    #[cfg(feature = "imageproc-ops")]
+    LabelComponents,
+
+    #[cfg(feature = "imageproc-ops")]
+    Median,
+
+    #[cfg(feature = "imageproc-ops")]
+    MorphClose,
+
+    #[cfg(feature = "imageproc-ops")]
+    MorphOpen,
+
+    #[cfg(feature = "imageproc-ops")]
+    Noise,
+
+    OilPaint,
+    Opacity,
     Overlay,
+    PadToRatio,
+    PixelUpscale,
+
+    #[cfg(feature = "imageproc-ops")]
+    Perspective,
+
+    RadialBlur,
+    Redact,
     Resize,
     Rotate90,
     Rotate180,
     Rotate270,
+    Sharpen,
+    SmartCrop,
+    Stamp,
+    Tonemap,
+    Trim,
     Unsharpen,
+    Vstack,
 
     // modifiers
+    #[cfg(feature = "imageproc-ops")]
+    AdaptiveThresholdMethod,
+
+    Background,
+    BlurAlgorithm,
+    Gravity,
+    Mask,
     PreserveAspectRatio,
+    Region,
     SamplingFilter,
 }
 
@@ -65,109 +165,178 @@ impl OperationId {
     ///     conditions into account, but they are not relevant for this particular method =).
     pub fn takes_number_of_arguments(self) -> usize {
         match self {
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::AdaptiveThreshold => 1,
+            OperationId::AlphaAdd => 0,
+            OperationId::AlphaPremultiply => 0,
+            OperationId::AlphaRemove => 0,
+            OperationId::AlphaThreshold => 1,
+            OperationId::AutoEnhance => 0,
             OperationId::Blur => 1,
+            OperationId::Border => 2,
+            OperationId::BoxBlur => 1,
             OperationId::Brighten => 1,
+            OperationId::ChannelExtract => 1,
+            OperationId::ChannelSwap => 2,
+            OperationId::ChromaKey => 3,
+            OperationId::Contours => 1,
             OperationId::Contrast => 1,
+            OperationId::Convolve => 2,
+            OperationId::ConvolveFile => 1,
             OperationId::Crop => 4,
+            OperationId::CropCenter => 2,
+            OperationId::CropGravity => 3,
             OperationId::Diff => 1,
+            OperationId::Dither => 1,
+            OperationId::DrawHistogram => 0,
+            OperationId::Duotone => 2,
+            OperationId::Equalize => 0,
+            OperationId::Extend => 2,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Clahe => 2,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawArrow => 4,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawCorners => 1,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawEllipse => 4,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawLine => 4,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawPolygon => 4,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawRect => 2,
             #[cfg(feature = "imageproc-ops")]
             OperationId::DrawText => 5,
             OperationId::Filter3x3 => 9,
+            OperationId::Find => 1,
+            OperationId::Fisheye => 1,
+            OperationId::FlattenChecker => 1,
             OperationId::FlipHorizontal => 0,
             OperationId::FlipVertical => 0,
+            OperationId::Gradient => 3,
+            OperationId::GradientMap => 2,
             OperationId::Grayscale => 0,
+            OperationId::GrayscaleWeighted => 3,
+            OperationId::Hstack => 1,
             OperationId::HueRotate => 1,
             OperationId::Invert => 0,
+            OperationId::LocalMean => 1,
+            OperationId::LocalVariance => 1,
+            OperationId::Lut => 1,
+            OperationId::Mirror => 1,
+            OperationId::NineSlice => 6,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::LabelComponents => 0,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Median => 1,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Dilate => 1,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DistanceTransform => 0,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Erode => 1,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::MorphClose => 1,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::MorphOpen => 1,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Noise => 2,
+            OperationId::OilPaint => 2,
+            OperationId::Opacity => 1,
             OperationId::Overlay => 3,
+            OperationId::PadToRatio => 1,
+            OperationId::PixelUpscale => 1,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Perspective => 8,
+            OperationId::RadialBlur => 3,
+            OperationId::Redact => 1,
             OperationId::Resize => 2,
             OperationId::Rotate90 => 0,
             OperationId::Rotate180 => 0,
             OperationId::Rotate270 => 0,
+            OperationId::Sharpen => 0,
+            OperationId::SmartCrop => 2,
+            OperationId::Stamp => 3,
+            OperationId::Tonemap => 1,
+            OperationId::Trim => 0,
             OperationId::Unsharpen => 2,
+            OperationId::Vstack => 1,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::AdaptiveThresholdMethod => 1,
+            OperationId::Background => 1,
+            OperationId::BlurAlgorithm => 1,
+            OperationId::Gravity => 1,
+            OperationId::Mask => 1,
             OperationId::PreserveAspectRatio => 1,
+            OperationId::Region => 1,
             OperationId::SamplingFilter => 1,
         }
     }
-}
 
-macro_rules! parse_inputs_by_type {
-    ($iterable:expr, $ty:ty) => {{
-        let input: Result<$ty, SicCliOpsError> =
-            ParseInputsFromIter::parse($iterable).map_err(|err| {
-                SicCliOpsError::UnableToParseValueOfType {
-                    err,
-                    typ: stringify!($ty).to_string(),
-                }
-            });
-        input
-    }};
+    /// Whether an operation accepts more arguments than the number given by
+    /// `takes_number_of_arguments`; these are all following arguments up to the next argument
+    /// which starts with `--`. E.g. a gradient map takes two or more color stops, and draw-text
+    /// optionally takes an outline, shadow and spacing after its font.
+    pub fn takes_additional_arguments(self) -> bool {
+        match self {
+            OperationId::Border => true,
+            OperationId::Contours => true,
+            OperationId::Convolve => true,
+            OperationId::DrawHistogram => true,
+            OperationId::Extend => true,
+            OperationId::Find => true,
+            OperationId::GradientMap => true,
+            OperationId::Overlay => true,
+            OperationId::PadToRatio => true,
+            OperationId::PixelUpscale => true,
+            OperationId::Redact => true,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawCorners => true,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawEllipse => true,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawPolygon => true,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawRect => true,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::DrawText => true,
+            _ => false,
+        }
+    }
+
+    /// Whether an operation accepts an optional numeric argument after the arguments given by
+    /// `takes_number_of_arguments`; the following argument is taken if it is a number. E.g.
+    /// sharpen optionally takes an amount, trim a tolerance, a gradient an angle, tonemap an exposure,
+    /// label-components a connectivity, and noise optionally takes a seed.
+    pub fn takes_optional_number(self) -> bool {
+        match self {
+            OperationId::Gradient => true,
+            OperationId::Sharpen => true,
+            OperationId::Tonemap => true,
+            OperationId::Trim => true,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::LabelComponents => true,
+            #[cfg(feature = "imageproc-ops")]
+            OperationId::Noise => true,
+            _ => false,
+        }
+    }
 }
 
 impl OperationId {
-    /// Constructs instructions for image operations which are taken as input by the image engine.
-    pub fn create_instruction<'a, T>(self, inputs: T) -> Result<Instr, SicCliOpsError>
-    where
-        T: IntoIterator,
-        T::Item: Into<Describable<'a>> + std::fmt::Debug,
-    {
-        let stmt = match self {
-            OperationId::Blur => Instr::Operation(ImgOp::Blur(parse_inputs_by_type!(inputs, f32)?)),
-            OperationId::Brighten => {
-                Instr::Operation(ImgOp::Brighten(parse_inputs_by_type!(inputs, i32)?))
-            }
-            OperationId::Contrast => {
-                Instr::Operation(ImgOp::Contrast(parse_inputs_by_type!(inputs, f32)?))
-            }
-            OperationId::Crop => Instr::Operation(ImgOp::Crop(parse_inputs_by_type!(
-                inputs,
-                (u32, u32, u32, u32)
-            )?)),
-            OperationId::Diff => {
-                Instr::Operation(ImgOp::Diff(parse_inputs_by_type!(inputs, ImageFromPath)?))
-            }
-            #[cfg(feature = "imageproc-ops")]
-            OperationId::DrawText => {
-                use sic_image_engine::wrapper::draw_text_inner::DrawTextInner;
-                Instr::Operation(ImgOp::DrawText(parse_inputs_by_type!(
-                    inputs,
-                    DrawTextInner
-                )?))
-            }
-            OperationId::Filter3x3 => {
-                Instr::Operation(ImgOp::Filter3x3(parse_inputs_by_type!(inputs, [f32; 9])?))
-            }
-            OperationId::FlipHorizontal => Instr::Operation(ImgOp::FlipHorizontal),
-            OperationId::FlipVertical => Instr::Operation(ImgOp::FlipVertical),
-            OperationId::Grayscale => Instr::Operation(ImgOp::GrayScale),
-            OperationId::HueRotate => {
-                Instr::Operation(ImgOp::HueRotate(parse_inputs_by_type!(inputs, i32)?))
-            }
-            OperationId::Invert => Instr::Operation(ImgOp::Invert),
-            OperationId::Overlay => Instr::Operation(ImgOp::Overlay(parse_inputs_by_type!(
-                inputs,
-                OverlayInputs
-            )?)),
-            OperationId::Resize => {
-                Instr::Operation(ImgOp::Resize(parse_inputs_by_type!(inputs, (u32, u32))?))
-            }
-            OperationId::Rotate90 => Instr::Operation(ImgOp::Rotate90),
-            OperationId::Rotate180 => Instr::Operation(ImgOp::Rotate180),
-            OperationId::Rotate270 => Instr::Operation(ImgOp::Rotate270),
-            OperationId::Unsharpen => {
-                Instr::Operation(ImgOp::Unsharpen(parse_inputs_by_type!(inputs, (f32, i32))?))
-            }
-
-            OperationId::PreserveAspectRatio => Instr::EnvAdd(EnvItem::PreserveAspectRatio(
-                parse_inputs_by_type!(inputs, bool)?,
-            )),
-            OperationId::SamplingFilter => {
-                let input = parse_inputs_by_type!(inputs, String)?;
-                let filter = FilterTypeWrap::try_from_str(&input)
-                    .map_err(SicParserError::FilterTypeError)?;
-                Instr::EnvAdd(EnvItem::CustomSamplingFilter(filter))
-            }
+    /// Constructs the instruction of an image operation or modifier, which is taken as input by the
+    /// image engine. Its arguments are parsed like those of the same operation or modifier in an
+    /// image operations script.
+    pub fn create_instruction(self, inputs: &[&str]) -> Result<Instr, SicCliOpsError> {
+        let instruction = match self.metadata().kind {
+            OperationKind::Operation => parse_operation(self.as_str(), inputs),
+            OperationKind::Modifier => parse_modifier(self.as_str(), inputs).map(Instr::EnvAdd),
         };
 
-        Ok(stmt)
+        instruction.map_err(|err| SicCliOpsError::UnableToParseArguments {
+            operation: self.as_str().to_string(),
+            err,
+        })
     }
 }
@@ -0,0 +1,85 @@
+//! Animated images, such as animated GIFs, consist of a sequence of frames, each of which is shown
+//! for its own delay. The image operations are applied to each frame separately, so the frames are
+//! kept as full RGBA images.
+
+use image::{DynamicImage, Frame};
+
+/// How often an animation is repeated after it has been shown once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Repeat {
+    Finite(u16),
+    Infinite,
+}
+
+/// The frames of an animated image, in the order in which they're shown. An animated image has at
+/// least one frame.
+#[derive(Clone)]
+pub struct AnimatedImage {
+    frames: Vec<Frame>,
+    repeat: Option<Repeat>,
+}
+
+impl AnimatedImage {
+    /// Returns `None` if there are no frames.
+    pub fn from_frames(frames: Vec<Frame>) -> Option<Self> {
+        if frames.is_empty() {
+            None
+        } else {
+            Some(Self {
+                frames,
+                repeat: None,
+            })
+        }
+    }
+
+    /// How often the animation is repeated, as stored by its source. `None` if the source didn't
+    /// store it, in which case viewers usually show the animation once.
+    pub fn with_repeat(mut self, repeat: Option<Repeat>) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn repeat(&self) -> Option<Repeat> {
+        self.repeat
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn into_frames(self) -> Vec<Frame> {
+        self.frames
+    }
+
+    /// The first frame, as still image.
+    pub fn first_frame(&self) -> DynamicImage {
+        DynamicImage::ImageRgba8(self.frames[0].buffer().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Delay, Rgba, RgbaImage};
+
+    fn frame(value: u8) -> Frame {
+        let buffer = RgbaImage::from_pixel(2, 1, Rgba([value, value, value, 255]));
+        Frame::from_parts(buffer, 0, 0, Delay::from_numer_denom_ms(100, 1))
+    }
+
+    #[test]
+    fn without_frames() {
+        assert!(AnimatedImage::from_frames(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn first_frame() {
+        let animation = AnimatedImage::from_frames(vec![frame(10), frame(20)]).unwrap();
+
+        assert_eq!(animation.frames().len(), 2);
+        assert_eq!(
+            animation.first_frame().to_rgba().get_pixel(0, 0),
+            &Rgba([10, 10, 10, 255])
+        );
+    }
+}
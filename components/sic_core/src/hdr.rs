@@ -0,0 +1,132 @@
+//! High dynamic range images, such as OpenEXR images, have floating point samples which aren't
+//! limited to the range [0, 1]. The DynamicImage of the image crate can't represent them, so they
+//! are stored as an [Rgba32FImage] with linear samples instead, and converted to a DynamicImage
+//! for the image operations, which don't support them.
+
+use image::buffer::ConvertBuffer;
+use image::{DynamicImage, ImageBuffer, Rgba};
+
+/// An image with linear, unclamped, 32 bit floating point RGBA samples.
+pub type Rgba32FImage = ImageBuffer<Rgba<f32>, Vec<f32>>;
+
+/// Converts a high dynamic range image to a 16 bit sRGB encoded image. Samples outside of the
+/// range [0, 1] are clamped; use a tone mapping operator first, to keep the highlights.
+pub fn to_dynamic_image(image: &Rgba32FImage) -> DynamicImage {
+    let buffer = ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+        let [r, g, b, a] = image.get_pixel(x, y).0;
+
+        Rgba([
+            to_u16(linear_to_srgb(r)),
+            to_u16(linear_to_srgb(g)),
+            to_u16(linear_to_srgb(b)),
+            to_u16(a),
+        ])
+    });
+
+    DynamicImage::ImageRgba16(buffer)
+}
+
+/// Converts an sRGB encoded image to a high dynamic range image with linear samples.
+pub fn from_dynamic_image(image: &DynamicImage) -> Rgba32FImage {
+    let buffer: ImageBuffer<Rgba<u16>, Vec<u16>> = match image {
+        DynamicImage::ImageRgba16(buffer) => buffer.clone(),
+        DynamicImage::ImageRgb16(buffer) => buffer.convert(),
+        DynamicImage::ImageLuma16(buffer) => buffer.convert(),
+        DynamicImage::ImageLumaA16(buffer) => buffer.convert(),
+        // converting the buffer wouldn't scale 8 bit samples to 16 bit
+        other => {
+            let buffer = other.to_rgba();
+
+            ImageBuffer::from_fn(buffer.width(), buffer.height(), |x, y| {
+                let [r, g, b, a] = buffer.get_pixel(x, y).0;
+                Rgba([r, g, b, a].map(|sample| u16::from(sample) * 257))
+            })
+        }
+    };
+
+    ImageBuffer::from_fn(buffer.width(), buffer.height(), |x, y| {
+        let [r, g, b, a] = buffer.get_pixel(x, y).0;
+
+        Rgba([
+            srgb_to_linear(to_f32(r)),
+            srgb_to_linear(to_f32(g)),
+            srgb_to_linear(to_f32(b)),
+            to_f32(a),
+        ])
+    })
+}
+
+/// The sRGB transfer function, for samples in the range [0, 1].
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The inverse of the sRGB transfer function, for samples in the range [0, 1].
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_u16(value: f32) -> u16 {
+    (value.clamp(0.0, 1.0) * f32::from(u16::MAX)).round() as u16
+}
+
+fn to_f32(value: u16) -> f32 {
+    f32::from(value) / f32::from(u16::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_functions_are_inverse() {
+        for i in 0..=100 {
+            let value = i as f32 / 100.0;
+            assert!((srgb_to_linear(linear_to_srgb(value)) - value).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let image = DynamicImage::ImageRgba16(ImageBuffer::from_fn(4, 3, |x, y| {
+            Rgba([x as u16 * 20_000, y as u16 * 30_000, 12_345, u16::MAX])
+        }));
+
+        let converted = to_dynamic_image(&from_dynamic_image(&image));
+
+        assert_eq!(converted.to_bytes(), image.to_bytes());
+    }
+
+    #[test]
+    fn roundtrip_8_bit() {
+        let image = DynamicImage::ImageRgb8(ImageBuffer::from_fn(4, 3, |x, y| {
+            image::Rgb([x as u8 * 60, y as u8 * 100, 17])
+        }));
+
+        let converted = to_dynamic_image(&from_dynamic_image(&image));
+
+        assert_eq!(converted.to_rgba(), image.to_rgba());
+    }
+
+    #[test]
+    fn to_dynamic_image_clamps() {
+        let image =
+            Rgba32FImage::from_raw(2, 1, vec![4.0, -1.0, 0.5, 1.0, 0.0, 1.0, 0.0, 0.5]).unwrap();
+
+        match to_dynamic_image(&image) {
+            DynamicImage::ImageRgba16(buffer) => {
+                assert_eq!(buffer.get_pixel(0, 0).0[..2], [u16::MAX, 0]);
+                assert_eq!(buffer.get_pixel(1, 0).0, [0, u16::MAX, 0, 32_768]);
+            }
+            _ => panic!("Expected a 16 bit RGBA image."),
+        }
+    }
+}
@@ -4,3 +4,6 @@
 /// sic crate.
 /// The purpose of this re-export is to have equal versions for all sic sub crates.
 pub use image;
+
+pub mod animation;
+pub mod hdr;
@@ -0,0 +1,273 @@
+use crate::engine::{Contour, ContourKind};
+use sic_core::image::GrayImage;
+
+/// The offsets of the 8 neighbours of a pixel, clockwise from the neighbour to its right.
+const NEIGHBOURS: [(i64, i64); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+const EAST: usize = 0;
+const WEST: usize = 4;
+
+/// Traces the borders between the foreground (non-zero) and background (zero) pixels of a binary
+/// image, by the border following algorithm of Suzuki and Abe (1985).
+///
+/// Each 8-connected area of foreground pixels has an outer border, and a hole border for each
+/// 4-connected area of background pixels it encloses. The points of a border are the foreground
+/// pixels along it, in tracing order: counterclockwise for outer borders, and clockwise for hole
+/// borders. Borders are returned in the order in which they are first met when scanning the image
+/// row by row, and start at the pixel at which they were met.
+pub(crate) fn find_contours(binary: &GrayImage) -> Vec<Contour> {
+    let (width, height) = binary.dimensions();
+    let mut labels = Labels::new(binary);
+    let mut contours = Vec::new();
+
+    // the number of the border which is being traced; 1 is reserved for the frame of the image
+    let mut border = 1;
+
+    for y in 1..=i64::from(height) {
+        for x in 1..=i64::from(width) {
+            let label = labels.get((x, y));
+
+            let start = if label == 1 && labels.get((x - 1, y)) == 0 {
+                Some((ContourKind::Outer, WEST))
+            } else if label >= 1 && labels.get((x + 1, y)) == 0 {
+                Some((ContourKind::Hole, EAST))
+            } else {
+                None
+            };
+
+            if let Some((kind, background)) = start {
+                border += 1;
+
+                contours.push(Contour {
+                    kind,
+                    points: labels.follow((x, y), background, border),
+                });
+            }
+        }
+    }
+
+    contours
+}
+
+/// The pixels of the binary image, surrounded by a frame of background pixels: 0 for background,
+/// 1 for foreground pixels which aren't on a traced border, and the (negated) number of the border
+/// for pixels which are.
+struct Labels {
+    values: Vec<i64>,
+    stride: i64,
+}
+
+impl Labels {
+    fn new(binary: &GrayImage) -> Self {
+        let stride = i64::from(binary.width()) + 2;
+        let mut values = vec![0; (stride * (i64::from(binary.height()) + 2)) as usize];
+
+        for (x, y, pixel) in binary.enumerate_pixels() {
+            if pixel[0] != 0 {
+                values[((i64::from(y) + 1) * stride + i64::from(x) + 1) as usize] = 1;
+            }
+        }
+
+        Self { values, stride }
+    }
+
+    fn get(&self, (x, y): (i64, i64)) -> i64 {
+        self.values[(y * self.stride + x) as usize]
+    }
+
+    fn set(&mut self, (x, y): (i64, i64), value: i64) {
+        self.values[(y * self.stride + x) as usize] = value;
+    }
+
+    /// Follows the border which starts at the given pixel, of which the neighbour in the
+    /// direction of `background` is a background pixel, and labels its pixels with its number.
+    fn follow(&mut self, start: (i64, i64), background: usize, border: i64) -> Vec<(u32, u32)> {
+        let neighbour = |(x, y): (i64, i64), direction: usize| {
+            let (dx, dy) = NEIGHBOURS[direction % 8];
+            (x + dx, y + dy)
+        };
+        let point = |(x, y): (i64, i64)| ((x - 1) as u32, (y - 1) as u32);
+
+        // clockwise from the background, the first foreground neighbour; a pixel without any is
+        // a border of its own
+        let first = match (background..background + 8)
+            .map(|direction| neighbour(start, direction))
+            .find(|&pixel| self.get(pixel) != 0)
+        {
+            Some(first) => first,
+            None => {
+                self.set(start, -border);
+                return vec![point(start)];
+            }
+        };
+
+        let mut points = Vec::new();
+        let (mut previous, mut current) = (first, start);
+
+        loop {
+            points.push(point(current));
+
+            let back = (0..8)
+                .find(|&direction| neighbour(current, direction) == previous)
+                .unwrap_or_default();
+
+            // counterclockwise from the previous pixel, the next foreground neighbour, which is at
+            // worst the previous pixel itself
+            let mut east_is_background = false;
+            let mut next = previous;
+
+            for direction in (back + 1..back + 8).rev() {
+                let pixel = neighbour(current, direction);
+
+                if self.get(pixel) != 0 {
+                    next = pixel;
+                    break;
+                }
+
+                east_is_background |= direction % 8 == EAST;
+            }
+
+            // pixels right of which the background is reached can't start a hole border later on
+            if east_is_background {
+                self.set(current, -border);
+            } else if self.get(current) == 1 {
+                self.set(current, border);
+            }
+
+            if next == start && current == first {
+                return points;
+            }
+
+            previous = current;
+            current = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::Luma;
+
+    fn binary(rows: &[&str]) -> GrayImage {
+        GrayImage::from_fn(rows[0].len() as u32, rows.len() as u32, |x, y| {
+            match rows[y as usize].as_bytes()[x as usize] {
+                b'#' => Luma([255]),
+                _ => Luma([0]),
+            }
+        })
+    }
+
+    #[test]
+    fn square() {
+        let contours = find_contours(&binary(&[
+            ".....", //
+            ".###.", //
+            ".###.", //
+            ".###.", //
+            ".....", //
+        ]));
+
+        assert_eq!(
+            contours,
+            vec![Contour {
+                kind: ContourKind::Outer,
+                points: vec![
+                    (1, 1),
+                    (1, 2),
+                    (1, 3),
+                    (2, 3),
+                    (3, 3),
+                    (3, 2),
+                    (3, 1),
+                    (2, 1)
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn ring_has_hole() {
+        let contours = find_contours(&binary(&[
+            "###", //
+            "#.#", //
+            "###", //
+        ]));
+
+        assert_eq!(contours.len(), 2);
+        assert_eq!(contours[0].kind, ContourKind::Outer);
+        assert_eq!(contours[0].points.len(), 8);
+        assert_eq!(contours[1].kind, ContourKind::Hole);
+        // only the pixels directly next to the hole are on its border
+        assert_eq!(contours[1].points, vec![(0, 1), (1, 0), (2, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn single_pixel() {
+        let contours = find_contours(&binary(&[
+            "...", //
+            ".#.", //
+            "...", //
+        ]));
+
+        assert_eq!(
+            contours,
+            vec![Contour {
+                kind: ContourKind::Outer,
+                points: vec![(1, 1)],
+            }]
+        );
+    }
+
+    #[test]
+    fn line_is_traced_forth_and_back() {
+        let contours = find_contours(&binary(&["###"]));
+
+        assert_eq!(contours.len(), 1);
+        assert_eq!(contours[0].points, vec![(0, 0), (1, 0), (2, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn separate_areas() {
+        let contours = find_contours(&binary(&[
+            "#..#", //
+            "#..#", //
+            "....", //
+            ".##.", //
+        ]));
+
+        let starts = contours
+            .iter()
+            .map(|contour| contour.points[0])
+            .collect::<Vec<_>>();
+
+        assert_eq!(starts, vec![(0, 0), (3, 0), (1, 3)]);
+        assert!(contours
+            .iter()
+            .all(|contour| contour.kind == ContourKind::Outer));
+    }
+
+    #[test]
+    fn diagonal_neighbours_are_connected() {
+        let contours = find_contours(&binary(&[
+            "#.", //
+            ".#", //
+        ]));
+
+        assert_eq!(contours.len(), 1);
+    }
+
+    #[test]
+    fn background_only() {
+        assert!(find_contours(&binary(&["...", "..."])).is_empty());
+    }
+}
@@ -0,0 +1,8 @@
+//! Analyses which estimate properties of an image, such as which of its areas are interesting,
+//! where a template appears within it, or where the borders of its bright areas lie, for
+//! operations which adapt themselves to the contents of the image, or which report what they
+//! found.
+
+pub(crate) mod contours;
+pub(crate) mod saliency;
+pub(crate) mod template_match;
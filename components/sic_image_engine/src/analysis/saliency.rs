@@ -0,0 +1,118 @@
+use crate::operations::summed_area_table::{SummedAreaTable, Window};
+use sic_core::image::{DynamicImage, GrayImage};
+
+/// Estimates where the most interesting `width` by `height` area of the image lies, and returns
+/// its top left corner. The area should fit within the image.
+///
+/// Interesting areas are estimated by their edge density: the sum of the gradient magnitudes of
+/// the luminance within the area. Areas with subjects, text or other details have many edges,
+/// while backgrounds such as the sky, a wall or a blurred backdrop have few. If several areas are
+/// equally interesting, the one closest to the center of the image is picked.
+pub(crate) fn most_salient_area(image: &DynamicImage, (width, height): (u32, u32)) -> (u32, u32) {
+    let luma = image.to_luma();
+    let (image_width, image_height) = luma.dimensions();
+
+    let table = SummedAreaTable::from_fn(image_width, image_height, |x, y| {
+        u64::from(gradient_magnitude(&luma, x, y))
+    });
+
+    let (free_x, free_y) = (image_width - width, image_height - height);
+    // twice the distance to the center, so it stays a whole number
+    let off_center = |x: u32, y: u32| {
+        let dx = i64::from(2 * x) - i64::from(free_x);
+        let dy = i64::from(2 * y) - i64::from(free_y);
+        dx * dx + dy * dy
+    };
+
+    let mut best = (free_x / 2, free_y / 2);
+    let mut best_score = table.sum(&Window::new(best.0, best.1, width, height));
+
+    for y in 0..=free_y {
+        for x in 0..=free_x {
+            let score = table.sum(&Window::new(x, y, width, height));
+
+            if score > best_score
+                || (score == best_score && off_center(x, y) < off_center(best.0, best.1))
+            {
+                best = (x, y);
+                best_score = score;
+            }
+        }
+    }
+
+    best
+}
+
+/// The sum of the absolute differences between the luminance of a pixel, and the luminance of its
+/// right and bottom neighbours.
+fn gradient_magnitude(luma: &GrayImage, x: u32, y: u32) -> u32 {
+    let (width, height) = luma.dimensions();
+    let at = |x: u32, y: u32| i32::from(luma.get_pixel(x, y)[0]);
+
+    let dx = if x + 1 < width {
+        at(x + 1, y) - at(x, y)
+    } else {
+        0
+    };
+    let dy = if y + 1 < height {
+        at(x, y + 1) - at(x, y)
+    } else {
+        0
+    };
+
+    dx.unsigned_abs() + dy.unsigned_abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Luma, Rgb, RgbImage};
+
+    /// A gray image with a checkerboard patch of `size` by `size` pixels at `(x, y)`.
+    fn patch_at(x: u32, y: u32, size: u32) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(40, 30, |px, py| {
+            let inside = (x..x + size).contains(&px) && (y..y + size).contains(&py);
+
+            if inside && (px + py) & 1 == 0 {
+                Luma([255])
+            } else {
+                Luma([128])
+            }
+        }))
+    }
+
+    #[test]
+    fn finds_the_detailed_area() {
+        let image = patch_at(28, 2, 8);
+
+        let (x, y) = most_salient_area(&image, (10, 10));
+
+        // the window holds the whole patch
+        assert!(x <= 28 && x + 10 >= 36, "x = {}", x);
+        assert!(y <= 2 && y + 10 >= 10, "y = {}", y);
+    }
+
+    #[test]
+    fn uniform_image_is_centered() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(40, 30, Rgb([10, 20, 30])));
+
+        assert_eq!(most_salient_area(&image, (10, 10)), (15, 10));
+    }
+
+    #[test]
+    fn whole_image() {
+        let image = patch_at(0, 0, 4);
+
+        assert_eq!(most_salient_area(&image, (40, 30)), (0, 0));
+    }
+
+    #[test]
+    fn gradient_at_the_edges() {
+        let luma = GrayImage::from_raw(2, 2, vec![0, 10, 30, 0]).unwrap();
+
+        assert_eq!(gradient_magnitude(&luma, 0, 0), 10 + 30);
+        assert_eq!(gradient_magnitude(&luma, 1, 0), 10);
+        assert_eq!(gradient_magnitude(&luma, 0, 1), 30);
+        assert_eq!(gradient_magnitude(&luma, 1, 1), 0);
+    }
+}
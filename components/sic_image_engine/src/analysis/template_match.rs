@@ -0,0 +1,156 @@
+use crate::operations::summed_area_table::{SummedAreaTable, Window};
+use sic_core::image::{DynamicImage, GenericImageView, GrayImage};
+
+/// The area of the image which is most similar to a template, as found by [`best_match`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TemplateMatch {
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// The normalized cross-correlation of the area and the template, between -1 and 1.
+    pub(crate) score: f32,
+}
+
+/// Slides the template over the image, and returns the position at which the luminance of the
+/// template and the area of the image below it have the highest zero-mean normalized
+/// cross-correlation. The template should fit within the image.
+///
+/// The correlation is insensitive to differences in brightness and contrast: an area scores 1 if
+/// its luminance is a brightened or contrast stretched copy of the template. Where either the
+/// template or the area has a single, uniform luminance, the correlation is undefined, and the
+/// area scores 0. If several areas score equally, the top-most, then left-most one is picked.
+pub(crate) fn best_match(image: &DynamicImage, template: &DynamicImage) -> TemplateMatch {
+    let luma = image.to_luma();
+    let (image_width, image_height) = luma.dimensions();
+    let (width, height) = template.dimensions();
+
+    let template = template.to_luma();
+    let area = f64::from(width) * f64::from(height);
+
+    let template_mean = template.pixels().map(|p| f64::from(p[0])).sum::<f64>() / area;
+    // the template with its mean subtracted, as the sum of the products with it doesn't depend
+    // on the mean of the area of the image, which therefore doesn't need to be subtracted too
+    let centered = template
+        .pixels()
+        .map(|p| f64::from(p[0]) - template_mean)
+        .collect::<Vec<_>>();
+    let template_deviation = centered.iter().map(|v| v * v).sum::<f64>().sqrt();
+
+    let sums = SummedAreaTable::from_fn(image_width, image_height, |x, y| {
+        u64::from(luma.get_pixel(x, y)[0])
+    });
+    let squared_sums = SummedAreaTable::from_fn(image_width, image_height, |x, y| {
+        u64::from(luma.get_pixel(x, y)[0]).pow(2)
+    });
+
+    let mut best = TemplateMatch {
+        x: 0,
+        y: 0,
+        width,
+        height,
+        score: f32::NEG_INFINITY,
+    };
+
+    for y in 0..=image_height - height {
+        for x in 0..=image_width - width {
+            let window = Window::new(x, y, width, height);
+            let sum = sums.sum(&window) as f64;
+            let deviation = (squared_sums.sum(&window) as f64 - sum * sum / area)
+                .max(0.0)
+                .sqrt();
+
+            let score = if deviation * template_deviation > f64::EPSILON {
+                cross_correlation(&luma, &centered, (x, y), (width, height))
+                    / (deviation * template_deviation)
+            } else {
+                0.0
+            } as f32;
+
+            if score > best.score {
+                best = TemplateMatch {
+                    x,
+                    y,
+                    score,
+                    ..best
+                };
+            }
+        }
+    }
+
+    best
+}
+
+/// The sum of the products of the luminance of the area of the image with its top left corner at
+/// `(x, y)`, and the corresponding values of the centered template.
+fn cross_correlation(
+    luma: &GrayImage,
+    centered: &[f64],
+    (x, y): (u32, u32),
+    (width, height): (u32, u32),
+) -> f64 {
+    (0..height)
+        .flat_map(|ty| (0..width).map(move |tx| (tx, ty)))
+        .zip(centered)
+        .map(|((tx, ty), value)| f64::from(luma.get_pixel(x + tx, y + ty)[0]) * value)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::Luma;
+
+    fn noise(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, y| {
+            Luma([((x * 37 + y * 91 + x * y * 13) % 251) as u8])
+        })
+    }
+
+    #[test]
+    fn finds_cropped_area() {
+        let image = DynamicImage::ImageLuma8(noise(20, 16));
+        let template = image.crop_imm(7, 5, 6, 4);
+
+        let found = best_match(&image, &template);
+
+        assert_eq!((found.x, found.y, found.width, found.height), (7, 5, 6, 4));
+        assert!((found.score - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn insensitive_to_brightness_and_contrast() {
+        let image = noise(20, 16);
+        let template = GrayImage::from_fn(5, 5, |x, y| {
+            Luma([image.get_pixel(11 + x, 3 + y)[0] / 2 + 40])
+        });
+
+        let found = best_match(
+            &DynamicImage::ImageLuma8(image),
+            &DynamicImage::ImageLuma8(template),
+        );
+
+        assert_eq!((found.x, found.y), (11, 3));
+        assert!(found.score > 0.99);
+    }
+
+    #[test]
+    fn template_as_large_as_image() {
+        let image = DynamicImage::ImageLuma8(noise(4, 3));
+
+        let found = best_match(&image, &image);
+
+        assert_eq!((found.x, found.y, found.width, found.height), (0, 0, 4, 3));
+    }
+
+    #[test]
+    fn uniform_template_scores_zero() {
+        let image = DynamicImage::ImageLuma8(noise(8, 8));
+        let template = DynamicImage::ImageLuma8(GrayImage::from_pixel(2, 2, Luma([100])));
+
+        let found = best_match(&image, &template);
+
+        assert_eq!((found.x, found.y), (0, 0));
+        assert_eq!(found.score, 0.0);
+    }
+}
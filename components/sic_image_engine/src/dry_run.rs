@@ -0,0 +1,543 @@
+//! A dry run follows the instructions of an image program without an image: it resolves the
+//! inputs of deferred operations, tracks the modifiers each operation would be applied with, and
+//! predicts the dimensions of the image after each instruction, so a program can be explained
+//! without decoding, operating on, or encoding any pixels.
+
+use std::collections::HashMap;
+
+use crate::engine::{
+    gravity_or_none, mask_or_none, region_or_none, CropSelection, Env, EnvItem, Instr, ItemName,
+    Occurrence,
+};
+use crate::errors::SicImageEngineError;
+use crate::wrapper::expression::ImageProperties;
+use crate::wrapper::histogram::HistogramPlacement;
+use crate::wrapper::mirror::MirrorMode;
+use crate::ImgOp;
+
+/// An instruction as it would be applied, and the dimensions of the image after it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Step {
+    /// The instruction, of which the inputs are resolved if it's a deferred operation and the
+    /// dimensions of the image are known.
+    pub instruction: Instr,
+    /// The modifiers the operation would be applied with, ordered by name; modifiers which don't
+    /// affect the operation are left out. Empty for instructions which aren't operations.
+    pub modifiers: Vec<EnvItem>,
+    /// The dimensions of the image after the instruction, if they can be predicted. They can't
+    /// after `trim`, which depends on the pixels of the image, nor after operations of which the
+    /// result depends on dimensions which aren't known.
+    pub dimensions: Option<(u32, u32)>,
+}
+
+pub struct DryRun {
+    environment: Env,
+    /// The environment items which were added for a single occurrence of an operation only.
+    occurrence_environments: HashMap<Occurrence, Env>,
+    /// The number of operations followed so far, by name.
+    occurrences: HashMap<String, usize>,
+    dimensions: Option<(u32, u32)>,
+    /// The number of frames of the input image, as referred to by `$frames`.
+    frames: usize,
+}
+
+impl DryRun {
+    /// A dry run for an input image of the given dimensions, or of unknown dimensions.
+    pub fn new(dimensions: Option<(u32, u32)>) -> Self {
+        Self {
+            environment: Env::default(),
+            occurrence_environments: HashMap::new(),
+            occurrences: HashMap::new(),
+            dimensions,
+            frames: 1,
+        }
+    }
+
+    /// Sets the number of frames of the input image; by default 1.
+    pub fn with_frame_count(mut self, frames: usize) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    /// Follows a single instruction. Fails where applying the instruction would fail for reasons
+    /// which don't depend on the pixels of the image, e.g. a crop selection outside of the image.
+    pub fn step(&mut self, instruction: &Instr) -> Result<Step, SicImageEngineError> {
+        match instruction {
+            Instr::Operation(op) => self.operation(op),
+            Instr::Deferred(op) => match self.dimensions {
+                Some((width, height)) => self.operation(&op.resolve(&ImageProperties {
+                    width,
+                    height,
+                    frames: self.frames,
+                })?),
+                None => {
+                    let environment = self.environment_of(op.as_ref());
+                    self.dimensions = None;
+                    Ok(self.operation_step(instruction.clone(), &environment))
+                }
+            },
+            Instr::EnvAdd(item) => {
+                self.environment.insert_or_update(item.clone());
+                Ok(self.step_without_operation(instruction))
+            }
+            Instr::EnvAddFor(occurrence, item) => {
+                self.occurrence_environments
+                    .entry(occurrence.clone())
+                    .or_default()
+                    .insert_or_update(item.clone());
+                Ok(self.step_without_operation(instruction))
+            }
+            Instr::EnvRemove(key) => {
+                self.environment.remove(*key);
+                Ok(self.step_without_operation(instruction))
+            }
+        }
+    }
+
+    fn operation(&mut self, operation: &ImgOp) -> Result<Step, SicImageEngineError> {
+        let mut environment = self.environment_of(operation.as_ref());
+        self.dimensions = dimensions_in_env(operation, self.dimensions, &mut environment)?;
+
+        Ok(self.operation_step(Instr::Operation(operation.clone()), &environment))
+    }
+
+    /// Counts the operation as followed, and returns the environment it would be applied in: the
+    /// environment, with the items which were added to this occurrence of the operation, if any.
+    fn environment_of(&mut self, operation: &str) -> Env {
+        let count = self.occurrences.entry(operation.to_string()).or_insert(0);
+        let occurrence = Occurrence::of(operation, *count);
+        *count += 1;
+
+        let mut environment = self.environment.clone();
+        if let Some(occurrence_environment) = self.occurrence_environments.remove(&occurrence) {
+            environment.extend(occurrence_environment);
+        }
+
+        environment
+    }
+
+    fn operation_step(&self, instruction: Instr, environment: &Env) -> Step {
+        let uses_modifier = |item: &&EnvItem| {
+            let modifier = ItemName::from(*item);
+
+            match &instruction {
+                Instr::Operation(op) => op.uses_modifier(modifier),
+                Instr::Deferred(op) => op.uses_modifier(modifier),
+                _ => false,
+            }
+        };
+
+        Step {
+            modifiers: environment
+                .items()
+                .into_iter()
+                .filter(uses_modifier)
+                .cloned()
+                .collect(),
+            instruction,
+            dimensions: self.dimensions,
+        }
+    }
+
+    fn step_without_operation(&self, instruction: &Instr) -> Step {
+        Step {
+            instruction: instruction.clone(),
+            modifiers: Vec::new(),
+            dimensions: self.dimensions,
+        }
+    }
+}
+
+/// The dimensions of the image after the operation, which, like the engine, applies a mask or a
+/// region if the environment has one; operations within them can't change the size of the image.
+fn dimensions_in_env(
+    operation: &ImgOp,
+    dimensions: Option<(u32, u32)>,
+    env: &mut Env,
+) -> Result<Option<(u32, u32)>, SicImageEngineError> {
+    let (mask, (width, height)) = match (mask_or_none(env), dimensions) {
+        (Some(mask), Some(dimensions)) => (mask, dimensions),
+        (Some(_), None) => return Ok(None),
+        (None, _) => return unmasked_dimensions(operation, dimensions, env),
+    };
+
+    let (mask_width, mask_height) = mask.dimensions()?;
+    if (mask_width, mask_height) != (width, height) {
+        return Err(SicImageEngineError::MaskSizeMismatch(
+            mask_width,
+            mask_height,
+            width,
+            height,
+        ));
+    }
+
+    match unmasked_dimensions(operation, dimensions, env)? {
+        Some((result_width, result_height)) if (result_width, result_height) != (width, height) => {
+            Err(SicImageEngineError::MaskSizeChanged(
+                width,
+                height,
+                result_width,
+                result_height,
+            ))
+        }
+        _ => Ok(dimensions),
+    }
+}
+
+fn unmasked_dimensions(
+    operation: &ImgOp,
+    dimensions: Option<(u32, u32)>,
+    env: &mut Env,
+) -> Result<Option<(u32, u32)>, SicImageEngineError> {
+    let (region, (width, height)) = match (region_or_none(env), dimensions) {
+        (Some(region), Some(dimensions)) => (region, dimensions),
+        (Some(_), None) => return Ok(None),
+        (None, _) => return operation_dimensions(operation, dimensions, env),
+    };
+
+    let (_, size) = region.clip((width, height)).ok_or_else(|| {
+        SicImageEngineError::RegionOutsideImage(
+            region.x(),
+            region.y(),
+            region.width(),
+            region.height(),
+            width,
+            height,
+        )
+    })?;
+
+    match operation_dimensions(operation, Some(size), env)? {
+        Some((result_width, result_height)) if (result_width, result_height) != size => Err(
+            SicImageEngineError::RegionSizeChanged(size.0, size.1, result_width, result_height),
+        ),
+        _ => Ok(dimensions),
+    }
+}
+
+/// The dimensions of the image after the operation; operations which produce an image of a given
+/// size are predicted even if the dimensions of the image aren't known.
+fn operation_dimensions(
+    operation: &ImgOp,
+    dimensions: Option<(u32, u32)>,
+    env: &mut Env,
+) -> Result<Option<(u32, u32)>, SicImageEngineError> {
+    let size = match operation {
+        ImgOp::Crop((lx, ly, rx, ry)) => {
+            let selection = CropSelection::new(*lx, *ly, *rx, *ry);
+            selection.dimensions_are_ok()?;
+
+            if let Some(dimensions) = dimensions {
+                match gravity_or_none(env) {
+                    Some(gravity) => selection
+                        .placed_by(gravity, dimensions)?
+                        .fits_within(dimensions)
+                        .map(drop)?,
+                    None => selection.fits_within(dimensions).map(drop)?,
+                }
+            }
+
+            selection.size()
+        }
+        ImgOp::CropGravity((_, width, height)) | ImgOp::SmartCrop((width, height)) => {
+            (*width, *height)
+        }
+        ImgOp::Extend(inputs) => {
+            let (canvas_width, canvas_height) = inputs.size();
+
+            match dimensions {
+                Some((width, height)) if canvas_width < width || canvas_height < height => {
+                    return Err(SicImageEngineError::ExtendCanvasTooSmall(
+                        canvas_width,
+                        canvas_height,
+                        width,
+                        height,
+                    ));
+                }
+                _ => inputs.size(),
+            }
+        }
+        ImgOp::NineSlice(inputs) => inputs.size(),
+        #[cfg(feature = "imageproc-ops")]
+        ImgOp::Perspective(inputs) => crate::operations::perspective::output_size(inputs)?,
+        ImgOp::Resize((width, height)) => {
+            // by default, the aspect ratio is not preserved
+            let preserve_aspect_ratio = matches!(
+                env.get(ItemName::PreserveAspectRatio),
+                Some(EnvItem::PreserveAspectRatio(true))
+            );
+
+            match dimensions {
+                Some(dimensions) => crate::operations::resample::resized_dimensions(
+                    dimensions,
+                    (*width, *height),
+                    preserve_aspect_ratio,
+                )?,
+                None if *width != 0 && *height != 0 && !preserve_aspect_ratio => (*width, *height),
+                None => return Ok(None),
+            }
+        }
+        ImgOp::Trim(_) => return Ok(None),
+        operation => match dimensions {
+            Some(dimensions) => relative_dimensions(operation, dimensions)?,
+            None => return Ok(None),
+        },
+    };
+
+    Ok(Some(size))
+}
+
+/// The dimensions of the image after an operation of which they depend on the dimensions of the
+/// image; most operations keep them.
+fn relative_dimensions(
+    operation: &ImgOp,
+    (width, height): (u32, u32),
+) -> Result<(u32, u32), SicImageEngineError> {
+    let size = match operation {
+        ImgOp::Border(border) => {
+            let grow = |size: u32, a: u32, b: u32| {
+                size.checked_add(a)
+                    .and_then(|size| size.checked_add(b))
+                    .ok_or(SicImageEngineError::BorderTooLarge)
+            };
+
+            (
+                grow(width, border.left(), border.right())?,
+                grow(height, border.top(), border.bottom())?,
+            )
+        }
+        ImgOp::Diff(image) => {
+            let (other_width, other_height) = image.dimensions()?;
+            (width.max(other_width), height.max(other_height))
+        }
+        ImgOp::DrawHistogram(inputs) => match inputs.placement() {
+            HistogramPlacement::Append => (
+                width,
+                height
+                    .checked_add(crate::operations::histogram::panel_height(width))
+                    .ok_or(SicImageEngineError::DrawHistogramTooLarge)?,
+            ),
+            HistogramPlacement::Overlay => (width, height),
+        },
+        ImgOp::Hstack(image) => {
+            let (other_width, other_height) = image.dimensions()?;
+            (
+                width
+                    .checked_add(other_width)
+                    .ok_or(SicImageEngineError::StackTooLarge)?,
+                height.max(other_height),
+            )
+        }
+        ImgOp::Mirror(mode) => {
+            let double = |size: u32| {
+                size.checked_mul(2)
+                    .ok_or(SicImageEngineError::MirrorTooLarge)
+            };
+
+            match mode {
+                MirrorMode::Horizontal => (double(width)?, height),
+                MirrorMode::Vertical => (width, double(height)?),
+                MirrorMode::Quad => (double(width)?, double(height)?),
+            }
+        }
+        ImgOp::PadToRatio(inputs) => {
+            crate::operations::pad_to_ratio::padded_size((width, height), inputs.ratio())?
+        }
+        ImgOp::PixelUpscale(inputs) => {
+            crate::operations::pixel_upscale::upscaled_size((width, height), inputs)?
+        }
+        ImgOp::Rotate90 | ImgOp::Rotate270 => (height, width),
+        ImgOp::Vstack(image) => {
+            let (other_width, other_height) = image.dimensions()?;
+            (
+                width.max(other_width),
+                height
+                    .checked_add(other_height)
+                    .ok_or(SicImageEngineError::StackTooLarge)?,
+            )
+        }
+        _ => (width, height),
+    };
+
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::DeferredOp;
+    use crate::wrapper::blur_algorithm::BlurAlgorithm;
+    use crate::wrapper::border::BorderInputs;
+    use crate::wrapper::dimension::Dimension;
+    use crate::wrapper::gravity::Gravity;
+    use crate::wrapper::image_path::ImageFromPath;
+    use crate::wrapper::region::Region;
+    use sic_core::image::Rgba;
+    use sic_testing::in_;
+    use std::path::PathBuf;
+
+    fn dimensions(dry_run: &mut DryRun, program: &[Instr]) -> Vec<Option<(u32, u32)>> {
+        program
+            .iter()
+            .map(|instruction| dry_run.step(instruction).unwrap().dimensions)
+            .collect()
+    }
+
+    #[test]
+    fn predicts_dimensions_per_step() {
+        let program = [
+            Instr::Operation(ImgOp::Resize((40, 0))),
+            Instr::Operation(ImgOp::Border(
+                BorderInputs::new(&[1, 2], Rgba([0, 0, 0, 255])).unwrap(),
+            )),
+            Instr::Operation(ImgOp::Rotate90),
+            Instr::Operation(ImgOp::Invert),
+            Instr::Operation(ImgOp::Mirror(MirrorMode::Horizontal)),
+        ];
+
+        assert_eq!(
+            dimensions(&mut DryRun::new(Some((80, 60))), &program),
+            vec![
+                Some((40, 30)),
+                Some((44, 32)),
+                Some((32, 44)),
+                Some((32, 44)),
+                Some((64, 44)),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_dimensions_until_given_size() {
+        let program = [
+            Instr::Operation(ImgOp::Rotate90),
+            Instr::Operation(ImgOp::Resize((10, 20))),
+            Instr::Operation(ImgOp::Rotate90),
+            Instr::Operation(ImgOp::Trim(0.0)),
+            Instr::Operation(ImgOp::CropGravity((Gravity::Center, 5, 5))),
+        ];
+
+        assert_eq!(
+            dimensions(&mut DryRun::new(None), &program),
+            vec![None, Some((10, 20)), Some((20, 10)), None, Some((5, 5))]
+        );
+    }
+
+    #[test]
+    fn resolves_deferred_operations() {
+        let mut dry_run = DryRun::new(Some((80, 60)));
+        let step = dry_run
+            .step(&Instr::Deferred(DeferredOp::Crop([
+                Dimension::Pixels(0),
+                Dimension::Pixels(0),
+                Dimension::Percentage(50.0),
+                Dimension::Pixels(30),
+            ])))
+            .unwrap();
+
+        assert_eq!(
+            step.instruction,
+            Instr::Operation(ImgOp::Crop((0, 0, 40, 30)))
+        );
+        assert_eq!(step.dimensions, Some((40, 30)));
+    }
+
+    #[test]
+    fn modifiers_of_occurrence() {
+        let mut dry_run = DryRun::new(Some((80, 60)));
+        let program = [
+            Instr::EnvAdd(EnvItem::Gravity(Gravity::Center)),
+            Instr::EnvAddFor(
+                Occurrence::new("resize", 1).unwrap(),
+                EnvItem::PreserveAspectRatio(true),
+            ),
+            Instr::Operation(ImgOp::Resize((40, 30))),
+            Instr::Operation(ImgOp::Resize((40, 20))),
+        ];
+
+        let steps = program
+            .iter()
+            .map(|instruction| dry_run.step(instruction).unwrap())
+            .collect::<Vec<_>>();
+
+        // the gravity doesn't affect a resize
+        assert!(steps[0].modifiers.is_empty());
+        assert!(steps[2].modifiers.is_empty());
+        assert_eq!(steps[2].dimensions, Some((40, 30)));
+        assert_eq!(steps[3].modifiers, vec![EnvItem::PreserveAspectRatio(true)]);
+        assert_eq!(steps[3].dimensions, Some((26, 20)));
+    }
+
+    #[test]
+    fn only_modifiers_used_by_the_operation() {
+        let mut dry_run = DryRun::new(Some((80, 60)));
+        let region = EnvItem::Region(Region::new((0, 0), (10, 10)));
+        let program = [
+            Instr::EnvAdd(EnvItem::Gravity(Gravity::Center)),
+            Instr::EnvAdd(EnvItem::BlurAlgorithm(BlurAlgorithm::Box)),
+            Instr::Operation(ImgOp::Blur(1.0)),
+            Instr::Operation(ImgOp::Crop((0, 0, 5, 5))),
+            Instr::EnvAdd(region.clone()),
+            Instr::Operation(ImgOp::Invert),
+        ];
+
+        let steps = program
+            .iter()
+            .map(|instruction| dry_run.step(instruction).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            steps[2].modifiers,
+            vec![EnvItem::BlurAlgorithm(BlurAlgorithm::Box)]
+        );
+        assert_eq!(steps[3].modifiers, vec![EnvItem::Gravity(Gravity::Center)]);
+        // a region applies to every operation
+        assert_eq!(steps[5].modifiers, vec![region]);
+    }
+
+    #[test]
+    fn crop_outside_image() {
+        let mut dry_run = DryRun::new(Some((80, 60)));
+
+        assert!(matches!(
+            dry_run.step(&Instr::Operation(ImgOp::Crop((0, 0, 100, 10)))),
+            Err(SicImageEngineError::CropCoordinateOutOfBounds(..))
+        ));
+    }
+
+    #[test]
+    fn region_keeps_dimensions() {
+        let mut dry_run = DryRun::new(Some((80, 60)));
+        dry_run
+            .step(&Instr::EnvAdd(EnvItem::Region(Region::new(
+                (0, 0),
+                (10, 10),
+            ))))
+            .unwrap();
+
+        assert_eq!(
+            dry_run
+                .step(&Instr::Operation(ImgOp::Rotate90))
+                .unwrap()
+                .dimensions,
+            Some((80, 60))
+        );
+        assert!(matches!(
+            dry_run.step(&Instr::Operation(ImgOp::Resize((5, 5)))),
+            Err(SicImageEngineError::RegionSizeChanged(10, 10, 5, 5))
+        ));
+    }
+
+    #[test]
+    fn stack_reads_dimensions_of_other_image() {
+        let other = ImageFromPath::new(PathBuf::from(in_!("palette_4x4.png")));
+        let program = [
+            Instr::Operation(ImgOp::Hstack(other.clone())),
+            Instr::Operation(ImgOp::Vstack(other)),
+        ];
+
+        assert_eq!(
+            dimensions(&mut DryRun::new(Some((2, 8))), &program),
+            vec![Some((6, 8)), Some((6, 12))]
+        );
+    }
+}
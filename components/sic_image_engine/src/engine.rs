@@ -1,29 +1,96 @@
 use std::cmp::max;
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::path::PathBuf;
 
-use sic_core::image::imageops::{self, FilterType};
-use sic_core::image::{DynamicImage, GenericImageView, ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+use sic_core::animation::AnimatedImage;
+use sic_core::hdr::Rgba32FImage;
+use sic_core::image::{imageops, DynamicImage, Frame, GenericImageView, ImageBuffer, Rgba};
 
 use crate::errors::SicImageEngineError;
+use crate::wrapper::adaptive_threshold_method::AdaptiveThresholdMethod;
+use crate::wrapper::blur_algorithm::BlurAlgorithm;
+use crate::wrapper::dimension::Dimension;
+use crate::wrapper::expression::ImageProperties;
+use crate::wrapper::extend::{EXTEND_DEFAULT_COLOR, EXTEND_DEFAULT_GRAVITY};
 use crate::wrapper::filter_type::FilterTypeWrap;
+use crate::wrapper::gravity::Gravity;
+use crate::wrapper::image_path::ImageFromPath;
+use crate::wrapper::pad_to_ratio::PAD_TO_RATIO_DEFAULT_COLOR;
+#[cfg(feature = "imageproc-ops")]
+use crate::wrapper::perspective::PERSPECTIVE_DEFAULT_BACKGROUND;
+use crate::wrapper::region::Region;
 use crate::ImgOp;
 
 trait EnvironmentKey {
     fn key(&self) -> ItemName;
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumDiscriminants)]
-#[strum_discriminants(name(ItemName), derive(Display, Hash))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, EnumDiscriminants, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[strum_discriminants(
+    name(ItemName),
+    derive(Display, Hash, Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum EnvItem {
+    AdaptiveThresholdMethod(AdaptiveThresholdMethod),
+    BackgroundColor(#[serde(with = "crate::serialization::rgba")] Rgba<u8>),
+    BlurAlgorithm(BlurAlgorithm),
     CustomSamplingFilter(FilterTypeWrap),
+    Gravity(Gravity),
+    Mask(ImageFromPath),
     PreserveAspectRatio(bool),
+    Region(Region),
 }
 
 impl EnvItem {
-    pub fn resize_sampling_filter(self) -> Option<FilterTypeWrap> {
+    pub fn resize_sampling_filter(&self) -> Option<FilterTypeWrap> {
         match self {
-            EnvItem::CustomSamplingFilter(k) => Some(k),
+            EnvItem::CustomSamplingFilter(k) => Some(*k),
+            _ => None,
+        }
+    }
+
+    pub fn adaptive_threshold_method(&self) -> Option<AdaptiveThresholdMethod> {
+        match self {
+            EnvItem::AdaptiveThresholdMethod(method) => Some(*method),
+            _ => None,
+        }
+    }
+
+    pub fn background_color(&self) -> Option<Rgba<u8>> {
+        match self {
+            EnvItem::BackgroundColor(color) => Some(*color),
+            _ => None,
+        }
+    }
+
+    pub fn blur_algorithm(&self) -> Option<BlurAlgorithm> {
+        match self {
+            EnvItem::BlurAlgorithm(algorithm) => Some(*algorithm),
+            _ => None,
+        }
+    }
+
+    pub fn gravity(&self) -> Option<Gravity> {
+        match self {
+            EnvItem::Gravity(gravity) => Some(*gravity),
+            _ => None,
+        }
+    }
+
+    pub fn mask(&self) -> Option<&ImageFromPath> {
+        match self {
+            EnvItem::Mask(mask) => Some(mask),
+            _ => None,
+        }
+    }
+
+    pub fn region(&self) -> Option<Region> {
+        match self {
+            EnvItem::Region(region) => Some(*region),
             _ => None,
         }
     }
@@ -32,8 +99,14 @@ impl EnvItem {
 impl EnvironmentKey for EnvItem {
     fn key(&self) -> ItemName {
         match self {
+            EnvItem::AdaptiveThresholdMethod(_) => ItemName::AdaptiveThresholdMethod,
+            EnvItem::BackgroundColor(_) => ItemName::BackgroundColor,
+            EnvItem::BlurAlgorithm(_) => ItemName::BlurAlgorithm,
             EnvItem::CustomSamplingFilter(_) => ItemName::CustomSamplingFilter,
+            EnvItem::Gravity(_) => ItemName::Gravity,
+            EnvItem::Mask(_) => ItemName::Mask,
             EnvItem::PreserveAspectRatio(_) => ItemName::PreserveAspectRatio,
+            EnvItem::Region(_) => ItemName::Region,
         }
     }
 }
@@ -53,9 +126,7 @@ impl Default for Env {
 
 impl Env {
     pub fn insert_or_update(&mut self, item: EnvItem) {
-        let key = item.key();
-
-        *self.store.entry(key).or_insert(item) = item;
+        self.store.insert(item.key(), item);
     }
 
     pub fn remove(&mut self, key: ItemName) -> Option<()> {
@@ -65,30 +136,400 @@ impl Env {
     pub fn get(&mut self, key: ItemName) -> Option<&EnvItem> {
         self.store.get(&key)
     }
+
+    /// The items of the environment, ordered by name.
+    pub fn items(&self) -> Vec<&EnvItem> {
+        let mut items = self.store.values().collect::<Vec<_>>();
+        items.sort_by_key(|item| item.key().to_string());
+        items
+    }
+
+    /// Inserts the items of the other environment, replacing items with the same name.
+    pub fn extend(&mut self, other: Env) {
+        self.store.extend(other.store);
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// A single occurrence of an image operation: the operation with the given name, e.g. `resize`,
+/// which is applied as the `index`-th of the operations with that name, counting from 0.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Occurrence {
+    operation: String,
+    index: usize,
+}
+
+impl Occurrence {
+    pub fn new(operation: &str, index: usize) -> Result<Self, SicImageEngineError> {
+        use strum::VariantNames;
+
+        if ImgOp::VARIANTS.contains(&operation) {
+            Ok(Self {
+                operation: operation.to_string(),
+                index,
+            })
+        } else {
+            Err(SicImageEngineError::UnknownOperationName(
+                operation.to_string(),
+            ))
+        }
+    }
+
+    /// The `index`-th occurrence of the operation.
+    pub(crate) fn of(operation: &str, index: usize) -> Self {
+        Self {
+            operation: operation.to_string(),
+            index,
+        }
+    }
+
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl std::fmt::Display for Occurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.operation, self.index)
+    }
+}
+
+/// Checks that each modifier which the program sets for a single occurrence of an operation is
+/// used, i.e. that the occurrence is applied after the modifier is set. An unused modifier, like
+/// the one of `set resize.5 sampling-filter nearest; resize 2 2`, would be ignored otherwise.
+pub fn check_occurrences(program: &[Instr]) -> Result<(), SicImageEngineError> {
+    let mut applied = HashMap::<&str, usize>::new();
+    let mut pending = Vec::<&Occurrence>::new();
+
+    for instruction in program {
+        let operation = match instruction {
+            Instr::Operation(op) => op.as_ref(),
+            Instr::Deferred(op) => op.as_ref(),
+            Instr::EnvAddFor(occurrence, _) => {
+                pending.push(occurrence);
+                continue;
+            }
+            _ => continue,
+        };
+
+        let count = applied.entry(operation).or_insert(0);
+        pending.retain(|occurrence| {
+            occurrence.operation() != operation || occurrence.index() != *count
+        });
+        *count += 1;
+    }
+
+    match pending.first() {
+        Some(occurrence) => Err(SicImageEngineError::UnusedOccurrence(
+            occurrence.to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Instr {
     Operation(ImgOp),
     EnvAdd(EnvItem),
+    /// Adds the item to the environment of a single occurrence of an operation only; the item
+    /// takes precedence over an item with the same name in the environment.
+    EnvAddFor(Occurrence, EnvItem),
     EnvRemove(ItemName),
+    /// An operation of which the inputs are only known when it's applied.
+    Deferred(DeferredOp),
+}
+
+impl Instr {
+    /// Checks the files which the instruction refers to, e.g. the image of `diff` or the font of
+    /// `draw-text`, so a program can be validated without an input image. Images are recognized
+    /// without decoding them; kernels, LUTs and fonts are loaded.
+    pub fn check_files(&self) -> Result<(), SicImageEngineError> {
+        match self {
+            Instr::Operation(op) => op.check_files(),
+            Instr::EnvAdd(EnvItem::Mask(mask)) | Instr::EnvAddFor(_, EnvItem::Mask(mask)) => {
+                mask.check()
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ImgOp {
+    /// Whether the operation is a geometric transformation which is applied to the unclamped
+    /// samples of a high dynamic range image as well.
+    fn keeps_hdr(&self) -> bool {
+        matches!(
+            self,
+            ImgOp::Crop(_)
+                | ImgOp::CropGravity(_)
+                | ImgOp::FlipHorizontal
+                | ImgOp::FlipVertical
+                | ImgOp::Rotate90
+                | ImgOp::Rotate180
+                | ImgOp::Rotate270
+        )
+    }
+
+    /// Whether the operation is affected by the modifier, if it's set. A mask and a region apply
+    /// to every operation.
+    pub fn uses_modifier(&self, modifier: ItemName) -> bool {
+        match (self, modifier) {
+            (_, ItemName::Mask) | (_, ItemName::Region) => true,
+            (ImgOp::AlphaRemove, ItemName::BackgroundColor)
+            | (ImgOp::Blur(_), ItemName::BlurAlgorithm)
+            | (ImgOp::Crop(_), ItemName::Gravity)
+            | (ImgOp::Extend(_), ItemName::Gravity)
+            | (ImgOp::Extend(_), ItemName::BackgroundColor)
+            | (ImgOp::NineSlice(_), ItemName::CustomSamplingFilter)
+            | (ImgOp::Overlay(_), ItemName::Gravity)
+            | (ImgOp::PadToRatio(_), ItemName::BackgroundColor)
+            | (ImgOp::Resize(_), ItemName::CustomSamplingFilter)
+            | (ImgOp::Resize(_), ItemName::PreserveAspectRatio) => true,
+            #[cfg(feature = "imageproc-ops")]
+            (ImgOp::AdaptiveThreshold(_), ItemName::AdaptiveThresholdMethod)
+            | (ImgOp::DrawText(_), ItemName::Gravity)
+            | (ImgOp::Perspective(_), ItemName::BackgroundColor) => true,
+            _ => false,
+        }
+    }
+
+    fn check_files(&self) -> Result<(), SicImageEngineError> {
+        match self {
+            ImgOp::ConvolveFile(kernel) => kernel.open_kernel().map(drop),
+            ImgOp::Diff(image) | ImgOp::Hstack(image) | ImgOp::Vstack(image) => image.check(),
+            ImgOp::Find(inputs) => inputs.template().check(),
+            ImgOp::Lut(lut) => lut.open_lut().map(drop),
+            ImgOp::Overlay(inputs) => inputs.image_path().check(),
+            ImgOp::Stamp(inputs) => inputs.image_path().check(),
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::DrawText(inner) => {
+                let options = inner.font_options();
+                crate::operations::font::load_font(&options.font, options.style).map(drop)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An operation with inputs which are relative to the size of the image, e.g. `crop 10% 10% 90%
+/// 90%` or `crop 0 0 $width $height/2`. The inputs are resolved against the properties of the
+/// image as they are when the operation is applied, after which it's applied like any other
+/// operation.
+#[derive(Debug, PartialEq, Clone, AsRefStr, Serialize, Deserialize)]
+#[strum(serialize_all = "kebab_case")]
+#[serde(rename_all = "kebab-case")]
+pub enum DeferredOp {
+    Crop([Dimension; 4]),
+    Resize([Dimension; 2]),
+}
+
+impl DeferredOp {
+    /// Whether the operation is affected by the modifier, if it's set; see
+    /// [ImgOp::uses_modifier].
+    pub fn uses_modifier(&self, modifier: ItemName) -> bool {
+        matches!(
+            (self, modifier),
+            (_, ItemName::Mask)
+                | (_, ItemName::Region)
+                | (DeferredOp::Crop(_), ItemName::Gravity)
+                | (DeferredOp::Resize(_), ItemName::CustomSamplingFilter)
+                | (DeferredOp::Resize(_), ItemName::PreserveAspectRatio)
+        )
+    }
+
+    /// The instruction to apply the operation: the operation itself if none of its inputs are
+    /// relative, so it can be applied right away.
+    pub fn into_instr(self) -> Instr {
+        use Dimension::Pixels;
+
+        match self {
+            DeferredOp::Crop([Pixels(lx), Pixels(ly), Pixels(rx), Pixels(ry)]) => {
+                Instr::Operation(ImgOp::Crop((lx, ly, rx, ry)))
+            }
+            DeferredOp::Resize([Pixels(width), Pixels(height)]) => {
+                Instr::Operation(ImgOp::Resize((width, height)))
+            }
+            deferred => Instr::Deferred(deferred),
+        }
+    }
+
+    /// The operation with its inputs resolved against the properties of the image.
+    pub fn resolve(&self, properties: &ImageProperties) -> Result<ImgOp, SicImageEngineError> {
+        let (width, height) = (properties.width, properties.height);
+
+        let op = match self {
+            DeferredOp::Crop([lx, ly, rx, ry]) => ImgOp::Crop((
+                lx.resolve(width, properties)?,
+                ly.resolve(height, properties)?,
+                rx.resolve(width, properties)?,
+                ry.resolve(height, properties)?,
+            )),
+            DeferredOp::Resize([w, h]) => ImgOp::Resize((
+                w.resolve(width, properties)?,
+                h.resolve(height, properties)?,
+            )),
+        };
+
+        Ok(op)
+    }
+}
+
+/// What an operation which analyses the image found, reported besides the resulting image.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AnalysisResult {
+    /// The area of the image which is most similar to the template, as found by `find`, and
+    /// their normalized cross-correlation, between -1 and 1.
+    TemplateMatch {
+        template: PathBuf,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        score: f32,
+    },
+    /// The borders of the bright areas of the image, binarized by the threshold, as traced by
+    /// `contours`.
+    Contours {
+        threshold: u8,
+        contours: Vec<Contour>,
+    },
+}
+
+/// A border between the bright and the dark areas of a binarized image.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Contour {
+    pub kind: ContourKind,
+    /// The bright pixels along the border, in the order in which they were traced.
+    pub points: Vec<(u32, u32)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ContourKind {
+    /// The border around a bright area.
+    Outer,
+    /// The border around a dark area enclosed by a bright area.
+    Hole,
+}
+
+impl AnalysisResult {
+    /// Moves the reported area by the given offset, e.g. from the coordinates of a region to
+    /// those of the whole image.
+    fn translate(&mut self, (dx, dy): (u32, u32)) {
+        match self {
+            AnalysisResult::TemplateMatch { x, y, .. } => {
+                *x += dx;
+                *y += dy;
+            }
+            AnalysisResult::Contours { contours, .. } => {
+                for (x, y) in contours.iter_mut().flat_map(|contour| &mut contour.points) {
+                    *x += dx;
+                    *y += dy;
+                }
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct ImageEngine {
     environment: Box<Env>,
+    /// The environment items which were added for a single occurrence of an operation only.
+    occurrence_environments: HashMap<Occurrence, Env>,
+    /// The number of operations applied so far, by name.
+    occurrences: HashMap<String, usize>,
     image: Box<DynamicImage>,
+    /// The unclamped samples of a high dynamic range input image, as they are after the image
+    /// operations applied so far, until an operation is applied which can't be applied to them.
+    hdr: Option<Box<Rgba32FImage>>,
+    /// The name of the operation after which the unclamped samples were dropped, if any.
+    hdr_dropped_by: Option<String>,
+    /// The frames of an animated input image, to each of which the image operations are applied.
+    animation: Option<AnimatedImage>,
+    /// The number of frames of the input image, as referred to by `$frames`.
+    frames: usize,
+    analysis_results: Vec<AnalysisResult>,
 }
 
 impl ImageEngine {
     pub fn new(image: DynamicImage) -> Self {
         Self {
             environment: Box::from(Env::default()),
+            occurrence_environments: HashMap::new(),
+            occurrences: HashMap::new(),
             image: Box::from(image),
+            hdr: None,
+            hdr_dropped_by: None,
+            animation: None,
+            frames: 1,
+            analysis_results: Vec::new(),
+        }
+    }
+
+    /// Sets the unclamped samples of a high dynamic range input image, of which the image is the
+    /// clamped version. Flips, rotations and crops of the whole image are applied to them as well;
+    /// they're dropped once any other image operation is applied, since the other image
+    /// operations work on the clamped image only.
+    pub fn with_hdr(mut self, hdr: Option<Rgba32FImage>) -> Self {
+        self.hdr = hdr.map(Box::from);
+        self
+    }
+
+    /// The unclamped samples of the high dynamic range input image, if they weren't dropped by
+    /// an image operation.
+    pub fn hdr_image(&self) -> Option<&Rgba32FImage> {
+        self.hdr.as_deref()
+    }
+
+    /// The name of the image operation which dropped the unclamped samples of the high dynamic
+    /// range input image, if any.
+    pub fn hdr_dropped_by(&self) -> Option<&str> {
+        self.hdr_dropped_by.as_deref()
+    }
+
+    /// Sets the frames of an animated input image; the image is replaced by the first frame.
+    /// The image operations are applied to each frame separately.
+    pub fn with_animation(mut self, animation: Option<AnimatedImage>) -> Self {
+        if let Some(animation) = &animation {
+            *self.image = animation.first_frame();
         }
+
+        self.animation = animation;
+        self
+    }
+
+    /// The frames of the animated input image, as they are after the image operations applied
+    /// so far, if the input image is animated. The image is the first frame.
+    pub fn animation(&self) -> Option<&AnimatedImage> {
+        self.animation.as_ref()
+    }
+
+    /// The image, as it is after the image operations applied so far.
+    pub fn image(&self) -> &DynamicImage {
+        &self.image
+    }
+
+    /// Sets the number of frames of the input image, e.g. of an animated GIF of which the frames
+    /// were combined into a single image; by default 1.
+    pub fn with_frame_count(mut self, frames: usize) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    /// The results of the operations which analysed the image so far, in the order in which the
+    /// operations were applied.
+    pub fn analysis_results(&self) -> &[AnalysisResult] {
+        &self.analysis_results
     }
 
     pub fn ignite(&mut self, instructions: &[Instr]) -> Result<&DynamicImage, SicImageEngineError> {
+        if let Some(animation) = self.animation.take() {
+            return self.ignite_animation(animation, instructions);
+        }
+
         for instruction in instructions {
             match self.process_instruction(instruction) {
                 Ok(_) => continue,
@@ -99,139 +540,739 @@ impl ImageEngine {
         Ok(&self.image)
     }
 
+    /// Applies the instructions to each frame of the animated image separately, each starting from
+    /// the current state of the engine. Since the instructions are the same for each frame, the
+    /// engine continues from the state of the first frame afterwards. The analysis results of all
+    /// frames are kept, in the order of the frames.
+    fn ignite_animation(
+        &mut self,
+        animation: AnimatedImage,
+        instructions: &[Instr],
+    ) -> Result<&DynamicImage, SicImageEngineError> {
+        let previous_results = self.analysis_results.len();
+        let mut analysis_results = Vec::new();
+        let mut first = None;
+        let mut frames = Vec::with_capacity(animation.frames().len());
+        let repeat = animation.repeat();
+
+        for frame in animation.into_frames() {
+            let (left, top, delay) = (frame.left(), frame.top(), frame.delay());
+
+            let mut engine = self.clone();
+            *engine.image = DynamicImage::ImageRgba8(frame.into_buffer());
+            engine.ignite(instructions)?;
+
+            analysis_results.extend_from_slice(&engine.analysis_results[previous_results..]);
+            frames.push(Frame::from_parts(engine.image.to_rgba(), left, top, delay));
+
+            if first.is_none() {
+                first = Some(engine);
+            }
+        }
+
+        if let Some(first) = first {
+            *self = first;
+        }
+
+        self.analysis_results.truncate(previous_results);
+        self.analysis_results.extend(analysis_results);
+        self.animation =
+            AnimatedImage::from_frames(frames).map(|animation| animation.with_repeat(repeat));
+
+        Ok(&self.image)
+    }
+
     fn process_instruction(&mut self, instruction: &Instr) -> Result<(), SicImageEngineError> {
         match instruction {
-            Instr::Operation(op) => self.process_operation(op),
-            Instr::EnvAdd(item) => self.insert_env(*item),
+            Instr::Operation(op) => {
+                let result = match self.take_occurrence_environment(op) {
+                    Some(environment) => self.process_operation_in_occurrence_env(op, environment),
+                    None => self.process_operation_in_env(op),
+                };
+
+                if !op.keeps_hdr() {
+                    self.drop_hdr(op);
+                }
+                result
+            }
+            Instr::EnvAdd(item) => self.insert_env(item.clone()),
+            Instr::EnvAddFor(occurrence, item) => self.insert_occurrence_env(occurrence, item),
             Instr::EnvRemove(key) => self.remove_env(*key),
+            Instr::Deferred(op) => {
+                let (width, height) = self.image.dimensions();
+                let op = op.resolve(&ImageProperties {
+                    width,
+                    height,
+                    frames: self.frames,
+                })?;
+                self.process_instruction(&Instr::Operation(op))
+            }
+        }
+    }
+
+    /// Counts the operation as applied, and takes the items which were added to the environment
+    /// of this occurrence of the operation, if any.
+    fn take_occurrence_environment(&mut self, operation: &ImgOp) -> Option<Env> {
+        let count = self
+            .occurrences
+            .entry(operation.as_ref().to_string())
+            .or_insert(0);
+        let occurrence = Occurrence::of(operation.as_ref(), *count);
+        *count += 1;
+
+        self.occurrence_environments.remove(&occurrence)
+    }
+
+    /// Applies a geometric transformation to the unclamped samples as well, if the operation is
+    /// applied to the whole image, i.e. if they have the same size as the image as it is before
+    /// the operation.
+    fn transform_hdr(&mut self, transform: impl FnOnce(&Rgba32FImage) -> Rgba32FImage) {
+        if let Some(hdr) = self.hdr.as_deref_mut() {
+            if hdr.dimensions() == self.image.dimensions() {
+                *hdr = transform(hdr);
+            }
+        }
+    }
+
+    /// Drops the unclamped samples, since the operation was applied to the clamped image only.
+    /// Tone mapping is meant to replace them by a clamped image, so it's not reported as having
+    /// dropped them.
+    fn drop_hdr(&mut self, operation: &ImgOp) {
+        if self.hdr.take().is_some() && !matches!(operation, ImgOp::Tonemap(_)) {
+            self.hdr_dropped_by = Some(operation.as_ref().to_string());
+        }
+    }
+
+    /// Applies the operation with the items of its occurrence added to the environment; the
+    /// environment is restored afterwards.
+    fn process_operation_in_occurrence_env(
+        &mut self,
+        operation: &ImgOp,
+        occurrence_environment: Env,
+    ) -> Result<(), SicImageEngineError> {
+        let environment = Env::clone(&self.environment);
+        self.environment.extend(occurrence_environment);
+
+        let result = self.process_operation_in_env(operation);
+        *self.environment = environment;
+        result
+    }
+
+    fn process_operation_in_env(&mut self, operation: &ImgOp) -> Result<(), SicImageEngineError> {
+        match mask_or_none(&mut self.environment) {
+            Some(mask) => self.process_operation_with_mask(operation, &mask),
+            None => self.process_unmasked_operation(operation),
+        }
+    }
+
+    /// Applies the operation, and blends its result with the image as it was before by the mask:
+    /// where the mask is white, the result is used, and where it is black, the image is kept.
+    fn process_operation_with_mask(
+        &mut self,
+        operation: &ImgOp,
+        mask: &ImageFromPath,
+    ) -> Result<(), SicImageEngineError> {
+        let mask = mask.open_image()?.to_luma();
+        let (width, height) = self.image.dimensions();
+
+        if mask.dimensions() != (width, height) {
+            return Err(SicImageEngineError::MaskSizeMismatch(
+                mask.width(),
+                mask.height(),
+                width,
+                height,
+            ));
+        }
+
+        let original = DynamicImage::clone(&self.image);
+        self.process_unmasked_operation(operation)?;
+        self.drop_hdr(operation);
+
+        let (result_width, result_height) = self.image.dimensions();
+        if (result_width, result_height) != (width, height) {
+            return Err(SicImageEngineError::MaskSizeChanged(
+                width,
+                height,
+                result_width,
+                result_height,
+            ));
+        }
+
+        *self.image = crate::operations::mask::blend_with_mask(&original, &self.image, &mask);
+        Ok(())
+    }
+
+    fn process_unmasked_operation(&mut self, operation: &ImgOp) -> Result<(), SicImageEngineError> {
+        match region_or_none(&mut self.environment) {
+            Some(region) => self.process_operation_in_region(operation, region),
+            None => self.process_operation(operation),
+        }
+    }
+
+    /// Applies the operation to the part of the image within the region only: the region is cut
+    /// from the image, operated on as if it were the whole image, and put back in its place.
+    fn process_operation_in_region(
+        &mut self,
+        operation: &ImgOp,
+        region: Region,
+    ) -> Result<(), SicImageEngineError> {
+        let (width, height) = self.image.dimensions();
+
+        let ((x, y), size) = region.clip((width, height)).ok_or_else(|| {
+            SicImageEngineError::RegionOutsideImage(
+                region.x(),
+                region.y(),
+                region.width(),
+                region.height(),
+                width,
+                height,
+            )
+        })?;
+
+        let view = self.image.crop_imm(x, y, size.0, size.1);
+        let image = std::mem::replace(&mut *self.image, view);
+        let reported = self.analysis_results.len();
+
+        let result = self.process_operation(operation);
+        let view = std::mem::replace(&mut *self.image, image);
+        result?;
+
+        if size != (width, height) {
+            self.drop_hdr(operation);
         }
+
+        for analysis_result in &mut self.analysis_results[reported..] {
+            analysis_result.translate((x, y));
+        }
+
+        let (view_width, view_height) = view.dimensions();
+        if (view_width, view_height) != size {
+            return Err(SicImageEngineError::RegionSizeChanged(
+                size.0,
+                size.1,
+                view_width,
+                view_height,
+            ));
+        }
+
+        crate::operations::region::paste_region(&mut self.image, &view, (x, y));
+        Ok(())
     }
 
     fn process_operation(&mut self, operation: &ImgOp) -> Result<(), SicImageEngineError> {
         match operation {
+            ImgOp::AlphaAdd => {
+                *self.image = crate::operations::alpha::alpha_add(&self.image);
+                Ok(())
+            }
+            ImgOp::AlphaPremultiply => {
+                *self.image = crate::operations::alpha::alpha_premultiply(&self.image);
+                Ok(())
+            }
+            ImgOp::AlphaRemove => {
+                *self.image = match background_color_or_none(&mut self.environment) {
+                    Some(background) => crate::operations::alpha::flatten(&self.image, background),
+                    None => crate::operations::alpha::alpha_remove(&self.image),
+                };
+                Ok(())
+            }
+            ImgOp::AlphaThreshold(threshold) => {
+                *self.image = crate::operations::alpha::alpha_threshold(&self.image, *threshold);
+                Ok(())
+            }
+            ImgOp::AutoEnhance => {
+                *self.image = crate::operations::auto_enhance::auto_enhance(&self.image);
+                Ok(())
+            }
             ImgOp::Blur(sigma) => {
-                *self.image = self.image.blur(*sigma);
+                *self.image = match blur_algorithm_or_default(&mut self.environment) {
+                    BlurAlgorithm::Gaussian => self.image.blur(*sigma),
+                    BlurAlgorithm::Box => crate::operations::box_blur::box_blur(
+                        &self.image,
+                        crate::operations::box_blur::radius_for_sigma(*sigma),
+                    ),
+                };
+                Ok(())
+            }
+            ImgOp::Border(border) => {
+                *self.image = crate::operations::border::border(&self.image, border)?;
+                Ok(())
+            }
+            ImgOp::BoxBlur(radius) => {
+                *self.image = crate::operations::box_blur::box_blur(&self.image, *radius);
                 Ok(())
             }
             ImgOp::Brighten(amount) => {
                 *self.image = self.image.brighten(*amount);
                 Ok(())
             }
+            ImgOp::ChannelExtract(channel) => {
+                *self.image = crate::operations::channel::channel_extract(&self.image, *channel);
+                Ok(())
+            }
+            ImgOp::ChannelSwap((lhs, rhs)) => {
+                *self.image = crate::operations::channel::channel_swap(&self.image, *lhs, *rhs);
+                Ok(())
+            }
+            ImgOp::ChromaKey(inputs) => {
+                *self.image = crate::operations::chroma_key::chroma_key(&self.image, inputs)?;
+                Ok(())
+            }
+            ImgOp::Contours(inputs) => {
+                let (image, contours) = crate::operations::contours::contours(&self.image, inputs);
+                *self.image = image;
+
+                self.analysis_results.push(AnalysisResult::Contours {
+                    threshold: inputs.threshold(),
+                    contours,
+                });
+                Ok(())
+            }
             ImgOp::Contrast(c) => {
                 *self.image = self.image.adjust_contrast(*c);
                 Ok(())
             }
+            ImgOp::Convolve(kernel) => {
+                *self.image = crate::operations::convolve::convolve(&self.image, kernel);
+                Ok(())
+            }
+            ImgOp::ConvolveFile(kernel) => {
+                let kernel = kernel.open_kernel()?;
+                *self.image = crate::operations::convolve::convolve(&self.image, &kernel);
+                Ok(())
+            }
             ImgOp::Crop((lx, ly, rx, ry)) => {
                 let selection = CropSelection::new(*lx, *ly, *rx, *ry);
 
+                // with a gravity, the selection is measured from the edges the gravity points to
+                let selection = match gravity_or_none(&mut self.environment) {
+                    Some(gravity) => selection
+                        .dimensions_are_ok()?
+                        .placed_by(gravity, self.image.dimensions())?,
+                    None => selection,
+                };
+
                 // 1. verify that the top left anchor is smaller than the bottom right anchor
                 // 2. verify that the selection is within the bounds of the image
                 selection
                     .dimensions_are_ok()
-                    .and_then(|selection| selection.fits_within(&self.image))
-                    .map(|_| {
-                        *self.image = self.image.crop(*lx, *ly, rx - lx, ry - ly);
+                    .and_then(|selection| selection.fits_within(self.image.dimensions()))
+                    .map(|s| {
+                        let (x, y, width, height) = (s.lx, s.ly, s.rx - s.lx, s.ry - s.ly);
+                        self.transform_hdr(|hdr| {
+                            imageops::crop_imm(hdr, x, y, width, height).to_image()
+                        });
+                        *self.image = self.image.crop(x, y, width, height);
                     })
             }
+            ImgOp::CropGravity((gravity, width, height)) => {
+                let cropped = crate::operations::crop_gravity::crop_gravity(
+                    &self.image,
+                    *gravity,
+                    (*width, *height),
+                )?;
+
+                let (x, y) = gravity.position(self.image.dimensions(), (*width, *height), 0);
+                self.transform_hdr(|hdr| imageops::crop_imm(hdr, x, y, *width, *height).to_image());
+                *self.image = cropped;
+                Ok(())
+            }
             ImgOp::Diff(img) => {
                 let other = img.open_image()?;
                 *self.image = produce_image_diff(&self.image, &other)?;
 
                 Ok(())
             }
-
-            #[cfg(feature = "imageproc-ops")]
-            ImgOp::DrawText(inner) => {
-                let text = inner.text();
-                let coords = inner.coords();
-                let font_options = inner.font_options();
-
-                let font_file = std::fs::read(&font_options.font_path)
-                    .map_err(SicImageEngineError::FontFileLoadError)?;
-
-                let font = rusttype::Font::try_from_bytes(&font_file)
-                    .ok_or_else(|| SicImageEngineError::FontError)?;
-
-                *self.image = DynamicImage::ImageRgba8(imageproc::drawing::draw_text(
-                    &mut *self.image,
-                    font_options.color,
-                    coords.0,
-                    coords.1,
-                    font_options.scale,
-                    &font,
-                    text,
-                ));
-
-                Ok(())
-            }
-            // We need to ensure here that Filter3x3's `it` (&[f32]) has length 9.
-            // Otherwise it will panic, see: https://docs.rs/image/0.19.0/src/image/dynimage.rs.html#349
-            // This check already happens within the `parse` module.
-            ImgOp::Filter3x3(ref it) => {
-                *self.image = self.image.filter3x3(it);
+            ImgOp::Dither(colors) => {
+                *self.image = crate::operations::dither::dither(&self.image, *colors)?;
                 Ok(())
             }
-            ImgOp::FlipHorizontal => {
-                *self.image = self.image.fliph();
+            ImgOp::DrawHistogram(inputs) => {
+                *self.image = crate::operations::histogram::draw_histogram(&self.image, inputs)?;
                 Ok(())
             }
-            ImgOp::FlipVertical => {
-                *self.image = self.image.flipv();
+            ImgOp::Duotone((shadows, highlights)) => {
+                *self.image =
+                    crate::operations::duotone::duotone(&self.image, *shadows, *highlights);
                 Ok(())
             }
-            ImgOp::GrayScale => {
-                *self.image = self.image.grayscale();
+            ImgOp::Equalize => {
+                *self.image = crate::operations::equalize::equalize(&self.image);
                 Ok(())
             }
-            ImgOp::HueRotate(degree) => {
-                *self.image = self.image.huerotate(*degree);
+            ImgOp::Extend(inputs) => {
+                let gravity =
+                    gravity_or_none(&mut self.environment).unwrap_or(EXTEND_DEFAULT_GRAVITY);
+                let color =
+                    background_color_or_none(&mut self.environment).unwrap_or(EXTEND_DEFAULT_COLOR);
+                *self.image =
+                    crate::operations::extend::extend(&self.image, inputs, gravity, color)?;
                 Ok(())
             }
-            ImgOp::Invert => {
-                self.image.invert();
+            ImgOp::FlattenChecker(cell_size) => {
+                *self.image =
+                    crate::operations::flatten_checker::flatten_checker(&self.image, *cell_size)?;
                 Ok(())
             }
-            ImgOp::Overlay(overlay) => {
-                let overlay_image = overlay.image_path().open_image()?;
-                let pos = overlay.position();
-                imageops::overlay(&mut *self.image, &overlay_image, pos.0, pos.1);
+
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::AdaptiveThreshold(block_size) => {
+                let method = adaptive_threshold_method_or_default(&mut self.environment);
+
+                *self.image = crate::operations::adaptive_threshold::adaptive_threshold(
+                    &self.image,
+                    *block_size,
+                    method,
+                )?;
 
                 Ok(())
             }
-            ImgOp::Resize((new_x, new_y)) => {
-                let filter = resize_filter_or_default(&mut self.environment);
-
-                if let Some(reg) = self.environment.get(ItemName::PreserveAspectRatio) {
-                    if let EnvItem::PreserveAspectRatio(preserve) = reg {
-                        if *preserve {
-                            *self.image = self.image.resize(*new_x, *new_y, filter);
-                        } else {
-                            *self.image = self.image.resize_exact(*new_x, *new_y, filter);
-                        }
-                    }
-                } else {
-                    // default if preserve-aspect-ratio option has not been set
-                    *self.image = self.image.resize_exact(*new_x, *new_y, filter);
-                }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::Clahe((tile_size, clip_limit)) => {
+                *self.image =
+                    crate::operations::clahe::clahe(&self.image, *tile_size, *clip_limit)?;
 
                 Ok(())
             }
-            ImgOp::Rotate90 => {
-                *self.image = self.image.rotate90();
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::DrawArrow(inputs) => {
+                *self.image = crate::operations::draw_shape::draw_arrow(&self.image, inputs)?;
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::DrawCorners(inputs) => {
+                *self.image = crate::operations::corners::draw_corners(&self.image, inputs);
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::DrawEllipse(inputs) => {
+                *self.image = crate::operations::draw_shape::draw_ellipse(&self.image, inputs)?;
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::DrawLine(inputs) => {
+                *self.image = crate::operations::draw_shape::draw_line(&self.image, inputs)?;
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::DrawPolygon(inputs) => {
+                *self.image = crate::operations::draw_shape::draw_polygon(&self.image, inputs)?;
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::DrawRect(inputs) => {
+                *self.image = crate::operations::draw_shape::draw_rect(&self.image, inputs)?;
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::DrawText(inner) => {
+                let text = inner.text();
+                let coords = inner.coords();
+                let font_options = inner.font_options();
+
+                let font =
+                    crate::operations::font::load_font(&font_options.font, font_options.style)?;
+                let fallbacks = crate::operations::font::load_fallback_fonts(text, &font);
+
+                // the coordinates are measured from the edges towards which the text gravitates
+                let size =
+                    crate::operations::draw_text::text_size(text, font_options, &font, &fallbacks);
+                let (x, y) = gravity_or_none(&mut self.environment)
+                    .unwrap_or(Gravity::TopLeft)
+                    .offset_position(self.image.dimensions(), size, coords);
+                let to_i32 = |v: i64| v.max(i64::from(i32::MIN)).min(i64::from(i32::MAX)) as i32;
+
+                *self.image = crate::operations::draw_text::draw_text(
+                    &self.image,
+                    text,
+                    (to_i32(x), to_i32(y)),
+                    font_options,
+                    &font,
+                    &fallbacks,
+                )?;
+
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::DistanceTransform => {
+                *self.image =
+                    crate::operations::distance_transform::distance_transform(&self.image);
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::LabelComponents(connectivity) => {
+                *self.image = crate::operations::label_components::label_components(
+                    &self.image,
+                    *connectivity,
+                )?;
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::Median(radius) => {
+                *self.image = crate::operations::median::median(&self.image, *radius)?;
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::Dilate(radius) => {
+                *self.image = crate::operations::morphology::dilate(&self.image, *radius);
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::Erode(radius) => {
+                *self.image = crate::operations::morphology::erode(&self.image, *radius);
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::MorphClose(radius) => {
+                *self.image = crate::operations::morphology::close(&self.image, *radius);
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::MorphOpen(radius) => {
+                *self.image = crate::operations::morphology::open(&self.image, *radius);
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::Noise(inputs) => {
+                *self.image = crate::operations::noise::noise(&self.image, inputs)?;
+                Ok(())
+            }
+            #[cfg(feature = "imageproc-ops")]
+            ImgOp::Perspective(inputs) => {
+                let background = background_color_or_none(&mut self.environment)
+                    .unwrap_or(PERSPECTIVE_DEFAULT_BACKGROUND);
+                *self.image =
+                    crate::operations::perspective::perspective(&self.image, inputs, background)?;
+                Ok(())
+            }
+            // We need to ensure here that Filter3x3's `it` (&[f32]) has length 9.
+            // Otherwise it will panic, see: https://docs.rs/image/0.19.0/src/image/dynimage.rs.html#349
+            // This check already happens within the `parse` module.
+            ImgOp::Filter3x3(ref it) => {
+                *self.image = self.image.filter3x3(it);
+                Ok(())
+            }
+            ImgOp::Find(inputs) => {
+                let template = inputs.template().open_image()?;
+                let found =
+                    crate::operations::find::find(&mut self.image, &template, inputs.outline())?;
+
+                self.analysis_results.push(AnalysisResult::TemplateMatch {
+                    template: inputs.template().path().to_path_buf(),
+                    x: found.x,
+                    y: found.y,
+                    width: found.width,
+                    height: found.height,
+                    score: found.score,
+                });
+                Ok(())
+            }
+            ImgOp::Fisheye(strength) => {
+                *self.image = crate::operations::fisheye::fisheye(&self.image, *strength)?;
+                Ok(())
+            }
+            ImgOp::FlipHorizontal => {
+                self.transform_hdr(imageops::flip_horizontal);
+                *self.image = self.image.fliph();
+                Ok(())
+            }
+            ImgOp::FlipVertical => {
+                self.transform_hdr(imageops::flip_vertical);
+                *self.image = self.image.flipv();
+                Ok(())
+            }
+            ImgOp::Gradient(inputs) => {
+                *self.image = crate::operations::gradient_fill::gradient_fill(&self.image, inputs)?;
+                Ok(())
+            }
+            ImgOp::GradientMap(gradient) => {
+                *self.image = crate::operations::gradient_map::gradient_map(&self.image, gradient)?;
+                Ok(())
+            }
+            ImgOp::GrayScale => {
+                *self.image = self.image.grayscale();
+                Ok(())
+            }
+            ImgOp::GrayscaleWeighted(weights) => {
+                *self.image =
+                    crate::operations::grayscale::grayscale_weighted(&self.image, *weights)?;
+                Ok(())
+            }
+            ImgOp::Hstack(img) => {
+                let other = img.open_image()?;
+                *self.image = crate::operations::stack::hstack(&self.image, &other)?;
+                Ok(())
+            }
+            ImgOp::HueRotate(degree) => {
+                *self.image = self.image.huerotate(*degree);
+                Ok(())
+            }
+            ImgOp::Invert => {
+                self.image.invert();
+                Ok(())
+            }
+            ImgOp::LocalMean(radius) => {
+                *self.image = crate::operations::local_statistics::local_mean(&self.image, *radius);
+                Ok(())
+            }
+            ImgOp::LocalVariance(radius) => {
+                *self.image =
+                    crate::operations::local_statistics::local_variance(&self.image, *radius);
+                Ok(())
+            }
+            ImgOp::Lut(lut) => {
+                let lut = lut.open_lut()?;
+                *self.image = crate::operations::lut::apply_lut(&self.image, &lut);
+                Ok(())
+            }
+            ImgOp::Mirror(mode) => {
+                *self.image = crate::operations::mirror::mirror(&self.image, *mode)?;
+                Ok(())
+            }
+            ImgOp::NineSlice(inputs) => {
+                let filter = resize_filter_or_default(&mut self.environment);
+
+                *self.image =
+                    crate::operations::nine_slice::nine_slice(&self.image, inputs, filter)?;
+                Ok(())
+            }
+            ImgOp::OilPaint((radius, levels)) => {
+                *self.image =
+                    crate::operations::oil_paint::oil_paint(&self.image, *radius, *levels)?;
+                Ok(())
+            }
+            ImgOp::Opacity(opacity) => {
+                *self.image = crate::operations::alpha::opacity(&self.image, *opacity)?;
+                Ok(())
+            }
+            ImgOp::Overlay(overlay) => {
+                let overlay_image = overlay.image_path().open_image()?;
+
+                // the position is measured from the edges towards which the overlay gravitates
+                let position = gravity_or_none(&mut self.environment)
+                    .unwrap_or(Gravity::TopLeft)
+                    .offset_position(
+                        self.image.dimensions(),
+                        overlay_image.dimensions(),
+                        overlay.position(),
+                    );
+
+                *self.image = crate::operations::composite::composite(
+                    &self.image,
+                    &overlay_image,
+                    position,
+                    overlay.opacity(),
+                    overlay.blend_mode(),
+                )?;
+
+                Ok(())
+            }
+            ImgOp::PadToRatio(inputs) => {
+                let color = background_color_or_none(&mut self.environment)
+                    .unwrap_or(PAD_TO_RATIO_DEFAULT_COLOR);
+                *self.image =
+                    crate::operations::pad_to_ratio::pad_to_ratio(&self.image, inputs, color)?;
+                Ok(())
+            }
+            ImgOp::PixelUpscale(inputs) => {
+                *self.image = crate::operations::pixel_upscale::pixel_upscale(&self.image, inputs)?;
+                Ok(())
+            }
+            ImgOp::RadialBlur((cx, cy, strength)) => {
+                *self.image = crate::operations::radial_blur::radial_blur(
+                    &self.image,
+                    (*cx, *cy),
+                    *strength,
+                )?;
+                Ok(())
+            }
+            ImgOp::Redact(inputs) => crate::operations::redact::redact(&mut self.image, inputs),
+            ImgOp::Resize((new_x, new_y)) => {
+                let filter = resize_filter_or_default(&mut self.environment);
+
+                // by default, the aspect ratio is not preserved
+                let preserve_aspect_ratio = matches!(
+                    self.environment.get(ItemName::PreserveAspectRatio),
+                    Some(EnvItem::PreserveAspectRatio(true))
+                );
+
+                *self.image = crate::operations::resample::resize(
+                    &self.image,
+                    *new_x,
+                    *new_y,
+                    filter,
+                    preserve_aspect_ratio,
+                )?;
+
+                Ok(())
+            }
+            ImgOp::Rotate90 => {
+                self.transform_hdr(imageops::rotate90);
+                *self.image = self.image.rotate90();
                 Ok(())
             }
             ImgOp::Rotate180 => {
+                self.transform_hdr(imageops::rotate180);
                 *self.image = self.image.rotate180();
                 Ok(())
             }
             ImgOp::Rotate270 => {
+                self.transform_hdr(imageops::rotate270);
                 *self.image = self.image.rotate270();
                 Ok(())
             }
+            ImgOp::Sharpen(amount) => {
+                *self.image = crate::operations::sharpen::sharpen(&self.image, *amount)?;
+                Ok(())
+            }
+            ImgOp::SmartCrop((width, height)) => {
+                *self.image =
+                    crate::operations::smart_crop::smart_crop(&self.image, (*width, *height))?;
+                Ok(())
+            }
+            ImgOp::Tonemap(inputs) => {
+                // within a region, the image is only a part of the high dynamic range image
+                *self.image = match (self.hdr.as_deref(), &self.hdr_dropped_by) {
+                    (Some(hdr), _) if hdr.dimensions() == self.image.dimensions() => {
+                        crate::operations::tonemap::tonemap(hdr, inputs)?
+                    }
+                    // the highlights would have been clipped without notice
+                    (None, Some(operation)) => {
+                        return Err(SicImageEngineError::TonemapAfterClampingOperation(
+                            operation.clone(),
+                        ))
+                    }
+                    _ => crate::operations::tonemap::tonemap(
+                        &sic_core::hdr::from_dynamic_image(&self.image),
+                        inputs,
+                    )?,
+                };
+                Ok(())
+            }
+            ImgOp::Stamp(stamp) => {
+                let stamp_image = stamp.image_path().open_image()?;
+                crate::operations::stamp::stamp(
+                    &mut self.image,
+                    &stamp_image,
+                    stamp.gravity(),
+                    stamp.margin(),
+                );
+
+                Ok(())
+            }
+            ImgOp::Trim(tolerance) => {
+                *self.image = crate::operations::trim::trim(&self.image, *tolerance)?;
+                Ok(())
+            }
             ImgOp::Unsharpen((sigma, threshold)) => {
                 *self.image = self.image.unsharpen(*sigma, *threshold);
                 Ok(())
             }
+            ImgOp::Vstack(img) => {
+                let other = img.open_image()?;
+                *self.image = crate::operations::stack::vstack(&self.image, &other)?;
+                Ok(())
+            }
         }
     }
 
@@ -241,6 +1282,19 @@ impl ImageEngine {
         Ok(())
     }
 
+    fn insert_occurrence_env(
+        &mut self,
+        occurrence: &Occurrence,
+        item: &EnvItem,
+    ) -> Result<(), SicImageEngineError> {
+        self.occurrence_environments
+            .entry(occurrence.clone())
+            .or_default()
+            .insert_or_update(item.clone());
+
+        Ok(())
+    }
+
     fn remove_env(&mut self, key: ItemName) -> Result<(), SicImageEngineError> {
         let success = self.environment.remove(key);
 
@@ -306,7 +1360,7 @@ fn produce_image_diff(
     Ok(DynamicImage::ImageRgba8(buffer))
 }
 
-struct CropSelection {
+pub(crate) struct CropSelection {
     lx: u32,
     ly: u32,
     rx: u32,
@@ -328,9 +1382,10 @@ impl CropSelection {
         }
     }
 
-    pub(crate) fn fits_within(&self, outer: &DynamicImage) -> Result<&Self, SicImageEngineError> {
-        let (dim_x, dim_y) = outer.dimensions();
-
+    pub(crate) fn fits_within(
+        &self,
+        (dim_x, dim_y): (u32, u32),
+    ) -> Result<&Self, SicImageEngineError> {
         match (
             self.lx <= dim_x,
             self.ly <= dim_y,
@@ -344,16 +1399,76 @@ impl CropSelection {
         }
     }
 
+    /// The selection of the same size, which is as far away from the edges towards which the
+    /// gravity points as the selection is from the top left corner of the image.
+    pub(crate) fn placed_by(
+        &self,
+        gravity: Gravity,
+        (dim_x, dim_y): (u32, u32),
+    ) -> Result<Self, SicImageEngineError> {
+        let (width, height) = self.size();
+        let (x, y) = gravity.offset_position((dim_x, dim_y), (width, height), (self.lx, self.ly));
+
+        let fits = x >= 0
+            && y >= 0
+            && x + i64::from(width) <= i64::from(dim_x)
+            && y + i64::from(height) <= i64::from(dim_y);
+
+        if fits {
+            let (x, y) = (x as u32, y as u32);
+            Ok(Self::new(x, y, x + width, y + height))
+        } else {
+            Err(SicImageEngineError::CropOutOfBoundsForGravity(
+                self.lx, self.ly, width, height, dim_x, dim_y,
+            ))
+        }
+    }
+
+    pub(crate) fn size(&self) -> (u32, u32) {
+        (self.rx - self.lx, self.ry - self.ly)
+    }
+
     fn are_dimensions_incorrect(&self) -> bool {
         (self.rx <= self.lx) || (self.ry <= self.ly)
     }
 }
 
-fn resize_filter_or_default(env: &mut Env) -> FilterType {
+fn resize_filter_or_default(env: &mut Env) -> FilterTypeWrap {
     env.get(ItemName::CustomSamplingFilter)
         .and_then(|item| item.resize_sampling_filter())
-        .map(FilterType::from)
-        .unwrap_or_else(|| FilterTypeWrap::default().into())
+        .unwrap_or_default()
+}
+
+pub(crate) fn mask_or_none(env: &mut Env) -> Option<ImageFromPath> {
+    env.get(ItemName::Mask)
+        .and_then(|item| item.mask())
+        .cloned()
+}
+
+pub(crate) fn region_or_none(env: &mut Env) -> Option<Region> {
+    env.get(ItemName::Region).and_then(|item| item.region())
+}
+
+pub(crate) fn gravity_or_none(env: &mut Env) -> Option<Gravity> {
+    env.get(ItemName::Gravity).and_then(|item| item.gravity())
+}
+
+fn background_color_or_none(env: &mut Env) -> Option<Rgba<u8>> {
+    env.get(ItemName::BackgroundColor)
+        .and_then(|item| item.background_color())
+}
+
+fn blur_algorithm_or_default(env: &mut Env) -> BlurAlgorithm {
+    env.get(ItemName::BlurAlgorithm)
+        .and_then(|item| item.blur_algorithm())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "imageproc-ops")]
+fn adaptive_threshold_method_or_default(env: &mut Env) -> AdaptiveThresholdMethod {
+    env.get(ItemName::AdaptiveThresholdMethod)
+        .and_then(|item| item.adaptive_threshold_method())
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -384,6 +1499,7 @@ mod compatibility {
 #[cfg(test)]
 mod environment_tests {
     use super::*;
+    use sic_core::image::imageops::FilterType;
 
     #[test]
     fn environment_insert() {
@@ -455,14 +1571,104 @@ mod environment_tests {
     }
 }
 
+#[cfg(test)]
+mod serialization_tests {
+    use super::*;
+    use crate::wrapper::aspect_ratio::AspectRatio;
+    use crate::wrapper::border::BorderInputs;
+    use crate::wrapper::gradient::{ColorStop, Gradient};
+    use crate::wrapper::kernel::Kernel;
+    use crate::wrapper::pad_to_ratio::PadToRatioInputs;
+    use sic_core::image::imageops::FilterType;
+
+    fn roundtrip(program: &[Instr]) -> Vec<Instr> {
+        let json = serde_json::to_string(program).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn serialize_by_name() {
+        let program = vec![
+            Instr::Operation(ImgOp::Resize((10, 20))),
+            Instr::Operation(ImgOp::GrayScale),
+            Instr::EnvAdd(EnvItem::BackgroundColor(Rgba([255, 0, 0, 255]))),
+            Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::new(
+                FilterType::Triangle,
+            ))),
+            Instr::EnvRemove(ItemName::CustomSamplingFilter),
+            Instr::Deferred(DeferredOp::Resize([
+                Dimension::Percentage(50.0),
+                Dimension::try_from_str("$height/2").unwrap(),
+            ])),
+        ];
+
+        assert_eq!(
+            serde_json::to_string(&program).unwrap(),
+            r#"[{"operation":{"resize":[10,20]}},{"operation":"grayscale"},{"env-add":{"background-color":[255,0,0,255]}},{"env-add":{"custom-sampling-filter":"triangle"}},{"env-remove":"custom-sampling-filter"},{"deferred":{"resize":[{"percentage":50.0},{"expression":"$height/2"}]}}]"#
+        );
+        assert_eq!(roundtrip(&program), program);
+    }
+
+    #[test]
+    fn roundtrip_inputs() {
+        let program = vec![
+            Instr::Operation(ImgOp::Border(
+                BorderInputs::new(&[1, 2], Rgba([0, 0, 0, 255])).unwrap(),
+            )),
+            Instr::Operation(ImgOp::Convolve(Kernel::new(1, vec![1.0]).unwrap())),
+            Instr::Operation(ImgOp::Duotone((Rgba([0, 0, 0, 255]), Rgba([9, 9, 9, 9])))),
+            Instr::Operation(ImgOp::GradientMap(Gradient::new(vec![
+                ColorStop::new(1.0, Rgba([255, 255, 255, 255])),
+                ColorStop::new(0.0, Rgba([0, 0, 0, 255])),
+            ]))),
+            Instr::Operation(ImgOp::PadToRatio(PadToRatioInputs::new(
+                AspectRatio::try_from_str("16:9").unwrap(),
+                None,
+            ))),
+            Instr::EnvAddFor(
+                Occurrence::new("blur", 1).unwrap(),
+                EnvItem::Gravity(Gravity::BottomRight),
+            ),
+        ];
+
+        assert_eq!(roundtrip(&program), program);
+    }
+
+    #[test]
+    fn deserialize_checks_inputs() {
+        let invalid = [
+            r#"{"operation":{"convolve":{"size":2,"values":[1.0,1.0,1.0,1.0]}}}"#,
+            r#"{"operation":{"pad-to-ratio":{"ratio":"16:0"}}}"#,
+            r#"{"env-add":{"custom-sampling-filter":"bilinear"}}"#,
+            r#"{"deferred":{"crop":[{"pixels":0},{"pixels":0},{"expression":"$depth"},{"pixels":0}]}}"#,
+        ];
+
+        for json in invalid.iter() {
+            assert!(serde_json::from_str::<Instr>(json).is_err(), "{}", json);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::engine::compatibility::*;
+    use crate::wrapper::aspect_ratio::AspectRatio;
+    use crate::wrapper::border::BorderInputs;
+    use crate::wrapper::channel::Channel;
+    use crate::wrapper::extend::ExtendInputs;
+    use crate::wrapper::gradient_fill::{GradientFillInputs, GradientKind, GRADIENT_DEFAULT_ANGLE};
+    use crate::wrapper::gravity::Gravity;
+    use crate::wrapper::histogram::{DrawHistogramInputs, HistogramChannels, HistogramPlacement};
     use crate::wrapper::image_path::ImageFromPath;
+    use crate::wrapper::lut::LutFromPath;
+    use crate::wrapper::mirror::MirrorMode;
+    use crate::wrapper::pad_to_ratio::PadToRatioInputs;
+    use crate::wrapper::pixel_upscale::{PixelUpscaleAlgorithm, PixelUpscaleInputs};
+    use crate::wrapper::redact::{RedactInputs, RedactStyle};
     use sic_core::image::imageops::FilterType;
     use sic_core::image::GenericImageView;
-    use sic_core::image::Rgba;
+    use sic_core::image::{Pixel, Rgba};
     use sic_testing::*;
     use std::path::PathBuf;
 
@@ -478,6 +1684,26 @@ mod tests {
         sic_testing::open_test_image(sic_testing::in_!(DEFAULT_TEST_IMAGE_PATH))
     }
 
+    #[test]
+    fn check_files() {
+        let lut = LutFromPath::new(PathBuf::from(in_!("lut/invert.cube")));
+        let missing = ImageFromPath::new(PathBuf::from(in_!("missing.png")));
+
+        assert!(Instr::Operation(ImgOp::Lut(lut)).check_files().is_ok());
+        assert!(Instr::Operation(ImgOp::Blur(1.0)).check_files().is_ok());
+        assert!(Instr::Operation(ImgOp::Diff(missing.clone()))
+            .check_files()
+            .is_err());
+        assert!(Instr::EnvAdd(EnvItem::Mask(missing)).check_files().is_err());
+        assert!(
+            Instr::Operation(ImgOp::Lut(LutFromPath::new(PathBuf::from(in_!(
+                "lut/missing.cube"
+            )))))
+            .check_files()
+            .is_err()
+        );
+    }
+
     #[test]
     fn diff_check_out_pixels() {
         const LEFT: &str = "2x3_wrabaa.png";
@@ -507,49 +1733,266 @@ mod tests {
         output_test_image_for_manual_inspection(&out, out_!("test_diff_3x3.png"));
     }
 
-    mod sizes {
-        use super::*;
+    #[test]
+    fn hdr_is_kept_until_an_operation_is_applied() {
+        let hdr = Rgba32FImage::from_pixel(2, 2, Rgba([4.0, 0.5, 0.0, 1.0]));
+        let image = sic_core::hdr::to_dynamic_image(&hdr);
+        let mut engine = ImageEngine::new(image).with_hdr(Some(hdr.clone()));
+
+        engine
+            .ignite(&[Instr::EnvAdd(EnvItem::PreserveAspectRatio(true))])
+            .unwrap();
+        assert_eq!(engine.hdr_image(), Some(&hdr));
+
+        engine
+            .ignite(&[Instr::Operation(ImgOp::Blur(1.0))])
+            .unwrap();
+        assert!(engine.hdr_image().is_none());
+        assert_eq!(engine.hdr_dropped_by(), Some("blur"));
+    }
 
-        ide!();
+    #[test]
+    fn hdr_is_transformed_with_the_image() {
+        let hdr = Rgba32FImage::from_fn(3, 2, |x, y| Rgba([x as f32 * 4.0, y as f32, 0.0, 1.0]));
+        let image = sic_core::hdr::to_dynamic_image(&hdr);
+        let mut engine = ImageEngine::new(image).with_hdr(Some(hdr));
+
+        engine
+            .ignite(&[
+                Instr::Operation(ImgOp::FlipHorizontal),
+                Instr::Operation(ImgOp::Rotate90),
+                Instr::Operation(ImgOp::Crop((0, 1, 2, 3))),
+            ])
+            .unwrap();
+
+        // after the flip, rotation and crop, pixel (x, y) comes from (1 - y, 1 - x) of the input
+        let hdr = engine.hdr_image().unwrap();
+        assert_eq!(hdr.dimensions(), (2, 2));
+        assert_eq!(engine.image().dimensions(), (2, 2));
+        assert_eq!(hdr.get_pixel(0, 0), &Rgba([4.0, 1.0, 0.0, 1.0]));
+        assert_eq!(hdr.get_pixel(1, 1), &Rgba([0.0, 0.0, 0.0, 1.0]));
+        assert!(engine.hdr_dropped_by().is_none());
+    }
 
-        #[pm(
-            left = {
-                "1x1_a.png",
-                "2x2_baab.png",
-                "2x3_yrgyyb.bmp",
-                "2x2_baab.png",
-            },
-            right = {
-                "1x1_b.png",
-                "1x1_b.png",
-                "3x2_ygyryb.bmp",
-                "2x3_rrgrbb.bmp",
-            },
-            expected_width = {
-                1,
-                2,
-                3,
-                2,
-            },
-            expected_height = {
-                1,
-                2,
-                3,
-                3,
-            },
-        )]
-        fn diff_has_expected_width_and_height(
-            left: &str,
-            right: &str,
-            expected_width: u32,
-            expected_height: u32,
-        ) {
-            let left_img = sic_testing::open_test_image(sic_testing::in_!(left));
+    #[test]
+    fn hdr_is_dropped_by_a_flip_within_a_region() {
+        let hdr = Rgba32FImage::from_pixel(4, 4, Rgba([4.0, 0.5, 0.0, 1.0]));
+        let image = sic_core::hdr::to_dynamic_image(&hdr);
+        let mut engine = ImageEngine::new(image).with_hdr(Some(hdr));
+
+        engine
+            .ignite(&[
+                Instr::EnvAdd(EnvItem::Region(Region::new((0, 0), (2, 2)))),
+                Instr::Operation(ImgOp::FlipVertical),
+            ])
+            .unwrap();
+
+        assert!(engine.hdr_image().is_none());
+        assert_eq!(engine.hdr_dropped_by(), Some("flip-vertical"));
+    }
 
-            let mut engine = ImageEngine::new(left_img);
-            let out = engine.ignite(&[Instr::Operation(ImgOp::Diff(ImageFromPath::new(
-                PathBuf::from(in_!(right)),
-            )))]);
+    #[test]
+    fn tonemap_uses_the_hdr_samples() {
+        use crate::wrapper::tonemap::{ToneMapInputs, ToneMapOperator};
+
+        let hdr = Rgba32FImage::from_fn(2, 1, |x, _| Rgba([x as f32 * 4.0 + 2.0, 0.0, 0.0, 1.0]));
+        let image = sic_core::hdr::to_dynamic_image(&hdr);
+        let mut engine = ImageEngine::new(image).with_hdr(Some(hdr));
+
+        let done = engine.ignite(&[Instr::Operation(ImgOp::Tonemap(ToneMapInputs::new(
+            ToneMapOperator::Reinhard,
+            0.0,
+        )))]);
+
+        let out = done.unwrap().to_rgba();
+        // clamped samples would both be white
+        assert!(out.get_pixel(0, 0)[0] < out.get_pixel(1, 0)[0]);
+        assert!(out.get_pixel(1, 0)[0] < 255);
+        assert!(engine.hdr_image().is_none());
+    }
+
+    #[test]
+    fn tonemap_after_clamping_operation() {
+        use crate::wrapper::tonemap::{ToneMapInputs, ToneMapOperator};
+
+        let hdr = Rgba32FImage::from_pixel(2, 1, Rgba([4.0, 0.5, 0.0, 1.0]));
+        let image = sic_core::hdr::to_dynamic_image(&hdr);
+        let mut engine = ImageEngine::new(image).with_hdr(Some(hdr));
+
+        let done = engine.ignite(&[
+            Instr::Operation(ImgOp::Brighten(1)),
+            Instr::Operation(ImgOp::Tonemap(ToneMapInputs::new(
+                ToneMapOperator::Reinhard,
+                0.0,
+            ))),
+        ]);
+
+        assert!(matches!(
+            done,
+            Err(SicImageEngineError::TonemapAfterClampingOperation(operation)) if operation == "brighten"
+        ));
+    }
+
+    #[test]
+    fn tonemap_after_geometric_operation() {
+        use crate::wrapper::tonemap::{ToneMapInputs, ToneMapOperator};
+
+        let hdr = Rgba32FImage::from_fn(2, 1, |x, _| Rgba([x as f32 * 4.0 + 2.0, 0.0, 0.0, 1.0]));
+        let image = sic_core::hdr::to_dynamic_image(&hdr);
+        let mut engine = ImageEngine::new(image).with_hdr(Some(hdr));
+
+        let done = engine.ignite(&[
+            Instr::Operation(ImgOp::FlipHorizontal),
+            Instr::Operation(ImgOp::Tonemap(ToneMapInputs::new(
+                ToneMapOperator::Reinhard,
+                0.0,
+            ))),
+        ]);
+
+        let out = done.unwrap().to_rgba();
+        assert!(out.get_pixel(0, 0)[0] > out.get_pixel(1, 0)[0]);
+        assert!(out.get_pixel(0, 0)[0] < 255);
+    }
+
+    mod animation {
+        use super::*;
+        use sic_core::image::{Delay, RgbaImage};
+
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+        const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+        fn animation() -> AnimatedImage {
+            let frame = |color, delay| {
+                Frame::from_parts(
+                    RgbaImage::from_pixel(4, 2, color),
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(delay, 1),
+                )
+            };
+
+            AnimatedImage::from_frames(vec![frame(RED, 100), frame(BLUE, 200)]).unwrap()
+        }
+
+        fn engine() -> ImageEngine {
+            let placeholder = DynamicImage::new_rgba8(1, 1);
+            ImageEngine::new(placeholder).with_animation(Some(animation()))
+        }
+
+        #[test]
+        fn image_is_the_first_frame() {
+            let engine = engine();
+
+            assert_eq!(engine.image().dimensions(), (4, 2));
+            assert_eq!(engine.image().get_pixel(0, 0), RED);
+        }
+
+        #[test]
+        fn operations_are_applied_to_every_frame() {
+            let mut engine = engine();
+            let done = engine.ignite(&[
+                Instr::Operation(ImgOp::FlipHorizontal),
+                Instr::Operation(ImgOp::Crop((0, 0, 2, 1))),
+                Instr::Operation(ImgOp::Invert),
+            ]);
+
+            assert_eq!(done.unwrap().dimensions(), (2, 1));
+
+            let frames = engine.animation().unwrap().frames();
+            assert_eq!(frames.len(), 2);
+            assert_eq!(
+                frames[0].buffer().get_pixel(0, 0),
+                &Rgba([0, 255, 255, 255])
+            );
+            assert_eq!(
+                frames[1].buffer().get_pixel(0, 0),
+                &Rgba([255, 255, 0, 255])
+            );
+            assert_eq!(frames[0].buffer().dimensions(), (2, 1));
+            assert_eq!(frames[1].buffer().dimensions(), (2, 1));
+        }
+
+        #[test]
+        fn delays_are_kept() {
+            let mut engine = engine();
+            engine
+                .ignite(&[Instr::Operation(ImgOp::Resize((2, 1)))])
+                .unwrap();
+
+            let delays = engine
+                .animation()
+                .unwrap()
+                .frames()
+                .iter()
+                .map(|frame| frame.delay().numer_denom_ms())
+                .collect::<Vec<_>>();
+            assert_eq!(delays, vec![(100, 1), (200, 1)]);
+        }
+
+        // e.g. with --explain-dir, the instructions are applied one by one
+        #[test]
+        fn environment_is_kept_between_ignitions() {
+            let mut engine = engine();
+            let extend = ExtendInputs::new((6, 2), Some(Gravity::Left), None);
+
+            engine
+                .ignite(&[Instr::EnvAdd(EnvItem::BackgroundColor(Rgba([
+                    0, 255, 0, 255,
+                ])))])
+                .unwrap();
+            engine
+                .ignite(&[Instr::Operation(ImgOp::Extend(extend))])
+                .unwrap();
+
+            for frame in engine.animation().unwrap().frames() {
+                assert_eq!(frame.buffer().get_pixel(5, 1), &Rgba([0, 255, 0, 255]));
+            }
+        }
+    }
+
+    mod sizes {
+        use super::*;
+
+        ide!();
+
+        #[pm(
+            left = {
+                "1x1_a.png",
+                "2x2_baab.png",
+                "2x3_yrgyyb.bmp",
+                "2x2_baab.png",
+            },
+            right = {
+                "1x1_b.png",
+                "1x1_b.png",
+                "3x2_ygyryb.bmp",
+                "2x3_rrgrbb.bmp",
+            },
+            expected_width = {
+                1,
+                2,
+                3,
+                2,
+            },
+            expected_height = {
+                1,
+                2,
+                3,
+                3,
+            },
+        )]
+        fn diff_has_expected_width_and_height(
+            left: &str,
+            right: &str,
+            expected_width: u32,
+            expected_height: u32,
+        ) {
+            let left_img = sic_testing::open_test_image(sic_testing::in_!(left));
+
+            let mut engine = ImageEngine::new(left_img);
+            let out = engine.ignite(&[Instr::Operation(ImgOp::Diff(ImageFromPath::new(
+                PathBuf::from(in_!(right)),
+            )))]);
 
             let out = out.unwrap();
 
@@ -669,6 +2112,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn resize_with_sampling_filter_mitchell() {
+        use crate::wrapper::filter_type::CustomFilterType;
+
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut engine = ImageEngine::new(img);
+        let mut engine2 = engine.clone();
+        let cmp_left = engine.ignite(&[
+            Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::custom(
+                CustomFilterType::Mitchell,
+            ))),
+            Instr::Operation(ImgOp::Resize((100, 100))),
+        ]);
+
+        let cmp_right = engine2.ignite(&[Instr::Operation(ImgOp::Resize((100, 100)))]);
+
+        let left = cmp_left.unwrap();
+        let right = cmp_right.unwrap();
+
+        assert_eq!(left.dimensions(), (100, 100));
+        assert_ne!(left.raw_pixels(), right.raw_pixels());
+
+        output_test_image_for_manual_inspection(
+            left,
+            out_!("test_resize_sampling_filter_mitchell.png"),
+        );
+    }
+
     #[test]
     fn register_unregister_sampling_filter() {
         let img: DynamicImage = setup_default_test_image();
@@ -706,269 +2178,703 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_blur() {
-        let img: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::Blur(10.0);
-
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
-
-        assert!(done.is_ok());
+    mod occurrence_environment {
+        use super::*;
 
-        output_test_image_for_manual_inspection(&done.unwrap(), out_!("test_blur.png"));
-    }
+        fn nearest() -> EnvItem {
+            EnvItem::CustomSamplingFilter(FilterTypeWrap::new(FilterType::Nearest))
+        }
 
-    #[test]
-    fn test_brighten_pos() {
-        let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
+        fn ignite(instructions: &[Instr]) -> DynamicImage {
+            let mut engine = ImageEngine::new(setup_default_test_image());
+            engine.ignite(instructions).unwrap().clone()
+        }
 
-        let operation = ImgOp::Brighten(25);
+        #[test]
+        fn applies_to_single_occurrence() {
+            let scoped = ignite(&[
+                Instr::EnvAddFor(Occurrence::new("resize", 1).unwrap(), nearest()),
+                Instr::Operation(ImgOp::Resize((100, 100))),
+                Instr::Operation(ImgOp::Resize((50, 50))),
+                Instr::Operation(ImgOp::Resize((20, 20))),
+            ]);
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+            let expected = ignite(&[
+                Instr::Operation(ImgOp::Resize((100, 100))),
+                Instr::EnvAdd(nearest()),
+                Instr::Operation(ImgOp::Resize((50, 50))),
+                Instr::EnvRemove(ItemName::CustomSamplingFilter),
+                Instr::Operation(ImgOp::Resize((20, 20))),
+            ]);
+            let everywhere = ignite(&[
+                Instr::EnvAdd(nearest()),
+                Instr::Operation(ImgOp::Resize((100, 100))),
+                Instr::Operation(ImgOp::Resize((50, 50))),
+                Instr::Operation(ImgOp::Resize((20, 20))),
+            ]);
 
-        assert!(done.is_ok());
+            assert_eq!(scoped.raw_pixels(), expected.raw_pixels());
+            assert_ne!(scoped.raw_pixels(), everywhere.raw_pixels());
+        }
 
-        let result_img = done.unwrap();
+        #[test]
+        fn counts_operations_before_it() {
+            let scoped = ignite(&[
+                Instr::Operation(ImgOp::Resize((100, 100))),
+                Instr::EnvAddFor(Occurrence::new("resize", 1).unwrap(), nearest()),
+                Instr::Operation(ImgOp::Resize((50, 50))),
+            ]);
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+            let expected = ignite(&[
+                Instr::Operation(ImgOp::Resize((100, 100))),
+                Instr::EnvAdd(nearest()),
+                Instr::Operation(ImgOp::Resize((50, 50))),
+            ]);
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_pos_25.png"));
-    }
+            assert_eq!(scoped.raw_pixels(), expected.raw_pixels());
+        }
 
-    #[test]
-    fn test_brighten_zero() {
-        let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::Brighten(0);
+        #[test]
+        fn check_used_occurrences() {
+            let program = [
+                Instr::Operation(ImgOp::Resize((100, 100))),
+                Instr::EnvAddFor(Occurrence::new("resize", 1).unwrap(), nearest()),
+                Instr::EnvAddFor(Occurrence::new("crop", 0).unwrap(), nearest()),
+                Instr::Deferred(DeferredOp::Resize([
+                    Dimension::Pixels(50),
+                    Dimension::Pixels(50),
+                ])),
+                Instr::Deferred(DeferredOp::Crop([
+                    Dimension::Pixels(0),
+                    Dimension::Pixels(0),
+                    Dimension::Pixels(10),
+                    Dimension::Pixels(10),
+                ])),
+            ];
+
+            assert!(check_occurrences(&program).is_ok());
+        }
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        #[test]
+        fn check_occurrence_never_applied() {
+            let program = [
+                Instr::EnvAddFor(Occurrence::new("resize", 5).unwrap(), nearest()),
+                Instr::Operation(ImgOp::Resize((2, 2))),
+            ];
+
+            assert!(matches!(
+                check_occurrences(&program),
+                Err(SicImageEngineError::UnusedOccurrence(occurrence)) if occurrence == "resize.5"
+            ));
+        }
 
-        assert!(done.is_ok());
+        #[test]
+        fn check_occurrence_applied_before_it_is_set() {
+            let program = [
+                Instr::Operation(ImgOp::Resize((100, 100))),
+                Instr::EnvAddFor(Occurrence::new("resize", 0).unwrap(), nearest()),
+                Instr::Operation(ImgOp::Resize((50, 50))),
+            ];
+
+            assert!(matches!(
+                check_occurrences(&program),
+                Err(SicImageEngineError::UnusedOccurrence(occurrence)) if occurrence == "resize.0"
+            ));
+        }
 
-        let result_img = done.unwrap();
+        #[test]
+        fn takes_precedence_over_environment() {
+            let (w, h) = setup_default_test_image().dimensions();
+            let top = Region::new((0, 0), (w, h / 2));
+            let bottom = Region::new((0, h / 2), (w, h - h / 2));
+
+            let scoped = ignite(&[
+                Instr::EnvAdd(EnvItem::Region(top)),
+                Instr::EnvAddFor(
+                    Occurrence::new("invert", 0).unwrap(),
+                    EnvItem::Region(bottom),
+                ),
+                Instr::Operation(ImgOp::Invert),
+                Instr::Operation(ImgOp::Blur(2.0)),
+            ]);
 
-        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+            let expected = ignite(&[
+                Instr::EnvAdd(EnvItem::Region(bottom)),
+                Instr::Operation(ImgOp::Invert),
+                Instr::EnvAdd(EnvItem::Region(top)),
+                Instr::Operation(ImgOp::Blur(2.0)),
+            ]);
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_zero.png"));
-    }
+            assert_eq!(scoped.raw_pixels(), expected.raw_pixels());
+        }
 
-    #[test]
-    fn test_brighten_neg() {
-        let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
+        #[test]
+        fn other_operations_are_not_counted() {
+            let scoped = ignite(&[
+                Instr::EnvAddFor(Occurrence::new("resize", 0).unwrap(), nearest()),
+                Instr::Operation(ImgOp::Blur(1.0)),
+                Instr::Operation(ImgOp::Resize((50, 50))),
+            ]);
 
-        let operation = ImgOp::Brighten(-25);
+            let expected = ignite(&[
+                Instr::Operation(ImgOp::Blur(1.0)),
+                Instr::EnvAdd(nearest()),
+                Instr::Operation(ImgOp::Resize((50, 50))),
+            ]);
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+            assert_eq!(scoped.raw_pixels(), expected.raw_pixels());
+        }
 
-        assert!(done.is_ok());
+        #[test]
+        fn unknown_operation() {
+            assert!(Occurrence::new("resise", 0).is_err());
+            assert!(Occurrence::new("grayscale", 0).is_ok());
+        }
+    }
 
-        let result_img = done.unwrap();
+    mod gravity {
+        use super::*;
+        use crate::wrapper::overlay::OverlayInputs;
+        use sic_core::image::{Rgb, RgbImage};
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        ide!();
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_neg_25.png"));
-    }
+        fn ignite(image: DynamicImage, instructions: &[Instr]) -> DynamicImage {
+            let mut engine = ImageEngine::new(image);
+            engine.ignite(instructions).unwrap().clone()
+        }
 
-    #[test]
-    fn test_contrast_pos() {
-        let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
+        #[test]
+        fn crop_measured_from_bottom_right() {
+            let img = setup_default_test_image();
+            let (width, height) = img.dimensions();
+
+            let result = ignite(
+                img.clone(),
+                &[
+                    Instr::EnvAdd(EnvItem::Gravity(Gravity::BottomRight)),
+                    Instr::Operation(ImgOp::Crop((5, 0, 15, 10))),
+                ],
+            );
 
-        let operation = ImgOp::Contrast(150.9);
+            let expected = img.crop_imm(width - 15, height - 10, 10, 10);
+            assert_eq!(result.raw_pixels(), expected.raw_pixels());
+        }
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        #[test]
+        fn crop_with_top_left_gravity_is_unchanged() {
+            let img = setup_default_test_image();
 
-        assert!(done.is_ok());
+            let result = ignite(
+                img.clone(),
+                &[
+                    Instr::EnvAdd(EnvItem::Gravity(Gravity::TopLeft)),
+                    Instr::Operation(ImgOp::Crop((5, 3, 15, 10))),
+                ],
+            );
 
-        let result_img = done.unwrap();
+            assert_eq!(result.raw_pixels(), img.crop_imm(5, 3, 10, 7).raw_pixels());
+        }
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        #[test]
+        fn crop_out_of_bounds() {
+            let img = setup_default_test_image();
+            let (width, _) = img.dimensions();
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_contrast_pos_15_9.png"));
-    }
+            let mut engine = ImageEngine::new(img);
+            let result = engine.ignite(&[
+                Instr::EnvAdd(EnvItem::Gravity(Gravity::BottomRight)),
+                Instr::Operation(ImgOp::Crop((10, 0, width + 5, 10))),
+            ]);
 
-    #[test]
-    fn test_contrast_neg() {
-        let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
+            assert!(matches!(
+                result,
+                Err(SicImageEngineError::CropOutOfBoundsForGravity(..))
+            ));
+        }
 
-        let operation = ImgOp::Contrast(-150.9);
+        #[pm(
+            gravity = { Gravity::BottomRight, Gravity::Center, Gravity::Top },
+            expected = { (6, 4), (4, 3), (4, 1) }
+        )]
+        fn overlay_positioned_by_gravity(gravity: Gravity, expected: (u32, u32)) {
+            let black = DynamicImage::ImageRgb8(RgbImage::new(8, 6));
+            let white = ImageFromPath::new(in_!("1x1_w.png").into());
+
+            let result = ignite(
+                black,
+                &[
+                    Instr::EnvAdd(EnvItem::Gravity(gravity)),
+                    Instr::Operation(ImgOp::Overlay(OverlayInputs::new(white, (1, 1)))),
+                ],
+            );
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+            let white_pixels = result
+                .pixels()
+                .filter(|(_, _, pixel)| pixel[0] == 255)
+                .map(|(x, y, _)| (x, y))
+                .collect::<Vec<_>>();
 
-        assert!(done.is_ok());
+            assert_eq!(white_pixels, vec![expected]);
+        }
 
-        let result_img = done.unwrap();
+        #[test]
+        fn extend_uses_gravity_if_none_given() {
+            let red = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([255, 0, 0])));
+            let extend = |gravity| {
+                Instr::Operation(ImgOp::Extend(ExtendInputs::new(
+                    (6, 4),
+                    gravity,
+                    Some(Rgba([0, 0, 0, 255])),
+                )))
+            };
+
+            let by_modifier = ignite(
+                red.clone(),
+                &[
+                    Instr::EnvAdd(EnvItem::Gravity(Gravity::BottomRight)),
+                    extend(None),
+                ],
+            );
+            assert_eq!(by_modifier.get_pixel(5, 3), Rgba([255, 0, 0, 255]));
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+            let by_default = ignite(red.clone(), &[extend(None)]);
+            assert_eq!(by_default.get_pixel(2, 1), Rgba([255, 0, 0, 255]));
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_contrast_pos_15_9.png"));
+            let given = ignite(
+                red,
+                &[
+                    Instr::EnvAdd(EnvItem::Gravity(Gravity::BottomRight)),
+                    extend(Some(Gravity::TopLeft)),
+                ],
+            );
+            assert_eq!(given.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+            assert_eq!(given.get_pixel(5, 3), Rgba([0, 0, 0, 255]));
+        }
     }
 
-    #[test]
-    fn test_crop_ok_no_change() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
-        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    mod background {
+        use super::*;
+        use crate::wrapper::aspect_ratio::AspectRatio;
+        use crate::wrapper::pad_to_ratio::PadToRatioInputs;
+        use sic_core::image::{Rgb, RgbImage, RgbaImage};
 
-        let operation = ImgOp::Crop((0, 0, 2, 2));
+        const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        fn ignite(image: DynamicImage, instructions: &[Instr]) -> DynamicImage {
+            let mut engine = ImageEngine::new(image);
+            engine.ignite(instructions).unwrap().clone()
+        }
 
-        assert!(done.is_ok());
+        fn red() -> DynamicImage {
+            DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([255, 0, 0])))
+        }
 
-        let result_img = done.unwrap();
+        #[test]
+        fn extend_uses_background_if_no_color_given() {
+            let extend = |color| {
+                Instr::Operation(ImgOp::Extend(ExtendInputs::new(
+                    (4, 2),
+                    Some(Gravity::Left),
+                    color,
+                )))
+            };
+            let background = Instr::EnvAdd(EnvItem::BackgroundColor(GREEN));
+
+            let by_modifier = ignite(red(), &[background.clone(), extend(None)]);
+            assert_eq!(by_modifier.get_pixel(3, 1), GREEN);
+
+            let by_default = ignite(red(), &[extend(None)]);
+            assert_eq!(by_default.get_pixel(3, 1), Rgba([0, 0, 0, 0]));
+
+            let given = ignite(red(), &[background, extend(Some(Rgba([0, 0, 255, 255])))]);
+            assert_eq!(given.get_pixel(3, 1), Rgba([0, 0, 255, 255]));
+        }
 
-        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+        #[test]
+        fn pad_to_ratio_uses_background_if_no_color_given() {
+            let inputs = PadToRatioInputs::new(AspectRatio::try_from_str("2:1").unwrap(), None);
+
+            let result = ignite(
+                red(),
+                &[
+                    Instr::EnvAdd(EnvItem::BackgroundColor(GREEN)),
+                    Instr::Operation(ImgOp::PadToRatio(inputs)),
+                ],
+            );
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_crop_no_change.bmp"));
+            assert_eq!(result.dimensions(), (4, 2));
+            assert_eq!(result.get_pixel(0, 0), GREEN);
+            assert_eq!(result.get_pixel(1, 0), Rgba([255, 0, 0, 255]));
+        }
+
+        #[test]
+        fn alpha_remove_flattens_onto_background() {
+            let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([255, 0, 0, 0])));
+
+            let flattened = ignite(
+                image.clone(),
+                &[
+                    Instr::EnvAdd(EnvItem::BackgroundColor(GREEN)),
+                    Instr::Operation(ImgOp::AlphaRemove),
+                ],
+            );
+            assert_eq!(flattened.get_pixel(0, 0), GREEN);
+
+            let removed = ignite(image, &[Instr::Operation(ImgOp::AlphaRemove)]);
+            assert_eq!(removed.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        }
+
+        #[test]
+        fn unset_background() {
+            let result = ignite(
+                red(),
+                &[
+                    Instr::EnvAdd(EnvItem::BackgroundColor(GREEN)),
+                    Instr::EnvRemove(ItemName::BackgroundColor),
+                    Instr::Operation(ImgOp::Extend(ExtendInputs::new((3, 2), None, None))),
+                ],
+            );
+
+            assert_eq!(result.get_pixel(2, 0), Rgba([0, 0, 0, 0]));
+        }
     }
 
-    #[test]
-    fn test_crop_ok_to_one_pixel() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
-        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    mod deferred {
+        use super::*;
+        use crate::wrapper::dimension::Dimension::{Percentage, Pixels};
 
-        let operation = ImgOp::Crop((0, 0, 1, 1));
+        fn ignite(image: DynamicImage, instructions: &[Instr]) -> DynamicImage {
+            let mut engine = ImageEngine::new(image);
+            engine.ignite(instructions).unwrap().clone()
+        }
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        #[test]
+        fn crop_by_percentage() {
+            // 217x447px => 173x357px
+            let img = setup_default_test_image();
+            let op = DeferredOp::Crop([
+                Percentage(10.0),
+                Percentage(10.0),
+                Percentage(90.0),
+                Percentage(90.0),
+            ]);
 
-        assert!(done.is_ok());
+            let result = ignite(img.clone(), &[Instr::Deferred(op)]);
+            let expected = ignite(img, &[Instr::Operation(ImgOp::Crop((22, 45, 195, 402)))]);
 
-        let result_img = done.unwrap();
+            assert_eq!(result.dimensions(), (173, 357));
+            assert_eq!(result.to_bytes(), expected.to_bytes());
+        }
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        #[test]
+        fn resolved_against_size_when_applied() {
+            let img = setup_default_test_image();
 
-        let result_dim = result_img.dimensions();
-        assert_eq!(1, result_dim.0);
-        assert_eq!(1, result_dim.1);
+            let result = ignite(
+                img,
+                &[
+                    Instr::Operation(ImgOp::Resize((100, 200))),
+                    Instr::Deferred(DeferredOp::Resize([Percentage(50.0), Pixels(50)])),
+                ],
+            );
 
-        assert_eq!(Rgba([0, 0, 0, 255]), result_img.get_pixel(0, 0));
+            assert_eq!(result.dimensions(), (50, 50));
+        }
 
-        output_test_image_for_manual_inspection(
-            &result_img,
-            out_!("test_crop_ok_to_one_pixel.bmp"),
-        );
+        #[test]
+        fn occurrence_counts_resolved_operation() {
+            let img = setup_default_test_image();
+            let nearest =
+                EnvItem::CustomSamplingFilter(FilterTypeWrap::try_from_str("nearest").unwrap());
+
+            let deferred = ignite(
+                img.clone(),
+                &[
+                    Instr::EnvAddFor(Occurrence::new("resize", 0).unwrap(), nearest.clone()),
+                    Instr::Deferred(DeferredOp::Resize([Percentage(10.0), Percentage(10.0)])),
+                ],
+            );
+            let resized = ignite(
+                img,
+                &[
+                    Instr::EnvAdd(nearest),
+                    Instr::Operation(ImgOp::Resize((22, 45))),
+                ],
+            );
+
+            assert_eq!(deferred.to_bytes(), resized.to_bytes());
+        }
+
+        #[test]
+        fn crop_by_variables() {
+            let img = setup_default_test_image();
+            let op = DeferredOp::Crop([
+                Pixels(0),
+                Pixels(0),
+                Dimension::try_from_str("$width").unwrap(),
+                Dimension::try_from_str("$height/2").unwrap(),
+            ]);
+
+            let result = ignite(img, &[Instr::Deferred(op)]);
+
+            assert_eq!(result.dimensions(), (217, 224));
+        }
+
+        #[test]
+        fn variables_of_frame_count() {
+            let img = setup_default_test_image();
+            let op = DeferredOp::Resize([
+                Dimension::try_from_str("$frames*10").unwrap(),
+                Dimension::try_from_str("$width/$aspect").unwrap(),
+            ]);
+
+            let mut engine = ImageEngine::new(img).with_frame_count(3);
+            let result = engine.ignite(&[Instr::Deferred(op)]).unwrap();
+
+            assert_eq!(result.dimensions(), (30, 447));
+        }
+
+        #[test]
+        fn negative_dimension_is_an_error() {
+            let img = setup_default_test_image();
+            let op =
+                DeferredOp::Resize([Pixels(10), Dimension::try_from_str("10-$height").unwrap()]);
+
+            let mut engine = ImageEngine::new(img);
+
+            assert!(engine.ignite(&[Instr::Deferred(op)]).is_err());
+        }
+
+        #[test]
+        fn into_instr() {
+            assert_eq!(
+                DeferredOp::Resize([Pixels(10), Pixels(20)]).into_instr(),
+                Instr::Operation(ImgOp::Resize((10, 20)))
+            );
+            assert_eq!(
+                DeferredOp::Resize([Pixels(10), Percentage(20.0)]).into_instr(),
+                Instr::Deferred(DeferredOp::Resize([Pixels(10), Percentage(20.0)]))
+            );
+        }
     }
 
     #[test]
-    fn test_crop_ok_to_half_horizontal() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
-        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
-
-        let operation = ImgOp::Crop((0, 0, 2, 1));
+    fn test_blur() {
+        let img: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::Blur(10.0);
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&[Instr::Operation(operation)]);
 
         assert!(done.is_ok());
 
-        let result_img = done.unwrap();
+        output_test_image_for_manual_inspection(&done.unwrap(), out_!("test_blur.png"));
+    }
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+    #[test]
+    fn test_blur_with_box_algorithm() {
+        let img: DynamicImage = setup_default_test_image();
 
-        let result_dim = result_img.dimensions();
-        assert_eq!(2, result_dim.0);
-        assert_eq!(1, result_dim.1);
+        let mut engine = ImageEngine::new(img);
+        let mut engine2 = engine.clone();
 
-        assert_eq!(Rgba([0, 0, 0, 255]), result_img.get_pixel(0, 0));
-        assert_eq!(Rgba([255, 255, 255, 255]), result_img.get_pixel(1, 0));
+        let left = engine
+            .ignite(&[
+                Instr::EnvAdd(EnvItem::BlurAlgorithm(BlurAlgorithm::Box)),
+                Instr::Operation(ImgOp::Blur(3.0)),
+            ])
+            .unwrap();
+        let right = engine2
+            .ignite(&[Instr::Operation(ImgOp::BoxBlur(5))])
+            .unwrap();
 
-        output_test_image_for_manual_inspection(
-            &result_img,
-            out_!("test_crop_ok_to_half_horizontal.bmp"),
-        );
+        assert_eq!(left.raw_pixels(), right.raw_pixels());
+
+        output_test_image_for_manual_inspection(left, out_!("test_blur_box_algorithm.png"));
     }
 
     #[test]
-    fn test_crop_err_lx_larger_than_rx() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    fn test_blur_algorithm_removed() {
+        let img: DynamicImage = setup_default_test_image();
 
-        // not rx >= lx
-        let operation = ImgOp::Crop((1, 0, 0, 0));
+        let mut engine = ImageEngine::new(img);
+        let mut engine2 = engine.clone();
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        let left = engine
+            .ignite(&[
+                Instr::EnvAdd(EnvItem::BlurAlgorithm(BlurAlgorithm::Box)),
+                Instr::EnvRemove(ItemName::BlurAlgorithm),
+                Instr::Operation(ImgOp::Blur(3.0)),
+            ])
+            .unwrap();
+        let right = engine2
+            .ignite(&[Instr::Operation(ImgOp::Blur(3.0))])
+            .unwrap();
 
-        assert!(done.is_err());
+        assert_eq!(left.raw_pixels(), right.raw_pixels());
     }
 
     #[test]
-    fn test_crop_err_ly_larger_than_ry() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    fn test_region_restricts_operation() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+        let (w, h) = img.dimensions();
 
-        // not rx >= lx
-        let operation = ImgOp::Crop((0, 1, 0, 0));
+        let mut engine = ImageEngine::new(img);
+        let done = engine
+            .ignite(&[
+                Instr::EnvAdd(EnvItem::Region(Region::new((0, h / 2), (w, h)))),
+                Instr::Operation(ImgOp::Invert),
+            ])
+            .unwrap();
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        assert_eq!(done.dimensions(), (w, h));
+        assert_eq!(done.get_pixel(0, h / 2 - 1), cmp.get_pixel(0, h / 2 - 1));
 
-        assert!(done.is_err());
+        let mut inverted = cmp.get_pixel(0, h / 2);
+        inverted.invert();
+        assert_eq!(done.get_pixel(0, h / 2), inverted);
+
+        output_test_image_for_manual_inspection(done, out_!("test_region_invert.png"));
     }
 
     #[test]
-    fn test_crop_err_out_of_image_bounds_top_lx() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    fn test_region_removed() {
+        let img: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Crop((3, 0, 1, 1));
+        let mut engine = ImageEngine::new(img);
+        let mut engine2 = engine.clone();
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        let left = engine
+            .ignite(&[
+                Instr::EnvAdd(EnvItem::Region(Region::new((1, 1), (2, 2)))),
+                Instr::EnvRemove(ItemName::Region),
+                Instr::Operation(ImgOp::Blur(3.0)),
+            ])
+            .unwrap();
+        let right = engine2
+            .ignite(&[Instr::Operation(ImgOp::Blur(3.0))])
+            .unwrap();
 
-        assert!(done.is_err());
+        assert_eq!(left.raw_pixels(), right.raw_pixels());
     }
 
     #[test]
-    fn test_crop_err_out_of_image_bounds_top_ly() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    fn test_region_operation_changes_size() {
+        let img: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Crop((0, 3, 1, 1));
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[
+            Instr::EnvAdd(EnvItem::Region(Region::new((0, 0), (4, 4)))),
+            Instr::Operation(ImgOp::Mirror(MirrorMode::Quad)),
+        ]);
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_region_outside_image() {
+        let img: DynamicImage = setup_default_test_image();
+        let (w, h) = img.dimensions();
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[
+            Instr::EnvAdd(EnvItem::Region(Region::new((w, 0), (4, h)))),
+            Instr::Operation(ImgOp::Invert),
+        ]);
 
         assert!(done.is_err());
     }
 
     #[test]
-    fn test_crop_err_out_of_image_bounds_top_rx() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    fn test_mask_blends_operation() {
+        let img = open_test_image(in_!("rainbow_8x6.bmp"));
+        let cmp = img.clone();
 
-        let operation = ImgOp::Crop((0, 0, 3, 1));
+        let mut engine = ImageEngine::new(img);
+        let done = engine
+            .ignite(&[
+                Instr::EnvAdd(EnvItem::Mask(ImageFromPath::new(
+                    in_!("mask_8x6.png").into(),
+                ))),
+                Instr::Operation(ImgOp::Invert),
+            ])
+            .unwrap();
+
+        // the mask is white in columns 0 to 3, gray in columns 4 and 5, and black in 6 and 7
+        let mut inverted = cmp.get_pixel(0, 0);
+        inverted.invert();
+        assert_eq!(done.get_pixel(0, 0), inverted);
+        assert_eq!(done.get_pixel(7, 5), cmp.get_pixel(7, 5));
+
+        let before = cmp.get_pixel(4, 2);
+        let after = done.get_pixel(4, 2);
+        for channel in 0..3 {
+            let expected = (f32::from(before[channel])
+                + (255.0 - 2.0 * f32::from(before[channel])) * 128.0 / 255.0)
+                .round() as u8;
+            assert_eq!(after[channel], expected);
+        }
+    }
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+    #[test]
+    fn test_mask_removed() {
+        let img = open_test_image(in_!("rainbow_8x6.bmp"));
 
-        assert!(done.is_err());
+        let mut engine = ImageEngine::new(img);
+        let mut engine2 = engine.clone();
+
+        let left = engine
+            .ignite(&[
+                Instr::EnvAdd(EnvItem::Mask(ImageFromPath::new(
+                    in_!("mask_8x6.png").into(),
+                ))),
+                Instr::EnvRemove(ItemName::Mask),
+                Instr::Operation(ImgOp::Invert),
+            ])
+            .unwrap();
+        let right = engine2.ignite(&[Instr::Operation(ImgOp::Invert)]).unwrap();
+
+        assert_eq!(left.raw_pixels(), right.raw_pixels());
     }
 
     #[test]
-    fn test_crop_err_out_of_image_bounds_top_ry() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    fn test_mask_of_other_size() {
+        let img: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Crop((0, 0, 1, 3));
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[
+            Instr::EnvAdd(EnvItem::Mask(ImageFromPath::new(
+                in_!("mask_8x6.png").into(),
+            ))),
+            Instr::Operation(ImgOp::Invert),
+        ]);
 
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_mask_operation_changes_size() {
+        let img = open_test_image(in_!("rainbow_8x6.bmp"));
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[
+            Instr::EnvAdd(EnvItem::Mask(ImageFromPath::new(
+                in_!("mask_8x6.png").into(),
+            ))),
+            Instr::Operation(ImgOp::Rotate90),
+        ]);
 
         assert!(done.is_err());
     }
 
     #[test]
-    fn test_filter3x3() {
+    fn test_box_blur() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Filter3x3([1.0, 0.5, 0.0, 1.0, 0.5, 0.0, 1.0, 0.5, 0.0]);
+        let operation = ImgOp::BoxBlur(2);
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&[Instr::Operation(operation)]);
@@ -977,269 +2883,1536 @@ mod tests {
 
         let result_img = done.unwrap();
 
+        assert_eq!(cmp.dimensions(), result_img.dimensions());
         assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_filter3x3.png"))
+        output_test_image_for_manual_inspection(result_img, out_!("test_box_blur.png"));
     }
 
     #[test]
-    fn test_flip_h() {
+    fn test_border() {
         let img: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::FlipHorizontal;
+        let (width, height) = img.dimensions();
+
+        let border = BorderInputs::new(&[4, 8], Rgba([255, 255, 255, 255])).unwrap();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Border(border))]);
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.dimensions(), (width + 16, height + 8));
+        assert_eq!(result_img.get_pixel(0, 0), Rgba([255, 255, 255, 255]));
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_border.png"));
+    }
+
+    #[test]
+    fn test_alpha_add_remove() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[Instr::Operation(ImgOp::AlphaAdd)]);
+        assert_eq!(done.unwrap().color(), sic_core::image::ColorType::Rgba8);
+
+        let done = engine.ignite(&[Instr::Operation(ImgOp::AlphaRemove)]);
+        assert_eq!(done.unwrap().color(), sic_core::image::ColorType::Rgb8);
+    }
+
+    #[test]
+    fn test_alpha_premultiply_threshold() {
+        let img = DynamicImage::ImageRgba8(sic_core::image::RgbaImage::from_pixel(
+            2,
+            1,
+            Rgba([200, 100, 50, 128]),
+        ));
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[
+            Instr::Operation(ImgOp::AlphaPremultiply),
+            Instr::Operation(ImgOp::AlphaThreshold(100)),
+        ]);
+
+        assert_eq!(done.unwrap().get_pixel(1, 0), Rgba([100, 50, 25, 255]));
+    }
+
+    #[test]
+    fn test_opacity() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[Instr::Operation(ImgOp::Opacity(0.5))]);
+        let done = done.unwrap();
+
+        assert_eq!(done.color(), sic_core::image::ColorType::Rgba8);
+        assert!(done.pixels().all(|(_, _, pixel)| pixel[3] == 128));
+    }
+
+    #[test]
+    fn test_opacity_out_of_range() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[Instr::Operation(ImgOp::Opacity(1.5))]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_channel_extract() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[Instr::Operation(ImgOp::ChannelExtract(Channel::Green))]);
+
+        assert_eq!(done.unwrap().color(), sic_core::image::ColorType::L8);
+    }
+
+    #[test]
+    fn test_channel_swap() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[Instr::Operation(ImgOp::ChannelSwap((
+            Channel::Red,
+            Channel::Blue,
+        )))]);
+
+        let result_img = done.unwrap();
+
+        for (x, y, pixel) in cmp.pixels() {
+            let swapped = result_img.get_pixel(x, y);
+            assert_eq!((swapped[0], swapped[2]), (pixel[2], pixel[0]));
+        }
+    }
+
+    #[test]
+    fn test_brighten_pos() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Brighten(25);
 
-        let (xa, ya) = img.dimensions();
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&[Instr::Operation(operation)]);
 
         assert!(done.is_ok());
 
-        let img_result = done.unwrap();
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_pos_25.png"));
+    }
+
+    #[test]
+    fn test_brighten_zero() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::Brighten(0);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_zero.png"));
+    }
+
+    #[test]
+    fn test_brighten_neg() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Brighten(-25);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_neg_25.png"));
+    }
+
+    #[test]
+    fn test_contrast_pos() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Contrast(150.9);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_contrast_pos_15_9.png"));
+    }
+
+    #[test]
+    fn test_contrast_neg() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Contrast(-150.9);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_contrast_pos_15_9.png"));
+    }
+
+    #[test]
+    fn test_convolve() {
+        use crate::wrapper::kernel::Kernel;
+
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let kernel = Kernel::new(5, vec![1.0; 25]).unwrap();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Convolve(kernel))]);
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.dimensions(), cmp.dimensions());
+        assert_ne!(result_img.raw_pixels(), cmp.raw_pixels());
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_convolve.png"));
+    }
+
+    #[test]
+    fn test_convolve_file() {
+        use crate::wrapper::kernel::KernelFromPath;
+
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::ConvolveFile(KernelFromPath::new(
+            PathBuf::from(in_!("kernel/emboss_5x5.txt")),
+        )))]);
+
+        output_test_image_for_manual_inspection(done.unwrap(), out_!("test_convolve_file.png"));
+    }
+
+    #[test]
+    fn test_convolve_file_missing() {
+        use crate::wrapper::kernel::KernelFromPath;
+
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::ConvolveFile(KernelFromPath::new(
+            PathBuf::from(in_!("kernel/missing.txt")),
+        )))]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_crop_ok_no_change() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((0, 0, 2, 2));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_crop_no_change.bmp"));
+    }
+
+    #[test]
+    fn test_crop_ok_to_one_pixel() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((0, 0, 1, 1));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        let result_dim = result_img.dimensions();
+        assert_eq!(1, result_dim.0);
+        assert_eq!(1, result_dim.1);
+
+        assert_eq!(Rgba([0, 0, 0, 255]), result_img.get_pixel(0, 0));
+
+        output_test_image_for_manual_inspection(
+            &result_img,
+            out_!("test_crop_ok_to_one_pixel.bmp"),
+        );
+    }
+
+    #[test]
+    fn test_crop_ok_to_half_horizontal() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((0, 0, 2, 1));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        let result_dim = result_img.dimensions();
+        assert_eq!(2, result_dim.0);
+        assert_eq!(1, result_dim.1);
+
+        assert_eq!(Rgba([0, 0, 0, 255]), result_img.get_pixel(0, 0));
+        assert_eq!(Rgba([255, 255, 255, 255]), result_img.get_pixel(1, 0));
+
+        output_test_image_for_manual_inspection(
+            &result_img,
+            out_!("test_crop_ok_to_half_horizontal.bmp"),
+        );
+    }
+
+    #[test]
+    fn test_crop_err_lx_larger_than_rx() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        // not rx >= lx
+        let operation = ImgOp::Crop((1, 0, 0, 0));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_crop_err_ly_larger_than_ry() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        // not rx >= lx
+        let operation = ImgOp::Crop((0, 1, 0, 0));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_crop_err_out_of_image_bounds_top_lx() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((3, 0, 1, 1));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_crop_err_out_of_image_bounds_top_ly() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((0, 3, 1, 1));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_crop_err_out_of_image_bounds_top_rx() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((0, 0, 3, 1));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_crop_err_out_of_image_bounds_top_ry() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((0, 0, 1, 3));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_crop_gravity() {
+        let img: DynamicImage = setup_default_test_image();
+        let (width, height) = img.dimensions();
+        let cmp = img.crop_imm(width - 100, (height - 100) / 2, 100, 100);
+
+        let operation = ImgOp::CropGravity((Gravity::Right, 100, 100));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.dimensions(), (100, 100));
+        assert_eq!(result_img.raw_pixels(), cmp.raw_pixels());
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_crop_gravity.png"));
+    }
+
+    #[test]
+    fn test_crop_gravity_larger_than_image() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::CropGravity((Gravity::Center, 3, 2));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_filter3x3() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Filter3x3([1.0, 0.5, 0.0, 1.0, 0.5, 0.0, 1.0, 0.5, 0.0]);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_filter3x3.png"))
+    }
+
+    #[test]
+    fn test_fisheye() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Fisheye(0.5))]);
+
+        let result_img = done.unwrap();
+        assert_eq!(result_img.dimensions(), cmp.dimensions());
+        assert_ne!(result_img.raw_pixels(), cmp.raw_pixels());
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_fisheye.png"));
+    }
+
+    #[test]
+    fn test_fisheye_invalid_strength() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Fisheye(-1.5))]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_flip_h() {
+        let img: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::FlipHorizontal;
+
+        let (xa, ya) = img.dimensions();
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let img_result = done.unwrap();
         let (xb, yb) = img_result.dimensions();
 
-        assert_eq!(xa, xb);
-        assert_eq!(ya, yb);
+        assert_eq!(xa, xb);
+        assert_eq!(ya, yb);
+
+        output_test_image_for_manual_inspection(&img_result, out_!("test_fliph.png"));
+    }
+
+    #[test]
+    fn test_flip_v() {
+        let img: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::FlipVertical;
+
+        let (xa, ya) = img.dimensions();
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let img_result = done.unwrap();
+        let (xb, yb) = img_result.dimensions();
+
+        assert_eq!(xa, xb);
+        assert_eq!(ya, yb);
+
+        output_test_image_for_manual_inspection(&img_result, out_!("test_flipv.png"));
+    }
+
+    #[test]
+    fn test_gradient_map() {
+        use crate::wrapper::gradient::{ColorStop, Gradient};
+
+        // white, black, transparent, white
+        let img: DynamicImage = sic_testing::open_test_image(in_!("2x2_wbaw.png"));
+
+        let gradient = Gradient::new(vec![
+            ColorStop::new(0.0, Rgba([0, 0, 255, 255])),
+            ColorStop::new(0.5, Rgba([0, 255, 0, 255])),
+            ColorStop::new(1.0, Rgba([255, 0, 0, 255])),
+        ]);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::GradientMap(gradient))]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(result_img.get_pixel(1, 0), Rgba([0, 0, 255, 255]));
+        assert_eq!(result_img.get_pixel(0, 1)[3], 0);
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_gradient_map.png"));
+    }
+
+    #[test]
+    fn test_gradient_map_too_few_stops() {
+        use crate::wrapper::gradient::Gradient;
+
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::GradientMap(Gradient::new(vec![])))]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_gray_scale() {
+        use sic_core::image::Pixel;
+
+        let img: DynamicImage = sic_testing::open_test_image(in_!("rainbow_8x6.bmp"));
+        let operation = ImgOp::GrayScale;
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let img_result = done.unwrap();
+
+        // The color type isn't actually changed to luma, so instead of checking color type,
+        // here pixels are checked to have equal (r, g, b) components.
+        for i in 0..8 {
+            for j in 0..6 {
+                let pixel = img_result.get_pixel(i, j);
+                let channels_result = pixel.channels();
+                let r_component = channels_result[0];
+                let g_component = channels_result[1];
+                let b_component = channels_result[2];
+
+                assert_eq!(r_component, g_component);
+                assert_eq!(g_component, b_component);
+            }
+        }
+
+        output_test_image_for_manual_inspection(&img_result, out_!("test_gray_scale.png"));
+    }
+
+    #[test]
+    fn test_grayscale_weighted() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("rainbow_8x6.bmp"));
+        let operation = ImgOp::GrayscaleWeighted((0.2126, 0.7152, 0.0722));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        let img_result = done.unwrap();
+
+        assert_eq!(img_result.color(), sic_core::image::ColorType::L8);
+
+        output_test_image_for_manual_inspection(img_result, out_!("test_grayscale_weighted.png"));
+    }
+
+    #[test]
+    fn test_grayscale_weighted_negative() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::GrayscaleWeighted((-1.0, 1.0, 1.0)))]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_hue_rotate_neg() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(-100);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_neg_100.png"));
+    }
+
+    #[test]
+    fn test_hue_rotate_pos() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(100);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_100.png"));
+    }
+
+    #[test]
+    fn test_hue_rotate_zero() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(0);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_0.png"));
+    }
+
+    #[test]
+    fn test_hue_rotate_360() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(360);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        // https://docs.rs/image/0.19.0/image/enum.DynamicImage.html#method.huerotate
+        // huerotate(0) should be huerotate(360), but this doesn't seem the case
+        assert_eq!(cmp.huerotate(360).raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_360.png"));
+    }
+
+    #[test]
+    fn test_hue_rotate_over_rotate_pos() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(460);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.huerotate(100).raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_460.png"));
+    }
+
+    #[test]
+    fn test_auto_enhance() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("unsplash_763569_cropped.jpg"));
+        let cmp = img.clone();
+
+        let operation = ImgOp::AutoEnhance;
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(cmp.dimensions(), result_img.dimensions());
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_auto_enhance.png"));
+    }
+
+    #[test]
+    fn test_chroma_key() {
+        // white, black, transparent, white
+        let img: DynamicImage = sic_testing::open_test_image(in_!("2x2_wbaw.png"));
+
+        let operation = ImgOp::ChromaKey(crate::wrapper::chroma_key::ChromaKeyInputs::new(
+            Rgba([255, 255, 255, 255]),
+            10.0,
+            0.0,
+        ));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.get_pixel(0, 0)[3], 0);
+        assert_eq!(result_img.get_pixel(1, 0)[3], 255);
+        assert_eq!(result_img.get_pixel(1, 1)[3], 0);
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_chroma_key.png"));
+    }
+
+    #[test]
+    fn test_dither() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("unsplash_763569_cropped.jpg"));
+
+        let operation = ImgOp::Dither(8);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        let colors = result_img
+            .to_rgb()
+            .pixels()
+            .map(|p| p.0)
+            .collect::<std::collections::HashSet<_>>();
+        assert!(colors.len() <= 8);
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_dither.png"));
+    }
+
+    #[test]
+    fn test_duotone() {
+        // white, black, transparent, white
+        let img: DynamicImage = sic_testing::open_test_image(in_!("2x2_wbaw.png"));
+
+        let shadows = Rgba([0, 0, 128, 255]);
+        let highlights = Rgba([255, 200, 0, 255]);
+        let operation = ImgOp::Duotone((shadows, highlights));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.get_pixel(0, 0), highlights);
+        assert_eq!(result_img.get_pixel(1, 0), shadows);
+        assert_eq!(result_img.get_pixel(0, 1)[3], 0);
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_duotone.png"));
+    }
+
+    #[test]
+    fn test_dither_invalid_color_count() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("unsplash_763569_cropped.jpg"));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Dither(1))]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_equalize() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("equalize_8x8_low_contrast.png"));
+        let cmp: DynamicImage =
+            sic_testing::open_test_image(in_!("equalize_8x8_low_contrast_reference.png"));
+
+        let operation = ImgOp::Equalize;
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_equalize.png"));
+    }
+
+    #[test]
+    fn test_extend() {
+        let img: DynamicImage = setup_default_test_image();
+        let (width, height) = img.dimensions();
+
+        let inputs = ExtendInputs::new((width + 20, height + 10), Some(Gravity::BottomRight), None);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Extend(inputs))]);
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.dimensions(), (width + 20, height + 10));
+        assert_eq!(result_img.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(result_img.get_pixel(width + 19, height + 9)[3], 255);
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_extend.png"));
+    }
+
+    #[test]
+    fn test_extend_canvas_too_small() {
+        let img: DynamicImage = setup_default_test_image();
+        let (width, height) = img.dimensions();
+
+        let inputs = ExtendInputs::new((width - 1, height), None, None);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Extend(inputs))]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_flatten_checker() {
+        // white, black, transparent, white
+        let img: DynamicImage = sic_testing::open_test_image(in_!("2x2_wbaw.png"));
+
+        let operation = ImgOp::FlattenChecker(1);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(
+            result_img.raw_pixels(),
+            vec![255, 255, 255, 0, 0, 0, 204, 204, 204, 255, 255, 255]
+        );
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_flatten_checker.png"));
+    }
+
+    #[test]
+    fn test_flatten_checker_invalid_cell_size() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("2x2_wbaw.png"));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::FlattenChecker(0))]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_local_mean() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::LocalMean(1);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.color(), sic_core::image::ColorType::L8);
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_local_mean.png"));
+    }
+
+    #[test]
+    fn test_local_variance() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::LocalVariance(1);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.color(), sic_core::image::ColorType::L8);
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_local_variance.png"));
+    }
+
+    #[test]
+    fn test_lut() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut engine = ImageEngine::new(img.clone());
+        let left = engine.ignite(&[Instr::Operation(ImgOp::Lut(LutFromPath::new(
+            PathBuf::from(in_!("lut/invert.cube")),
+        )))]);
+
+        let mut engine = ImageEngine::new(img);
+        let right = engine.ignite(&[Instr::Operation(ImgOp::Invert)]);
+
+        assert_eq!(left.unwrap().to_rgb(), right.unwrap().to_rgb());
+    }
+
+    #[test]
+    fn test_lut_missing_file() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[Instr::Operation(ImgOp::Lut(LutFromPath::new(
+            PathBuf::from(in_!("lut/missing.cube")),
+        )))]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_mirror_quad() {
+        let img: DynamicImage = setup_default_test_image();
+        let (w, h) = img.dimensions();
+
+        let mut engine = ImageEngine::new(img);
+        let done = engine.ignite(&[Instr::Operation(ImgOp::Mirror(MirrorMode::Quad))]);
+
+        let result_img = done.unwrap();
+        assert_eq!(result_img.dimensions(), (w * 2, h * 2));
+        assert_eq!(
+            result_img.get_pixel(0, 0),
+            result_img.get_pixel(w * 2 - 1, 0)
+        );
+        assert_eq!(
+            result_img.get_pixel(0, 0),
+            result_img.get_pixel(0, h * 2 - 1)
+        );
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_mirror_quad.png"));
+    }
+
+    #[test]
+    fn test_gradient_radial() {
+        let img: DynamicImage = setup_default_test_image();
+        let (w, h) = img.dimensions();
+
+        let mut engine = ImageEngine::new(img);
+        let inputs = GradientFillInputs::new(
+            GradientKind::Radial,
+            (Rgba([0, 0, 0, 0]), Rgba([0, 0, 0, 255])),
+            GRADIENT_DEFAULT_ANGLE,
+        );
+        let done = engine.ignite(&[Instr::Operation(ImgOp::Gradient(inputs))]);
+
+        let result_img = done.unwrap();
+        assert_eq!(result_img.dimensions(), (w, h));
+        assert_eq!(result_img.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_gradient_radial.png"));
+    }
+
+    #[test]
+    fn test_draw_histogram_append() {
+        let img: DynamicImage = setup_default_test_image();
+        let (w, h) = img.dimensions();
+
+        let mut engine = ImageEngine::new(img);
+        let inputs = DrawHistogramInputs::new(HistogramChannels::Rgb, HistogramPlacement::Append);
+        let done = engine.ignite(&[Instr::Operation(ImgOp::DrawHistogram(inputs))]);
+
+        let result_img = done.unwrap();
+        assert_eq!(result_img.dimensions(), (w, h + (w / 4).max(16)));
+
+        output_test_image_for_manual_inspection(
+            result_img,
+            out_!("test_draw_histogram_append.png"),
+        );
+    }
+
+    #[test]
+    fn test_invert() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Invert;
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_invert.png"));
+    }
+
+    mod overlay {
+        use super::*;
+        use crate::wrapper::overlay::OverlayInputs;
+
+        #[test]
+        fn overlay_with_self_at_origin() {
+            let img = setup_default_test_image();
+            let overlay = sic_testing::in_!("unsplash_763569_cropped.jpg");
+
+            let mut engine = ImageEngine::new(img.clone());
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Overlay(OverlayInputs::new(
+                ImageFromPath::new(overlay.into()),
+                (0, 0),
+            )))]);
+
+            let res_image = res.unwrap();
+            assert_eq!(img.raw_pixels(), res_image.raw_pixels());
+
+            output_test_image_for_manual_inspection(
+                &res_image,
+                out_!("test_overlay_self_origin.png"),
+            );
+        }
+
+        #[test]
+        fn overlay_with_self_outside_bounds() {
+            let img = setup_default_test_image();
+            let bounds = img.dimensions();
+
+            let overlay = sic_testing::in_!("unsplash_763569_cropped.jpg");
+
+            let mut engine = ImageEngine::new(img.clone());
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Overlay(OverlayInputs::new(
+                ImageFromPath::new(overlay.into()),
+                (bounds.0, bounds.1),
+            )))]);
+
+            let res_image = res.unwrap();
+            assert_eq!(img.raw_pixels(), res_image.raw_pixels());
+
+            output_test_image_for_manual_inspection(
+                &res_image,
+                out_!("test_overlay_self_bounds.png"),
+            );
+        }
+
+        #[test]
+        fn overlay_with_self_se_quarter() {
+            let img = setup_default_test_image();
+            let bounds = img.dimensions();
+
+            let overlay = sic_testing::in_!("unsplash_763569_cropped.jpg");
+
+            let mut engine = ImageEngine::new(img.clone());
+            let res = engine.ignite(&[
+                Instr::Operation(ImgOp::Invert),
+                Instr::Operation(ImgOp::Overlay(OverlayInputs::new(
+                    ImageFromPath::new(overlay.into()),
+                    (bounds.0 / 2, bounds.1 / 2),
+                ))),
+            ]);
+
+            let res_image = res.unwrap();
+            assert_ne!(img.raw_pixels(), res_image.raw_pixels());
+
+            output_test_image_for_manual_inspection(
+                &res_image,
+                out_!("test_overlay_self_se_quarter.png"),
+            );
+        }
+
+        #[test]
+        fn overlay_with_self_multiply_half_opacity() {
+            use crate::wrapper::blend_mode::BlendMode;
+
+            let img = setup_default_test_image();
+            let overlay = sic_testing::in_!("unsplash_763569_cropped.jpg");
+
+            let mut engine = ImageEngine::new(img.clone());
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Overlay(
+                OverlayInputs::new(ImageFromPath::new(overlay.into()), (0, 0))
+                    .with_opacity(0.5)
+                    .with_blend_mode(BlendMode::Multiply),
+            ))]);
+
+            let res_image = res.unwrap();
+            assert_eq!(img.dimensions(), res_image.dimensions());
+
+            // multiplying never lightens the image
+            let darker = img
+                .raw_pixels()
+                .iter()
+                .zip(res_image.raw_pixels().iter())
+                .all(|(original, blended)| blended <= original);
+            assert!(darker);
+
+            output_test_image_for_manual_inspection(
+                res_image,
+                out_!("test_overlay_self_multiply_half_opacity.png"),
+            );
+        }
+    }
+
+    mod stack {
+        use super::*;
+
+        #[test]
+        fn hstack_with_self() {
+            let img = setup_default_test_image();
+            let other = sic_testing::in_!("unsplash_763569_cropped.jpg");
+
+            let mut engine = ImageEngine::new(img.clone());
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Hstack(ImageFromPath::new(
+                other.into(),
+            )))]);
+
+            let res_image = res.unwrap();
+            assert_eq!(res_image.dimensions(), (img.width() * 2, img.height()));
+            assert_eq!(img.get_pixel(0, 0), res_image.get_pixel(img.width(), 0));
+
+            output_test_image_for_manual_inspection(res_image, out_!("test_hstack_self.png"));
+        }
+
+        #[test]
+        fn vstack_smaller_image() {
+            let img = setup_default_test_image();
+            let other = sic_testing::in_!("3x2_wbaaba.png");
+
+            let mut engine = ImageEngine::new(img.clone());
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Vstack(ImageFromPath::new(
+                other.into(),
+            )))]);
+
+            let res_image = res.unwrap();
+            assert_eq!(res_image.dimensions(), (img.width(), img.height() + 2));
+            assert!(res_image.color().has_alpha());
+
+            output_test_image_for_manual_inspection(
+                res_image,
+                out_!("test_vstack_smaller_image.png"),
+            );
+        }
+
+        #[test]
+        fn hstack_not_found() {
+            let mut engine = ImageEngine::new(setup_default_test_image());
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Hstack(ImageFromPath::new(
+                "does-not-exist.png".into(),
+            )))]);
+
+            assert!(res.is_err());
+        }
+    }
+
+    mod stamp {
+        use super::*;
+        use crate::wrapper::gravity::Gravity;
+        use crate::wrapper::stamp::StampInputs;
+
+        #[test]
+        fn stamp_bottom_right() {
+            let img = setup_default_test_image();
+            let stamp = sic_testing::in_!("3x2_wbaaba.png");
+
+            let mut engine = ImageEngine::new(img.clone());
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Stamp(StampInputs::new(
+                ImageFromPath::new(stamp.into()),
+                Gravity::BottomRight,
+                10,
+            )))]);
+
+            let res_image = res.unwrap();
+            assert_eq!(img.dimensions(), res_image.dimensions());
+            assert_ne!(img.raw_pixels(), res_image.raw_pixels());
+            assert_eq!(img.get_pixel(0, 0), res_image.get_pixel(0, 0));
+
+            output_test_image_for_manual_inspection(
+                res_image,
+                out_!("test_stamp_bottom_right.png"),
+            );
+        }
+
+        #[test]
+        fn stamp_not_found() {
+            let img = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Stamp(StampInputs::new(
+                ImageFromPath::new("does-not-exist.png".into()),
+                Gravity::Center,
+                0,
+            )))]);
+
+            assert!(res.is_err());
+        }
+    }
+
+    mod find {
+        use super::*;
+        use crate::wrapper::find::FindInputs;
+
+        const TEMPLATE: &str = "find_template_24x16.png";
+
+        #[test]
+        fn find_reports_match() {
+            let img = setup_default_test_image();
+            let template = ImageFromPath::new(sic_testing::in_!(TEMPLATE).into());
+
+            let mut engine = ImageEngine::new(img.clone());
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Find(FindInputs::new(
+                template.clone(),
+                Some(Rgba([255, 0, 0, 255])),
+            )))]);
+
+            let res_image = res.unwrap();
+            assert_eq!(res_image.get_pixel(100, 60), Rgba([255, 0, 0, 255]));
+            assert_eq!(img.get_pixel(0, 0), res_image.get_pixel(0, 0));
+
+            output_test_image_for_manual_inspection(res_image, out_!("test_find.png"));
+
+            match engine.analysis_results() {
+                [AnalysisResult::TemplateMatch {
+                    template: path,
+                    x: 100,
+                    y: 60,
+                    width: 24,
+                    height: 16,
+                    score,
+                }] => {
+                    assert_eq!(path, template.path());
+                    assert!((score - 1.0).abs() < 1e-4);
+                }
+                results => panic!("unexpected analysis results: {:?}", results),
+            }
+        }
+
+        #[test]
+        fn find_in_region_reports_image_coordinates() {
+            let img = setup_default_test_image();
+            let template = ImageFromPath::new(sic_testing::in_!(TEMPLATE).into());
+
+            let mut engine = ImageEngine::new(img);
+            engine
+                .ignite(&[
+                    Instr::EnvAdd(EnvItem::Region(Region::new((80, 50), (60, 40)))),
+                    Instr::Operation(ImgOp::Find(FindInputs::new(template, None))),
+                ])
+                .unwrap();
+
+            assert!(matches!(
+                engine.analysis_results(),
+                [AnalysisResult::TemplateMatch { x: 100, y: 60, .. }]
+            ));
+        }
+
+        #[test]
+        fn find_template_too_large() {
+            let img = setup_default_test_image();
+            let template = ImageFromPath::new(sic_testing::in_!(TEMPLATE).into());
+
+            let mut engine = ImageEngine::new(img);
+            let res = engine.ignite(&[
+                Instr::EnvAdd(EnvItem::Region(Region::new((0, 0), (10, 10)))),
+                Instr::Operation(ImgOp::Find(FindInputs::new(template, None))),
+            ]);
+
+            assert!(res.is_err());
+            assert!(engine.analysis_results().is_empty());
+        }
+    }
+
+    mod contours {
+        use super::*;
+        use crate::wrapper::contours::ContoursInputs;
+        use sic_core::image::{Rgb, RgbImage};
+
+        #[test]
+        fn contours_reports_contours() {
+            let img = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let res = engine.ignite(&[Instr::Operation(ImgOp::Contours(ContoursInputs::new(
+                128,
+                Rgba([0, 255, 0, 255]),
+            )))]);
 
-        output_test_image_for_manual_inspection(&img_result, out_!("test_fliph.png"));
+            let res_image = res.unwrap().clone();
+            output_test_image_for_manual_inspection(&res_image, out_!("test_contours.png"));
+
+            match engine.analysis_results() {
+                [AnalysisResult::Contours {
+                    threshold: 128,
+                    contours,
+                }] => {
+                    assert!(!contours.is_empty());
+                    assert!(contours
+                        .iter()
+                        .flat_map(|contour| &contour.points)
+                        .all(|&(x, y)| res_image.get_pixel(x, y) == Rgba([0, 255, 0, 255])));
+                }
+                results => panic!("unexpected analysis results: {:?}", results),
+            }
+        }
+
+        #[test]
+        fn contours_in_region_reports_image_coordinates() {
+            let img = DynamicImage::ImageRgb8(RgbImage::from_fn(20, 20, |x, y| {
+                if (12..15).contains(&x) && (12..15).contains(&y) {
+                    Rgb([255, 255, 255])
+                } else {
+                    Rgb([0, 0, 0])
+                }
+            }));
+
+            let mut engine = ImageEngine::new(img);
+            engine
+                .ignite(&[
+                    Instr::EnvAdd(EnvItem::Region(Region::new((10, 10), (10, 10)))),
+                    Instr::Operation(ImgOp::Contours(ContoursInputs::new(
+                        128,
+                        Rgba([255, 0, 0, 255]),
+                    ))),
+                ])
+                .unwrap();
+
+            match engine.analysis_results() {
+                [AnalysisResult::Contours { contours, .. }] => {
+                    assert_eq!(contours.len(), 1);
+                    assert_eq!(contours[0].points[0], (12, 12));
+                }
+                results => panic!("unexpected analysis results: {:?}", results),
+            }
+        }
     }
 
     #[test]
-    fn test_flip_v() {
-        let img: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::FlipVertical;
+    fn test_nine_slice() {
+        use crate::wrapper::nine_slice::NineSliceInputs;
+        use sic_core::image::imageops::FilterType;
 
-        let (xa, ya) = img.dimensions();
-        let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        let img: DynamicImage = setup_default_test_image();
+        let (width, height) = img.dimensions();
 
-        assert!(done.is_ok());
+        let mut operator = ImageEngine::new(img.clone());
+        let done = operator.ignite(&[
+            Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::new(
+                FilterType::Nearest,
+            ))),
+            Instr::Operation(ImgOp::NineSlice(NineSliceInputs::new(
+                (20, 10, 30, 40),
+                (800, 100),
+            ))),
+        ]);
 
-        let img_result = done.unwrap();
-        let (xb, yb) = img_result.dimensions();
+        let result_img = done.unwrap();
 
-        assert_eq!(xa, xb);
-        assert_eq!(ya, yb);
+        assert_eq!(result_img.dimensions(), (800, 100));
+        // the corners are copied as they are
+        assert_eq!(result_img.get_pixel(19, 9), img.get_pixel(19, 9));
+        assert_eq!(
+            result_img.get_pixel(800 - 30, 100 - 40),
+            img.get_pixel(width - 30, height - 40)
+        );
 
-        output_test_image_for_manual_inspection(&img_result, out_!("test_flipv.png"));
+        output_test_image_for_manual_inspection(result_img, out_!("test_nine_slice.png"));
     }
 
     #[test]
-    fn test_gray_scale() {
-        use sic_core::image::Pixel;
+    fn test_nine_slice_invalid_insets() {
+        use crate::wrapper::nine_slice::NineSliceInputs;
 
-        let img: DynamicImage = sic_testing::open_test_image(in_!("rainbow_8x6.bmp"));
-        let operation = ImgOp::GrayScale;
+        let img: DynamicImage = setup_default_test_image();
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
-
-        assert!(done.is_ok());
-
-        let img_result = done.unwrap();
-
-        // The color type isn't actually changed to luma, so instead of checking color type,
-        // here pixels are checked to have equal (r, g, b) components.
-        for i in 0..8 {
-            for j in 0..6 {
-                let pixel = img_result.get_pixel(i, j);
-                let channels_result = pixel.channels();
-                let r_component = channels_result[0];
-                let g_component = channels_result[1];
-                let b_component = channels_result[2];
-
-                assert_eq!(r_component, g_component);
-                assert_eq!(g_component, b_component);
-            }
-        }
+        let done = operator.ignite(&[Instr::Operation(ImgOp::NineSlice(NineSliceInputs::new(
+            (20, 10, 30, 40),
+            (40, 100),
+        )))]);
 
-        output_test_image_for_manual_inspection(&img_result, out_!("test_gray_scale.png"));
+        assert!(done.is_err());
     }
 
     #[test]
-    fn test_hue_rotate_neg() {
+    fn test_oil_paint() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::HueRotate(-100);
-
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
-
-        assert!(done.is_ok());
+        let done = operator.ignite(&[Instr::Operation(ImgOp::OilPaint((3, 20)))]);
 
         let result_img = done.unwrap();
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(result_img.dimensions(), cmp.dimensions());
+        assert_ne!(result_img.raw_pixels(), cmp.raw_pixels());
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_neg_100.png"));
+        output_test_image_for_manual_inspection(result_img, out_!("test_oil_paint.png"));
     }
 
     #[test]
-    fn test_hue_rotate_pos() {
+    fn test_oil_paint_invalid_levels() {
         let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
-
-        let operation = ImgOp::HueRotate(100);
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
-
-        assert!(done.is_ok());
-
-        let result_img = done.unwrap();
-
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        let done = operator.ignite(&[Instr::Operation(ImgOp::OilPaint((3, 0)))]);
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_100.png"));
+        assert!(done.is_err());
     }
 
     #[test]
-    fn test_hue_rotate_zero() {
+    fn test_pad_to_ratio() {
+        // 217x447px => 795x447px
         let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::HueRotate(0);
+        let inputs = PadToRatioInputs::new(
+            AspectRatio::try_from_str("16:9").unwrap(),
+            Some(Rgba([255, 255, 255, 255])),
+        );
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
-
-        assert!(done.is_ok());
+        let done = operator.ignite(&[Instr::Operation(ImgOp::PadToRatio(inputs))]);
 
         let result_img = done.unwrap();
 
-        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(result_img.dimensions(), (795, 447));
+        assert_eq!(result_img.get_pixel(0, 0), Rgba([255, 255, 255, 255]));
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_0.png"));
+        output_test_image_for_manual_inspection(result_img, out_!("test_pad_to_ratio.png"));
     }
 
     #[test]
-    fn test_hue_rotate_360() {
-        let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
-
-        let operation = ImgOp::HueRotate(360);
+    fn test_pixel_upscale() {
+        let img: DynamicImage = open_test_image(in_!("rainbow_8x6.bmp"));
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
-
-        assert!(done.is_ok());
+        let done = operator.ignite(&[Instr::Operation(ImgOp::PixelUpscale(
+            PixelUpscaleInputs::new(3, PixelUpscaleAlgorithm::Scale2x),
+        ))]);
 
         let result_img = done.unwrap();
+        assert_eq!(result_img.dimensions(), (24, 18));
 
-        // https://docs.rs/image/0.19.0/image/enum.DynamicImage.html#method.huerotate
-        // huerotate(0) should be huerotate(360), but this doesn't seem the case
-        assert_eq!(cmp.huerotate(360).raw_pixels(), result_img.raw_pixels());
-
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_360.png"));
+        output_test_image_for_manual_inspection(result_img, out_!("test_pixel_upscale.png"));
     }
 
     #[test]
-    fn test_hue_rotate_over_rotate_pos() {
+    fn test_pixel_upscale_invalid_factor() {
         let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
-
-        let operation = ImgOp::HueRotate(460);
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
-
-        assert!(done.is_ok());
-
-        let result_img = done.unwrap();
+        let done = operator.ignite(&[Instr::Operation(ImgOp::PixelUpscale(
+            PixelUpscaleInputs::new(8, PixelUpscaleAlgorithm::Scale2x),
+        ))]);
 
-        assert_ne!(cmp.huerotate(100).raw_pixels(), result_img.raw_pixels());
-
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_460.png"));
+        assert!(done.is_err());
     }
 
     #[test]
-    fn test_invert() {
+    fn test_radial_blur() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Invert;
-
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
-
-        assert!(done.is_ok());
+        let done = operator.ignite(&[Instr::Operation(ImgOp::RadialBlur((100, 60, 0.3)))]);
 
         let result_img = done.unwrap();
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(result_img.dimensions(), cmp.dimensions());
+        assert_ne!(result_img.raw_pixels(), cmp.raw_pixels());
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_invert.png"));
+        output_test_image_for_manual_inspection(result_img, out_!("test_radial_blur.png"));
     }
 
-    mod overlay {
-        use super::*;
-        use crate::wrapper::overlay::OverlayInputs;
-
-        #[test]
-        fn overlay_with_self_at_origin() {
-            let img = setup_default_test_image();
-            let overlay = sic_testing::in_!("unsplash_763569_cropped.jpg");
-
-            let mut engine = ImageEngine::new(img.clone());
-            let res = engine.ignite(&[Instr::Operation(ImgOp::Overlay(OverlayInputs::new(
-                ImageFromPath::new(overlay.into()),
-                (0, 0),
-            )))]);
-
-            let res_image = res.unwrap();
-            assert_eq!(img.raw_pixels(), res_image.raw_pixels());
-
-            output_test_image_for_manual_inspection(
-                &res_image,
-                out_!("test_overlay_self_origin.png"),
-            );
-        }
-
-        #[test]
-        fn overlay_with_self_outside_bounds() {
-            let img = setup_default_test_image();
-            let bounds = img.dimensions();
-
-            let overlay = sic_testing::in_!("unsplash_763569_cropped.jpg");
-
-            let mut engine = ImageEngine::new(img.clone());
-            let res = engine.ignite(&[Instr::Operation(ImgOp::Overlay(OverlayInputs::new(
-                ImageFromPath::new(overlay.into()),
-                (bounds.0, bounds.1),
-            )))]);
+    #[test]
+    fn test_radial_blur_invalid_strength() {
+        let img: DynamicImage = setup_default_test_image();
 
-            let res_image = res.unwrap();
-            assert_eq!(img.raw_pixels(), res_image.raw_pixels());
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::RadialBlur((100, 60, 2.0)))]);
 
-            output_test_image_for_manual_inspection(
-                &res_image,
-                out_!("test_overlay_self_bounds.png"),
-            );
-        }
+        assert!(done.is_err());
+    }
 
-        #[test]
-        fn overlay_with_self_se_quarter() {
-            let img = setup_default_test_image();
-            let bounds = img.dimensions();
+    #[test]
+    fn test_redact() {
+        let img: DynamicImage = setup_default_test_image();
 
-            let overlay = sic_testing::in_!("unsplash_763569_cropped.jpg");
+        let mut operator = ImageEngine::new(img);
+        let region = Region::new((10, 20), (30, 40));
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Redact(RedactInputs::new(
+            region,
+            RedactStyle::Fill,
+        )))]);
 
-            let mut engine = ImageEngine::new(img.clone());
-            let res = engine.ignite(&[
-                Instr::Operation(ImgOp::Invert),
-                Instr::Operation(ImgOp::Overlay(OverlayInputs::new(
-                    ImageFromPath::new(overlay.into()),
-                    (bounds.0 / 2, bounds.1 / 2),
-                ))),
-            ]);
+        let result_img = done.unwrap();
 
-            let res_image = res.unwrap();
-            assert_ne!(img.raw_pixels(), res_image.raw_pixels());
+        assert_eq!(result_img.get_pixel(10, 20), Rgba([0, 0, 0, 255]));
+        assert_eq!(result_img.get_pixel(39, 59), Rgba([0, 0, 0, 255]));
 
-            output_test_image_for_manual_inspection(
-                &res_image,
-                out_!("test_overlay_self_se_quarter.png"),
-            );
-        }
+        output_test_image_for_manual_inspection(result_img, out_!("test_redact.png"));
     }
 
     #[test]
@@ -1309,47 +4482,127 @@ mod tests {
         assert_eq!(xa, yb);
         assert_eq!(xb, ya);
 
-        output_test_image_for_manual_inspection(&img_result, out_!("test_rotate90.png"));
+        output_test_image_for_manual_inspection(&img_result, out_!("test_rotate90.png"));
+    }
+
+    #[test]
+    fn test_rotate180() {
+        let img: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::Rotate180;
+
+        let (xa, ya) = img.dimensions();
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let img_result = done.unwrap();
+        let (xb, yb) = img_result.dimensions();
+
+        assert_eq!(xa, xb);
+        assert_eq!(ya, yb);
+
+        output_test_image_for_manual_inspection(&img_result, out_!("test_rotate180.png"));
+    }
+
+    #[test]
+    fn test_rotate270() {
+        let img: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::Rotate270;
+
+        let (xa, ya) = img.dimensions();
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let img_result = done.unwrap();
+        let (xb, yb) = img_result.dimensions();
+
+        assert_eq!(xa, yb);
+        assert_eq!(xb, ya);
+
+        output_test_image_for_manual_inspection(&img_result, out_!("test_rotate270.png"));
+    }
+
+    #[test]
+    fn test_sharpen() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Sharpen(1.0))]);
+
+        let result_img = done.unwrap();
+
+        assert_eq!(result_img.dimensions(), cmp.dimensions());
+        assert_ne!(result_img.raw_pixels(), cmp.raw_pixels());
+
+        output_test_image_for_manual_inspection(result_img, out_!("test_sharpen.png"));
+    }
+
+    #[test]
+    fn test_sharpen_negative() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Sharpen(-1.0))]);
+
+        assert!(done.is_err());
     }
 
     #[test]
-    fn test_rotate180() {
+    fn test_smart_crop() {
         let img: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::Rotate180;
 
-        let (xa, ya) = img.dimensions();
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::SmartCrop((100, 100)))]);
 
-        assert!(done.is_ok());
+        let result_img = done.unwrap();
 
-        let img_result = done.unwrap();
-        let (xb, yb) = img_result.dimensions();
+        assert_eq!(result_img.dimensions(), (100, 100));
 
-        assert_eq!(xa, xb);
-        assert_eq!(ya, yb);
+        output_test_image_for_manual_inspection(result_img, out_!("test_smart_crop.png"));
+    }
 
-        output_test_image_for_manual_inspection(&img_result, out_!("test_rotate180.png"));
+    #[test]
+    fn test_smart_crop_larger_than_image() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::SmartCrop((2, 3)))]);
+
+        assert!(done.is_err());
     }
 
     #[test]
-    fn test_rotate270() {
+    fn test_trim() {
         let img: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::Rotate270;
+        let (width, height) = img.dimensions();
+
+        let border = BorderInputs::new(&[10, 20], Rgba([1, 2, 3, 255])).unwrap();
 
-        let (xa, ya) = img.dimensions();
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&[Instr::Operation(operation)]);
+        let done = operator.ignite(&[
+            Instr::Operation(ImgOp::Border(border)),
+            Instr::Operation(ImgOp::Trim(0.0)),
+        ]);
 
-        assert!(done.is_ok());
+        let result_img = done.unwrap();
 
-        let img_result = done.unwrap();
-        let (xb, yb) = img_result.dimensions();
+        assert_eq!(result_img.dimensions(), (width, height));
 
-        assert_eq!(xa, yb);
-        assert_eq!(xb, ya);
+        output_test_image_for_manual_inspection(result_img, out_!("test_trim.png"));
+    }
 
-        output_test_image_for_manual_inspection(&img_result, out_!("test_rotate270.png"));
+    #[test]
+    fn test_trim_negative_tolerance() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instr::Operation(ImgOp::Trim(-1.0))]);
+
+        assert!(done.is_err());
     }
 
     #[test]
@@ -1425,8 +4678,106 @@ mod tests {
     #[cfg(feature = "imageproc-ops")]
     mod imageproc_ops_tests {
         use super::*;
+        use crate::wrapper::draw_shape::{
+            DrawEllipseInputs, DrawLineInputs, DrawPolygonInputs, DrawRectInputs, ShapeStyle,
+        };
         use crate::wrapper::draw_text_inner::DrawTextInner;
-        use crate::wrapper::font_options::{FontOptions, FontScale};
+        use crate::wrapper::font_options::{FontOptions, FontScale, TextOutline, TextShadow};
+        use crate::wrapper::noise::{NoiseInputs, NoiseKind};
+        use crate::wrapper::perspective::PerspectiveInputs;
+
+        ide!();
+
+        #[pm(
+            style = { ShapeStyle::Filled, ShapeStyle::Stroke(4) },
+            name = { "test_draw_rect_filled.png", "test_draw_rect_stroke.png" }
+        )]
+        fn draw_rect(style: ShapeStyle, name: &str) {
+            let img = setup_default_test_image();
+            let color = Rgba([255, 0, 0, 160]);
+
+            let mut operator = ImageEngine::new(img.clone());
+            let done = operator.ignite(&[Instr::Operation(ImgOp::DrawRect(DrawRectInputs::new(
+                (100, 50, 120, 80),
+                color,
+                style,
+            )))]);
+
+            let result_img = done.unwrap();
+
+            assert_eq!(result_img.dimensions(), img.dimensions());
+            assert_eq!(result_img.get_pixel(0, 0), img.get_pixel(0, 0));
+            assert_ne!(result_img.get_pixel(100, 50), img.get_pixel(100, 50));
+
+            output_test_image_for_manual_inspection(result_img, out_!(name));
+        }
+
+        #[pm(
+            style = { ShapeStyle::Filled, ShapeStyle::Stroke(3) },
+            name = { "test_draw_ellipse_filled.png", "test_draw_ellipse_stroke.png" }
+        )]
+        fn draw_ellipse(style: ShapeStyle, name: &str) {
+            let img = setup_default_test_image();
+            let color = Rgba([0, 0, 255, 160]);
+
+            let mut operator = ImageEngine::new(img.clone());
+            let done = operator.ignite(&[Instr::Operation(ImgOp::DrawEllipse(
+                DrawEllipseInputs::new((200, 120), (150, 60), color, style),
+            ))]);
+
+            let result_img = done.unwrap();
+
+            assert_eq!(result_img.dimensions(), img.dimensions());
+            assert_eq!(result_img.get_pixel(0, 0), img.get_pixel(0, 0));
+            assert_ne!(result_img.get_pixel(50, 120), img.get_pixel(50, 120));
+
+            output_test_image_for_manual_inspection(result_img, out_!(name));
+        }
+
+        #[pm(
+            style = { ShapeStyle::Filled, ShapeStyle::Stroke(5) },
+            name = { "test_draw_polygon_filled.png", "test_draw_polygon_stroke.png" }
+        )]
+        fn draw_polygon(style: ShapeStyle, name: &str) {
+            let img = setup_default_test_image();
+            let color = Rgba([0, 255, 0, 160]);
+            let star = vec![(200, 20), (240, 220), (100, 90), (300, 90), (160, 220)];
+
+            let mut operator = ImageEngine::new(img.clone());
+            let done = operator.ignite(&[Instr::Operation(ImgOp::DrawPolygon(
+                DrawPolygonInputs::new(star, color, style).unwrap(),
+            ))]);
+
+            let result_img = done.unwrap();
+
+            assert_eq!(result_img.dimensions(), img.dimensions());
+            assert_eq!(result_img.get_pixel(0, 0), img.get_pixel(0, 0));
+            assert_ne!(result_img.get_pixel(200, 20), img.get_pixel(200, 20));
+
+            output_test_image_for_manual_inspection(result_img, out_!(name));
+        }
+
+        #[test]
+        fn draw_line_and_arrow() {
+            let img = setup_default_test_image();
+            let line = DrawLineInputs::new((10, 10), (200, 40), Rgba([255, 255, 0, 255]), 3);
+            let arrow = DrawLineInputs::new((20, 200), (150, 80), Rgba([255, 0, 0, 255]), 4);
+
+            let mut operator = ImageEngine::new(img.clone());
+            let done = operator.ignite(&[
+                Instr::Operation(ImgOp::DrawLine(line)),
+                Instr::Operation(ImgOp::DrawArrow(arrow)),
+            ]);
+
+            let result_img = done.unwrap();
+
+            assert_eq!(result_img.dimensions(), img.dimensions());
+            assert_eq!(result_img.get_pixel(10, 10), Rgba([255, 255, 0, 255]));
+            assert_eq!(result_img.get_pixel(85, 140), Rgba([255, 0, 0, 255]));
+            assert_eq!(result_img.get_pixel(0, 100), img.get_pixel(0, 100));
+
+            output_test_image_for_manual_inspection(result_img, out_!("test_draw_line_arrow.png"));
+        }
 
         #[test]
         fn draw_text() {
@@ -1457,5 +4808,365 @@ mod tests {
                 out_!("test_imageproc_ops_draw__text.png"),
             );
         }
+
+        #[test]
+        fn draw_text_measured_from_bottom_right() {
+            let img: DynamicImage =
+                DynamicImage::ImageRgb8(sic_core::image::RgbImage::new(200, 200));
+
+            let font_file = Into::<PathBuf>::into(env!("CARGO_MANIFEST_DIR"))
+                .join("../../resources/font/Lato-Regular.ttf");
+
+            let operation = ImgOp::DrawText(DrawTextInner::new(
+                "HELLO".to_string(),
+                (10, 10),
+                FontOptions::new(
+                    font_file,
+                    Rgba([255, 255, 0, 255]),
+                    FontScale::Uniform(16.0),
+                ),
+            ));
+
+            let mut operator = ImageEngine::new(img);
+            let done = operator.ignite(&[
+                Instr::EnvAdd(EnvItem::Gravity(Gravity::BottomRight)),
+                Instr::Operation(operation),
+            ]);
+
+            let result_img = done.unwrap();
+            let drawn = result_img
+                .pixels()
+                .filter(|(_, _, pixel)| pixel[0] > 0)
+                .map(|(x, y, _)| (x, y))
+                .collect::<Vec<_>>();
+
+            assert!(!drawn.is_empty());
+            assert!(drawn
+                .iter()
+                .all(|&(x, y)| (100..190).contains(&x) && (150..190).contains(&y)));
+        }
+
+        #[test]
+        fn draw_text_with_outline_and_shadow() {
+            let img: DynamicImage =
+                DynamicImage::ImageRgb8(sic_core::image::RgbImage::new(200, 200));
+
+            let font_file = Into::<PathBuf>::into(env!("CARGO_MANIFEST_DIR"))
+                .join("../../resources/font/Lato-Regular.ttf");
+
+            let operation = ImgOp::DrawText(DrawTextInner::new(
+                "HELLO WORLD".to_string(),
+                (10, 10),
+                FontOptions::new(
+                    font_file,
+                    Rgba([255, 255, 0, 255]),
+                    FontScale::Uniform(16.0),
+                )
+                .with_outline(TextOutline {
+                    width: 2.0,
+                    color: Rgba([0, 0, 255, 255]),
+                })
+                .with_shadow(TextShadow {
+                    offset: (3, 3),
+                    color: Rgba([0, 0, 0, 128]),
+                })
+                .with_spacing(1.5, 0.0)
+                .with_angle(-20.0),
+            ));
+
+            let mut operator = ImageEngine::new(img);
+            let done = operator.ignite(&[Instr::Operation(operation)]);
+            assert!(done.is_ok());
+
+            let result_img = done.unwrap();
+
+            output_test_image_for_manual_inspection(
+                result_img,
+                out_!("test_imageproc_ops_draw_text_outline_shadow.png"),
+            );
+        }
+
+        #[test]
+        fn draw_text_invalid_outline_width() {
+            let img: DynamicImage = DynamicImage::ImageRgb8(sic_core::image::RgbImage::new(20, 20));
+
+            let font_file = Into::<PathBuf>::into(env!("CARGO_MANIFEST_DIR"))
+                .join("../../resources/font/Lato-Regular.ttf");
+
+            let operation = ImgOp::DrawText(DrawTextInner::new(
+                "A".to_string(),
+                (0, 0),
+                FontOptions::new(font_file, Rgba([0, 0, 0, 255]), FontScale::Uniform(16.0))
+                    .with_outline(TextOutline {
+                        width: 0.0,
+                        color: Rgba([0, 0, 0, 255]),
+                    }),
+            ));
+
+            let mut operator = ImageEngine::new(img);
+            let done = operator.ignite(&[Instr::Operation(operation)]);
+
+            assert!(matches!(
+                done,
+                Err(SicImageEngineError::DrawTextInvalidOutlineWidth(_))
+            ));
+        }
+
+        #[test]
+        fn adaptive_threshold() {
+            let img: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::AdaptiveThreshold(15))]);
+            assert!(done.is_ok());
+
+            let result_img = done.unwrap();
+            assert_eq!(result_img.color(), sic_core::image::ColorType::L8);
+
+            output_test_image_for_manual_inspection(
+                result_img,
+                out_!("test_imageproc_ops_adaptive_threshold.png"),
+            );
+        }
+
+        #[test]
+        fn clahe() {
+            let img: DynamicImage = setup_default_test_image();
+            let cmp: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::Clahe((4, 2.0)))]);
+            assert!(done.is_ok());
+
+            let result_img = done.unwrap();
+            assert_eq!(result_img.dimensions(), cmp.dimensions());
+            assert_eq!(result_img.color(), cmp.color());
+
+            output_test_image_for_manual_inspection(
+                result_img,
+                out_!("test_imageproc_ops_clahe.png"),
+            );
+        }
+
+        #[test]
+        fn distance_transform() {
+            let img: DynamicImage = setup_default_test_image();
+            let cmp: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[
+                Instr::Operation(ImgOp::AdaptiveThreshold(15)),
+                Instr::Operation(ImgOp::DistanceTransform),
+            ]);
+
+            let result_img = done.unwrap();
+            assert_eq!(result_img.dimensions(), cmp.dimensions());
+            assert_eq!(result_img.color(), sic_core::image::ColorType::L8);
+
+            output_test_image_for_manual_inspection(
+                result_img,
+                out_!("test_imageproc_ops_distance_transform.png"),
+            );
+        }
+
+        #[test]
+        fn draw_corners() {
+            use crate::wrapper::draw_corners::DrawCornersInputs;
+
+            let img: DynamicImage = setup_default_test_image();
+            let cmp: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::DrawCorners(
+                DrawCornersInputs::new(40, Rgba([0, 255, 0, 255])),
+            ))]);
+
+            let result_img = done.unwrap();
+            assert_eq!(result_img.dimensions(), cmp.dimensions());
+            assert_ne!(result_img.to_bytes(), cmp.to_bytes());
+
+            output_test_image_for_manual_inspection(
+                result_img,
+                out_!("test_imageproc_ops_draw_corners.png"),
+            );
+        }
+
+        #[test]
+        fn label_components() {
+            let img: DynamicImage = setup_default_test_image();
+            let cmp: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[
+                Instr::Operation(ImgOp::AdaptiveThreshold(15)),
+                Instr::Operation(ImgOp::LabelComponents(8)),
+            ]);
+
+            let result_img = done.unwrap();
+            assert_eq!(result_img.dimensions(), cmp.dimensions());
+            assert_eq!(result_img.color(), sic_core::image::ColorType::Rgb8);
+
+            output_test_image_for_manual_inspection(
+                result_img,
+                out_!("test_imageproc_ops_label_components.png"),
+            );
+        }
+
+        #[test]
+        fn median() {
+            let img: DynamicImage = setup_default_test_image();
+            let cmp: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::Median(1))]);
+
+            let result_img = done.unwrap();
+            assert_eq!(result_img.dimensions(), cmp.dimensions());
+            assert_eq!(result_img.color(), cmp.color());
+
+            output_test_image_for_manual_inspection(
+                result_img,
+                out_!("test_imageproc_ops_median.png"),
+            );
+        }
+
+        #[pm(
+            operation = {
+                ImgOp::Dilate(2),
+                ImgOp::Erode(2),
+                ImgOp::MorphOpen(2),
+                ImgOp::MorphClose(2),
+            },
+            output = {
+                "test_imageproc_ops_dilate.png",
+                "test_imageproc_ops_erode.png",
+                "test_imageproc_ops_morph_open.png",
+                "test_imageproc_ops_morph_close.png",
+            }
+        )]
+        fn morphology(operation: ImgOp, output: &str) {
+            let img: DynamicImage = setup_default_test_image();
+            let cmp: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[
+                Instr::Operation(ImgOp::AdaptiveThreshold(15)),
+                Instr::Operation(operation),
+            ]);
+
+            let result_img = done.unwrap();
+            assert_eq!(result_img.dimensions(), cmp.dimensions());
+            assert_eq!(result_img.color(), sic_core::image::ColorType::L8);
+
+            output_test_image_for_manual_inspection(result_img, out_!(output));
+        }
+
+        #[test]
+        fn noise() {
+            let img: DynamicImage = setup_default_test_image();
+            let cmp: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::Noise(NoiseInputs::new(
+                NoiseKind::Gaussian,
+                0.1,
+                Some(1),
+            )))]);
+
+            let result_img = done.unwrap();
+            assert_eq!(result_img.dimensions(), cmp.dimensions());
+            assert_ne!(result_img.raw_pixels(), cmp.raw_pixels());
+
+            output_test_image_for_manual_inspection(
+                result_img,
+                out_!("test_imageproc_ops_noise.png"),
+            );
+        }
+
+        #[test]
+        fn noise_invalid_amount() {
+            let img: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::Noise(NoiseInputs::new(
+                NoiseKind::SaltPepper,
+                1.5,
+                None,
+            )))]);
+
+            assert!(done.is_err());
+        }
+
+        #[test]
+        fn perspective() {
+            let img: DynamicImage = setup_default_test_image();
+            let (w, h) = img.dimensions();
+            let (w, h) = (w as f32, h as f32);
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::Perspective(
+                PerspectiveInputs::new([(w * 0.25, 0.0), (w * 0.75, 0.0), (w, h), (0.0, h)]),
+            ))]);
+
+            // the slanted left and right edges are longer than the height of the image
+            let result_img = done.unwrap();
+            assert_eq!(result_img.width(), w as u32);
+            assert!(result_img.height() > h as u32);
+
+            output_test_image_for_manual_inspection(
+                result_img,
+                out_!("test_imageproc_ops_perspective.png"),
+            );
+        }
+
+        #[test]
+        fn perspective_invalid_corners() {
+            let img: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::Perspective(
+                PerspectiveInputs::new([(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.0)]),
+            ))]);
+
+            assert!(done.is_err());
+        }
+
+        #[test]
+        fn clahe_invalid_tile_size() {
+            let img: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::Clahe((0, 2.0)))]);
+
+            assert!(done.is_err());
+        }
+
+        #[test]
+        fn adaptive_threshold_with_method() {
+            let img: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let mut engine2 = engine.clone();
+
+            let left = engine.ignite(&[
+                Instr::EnvAdd(EnvItem::AdaptiveThresholdMethod(
+                    AdaptiveThresholdMethod::Gaussian,
+                )),
+                Instr::Operation(ImgOp::AdaptiveThreshold(15)),
+            ]);
+            let right = engine2.ignite(&[Instr::Operation(ImgOp::AdaptiveThreshold(15))]);
+
+            assert_ne!(left.unwrap().raw_pixels(), right.unwrap().raw_pixels());
+        }
+
+        #[test]
+        fn adaptive_threshold_even_block_size() {
+            let img: DynamicImage = setup_default_test_image();
+
+            let mut engine = ImageEngine::new(img);
+            let done = engine.ignite(&[Instr::Operation(ImgOp::AdaptiveThreshold(4))]);
+
+            assert!(done.is_err());
+        }
     }
 }
@@ -2,18 +2,252 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum SicImageEngineError {
+    #[error(
+        "unable to convolve; the kernel size should be an odd number between 1 and 31, but was {0}"
+    )]
+    ConvolveInvalidKernelSize(u32),
+
+    #[error(
+        "unable to convolve; a kernel of size {0} takes {0} by {0} weights, but {1} were given"
+    )]
+    ConvolveKernelValueCount(u32, usize),
+
+    #[error("unable to convolve; the weights of the kernel should be finite numbers")]
+    ConvolveKernelNotFinite,
+
+    #[error("unable to load kernel file: {0}")]
+    ConvolveKernelFileLoadError(std::io::Error),
+
+    #[error("unable to parse kernel file; {1} (line {0})")]
+    ConvolveKernelParseError(usize, String),
+
     #[error("unable to crop; required top-left anchor < bottom-right anchor; note that (x=0,y=0) is the smallest top-left coordinate; [top-left anchor: (x={0}, y={1}), bottom-right anchor: (x={2}, y={3})]")]
     CropInvalidSelection(u32, u32, u32, u32),
 
     #[error("unable to crop; anchor coordinates should be within image bounds [image size: (x={0}, y={1}), top-left anchor: (x={2}, y={3}), bottom-right anchor: (x={4}, y={5})]")]
     CropCoordinateOutOfBounds(u32, u32, u32, u32, u32, u32),
 
+    #[error("unable to crop; the selection should be at least 1 by 1 pixels, and fit within the image [selection size: (x={0}, y={1}), image size: (x={2}, y={3})]")]
+    CropInvalidSize(u32, u32, u32, u32),
+
+    #[error("unable to crop; the selection should fit within the image when it's placed by the gravity [offset from the gravity: (x={0}, y={1}), selection size: (x={2}, y={3}), image size: (x={4}, y={5})]")]
+    CropOutOfBoundsForGravity(u32, u32, u32, u32, u32, u32),
+
     #[error("unable to load image argument from given path")]
     LoadImageFromPath,
 
+    #[error("unable to read image argument '{0}': {1}")]
+    ImageFromPathNotReadable(std::path::PathBuf, std::io::Error),
+
+    #[error("image argument '{0}' is not an image of a supported format")]
+    ImageFromPathUnknownFormat(std::path::PathBuf),
+
     #[error("filter type '{0}' not found")]
     UnknownFilterType(String),
 
+    #[error("adaptive threshold method '{0}' not found; valid methods are 'mean' and 'gaussian'")]
+    UnknownAdaptiveThresholdMethod(String),
+
+    #[error("blur algorithm '{0}' not found; valid algorithms are 'gaussian' and 'box'")]
+    UnknownBlurAlgorithm(String),
+
+    #[error("gravity '{0}' not found; valid values are 'top-left', 'top', 'top-right', 'left', 'center', 'right', 'bottom-left', 'bottom' and 'bottom-right', or their compass directions, e.g. 'north-west'")]
+    UnknownGravity(String),
+
+    #[error("aspect ratio '{0}' is not valid; expected a ratio of the form '<width>:<height>', e.g. '16:9', where both sides are natural numbers larger than 0")]
+    InvalidAspectRatio(String),
+
+    #[error("'{0}' is neither a number of pixels, a percentage nor an expression; expected e.g. '640', '50%' or '$height/2'")]
+    InvalidDimension(String),
+
+    #[error("'{0}' is not a valid expression; expected numbers and variables combined by '+', '-', '*', '/', parentheses, 'min(a, b)' or 'max(a, b)', e.g. '($width-10)/2'")]
+    InvalidExpression(String),
+
+    #[error("variable '{0}' not found; valid variables are '$width', '$height', '$aspect' and '$frames'")]
+    UnknownVariable(String),
+
+    #[error("'{0}' resolves to {1}, which is not a valid number of pixels")]
+    DimensionOutOfRange(String, f64),
+
+    #[error("channel '{0}' not found; valid channels are 'r' (red), 'g' (green), 'b' (blue) and 'a' (alpha)")]
+    UnknownChannel(String),
+
+    #[error("mirror mode '{0}' not found; valid modes are 'horizontal', 'vertical' and 'quad'")]
+    UnknownMirrorMode(String),
+
+    #[error("gradient '{0}' not found; valid gradients are 'linear' and 'radial'")]
+    UnknownGradientKind(String),
+
+    #[error("tone mapping operator '{0}' not found; valid operators are 'reinhard' and 'aces'")]
+    UnknownToneMapOperator(String),
+
+    #[error("histogram channels '{0}' not found; valid values are 'luma' and 'rgb'")]
+    UnknownHistogramChannels(String),
+
+    #[error("histogram placement '{0}' not found; valid placements are 'append' and 'overlay'")]
+    UnknownHistogramPlacement(String),
+
+    #[error("redact style '{0}' not found; valid styles are 'fill' and 'pixelate'")]
+    UnknownRedactStyle(String),
+
+    #[error("blend mode '{0}' not found; valid modes are 'normal', 'multiply', 'screen', 'overlay', 'darken' and 'lighten'")]
+    UnknownBlendMode(String),
+
+    #[error("image operation '{0}' not found; a modifier can only be set for an occurrence of an existing image operation, e.g. 'resize.0'")]
+    UnknownOperationName(String),
+
+    #[error("the modifier set for '{0}' is never used; that occurrence of the image operation isn't applied after the modifier is set (occurrences are counted from 0)")]
+    UnusedOccurrence(String),
+
+    #[error("unable to add border; 1 to 4 widths should be given, as for CSS borders, but {0} were given")]
+    BorderInvalidWidthCount(usize),
+
+    #[error("unable to add border; the image with its border would be too large")]
+    BorderTooLarge,
+
+    #[error("unable to extend; the canvas should be at least as large as the image [canvas size: (x={0}, y={1}), image size: (x={2}, y={3})]")]
+    ExtendCanvasTooSmall(u32, u32, u32, u32),
+
+    #[error("unable to tone map; the high dynamic range samples of the image were clamped by the preceding '{0}' operation, so its highlights would be clipped; apply tonemap before it, or use only flips, rotations and crops before tonemap")]
+    TonemapAfterClampingOperation(String),
+
+    #[error("unable to apply the operation within the region; no part of the region lies within the image [region: (x={0}, y={1}, width={2}, height={3}), image size: (x={4}, y={5})]")]
+    RegionOutsideImage(u32, u32, u32, u32, u32, u32),
+
+    #[error("unable to apply the operation within the region; operations which change the size of the image can't be restricted to a region [region size: (x={0}, y={1}), size after the operation: (x={2}, y={3})]")]
+    RegionSizeChanged(u32, u32, u32, u32),
+
+    #[error("unable to apply the operation with the mask; the mask should be as large as the image [mask size: (x={0}, y={1}), image size: (x={2}, y={3})]")]
+    MaskSizeMismatch(u32, u32, u32, u32),
+
+    #[error("unable to apply the operation with the mask; operations which change the size of the image can't be masked [image size: (x={0}, y={1}), size after the operation: (x={2}, y={3})]")]
+    MaskSizeChanged(u32, u32, u32, u32),
+
+    #[error("unable to redact; no part of the region lies within the image [region: (x={0}, y={1}, width={2}, height={3}), image size: (x={4}, y={5})]")]
+    RedactOutsideImage(u32, u32, u32, u32, u32, u32),
+
+    #[error("unable to find the template; the template should not be empty, nor larger than the image [template size: (x={0}, y={1}), image size: (x={2}, y={3})]")]
+    FindTemplateTooLarge(u32, u32, u32, u32),
+
+    #[error("unable to draw histogram; the image with the histogram appended would be too large")]
+    DrawHistogramTooLarge,
+
+    #[error("unable to trim; the tolerance should be 0 or larger, but was {0}")]
+    TrimInvalidTolerance(f32),
+
+    #[error("unable to pad to aspect ratio; the padded image would be too large")]
+    PadToRatioTooLarge,
+
+    #[error("unable to apply chroma key; the tolerance should be 0 or larger, but was {0}")]
+    ChromaKeyInvalidTolerance(f32),
+
+    #[error("unable to apply chroma key; the feather should be 0 or larger, but was {0}")]
+    ChromaKeyInvalidFeather(f32),
+
+    #[error("unable to dither; the number of colors should be between 2 and 256, but was {0}")]
+    DitherInvalidColorCount(u32),
+
+    #[error("unable to apply fisheye; the strength should be between -1 and 1, but was {0}")]
+    FisheyeInvalidStrength(f32),
+
+    #[error("unable to flatten on a checkerboard; the cell size should be larger than 0")]
+    FlattenCheckerInvalidCellSize,
+
+    #[error(
+        "unable to apply gradient map; at least 2 color stops are required, but {0} were given"
+    )]
+    GradientMapTooFewStops(usize),
+
+    #[error("unable to apply gradient map; the position of a color stop should be between 0 and 1, but was {0}")]
+    GradientMapInvalidStopPosition(f32),
+
+    #[error("unable to convert to grayscale; the weights should not be negative and at least one should be positive, but were ({0}, {1}, {2})")]
+    GrayscaleInvalidWeights(f32, f32, f32),
+
+    #[error("unable to open LUT file from path: '{0}'")]
+    LutFileLoadError(std::io::Error),
+
+    #[error("unable to parse LUT file; {1} (line {0})")]
+    LutParseError(usize, String),
+
+    #[error("unable to mirror; the mirrored image would be too large")]
+    MirrorTooLarge,
+
+    #[error("unable to apply nine-slice; the left and right insets should fit within both the width of the image and the output width, and the top and bottom insets within both heights, and the output should be at least 1 by 1 pixels [image size: (x={0}, y={1}), output size: (x={2}, y={3})]")]
+    NineSliceInvalidInsets(u32, u32, u32, u32),
+
+    #[error(
+        "unable to apply oil paint; the number of levels should be between 1 and 256, but was {0}"
+    )]
+    OilPaintInvalidLevels(u32),
+
+    #[error("unable to change the opacity; the opacity should be between 0 and 1, but was {0}")]
+    OpacityOutOfRange(f32),
+
+    #[error("unable to overlay; the opacity should be between 0 and 1, but was {0}")]
+    OverlayInvalidOpacity(f32),
+
+    #[error("unable to upscale pixel art; the factor should be 2, 3 or 4, but was {0}")]
+    PixelUpscaleInvalidFactor(u32),
+
+    #[error("unable to upscale pixel art; the upscaled image would be too large")]
+    PixelUpscaleTooLarge,
+
+    #[error("unable to apply radial blur; the strength should be between 0 and 1, but was {0}")]
+    RadialBlurInvalidStrength(f32),
+
+    #[error("unable to resize; only one of the dimensions can be 0, to be derived from the aspect ratio of the image")]
+    ResizeBothDimensionsZero,
+
+    #[error("unable to sharpen; the amount should not be negative, but was {0}")]
+    SharpenInvalidAmount(f32),
+
+    #[error("unable to stack images; the stacked image would be too large")]
+    StackTooLarge,
+
+    #[error("unable to tone map; the exposure should be a finite number, but was {0}")]
+    ToneMapInvalidExposure(f32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to apply adaptive threshold; the block size should be an odd number larger than 1, but was {0}")]
+    AdaptiveThresholdInvalidBlockSize(u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to apply clahe; the tile size should be larger than 0")]
+    ClaheInvalidTileSize,
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to apply clahe; the clip limit should be larger than 0, but was {0}")]
+    ClaheInvalidClipLimit(f32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to draw arrow; its start and end point should differ")]
+    DrawArrowZeroLength,
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to draw ellipse; its radii should be larger than 0, but were {0} and {1}")]
+    DrawEllipseInvalidRadius(u32, u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to draw polygon; it should have at least 3 points, but had {0}")]
+    DrawPolygonTooFewPoints(usize),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to draw rectangle; its width and height should be larger than 0, but were {0} and {1}")]
+    DrawRectInvalidSize(u32, u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to label components; the connectivity should be 4 or 8, but was {0}")]
+    LabelComponentsInvalidConnectivity(u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to apply median; the radius should be at most {1} (smaller than the largest image dimension, and at most 255), but was {0}")]
+    MedianInvalidRadius(u32, u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to draw shape; the stroke width should be larger than 0")]
+    DrawShapeInvalidStrokeWidth,
+
     #[cfg(feature = "imageproc-ops")]
     #[error("unable to load font: invalid format")]
     FontError,
@@ -21,4 +255,43 @@ pub enum SicImageEngineError {
     #[cfg(feature = "imageproc-ops")]
     #[error("unable to open font file from path: '{0}'")]
     FontFileLoadError(std::io::Error),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to find font family '{0}' among the fonts installed on this system")]
+    FontFamilyNotFound(String),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("font style '{0}' not found; valid styles are 'regular', 'bold', 'italic' and 'bold italic'")]
+    UnknownFontStyle(String),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to draw text; the font size should be a number larger than 0, but was {0}")]
+    DrawTextInvalidSize(f32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to draw text; the outline width should be larger than 0 and at most 64, but was {0}")]
+    DrawTextInvalidOutlineWidth(f32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to draw text; the width to wrap text at should be larger than 0, but was {0}")]
+    DrawTextInvalidWrapWidth(f32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("text alignment '{0}' not found; valid alignments are 'left', 'center' and 'right'")]
+    UnknownTextAlign(String),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("noise kind '{0}' not found; valid kinds are 'gaussian' and 'salt-pepper'")]
+    UnknownNoiseKind(String),
+
+    #[error("pixel upscale algorithm '{0}' not found; the only valid algorithm is 'scale2x'")]
+    UnknownPixelUpscaleAlgorithm(String),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to add noise; the amount should be between 0 and 1, but was {0}")]
+    NoiseInvalidAmount(f32),
+
+    #[cfg(feature = "imageproc-ops")]
+    #[error("unable to apply perspective; the corners should be finite, and span a quadrilateral of which no three corners lie on a line")]
+    PerspectiveInvalidCorners,
 }
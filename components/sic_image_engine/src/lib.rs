@@ -3,35 +3,180 @@
 #[macro_use]
 extern crate strum_macros;
 
+use crate::wrapper::border::BorderInputs;
+use crate::wrapper::channel::Channel;
+use crate::wrapper::chroma_key::ChromaKeyInputs;
+use crate::wrapper::contours::ContoursInputs;
+#[cfg(feature = "imageproc-ops")]
+use crate::wrapper::draw_corners::DrawCornersInputs;
+#[cfg(feature = "imageproc-ops")]
+use crate::wrapper::draw_shape::{
+    DrawEllipseInputs, DrawLineInputs, DrawPolygonInputs, DrawRectInputs,
+};
 #[cfg(feature = "imageproc-ops")]
 use crate::wrapper::draw_text_inner::DrawTextInner;
+use crate::wrapper::extend::ExtendInputs;
+use crate::wrapper::find::FindInputs;
+use crate::wrapper::gradient::Gradient;
+use crate::wrapper::gradient_fill::GradientFillInputs;
+use crate::wrapper::gravity::Gravity;
+use crate::wrapper::histogram::DrawHistogramInputs;
 use crate::wrapper::image_path::ImageFromPath;
+use crate::wrapper::kernel::{Kernel, KernelFromPath};
+use crate::wrapper::lut::LutFromPath;
+use crate::wrapper::mirror::MirrorMode;
+use crate::wrapper::nine_slice::NineSliceInputs;
+#[cfg(feature = "imageproc-ops")]
+use crate::wrapper::noise::NoiseInputs;
 use crate::wrapper::overlay::OverlayInputs;
+use crate::wrapper::pad_to_ratio::PadToRatioInputs;
+#[cfg(feature = "imageproc-ops")]
+use crate::wrapper::perspective::PerspectiveInputs;
+use crate::wrapper::pixel_upscale::PixelUpscaleInputs;
+use crate::wrapper::redact::RedactInputs;
+use crate::wrapper::stamp::StampInputs;
+use crate::wrapper::tonemap::ToneMapInputs;
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
 
+mod analysis;
+pub mod dry_run;
 pub mod engine;
 pub mod errors;
+mod operations;
+mod serialization;
 pub mod wrapper;
 
-#[derive(Debug, PartialEq, Clone)]
+/// The amount by which `sharpen` sharpens the image, if no amount is given.
+pub const SHARPEN_DEFAULT_AMOUNT: f32 = 1.0;
+
+/// The tolerance by which `trim` recognizes the border of the image, if no tolerance is given.
+pub const TRIM_DEFAULT_TOLERANCE: f32 = 0.0;
+
+/// The connectivity by which `label-components` connects pixels, if no connectivity is given.
+#[cfg(feature = "imageproc-ops")]
+pub const LABEL_COMPONENTS_DEFAULT_CONNECTIVITY: u32 = 8;
+
+/// Image operations are serialized by their names, e.g. `{"resize": [10, 10]}` in JSON; colors are
+/// serialized as their channels, e.g. `[255, 0, 0, 255]`.
+#[derive(Debug, PartialEq, Clone, AsRefStr, EnumVariantNames, Serialize, Deserialize)]
+#[strum(serialize_all = "kebab_case")]
+#[serde(rename_all = "kebab-case")]
 pub enum ImgOp {
+    AlphaAdd,
+    AlphaPremultiply,
+    AlphaRemove,
+    AlphaThreshold(u8),
+    AutoEnhance,
     Blur(f32),
+    Border(BorderInputs),
+    BoxBlur(u32),
     Brighten(i32),
+    ChannelExtract(Channel),
+    ChannelSwap((Channel, Channel)),
+    ChromaKey(ChromaKeyInputs),
+    Contours(ContoursInputs),
     Contrast(f32),
+    Convolve(Kernel),
+    ConvolveFile(KernelFromPath),
     Crop((u32, u32, u32, u32)),
+    CropGravity((Gravity, u32, u32)),
     Diff(ImageFromPath),
+    Dither(u32),
+    DrawHistogram(DrawHistogramInputs),
+    Duotone(#[serde(with = "serialization::rgba_pair")] (Rgba<u8>, Rgba<u8>)),
+    Equalize,
+    Extend(ExtendInputs),
     Filter3x3([f32; 9]),
+    Find(FindInputs),
+    Fisheye(f32),
+    FlattenChecker(u32),
     FlipHorizontal,
     FlipVertical,
+    Gradient(GradientFillInputs),
+    GradientMap(Gradient),
+    #[strum(serialize = "grayscale")]
+    #[serde(rename = "grayscale")]
     GrayScale,
+    GrayscaleWeighted((f32, f32, f32)),
+    Hstack(ImageFromPath),
     HueRotate(i32),
     Invert,
+    LocalMean(u32),
+    LocalVariance(u32),
+    Lut(LutFromPath),
+    Mirror(MirrorMode),
+    NineSlice(NineSliceInputs),
+    OilPaint((u32, u32)),
+    Opacity(f32),
     Overlay(OverlayInputs),
+    PadToRatio(PadToRatioInputs),
+    PixelUpscale(PixelUpscaleInputs),
+    RadialBlur((u32, u32, f32)),
+    Redact(RedactInputs),
     Resize((u32, u32)),
     Rotate90,
     Rotate180,
     Rotate270,
+    Sharpen(f32),
+    SmartCrop((u32, u32)),
+    Stamp(StampInputs),
+    Tonemap(ToneMapInputs),
+    Trim(f32),
     Unsharpen((f32, i32)),
+    Vstack(ImageFromPath),
+
+    #[cfg(feature = "imageproc-ops")]
+    AdaptiveThreshold(u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    Clahe((u32, f32)),
+
+    #[cfg(feature = "imageproc-ops")]
+    Dilate(u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    DistanceTransform,
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawArrow(DrawLineInputs),
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawCorners(DrawCornersInputs),
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawEllipse(DrawEllipseInputs),
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawLine(DrawLineInputs),
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawPolygon(DrawPolygonInputs),
+
+    #[cfg(feature = "imageproc-ops")]
+    DrawRect(DrawRectInputs),
 
     #[cfg(feature = "imageproc-ops")]
     DrawText(DrawTextInner),
+
+    #[cfg(feature = "imageproc-ops")]
+    Erode(u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    LabelComponents(u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    Median(u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    MorphClose(u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    MorphOpen(u32),
+
+    #[cfg(feature = "imageproc-ops")]
+    Noise(NoiseInputs),
+
+    #[cfg(feature = "imageproc-ops")]
+    Perspective(PerspectiveInputs),
 }
@@ -0,0 +1,91 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::local_statistics::LocalStatistics;
+use crate::wrapper::adaptive_threshold_method::AdaptiveThresholdMethod;
+use sic_core::image::{DynamicImage, GrayImage, Luma};
+
+/// Binarizes an image by comparing each pixel to a threshold computed from its
+/// `block_size` x `block_size` neighbourhood, instead of using a single global threshold.
+/// Pixels which are at least as bright as their local threshold become white, others black.
+pub(crate) fn adaptive_threshold(
+    image: &DynamicImage,
+    block_size: u32,
+    method: AdaptiveThresholdMethod,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if block_size < 3 || block_size & 1 == 0 {
+        return Err(SicImageEngineError::AdaptiveThresholdInvalidBlockSize(
+            block_size,
+        ));
+    }
+
+    let gray = image.to_luma();
+
+    let out = match method {
+        AdaptiveThresholdMethod::Mean => mean_threshold(&gray, block_size),
+        AdaptiveThresholdMethod::Gaussian => gaussian_threshold(&gray, block_size),
+    };
+
+    Ok(DynamicImage::ImageLuma8(out))
+}
+
+fn mean_threshold(image: &GrayImage, block_size: u32) -> GrayImage {
+    let local = LocalStatistics::new(image, block_size / 2);
+
+    GrayImage::from_fn(image.width(), image.height(), |x, y| {
+        if f64::from(image.get_pixel(x, y)[0]) >= local.mean(x, y) {
+            Luma([255])
+        } else {
+            Luma([0])
+        }
+    })
+}
+
+fn gaussian_threshold(image: &GrayImage, block_size: u32) -> GrayImage {
+    // Derives sigma from the block size in the same way as OpenCV does for its Gaussian kernels.
+    let sigma = 0.3 * ((block_size as f32 - 1.0) * 0.5 - 1.0) + 0.8;
+    let local = imageproc::filter::gaussian_blur_f32(image, sigma);
+
+    GrayImage::from_fn(image.width(), image.height(), |x, y| {
+        if image.get_pixel(x, y)[0] >= local.get_pixel(x, y)[0] {
+            Luma([255])
+        } else {
+            Luma([0])
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    // A horizontal gradient from dark to less dark, with a single bright stripe at x = 8.
+    // A global threshold at the mean would mark most of the right half white.
+    fn unevenly_lit() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(16, 4, |x, _| {
+            if x == 8 {
+                Luma([200])
+            } else {
+                Luma([(x * 8) as u8])
+            }
+        }))
+    }
+
+    #[pm(method = { AdaptiveThresholdMethod::Mean, AdaptiveThresholdMethod::Gaussian })]
+    fn binarizes(method: AdaptiveThresholdMethod) {
+        let out = adaptive_threshold(&unevenly_lit(), 5, method).unwrap();
+        let out = out.as_luma8().unwrap();
+
+        assert!(out.pixels().all(|px| px[0] == 0 || px[0] == 255));
+        assert_eq!(out.get_pixel(8, 2)[0], 255);
+        assert_eq!(out.get_pixel(9, 2)[0], 0);
+    }
+
+    #[pm(block_size = { 0, 1, 2, 4 })]
+    fn invalid_block_size(block_size: u32) {
+        let result = adaptive_threshold(&unevenly_lit(), block_size, AdaptiveThresholdMethod::Mean);
+
+        assert!(result.is_err());
+    }
+}
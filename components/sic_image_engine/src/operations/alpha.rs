@@ -0,0 +1,281 @@
+use crate::errors::SicImageEngineError;
+use sic_core::image::{DynamicImage, GrayImage, ImageBuffer, Luma, Rgb, Rgba};
+
+/// Adds an opaque alpha channel to the image. Images which already have an alpha channel are left
+/// as is.
+pub(crate) fn alpha_add(image: &DynamicImage) -> DynamicImage {
+    match image {
+        _ if image.color().has_alpha() => image.clone(),
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLuma16(_) => {
+            DynamicImage::ImageLumaA8(image.to_luma_alpha())
+        }
+        _ => DynamicImage::ImageRgba8(image.to_rgba()),
+    }
+}
+
+/// Drops the alpha channel of the image. The color of each pixel is kept, regardless of how
+/// transparent it was. Images without an alpha channel are left as is.
+pub(crate) fn alpha_remove(image: &DynamicImage) -> DynamicImage {
+    match image {
+        _ if !image.color().has_alpha() => image.clone(),
+        DynamicImage::ImageLumaA8(_) | DynamicImage::ImageLumaA16(_) => {
+            let buffer = image.to_luma_alpha();
+            let (width, height) = buffer.dimensions();
+
+            DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+                Luma([buffer.get_pixel(x, y)[0]])
+            }))
+        }
+        _ => {
+            let buffer = image.to_rgba();
+            let (width, height) = buffer.dimensions();
+
+            DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+                let pixel = buffer.get_pixel(x, y);
+                Rgb([pixel[0], pixel[1], pixel[2]])
+            }))
+        }
+    }
+}
+
+/// Composites the image over the background color, and drops the alpha channel: transparent
+/// pixels take the background color. The alpha channel of the background color is ignored. Images
+/// without an alpha channel are left as is.
+pub(crate) fn flatten(image: &DynamicImage, background: Rgba<u8>) -> DynamicImage {
+    if !image.color().has_alpha() {
+        return image.clone();
+    }
+
+    let buffer = image.to_rgba();
+    let (width, height) = buffer.dimensions();
+
+    DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+        let pixel = buffer.get_pixel(x, y);
+        let alpha = u16::from(pixel[3]);
+        let blend = |c: usize| {
+            let mixed = u16::from(pixel[c]) * alpha + u16::from(background[c]) * (255 - alpha);
+            ((mixed + 127) / 255) as u8
+        };
+
+        Rgb([blend(0), blend(1), blend(2)])
+    }))
+}
+
+/// Multiplies the color channels of each pixel by its alpha value, i.e. converts straight alpha
+/// to premultiplied alpha. Images without an alpha channel are left as is.
+pub(crate) fn alpha_premultiply(image: &DynamicImage) -> DynamicImage {
+    if !image.color().has_alpha() {
+        return image.clone();
+    }
+
+    let mut buffer = image.to_rgba();
+
+    for pixel in buffer.pixels_mut() {
+        let alpha = u32::from(pixel[3]);
+
+        for channel in pixel.0.iter_mut().take(3) {
+            *channel = ((u32::from(*channel) * alpha + 127) / 255) as u8;
+        }
+    }
+
+    DynamicImage::ImageRgba8(buffer)
+}
+
+/// Hardens the alpha channel: pixels with an alpha value below the threshold become fully
+/// transparent, all others fully opaque. Images without an alpha channel are left as is.
+pub(crate) fn alpha_threshold(image: &DynamicImage, threshold: u8) -> DynamicImage {
+    if !image.color().has_alpha() {
+        return image.clone();
+    }
+
+    let mut buffer = image.to_rgba();
+
+    for pixel in buffer.pixels_mut() {
+        pixel[3] = if pixel[3] < threshold { 0 } else { u8::MAX };
+    }
+
+    DynamicImage::ImageRgba8(buffer)
+}
+
+/// Multiplies the alpha channel by the opacity, which should be between 0 (fully transparent) and
+/// 1 (unchanged). An opaque alpha channel is added first to images without one.
+pub(crate) fn opacity(
+    image: &DynamicImage,
+    opacity: f32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if !(0.0..=1.0).contains(&opacity) {
+        return Err(SicImageEngineError::OpacityOutOfRange(opacity));
+    }
+
+    let scale = |alpha: u8| (f32::from(alpha) * opacity).round() as u8;
+
+    match alpha_add(image) {
+        DynamicImage::ImageLumaA8(mut buffer) => {
+            for pixel in buffer.pixels_mut() {
+                pixel[1] = scale(pixel[1]);
+            }
+
+            Ok(DynamicImage::ImageLumaA8(buffer))
+        }
+        image => {
+            let mut buffer = image.to_rgba();
+
+            for pixel in buffer.pixels_mut() {
+                pixel[3] = scale(pixel[3]);
+            }
+
+            Ok(DynamicImage::ImageRgba8(buffer))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, LumaA, RgbImage, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    fn rgba(pixel: [u8; 4]) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba(pixel)))
+    }
+
+    fn rgb(pixel: [u8; 3]) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb(pixel)))
+    }
+
+    #[test]
+    fn add_to_rgb() {
+        let out = alpha_add(&rgb([10, 20, 30]));
+
+        assert!(matches!(out, DynamicImage::ImageRgba8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 20, 30, 255]));
+    }
+
+    #[test]
+    fn add_to_luma() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 1, Luma([10])));
+        let out = alpha_add(&image);
+
+        assert!(matches!(out, DynamicImage::ImageLumaA8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 10, 10, 255]));
+    }
+
+    #[test]
+    fn add_keeps_existing_alpha() {
+        let out = alpha_add(&rgba([10, 20, 30, 40]));
+
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 20, 30, 40]));
+    }
+
+    #[test]
+    fn remove_from_rgba() {
+        let out = alpha_remove(&rgba([10, 20, 30, 40]));
+
+        assert!(matches!(out, DynamicImage::ImageRgb8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 20, 30, 255]));
+    }
+
+    #[test]
+    fn remove_from_luma_alpha() {
+        let image = DynamicImage::ImageLumaA8(ImageBuffer::from_pixel(1, 1, LumaA([10, 40])));
+        let out = alpha_remove(&image);
+
+        assert!(matches!(out, DynamicImage::ImageLuma8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 10, 10, 255]));
+    }
+
+    #[test]
+    fn remove_without_alpha() {
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+        let out = alpha_remove(&image);
+
+        assert_eq!(out.to_bytes(), image.to_bytes());
+    }
+
+    #[pm(
+        pixel = { [200, 100, 50, 255], [200, 100, 50, 0], [0, 0, 0, 128] },
+        expected = { [200, 100, 50, 255], [0, 0, 255, 255], [0, 0, 127, 255] }
+    )]
+    fn flatten_onto_background(pixel: [u8; 4], expected: [u8; 4]) {
+        let out = flatten(&rgba(pixel), Rgba([0, 0, 255, 128]));
+
+        assert!(matches!(out, DynamicImage::ImageRgb8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba(expected));
+    }
+
+    #[test]
+    fn flatten_without_alpha() {
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+        let out = flatten(&image, Rgba([0, 0, 255, 255]));
+
+        assert_eq!(out.to_bytes(), image.to_bytes());
+    }
+
+    #[pm(
+        pixel = { [200, 100, 50, 255], [200, 100, 50, 0], [200, 100, 50, 128], [255, 255, 255, 51] },
+        expected = { [200, 100, 50, 255], [0, 0, 0, 0], [100, 50, 25, 128], [51, 51, 51, 51] }
+    )]
+    fn premultiply(pixel: [u8; 4], expected: [u8; 4]) {
+        let out = alpha_premultiply(&rgba(pixel));
+
+        assert_eq!(out.get_pixel(0, 0), Rgba(expected));
+    }
+
+    #[test]
+    fn premultiply_without_alpha() {
+        let out = alpha_premultiply(&rgb([10, 20, 30]));
+
+        assert!(matches!(out, DynamicImage::ImageRgb8(_)));
+    }
+
+    #[pm(
+        alpha = { 0, 127, 128, 255 },
+        expected = { 0, 0, 255, 255 }
+    )]
+    fn threshold(alpha: u8, expected: u8) {
+        let out = alpha_threshold(&rgba([10, 20, 30, alpha]), 128);
+
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 20, 30, expected]));
+    }
+
+    #[test]
+    fn threshold_zero_keeps_all_pixels() {
+        let out = alpha_threshold(&rgba([10, 20, 30, 0]), 0);
+
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 20, 30, 255]));
+    }
+
+    #[pm(
+        opacity = { 1.0, 0.5, 0.0 },
+        expected = { [10, 20, 30, 200], [10, 20, 30, 100], [10, 20, 30, 0] }
+    )]
+    fn opacity_rgba(opacity: f32, expected: [u8; 4]) {
+        let out = super::opacity(&rgba([10, 20, 30, 200]), opacity).unwrap();
+
+        assert_eq!(out.get_pixel(0, 0), Rgba(expected));
+    }
+
+    #[test]
+    fn opacity_adds_alpha_to_rgb() {
+        let out = opacity(&rgb([10, 20, 30]), 0.5).unwrap();
+
+        assert!(matches!(out, DynamicImage::ImageRgba8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 20, 30, 128]));
+    }
+
+    #[test]
+    fn opacity_adds_alpha_to_luma() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 1, Luma([10])));
+        let out = opacity(&image, 0.5).unwrap();
+
+        assert!(matches!(out, DynamicImage::ImageLumaA8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 10, 10, 128]));
+    }
+
+    #[pm(value = { -0.1, 1.1, f32::NAN })]
+    fn opacity_out_of_range(value: f32) {
+        assert!(opacity(&rgb([10, 20, 30]), value).is_err());
+    }
+}
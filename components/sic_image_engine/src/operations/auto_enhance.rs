@@ -0,0 +1,283 @@
+use sic_core::image::{imageops, DynamicImage, Pixel, RgbaImage};
+
+/// The fraction of the darkest and of the brightest pixels which may be clipped by the contrast
+/// stretch, so a few outliers don't prevent the stretch.
+const CLIP_FRACTION: f64 = 0.005;
+
+/// The contrast isn't stretched if the luminance of the image already spans at least this
+/// range.
+const FULL_RANGE: u8 = 240;
+
+/// The mean saturation which the saturation boost aims for, and the largest boost.
+const TARGET_SATURATION: f32 = 0.35;
+const MAX_SATURATION_BOOST: f32 = 1.3;
+
+const SHARPEN_SIGMA: f32 = 0.8;
+const SHARPEN_THRESHOLD: i32 = 4;
+
+/// The adjustments made by `auto_enhance`, derived from the histograms of the image.
+#[derive(Debug, PartialEq)]
+struct Enhancement {
+    /// The luminance which becomes black.
+    black: u8,
+    /// The luminance which becomes white.
+    white: u8,
+    /// The factor by which the saturation is multiplied.
+    saturation: f32,
+}
+
+impl Enhancement {
+    fn from_image(image: &RgbaImage) -> Self {
+        let mut histogram = [0u64; 256];
+        let mut saturation = 0.0;
+
+        for pixel in image.pixels() {
+            histogram[usize::from(pixel.to_luma()[0])] += 1;
+
+            let channels = &pixel.channels()[..3];
+            let max = channels.iter().max().copied().unwrap_or(0);
+            let min = channels.iter().min().copied().unwrap_or(0);
+            saturation += f32::from(max - min) / 255.0;
+        }
+
+        let total = u64::from(image.width()) * u64::from(image.height());
+        let (black, white) = stretch_range(&histogram, total);
+
+        let mean_saturation = if total > 0 {
+            saturation / total as f32
+        } else {
+            0.0
+        };
+
+        Self {
+            black,
+            white,
+            saturation: saturation_boost(mean_saturation),
+        }
+    }
+
+    fn apply(&self, pixel: &mut [u8]) {
+        let scale = 255.0 / f32::from(self.white - self.black);
+        let stretch = |value: u8| (f32::from(value) - f32::from(self.black)) * scale;
+
+        let (r, g, b) = (stretch(pixel[0]), stretch(pixel[1]), stretch(pixel[2]));
+        let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+
+        for (sample, value) in pixel.iter_mut().zip([r, g, b].iter()) {
+            let saturated = luminance + (value - luminance) * self.saturation;
+            *sample = saturated.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// The luminance values at which the darkest and the brightest `CLIP_FRACTION` of the pixels
+/// start. If the image already has a (nearly) full range, the range is left as is.
+fn stretch_range(histogram: &[u64; 256], total: u64) -> (u8, u8) {
+    let clipped = (total as f64 * CLIP_FRACTION) as u64;
+
+    let mut seen = 0;
+    let black = histogram
+        .iter()
+        .position(|&count| {
+            seen += count;
+            seen > clipped
+        })
+        .unwrap_or(0);
+
+    let mut seen = 0;
+    let white = 255
+        - histogram
+            .iter()
+            .rev()
+            .position(|&count| {
+                seen += count;
+                seen > clipped
+            })
+            .unwrap_or(0);
+
+    if white <= black || white - black >= usize::from(FULL_RANGE) {
+        (0, 255)
+    } else {
+        (black as u8, white as u8)
+    }
+}
+
+/// Dull images get a stronger saturation boost than colorful ones; gray images stay gray, since
+/// their saturation is zero regardless.
+fn saturation_boost(mean_saturation: f32) -> f32 {
+    let missing = (TARGET_SATURATION - mean_saturation).max(0.0) / TARGET_SATURATION;
+
+    1.0 + missing * (MAX_SATURATION_BOOST - 1.0)
+}
+
+/// Improves an image in a single step, like the "enhance" button of a photo gallery:
+///
+/// 1. the contrast is stretched, so the luminance spans the full range;
+/// 2. the saturation is boosted mildly, more so for dull images;
+/// 3. the image is sharpened slightly.
+///
+/// The contrast stretch and saturation boost are derived from the histograms of the image.
+/// The alpha channel is left untouched.
+pub(crate) fn auto_enhance(image: &DynamicImage) -> DynamicImage {
+    let mut buffer = image.to_rgba();
+    let enhancement = Enhancement::from_image(&buffer);
+
+    for pixel in buffer.pixels_mut() {
+        enhancement.apply(&mut pixel.0);
+    }
+
+    let mut sharpened = imageops::unsharpen(&buffer, SHARPEN_SIGMA, SHARPEN_THRESHOLD);
+    for (sharp, original) in sharpened.pixels_mut().zip(buffer.pixels()) {
+        sharp[3] = original[3];
+    }
+
+    let enhanced = DynamicImage::ImageRgba8(sharpened);
+
+    match image {
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLuma16(_) => {
+            DynamicImage::ImageLuma8(enhanced.to_luma())
+        }
+        DynamicImage::ImageLumaA8(_) | DynamicImage::ImageLumaA16(_) => {
+            DynamicImage::ImageLumaA8(enhanced.to_luma_alpha())
+        }
+        DynamicImage::ImageRgb8(_) | DynamicImage::ImageBgr8(_) | DynamicImage::ImageRgb16(_) => {
+            DynamicImage::ImageRgb8(enhanced.to_rgb())
+        }
+        _ => enhanced,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, GrayImage, Luma, Rgb, RgbImage, Rgba};
+    use sic_testing::*;
+
+    ide!();
+
+    fn luminance_range(image: &DynamicImage) -> (u8, u8) {
+        let gray = image.to_luma();
+        let min = gray.pixels().map(|p| p[0]).min().unwrap();
+        let max = gray.pixels().map(|p| p[0]).max().unwrap();
+        (min, max)
+    }
+
+    #[test]
+    fn stretches_low_contrast() {
+        let image = open_test_image(setup_test_image("equalize_8x8_low_contrast.png"));
+        let (min, max) = luminance_range(&image);
+
+        let out = auto_enhance(&image);
+        let (out_min, out_max) = luminance_range(&out);
+
+        assert!(out_min < min);
+        assert!(out_max > max);
+        assert!(out_max - out_min > 200);
+    }
+
+    #[test]
+    fn full_range_is_not_stretched() {
+        let mut histogram = [0u64; 256];
+        histogram[0] = 10;
+        histogram[128] = 80;
+        histogram[255] = 10;
+
+        assert_eq!(stretch_range(&histogram, 100), (0, 255));
+    }
+
+    #[test]
+    fn outliers_are_clipped() {
+        let mut histogram = [0u64; 256];
+        histogram[0] = 1;
+        histogram[100] = 500;
+        histogram[150] = 498;
+        histogram[255] = 1;
+
+        assert_eq!(stretch_range(&histogram, 1000), (100, 150));
+    }
+
+    #[test]
+    fn single_value() {
+        let mut histogram = [0u64; 256];
+        histogram[77] = 64;
+
+        assert_eq!(stretch_range(&histogram, 64), (0, 255));
+    }
+
+    #[pm(
+        mean = { 0.0, 0.175, 0.35, 0.8 },
+        expected = { 1.3, 1.15, 1.0, 1.0 }
+    )]
+    fn saturation(mean: f32, expected: f32) {
+        approx_eq_f32!(saturation_boost(mean), expected);
+    }
+
+    #[test]
+    fn gray_stays_gray() {
+        let gradient = GrayImage::from_fn(16, 4, |x, _| Luma([(100 + x * 3) as u8]));
+
+        let out = auto_enhance(&DynamicImage::ImageLuma8(gradient));
+
+        assert!(out.as_luma8().is_some());
+    }
+
+    #[test]
+    fn dull_image_gets_saturation_boost() {
+        let image = RgbaImage::from_fn(8, 8, |x, _| {
+            let value = (x * 30) as u8;
+            Rgba([value + 10, value, value, 255])
+        });
+
+        let enhancement = Enhancement::from_image(&image);
+
+        assert!(enhancement.saturation > 1.2);
+    }
+
+    #[test]
+    fn apply_saturates() {
+        let enhancement = Enhancement {
+            black: 0,
+            white: 255,
+            saturation: 1.3,
+        };
+
+        let mut pixel = [120, 100, 100, 255];
+        enhancement.apply(&mut pixel);
+
+        assert_eq!(pixel, [125, 99, 99, 255]);
+    }
+
+    #[test]
+    fn apply_stretches() {
+        let enhancement = Enhancement {
+            black: 50,
+            white: 200,
+            saturation: 1.0,
+        };
+
+        let mut pixel = [50, 110, 200, 7];
+        enhancement.apply(&mut pixel);
+
+        assert_eq!(pixel, [0, 102, 255, 7]);
+    }
+
+    #[test]
+    fn keeps_color_type() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, Rgb([10, 200, 30])));
+
+        assert!(auto_enhance(&image).as_rgb8().is_some());
+    }
+
+    #[test]
+    fn alpha_is_kept() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(6, 6, |x, y| {
+            Rgba([(x * 20) as u8, (y * 20) as u8, 90, (x * 40 + y) as u8])
+        }));
+
+        let out = auto_enhance(&image);
+
+        let alpha = |img: &DynamicImage| img.to_rgba().pixels().map(|p| p[3]).collect::<Vec<_>>();
+        assert_eq!(alpha(&out), alpha(&image));
+        assert_eq!(out.dimensions(), image.dimensions());
+    }
+}
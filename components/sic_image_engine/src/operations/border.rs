@@ -0,0 +1,58 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::canvas::place_on_canvas;
+use crate::wrapper::border::BorderInputs;
+use sic_core::image::{DynamicImage, GenericImageView};
+
+/// Adds a solid border around the image; the canvas grows by the widths of the border.
+pub(crate) fn border(
+    image: &DynamicImage,
+    border: &BorderInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+
+    let grow = |size: u32, a: u32, b: u32| {
+        size.checked_add(a)
+            .and_then(|size| size.checked_add(b))
+            .ok_or(SicImageEngineError::BorderTooLarge)
+    };
+
+    let width = grow(width, border.left(), border.right())?;
+    let height = grow(height, border.top(), border.bottom())?;
+
+    Ok(place_on_canvas(
+        image,
+        (width, height),
+        (border.left(), border.top()),
+        border.color(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage, Rgba};
+
+    #[test]
+    fn grows_canvas_per_side() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([255, 255, 255])));
+        let inputs = BorderInputs::new(&[1, 2, 3, 4], Rgba([0, 0, 0, 255])).unwrap();
+
+        let out = border(&image, &inputs).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (2 + 2 + 4, 2 + 1 + 3));
+        assert_eq!(out.get_pixel(4, 1), &Rgb([255, 255, 255]));
+        assert_eq!(out.get_pixel(5, 2), &Rgb([255, 255, 255]));
+        assert_eq!(out.get_pixel(3, 1), &Rgb([0, 0, 0]));
+        assert_eq!(out.get_pixel(4, 0), &Rgb([0, 0, 0]));
+        assert_eq!(out.get_pixel(6, 3), &Rgb([0, 0, 0]));
+    }
+
+    #[test]
+    fn too_large() {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(2, 2));
+        let inputs = BorderInputs::new(&[u32::MAX], Rgba([0, 0, 0, 255])).unwrap();
+
+        assert!(border(&image, &inputs).is_err());
+    }
+}
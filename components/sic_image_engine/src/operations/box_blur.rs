@@ -0,0 +1,113 @@
+use crate::operations::summed_area_table::{SummedAreaTable, Window};
+use sic_core::image::{DynamicImage, ImageBuffer, Pixel};
+
+/// Blurs an image by replacing each pixel with the mean of the `2 * radius + 1` by
+/// `2 * radius + 1` pixels around it. Near the edges of the image, only the pixels within
+/// the bounds of the image are used.
+///
+/// The means are computed using summed-area tables, so the time it takes to blur an image does not
+/// depend on the radius. Images with 16-bit samples are converted to 8-bit samples.
+pub(crate) fn box_blur(image: &DynamicImage, radius: u32) -> DynamicImage {
+    match image {
+        DynamicImage::ImageLuma8(buffer) => DynamicImage::ImageLuma8(blur_buffer(buffer, radius)),
+        DynamicImage::ImageLumaA8(buffer) => DynamicImage::ImageLumaA8(blur_buffer(buffer, radius)),
+        DynamicImage::ImageRgb8(buffer) => DynamicImage::ImageRgb8(blur_buffer(buffer, radius)),
+        DynamicImage::ImageRgba8(buffer) => DynamicImage::ImageRgba8(blur_buffer(buffer, radius)),
+        _ => DynamicImage::ImageRgba8(blur_buffer(&image.to_rgba(), radius)),
+    }
+}
+
+/// The radius of the box blur with about the same spread as a Gaussian blur with the given sigma.
+/// The variance of a box of `2 * radius + 1` pixels is `radius * (radius + 1) / 3`, which is
+/// solved for the radius. Like the Gaussian blur, a sigma of 0 or less is treated as 1.
+pub(crate) fn radius_for_sigma(sigma: f32) -> u32 {
+    let sigma = if sigma <= 0.0 { 1.0 } else { sigma };
+
+    (((1.0 + 12.0 * sigma * sigma).sqrt() - 1.0) / 2.0).round() as u32
+}
+
+fn blur_buffer<P>(buffer: &ImageBuffer<P, Vec<u8>>, radius: u32) -> ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8> + 'static,
+{
+    let (width, height) = buffer.dimensions();
+
+    let tables = (0..usize::from(P::CHANNEL_COUNT))
+        .map(|channel| {
+            SummedAreaTable::from_fn(width, height, |x, y| {
+                u64::from(buffer.get_pixel(x, y).channels()[channel])
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut out = ImageBuffer::<P, Vec<u8>>::new(width, height);
+
+    for (x, y, pixel) in out.enumerate_pixels_mut() {
+        let window = Window::around(x, y, radius, width, height);
+        let area = window.area();
+
+        for (sample, table) in pixel.channels_mut().iter_mut().zip(&tables) {
+            *sample = ((table.sum(&window) + area / 2) / area) as u8;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma, Rgba, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(sigma = { -1.0, 0.0, 0.25, 1.0, 3.0, 10.0 }, expected = { 1, 1, 0, 1, 5, 17 })]
+    fn radius_for_sigma_matches_variance(sigma: f32, expected: u32) {
+        assert_eq!(radius_for_sigma(sigma), expected);
+    }
+
+    #[test]
+    fn radius_zero_is_identity() {
+        let image = open_test_image(setup_test_image("rainbow_8x6.bmp"));
+
+        let out = box_blur(&image, 0);
+
+        assert_eq!(out.to_bytes(), image.to_bytes());
+    }
+
+    #[test]
+    fn averages_neighbourhood() {
+        // a single non-black pixel in the center
+        let image = DynamicImage::ImageLuma8(GrayImage::from_fn(3, 3, |x, y| {
+            if (x, y) == (1, 1) {
+                Luma([90])
+            } else {
+                Luma([0])
+            }
+        }));
+
+        let out = box_blur(&image, 1);
+        let out = out.as_luma8().unwrap();
+
+        assert_eq!(out.get_pixel(1, 1)[0], 10);
+        // the corners only have 4 pixels within their window
+        assert_eq!(out.get_pixel(0, 0)[0], 23);
+    }
+
+    #[test]
+    fn large_radius_averages_everything() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(4, 4, |x, _| {
+            if x < 2 {
+                Rgba([0, 100, 200, 255])
+            } else {
+                Rgba([200, 100, 0, 55])
+            }
+        }));
+
+        let out = box_blur(&image, 1000);
+        let out = out.as_rgba8().unwrap();
+
+        assert!(out.pixels().all(|px| px == &Rgba([100, 100, 100, 155])));
+    }
+}
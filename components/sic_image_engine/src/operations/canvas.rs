@@ -0,0 +1,54 @@
+use sic_core::image::{imageops, DynamicImage, Rgba, RgbaImage};
+
+/// Places the image on a canvas of `width` by `height` pixels filled with `color`, with its top
+/// left corner at `(x, y)`. Parts of the image which fall outside of the canvas are cut off.
+///
+/// The result has an alpha channel if the image has one, or if the canvas color is not opaque.
+pub(crate) fn place_on_canvas(
+    image: &DynamicImage,
+    (width, height): (u32, u32),
+    (x, y): (u32, u32),
+    color: Rgba<u8>,
+) -> DynamicImage {
+    let mut canvas = RgbaImage::from_pixel(width, height, color);
+    imageops::replace(&mut canvas, &image.to_rgba(), x, y);
+
+    let canvas = DynamicImage::ImageRgba8(canvas);
+
+    if image.color().has_alpha() || color[3] != u8::MAX {
+        canvas
+    } else {
+        DynamicImage::ImageRgb8(canvas.to_rgb())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Rgb, RgbImage};
+
+    #[test]
+    fn places_image_at_offset() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 1, Rgb([255, 0, 0])));
+
+        let out = place_on_canvas(&image, (4, 3), (1, 2), Rgba([0, 0, 255, 255]));
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (4, 3));
+        assert_eq!(out.get_pixel(0, 2), &Rgb([0, 0, 255]));
+        assert_eq!(out.get_pixel(1, 2), &Rgb([255, 0, 0]));
+        assert_eq!(out.get_pixel(2, 2), &Rgb([255, 0, 0]));
+        assert_eq!(out.get_pixel(1, 1), &Rgb([0, 0, 255]));
+    }
+
+    #[test]
+    fn transparent_canvas_adds_alpha() {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(1, 1));
+
+        let out = place_on_canvas(&image, (3, 3), (1, 1), Rgba([0, 0, 0, 0]));
+
+        assert!(out.color().has_alpha());
+        assert_eq!(out.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(out.get_pixel(1, 1), Rgba([0, 0, 0, 255]));
+    }
+}
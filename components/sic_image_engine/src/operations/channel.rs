@@ -0,0 +1,122 @@
+use crate::wrapper::channel::Channel;
+use sic_core::image::{DynamicImage, GrayImage, ImageBuffer, Luma, Rgb};
+
+/// Swaps two channels of the image, e.g. to fix an image of which the red and blue channels were
+/// mixed up (BGR instead of RGB).
+///
+/// The result has an alpha channel if the image has one, or if the alpha channel is swapped.
+pub(crate) fn channel_swap(image: &DynamicImage, lhs: Channel, rhs: Channel) -> DynamicImage {
+    let mut buffer = image.to_rgba();
+
+    for pixel in buffer.pixels_mut() {
+        pixel.0.swap(lhs.index(), rhs.index());
+    }
+
+    if has_alpha(image) || lhs == Channel::Alpha || rhs == Channel::Alpha {
+        DynamicImage::ImageRgba8(buffer)
+    } else {
+        let (width, height) = buffer.dimensions();
+
+        DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+            let pixel = buffer.get_pixel(x, y);
+            Rgb([pixel[0], pixel[1], pixel[2]])
+        }))
+    }
+}
+
+/// Creates a grayscale image from a single channel of the image. Extracting the alpha channel of
+/// an image without one results in a white image.
+pub(crate) fn channel_extract(image: &DynamicImage, channel: Channel) -> DynamicImage {
+    let buffer = image.to_rgba();
+    let (width, height) = buffer.dimensions();
+
+    DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+        Luma([buffer.get_pixel(x, y)[channel.index()]])
+    }))
+}
+
+fn has_alpha(image: &DynamicImage) -> bool {
+    matches!(
+        image,
+        DynamicImage::ImageLumaA8(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgba8(_)
+            | DynamicImage::ImageRgba16(_)
+            | DynamicImage::ImageBgra8(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, RgbImage, Rgba, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    const PIXEL: Rgba<u8> = Rgba([10, 20, 30, 40]);
+
+    fn rgba() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, PIXEL))
+    }
+
+    #[pm(
+        lhs = { Channel::Red, Channel::Blue, Channel::Green, Channel::Red },
+        rhs = { Channel::Blue, Channel::Red, Channel::Alpha, Channel::Red },
+        expected = { [30, 20, 10, 40], [30, 20, 10, 40], [10, 40, 30, 20], [10, 20, 30, 40] }
+    )]
+    fn swap(lhs: Channel, rhs: Channel, expected: [u8; 4]) {
+        let out = channel_swap(&rgba(), lhs, rhs);
+
+        assert_eq!(out.get_pixel(0, 0), Rgba(expected));
+    }
+
+    #[test]
+    fn swap_keeps_rgb() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb([10, 20, 30])));
+        let out = channel_swap(&image, Channel::Red, Channel::Blue);
+
+        assert!(matches!(out, DynamicImage::ImageRgb8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([30, 20, 10, 255]));
+    }
+
+    #[test]
+    fn swap_with_alpha_of_rgb() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb([10, 20, 30])));
+        let out = channel_swap(&image, Channel::Alpha, Channel::Green);
+
+        assert!(matches!(out, DynamicImage::ImageRgba8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 255, 30, 20]));
+    }
+
+    #[test]
+    fn swap_twice() {
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+        let once = channel_swap(&image, Channel::Red, Channel::Green);
+        let twice = channel_swap(&once, Channel::Green, Channel::Red);
+
+        assert_eq!(twice.to_rgb(), image.to_rgb());
+    }
+
+    #[pm(
+        channel = { Channel::Red, Channel::Green, Channel::Blue, Channel::Alpha },
+        expected = { 10, 20, 30, 40 }
+    )]
+    fn extract(channel: Channel, expected: u8) {
+        let out = channel_extract(&rgba(), channel);
+
+        assert!(matches!(out, DynamicImage::ImageLuma8(_)));
+        assert_eq!(
+            out.get_pixel(0, 0),
+            Rgba([expected, expected, expected, 255])
+        );
+    }
+
+    #[test]
+    fn extract_alpha_of_rgb() {
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+        let out = channel_extract(&image, Channel::Alpha);
+
+        assert!(out.to_luma().pixels().all(|pixel| pixel[0] == 255));
+    }
+}
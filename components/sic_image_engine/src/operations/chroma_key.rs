@@ -0,0 +1,146 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::chroma_key::ChromaKeyInputs;
+use sic_core::image::{DynamicImage, Rgba};
+
+/// Makes the pixels which are close to the key color transparent, e.g. to remove the green
+/// screen background of a photo.
+///
+/// The closeness of a pixel is its euclidean distance to the key color in RGB space, which is at
+/// most 441 (from black to white). Pixels within `tolerance` of the key color become fully
+/// transparent. Pixels up to `feather` further away become partially transparent, which gives the
+/// remaining subject soft edges. The result always has an alpha channel.
+pub(crate) fn chroma_key(
+    image: &DynamicImage,
+    inputs: &ChromaKeyInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (tolerance, feather) = (inputs.tolerance(), inputs.feather());
+
+    if tolerance.is_nan() || tolerance < 0.0 {
+        return Err(SicImageEngineError::ChromaKeyInvalidTolerance(tolerance));
+    }
+
+    if feather.is_nan() || feather < 0.0 {
+        return Err(SicImageEngineError::ChromaKeyInvalidFeather(feather));
+    }
+
+    let key = inputs.color();
+    let mut buffer = image.to_rgba();
+
+    for pixel in buffer.pixels_mut() {
+        let opacity = opacity(distance(pixel, &key), tolerance, feather);
+        pixel[3] = (f32::from(pixel[3]) * opacity).round() as u8;
+    }
+
+    Ok(DynamicImage::ImageRgba8(buffer))
+}
+
+fn distance(pixel: &Rgba<u8>, key: &Rgba<u8>) -> f32 {
+    (0..3)
+        .map(|channel| (f32::from(pixel[channel]) - f32::from(key[channel])).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// The fraction of the original opacity which a pixel at the given distance from the key color
+/// keeps.
+fn opacity(distance: f32, tolerance: f32, feather: f32) -> f32 {
+    if distance <= tolerance {
+        0.0
+    } else if distance >= tolerance + feather {
+        1.0
+    } else {
+        (distance - tolerance) / feather
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Rgb, RgbImage};
+    use sic_testing::*;
+
+    ide!();
+
+    const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
+
+    fn green_screen() -> DynamicImage {
+        // a red subject on a green background, with a slightly darker green shade on the right
+        DynamicImage::ImageRgb8(RgbImage::from_fn(6, 2, |x, _| match x {
+            0 | 1 => Rgb([0, 255, 0]),
+            2 | 3 => Rgb([220, 30, 40]),
+            4 => Rgb([0, 235, 0]),
+            _ => Rgb([0, 215, 0]),
+        }))
+    }
+
+    #[test]
+    fn background_becomes_transparent() {
+        let inputs = ChromaKeyInputs::new(GREEN, 10.0, 0.0);
+
+        let out = chroma_key(&green_screen(), &inputs).unwrap();
+
+        assert_eq!(out.get_pixel(0, 0), Rgba([0, 255, 0, 0]));
+        assert_eq!(out.get_pixel(2, 1), Rgba([220, 30, 40, 255]));
+        // the darker shades are further away than the tolerance
+        assert_eq!(out.get_pixel(4, 0)[3], 255);
+        assert_eq!(out.get_pixel(5, 0)[3], 255);
+    }
+
+    #[test]
+    fn tolerance_includes_shades() {
+        let inputs = ChromaKeyInputs::new(GREEN, 45.0, 0.0);
+
+        let out = chroma_key(&green_screen(), &inputs).unwrap();
+
+        assert_eq!(out.get_pixel(4, 0)[3], 0);
+        assert_eq!(out.get_pixel(5, 0)[3], 0);
+        assert_eq!(out.get_pixel(3, 0)[3], 255);
+    }
+
+    #[test]
+    fn feather_gives_soft_edges() {
+        let inputs = ChromaKeyInputs::new(GREEN, 0.0, 40.0);
+
+        let out = chroma_key(&green_screen(), &inputs).unwrap();
+
+        assert_eq!(out.get_pixel(0, 0)[3], 0);
+        assert_eq!(out.get_pixel(4, 0)[3], 128);
+        assert_eq!(out.get_pixel(5, 0)[3], 255);
+    }
+
+    #[test]
+    fn existing_transparency_is_kept() {
+        let image = DynamicImage::ImageRgba8(sic_core::image::RgbaImage::from_pixel(
+            1,
+            1,
+            Rgba([255, 0, 0, 100]),
+        ));
+        let inputs = ChromaKeyInputs::new(GREEN, 10.0, 10.0);
+
+        let out = chroma_key(&image, &inputs).unwrap();
+
+        assert_eq!(out.get_pixel(0, 0), Rgba([255, 0, 0, 100]));
+    }
+
+    #[pm(
+        distance = { 0.0, 10.0, 15.0, 20.0, 30.0 },
+        expected = { 0.0, 0.0, 0.5, 1.0, 1.0 }
+    )]
+    fn opacity_ramp(distance: f32, expected: f32) {
+        approx_eq_f32!(opacity(distance, 10.0, 10.0), expected);
+    }
+
+    #[pm(tolerance = { -1.0, f32::NAN }, feather = { 0.0, 0.0 })]
+    fn invalid_tolerance(tolerance: f32, feather: f32) {
+        let inputs = ChromaKeyInputs::new(GREEN, tolerance, feather);
+
+        assert!(chroma_key(&green_screen(), &inputs).is_err());
+    }
+
+    #[test]
+    fn invalid_feather() {
+        let inputs = ChromaKeyInputs::new(GREEN, 10.0, -5.0);
+
+        assert!(chroma_key(&green_screen(), &inputs).is_err());
+    }
+}
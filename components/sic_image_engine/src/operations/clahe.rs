@@ -0,0 +1,225 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::luminance::map_luminance;
+use sic_core::image::{DynamicImage, GrayImage, Luma};
+
+/// Contrast limited adaptive histogram equalization (CLAHE) of the luminance of an image.
+///
+/// The image is divided into tiles of `tile_size` by `tile_size` pixels, and the histogram of
+/// each tile is equalized separately. To keep the noise in flat areas from being amplified,
+/// the bins of the histogram of each tile are clipped to `clip_limit` times the average bin size;
+/// the clipped counts are redistributed over all bins. Each pixel is mapped by interpolating
+/// between the mappings of the four nearest tiles, so no tile borders are visible.
+pub(crate) fn clahe(
+    image: &DynamicImage,
+    tile_size: u32,
+    clip_limit: f32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if tile_size == 0 {
+        return Err(SicImageEngineError::ClaheInvalidTileSize);
+    }
+
+    if !clip_limit.is_finite() || clip_limit <= 0.0 {
+        return Err(SicImageEngineError::ClaheInvalidClipLimit(clip_limit));
+    }
+
+    Ok(map_luminance(image, |luminance| {
+        equalize_tiles(luminance, tile_size, clip_limit)
+    }))
+}
+
+fn equalize_tiles(image: &GrayImage, tile_size: u32, clip_limit: f32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let columns = width.div_ceil(tile_size);
+    let rows = height.div_ceil(tile_size);
+
+    let tables = (0..rows)
+        .flat_map(|row| {
+            (0..columns).map(move |column| {
+                tile_table(
+                    image,
+                    column * tile_size,
+                    row * tile_size,
+                    tile_size,
+                    clip_limit,
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let value = usize::from(image.get_pixel(x, y)[0]);
+        let (left, right, weight_x) = neighbouring_tiles(x, tile_size, columns);
+        let (top, bottom, weight_y) = neighbouring_tiles(y, tile_size, rows);
+
+        let mapped =
+            |row: u32, column: u32| f32::from(tables[(row * columns + column) as usize][value]);
+
+        let upper = mapped(top, left) * (1.0 - weight_x) + mapped(top, right) * weight_x;
+        let lower = mapped(bottom, left) * (1.0 - weight_x) + mapped(bottom, right) * weight_x;
+
+        Luma([(upper * (1.0 - weight_y) + lower * weight_y).round() as u8])
+    })
+}
+
+/// The two tiles (along a single axis) whose centers surround the given position, and the weight
+/// of the second tile. Positions before the center of the first tile, or after the center of the
+/// last tile, only use that tile.
+fn neighbouring_tiles(position: u32, tile_size: u32, tiles: u32) -> (u32, u32, f32) {
+    let relative = (position as f32 + 0.5) / tile_size as f32 - 0.5;
+
+    if relative <= 0.0 {
+        return (0, 0, 0.0);
+    }
+
+    let first = (relative.floor() as u32).min(tiles - 1);
+    let second = (first + 1).min(tiles - 1);
+
+    (first, second, relative - first as f32)
+}
+
+/// The equalization mapping of the tile with the given top-left corner, based on its
+/// clipped histogram.
+fn tile_table(
+    image: &GrayImage,
+    left: u32,
+    top: u32,
+    tile_size: u32,
+    clip_limit: f32,
+) -> [u8; 256] {
+    let right = (left + tile_size).min(image.width());
+    let bottom = (top + tile_size).min(image.height());
+    let area = (right - left) * (bottom - top);
+
+    let mut histogram = [0u32; 256];
+    for y in top..bottom {
+        for x in left..right {
+            histogram[usize::from(image.get_pixel(x, y)[0])] += 1;
+        }
+    }
+
+    let limit = ((clip_limit * area as f32 / 256.0) as u32).max(1);
+    clip_histogram(&mut histogram, limit);
+
+    let mut table = [0u8; 256];
+    let mut cumulative = 0;
+
+    for (value, count) in histogram.iter().enumerate() {
+        cumulative += count;
+        table[value] = (cumulative as f32 * 255.0 / area as f32).round().min(255.0) as u8;
+    }
+
+    table
+}
+
+/// Clips each bin to the limit, and redistributes the clipped counts evenly over all bins.
+fn clip_histogram(histogram: &mut [u32; 256], limit: u32) {
+    let mut excess = 0;
+
+    for count in histogram.iter_mut() {
+        if *count > limit {
+            excess += *count - limit;
+            *count = limit;
+        }
+    }
+
+    let increment = excess / 256;
+    let remainder = (excess % 256) as usize;
+
+    for count in histogram.iter_mut() {
+        *count += increment;
+    }
+
+    if let Some(step) = 256usize.checked_div(remainder) {
+        for count in histogram.iter_mut().step_by(step).take(remainder) {
+            *count += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    // A dark and a bright half, both with very little contrast, like the ground and the sky
+    // on a photo taken from above.
+    fn ground_and_sky() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(32, 16, |x, y| {
+            let variation = ((x * 7 + y * 3) % 8) as u8;
+
+            if x < 16 {
+                Luma([30 + variation])
+            } else {
+                Luma([210 + variation])
+            }
+        }))
+    }
+
+    fn range(image: &GrayImage, xs: std::ops::Range<u32>) -> u8 {
+        let values = xs
+            .flat_map(|x| (0..image.height()).map(move |y| (x, y)))
+            .map(|(x, y)| image.get_pixel(x, y)[0])
+            .collect::<Vec<_>>();
+
+        values.iter().max().unwrap() - values.iter().min().unwrap()
+    }
+
+    #[test]
+    fn enhances_local_contrast() {
+        let out = clahe(&ground_and_sky(), 16, 4.0).unwrap();
+        let out = out.as_luma8().unwrap();
+
+        // away from the border between the halves, where the tiles are interpolated
+        assert!(range(out, 0..4) > 7 * 2);
+        assert!(range(out, 28..32) > 7 * 2);
+    }
+
+    #[test]
+    fn does_not_blow_out_bright_areas() {
+        let out = clahe(&ground_and_sky(), 16, 2.0).unwrap();
+        let out = out.as_luma8().unwrap();
+
+        assert!(out.get_pixel(0, 0)[0] < out.get_pixel(31, 0)[0]);
+        assert!((28..32).all(|x| out.get_pixel(x, 5)[0] < 255));
+    }
+
+    #[test]
+    fn higher_clip_limit_allows_more_contrast() {
+        let low = clahe(&ground_and_sky(), 16, 1.0).unwrap();
+        let high = clahe(&ground_and_sky(), 16, 4.0).unwrap();
+
+        assert!(range(low.as_luma8().unwrap(), 0..4) < range(high.as_luma8().unwrap(), 0..4));
+    }
+
+    #[test]
+    fn clipped_histogram_keeps_total() {
+        let mut histogram = [0u32; 256];
+        histogram[10] = 1000;
+        histogram[20] = 24;
+
+        clip_histogram(&mut histogram, 8);
+
+        assert_eq!(histogram.iter().sum::<u32>(), 1024);
+        assert!(histogram.iter().all(|&count| count <= 8 + 4));
+    }
+
+    #[pm(position = { 0, 3, 4, 8, 12, 15 }, expected = { (0, 0), (0, 0), (0, 1), (0, 1), (1, 1), (1, 1) })]
+    fn neighbours(position: u32, expected: (u32, u32)) {
+        let (first, second, weight) = neighbouring_tiles(position, 8, 2);
+
+        assert_eq!((first, second), expected);
+        assert!((0.0..=1.0).contains(&weight));
+    }
+
+    #[test]
+    fn invalid_tile_size() {
+        assert!(clahe(&ground_and_sky(), 0, 2.0).is_err());
+    }
+
+    #[pm(clip_limit = { 0.0, -1.0, f32::NAN })]
+    fn invalid_clip_limit(clip_limit: f32) {
+        assert!(clahe(&ground_and_sky(), 8, clip_limit).is_err());
+    }
+}
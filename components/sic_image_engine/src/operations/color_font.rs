@@ -0,0 +1,272 @@
+use owned_ttf_parser::AsFontRef;
+use rusttype::{Font, GlyphId};
+use sic_core::image::{self, ImageFormat, Rgba, RgbaImage};
+use std::collections::HashMap;
+
+/// A layer of a color glyph: a glyph which is drawn in a color of its own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ColorLayer {
+    pub(crate) glyph: GlyphId,
+    /// `None` if the layer is drawn in the color of the text.
+    pub(crate) color: Option<Rgba<u8>>,
+}
+
+/// The color glyphs of a font with a COLR table, which draws each of them as a stack of layers.
+///
+/// Only the layers of version 0 of the table are read, with the colors of the first palette of the
+/// CPAL table.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ColorLayers {
+    glyphs: HashMap<u16, Vec<ColorLayer>>,
+}
+
+impl ColorLayers {
+    /// Reads the color layers from the data of a font file; `None` if the font has no (valid)
+    /// COLR table. The index selects the font of a font collection.
+    pub(crate) fn parse(data: &[u8], index: u32) -> Option<Self> {
+        let colr = find_table(data, index, b"COLR")?;
+        let palette = find_table(data, index, b"CPAL")
+            .and_then(parse_palette)
+            .unwrap_or_default();
+
+        let base_glyph_count = usize::from(read_u16(colr, 2)?);
+        let base_glyphs = read_u32(colr, 4)? as usize;
+        let layers = read_u32(colr, 8)? as usize;
+        let layer_count = usize::from(read_u16(colr, 12)?);
+
+        let mut glyphs = HashMap::with_capacity(base_glyph_count);
+
+        for record in (0..base_glyph_count).map(|i| base_glyphs + 6 * i) {
+            let glyph = read_u16(colr, record)?;
+            let first = usize::from(read_u16(colr, record + 2)?);
+            let count = usize::from(read_u16(colr, record + 4)?);
+
+            if first + count > layer_count {
+                return None;
+            }
+
+            let stack = (first..first + count)
+                .map(|layer| {
+                    let record = layers + 4 * layer;
+                    let palette_index = read_u16(colr, record + 2)?;
+
+                    Some(ColorLayer {
+                        glyph: GlyphId(read_u16(colr, record)?),
+                        // an index of 0xFFFF, and any other index outside of the palette,
+                        // selects the color of the text
+                        color: palette.get(usize::from(palette_index)).copied(),
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            glyphs.insert(glyph, stack);
+        }
+
+        Some(Self { glyphs })
+    }
+
+    /// The layers of a color glyph, bottom layer first; `None` if the glyph isn't a color glyph.
+    pub(crate) fn layers(&self, glyph: GlyphId) -> Option<&[ColorLayer]> {
+        self.glyphs
+            .get(&glyph.0)
+            .map(Vec::as_slice)
+            .filter(|layers| !layers.is_empty())
+    }
+}
+
+/// A color bitmap of a glyph, e.g. of an emoji, as stored in the CBDT or sbix table of a font.
+pub(crate) struct GlyphBitmap {
+    pub(crate) image: RgbaImage,
+    /// Offset from the origin of the glyph to the bottom left corner of the bitmap, in pixels of
+    /// the bitmap, and upwards.
+    pub(crate) offset: (i16, i16),
+    /// The size of the font for which the bitmap was made.
+    pub(crate) pixels_per_em: u16,
+}
+
+/// Finds the color bitmap of a glyph, in the size closest to `pixels_per_em` (preferring a larger
+/// size), and decodes it. Only bitmaps stored as PNG are supported.
+pub(crate) fn glyph_bitmap(font: &Font, glyph: GlyphId, pixels_per_em: u16) -> Option<GlyphBitmap> {
+    let glyph = owned_ttf_parser::GlyphId(glyph.0);
+
+    let raster = match font {
+        Font::Ref(font) => font.glyph_raster_image(glyph, pixels_per_em),
+        Font::Owned(font) => font.as_font().glyph_raster_image(glyph, pixels_per_em),
+    }?;
+
+    let image = image::load_from_memory_with_format(raster.data, ImageFormat::Png).ok()?;
+
+    Some(GlyphBitmap {
+        image: image.to_rgba(),
+        offset: (raster.x, raster.y),
+        pixels_per_em: raster.pixels_per_em.max(1),
+    })
+}
+
+/// The colors of the first palette of a CPAL table.
+fn parse_palette(cpal: &[u8]) -> Option<Vec<Rgba<u8>>> {
+    let entries = usize::from(read_u16(cpal, 2)?);
+    let records = read_u32(cpal, 8)? as usize;
+    let first = usize::from(read_u16(cpal, 12)?);
+
+    (first..first + entries)
+        .map(|record| {
+            let bgra = cpal.get(records + 4 * record..records + 4 * record + 4)?;
+
+            Some(Rgba([bgra[2], bgra[1], bgra[0], bgra[3]]))
+        })
+        .collect()
+}
+
+/// Finds a table of a font by its tag. Font collections contain multiple fonts, of which the one
+/// at the index is used.
+fn find_table<'data>(data: &'data [u8], index: u32, tag: &[u8; 4]) -> Option<&'data [u8]> {
+    let font = if data.get(0..4)? == b"ttcf" {
+        if index >= read_u32(data, 8)? {
+            return None;
+        }
+
+        read_u32(data, 12 + 4 * index as usize)? as usize
+    } else {
+        0
+    };
+
+    let table_count = usize::from(read_u16(data, font + 4)?);
+
+    let record = (0..table_count)
+        .map(|i| font + 12 + 16 * i)
+        .find(|&record| data.get(record..record + 4) == Some(&tag[..]))?;
+
+    let offset = read_u32(data, record + 8)? as usize;
+    let length = read_u32(data, record + 12)? as usize;
+
+    data.get(offset..offset.checked_add(length)?)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    // A font consisting of only a COLR and CPAL table: glyph 5 is drawn as glyph 6 in the color
+    // of the text, below glyph 7 in red, and glyph 8 has no layers.
+    fn colr_font() -> Vec<u8> {
+        let colr: &[u8] = &[
+            0, 0, // version
+            0, 2, // number of base glyph records
+            0, 0, 0, 14, // offset of the base glyph records
+            0, 0, 0, 26, // offset of the layer records
+            0, 2, // number of layer records
+            0, 5, 0, 0, 0, 2, // glyph 5: layers 0 and 1
+            0, 8, 0, 2, 0, 0, // glyph 8: no layers
+            0, 6, 0xFF, 0xFF, // layer 0: glyph 6, text color
+            0, 7, 0, 0, // layer 1: glyph 7, palette entry 0
+        ];
+
+        let cpal: &[u8] = &[
+            0, 0, // version
+            0, 1, // number of palette entries
+            0, 1, // number of palettes
+            0, 1, // number of color records
+            0, 0, 0, 14, // offset of the color records
+            0, 0, // first color record of palette 0
+            0, 0, 255, 255, // red, as BGRA
+        ];
+
+        let directory_length = 12 + 2 * 16;
+
+        let mut font = vec![0, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0];
+        font.extend_from_slice(b"COLR");
+        font.extend_from_slice(&[0; 4]);
+        font.extend_from_slice(&(directory_length as u32).to_be_bytes());
+        font.extend_from_slice(&(colr.len() as u32).to_be_bytes());
+        font.extend_from_slice(b"CPAL");
+        font.extend_from_slice(&[0; 4]);
+        font.extend_from_slice(&((directory_length + colr.len()) as u32).to_be_bytes());
+        font.extend_from_slice(&(cpal.len() as u32).to_be_bytes());
+        font.extend_from_slice(colr);
+        font.extend_from_slice(cpal);
+        font
+    }
+
+    #[test]
+    fn parse_layers() {
+        let layers = ColorLayers::parse(&colr_font(), 0).unwrap();
+
+        assert_eq!(
+            layers.layers(GlyphId(5)).unwrap(),
+            &[
+                ColorLayer {
+                    glyph: GlyphId(6),
+                    color: None,
+                },
+                ColorLayer {
+                    glyph: GlyphId(7),
+                    color: Some(Rgba([255, 0, 0, 255])),
+                },
+            ]
+        );
+    }
+
+    #[pm(glyph = { 6, 8 })]
+    fn not_a_color_glyph(glyph: u16) {
+        let layers = ColorLayers::parse(&colr_font(), 0).unwrap();
+
+        assert!(layers.layers(GlyphId(glyph)).is_none());
+    }
+
+    #[test]
+    fn parse_in_font_collection() {
+        let font = colr_font();
+
+        // a collection of which the second font is the font above, which starts right after the
+        // header; the offsets of its tables are relative to the start of the collection
+        let mut collection = b"ttcf".to_vec();
+        collection.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 20]);
+        collection.extend_from_slice(&font);
+
+        for record in &[20 + 12, 20 + 12 + 16] {
+            let offset = read_u32(&collection, record + 8).unwrap() + 20;
+            collection[record + 8..record + 12].copy_from_slice(&offset.to_be_bytes());
+        }
+
+        assert!(ColorLayers::parse(&collection, 1).is_some());
+        assert!(ColorLayers::parse(&collection, 2).is_none());
+    }
+
+    #[test]
+    fn font_without_color_layers() {
+        let font = std::fs::read(in_!("font/Lato-Regular.ttf")).unwrap();
+
+        assert!(ColorLayers::parse(&font, 0).is_none());
+    }
+
+    #[test]
+    fn truncated_font() {
+        let font = colr_font();
+
+        // cut off in the middle of the COLR table
+        assert!(ColorLayers::parse(&font[..12 + 2 * 16 + 20], 0).is_none());
+    }
+
+    #[test]
+    fn font_without_color_bitmaps() {
+        let font =
+            Font::try_from_vec(std::fs::read(in_!("font/Lato-Regular.ttf")).unwrap()).unwrap();
+        let glyph = font.glyph('a').id();
+
+        assert!(glyph_bitmap(&font, glyph, 24).is_none());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::blend_mode::BlendMode;
+use sic_core::image::{DynamicImage, Rgba};
+
+/// Composites `source` over `image`, with the top left corner of `source` at `(x, y)`, which may
+/// lie outside of the image. Parts of `source` which fall outside of the image are cut off.
+///
+/// The colors are combined by the blend mode, and the result is alpha composited (source-over)
+/// over the image, where the alpha channel of `source` is multiplied by `opacity`, which should be
+/// between 0 and 1. The result has an alpha channel if the image has one. Images with 16-bit
+/// samples are converted to 8-bit samples.
+pub(crate) fn composite(
+    image: &DynamicImage,
+    source: &DynamicImage,
+    (x, y): (i64, i64),
+    opacity: f32,
+    blend_mode: BlendMode,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if !(0.0..=1.0).contains(&opacity) {
+        return Err(SicImageEngineError::OverlayInvalidOpacity(opacity));
+    }
+
+    let mut out = image.to_rgba();
+    let source = source.to_rgba();
+    let (width, height) = out.dimensions();
+
+    for (sx, sy, pixel) in source.enumerate_pixels() {
+        let (tx, ty) = (x + i64::from(sx), y + i64::from(sy));
+
+        if tx < 0 || ty < 0 || tx >= i64::from(width) || ty >= i64::from(height) {
+            continue;
+        }
+
+        let backdrop = out.get_pixel_mut(tx as u32, ty as u32);
+        *backdrop = blend_pixel(*backdrop, *pixel, opacity, blend_mode);
+    }
+
+    let out = DynamicImage::ImageRgba8(out);
+
+    if image.color().has_alpha() {
+        Ok(out)
+    } else {
+        Ok(DynamicImage::ImageRgb8(out.to_rgb()))
+    }
+}
+
+/// The source-over compositing of a blended pixel, as given by the W3C "Compositing and Blending"
+/// specification: where the backdrop is transparent, the source color is used as is.
+fn blend_pixel(backdrop: Rgba<u8>, source: Rgba<u8>, opacity: f32, mode: BlendMode) -> Rgba<u8> {
+    let normalize = |v: u8| f32::from(v) / 255.0;
+
+    let alpha_b = normalize(backdrop[3]);
+    let alpha_s = normalize(source[3]) * opacity;
+    let alpha_o = alpha_s + alpha_b * (1.0 - alpha_s);
+
+    if alpha_o == 0.0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+
+    let mut out = Rgba([0, 0, 0, (alpha_o * 255.0).round() as u8]);
+
+    for c in 0..3 {
+        let (cb, cs) = (normalize(backdrop[c]), normalize(source[c]));
+        let blended = (1.0 - alpha_b) * cs + alpha_b * mode.blend(cb, cs);
+        let color = (alpha_s * blended + alpha_b * (1.0 - alpha_s) * cb) / alpha_o;
+
+        out[c] = (color * 255.0).round() as u8;
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Rgb, RgbImage, RgbaImage};
+
+    fn gray(value: u8) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([value, value, value])))
+    }
+
+    #[test]
+    fn normal_covers_image() {
+        let out = composite(&gray(200), &gray(50), (1, 0), 1.0, BlendMode::Normal).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.get_pixel(0, 0), &Rgb([200, 200, 200]));
+        assert_eq!(out.get_pixel(1, 0), &Rgb([50, 50, 50]));
+        assert_eq!(out.get_pixel(1, 1), &Rgb([50, 50, 50]));
+    }
+
+    #[test]
+    fn opacity_mixes_colors() {
+        let out = composite(&gray(200), &gray(0), (0, 0), 0.5, BlendMode::Normal).unwrap();
+
+        assert_eq!(
+            out.as_rgb8().unwrap().get_pixel(0, 0),
+            &Rgb([100, 100, 100])
+        );
+    }
+
+    #[test]
+    fn multiply() {
+        let out = composite(&gray(255), &gray(51), (0, 0), 1.0, BlendMode::Multiply).unwrap();
+
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(0, 0), &Rgb([51, 51, 51]));
+    }
+
+    #[test]
+    fn screen() {
+        let out = composite(&gray(0), &gray(51), (0, 0), 1.0, BlendMode::Screen).unwrap();
+
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(0, 0), &Rgb([51, 51, 51]));
+    }
+
+    #[test]
+    fn darken_and_lighten() {
+        let darken = composite(&gray(100), &gray(150), (0, 0), 1.0, BlendMode::Darken).unwrap();
+        let lighten = composite(&gray(100), &gray(150), (0, 0), 1.0, BlendMode::Lighten).unwrap();
+
+        assert_eq!(
+            darken.as_rgb8().unwrap().get_pixel(0, 0),
+            &Rgb([100, 100, 100])
+        );
+        assert_eq!(
+            lighten.as_rgb8().unwrap().get_pixel(0, 0),
+            &Rgb([150, 150, 150])
+        );
+    }
+
+    #[test]
+    fn blend_mode_ignored_on_transparent_backdrop() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(1, 1));
+        let source = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([80, 0, 0, 255])));
+
+        let out = composite(&image, &source, (0, 0), 1.0, BlendMode::Multiply).unwrap();
+
+        assert_eq!(out.get_pixel(0, 0), Rgba([80, 0, 0, 255]));
+    }
+
+    #[test]
+    fn outside_of_image() {
+        let out = composite(
+            &gray(200),
+            &gray(0),
+            (i64::from(u32::MAX), 2),
+            1.0,
+            BlendMode::Normal,
+        )
+        .unwrap();
+
+        assert_eq!(out.as_rgb8(), gray(200).as_rgb8());
+    }
+
+    #[test]
+    fn partly_left_of_and_above_image() {
+        let out = composite(&gray(200), &gray(50), (-1, -1), 1.0, BlendMode::Normal).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.get_pixel(0, 0), &Rgb([50, 50, 50]));
+        assert_eq!(out.get_pixel(1, 0), &Rgb([200, 200, 200]));
+        assert_eq!(out.get_pixel(0, 1), &Rgb([200, 200, 200]));
+    }
+
+    #[test]
+    fn invalid_opacity() {
+        assert!(composite(&gray(0), &gray(0), (0, 0), 1.5, BlendMode::Normal).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::analysis::contours::find_contours;
+use crate::engine::Contour;
+use crate::wrapper::contours::ContoursInputs;
+use sic_core::image::DynamicImage;
+
+/// Binarizes the image, traces the borders between its bright and dark areas, and draws them in
+/// the color of the inputs, e.g. to outline the shapes of a scanned drawing or a mask.
+///
+/// Pixels with a luminance of at least the threshold are bright. The pixels of each contour are
+/// the bright pixels along the border, so the outlines are drawn on the edge of the bright areas.
+/// Returns the image with the outlines, and the traced contours.
+pub(crate) fn contours(
+    image: &DynamicImage,
+    inputs: &ContoursInputs,
+) -> (DynamicImage, Vec<Contour>) {
+    let mut binary = image.to_luma();
+    binary.pixels_mut().for_each(|pixel| {
+        pixel[0] = if pixel[0] >= inputs.threshold() {
+            u8::MAX
+        } else {
+            0
+        }
+    });
+
+    let contours = find_contours(&binary);
+    let mut canvas = image.to_rgba();
+
+    for &(x, y) in contours.iter().flat_map(|contour| &contour.points) {
+        canvas.put_pixel(x, y, inputs.color());
+    }
+
+    let canvas = DynamicImage::ImageRgba8(canvas);
+
+    if image.color().has_alpha() {
+        (canvas, contours)
+    } else {
+        (DynamicImage::ImageRgb8(canvas.to_rgb()), contours)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ContourKind;
+    use sic_core::image::{GenericImageView, Rgb, RgbImage, Rgba};
+
+    const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
+
+    // a gray square with a dark hole, on a black background
+    fn square() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(12, 12, |x, y| {
+            if (4..7).contains(&x) && (4..7).contains(&y) {
+                Rgb([20, 20, 20])
+            } else if (2..9).contains(&x) && (2..9).contains(&y) {
+                Rgb([160, 160, 160])
+            } else {
+                Rgb([0, 0, 0])
+            }
+        }))
+    }
+
+    #[test]
+    fn outlines_square_with_hole() {
+        let (out, contours) = contours(&square(), &ContoursInputs::new(128, GREEN));
+
+        let kinds = contours
+            .iter()
+            .map(|contour| contour.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![ContourKind::Outer, ContourKind::Hole]);
+
+        // the outer border of 7x7 pixels, and the border directly next to the hole of 3x3 pixels
+        assert_eq!(contours[0].points.len(), 24);
+        assert_eq!(contours[1].points.len(), 12);
+
+        let out = out.as_rgb8().unwrap();
+        assert_eq!(out.get_pixel(2, 2), &Rgb([0, 255, 0]));
+        assert_eq!(out.get_pixel(4, 3), &Rgb([0, 255, 0]));
+        assert_eq!(out.get_pixel(8, 5), &Rgb([0, 255, 0]));
+        // inside the bright area, inside the hole, and outside of the square nothing is drawn
+        assert_eq!(out.get_pixel(3, 3), &Rgb([160, 160, 160]));
+        assert_eq!(out.get_pixel(5, 5), &Rgb([20, 20, 20]));
+        assert_eq!(out.get_pixel(1, 1), &Rgb([0, 0, 0]));
+    }
+
+    #[test]
+    fn outlines_grayscale_image_in_color() {
+        let image = DynamicImage::ImageLuma8(square().to_luma());
+        let (out, _) = contours(&image, &ContoursInputs::new(128, GREEN));
+
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(2, 2), &Rgb([0, 255, 0]));
+    }
+
+    #[test]
+    fn threshold_selects_bright_areas() {
+        let (_, contours) = contours(&square(), &ContoursInputs::new(161, GREEN));
+
+        assert!(contours.is_empty());
+    }
+
+    #[test]
+    fn zero_threshold_outlines_image() {
+        let image = square();
+        let (out, contours) = contours(&image, &ContoursInputs::new(0, GREEN));
+
+        assert_eq!(contours.len(), 1);
+        assert_eq!(contours[0].points.len(), 44);
+        assert_eq!(out.get_pixel(0, 0), GREEN);
+        assert_eq!(out.get_pixel(5, 5), image.get_pixel(5, 5));
+    }
+}
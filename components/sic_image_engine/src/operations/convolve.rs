@@ -0,0 +1,141 @@
+use crate::operations::{apply_to_color_samples, ColorSamplesOperation};
+use crate::wrapper::kernel::Kernel;
+use sic_core::image::{DynamicImage, ImageBuffer, Pixel};
+
+/// Convolves the image with a square kernel of any odd size. Like `filter3x3`, the result is
+/// divided by the sum of the weights of the kernel, unless they sum to 0 (as for edge detection
+/// kernels), so kernels don't have to be normalized up front.
+///
+/// Pixels beyond the edges take the value of the nearest edge pixel. The alpha channel is left
+/// untouched. Images with 16-bit samples are converted to 8-bit samples.
+pub(crate) fn convolve(image: &DynamicImage, kernel: &Kernel) -> DynamicImage {
+    apply_to_color_samples(image, Convolve(kernel))
+}
+
+struct Convolve<'k>(&'k Kernel);
+
+impl ColorSamplesOperation for Convolve<'_> {
+    fn apply<P>(
+        self,
+        buffer: ImageBuffer<P, Vec<u8>>,
+        color_channels: usize,
+    ) -> ImageBuffer<P, Vec<u8>>
+    where
+        P: Pixel<Subpixel = u8> + 'static,
+    {
+        convolve_buffer(buffer, color_channels, self.0)
+    }
+}
+
+fn convolve_buffer<P>(
+    buffer: ImageBuffer<P, Vec<u8>>,
+    color_channels: usize,
+    kernel: &Kernel,
+) -> ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8> + 'static,
+{
+    let (width, height) = buffer.dimensions();
+    let mut out = buffer.clone();
+
+    if width == 0 || height == 0 {
+        return out;
+    }
+
+    let sum = kernel.values().iter().sum::<f32>();
+    let divisor = if sum == 0.0 { 1.0 } else { sum };
+
+    let radius = (kernel.size() / 2) as i64;
+    let offsets = (-radius..=radius)
+        .flat_map(|dy| (-radius..=radius).map(move |dx| (dx, dy)))
+        .zip(kernel.values())
+        .collect::<Vec<_>>();
+
+    let clamp = |value: i64, max: u32| value.clamp(0, i64::from(max) - 1) as u32;
+
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = out.get_pixel_mut(x, y);
+
+            for channel in 0..color_channels {
+                let total = offsets
+                    .iter()
+                    .map(|&((dx, dy), weight)| {
+                        let sx = clamp(i64::from(x) + dx, width);
+                        let sy = clamp(i64::from(y) + dy, height);
+
+                        weight * f32::from(buffer.get_pixel(sx, sy).channels()[channel])
+                    })
+                    .sum::<f32>();
+
+                pixel.channels_mut()[channel] = (total / divisor).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma, Rgba, RgbaImage};
+
+    fn dot() -> DynamicImage {
+        // a single bright pixel in the center
+        DynamicImage::ImageLuma8(GrayImage::from_fn(5, 5, |x, y| {
+            Luma([if (x, y) == (2, 2) { 250 } else { 0 }])
+        }))
+    }
+
+    #[test]
+    fn identity_kernel() {
+        let mut values = vec![0.0; 25];
+        values[12] = 1.0;
+        let kernel = Kernel::new(5, values).unwrap();
+
+        let out = convolve(&dot(), &kernel);
+
+        assert_eq!(out.as_luma8().unwrap(), dot().as_luma8().unwrap());
+    }
+
+    #[test]
+    fn normalizes_by_sum_of_weights() {
+        let kernel = Kernel::new(5, vec![1.0; 25]).unwrap();
+
+        let out = convolve(&dot(), &kernel);
+        let out = out.as_luma8().unwrap();
+
+        assert!(out.pixels().all(|px| px[0] == 10));
+    }
+
+    #[test]
+    fn zero_sum_kernel_is_not_normalized() {
+        #[rustfmt::skip]
+        let kernel = Kernel::new(3, vec![
+            0.0, -1.0, 0.0,
+            -1.0, 4.0, -1.0,
+            0.0, -1.0, 0.0,
+        ]).unwrap();
+
+        let out = convolve(&dot(), &kernel);
+        let out = out.as_luma8().unwrap();
+
+        assert_eq!(out.get_pixel(2, 2)[0], 255);
+        assert_eq!(out.get_pixel(2, 1)[0], 0);
+        assert_eq!(out.get_pixel(0, 0)[0], 0);
+    }
+
+    #[test]
+    fn keeps_alpha() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(3, 3, |x, _| {
+            Rgba([x as u8 * 100, 0, 0, x as u8 * 50])
+        }));
+        let kernel = Kernel::new(3, vec![1.0; 9]).unwrap();
+
+        let out = convolve(&image, &kernel);
+        let out = out.as_rgba8().unwrap();
+
+        assert_eq!(out.get_pixel(2, 1), &Rgba([167, 0, 0, 100]));
+    }
+}
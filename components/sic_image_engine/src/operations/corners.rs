@@ -0,0 +1,207 @@
+use crate::wrapper::draw_corners::DrawCornersInputs;
+use imageproc::corners::Corner;
+use imageproc::drawing::draw_hollow_circle_mut;
+use imageproc::suppress::local_maxima;
+use sic_core::image::{DynamicImage, GrayImage};
+
+/// The offsets of the 16 pixels on the circle of radius 3 around a pixel, clockwise from the top.
+const CIRCLE: [(i32, i32); 16] = [
+    (0, -3),
+    (1, -3),
+    (2, -2),
+    (3, -1),
+    (3, 0),
+    (3, 1),
+    (2, 2),
+    (1, 3),
+    (0, 3),
+    (-1, 3),
+    (-2, 2),
+    (-3, 1),
+    (-3, 0),
+    (-3, -1),
+    (-2, -2),
+    (-1, -3),
+];
+
+/// The amount of contiguous pixels on the circle which should all be brighter, or all be darker,
+/// than a corner.
+const ARC_LENGTH: usize = 9;
+
+/// The radius of the circles which mark the corners, in pixels.
+const MARKER_RADIUS: i32 = 3;
+
+/// Corners within this distance of a stronger corner aren't marked, so a single corner isn't
+/// marked by a cluster of overlapping circles.
+const SUPPRESSION_RADIUS: u32 = 3;
+
+/// Detects the corners of the image with the FAST-9 detector, and marks each of them with a
+/// small circle, e.g. to inspect how many features an image has for panorama stitching or
+/// tracking.
+///
+/// A pixel is a corner if at least 9 contiguous pixels of the circle of 16 pixels around it are
+/// all brighter, or all darker, than the pixel itself by more than the threshold. A lower
+/// threshold thus detects more, but weaker, corners. Corners are detected on the luminance of the
+/// image; pixels within 3 pixels of the edge of the image can't be corners.
+pub(crate) fn draw_corners(image: &DynamicImage, inputs: &DrawCornersInputs) -> DynamicImage {
+    let corners = detect_corners(&image.to_luma(), inputs.threshold());
+    let corners = local_maxima(&corners, SUPPRESSION_RADIUS);
+
+    let mut canvas = image.to_rgba();
+
+    for corner in corners {
+        draw_hollow_circle_mut(
+            &mut canvas,
+            (corner.x as i32, corner.y as i32),
+            MARKER_RADIUS,
+            inputs.color(),
+        );
+    }
+
+    let canvas = DynamicImage::ImageRgba8(canvas);
+
+    if image.color().has_alpha() {
+        canvas
+    } else {
+        DynamicImage::ImageRgb8(canvas.to_rgb())
+    }
+}
+
+/// The FAST-9 corners of the image. The score of a corner is the largest threshold for which it
+/// is still a corner, so stronger corners are kept when nearby corners are suppressed.
+fn detect_corners(luma: &GrayImage, threshold: u8) -> Vec<Corner> {
+    let (width, height) = luma.dimensions();
+    let mut corners = Vec::new();
+
+    for y in 3..height.saturating_sub(3) {
+        for x in 3..width.saturating_sub(3) {
+            if is_corner(luma, x, y, threshold) {
+                // the largest threshold for which the pixel is a corner, by binary search
+                let (mut low, mut high) = (threshold, u8::MAX);
+
+                while low < high {
+                    let middle = high - (high - low) / 2;
+
+                    if is_corner(luma, x, y, middle) {
+                        low = middle;
+                    } else {
+                        high = middle - 1;
+                    }
+                }
+
+                corners.push(Corner::new(x, y, f32::from(low)));
+            }
+        }
+    }
+
+    corners
+}
+
+/// Whether at least `ARC_LENGTH` contiguous pixels on the circle around the pixel at `(x, y)` are
+/// all brighter, or all darker, than the pixel by more than the threshold. The pixel should lie at
+/// least 3 pixels from the edge of the image.
+fn is_corner(luma: &GrayImage, x: u32, y: u32, threshold: u8) -> bool {
+    let center = i16::from(luma.get_pixel(x, y)[0]);
+    let threshold = i16::from(threshold);
+
+    let on_circle = |i: usize| {
+        let (dx, dy) = CIRCLE[i % CIRCLE.len()];
+        i16::from(luma.get_pixel((x as i32 + dx) as u32, (y as i32 + dy) as u32)[0])
+    };
+
+    let (mut brighter, mut darker) = (0, 0);
+
+    // the arc may wrap around the start of the circle
+    for i in 0..CIRCLE.len() + ARC_LENGTH - 1 {
+        let value = on_circle(i);
+
+        brighter = if value > center + threshold {
+            brighter + 1
+        } else {
+            0
+        };
+        darker = if value < center - threshold {
+            darker + 1
+        } else {
+            0
+        };
+
+        if brighter >= ARC_LENGTH || darker >= ARC_LENGTH {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Rgb, RgbImage, Rgba};
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    // a white square on a black background, which has a corner at each of its corners
+    fn square() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(32, 32, |x, y| {
+            if (10..22).contains(&x) && (10..22).contains(&y) {
+                Rgb([255, 255, 255])
+            } else {
+                Rgb([0, 0, 0])
+            }
+        }))
+    }
+
+    fn marked(image: &DynamicImage) -> Vec<(u32, u32)> {
+        image
+            .pixels()
+            .filter(|(_, _, pixel)| *pixel == RED)
+            .map(|(x, y, _)| (x, y))
+            .collect()
+    }
+
+    #[test]
+    fn marks_corners_of_square() {
+        let out = draw_corners(&square(), &DrawCornersInputs::new(50, RED));
+        let marked = marked(&out);
+
+        assert!(out.as_rgb8().is_some());
+
+        let corners = [(10, 10), (21, 10), (10, 21), (21, 21)];
+        let near = |(x, y): (u32, u32), (cx, cy): (u32, u32)| {
+            x.max(cx) - x.min(cx) <= 6 && y.max(cy) - y.min(cy) <= 6
+        };
+
+        // each corner of the square is marked, and nothing else is
+        assert!(corners
+            .iter()
+            .all(|&corner| marked.iter().any(|&pixel| near(pixel, corner))));
+        assert!(marked
+            .iter()
+            .all(|&pixel| corners.iter().any(|&corner| near(pixel, corner))));
+    }
+
+    #[test]
+    fn uniform_image_has_no_corners() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 16, Rgb([80, 80, 80])));
+
+        let out = draw_corners(&image, &DrawCornersInputs::new(0, RED));
+
+        assert!(marked(&out).is_empty());
+    }
+
+    #[test]
+    fn high_threshold_detects_nothing() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(32, 32, |x, y| {
+            if (10..22).contains(&x) && (10..22).contains(&y) {
+                Rgb([100, 100, 100])
+            } else {
+                Rgb([60, 60, 60])
+            }
+        }));
+
+        let out = draw_corners(&image, &DrawCornersInputs::new(60, RED));
+
+        assert!(marked(&out).is_empty());
+    }
+}
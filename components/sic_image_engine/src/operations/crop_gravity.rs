@@ -0,0 +1,63 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::gravity::Gravity;
+use sic_core::image::{DynamicImage, GenericImageView};
+
+/// Crops a selection of `width` by `height` pixels at the side or corner of the image given by
+/// the gravity, so a crop of a fixed size can be taken from images of different sizes.
+pub(crate) fn crop_gravity(
+    image: &DynamicImage,
+    gravity: Gravity,
+    (width, height): (u32, u32),
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (image_width, image_height) = image.dimensions();
+
+    if width == 0 || height == 0 || width > image_width || height > image_height {
+        return Err(SicImageEngineError::CropInvalidSize(
+            width,
+            height,
+            image_width,
+            image_height,
+        ));
+    }
+
+    let (x, y) = gravity.position(image.dimensions(), (width, height), 0);
+
+    Ok(image.crop_imm(x, y, width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma};
+    use sic_testing::*;
+
+    ide!();
+
+    /// A 4x3 image of which each pixel has the value `10 * y + x`.
+    fn numbered() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(4, 3, |x, y| Luma([(10 * y + x) as u8])))
+    }
+
+    #[pm(
+        gravity = { Gravity::TopLeft, Gravity::Top, Gravity::Center, Gravity::Right, Gravity::BottomRight },
+        expected = { 0, 1, 11, 12, 22 }
+    )]
+    fn crops_by_gravity(gravity: Gravity, expected: u8) {
+        let out = crop_gravity(&numbered(), gravity, (2, 1)).unwrap();
+
+        assert_eq!(out.dimensions(), (2, 1));
+        assert_eq!(out.as_luma8().unwrap().get_pixel(0, 0), &Luma([expected]));
+    }
+
+    #[test]
+    fn whole_image() {
+        let out = crop_gravity(&numbered(), Gravity::Center, (4, 3)).unwrap();
+
+        assert_eq!(out.as_luma8(), numbered().as_luma8());
+    }
+
+    #[pm(size = { (5, 3), (4, 4), (0, 1), (1, 0) })]
+    fn invalid_size(size: (u32, u32)) {
+        assert!(crop_gravity(&numbered(), Gravity::Center, size).is_err());
+    }
+}
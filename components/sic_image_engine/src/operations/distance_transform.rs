@@ -0,0 +1,112 @@
+use imageproc::distance_transform::euclidean_squared_distance_transform;
+use sic_core::image::{DynamicImage, GrayImage, Luma};
+
+/// Pixels at least this bright belong to the foreground.
+const FOREGROUND_THRESHOLD: u8 = 128;
+
+/// Replaces each pixel of the bright areas of the image by its (Euclidean) distance to the
+/// nearest dark pixel, e.g. to find the centers or the thickness of shapes after thresholding.
+///
+/// The image is binarized first: pixels with a luminance of at least 128 are foreground, others
+/// are background, and stay black. The distances are normalized, so the pixel farthest from the
+/// background becomes white. If the image has no background, there is nothing to measure the
+/// distance to, and the whole image becomes white.
+pub(crate) fn distance_transform(image: &DynamicImage) -> DynamicImage {
+    // the distances are measured to the nearest non-zero pixel, which should be the background
+    let mut background = image.to_luma();
+    background.pixels_mut().for_each(|pixel| {
+        pixel[0] = if pixel[0] >= FOREGROUND_THRESHOLD {
+            0
+        } else {
+            u8::MAX
+        }
+    });
+
+    let distances = euclidean_squared_distance_transform(&background);
+
+    let max = distances
+        .pixels()
+        .map(|pixel| pixel[0])
+        .filter(|distance| distance.is_finite())
+        .fold(0.0, f64::max)
+        .sqrt();
+
+    DynamicImage::ImageLuma8(GrayImage::from_fn(
+        distances.width(),
+        distances.height(),
+        |x, y| {
+            let distance = distances.get_pixel(x, y)[0];
+
+            if distance.is_infinite() {
+                Luma([u8::MAX])
+            } else if max > 0.0 {
+                Luma([(distance.sqrt() / max * f64::from(u8::MAX)).round() as u8])
+            } else {
+                Luma([0])
+            }
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_to_background() {
+        // a bright row of 5 pixels wide, between dark rows
+        let image = DynamicImage::ImageLuma8(GrayImage::from_fn(5, 3, |_, y| {
+            Luma([if y == 1 { 200 } else { 0 }])
+        }));
+
+        let out = distance_transform(&image);
+        let out = out.as_luma8().unwrap();
+
+        assert!(out.pixels().all(|pixel| pixel[0] == 0 || pixel[0] == 255));
+        assert_eq!(out.get_pixel(2, 1), &Luma([255]));
+        assert_eq!(out.get_pixel(2, 0), &Luma([0]));
+    }
+
+    #[test]
+    fn normalized_to_the_farthest_pixel() {
+        // a bright square of 5 by 5 pixels, surrounded by a dark border
+        let image = DynamicImage::ImageLuma8(GrayImage::from_fn(7, 7, |x, y| {
+            Luma([if (1..=5).contains(&x) && (1..=5).contains(&y) {
+                255
+            } else {
+                0
+            }])
+        }));
+
+        let out = distance_transform(&image);
+        let out = out.as_luma8().unwrap();
+
+        // 1, 2 and 3 pixels away from the border
+        assert_eq!(out.get_pixel(1, 3), &Luma([85]));
+        assert_eq!(out.get_pixel(2, 3), &Luma([170]));
+        assert_eq!(out.get_pixel(3, 3), &Luma([255]));
+        assert_eq!(out.get_pixel(0, 3), &Luma([0]));
+    }
+
+    #[test]
+    fn without_foreground() {
+        let image = DynamicImage::ImageLuma8(GrayImage::new(3, 3));
+
+        let out = distance_transform(&image);
+
+        assert!(out.as_luma8().unwrap().pixels().all(|pixel| pixel[0] == 0));
+    }
+
+    #[test]
+    fn without_background() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(3, 3, Luma([255])));
+
+        let out = distance_transform(&image);
+
+        assert!(out
+            .as_luma8()
+            .unwrap()
+            .pixels()
+            .all(|pixel| pixel[0] == 255));
+    }
+}
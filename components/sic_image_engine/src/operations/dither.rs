@@ -0,0 +1,175 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::palette::Palette;
+use sic_core::image::{DynamicImage, RgbaImage};
+
+/// The Floyd–Steinberg error diffusion kernel: `(dx, dy, weight)` relative to the current pixel.
+const KERNEL: [(i64, u32, f32); 4] = [
+    (1, 0, 7.0 / 16.0),
+    (-1, 1, 3.0 / 16.0),
+    (0, 1, 5.0 / 16.0),
+    (1, 1, 1.0 / 16.0),
+];
+
+/// Reduces the colors of an image to a palette of at most `colors` colors, chosen by the median
+/// cut algorithm. The error made by replacing a pixel by its nearest palette color is diffused to
+/// its neighbouring pixels using Floyd–Steinberg dithering, so gradients remain smooth.
+///
+/// The alpha channel is left untouched. Fully transparent pixels are not taken into account when
+/// choosing the palette, and are left as is.
+pub(crate) fn dither(
+    image: &DynamicImage,
+    colors: u32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if !(2..=256).contains(&colors) {
+        return Err(SicImageEngineError::DitherInvalidColorCount(colors));
+    }
+
+    let mut buffer = image.to_rgba();
+
+    let visible = buffer
+        .pixels()
+        .filter(|pixel| pixel[3] > 0)
+        .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        .collect();
+
+    let palette = Palette::median_cut(visible, colors as usize);
+    diffuse(&mut buffer, &palette);
+
+    if image.color().has_alpha() {
+        Ok(DynamicImage::ImageRgba8(buffer))
+    } else {
+        Ok(DynamicImage::ImageRgb8(
+            DynamicImage::ImageRgba8(buffer).to_rgb(),
+        ))
+    }
+}
+
+fn diffuse(buffer: &mut RgbaImage, palette: &Palette) {
+    let (width, height) = buffer.dimensions();
+    let width = width as usize;
+
+    // the accumulated error for the current and the next row
+    let mut errors = vec![[0f32; 3]; width * 2];
+
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = buffer.get_pixel_mut(x as u32, y);
+
+            if pixel[3] == 0 {
+                continue;
+            }
+
+            let error = errors[x];
+            let wanted = [
+                f32::from(pixel[0]) + error[0],
+                f32::from(pixel[1]) + error[1],
+                f32::from(pixel[2]) + error[2],
+            ];
+
+            let chosen = palette.nearest(wanted);
+            pixel[0] = chosen[0];
+            pixel[1] = chosen[1];
+            pixel[2] = chosen[2];
+
+            for &(dx, dy, weight) in KERNEL.iter() {
+                let nx = x as i64 + dx;
+
+                if nx < 0 || nx >= width as i64 {
+                    continue;
+                }
+
+                let target = &mut errors[dy as usize * width + nx as usize];
+
+                for channel in 0..3 {
+                    target[channel] += (wanted[channel] - f32::from(chosen[channel])) * weight;
+                }
+            }
+        }
+
+        // the next row becomes the current row
+        errors.copy_within(width.., 0);
+        errors[width..].iter_mut().for_each(|e| *e = [0.0; 3]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, GrayImage, Luma, Rgba};
+    use sic_testing::*;
+    use std::collections::HashSet;
+
+    ide!();
+
+    fn distinct_colors(image: &DynamicImage) -> usize {
+        image
+            .to_rgba()
+            .pixels()
+            .map(|p| p.0)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    #[pm(colors = { 2, 4, 16 })]
+    fn reduces_colors(colors: u32) {
+        let image = open_test_image(setup_test_image("unsplash_763569_cropped.jpg"));
+
+        let out = dither(&image, colors).unwrap();
+
+        assert!(distinct_colors(&out) <= colors as usize);
+        assert_eq!(out.dimensions(), image.dimensions());
+    }
+
+    #[test]
+    fn gradient_keeps_average_intensity() {
+        let gradient = GrayImage::from_fn(64, 8, |x, _| Luma([(x * 4) as u8]));
+        let image = DynamicImage::ImageLuma8(gradient);
+
+        let out = dither(&image, 2).unwrap();
+        assert_eq!(distinct_colors(&out), 2);
+
+        let mean = |img: &DynamicImage| {
+            img.to_luma().pixels().map(|p| f64::from(p[0])).sum::<f64>() / (64.0 * 8.0)
+        };
+
+        assert!((mean(&image) - mean(&out)).abs() < 4.0);
+    }
+
+    #[test]
+    fn palette_image_is_unchanged() {
+        let image = open_test_image(setup_test_image("palette_4x4.png"));
+        let colors = distinct_colors(&image) as u32;
+
+        let out = dither(&image, colors.max(2)).unwrap();
+
+        assert_eq!(out.to_rgba().into_raw(), image.to_rgba().into_raw());
+    }
+
+    #[test]
+    fn alpha_is_kept() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(4, 4, |x, y| {
+            Rgba([(x * 60) as u8, (y * 60) as u8, 0, (x * 16 + y) as u8])
+        }));
+
+        let out = dither(&image, 2).unwrap();
+
+        let alpha = |img: &DynamicImage| img.to_rgba().pixels().map(|p| p[3]).collect::<Vec<_>>();
+        assert_eq!(alpha(&out), alpha(&image));
+    }
+
+    #[test]
+    fn without_alpha_stays_without_alpha() {
+        let image = open_test_image(setup_test_image("rainbow_8x6.bmp"));
+
+        let out = dither(&image, 4).unwrap();
+
+        assert!(!out.color().has_alpha());
+    }
+
+    #[pm(colors = { 0, 1, 257 })]
+    fn invalid_color_count(colors: u32) {
+        let image = open_test_image(setup_test_image("rainbow_8x6.bmp"));
+
+        assert!(dither(&image, colors).is_err());
+    }
+}
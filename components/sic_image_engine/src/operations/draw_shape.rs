@@ -0,0 +1,616 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::draw_shape::{
+    DrawEllipseInputs, DrawLineInputs, DrawPolygonInputs, DrawRectInputs, ShapeStyle,
+};
+use imageproc::drawing::{draw_filled_rect_mut, Blend, Canvas};
+use imageproc::rect::Rect;
+use sic_core::image::{DynamicImage, Rgba, RgbaImage};
+
+/// Draws a rectangle, either filled or outlined. The outline is drawn within the rectangle; if it's
+/// at least half as wide as the rectangle, the rectangle is filled instead.
+///
+/// The rectangle is alpha blended onto the image, so translucent colors can be used to highlight
+/// an area. Parts of the rectangle which fall outside of the image are cut off.
+pub(crate) fn draw_rect(
+    image: &DynamicImage,
+    inputs: &DrawRectInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (x, y, width, height) = inputs.rect();
+
+    if width == 0 || height == 0 {
+        return Err(SicImageEngineError::DrawRectInvalidSize(width, height));
+    }
+
+    let mut canvas = Blend(image.to_rgba());
+    let color = inputs.color();
+
+    let (x, y, width, height) = (
+        u64::from(x),
+        u64::from(y),
+        u64::from(width),
+        u64::from(height),
+    );
+
+    match validate_style(inputs.style())? {
+        ShapeStyle::Stroke(stroke) if 2 * u64::from(stroke) < width.min(height) => {
+            let stroke = u64::from(stroke);
+            let inner_height = height - 2 * stroke;
+
+            fill_rect(&mut canvas, (x, y, width, stroke), color);
+            fill_rect(&mut canvas, (x, y + height - stroke, width, stroke), color);
+            fill_rect(&mut canvas, (x, y + stroke, stroke, inner_height), color);
+            fill_rect(
+                &mut canvas,
+                (x + width - stroke, y + stroke, stroke, inner_height),
+                color,
+            );
+        }
+        _ => fill_rect(&mut canvas, (x, y, width, height), color),
+    }
+
+    Ok(into_dynamic_image(canvas.0, image))
+}
+
+/// Draws a line of the given width with rounded ends. Like a rectangle, the line is alpha blended
+/// onto the image, and parts of it which fall outside of the image are cut off.
+pub(crate) fn draw_line(
+    image: &DynamicImage,
+    inputs: &DrawLineInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let half_width = half_line_width(inputs.width())?;
+    let (from, to) = line_points(inputs);
+
+    let mut canvas = Blend(image.to_rgba());
+
+    fill_where(&mut canvas, &[from, to], half_width, inputs.color(), |p| {
+        distance_to_segment(p, from, to) < half_width
+    });
+
+    Ok(into_dynamic_image(canvas.0, image))
+}
+
+/// Draws a line with a filled arrowhead at its end. The arrowhead grows with the width of the
+/// line; if the line is shorter than the arrowhead, only the (shortened) arrowhead is drawn.
+pub(crate) fn draw_arrow(
+    image: &DynamicImage,
+    inputs: &DrawLineInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let half_width = half_line_width(inputs.width())?;
+
+    if inputs.from() == inputs.to() {
+        return Err(SicImageEngineError::DrawArrowZeroLength);
+    }
+
+    let (from, to) = line_points(inputs);
+
+    let length = (to.0 - from.0).hypot(to.1 - from.1);
+    let (dx, dy) = ((to.0 - from.0) / length, (to.1 - from.1) / length);
+
+    let head_length = (6.0 * half_width + 6.0).min(length);
+    let head_half_width = head_length / 2.0;
+
+    let base = (to.0 - dx * head_length, to.1 - dy * head_length);
+    let head = [
+        to,
+        (base.0 - dy * head_half_width, base.1 + dx * head_half_width),
+        (base.0 + dy * head_half_width, base.1 - dx * head_half_width),
+    ];
+    let has_shaft = head_length < length;
+
+    let mut canvas = Blend(image.to_rgba());
+
+    // the shaft and the head overlap, so the pixels are tested against both, and drawn once
+    fill_where(
+        &mut canvas,
+        &[from, head[0], head[1], head[2]],
+        half_width,
+        inputs.color(),
+        |p| (has_shaft && distance_to_segment(p, from, base) < half_width) || in_triangle(p, head),
+    );
+
+    Ok(into_dynamic_image(canvas.0, image))
+}
+
+/// Draws an axis aligned ellipse, either filled or outlined. Its outline is drawn within the
+/// ellipse; if it's at least as wide as the smaller radius, the ellipse is filled instead.
+///
+/// The pixels whose centers satisfy `(x / rx)^2 + (y / ry)^2 <= 1` are covered, which is the same
+/// ellipse as imageproc's `draw_filled_ellipse`; we don't use the latter as its arithmetic overflows
+/// for ellipses with radii of a few hundred pixels.
+pub(crate) fn draw_ellipse(
+    image: &DynamicImage,
+    inputs: &DrawEllipseInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (rx, ry) = inputs.radii();
+
+    if rx == 0 || ry == 0 {
+        return Err(SicImageEngineError::DrawEllipseInvalidRadius(rx, ry));
+    }
+
+    let (cx, cy) = inputs.center();
+    let center = (f64::from(cx) + 0.5, f64::from(cy) + 0.5);
+    let (rx, ry) = (f64::from(rx), f64::from(ry));
+
+    let within = |(x, y): (f64, f64), rx: f64, ry: f64| {
+        let (dx, dy) = ((x - center.0) / rx, (y - center.1) / ry);
+
+        dx * dx + dy * dy <= 1.0
+    };
+
+    let inner = match validate_style(inputs.style())? {
+        ShapeStyle::Stroke(stroke) if f64::from(stroke) < rx.min(ry) => {
+            Some((rx - f64::from(stroke), ry - f64::from(stroke)))
+        }
+        _ => None,
+    };
+
+    let mut canvas = Blend(image.to_rgba());
+
+    let bounds = [
+        (center.0 - rx, center.1 - ry),
+        (center.0 + rx, center.1 + ry),
+    ];
+    fill_where(&mut canvas, &bounds, 0.0, inputs.color(), |p| {
+        within(p, rx, ry) && inner.is_none_or(|(irx, iry)| !within(p, irx, iry))
+    });
+
+    Ok(into_dynamic_image(canvas.0, image))
+}
+
+/// Draws a closed polygon. Its outline consists of lines between the points, of the width of the
+/// stroke, with rounded corners. If it's filled, the pixels within the polygon, and on its edges,
+/// are covered; for polygons with intersecting edges, the areas which are enclosed an odd number
+/// of times are within the polygon (the even-odd rule).
+pub(crate) fn draw_polygon(
+    image: &DynamicImage,
+    inputs: &DrawPolygonInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    // the edges of a filled polygon are covered like a line with a width of 1 pixel
+    let (width, filled) = match validate_style(inputs.style())? {
+        ShapeStyle::Filled => (1, true),
+        ShapeStyle::Stroke(stroke) => (stroke, false),
+    };
+
+    let half_width = f64::from(width) / 2.0;
+    let offset = point_offset(width);
+    let points = inputs
+        .points()
+        .iter()
+        .map(|&(x, y)| (f64::from(x) + offset, f64::from(y) + offset))
+        .collect::<Vec<_>>();
+
+    let on_edge = |p| {
+        let closing = (points[points.len() - 1], points[0]);
+
+        points
+            .windows(2)
+            .map(|edge| (edge[0], edge[1]))
+            .chain(std::iter::once(closing))
+            .any(|(a, b)| distance_to_segment(p, a, b) < half_width)
+    };
+
+    let mut canvas = Blend(image.to_rgba());
+
+    fill_where(&mut canvas, &points, half_width, inputs.color(), |p| {
+        (filled && in_polygon(p, &points)) || on_edge(p)
+    });
+
+    Ok(into_dynamic_image(canvas.0, image))
+}
+
+fn half_line_width(width: u32) -> Result<f64, SicImageEngineError> {
+    if width == 0 {
+        Err(SicImageEngineError::DrawShapeInvalidStrokeWidth)
+    } else {
+        Ok(f64::from(width) / 2.0)
+    }
+}
+
+/// The end points of a line, in continuous coordinates. The points of lines with an odd width are
+/// placed at the center of their pixel, and of lines with an even width at its top left corner,
+/// so the line covers exactly `width` pixels across.
+fn line_points(inputs: &DrawLineInputs) -> ((f64, f64), (f64, f64)) {
+    let offset = point_offset(inputs.width());
+    let point = |(x, y): (u32, u32)| (f64::from(x) + offset, f64::from(y) + offset);
+
+    (point(inputs.from()), point(inputs.to()))
+}
+
+fn point_offset(width: u32) -> f64 {
+    if width % 2 == 1 {
+        0.5
+    } else {
+        0.0
+    }
+}
+
+/// Draws each pixel within `margin` of the bounding box of the points, whose center is covered
+/// by the shape.
+fn fill_where<F>(
+    canvas: &mut Blend<RgbaImage>,
+    points: &[(f64, f64)],
+    margin: f64,
+    color: Rgba<u8>,
+    covered: F,
+) where
+    F: Fn((f64, f64)) -> bool,
+{
+    let (width, height) = canvas.0.dimensions();
+
+    let bounds = |coords: &mut dyn Iterator<Item = f64>, size: u32| {
+        let (min, max) = coords.fold((f64::MAX, f64::MIN), |(min, max), c| {
+            (min.min(c), max.max(c))
+        });
+
+        // clamped to the canvas, so the bounds fit
+        let lower = (min - margin).floor().max(0.0) as u32;
+        let upper = (max + margin).ceil().min(f64::from(size)) as u32;
+
+        lower..upper
+    };
+
+    let xs = bounds(&mut points.iter().map(|p| p.0), width);
+    let ys = bounds(&mut points.iter().map(|p| p.1), height);
+
+    for y in ys {
+        for x in xs.clone() {
+            if covered((f64::from(x) + 0.5, f64::from(y) + 0.5)) {
+                canvas.draw_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+fn distance_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = abx * abx + aby * aby;
+
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * abx + (p.1 - a.1) * aby) / length_squared).clamp(0.0, 1.0)
+    };
+
+    (p.0 - (a.0 + t * abx)).hypot(p.1 - (a.1 + t * aby))
+}
+
+fn in_triangle(p: (f64, f64), [a, b, c]: [(f64, f64); 3]) -> bool {
+    let side = |a: (f64, f64), b: (f64, f64)| (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+    let (ab, bc, ca) = (side(a, b), side(b, c), side(c, a));
+
+    (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+}
+
+/// Whether the point is within the polygon by the even-odd rule: a ray from the point crosses its
+/// edges an odd number of times.
+fn in_polygon(p: (f64, f64), points: &[(f64, f64)]) -> bool {
+    let edges = points.iter().zip(points.iter().cycle().skip(1));
+
+    edges
+        .filter(|(a, b)| (a.1 > p.1) != (b.1 > p.1))
+        .filter(|(a, b)| p.0 < a.0 + (p.1 - a.1) * (b.0 - a.0) / (b.1 - a.1))
+        .count()
+        % 2
+        == 1
+}
+
+fn validate_style(style: ShapeStyle) -> Result<ShapeStyle, SicImageEngineError> {
+    match style {
+        ShapeStyle::Stroke(0) => Err(SicImageEngineError::DrawShapeInvalidStrokeWidth),
+        style => Ok(style),
+    }
+}
+
+/// Fills the part of the rectangle `(x, y, width, height)` which lies within the canvas.
+fn fill_rect(
+    canvas: &mut Blend<RgbaImage>,
+    (x, y, width, height): (u64, u64, u64, u64),
+    color: Rgba<u8>,
+) {
+    let (canvas_width, canvas_height) = canvas.0.dimensions();
+
+    let right = (x + width).min(canvas_width.into());
+    let bottom = (y + height).min(canvas_height.into());
+
+    if x >= right || y >= bottom {
+        return;
+    }
+
+    // clipped to the canvas, so the coordinates and sizes fit
+    let rect = Rect::at(x as i32, y as i32).of_size((right - x) as u32, (bottom - y) as u32);
+    draw_filled_rect_mut(canvas, rect, color);
+}
+
+/// The result has an alpha channel if the image has one.
+fn into_dynamic_image(canvas: RgbaImage, image: &DynamicImage) -> DynamicImage {
+    let canvas = DynamicImage::ImageRgba8(canvas);
+
+    if image.color().has_alpha() {
+        canvas
+    } else {
+        DynamicImage::ImageRgb8(canvas.to_rgb())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage};
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    fn white(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([255, 255, 255])))
+    }
+
+    // the pixels of the image as rows of 'x' (red) and '.' (white)
+    fn to_rows(image: &DynamicImage) -> Vec<String> {
+        image
+            .as_rgb8()
+            .unwrap()
+            .rows()
+            .map(|row| row.map(|px| if px[1] == 0 { 'x' } else { '.' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn filled() {
+        let inputs = DrawRectInputs::new((1, 1, 3, 2), RED, ShapeStyle::Filled);
+
+        let out = draw_rect(&white(5, 4), &inputs).unwrap();
+
+        assert_eq!(to_rows(&out), vec![".....", ".xxx.", ".xxx.", "....."]);
+    }
+
+    #[test]
+    fn stroke() {
+        let inputs = DrawRectInputs::new((0, 0, 5, 4), RED, ShapeStyle::Stroke(1));
+
+        let out = draw_rect(&white(6, 5), &inputs).unwrap();
+
+        assert_eq!(
+            to_rows(&out),
+            vec!["xxxxx.", "x...x.", "x...x.", "xxxxx.", "......"]
+        );
+    }
+
+    #[test]
+    fn wide_stroke_fills() {
+        let inputs = DrawRectInputs::new((0, 0, 4, 3), RED, ShapeStyle::Stroke(2));
+
+        let out = draw_rect(&white(4, 3), &inputs).unwrap();
+
+        assert_eq!(to_rows(&out), vec!["xxxx", "xxxx", "xxxx"]);
+    }
+
+    #[test]
+    fn cut_off_at_edges() {
+        let inputs = DrawRectInputs::new((2, 1, u32::MAX, u32::MAX), RED, ShapeStyle::Filled);
+
+        let out = draw_rect(&white(4, 3), &inputs).unwrap();
+
+        assert_eq!(to_rows(&out), vec!["....", "..xx", "..xx"]);
+    }
+
+    #[test]
+    fn translucent_color_blends() {
+        let inputs = DrawRectInputs::new((0, 0, 1, 1), Rgba([0, 0, 0, 128]), ShapeStyle::Filled);
+
+        let out = draw_rect(&white(1, 1), &inputs).unwrap();
+
+        assert_eq!(
+            out.as_rgb8().unwrap().get_pixel(0, 0),
+            &Rgb([127, 127, 127])
+        );
+    }
+
+    #[test]
+    fn empty_rect() {
+        let inputs = DrawRectInputs::new((0, 0, 0, 1), RED, ShapeStyle::Filled);
+
+        assert!(draw_rect(&white(2, 2), &inputs).is_err());
+    }
+
+    #[test]
+    fn zero_stroke() {
+        let inputs = DrawRectInputs::new((0, 0, 1, 1), RED, ShapeStyle::Stroke(0));
+
+        assert!(draw_rect(&white(2, 2), &inputs).is_err());
+    }
+
+    #[test]
+    fn filled_circle() {
+        let inputs = DrawEllipseInputs::new((2, 2), (2, 2), RED, ShapeStyle::Filled);
+
+        let out = draw_ellipse(&white(5, 5), &inputs).unwrap();
+
+        assert_eq!(
+            to_rows(&out),
+            vec!["..x..", ".xxx.", "xxxxx", ".xxx.", "..x.."]
+        );
+    }
+
+    #[test]
+    fn filled_ellipse() {
+        let inputs = DrawEllipseInputs::new((3, 1), (3, 1), RED, ShapeStyle::Filled);
+
+        let out = draw_ellipse(&white(7, 3), &inputs).unwrap();
+
+        assert_eq!(to_rows(&out), vec!["...x...", "xxxxxxx", "...x..."]);
+    }
+
+    #[test]
+    fn outlined_ellipse() {
+        let inputs = DrawEllipseInputs::new((3, 3), (3, 3), RED, ShapeStyle::Stroke(1));
+
+        let out = draw_ellipse(&white(7, 7), &inputs).unwrap();
+        let rows = to_rows(&out);
+
+        assert_eq!(rows[0], "...x...");
+        assert_eq!(rows[1], ".xx.xx.");
+        assert_eq!(rows[3], "x.....x");
+        assert_eq!(rows[6], "...x...");
+    }
+
+    #[test]
+    fn ellipse_wide_stroke_fills() {
+        let filled = DrawEllipseInputs::new((3, 3), (3, 2), RED, ShapeStyle::Filled);
+        let stroke = DrawEllipseInputs::new((3, 3), (3, 2), RED, ShapeStyle::Stroke(2));
+
+        assert_eq!(
+            to_rows(&draw_ellipse(&white(7, 7), &stroke).unwrap()),
+            to_rows(&draw_ellipse(&white(7, 7), &filled).unwrap())
+        );
+    }
+
+    #[test]
+    fn ellipse_cut_off_at_edges() {
+        let inputs = DrawEllipseInputs::new((0, 0), (2, 2), RED, ShapeStyle::Filled);
+
+        let out = draw_ellipse(&white(4, 4), &inputs).unwrap();
+
+        assert_eq!(to_rows(&out), vec!["xxx.", "xx..", "x...", "...."]);
+    }
+
+    #[test]
+    fn large_ellipse() {
+        let inputs = DrawEllipseInputs::new((2, 2), (u32::MAX, 300), RED, ShapeStyle::Stroke(1));
+
+        let out = draw_ellipse(&white(4, 4), &inputs).unwrap();
+
+        assert!(to_rows(&out).iter().all(|row| row == "...."));
+    }
+
+    #[test]
+    fn zero_radius() {
+        let inputs = DrawEllipseInputs::new((1, 1), (0, 1), RED, ShapeStyle::Filled);
+
+        assert!(draw_ellipse(&white(2, 2), &inputs).is_err());
+    }
+
+    #[test]
+    fn filled_triangle() {
+        let inputs =
+            DrawPolygonInputs::new(vec![(0, 0), (4, 0), (0, 4)], RED, ShapeStyle::Filled).unwrap();
+
+        let out = draw_polygon(&white(5, 5), &inputs).unwrap();
+
+        assert_eq!(
+            to_rows(&out),
+            vec!["xxxxx", "xxxx.", "xxx..", "xx...", "x...."]
+        );
+    }
+
+    #[test]
+    fn outlined_square() {
+        let inputs = DrawPolygonInputs::new(
+            vec![(1, 1), (4, 1), (4, 4), (1, 4)],
+            RED,
+            ShapeStyle::Stroke(1),
+        )
+        .unwrap();
+
+        let out = draw_polygon(&white(6, 6), &inputs).unwrap();
+
+        assert_eq!(
+            to_rows(&out),
+            vec!["......", ".xxxx.", ".x..x.", ".x..x.", ".xxxx.", "......"]
+        );
+    }
+
+    #[test]
+    fn self_intersecting_polygon_even_odd() {
+        // a pentagram, of which the center is enclosed twice
+        let star = vec![(20, 0), (32, 38), (0, 14), (40, 14), (8, 38)];
+        let inputs = DrawPolygonInputs::new(star, RED, ShapeStyle::Filled).unwrap();
+
+        let out = draw_polygon(&white(41, 41), &inputs).unwrap();
+        let rows = to_rows(&out);
+
+        assert_eq!(rows[20].chars().nth(20), Some('.'));
+        assert_eq!(rows[5].chars().nth(20), Some('x'));
+    }
+
+    #[test]
+    fn too_few_points() {
+        assert!(DrawPolygonInputs::new(vec![(0, 0), (1, 1)], RED, ShapeStyle::Filled).is_err());
+    }
+
+    #[test]
+    fn line() {
+        let inputs = DrawLineInputs::new((0, 1), (3, 1), RED, 1);
+
+        let out = draw_line(&white(5, 3), &inputs).unwrap();
+
+        assert_eq!(to_rows(&out), vec![".....", "xxxx.", "....."]);
+    }
+
+    #[test]
+    fn diagonal_line() {
+        let inputs = DrawLineInputs::new((3, 3), (0, 0), RED, 1);
+
+        let out = draw_line(&white(4, 4), &inputs).unwrap();
+
+        assert_eq!(to_rows(&out), vec!["x...", ".x..", "..x.", "...x"]);
+    }
+
+    #[test]
+    fn wide_line_has_round_ends() {
+        let inputs = DrawLineInputs::new((1, 2), (4, 2), RED, 2);
+
+        let out = draw_line(&white(6, 4), &inputs).unwrap();
+
+        assert_eq!(to_rows(&out), vec!["......", "xxxxx.", "xxxxx.", "......"]);
+    }
+
+    #[test]
+    fn line_cut_off_at_edges() {
+        let inputs = DrawLineInputs::new((1, 1), (u32::MAX, 1), RED, 3);
+
+        let out = draw_line(&white(4, 4), &inputs).unwrap();
+
+        assert_eq!(to_rows(&out), vec!["xxxx", "xxxx", "xxxx", "...."]);
+    }
+
+    #[test]
+    fn zero_width_line() {
+        let inputs = DrawLineInputs::new((0, 0), (1, 1), RED, 0);
+
+        assert!(draw_line(&white(2, 2), &inputs).is_err());
+        assert!(draw_arrow(&white(2, 2), &inputs).is_err());
+    }
+
+    #[test]
+    fn arrow() {
+        let inputs = DrawLineInputs::new((0, 4), (14, 4), RED, 1);
+
+        let out = draw_arrow(&white(16, 9), &inputs).unwrap();
+        let rows = to_rows(&out);
+
+        // the shaft, and the head which ends at the tip
+        assert_eq!(rows[4], "xxxxxxxxxxxxxxx.");
+        // the head is wider than the shaft
+        assert_eq!(rows[1], ".....xxxx.......");
+        assert_eq!(rows[3], ".....xxxxxxxx...");
+    }
+
+    #[test]
+    fn translucent_arrow_blends_once() {
+        let inputs = DrawLineInputs::new((0, 4), (14, 4), Rgba([0, 0, 0, 128]), 1);
+
+        let out = draw_arrow(&white(16, 9), &inputs).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        // where the shaft meets the head, and in the head
+        assert_eq!(out.get_pixel(5, 4), &Rgb([127, 127, 127]));
+        assert_eq!(out.get_pixel(10, 4), &Rgb([127, 127, 127]));
+    }
+
+    #[test]
+    fn zero_length_arrow() {
+        let inputs = DrawLineInputs::new((1, 1), (1, 1), RED, 1);
+
+        assert!(draw_arrow(&white(2, 2), &inputs).is_err());
+        assert!(draw_line(&white(2, 2), &inputs).is_ok());
+    }
+}
@@ -0,0 +1,1199 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::color_font::glyph_bitmap;
+use crate::operations::font::{is_invisible, LoadedFont};
+use crate::wrapper::font_options::{FontOptions, TextAlign};
+use rusttype::{point, PositionedGlyph, Rect, ScaledGlyph};
+use sic_core::image::{imageops, DynamicImage, Rgba, RgbaImage};
+
+/// The largest outline width we accept; wider outlines would swallow the glyphs.
+const MAX_OUTLINE_WIDTH: f32 = 64.0;
+
+/// Draws text with its top left corner at the given coordinate, which may lie outside of the
+/// image. Lines are separated by newlines,
+/// and if the font options specify a wrap width, lines which are wider are wrapped between words.
+/// If the font options specify an angle, the text is rotated clockwise around its center.
+///
+/// Characters which the font has no glyph for are drawn with the first of the fallback fonts
+/// which has one. Color glyphs, like emoji, are drawn in their own colors, with the opacity of the
+/// text color.
+///
+/// The text is alpha blended onto the image. If the font options specify a shadow and an outline,
+/// the shadow is drawn first, then the outline and finally the glyphs themselves.
+pub(crate) fn draw_text(
+    image: &DynamicImage,
+    text: &str,
+    coord: (i32, i32),
+    options: &FontOptions,
+    font: &LoadedFont,
+    fallbacks: &[LoadedFont],
+) -> Result<DynamicImage, SicImageEngineError> {
+    let scale = options.scale;
+    if let Some(size) = [scale.x, scale.y]
+        .iter()
+        .find(|size| !(**size > 0.0 && size.is_finite()))
+    {
+        return Err(SicImageEngineError::DrawTextInvalidSize(*size));
+    }
+
+    if let Some(outline) = options.outline {
+        if !(outline.width > 0.0 && outline.width <= MAX_OUTLINE_WIDTH) {
+            return Err(SicImageEngineError::DrawTextInvalidOutlineWidth(
+                outline.width,
+            ));
+        }
+    }
+
+    if let Some(width) = options.wrap_width {
+        if !(width > 0.0 && width.is_finite()) {
+            return Err(SicImageEngineError::DrawTextInvalidWrapWidth(width));
+        }
+    }
+
+    let mut canvas = image.to_rgba();
+
+    let fonts = Fonts { font, fallbacks };
+    let glyphs = layout(text, coord, options, &fonts);
+
+    let (glyphs, paint) = match rasterize(&shapes(glyphs, options), options.color) {
+        Some((mask, paint)) if options.angle % 360.0 != 0.0 => {
+            (rotate(&mask, options.angle), rotate(&paint, options.angle))
+        }
+        Some(layers) => layers,
+        None => return Ok(DynamicImage::ImageRgba8(canvas)),
+    };
+
+    let outline = options
+        .outline
+        .map(|outline| (dilate(&glyphs, outline.width), outline.color));
+
+    if let Some(shadow) = options.shadow {
+        let cast_by = outline.as_ref().map(|(mask, _)| mask).unwrap_or(&glyphs);
+        blend(&mut canvas, cast_by, shadow.color, shadow.offset);
+    }
+
+    if let Some((mask, color)) = &outline {
+        blend(&mut canvas, mask, *color, (0, 0));
+    }
+
+    for (x, y, value) in paint.covered() {
+        if let Some(pixel) = pixel_mut(&mut canvas, x, y) {
+            composite(pixel, value);
+        }
+    }
+
+    Ok(DynamicImage::ImageRgba8(canvas))
+}
+
+/// The size of the box which [`draw_text`] lays out the text in, before it's rotated: as wide as
+/// the wrap width, or else as the widest line, and as high as the lines from the ascent of the
+/// first line to the descent of the last line. Glyphs may reach slightly beyond the box.
+pub(crate) fn text_size(
+    text: &str,
+    options: &FontOptions,
+    font: &LoadedFont,
+    fallbacks: &[LoadedFont],
+) -> (u32, u32) {
+    let fonts = Fonts { font, fallbacks };
+    let metrics = font.font.v_metrics(options.scale);
+    let lines = lines(text, options, &fonts);
+
+    let height = match lines.len() {
+        0 => 0.0,
+        n => metrics.ascent - metrics.descent + (n - 1) as f32 * line_height(options, &fonts),
+    };
+
+    (
+        box_width(&lines, options).ceil() as u32,
+        height.ceil() as u32,
+    )
+}
+
+/// The font to draw text with, and the fonts to fall back to for characters it has no glyph for.
+struct Fonts<'a> {
+    font: &'a LoadedFont,
+    fallbacks: &'a [LoadedFont],
+}
+
+impl<'a> Fonts<'a> {
+    /// The first font which has a glyph for the character; the font itself if none has one, so
+    /// its replacement glyph is drawn.
+    fn for_char(&self, c: char) -> &'a LoadedFont {
+        std::iter::once(self.font)
+            .chain(self.fallbacks)
+            .find(|font| font.has_glyph(c))
+            .unwrap_or(self.font)
+    }
+}
+
+/// A value of a pixel of a [`Layer`].
+trait Sample: Copy + Default + PartialEq {
+    /// Combines the value with a value which is drawn on top of it.
+    fn combine(self, top: Self) -> Self;
+
+    /// Linearly interpolates between the value, at `t = 0`, and the other value, at `t = 1`.
+    fn lerp(self, other: Self, t: f32) -> Self;
+
+    /// Limits the value to its valid range, after it was interpolated.
+    fn saturate(self) -> Self;
+}
+
+/// The coverage of a pixel, between 0 and 1. Where glyphs overlap, the largest coverage is taken.
+impl Sample for f32 {
+    fn combine(self, top: Self) -> Self {
+        self.max(top)
+    }
+
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self * (1.0 - t) + other * t
+    }
+
+    fn saturate(self) -> Self {
+        self.min(1.0)
+    }
+}
+
+/// A color, with premultiplied alpha; all channels are between 0 and 1.
+impl Sample for [f32; 4] {
+    fn combine(self, top: Self) -> Self {
+        let [r, g, b, a] = self;
+        let below = 1.0 - top[3];
+
+        [
+            top[0] + r * below,
+            top[1] + g * below,
+            top[2] + b * below,
+            top[3] + a * below,
+        ]
+    }
+
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let [r, g, b, a] = self;
+
+        [
+            r * (1.0 - t) + other[0] * t,
+            g * (1.0 - t) + other[1] * t,
+            b * (1.0 - t) + other[2] * t,
+            a * (1.0 - t) + other[3] * t,
+        ]
+    }
+
+    fn saturate(self) -> Self {
+        let alpha = self[3].min(1.0);
+        let [r, g, b, _] = self;
+
+        [r.min(alpha), g.min(alpha), b.min(alpha), alpha]
+    }
+}
+
+/// A rectangle of the image which starts at `(x, y)`, with a value for each pixel; the rectangle
+/// may extend beyond the image.
+struct Layer<T> {
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    values: Vec<T>,
+}
+
+/// The coverage of the text.
+type Mask = Layer<f32>;
+
+/// The colors of the text.
+type Paint = Layer<[f32; 4]>;
+
+impl<T: Sample> Layer<T> {
+    fn new(x: i32, y: i32, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            values: vec![T::default(); width * height],
+        }
+    }
+
+    /// Value at an image coordinate; the default value outside of the layer.
+    fn get(&self, x: i32, y: i32) -> T {
+        let (x, y) = (x - self.x, y - self.y);
+
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            T::default()
+        } else {
+            self.values[y as usize * self.width + x as usize]
+        }
+    }
+
+    fn cover(&mut self, x: i32, y: i32, value: T) {
+        let (x, y) = (x - self.x, y - self.y);
+
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            let index = y as usize * self.width + x as usize;
+            self.values[index] = self.values[index].combine(value);
+        }
+    }
+
+    /// The image coordinates and values of the covered pixels.
+    fn covered(&self) -> impl Iterator<Item = (i32, i32, T)> + '_ {
+        self.values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value != T::default())
+            .map(move |(index, value)| {
+                let x = self.x + (index % self.width) as i32;
+                let y = self.y + (index / self.width) as i32;
+
+                (x, y, *value)
+            })
+    }
+
+    /// Bilinearly interpolated value at a point of the image, where pixel centers lie at `.5`.
+    fn sample(&self, x: f32, y: f32) -> T {
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (left, top) = (x.floor(), y.floor());
+        let (fx, fy) = (x - left, y - top);
+        let (left, top) = (left as i32, top as i32);
+
+        let upper = self.get(left, top).lerp(self.get(left + 1, top), fx);
+        let lower = self
+            .get(left, top + 1)
+            .lerp(self.get(left + 1, top + 1), fx);
+
+        upper.lerp(lower, fy)
+    }
+}
+
+/// Positions the glyphs of each line of the text, taking into account kerning, the letter and
+/// line spacing, wrapping and alignment. Each glyph comes with the font it's taken from.
+fn layout<'a>(
+    text: &str,
+    (x, y): (i32, i32),
+    options: &FontOptions,
+    fonts: &Fonts<'a>,
+) -> Vec<(PositionedGlyph<'static>, &'a LoadedFont)> {
+    let metrics = fonts.font.font.v_metrics(options.scale);
+    let line_height = line_height(options, fonts);
+
+    let lines = lines(text, options, fonts);
+    let box_width = box_width(&lines, options);
+
+    let mut glyphs = Vec::new();
+
+    for (line_number, (line, width)) in lines.into_iter().enumerate() {
+        let indent = match options.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => (box_width - width) / 2.0,
+            TextAlign::Right => box_width - width,
+        };
+
+        let baseline = y as f32 + metrics.ascent + line_number as f32 * line_height;
+
+        glyphs.extend(line.into_iter().map(|(glyph, font, caret)| {
+            let position = point(x as f32 + indent + caret, baseline);
+
+            (glyph.positioned(position), font)
+        }));
+    }
+
+    glyphs
+}
+
+/// The distance between the baselines of two lines.
+fn line_height(options: &FontOptions, fonts: &Fonts) -> f32 {
+    let metrics = fonts.font.font.v_metrics(options.scale);
+
+    metrics.ascent - metrics.descent + metrics.line_gap + options.line_spacing
+}
+
+/// The lines of the text, after wrapping, each with its glyphs and its width.
+fn lines<'a>(text: &str, options: &FontOptions, fonts: &Fonts<'a>) -> Vec<Line<'a>> {
+    text.lines()
+        .flat_map(|line| match options.wrap_width {
+            Some(width) => wrap(line, width, options, fonts),
+            None => vec![line.to_string()],
+        })
+        .map(|line| {
+            let glyphs = layout_line(&line, options, fonts);
+            let width = line_width(&glyphs);
+
+            (glyphs, width)
+        })
+        .collect()
+}
+
+/// The width of the box the lines are aligned in: the wrap width, or else the widest line.
+fn box_width(lines: &[Line], options: &FontOptions) -> f32 {
+    options
+        .wrap_width
+        .unwrap_or_else(|| lines.iter().map(|(_, width)| *width).fold(0.0, f32::max))
+}
+
+/// The glyphs of a line, each with its font and its position on the line, and the width of the
+/// line.
+type Line<'a> = (Vec<(ScaledGlyph<'static>, &'a LoadedFont, f32)>, f32);
+
+/// The glyphs of a line, each with its font and its position on the line. Kerning is only applied
+/// between glyphs of the same font.
+fn layout_line<'a>(
+    line: &str,
+    options: &FontOptions,
+    fonts: &Fonts<'a>,
+) -> Vec<(ScaledGlyph<'static>, &'a LoadedFont, f32)> {
+    let scale = options.scale;
+
+    let mut glyphs = Vec::new();
+    let mut caret = 0.0;
+    let mut previous: Option<(&LoadedFont, _)> = None;
+
+    for c in line.chars().filter(|&c| !is_invisible(c)) {
+        let font = fonts.for_char(c);
+        let glyph = font.font.glyph(c).scaled(scale);
+
+        if let Some((previous_font, previous)) = previous {
+            if std::ptr::eq(previous_font, font) {
+                caret += font.font.pair_kerning(scale, previous, glyph.id());
+            }
+        }
+
+        previous = Some((font, glyph.id()));
+        let advance = glyph.h_metrics().advance_width;
+        glyphs.push((glyph, font, caret));
+
+        caret += advance + options.letter_spacing;
+    }
+
+    glyphs
+}
+
+/// The width of a line, up to the advance of its last glyph; letter spacing after the last glyph
+/// is not counted.
+fn line_width(glyphs: &[(ScaledGlyph, &LoadedFont, f32)]) -> f32 {
+    glyphs.last().map_or(0.0, |(glyph, _, caret)| {
+        caret + glyph.h_metrics().advance_width
+    })
+}
+
+/// Breaks a line into lines which fit within the width, between words. Words are separated by
+/// whitespace, which is collapsed to a single space where the line is wrapped; a word which is
+/// wider than the width by itself is put on a line of its own.
+fn wrap(line: &str, width: f32, options: &FontOptions, fonts: &Fonts) -> Vec<String> {
+    let fits = |line: &str| line_width(&layout_line(line, options, fonts)) <= width;
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in line.split_whitespace() {
+        if current.is_empty() {
+            current.push_str(word);
+        } else if fits(&format!("{} {}", current, word)) {
+            current.push(' ');
+            current.push_str(word);
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+
+    lines.push(current);
+    lines
+}
+
+/// What a glyph is drawn as.
+enum Shape {
+    /// The outline of a glyph, filled with a color; `None` for the color of the text.
+    Outline(PositionedGlyph<'static>, Option<Rgba<u8>>),
+    /// A color bitmap, with its top left corner at a pixel of the image.
+    Bitmap(i32, i32, RgbaImage),
+}
+
+impl Shape {
+    fn bounds(&self) -> Option<Rect<i32>> {
+        match self {
+            Shape::Outline(glyph, _) => glyph.pixel_bounding_box(),
+            Shape::Bitmap(x, y, image) => Some(Rect {
+                min: point(*x, *y),
+                max: point(*x + image.width() as i32, *y + image.height() as i32),
+            }),
+        }
+    }
+}
+
+/// Finds out what each glyph is drawn as: a color bitmap, the layers of a color glyph, or just
+/// the outline of the glyph.
+fn shapes(
+    glyphs: Vec<(PositionedGlyph<'static>, &LoadedFont)>,
+    options: &FontOptions,
+) -> Vec<Shape> {
+    let mut shapes = Vec::with_capacity(glyphs.len());
+
+    for (glyph, font) in glyphs {
+        let pixels_per_em = options.scale.y.round().max(1.0).min(f32::from(u16::MAX)) as u16;
+
+        if let Some(bitmap) = glyph_bitmap(&font.font, glyph.id(), pixels_per_em) {
+            // scale the bitmap from the size it was made for, to the size of the text
+            let scale_x = options.scale.x / f32::from(bitmap.pixels_per_em);
+            let scale_y = options.scale.y / f32::from(bitmap.pixels_per_em);
+
+            let (width, height) = bitmap.image.dimensions();
+            let (offset_x, offset_y) = bitmap.offset;
+            let origin = glyph.position();
+
+            let left = origin.x + f32::from(offset_x) * scale_x;
+            let top = origin.y - (f32::from(offset_y) + height as f32) * scale_y;
+            let width = (width as f32 * scale_x).round().max(1.0) as u32;
+            let height = (height as f32 * scale_y).round().max(1.0) as u32;
+
+            shapes.push(Shape::Bitmap(
+                left.round() as i32,
+                top.round() as i32,
+                imageops::resize(&bitmap.image, width, height, imageops::FilterType::Triangle),
+            ));
+        } else if let Some(layers) = font
+            .color_layers
+            .as_ref()
+            .and_then(|color_layers| color_layers.layers(glyph.id()))
+        {
+            let position = glyph.position();
+
+            shapes.extend(layers.iter().map(|layer| {
+                let glyph = font
+                    .font
+                    .glyph(layer.glyph)
+                    .scaled(options.scale)
+                    .positioned(position);
+
+                Shape::Outline(glyph, layer.color)
+            }));
+        } else {
+            shapes.push(Shape::Outline(glyph, None));
+        }
+    }
+
+    shapes
+}
+
+/// Rasterizes the shapes into a mask of their coverage and a layer of their colors, which fit
+/// the shapes exactly; `None` if no shape covers any pixel, e.g. if the text consists of whitespace
+/// only. The opacity of the color of the text applies to each shape.
+fn rasterize(shapes: &[Shape], color: Rgba<u8>) -> Option<(Mask, Paint)> {
+    let bounds =
+        shapes
+            .iter()
+            .filter_map(Shape::bounds)
+            .fold(None, |union: Option<Rect<i32>>, rect| match union {
+                Some(union) => Some(Rect {
+                    min: point(union.min.x.min(rect.min.x), union.min.y.min(rect.min.y)),
+                    max: point(union.max.x.max(rect.max.x), union.max.y.max(rect.max.y)),
+                }),
+                None => Some(rect),
+            })?;
+
+    let (x, y) = (bounds.min.x, bounds.min.y);
+    let (width, height) = (bounds.width() as usize, bounds.height() as usize);
+
+    let mut mask = Mask::new(x, y, width, height);
+    let mut paint = Paint::new(x, y, width, height);
+
+    let opacity = f32::from(color[3]) / 255.0;
+
+    for shape in shapes {
+        match shape {
+            Shape::Outline(glyph, fill) => {
+                let fill = fill.map_or(color, |fill| {
+                    Rgba([
+                        fill[0],
+                        fill[1],
+                        fill[2],
+                        (f32::from(fill[3]) * opacity).round() as u8,
+                    ])
+                });
+
+                if let Some(bounds) = glyph.pixel_bounding_box() {
+                    glyph.draw(|gx, gy, value| {
+                        let (x, y) = (bounds.min.x + gx as i32, bounds.min.y + gy as i32);
+
+                        mask.cover(x, y, value);
+                        paint.cover(x, y, premultiplied(fill, value));
+                    });
+                }
+            }
+            Shape::Bitmap(left, top, image) => {
+                for (bx, by, pixel) in image.enumerate_pixels() {
+                    let (x, y) = (left + bx as i32, top + by as i32);
+
+                    mask.cover(x, y, f32::from(pixel[3]) / 255.0);
+                    paint.cover(x, y, premultiplied(*pixel, opacity));
+                }
+            }
+        }
+    }
+
+    Some((mask, paint))
+}
+
+/// Rotates the layer clockwise by an angle in degrees, around its center. The rotated layer is
+/// expanded to the bounding box of the rotated text, so no part of the text is cut off.
+fn rotate<T: Sample>(layer: &Layer<T>, degrees: f32) -> Layer<T> {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+
+    let center_x = layer.x as f32 + layer.width as f32 / 2.0;
+    let center_y = layer.y as f32 + layer.height as f32 / 2.0;
+
+    // half of the width and height of the bounding box of the rotated layer
+    let half_width = (layer.width as f32 * cos.abs() + layer.height as f32 * sin.abs()) / 2.0;
+    let half_height = (layer.width as f32 * sin.abs() + layer.height as f32 * cos.abs()) / 2.0;
+
+    // one extra pixel on each side, for the edges smoothed by interpolation
+    let left = (center_x - half_width).floor() as i32 - 1;
+    let top = (center_y - half_height).floor() as i32 - 1;
+    let right = (center_x + half_width).ceil() as i32 + 1;
+    let bottom = (center_y + half_height).ceil() as i32 + 1;
+
+    let mut rotated = Layer::new(left, top, (right - left) as usize, (bottom - top) as usize);
+
+    for y in top..bottom {
+        for x in left..right {
+            // rotate the center of the pixel back, counter-clockwise, to find where it came from
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+
+            let value = layer.sample(
+                center_x + dx * cos + dy * sin,
+                center_y - dx * sin + dy * cos,
+            );
+
+            if value != T::default() {
+                rotated.cover(x, y, value.saturate());
+            }
+        }
+    }
+
+    rotated
+}
+
+/// Grows the coverage by the given radius, with an anti-aliased edge.
+///
+/// The edge of a glyph is estimated to lie at `0.5 - coverage` pixels beyond the center of a
+/// partially covered pixel, so the outline stays smooth where the glyph itself was anti-aliased.
+fn dilate(mask: &Mask, radius: f32) -> Mask {
+    let reach = radius.ceil() as i32 + 1;
+
+    let kernel = (-reach..=reach)
+        .flat_map(|dy| (-reach..=reach).map(move |dx| (dx, dy)))
+        .map(|(dx, dy)| (dx, dy, ((dx * dx + dy * dy) as f32).sqrt()))
+        .filter(|(_, _, distance)| *distance < radius + 1.0)
+        .collect::<Vec<_>>();
+
+    let mut dilated = Mask::new(
+        mask.x - reach,
+        mask.y - reach,
+        mask.width + 2 * reach as usize,
+        mask.height + 2 * reach as usize,
+    );
+
+    for (x, y, value) in mask.covered() {
+        for (dx, dy, distance) in kernel.iter() {
+            let coverage = (radius + value - distance).min(1.0);
+
+            if coverage > 0.0 {
+                dilated.cover(x + dx, y + dy, coverage);
+            }
+        }
+    }
+
+    dilated
+}
+
+/// Blends the color onto the canvas, weighted by the coverage of the mask, which is moved by the
+/// offset first.
+fn blend(canvas: &mut RgbaImage, mask: &Mask, color: Rgba<u8>, (dx, dy): (i32, i32)) {
+    for (x, y, coverage) in mask.covered() {
+        if let Some(pixel) = pixel_mut(canvas, x + dx, y + dy) {
+            composite(pixel, premultiplied(color, coverage));
+        }
+    }
+}
+
+/// The pixel at an image coordinate, if it lies within the canvas.
+fn pixel_mut(canvas: &mut RgbaImage, x: i32, y: i32) -> Option<&mut Rgba<u8>> {
+    let (width, height) = canvas.dimensions();
+
+    if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height {
+        None
+    } else {
+        Some(canvas.get_pixel_mut(x as u32, y as u32))
+    }
+}
+
+/// The color with premultiplied alpha, after its alpha is multiplied by the coverage.
+fn premultiplied(color: Rgba<u8>, coverage: f32) -> [f32; 4] {
+    let alpha = coverage * f32::from(color[3]) / 255.0;
+    let channel = |channel: u8| f32::from(channel) / 255.0 * alpha;
+
+    [
+        channel(color[0]),
+        channel(color[1]),
+        channel(color[2]),
+        alpha,
+    ]
+}
+
+/// Blends a color with premultiplied alpha onto the pixel.
+fn composite(pixel: &mut Rgba<u8>, [r, g, b, alpha]: [f32; 4]) {
+    let background_alpha = f32::from(pixel[3]) / 255.0;
+    let out_alpha = alpha + background_alpha * (1.0 - alpha);
+
+    if out_alpha <= 0.0 {
+        return;
+    }
+
+    for (channel, value) in [r, g, b].iter().enumerate() {
+        let value = (value * 255.0 + f32::from(pixel[channel]) * background_alpha * (1.0 - alpha))
+            / out_alpha;
+        pixel[channel] = value.round().min(255.0) as u8;
+    }
+
+    pixel[3] = (out_alpha * 255.0).round() as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wrapper::font_options::{FontScale, TextOutline, TextShadow};
+    use sic_core::image::GenericImageView;
+    use sic_testing::*;
+    use std::path::PathBuf;
+
+    ide!();
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    fn font() -> LoadedFont {
+        LoadedFont::from_data(std::fs::read(in_!("font/Lato-Regular.ttf")).unwrap(), 0).unwrap()
+    }
+
+    fn options(color: Rgba<u8>) -> FontOptions {
+        FontOptions::new(
+            PathBuf::from(in_!("font/Lato-Regular.ttf")),
+            color,
+            FontScale::Uniform(24.0),
+        )
+    }
+
+    fn canvas() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 64, WHITE))
+    }
+
+    fn count(image: &DynamicImage, color: Rgba<u8>) -> usize {
+        image
+            .pixels()
+            .filter(|(_, _, pixel)| *pixel == color)
+            .count()
+    }
+
+    #[test]
+    fn draws_text() {
+        let out = draw_text(&canvas(), "I", (10, 10), &options(BLACK), &font(), &[]).unwrap();
+
+        assert!(count(&out, BLACK) > 0);
+        assert_eq!(out.get_pixel(0, 0), WHITE);
+    }
+
+    #[test]
+    fn text_size_grows_with_lines() {
+        let (width, height) = text_size("II", &options(BLACK), &font(), &[]);
+        let (narrow, two_lines) = text_size("I\nI", &options(BLACK), &font(), &[]);
+
+        assert!(width > narrow);
+        assert!(two_lines > height);
+        assert_eq!(text_size("", &options(BLACK), &font(), &[]), (0, 0));
+    }
+
+    #[test]
+    fn coordinate_left_of_and_above_image() {
+        let out = draw_text(
+            &canvas(),
+            "I",
+            (-1000, -1000),
+            &options(BLACK),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(count(&out, BLACK), 0);
+    }
+
+    #[test]
+    fn translucent_text_is_blended() {
+        let color = Rgba([0, 0, 0, 128]);
+        let out = draw_text(&canvas(), "I", (10, 10), &options(color), &font(), &[]).unwrap();
+
+        assert_eq!(count(&out, BLACK), 0);
+        assert!(out
+            .pixels()
+            .any(|(_, _, pixel)| pixel == Rgba([127, 127, 127, 255])));
+    }
+
+    #[test]
+    fn outline_surrounds_text() {
+        let outline = TextOutline {
+            width: 2.0,
+            color: RED,
+        };
+        let out = draw_text(
+            &canvas(),
+            "I",
+            (10, 10),
+            &options(BLACK).with_outline(outline),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        assert!(count(&out, BLACK) > 0);
+        assert!(count(&out, RED) > 0);
+    }
+
+    #[pm(width = { 0.0, -1.0, 65.0 })]
+    fn outline_invalid_width(width: f32) {
+        let outline = TextOutline { width, color: RED };
+        let result = draw_text(
+            &canvas(),
+            "I",
+            (10, 10),
+            &options(BLACK).with_outline(outline),
+            &font(),
+            &[],
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn shadow_is_offset() {
+        let shadow = TextShadow {
+            offset: (20, 0),
+            color: RED,
+        };
+        let plain = draw_text(&canvas(), "I", (10, 10), &options(BLACK), &font(), &[]).unwrap();
+        let out = draw_text(
+            &canvas(),
+            "I",
+            (10, 10),
+            &options(BLACK).with_shadow(shadow),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        // the shadow doesn't overlap the glyph, so each black pixel has a red twin
+        assert_eq!(count(&out, BLACK), count(&plain, BLACK));
+        assert_eq!(count(&out, RED), count(&plain, BLACK));
+    }
+
+    #[test]
+    fn letter_spacing_widens_text() {
+        let right_most = |image: &DynamicImage| {
+            image
+                .pixels()
+                .filter(|(_, _, pixel)| *pixel != WHITE)
+                .map(|(x, _, _)| x)
+                .max()
+        };
+
+        let plain = draw_text(&canvas(), "II", (0, 0), &options(BLACK), &font(), &[]).unwrap();
+        let spaced = draw_text(
+            &canvas(),
+            "II",
+            (0, 0),
+            &options(BLACK).with_spacing(10.0, 0.0),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            right_most(&spaced).unwrap(),
+            right_most(&plain).unwrap() + 10
+        );
+    }
+
+    #[test]
+    fn line_spacing_moves_lines_down() {
+        let bottom_most = |image: &DynamicImage| {
+            image
+                .pixels()
+                .filter(|(_, _, pixel)| *pixel != WHITE)
+                .map(|(_, y, _)| y)
+                .max()
+        };
+
+        let plain = draw_text(&canvas(), "I\nI", (0, 0), &options(BLACK), &font(), &[]).unwrap();
+        let spaced = draw_text(
+            &canvas(),
+            "I\nI",
+            (0, 0),
+            &options(BLACK).with_spacing(0.0, 5.0),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            bottom_most(&spaced).unwrap(),
+            bottom_most(&plain).unwrap() + 5
+        );
+    }
+
+    fn ink_bounds(image: &DynamicImage) -> (u32, u32, u32, u32) {
+        let ink = image
+            .pixels()
+            .filter(|(_, _, pixel)| *pixel != WHITE)
+            .map(|(x, y, _)| (x, y))
+            .collect::<Vec<_>>();
+
+        (
+            ink.iter().map(|(x, _)| *x).min().unwrap(),
+            ink.iter().map(|(_, y)| *y).min().unwrap(),
+            ink.iter().map(|(x, _)| *x).max().unwrap(),
+            ink.iter().map(|(_, y)| *y).max().unwrap(),
+        )
+    }
+
+    #[pm(angle = { 0.0, 360.0, -720.0 })]
+    fn angle_full_turn_is_unrotated(angle: f32) {
+        let plain = draw_text(&canvas(), "Il", (10, 10), &options(BLACK), &font(), &[]).unwrap();
+        let rotated = draw_text(
+            &canvas(),
+            "Il",
+            (10, 10),
+            &options(BLACK).with_angle(angle),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(plain.to_bytes(), rotated.to_bytes());
+    }
+
+    #[test]
+    fn angle_rotates_around_center() {
+        let plain = draw_text(&canvas(), "IIIII", (10, 20), &options(BLACK), &font(), &[]).unwrap();
+        let rotated = draw_text(
+            &canvas(),
+            "IIIII",
+            (10, 20),
+            &options(BLACK).with_angle(90.0),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        let (left, top, right, bottom) = ink_bounds(&plain);
+        let (rotated_left, rotated_top, rotated_right, rotated_bottom) = ink_bounds(&rotated);
+
+        // the wide text becomes tall, and extends beyond the bounds of the unrotated text
+        let (width, height) = (right - left, bottom - top);
+        let (rotated_width, rotated_height) =
+            (rotated_right - rotated_left, rotated_bottom - rotated_top);
+
+        assert!(width > height);
+        assert!((rotated_height as i32 - width as i32).abs() <= 2);
+        assert!((rotated_width as i32 - height as i32).abs() <= 2);
+        assert!(rotated_top < top && rotated_bottom > bottom);
+
+        // the center stays in place
+        assert!(((rotated_left + rotated_right) as i32 - (left + right) as i32).abs() <= 2);
+        assert!(((rotated_top + rotated_bottom) as i32 - (top + bottom) as i32).abs() <= 2);
+    }
+
+    #[test]
+    fn wrap_breaks_between_words() {
+        let font = font();
+        let fonts = Fonts {
+            font: &font,
+            fallbacks: &[],
+        };
+        let options = options(BLACK);
+
+        // the width of "Il Il" fits two words, but not three
+        let width = line_width(&layout_line("Il Il", &options, &fonts));
+        let lines = wrap("Il  Il Il", width, &options, &fonts);
+
+        assert_eq!(lines, vec!["Il Il".to_string(), "Il".to_string()]);
+    }
+
+    #[test]
+    fn wrap_keeps_long_words_whole() {
+        let font = font();
+        let fonts = Fonts {
+            font: &font,
+            fallbacks: &[],
+        };
+        let lines = wrap("IIIIII I", 1.0, &options(BLACK), &fonts);
+
+        assert_eq!(lines, vec!["IIIIII".to_string(), "I".to_string()]);
+    }
+
+    #[test]
+    fn wrapped_text_is_drawn_on_multiple_lines() {
+        let one_line =
+            draw_text(&canvas(), "I I I", (0, 0), &options(BLACK), &font(), &[]).unwrap();
+        let wrapped = draw_text(
+            &canvas(),
+            "I I I",
+            (0, 0),
+            &options(BLACK).with_wrap_width(1.0),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        let (_, _, right, bottom) = ink_bounds(&one_line);
+        let (_, _, wrapped_right, wrapped_bottom) = ink_bounds(&wrapped);
+
+        assert!(wrapped_right < right);
+        assert!(wrapped_bottom > bottom);
+    }
+
+    #[pm(width = { 0.0, -1.0 })]
+    fn wrap_invalid_width(width: f32) {
+        let result = draw_text(
+            &canvas(),
+            "I",
+            (10, 10),
+            &options(BLACK).with_wrap_width(width),
+            &font(),
+            &[],
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[pm(size = { 0.0, -3.0, f32::NAN, f32::INFINITY })]
+    fn invalid_size(size: f32) {
+        let options = FontOptions::new(
+            PathBuf::from(in_!("font/Lato-Regular.ttf")),
+            BLACK,
+            FontScale::Uniform(size),
+        );
+        let result = draw_text(&canvas(), "hi", (10, 10), &options, &font(), &[]);
+
+        assert!(matches!(
+            result,
+            Err(SicImageEngineError::DrawTextInvalidSize(_))
+        ));
+    }
+
+    #[test]
+    fn align_relative_to_widest_line() {
+        let draw = |align| {
+            let options = options(BLACK).with_align(align);
+            ink_bounds(&draw_text(&canvas(), "IIIII\nI", (0, 0), &options, &font(), &[]).unwrap())
+        };
+
+        // the bounds span the widest line, whatever the alignment
+        let (left, _, right, _) = draw(TextAlign::Left);
+        assert_eq!(draw(TextAlign::Center).0, left);
+        assert_eq!(draw(TextAlign::Right).2, right);
+    }
+
+    #[test]
+    fn align_moves_short_lines() {
+        // only the short second line is drawn below the first line, so look at the bottom rows
+        let bottom_left = |align| {
+            let options = options(BLACK).with_align(align);
+            let out = draw_text(&canvas(), "IIIII\nI", (0, 0), &options, &font(), &[]).unwrap();
+            let (_, _, _, bottom) = ink_bounds(&out);
+
+            out.pixels()
+                .filter(|(_, y, pixel)| *y == bottom && *pixel != WHITE)
+                .map(|(x, _, _)| x)
+                .min()
+                .unwrap()
+        };
+
+        let left = bottom_left(TextAlign::Left);
+        let center = bottom_left(TextAlign::Center);
+        let right = bottom_left(TextAlign::Right);
+
+        assert!(left < center && center < right);
+    }
+
+    #[test]
+    fn align_within_wrap_width() {
+        let left = draw_text(
+            &canvas(),
+            "I",
+            (0, 0),
+            &options(BLACK).with_wrap_width(60.0),
+            &font(),
+            &[],
+        )
+        .unwrap();
+        let right = draw_text(
+            &canvas(),
+            "I",
+            (0, 0),
+            &options(BLACK)
+                .with_wrap_width(60.0)
+                .with_align(TextAlign::Right),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        assert!(ink_bounds(&left).0 < 10);
+        assert!(ink_bounds(&right).2 > 50);
+    }
+
+    /// Lato, with a COLR and CPAL table added, which make 'I' a color glyph: the glyph of 'I' in
+    /// red.
+    fn color_font() -> LoadedFont {
+        let mut data = std::fs::read(in_!("font/Lato-Regular.ttf")).unwrap();
+        let glyph = font().font.glyph('I').id().0.to_be_bytes();
+
+        let colr = [
+            &[0, 0, 0, 1, 0, 0, 0, 14, 0, 0, 0, 20, 0, 1][..],
+            &[glyph[0], glyph[1], 0, 0, 0, 1],
+            &[glyph[0], glyph[1], 0, 0],
+        ]
+        .concat();
+        let cpal = [0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 14, 0, 0, 0, 0, 255, 255];
+
+        // make room for two more table records, after the existing ones
+        let count = u16::from_be_bytes([data[4], data[5]]) as usize;
+        let end = 12 + 16 * count;
+        data.splice(end..end, vec![0; 32]);
+        data[4..6].copy_from_slice(&(count as u16 + 2).to_be_bytes());
+
+        for record in (0..count).map(|i| 12 + 16 * i) {
+            let offset = u32::from_be_bytes([
+                data[record + 8],
+                data[record + 9],
+                data[record + 10],
+                data[record + 11],
+            ]);
+            data[record + 8..record + 12].copy_from_slice(&(offset + 32).to_be_bytes());
+        }
+
+        for (i, (tag, table)) in [(b"COLR", &colr[..]), (b"CPAL", &cpal[..])]
+            .iter()
+            .enumerate()
+        {
+            let (record, offset) = (end + 16 * i, data.len() as u32);
+            data[record..record + 4].copy_from_slice(*tag);
+            data[record + 8..record + 12].copy_from_slice(&offset.to_be_bytes());
+            data[record + 12..record + 16].copy_from_slice(&(table.len() as u32).to_be_bytes());
+            data.extend_from_slice(table);
+        }
+
+        LoadedFont::from_data(data, 0).unwrap()
+    }
+
+    #[test]
+    fn color_glyph_keeps_its_colors() {
+        let out = draw_text(
+            &canvas(),
+            "I",
+            (10, 10),
+            &options(BLACK),
+            &color_font(),
+            &[],
+        )
+        .unwrap();
+
+        assert!(count(&out, RED) > 0);
+        assert_eq!(count(&out, BLACK), 0);
+    }
+
+    #[test]
+    fn color_glyph_takes_opacity_of_text() {
+        let color = Rgba([0, 0, 0, 128]);
+        let out = draw_text(
+            &canvas(),
+            "I",
+            (10, 10),
+            &options(color),
+            &color_font(),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(count(&out, RED), 0);
+        assert!(out
+            .pixels()
+            .any(|(_, _, pixel)| pixel == Rgba([255, 127, 127, 255])));
+    }
+
+    #[test]
+    fn font_is_preferred_over_fallbacks() {
+        let (font, fallbacks) = (font(), [color_font()]);
+        let fonts = Fonts {
+            font: &font,
+            fallbacks: &fallbacks,
+        };
+
+        assert!(std::ptr::eq(fonts.for_char('I'), &font));
+        // neither has a glyph for it
+        assert!(std::ptr::eq(fonts.for_char('\u{2603}'), &font));
+    }
+
+    #[test]
+    fn bitmap_glyph_is_drawn_in_its_colors() {
+        let bitmap = RgbaImage::from_fn(4, 4, |x, _| {
+            if x < 2 {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 255, 128])
+            }
+        });
+
+        let (mask, paint) = rasterize(&[Shape::Bitmap(10, 20, bitmap)], BLACK).unwrap();
+
+        assert_eq!((mask.x, mask.y, mask.width, mask.height), (10, 20, 4, 4));
+        assert_eq!(mask.get(11, 21), 1.0);
+        assert!((mask.get(12, 21) - 128.0 / 255.0).abs() < 1e-6);
+        assert_eq!(paint.get(11, 21), [1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn invisible_characters_are_skipped() {
+        let plain = draw_text(&canvas(), "II", (10, 10), &options(BLACK), &font(), &[]).unwrap();
+        let joined = draw_text(
+            &canvas(),
+            "I\u{200D}I\u{FE0F}",
+            (10, 10),
+            &options(BLACK),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(plain.to_bytes(), joined.to_bytes());
+    }
+
+    #[test]
+    fn missing_glyph_without_fallback() {
+        // drawn as the replacement glyph of the font
+        let out = draw_text(
+            &canvas(),
+            "\u{2603}",
+            (10, 10),
+            &options(BLACK),
+            &font(),
+            &[],
+        )
+        .unwrap();
+
+        assert!(count(&out, WHITE) < 64 * 64);
+    }
+
+    #[test]
+    fn whitespace_only() {
+        let out = draw_text(&canvas(), "  ", (10, 10), &options(BLACK), &font(), &[]).unwrap();
+
+        assert_eq!(count(&out, WHITE), 64 * 64);
+    }
+}
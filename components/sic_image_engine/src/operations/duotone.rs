@@ -0,0 +1,89 @@
+use crate::operations::gradient_map::map_luminance_to_gradient;
+use crate::wrapper::gradient::{ColorStop, Gradient};
+use sic_core::image::{DynamicImage, Rgba};
+
+/// Maps the luminance of each pixel onto a gradient from the `shadows` color (black) to the
+/// `highlights` color (white).
+///
+/// The alpha of the gradient is multiplied with the alpha of the pixel. If neither the image nor
+/// the colors are translucent, the result has no alpha channel.
+pub(crate) fn duotone(
+    image: &DynamicImage,
+    shadows: Rgba<u8>,
+    highlights: Rgba<u8>,
+) -> DynamicImage {
+    let gradient = Gradient::new(vec![
+        ColorStop::new(0.0, shadows),
+        ColorStop::new(1.0, highlights),
+    ]);
+
+    map_luminance_to_gradient(image, &gradient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, GrayImage, ImageBuffer, Luma, LumaA};
+    use sic_testing::*;
+
+    ide!();
+
+    const NAVY: Rgba<u8> = Rgba([0, 0, 128, 255]);
+    const PINK: Rgba<u8> = Rgba([255, 128, 192, 255]);
+
+    #[pm(luminance = { 0, 255, 128 }, expected = {
+        [0, 0, 128, 255],
+        [255, 128, 192, 255],
+        [128, 64, 160, 255],
+    })]
+    fn tones(luminance: u8, expected: [u8; 4]) {
+        let image = GrayImage::from_pixel(1, 1, Luma([luminance]));
+        let out = duotone(&DynamicImage::ImageLuma8(image), NAVY, PINK);
+
+        assert_eq!(out.get_pixel(0, 0), Rgba(expected));
+    }
+
+    #[test]
+    fn black_and_white() {
+        let image = GrayImage::from_raw(2, 1, vec![0, 255]).unwrap();
+        let out = duotone(&DynamicImage::ImageLuma8(image), NAVY, PINK);
+
+        assert!(matches!(out, DynamicImage::ImageRgb8(_)));
+        assert_eq!(out.get_pixel(0, 0), NAVY);
+        assert_eq!(out.get_pixel(1, 0), PINK);
+    }
+
+    #[test]
+    fn keeps_alpha() {
+        let image = ImageBuffer::from_pixel(1, 1, LumaA([255, 100]));
+        let out = duotone(&DynamicImage::ImageLumaA8(image), NAVY, PINK);
+
+        assert!(matches!(out, DynamicImage::ImageRgba8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([255, 128, 192, 100]));
+    }
+
+    #[test]
+    fn translucent_colors() {
+        let image = GrayImage::from_pixel(1, 1, Luma([0]));
+        let out = duotone(
+            &DynamicImage::ImageLuma8(image),
+            Rgba([0, 0, 0, 0]),
+            Rgba([255, 255, 255, 255]),
+        );
+
+        assert!(matches!(out, DynamicImage::ImageRgba8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn color_image() {
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+        let out = duotone(&image, NAVY, PINK);
+
+        assert_eq!(out.dimensions(), image.dimensions());
+        assert!(out
+            .to_rgb()
+            .pixels()
+            .all(|pixel| pixel[2] >= 128 && pixel[2] <= 192));
+    }
+}
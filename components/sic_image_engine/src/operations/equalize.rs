@@ -0,0 +1,102 @@
+use crate::operations::luminance::map_luminance;
+use sic_core::image::{DynamicImage, GrayImage, Luma};
+
+/// Performs a global histogram equalization on the luminance of an image, which spreads the
+/// most frequent luminance values over the full range, and thereby improves the contrast of
+/// flat images.
+pub(crate) fn equalize(image: &DynamicImage) -> DynamicImage {
+    map_luminance(image, |luminance| {
+        let mut histogram = [0u64; 256];
+        for px in luminance.pixels() {
+            histogram[usize::from(px[0])] += 1;
+        }
+
+        match equalization_table(&histogram) {
+            Some(table) => GrayImage::from_fn(luminance.width(), luminance.height(), |x, y| {
+                Luma([table[usize::from(luminance.get_pixel(x, y)[0])]])
+            }),
+            None => luminance.clone(),
+        }
+    })
+}
+
+/// Maps each luminance value to its equalized value, based on the cumulative histogram.
+/// Returns `None` if the image consists of a single luminance value, which can't be equalized.
+fn equalization_table(histogram: &[u64; 256]) -> Option<[u8; 256]> {
+    let total: u64 = histogram.iter().sum();
+    let lowest = histogram.iter().copied().find(|&count| count > 0)?;
+
+    if total == lowest {
+        return None;
+    }
+
+    let mut table = [0u8; 256];
+    let mut cumulative = 0;
+
+    for (value, count) in histogram.iter().enumerate() {
+        cumulative += count;
+        let scaled = cumulative.saturating_sub(lowest) as f64 * 255.0 / (total - lowest) as f64;
+        table[value] = scaled.round() as u8;
+    }
+
+    Some(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgba, RgbaImage};
+    use sic_testing::*;
+
+    const LOW_CONTRAST: &str = "equalize_8x8_low_contrast.png";
+    const REFERENCE: &str = "equalize_8x8_low_contrast_reference.png";
+
+    #[test]
+    fn matches_reference() {
+        let image = open_test_image(setup_test_image(LOW_CONTRAST));
+        let reference = open_test_image(setup_test_image(REFERENCE));
+
+        let out = equalize(&image);
+
+        assert_eq!(out.as_luma8().unwrap(), reference.as_luma8().unwrap());
+    }
+
+    #[test]
+    fn gray_rgba_matches_reference_and_keeps_alpha() {
+        let image = open_test_image(setup_test_image(LOW_CONTRAST)).to_luma();
+        let reference = open_test_image(setup_test_image(REFERENCE)).to_luma();
+
+        let rgba = RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+            let v = image.get_pixel(x, y)[0];
+            Rgba([v, v, v, x as u8])
+        });
+
+        let out = equalize(&DynamicImage::ImageRgba8(rgba));
+        let out = out.as_rgba8().unwrap();
+
+        for (x, y, px) in out.enumerate_pixels() {
+            let v = reference.get_pixel(x, y)[0];
+            assert_eq!(px, &Rgba([v, v, v, x as u8]));
+        }
+    }
+
+    #[test]
+    fn stretches_to_full_range() {
+        let image = open_test_image(setup_test_image(LOW_CONTRAST));
+
+        let out = equalize(&image);
+        let out = out.as_luma8().unwrap();
+
+        assert_eq!(out.pixels().map(|px| px[0]).min(), Some(0));
+        assert_eq!(out.pixels().map(|px| px[0]).max(), Some(255));
+    }
+
+    #[test]
+    fn flat_image_is_unchanged() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(4, 4, Luma([77])));
+
+        let out = equalize(&image);
+
+        assert_eq!(out.as_luma8().unwrap(), image.as_luma8().unwrap());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::canvas::place_on_canvas;
+use crate::wrapper::extend::ExtendInputs;
+use crate::wrapper::gravity::Gravity;
+use sic_core::image::{DynamicImage, GenericImageView, Rgba};
+
+/// Places the image, without scaling it, on a canvas which is at least as large as the image. The
+/// image is placed by the gravity of the inputs, or by the fallback gravity if they have none, and
+/// likewise for the color of the canvas.
+pub(crate) fn extend(
+    image: &DynamicImage,
+    inputs: &ExtendInputs,
+    fallback_gravity: Gravity,
+    fallback_color: Rgba<u8>,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+    let (canvas_width, canvas_height) = inputs.size();
+
+    if canvas_width < width || canvas_height < height {
+        return Err(SicImageEngineError::ExtendCanvasTooSmall(
+            canvas_width,
+            canvas_height,
+            width,
+            height,
+        ));
+    }
+
+    let gravity = inputs.gravity().unwrap_or(fallback_gravity);
+    let position = gravity.position(inputs.size(), (width, height), 0);
+
+    Ok(place_on_canvas(
+        image,
+        inputs.size(),
+        position,
+        inputs.color().unwrap_or(fallback_color),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage};
+    use sic_testing::*;
+
+    ide!();
+
+    const RED: Rgb<u8> = Rgb([255, 0, 0]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    #[pm(
+        gravity = { Gravity::TopLeft, Gravity::Center, Gravity::BottomRight, Gravity::Top },
+        expected = { (0, 0), (2, 1), (4, 2), (2, 0) }
+    )]
+    fn places_image_by_gravity(gravity: Gravity, expected: (u32, u32)) {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, RED));
+        let inputs = ExtendInputs::new((6, 4), Some(gravity), Some(BLUE));
+
+        let out = extend(&image, &inputs, Gravity::Center, BLUE).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (6, 4));
+        assert_eq!(out.get_pixel(expected.0, expected.1), &RED);
+        assert_eq!(out.get_pixel(expected.0 + 1, expected.1 + 1), &RED);
+        assert_eq!(
+            out.pixels().filter(|px| **px == RED).count(),
+            4,
+            "the image is not scaled"
+        );
+    }
+
+    #[test]
+    fn places_image_by_fallback_gravity() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, RED));
+        let inputs = ExtendInputs::new((6, 4), None, Some(BLUE));
+
+        let out = extend(&image, &inputs, Gravity::BottomRight, BLUE).unwrap();
+
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(4, 2), &RED);
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(5, 3), &RED);
+    }
+
+    #[test]
+    fn fills_canvas_with_fallback_color() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, RED));
+        let inputs = ExtendInputs::new((3, 2), Some(Gravity::Left), None);
+
+        let out = extend(&image, &inputs, Gravity::Center, Rgba([0, 255, 0, 255])).unwrap();
+
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(2, 1), &Rgb([0, 255, 0]));
+    }
+
+    #[test]
+    fn same_size() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, RED));
+        let inputs = ExtendInputs::new((2, 2), Some(Gravity::Center), Some(Rgba([0, 0, 0, 255])));
+
+        let out = extend(&image, &inputs, Gravity::Center, BLUE).unwrap();
+
+        assert!(out.as_rgb8().unwrap().pixels().all(|px| *px == RED));
+    }
+
+    #[pm(size = { (1, 2), (2, 1), (0, 0) })]
+    fn canvas_too_small(size: (u32, u32)) {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(2, 2));
+        let inputs = ExtendInputs::new(size, None, None);
+
+        assert!(extend(&image, &inputs, Gravity::Center, BLUE).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::analysis::template_match::{best_match, TemplateMatch};
+use crate::errors::SicImageEngineError;
+use sic_core::image::{DynamicImage, GenericImage, GenericImageView, Rgba};
+
+/// The width of the outline around the area where the template was found, in pixels.
+const OUTLINE_WIDTH: u32 = 2;
+
+/// Finds the area of the image which is most similar to the template, and outlines it in the
+/// given color, if any. The outline is drawn just within the area.
+pub(crate) fn find(
+    image: &mut DynamicImage,
+    template: &DynamicImage,
+    outline: Option<Rgba<u8>>,
+) -> Result<TemplateMatch, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+    let (template_width, template_height) = template.dimensions();
+
+    if template_width == 0
+        || template_height == 0
+        || template_width > width
+        || template_height > height
+    {
+        return Err(SicImageEngineError::FindTemplateTooLarge(
+            template_width,
+            template_height,
+            width,
+            height,
+        ));
+    }
+
+    let found = best_match(image, template);
+
+    if let Some(color) = outline {
+        draw_outline(image, &found, color);
+    }
+
+    Ok(found)
+}
+
+fn draw_outline(image: &mut DynamicImage, found: &TemplateMatch, color: Rgba<u8>) {
+    let thickness = OUTLINE_WIDTH.min(found.width).min(found.height);
+
+    for y in found.y..found.y + found.height {
+        for x in found.x..found.x + found.width {
+            let inset = (x - found.x)
+                .min(y - found.y)
+                .min(found.x + found.width - 1 - x)
+                .min(found.y + found.height - 1 - y);
+
+            if inset < thickness {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage};
+
+    fn checkers() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(12, 10, |x, y| {
+            Rgb([(x * 20) as u8, (y * 25) as u8, ((x ^ y) * 15) as u8])
+        }))
+    }
+
+    #[test]
+    fn outlines_match() {
+        let mut image = checkers();
+        let template = image.crop_imm(3, 2, 6, 5);
+        let red = Rgba([255, 0, 0, 255]);
+
+        let found = find(&mut image, &template, Some(red)).unwrap();
+
+        assert_eq!((found.x, found.y, found.width, found.height), (3, 2, 6, 5));
+        assert_eq!(image.get_pixel(3, 2), red);
+        assert_eq!(image.get_pixel(4, 3), red);
+        assert_eq!(image.get_pixel(8, 6), red);
+        assert_ne!(image.get_pixel(5, 4), red);
+        assert_ne!(image.get_pixel(2, 2), red);
+    }
+
+    #[test]
+    fn without_outline() {
+        let mut image = checkers();
+        let original = image.clone();
+        let template = image.crop_imm(0, 0, 4, 4);
+
+        find(&mut image, &template, None).unwrap();
+
+        assert_eq!(image.to_bytes(), original.to_bytes());
+    }
+
+    #[test]
+    fn template_too_large() {
+        let mut image = checkers();
+        let template = DynamicImage::ImageRgb8(RgbImage::new(13, 2));
+
+        assert!(find(&mut image, &template, None).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::remap::remap;
+use sic_core::image::{DynamicImage, GenericImageView, Rgba};
+
+/// Bulges or pinches the image within the largest circle around its center which fits the image.
+///
+/// A positive strength bulges the image, as if seen through a fisheye lens: the center is
+/// magnified, while the image near the edge of the circle is compressed. A negative strength
+/// pinches the image instead. The strength should be between -1 and 1; 0 leaves the image
+/// unchanged. Outside of the circle, the image is left as is.
+pub(crate) fn fisheye(
+    image: &DynamicImage,
+    strength: f32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if !(-1.0..=1.0).contains(&strength) {
+        return Err(SicImageEngineError::FisheyeInvalidStrength(strength));
+    }
+
+    let (width, height) = image.dimensions();
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let radius = cx.min(cy);
+
+    // a pixel at the relative distance r to the center (between 0 and 1) is sampled at the
+    // distance r^exponent, which is closer to the center for a bulge, and further away for a pinch
+    let exponent = 2f32.powf(strength);
+
+    // every pixel is sampled from within the image, so the background is never used
+    Ok(remap(image, (width, height), Rgba([0, 0, 0, 0]), |x, y| {
+        let (dx, dy) = (x - cx, y - cy);
+        let distance = dx.hypot(dy);
+
+        if distance == 0.0 || distance >= radius {
+            return Some((x, y));
+        }
+
+        let r = distance / radius;
+        let scale = r.powf(exponent) / r;
+
+        Some((cx + dx * scale, cy + dy * scale))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage};
+
+    /// An image with a white square of 4 by 4 pixels in the center of a black image.
+    fn square() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(20, 20, |x, y| {
+            if (8..12).contains(&x) && (8..12).contains(&y) {
+                Rgb([255, 255, 255])
+            } else {
+                Rgb([0, 0, 0])
+            }
+        }))
+    }
+
+    fn count_white(image: &DynamicImage) -> usize {
+        let image = image.as_rgb8().unwrap();
+
+        image.pixels().filter(|px| px[0] > 127).count()
+    }
+
+    #[test]
+    fn bulge_magnifies_center() {
+        let out = fisheye(&square(), 0.5).unwrap();
+
+        assert_eq!(out.dimensions(), (20, 20));
+        assert!(count_white(&out) > count_white(&square()));
+    }
+
+    #[test]
+    fn pinch_shrinks_center() {
+        let out = fisheye(&square(), -0.5).unwrap();
+
+        assert!(count_white(&out) < count_white(&square()));
+    }
+
+    #[test]
+    fn zero_strength_is_identity() {
+        let out = fisheye(&square(), 0.0).unwrap();
+
+        assert_eq!(out.as_rgb8(), square().as_rgb8());
+    }
+
+    #[test]
+    fn outside_of_circle_unchanged() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(20, 10, |x, y| {
+            Rgb([x as u8 * 10, y as u8 * 20, 0])
+        }));
+
+        let out = fisheye(&image, 1.0).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(
+            out.get_pixel(0, 0),
+            image.as_rgb8().unwrap().get_pixel(0, 0)
+        );
+        assert_eq!(
+            out.get_pixel(19, 5),
+            image.as_rgb8().unwrap().get_pixel(19, 5)
+        );
+    }
+
+    #[test]
+    fn invalid_strength() {
+        assert!(fisheye(&square(), 1.5).is_err());
+        assert!(fisheye(&square(), f32::NAN).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use crate::errors::SicImageEngineError;
+use sic_core::image::{DynamicImage, Rgb, RgbImage};
+
+const LIGHT: u8 = 255;
+const DARK: u8 = 204;
+
+/// Composites an image over a checkerboard of `cell_size` by `cell_size` pixel cells, so its
+/// transparency can be judged in formats or viewers which don't support an alpha channel.
+/// The result doesn't have an alpha channel.
+pub(crate) fn flatten_checker(
+    image: &DynamicImage,
+    cell_size: u32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if cell_size == 0 {
+        return Err(SicImageEngineError::FlattenCheckerInvalidCellSize);
+    }
+
+    let image = image.to_rgba();
+
+    let out = RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let background = if ((x / cell_size) + (y / cell_size)) & 1 == 0 {
+            LIGHT
+        } else {
+            DARK
+        };
+
+        let pixel = image.get_pixel(x, y);
+        let alpha = u16::from(pixel[3]);
+        let blend = |sample: u8| {
+            let mixed = u16::from(sample) * alpha + u16::from(background) * (255 - alpha);
+            ((mixed + 127) / 255) as u8
+        };
+
+        Rgb([blend(pixel[0]), blend(pixel[1]), blend(pixel[2])])
+    });
+
+    Ok(DynamicImage::ImageRgb8(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Rgba, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    fn transparent(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 0])))
+    }
+
+    #[test]
+    fn transparent_image_becomes_checkerboard() {
+        let out = flatten_checker(&transparent(4, 4), 2).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.get_pixel(0, 0), &Rgb([LIGHT; 3]));
+        assert_eq!(out.get_pixel(1, 1), &Rgb([LIGHT; 3]));
+        assert_eq!(out.get_pixel(2, 0), &Rgb([DARK; 3]));
+        assert_eq!(out.get_pixel(0, 2), &Rgb([DARK; 3]));
+        assert_eq!(out.get_pixel(3, 3), &Rgb([LIGHT; 3]));
+    }
+
+    #[test]
+    fn opaque_image_is_unchanged() {
+        let image = open_test_image(setup_test_image("rainbow_8x6.bmp"));
+
+        let out = flatten_checker(&image, 3).unwrap();
+
+        assert_eq!(out.to_rgb().into_raw(), image.to_rgb().into_raw());
+    }
+
+    #[test]
+    fn half_transparent_is_blended() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([0, 100, 255, 128])));
+
+        let out = flatten_checker(&image, 8).unwrap();
+
+        // the single pixel lies on a light cell
+        assert_eq!(
+            out.as_rgb8().unwrap().get_pixel(0, 0),
+            &Rgb([127, 177, 255])
+        );
+    }
+
+    #[pm(cell_size = { 1, 16, 1000 })]
+    fn keeps_dimensions(cell_size: u32) {
+        let out = flatten_checker(&transparent(5, 3), cell_size).unwrap();
+
+        assert_eq!(out.dimensions(), (5, 3));
+    }
+
+    #[test]
+    fn invalid_cell_size() {
+        assert!(flatten_checker(&transparent(2, 2), 0).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::color_font::ColorLayers;
+use crate::wrapper::font_options::{FontSource, FontStyle};
+use fontdb::{Database, Family, Query, Style, Weight};
+use rusttype::Font;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Font families which are tried, in order, to draw the characters which a font has no glyph for,
+/// such as emoji. Color emoji fonts come first, so emoji are drawn in color where possible.
+const FALLBACK_FAMILIES: &[&str] = &[
+    "Noto Color Emoji",
+    "Apple Color Emoji",
+    "Segoe UI Emoji",
+    "Twemoji Mozilla",
+    "Noto Emoji",
+    "Segoe UI Symbol",
+    "Symbola",
+    "DejaVu Sans",
+];
+
+lazy_static::lazy_static! {
+    // Loaded fonts are kept for the lifetime of the process, so drawing text on each frame of an
+    // animation, or on each image of a batch, parses the font only once.
+    static ref FONTS: Mutex<HashMap<(FontSource, FontStyle), LoadedFont>> =
+        Mutex::new(HashMap::new());
+
+    // Discovering the installed fonts requires reading each font file in the system font
+    // directories, so it's done at most once, and only when a font family is requested.
+    static ref SYSTEM_FONTS: Database = {
+        let mut database = Database::new();
+        database.load_system_fonts();
+        database
+    };
+}
+
+/// A font, along with the color layers of its color glyphs, if it has any.
+#[derive(Clone)]
+pub(crate) struct LoadedFont {
+    pub(crate) font: Font<'static>,
+    pub(crate) color_layers: Option<Arc<ColorLayers>>,
+}
+
+impl LoadedFont {
+    /// Parses the font at the index of a font file, which is 0 unless the file is a collection.
+    pub(crate) fn from_data(data: Vec<u8>, index: u32) -> Option<Self> {
+        let color_layers = ColorLayers::parse(&data, index).map(Arc::new);
+        let font = Font::try_from_vec_and_index(data, index)?;
+
+        Some(Self { font, color_layers })
+    }
+
+    pub(crate) fn has_glyph(&self, c: char) -> bool {
+        self.font.glyph(c).id().0 != 0
+    }
+}
+
+/// Loads a font from a file, or from the fonts installed on the system.
+pub(crate) fn load_font(
+    source: &FontSource,
+    style: FontStyle,
+) -> Result<LoadedFont, SicImageEngineError> {
+    let key = (source.clone(), style);
+
+    if let Some(font) = fonts().get(&key) {
+        return Ok(font.clone());
+    }
+
+    let (data, index) = match source {
+        FontSource::File(path) => (
+            std::fs::read(path).map_err(SicImageEngineError::FontFileLoadError)?,
+            0,
+        ),
+        FontSource::Family(family) => find_family(&SYSTEM_FONTS, family, style)
+            .ok_or_else(|| SicImageEngineError::FontFamilyNotFound(family.to_string()))?,
+    };
+
+    let font = LoadedFont::from_data(data, index).ok_or(SicImageEngineError::FontError)?;
+
+    fonts().insert(key, font.clone());
+
+    Ok(font)
+}
+
+/// Loads the installed fallback fonts which are needed to draw the characters of the text which the
+/// font has no glyph for. No fallback fonts are loaded if the font has a glyph for each character.
+pub(crate) fn load_fallback_fonts(text: &str, font: &LoadedFont) -> Vec<LoadedFont> {
+    fallback_fonts(
+        text,
+        font,
+        FALLBACK_FAMILIES.iter().filter_map(|family| {
+            load_font(
+                &FontSource::Family(family.to_string()),
+                FontStyle::default(),
+            )
+            .ok()
+        }),
+    )
+}
+
+/// Picks, from the candidates in order, the fonts which have a glyph for a character of the text
+/// which neither the font nor an earlier picked candidate has a glyph for. Candidates are only
+/// taken from the iterator while such characters are left.
+fn fallback_fonts(
+    text: &str,
+    font: &LoadedFont,
+    mut candidates: impl Iterator<Item = LoadedFont>,
+) -> Vec<LoadedFont> {
+    let mut missing = text
+        .chars()
+        .filter(|&c| !is_invisible(c) && !font.has_glyph(c))
+        .collect::<Vec<_>>();
+
+    let mut fallbacks = Vec::new();
+
+    while !missing.is_empty() {
+        let candidate = match candidates.next() {
+            Some(candidate) => candidate,
+            None => break,
+        };
+
+        let count = missing.len();
+        missing.retain(|&c| !candidate.has_glyph(c));
+
+        if missing.len() < count {
+            fallbacks.push(candidate);
+        }
+    }
+
+    fallbacks
+}
+
+/// Characters which aren't drawn themselves, like line breaks, and the joiners and variation
+/// selectors which are part of emoji sequences. Since text isn't shaped, the parts of an emoji
+/// sequence are drawn as separate emoji.
+pub(crate) fn is_invisible(c: char) -> bool {
+    c.is_control()
+        || matches!(c,
+            '\u{200B}'..='\u{200F}'
+            | '\u{2060}'..='\u{206F}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{E0000}'..='\u{E0FFF}'
+        )
+}
+
+fn fonts() -> std::sync::MutexGuard<'static, HashMap<(FontSource, FontStyle), LoadedFont>> {
+    // The cache can't be left in an inconsistent state, so a poisoned lock is still usable
+    FONTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Finds the face of a font family which best matches the style, and returns its data and its
+/// index within the font file. Family names are matched case insensitively. Like CSS, if the family
+/// has no bold or italic face, the closest face is used instead.
+fn find_family(database: &Database, family: &str, style: FontStyle) -> Option<(Vec<u8>, u32)> {
+    let name = database
+        .faces()
+        .iter()
+        .find(|face| face.family.eq_ignore_ascii_case(family))
+        .map(|face| face.family.as_str())?;
+
+    let id = database.query(&Query {
+        families: &[Family::Name(name)],
+        weight: if style.bold {
+            Weight::BOLD
+        } else {
+            Weight::NORMAL
+        },
+        style: if style.italic {
+            Style::Italic
+        } else {
+            Style::Normal
+        },
+        ..Query::default()
+    })?;
+
+    database.with_face_data(id, |data, index| (data.to_vec(), index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+    use std::path::PathBuf;
+
+    ide!();
+
+    fn database() -> Database {
+        let mut database = Database::new();
+        database
+            .load_font_file(in_!("font/Lato-Regular.ttf"))
+            .unwrap();
+        database
+    }
+
+    #[pm(family = { "Lato", "lato", "LATO" })]
+    fn find_family_by_name(family: &str) {
+        assert!(find_family(&database(), family, FontStyle::default()).is_some());
+    }
+
+    #[test]
+    fn find_family_falls_back_to_closest_style() {
+        let style = FontStyle {
+            bold: true,
+            italic: true,
+        };
+
+        assert!(find_family(&database(), "Lato", style).is_some());
+    }
+
+    #[test]
+    fn find_unknown_family() {
+        assert!(find_family(&database(), "Unknown Family", FontStyle::default()).is_none());
+    }
+
+    #[test]
+    fn load_font_file_is_cached() {
+        let source = FontSource::File(PathBuf::from(in_!("font/Lato-Regular.ttf")));
+
+        assert!(load_font(&source, FontStyle::default()).is_ok());
+        assert!(fonts().contains_key(&(source.clone(), FontStyle::default())));
+        assert!(load_font(&source, FontStyle::default()).is_ok());
+    }
+
+    fn lato() -> LoadedFont {
+        LoadedFont::from_data(std::fs::read(in_!("font/Lato-Regular.ttf")).unwrap(), 0).unwrap()
+    }
+
+    #[test]
+    fn no_fallback_needed() {
+        let mut taken = 0;
+        let candidates = std::iter::repeat_with(|| {
+            taken += 1;
+            lato()
+        });
+
+        // the fallback fonts aren't loaded at all if they aren't needed
+        assert!(fallback_fonts("Hello\nworld\u{FE0F}", &lato(), candidates).is_empty());
+        assert_eq!(taken, 0);
+    }
+
+    #[test]
+    fn fallback_without_glyph_is_skipped() {
+        let candidates = vec![lato(), lato()].into_iter();
+
+        assert!(fallback_fonts("\u{2603}", &lato(), candidates).is_empty());
+    }
+
+    #[pm(c = { '\n', '\u{200D}', '\u{FE0F}', '\u{E0067}' })]
+    fn invisible(c: char) {
+        assert!(is_invisible(c));
+    }
+
+    #[test]
+    fn load_missing_font_file() {
+        let source = FontSource::File(PathBuf::from(in_!("font/Missing.ttf")));
+
+        assert!(matches!(
+            load_font(&source, FontStyle::default()),
+            Err(SicImageEngineError::FontFileLoadError(_))
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::composite::composite;
+use crate::wrapper::blend_mode::BlendMode;
+use crate::wrapper::gradient::{ColorStop, Gradient};
+use crate::wrapper::gradient_fill::{GradientFillInputs, GradientKind};
+use sic_core::image::{DynamicImage, GenericImageView, RgbaImage};
+
+/// Draws a linear or radial gradient between two colors over the whole image. Opaque colors
+/// cover the image; translucent colors are alpha composited over it, e.g. to darken the edges of
+/// the image with a radial gradient from transparent to translucent black.
+///
+/// A linear gradient runs from the first color at one side (or corner) of the image to the second
+/// color at the opposite side, in the direction of its angle. A radial gradient runs from the
+/// first color at the center of the image to the second color at its corners.
+pub(crate) fn gradient_fill(
+    image: &DynamicImage,
+    inputs: &GradientFillInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+    let (from, to) = inputs.colors();
+    let gradient = Gradient::new(vec![ColorStop::new(0.0, from), ColorStop::new(1.0, to)]);
+
+    let position = position_fn(inputs, (width as f32, height as f32));
+
+    let fill = RgbaImage::from_fn(width, height, |x, y| {
+        gradient.color_at(position(x as f32 + 0.5, y as f32 + 0.5))
+    });
+
+    composite(
+        image,
+        &DynamicImage::ImageRgba8(fill),
+        (0, 0),
+        1.0,
+        BlendMode::Normal,
+    )
+}
+
+/// The function which computes the position on the gradient, from 0 to 1, of a point of an image
+/// of the given size.
+fn position_fn(
+    inputs: &GradientFillInputs,
+    (width, height): (f32, f32),
+) -> Box<dyn Fn(f32, f32) -> f32> {
+    match inputs.kind() {
+        GradientKind::Linear => {
+            let (dy, dx) = inputs.angle().to_radians().sin_cos();
+            let project = move |x: f32, y: f32| x * dx + y * dy;
+
+            // the gradient starts and ends at the corners which lie furthest along its direction
+            let corners = [
+                project(0.0, 0.0),
+                project(width, 0.0),
+                project(0.0, height),
+                project(width, height),
+            ];
+            let start = corners.iter().copied().fold(f32::INFINITY, f32::min);
+            let end = corners.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let length = (end - start).max(f32::EPSILON);
+
+            Box::new(move |x, y| (project(x, y) - start) / length)
+        }
+        GradientKind::Radial => {
+            let (cx, cy) = (width / 2.0, height / 2.0);
+            let radius = cx.hypot(cy).max(f32::EPSILON);
+
+            Box::new(move |x, y| (x - cx).hypot(y - cy) / radius)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage, Rgba};
+    use sic_testing::*;
+
+    ide!();
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    fn fill(
+        size: (u32, u32),
+        kind: GradientKind,
+        colors: (Rgba<u8>, Rgba<u8>),
+        angle: f32,
+    ) -> DynamicImage {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(size.0, size.1));
+
+        gradient_fill(&image, &GradientFillInputs::new(kind, colors, angle)).unwrap()
+    }
+
+    #[test]
+    fn linear_left_to_right() {
+        let out = fill((4, 1), GradientKind::Linear, (BLACK, WHITE), 0.0);
+        let out = out.as_rgb8().unwrap();
+
+        // pixel centers lie at 1/8, 3/8, 5/8 and 7/8 of the width
+        assert_eq!(out.get_pixel(0, 0), &Rgb([32, 32, 32]));
+        assert_eq!(out.get_pixel(1, 0), &Rgb([96, 96, 96]));
+        assert_eq!(out.get_pixel(3, 0), &Rgb([223, 223, 223]));
+    }
+
+    #[pm(
+        angle = { 90.0, 180.0, 270.0 },
+        first = { (0, 0), (3, 0), (0, 3) },
+        last = { (0, 3), (0, 0), (0, 0) }
+    )]
+    fn linear_angle(angle: f32, first: (u32, u32), last: (u32, u32)) {
+        let out = fill((4, 4), GradientKind::Linear, (BLACK, WHITE), angle);
+
+        assert_eq!(out.get_pixel(first.0, first.1), Rgba([32, 32, 32, 255]));
+        assert_eq!(out.get_pixel(last.0, last.1), Rgba([223, 223, 223, 255]));
+    }
+
+    #[test]
+    fn linear_diagonal() {
+        let out = fill((4, 4), GradientKind::Linear, (BLACK, WHITE), 45.0);
+
+        // along the diagonal, the gradient runs from the top left to the bottom right corner
+        assert_eq!(out.get_pixel(0, 0), Rgba([32, 32, 32, 255]));
+        assert_eq!(out.get_pixel(3, 3), Rgba([223, 223, 223, 255]));
+        assert_eq!(out.get_pixel(3, 0), out.get_pixel(0, 3));
+    }
+
+    #[test]
+    fn radial() {
+        let out = fill((5, 5), GradientKind::Radial, (WHITE, BLACK), 0.0);
+
+        assert_eq!(out.get_pixel(2, 2), WHITE);
+        assert_eq!(out.get_pixel(0, 2), out.get_pixel(2, 0));
+        assert_eq!(out.get_pixel(0, 0), out.get_pixel(4, 4));
+        assert!(out.get_pixel(0, 0)[0] < out.get_pixel(0, 2)[0]);
+    }
+
+    #[test]
+    fn translucent_colors_are_composited() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 1, Rgb([200, 100, 0])));
+        let inputs = GradientFillInputs::new(
+            GradientKind::Linear,
+            (Rgba([0, 0, 0, 0]), Rgba([0, 0, 0, 0])),
+            0.0,
+        );
+
+        let out = gradient_fill(&image, &inputs).unwrap();
+
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(1, 0), &Rgb([200, 100, 0]));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::gradient::Gradient;
+use sic_core::image::{DynamicImage, ImageBuffer, Rgb, Rgba};
+
+/// Remaps the luminance of each pixel through the gradient: black maps to the color at position 0,
+/// and white to the color at position 1.
+pub(crate) fn gradient_map(
+    image: &DynamicImage,
+    gradient: &Gradient,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if gradient.stops().len() < 2 {
+        return Err(SicImageEngineError::GradientMapTooFewStops(
+            gradient.stops().len(),
+        ));
+    }
+
+    if let Some(stop) = gradient
+        .stops()
+        .iter()
+        .find(|stop| !(0.0..=1.0).contains(&stop.position()))
+    {
+        return Err(SicImageEngineError::GradientMapInvalidStopPosition(
+            stop.position(),
+        ));
+    }
+
+    Ok(map_luminance_to_gradient(image, gradient))
+}
+
+/// The alpha of the gradient is multiplied with the alpha of the pixel. If neither the image nor
+/// the gradient is translucent, the result has no alpha channel.
+pub(crate) fn map_luminance_to_gradient(image: &DynamicImage, gradient: &Gradient) -> DynamicImage {
+    let gray = image.to_luma_alpha();
+    let (width, height) = gray.dimensions();
+
+    // The color of each possible luminance value
+    let lut = (0..=u8::MAX)
+        .map(|luminance| gradient.color_at(f32::from(luminance) / 255.0))
+        .collect::<Vec<_>>();
+
+    let opaque = gray.pixels().all(|pixel| pixel[1] == u8::MAX)
+        && lut.iter().all(|color| color[3] == u8::MAX);
+
+    if opaque {
+        DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+            let color = lut[usize::from(gray.get_pixel(x, y)[0])];
+            Rgb([color[0], color[1], color[2]])
+        }))
+    } else {
+        DynamicImage::ImageRgba8(ImageBuffer::from_fn(width, height, |x, y| {
+            let pixel = gray.get_pixel(x, y);
+            let color = lut[usize::from(pixel[0])];
+            let alpha = (f32::from(color[3]) * f32::from(pixel[1]) / 255.0).round() as u8;
+            Rgba([color[0], color[1], color[2], alpha])
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wrapper::gradient::ColorStop;
+    use sic_core::image::{GenericImageView, GrayImage};
+    use sic_testing::*;
+
+    ide!();
+
+    fn heat() -> Gradient {
+        Gradient::new(vec![
+            ColorStop::new(0.0, Rgba([0, 0, 0, 255])),
+            ColorStop::new(0.5, Rgba([255, 0, 0, 255])),
+            ColorStop::new(1.0, Rgba([255, 255, 0, 255])),
+        ])
+    }
+
+    #[test]
+    fn maps_luminance() {
+        let image = GrayImage::from_raw(3, 1, vec![0, 51, 255]).unwrap();
+        let out = gradient_map(&DynamicImage::ImageLuma8(image), &heat()).unwrap();
+
+        assert!(matches!(out, DynamicImage::ImageRgb8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+        assert_eq!(out.get_pixel(1, 0), Rgba([102, 0, 0, 255]));
+        assert_eq!(out.get_pixel(2, 0), Rgba([255, 255, 0, 255]));
+    }
+
+    #[test]
+    fn translucent_stop() {
+        let gradient = Gradient::new(vec![
+            ColorStop::new(0.0, Rgba([0, 0, 0, 0])),
+            ColorStop::new(1.0, Rgba([255, 255, 255, 255])),
+        ]);
+        let image = GrayImage::from_raw(1, 1, vec![0]).unwrap();
+        let out = gradient_map(&DynamicImage::ImageLuma8(image), &gradient).unwrap();
+
+        assert!(matches!(out, DynamicImage::ImageRgba8(_)));
+        assert_eq!(out.get_pixel(0, 0)[3], 0);
+    }
+
+    #[test]
+    fn color_image() {
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+        let out = gradient_map(&image, &heat()).unwrap();
+
+        assert_eq!(out.dimensions(), image.dimensions());
+        assert!(out.to_rgb().pixels().all(|pixel| pixel[2] == 0));
+    }
+
+    #[test]
+    fn too_few_stops() {
+        let gradient = Gradient::new(vec![ColorStop::new(0.0, Rgba([0, 0, 0, 255]))]);
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+
+        assert!(gradient_map(&image, &gradient).is_err());
+    }
+
+    #[pm(position = { -0.1, 1.5, f32::NAN })]
+    fn invalid_stop_position(position: f32) {
+        let gradient = Gradient::new(vec![
+            ColorStop::new(0.0, Rgba([0, 0, 0, 255])),
+            ColorStop::new(position, Rgba([255, 255, 255, 255])),
+        ]);
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+
+        assert!(gradient_map(&image, &gradient).is_err());
+    }
+}
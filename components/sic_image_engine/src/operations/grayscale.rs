@@ -0,0 +1,119 @@
+use crate::errors::SicImageEngineError;
+use sic_core::image::{DynamicImage, GrayAlphaImage, GrayImage, Luma, LumaA};
+
+/// Converts the image to grayscale, with the luminance of each pixel computed as the weighted sum
+/// of its red, green and blue channels, e.g. `(0.2126, 0.7152, 0.0722)` for Rec. 709.
+///
+/// The weights are normalized, so they don't have to sum to 1, but none of them may be negative
+/// and at least one of them must be positive. The alpha channel, if any, is kept.
+pub(crate) fn grayscale_weighted(
+    image: &DynamicImage,
+    weights: (f32, f32, f32),
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (wr, wg, wb) = weights;
+    let sum = wr + wg + wb;
+
+    if !(wr >= 0.0 && wg >= 0.0 && wb >= 0.0 && sum > 0.0 && sum.is_finite()) {
+        return Err(SicImageEngineError::GrayscaleInvalidWeights(wr, wg, wb));
+    }
+
+    let (wr, wg, wb) = (wr / sum, wg / sum, wb / sum);
+    let luma = |r: u8, g: u8, b: u8| {
+        (wr * f32::from(r) + wg * f32::from(g) + wb * f32::from(b))
+            .round()
+            .min(255.0) as u8
+    };
+
+    let out = match image {
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLuma16(_) => {
+            DynamicImage::ImageLuma8(image.to_luma())
+        }
+        DynamicImage::ImageLumaA8(_) | DynamicImage::ImageLumaA16(_) => {
+            DynamicImage::ImageLumaA8(image.to_luma_alpha())
+        }
+        DynamicImage::ImageRgb8(_) | DynamicImage::ImageBgr8(_) | DynamicImage::ImageRgb16(_) => {
+            let buffer = image.to_rgb();
+
+            DynamicImage::ImageLuma8(GrayImage::from_fn(
+                buffer.width(),
+                buffer.height(),
+                |x, y| {
+                    let pixel = buffer.get_pixel(x, y);
+                    Luma([luma(pixel[0], pixel[1], pixel[2])])
+                },
+            ))
+        }
+        _ => {
+            let buffer = image.to_rgba();
+
+            DynamicImage::ImageLumaA8(GrayAlphaImage::from_fn(
+                buffer.width(),
+                buffer.height(),
+                |x, y| {
+                    let pixel = buffer.get_pixel(x, y);
+                    LumaA([luma(pixel[0], pixel[1], pixel[2]), pixel[3]])
+                },
+            ))
+        }
+    };
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, RgbImage, Rgba, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    fn rgb(pixel: [u8; 3]) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, sic_core::image::Rgb(pixel)))
+    }
+
+    #[pm(
+        weights = {
+            (1.0, 0.0, 0.0),
+            (0.0, 1.0, 0.0),
+            (0.0, 0.0, 1.0),
+            (1.0, 1.0, 1.0),
+            (0.2126, 0.7152, 0.0722),
+            (0.299, 0.587, 0.114),
+        },
+        expected = { 200, 100, 50, 117, 118, 124 }
+    )]
+    fn weighted_luminance(weights: (f32, f32, f32), expected: u8) {
+        let out = grayscale_weighted(&rgb([200, 100, 50]), weights).unwrap();
+
+        assert!(matches!(out, DynamicImage::ImageLuma8(_)));
+        assert_eq!(out.as_luma8().unwrap().get_pixel(0, 0), &Luma([expected]));
+    }
+
+    #[test]
+    fn keeps_alpha() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([0, 0, 90, 20])));
+        let out = grayscale_weighted(&image, (0.0, 0.0, 1.0)).unwrap();
+
+        assert!(matches!(out, DynamicImage::ImageLumaA8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([90, 90, 90, 20]));
+    }
+
+    #[test]
+    fn gray_image_is_unchanged() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 1, Luma([42])));
+        let out = grayscale_weighted(&image, (1.0, 0.0, 0.0)).unwrap();
+
+        assert_eq!(out.as_luma8().unwrap().get_pixel(0, 0), &Luma([42]));
+    }
+
+    #[pm(weights = {
+        (-0.1, 0.5, 0.5),
+        (0.0, 0.0, 0.0),
+        (f32::NAN, 1.0, 1.0),
+        (f32::INFINITY, 1.0, 1.0),
+    })]
+    fn invalid_weights(weights: (f32, f32, f32)) {
+        assert!(grayscale_weighted(&rgb([1, 2, 3]), weights).is_err());
+    }
+}
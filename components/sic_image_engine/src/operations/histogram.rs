@@ -0,0 +1,301 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::histogram::{DrawHistogramInputs, HistogramChannels, HistogramPlacement};
+use sic_core::image::{imageops, DynamicImage, GenericImageView, Rgba, RgbaImage};
+use std::ops::Range;
+
+/// The color behind the bars of the histogram.
+const BACKGROUND: [u8; 3] = [24, 24, 24];
+
+/// The opacity of the background when the histogram is drawn on top of the image; the bars
+/// themselves are always opaque.
+const OVERLAY_BACKGROUND_ALPHA: u8 = 128;
+
+/// The color of the bars of a histogram of the luminance.
+const LUMA_BARS: [u8; 3] = [220, 220, 220];
+
+/// The colors of the bars of the histograms of the red, green and blue channels, which add up
+/// where bars overlap.
+const RGB_BARS: [[u8; 3]; 3] = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+
+/// The height of the histogram, for narrow images.
+const MIN_PANEL_HEIGHT: u32 = 16;
+
+type Histogram = [u64; 256];
+
+/// Renders a histogram of the luminance, or of each color channel, of the image, as wide as the
+/// image and a quarter as high, and either appends it below the image or draws it on top of the
+/// bottom of the image. Each column of the histogram shows the largest count of the values it
+/// covers, relative to the largest count of all values.
+pub(crate) fn draw_histogram(
+    image: &DynamicImage,
+    inputs: &DrawHistogramInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+    let panel_height = panel_height(width);
+    let histograms = histograms(image, inputs.channels());
+
+    let canvas = match inputs.placement() {
+        HistogramPlacement::Append => {
+            let total_height = height
+                .checked_add(panel_height)
+                .ok_or(SicImageEngineError::DrawHistogramTooLarge)?;
+
+            let mut canvas = RgbaImage::new(width, total_height);
+            let panel = render(&histograms, (width, panel_height), u8::MAX);
+            imageops::replace(&mut canvas, &image.to_rgba(), 0, 0);
+            imageops::replace(&mut canvas, &panel, 0, height);
+            canvas
+        }
+        HistogramPlacement::Overlay => {
+            let panel_height = panel_height.min(height);
+
+            let mut canvas = image.to_rgba();
+            let panel = render(&histograms, (width, panel_height), OVERLAY_BACKGROUND_ALPHA);
+            imageops::overlay(&mut canvas, &panel, 0, height - panel_height);
+            canvas
+        }
+    };
+
+    let canvas = DynamicImage::ImageRgba8(canvas);
+
+    if image.color().has_alpha() {
+        Ok(canvas)
+    } else {
+        Ok(DynamicImage::ImageRgb8(canvas.to_rgb()))
+    }
+}
+
+/// The height of the histogram drawn for an image of the given width.
+pub(crate) fn panel_height(width: u32) -> u32 {
+    (width / 4).max(MIN_PANEL_HEIGHT)
+}
+
+/// The histograms to render, with the color of their bars.
+fn histograms(image: &DynamicImage, channels: HistogramChannels) -> Vec<(Histogram, [u8; 3])> {
+    match channels {
+        HistogramChannels::Luma => {
+            let mut histogram = [0; 256];
+
+            for pixel in image.to_luma().pixels() {
+                histogram[usize::from(pixel[0])] += 1;
+            }
+
+            vec![(histogram, LUMA_BARS)]
+        }
+        HistogramChannels::Rgb => {
+            let mut histograms = [[0; 256]; 3];
+
+            for pixel in image.to_rgb().pixels() {
+                for (histogram, &value) in histograms.iter_mut().zip(pixel.0.iter()) {
+                    histogram[usize::from(value)] += 1;
+                }
+            }
+
+            histograms
+                .iter()
+                .copied()
+                .zip(RGB_BARS.iter().copied())
+                .collect()
+        }
+    }
+}
+
+/// Renders the bars of the histograms, scaled to the width and height of the panel, on a
+/// background of the given opacity.
+fn render(
+    histograms: &[(Histogram, [u8; 3])],
+    (width, height): (u32, u32),
+    background_alpha: u8,
+) -> RgbaImage {
+    let [r, g, b] = BACKGROUND;
+    let mut panel = RgbaImage::from_pixel(width, height, Rgba([r, g, b, background_alpha]));
+
+    let max = histograms
+        .iter()
+        .flat_map(|(histogram, _)| histogram.iter())
+        .copied()
+        .max()
+        .unwrap_or(0);
+
+    if max == 0 {
+        return panel;
+    }
+
+    for x in 0..width {
+        let bins = bins(x, width);
+
+        let bars = histograms
+            .iter()
+            .map(|(histogram, color)| {
+                let count = histogram[bins.clone()].iter().copied().max().unwrap_or(0);
+                let bar = (count as f64 / max as f64 * f64::from(height)).round() as u32;
+
+                (bar, *color)
+            })
+            .collect::<Vec<_>>();
+
+        for y in 0..height {
+            let level = height - y;
+
+            let color = bars
+                .iter()
+                .filter(|(bar, _)| *bar >= level)
+                .map(|(_, color)| color)
+                .fold(None, |sum: Option<[u8; 3]>, color| {
+                    let [r, g, b] = sum.unwrap_or([0; 3]);
+
+                    Some([
+                        r.saturating_add(color[0]),
+                        g.saturating_add(color[1]),
+                        b.saturating_add(color[2]),
+                    ])
+                });
+
+            if let Some([r, g, b]) = color {
+                panel.put_pixel(x, y, Rgba([r, g, b, u8::MAX]));
+            }
+        }
+    }
+
+    panel
+}
+
+/// The values of the histogram covered by a column of a panel of the given width: a single value
+/// if the panel is at least 256 pixels wide, or otherwise a range of values.
+fn bins(x: u32, width: u32) -> Range<usize> {
+    let bin = |x: u32| (u64::from(x) * 256 / u64::from(width)) as usize;
+    let start = bin(x);
+
+    start..bin(x + 1).max(start + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage};
+    use sic_testing::*;
+
+    ide!();
+
+    fn inputs(channels: HistogramChannels, placement: HistogramPlacement) -> DrawHistogramInputs {
+        DrawHistogramInputs::new(channels, placement)
+    }
+
+    #[test]
+    fn append_luma() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 2, Rgb([255, 255, 255])));
+        let inputs = inputs(HistogramChannels::Luma, HistogramPlacement::Append);
+
+        let out = draw_histogram(&image, &inputs).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (8, 2 + MIN_PANEL_HEIGHT));
+        assert_eq!(out.get_pixel(7, 1), &Rgb([255, 255, 255]));
+
+        // all pixels are white: the last column is a full bar, the others are empty
+        assert_eq!(out.get_pixel(7, 2), &Rgb(LUMA_BARS));
+        assert_eq!(out.get_pixel(7, 2 + MIN_PANEL_HEIGHT - 1), &Rgb(LUMA_BARS));
+        assert_eq!(out.get_pixel(0, 2 + MIN_PANEL_HEIGHT - 1), &Rgb(BACKGROUND));
+        assert_eq!(out.get_pixel(6, 2), &Rgb(BACKGROUND));
+    }
+
+    #[test]
+    fn append_rgb_adds_colors_of_overlapping_bars() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 2, Rgb([255, 0, 0])));
+        let inputs = inputs(HistogramChannels::Rgb, HistogramPlacement::Append);
+
+        let out = draw_histogram(&image, &inputs).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        // the green and blue bars overlap in the first column, the red bar is in the last
+        assert_eq!(out.get_pixel(0, 2), &Rgb([0, 255, 255]));
+        assert_eq!(out.get_pixel(7, 2), &Rgb([255, 0, 0]));
+        assert_eq!(out.get_pixel(3, 2), &Rgb(BACKGROUND));
+    }
+
+    #[test]
+    fn bars_relative_to_largest_count() {
+        // a quarter of the pixels is black, the others are white
+        let mut image = RgbImage::from_pixel(4, 4, Rgb([255, 255, 255]));
+        for x in 0..4 {
+            image.put_pixel(x, 0, Rgb([0, 0, 0]));
+        }
+
+        let image = DynamicImage::ImageRgb8(image);
+        let inputs = inputs(HistogramChannels::Luma, HistogramPlacement::Append);
+
+        let out = draw_histogram(&image, &inputs).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        let bar_height = |x| {
+            (4..4 + MIN_PANEL_HEIGHT)
+                .filter(|&y| out.get_pixel(x, y) == &Rgb(LUMA_BARS))
+                .count()
+        };
+
+        assert_eq!(bar_height(0), MIN_PANEL_HEIGHT as usize / 3);
+        assert_eq!(bar_height(3), MIN_PANEL_HEIGHT as usize);
+    }
+
+    #[test]
+    fn panel_height_scales_with_width() {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(400, 10));
+        let inputs = inputs(HistogramChannels::Luma, HistogramPlacement::Append);
+
+        let out = draw_histogram(&image, &inputs).unwrap();
+
+        assert_eq!(out.dimensions(), (400, 10 + 100));
+    }
+
+    #[test]
+    fn overlay_keeps_dimensions() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 20, Rgb([255, 255, 255])));
+        let inputs = inputs(HistogramChannels::Luma, HistogramPlacement::Overlay);
+
+        let out = draw_histogram(&image, &inputs).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (8, 20));
+        assert_eq!(
+            out.get_pixel(0, 20 - MIN_PANEL_HEIGHT - 1),
+            &Rgb([255, 255, 255])
+        );
+        assert_eq!(out.get_pixel(7, 20 - MIN_PANEL_HEIGHT), &Rgb(LUMA_BARS));
+
+        // the translucent background darkens the image
+        let background = out.get_pixel(0, 19);
+        assert!(background[0] > BACKGROUND[0] && background[0] < 255);
+    }
+
+    #[test]
+    fn overlay_on_short_image() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 4, Rgb([255, 255, 255])));
+        let inputs = inputs(HistogramChannels::Luma, HistogramPlacement::Overlay);
+
+        let out = draw_histogram(&image, &inputs).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (8, 4));
+        assert_eq!(out.get_pixel(7, 0), &Rgb(LUMA_BARS));
+    }
+
+    #[pm(placement = { HistogramPlacement::Append, HistogramPlacement::Overlay })]
+    fn keeps_alpha(placement: HistogramPlacement) {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(8, 20));
+
+        let out = draw_histogram(&image, &inputs(HistogramChannels::Rgb, placement)).unwrap();
+
+        assert!(out.color().has_alpha());
+    }
+
+    #[pm(width = { 1, 3, 255, 256, 1000 })]
+    fn bins_cover_all_values(width: u32) {
+        assert_eq!(bins(0, width).start, 0);
+        assert_eq!(bins(width - 1, width).end, 256);
+
+        for x in 1..width {
+            assert!(bins(x, width).start >= bins(x - 1, width).start);
+            assert!(!bins(x, width).is_empty());
+        }
+    }
+}
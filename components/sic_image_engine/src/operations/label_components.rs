@@ -0,0 +1,151 @@
+use crate::errors::SicImageEngineError;
+use imageproc::region_labelling::{connected_components, Connectivity};
+use sic_core::image::{DynamicImage, GenericImageView, GrayImage, Luma, Rgb, RgbImage};
+
+/// Pixels at least this bright belong to the foreground.
+const FOREGROUND_THRESHOLD: u8 = 128;
+
+/// The angle in degrees between the hues of consecutive labels; being irrational with respect to
+/// a full turn, neighbouring labels (which are usually also neighbouring components) get clearly
+/// different hues, and no two labels get the exact same hue.
+const GOLDEN_ANGLE: f32 = 137.507_76;
+
+/// Labels the connected components of the bright areas of the image, and draws each component in
+/// a distinct color on a black background, e.g. to count blobs after thresholding.
+///
+/// The image is binarized first: pixels with a luminance of at least 128 are part of a component,
+/// others are background. With a connectivity of 4, pixels are only connected to the pixels
+/// directly above, below, left and right of them; with a connectivity of 8, also to the pixels
+/// diagonal to them.
+pub(crate) fn label_components(
+    image: &DynamicImage,
+    connectivity: u32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let connectivity = match connectivity {
+        4 => Connectivity::Four,
+        8 => Connectivity::Eight,
+        _ => {
+            return Err(SicImageEngineError::LabelComponentsInvalidConnectivity(
+                connectivity,
+            ))
+        }
+    };
+
+    // imageproc panics if there are as many components as pixels, which is the case for an image
+    // of a single bright pixel; an extra column of background pixels rules this out
+    let (width, height) = image.dimensions();
+    let luma = image.to_luma();
+    let binary = GrayImage::from_fn(width + 1, height, |x, y| {
+        if x < width && luma.get_pixel(x, y)[0] >= FOREGROUND_THRESHOLD {
+            Luma([u8::MAX])
+        } else {
+            Luma([0])
+        }
+    });
+
+    let labels = connected_components(&binary, connectivity, Luma([0u8]));
+
+    Ok(DynamicImage::ImageRgb8(RgbImage::from_fn(
+        width,
+        height,
+        |x, y| label_color(labels.get_pixel(x, y)[0]),
+    )))
+}
+
+/// Black for the background (label 0), and a bright, saturated color for each component.
+fn label_color(label: u32) -> Rgb<u8> {
+    if label == 0 {
+        return Rgb([0, 0, 0]);
+    }
+
+    let hue = (label as f32 * GOLDEN_ANGLE) % 360.0 / 60.0;
+    let (value, saturation) = (255.0, 0.75);
+
+    let chroma = value * saturation;
+    let rising = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let min = value - chroma;
+
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, rising, 0.0),
+        1 => (rising, chroma, 0.0),
+        2 => (0.0, chroma, rising),
+        3 => (0.0, rising, chroma),
+        4 => (rising, 0.0, chroma),
+        _ => (chroma, 0.0, rising),
+    };
+
+    Rgb([
+        (r + min).round() as u8,
+        (g + min).round() as u8,
+        (b + min).round() as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    // two bright squares which only touch at a corner, and a dim pixel which is background
+    fn squares() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(6, 6, |x, y| match (x, y) {
+            (0..=1, 0..=1) | (2..=3, 2..=3) => Luma([255]),
+            (5, 5) => Luma([100]),
+            _ => Luma([0]),
+        }))
+    }
+
+    fn colors(image: &DynamicImage) -> Vec<Rgb<u8>> {
+        let mut colors = image
+            .as_rgb8()
+            .unwrap()
+            .pixels()
+            .copied()
+            .collect::<Vec<_>>();
+        colors.sort_by_key(|pixel| pixel.0);
+        colors.dedup();
+        colors
+    }
+
+    #[pm(connectivity = { 4, 8 }, expected = { 2, 1 })]
+    fn components_by_connectivity(connectivity: u32, expected: usize) {
+        let out = label_components(&squares(), connectivity).unwrap();
+
+        // the background is black, and each component has a color of its own
+        assert_eq!(colors(&out).len(), expected + 1);
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(5, 5), &Rgb([0, 0, 0]));
+    }
+
+    #[pm(value = { 0, 255 }, expected = { Rgb([0, 0, 0]), label_color(1) })]
+    fn single_pixel(value: u8, expected: Rgb<u8>) {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 1, Luma([value])));
+        let out = label_components(&image, 4).unwrap();
+
+        assert_eq!(out.dimensions(), (1, 1));
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(0, 0), &expected);
+    }
+
+    #[test]
+    fn single_pixel_from_file() {
+        let image = open_test_image(in_!("1x1_w.png"));
+        let out = label_components(&image, 8).unwrap();
+
+        assert_eq!(out.as_rgb8().unwrap().get_pixel(0, 0), &label_color(1));
+    }
+
+    #[test]
+    fn labels_have_distinct_colors() {
+        let colors = (0..64).map(label_color).collect::<Vec<_>>();
+
+        for (i, color) in colors.iter().enumerate() {
+            assert!(!colors[i + 1..].contains(color));
+        }
+    }
+
+    #[pm(connectivity = { 0, 6, 9 })]
+    fn invalid_connectivity(connectivity: u32) {
+        assert!(label_components(&squares(), connectivity).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use crate::operations::summed_area_table::{SummedAreaTable, Window};
+use sic_core::image::{DynamicImage, GrayImage, Luma};
+
+/// The largest possible variance of 8-bit samples, i.e. the variance of an area which consists
+/// for one half of black pixels and for the other half of white pixels.
+const MAX_VARIANCE: f64 = 127.5 * 127.5;
+
+/// The mean and variance of the pixels within the `2 * radius + 1` by `2 * radius + 1` window
+/// around each pixel of a gray image. Near the edges of the image, only the pixels within
+/// the bounds of the image are used.
+///
+/// Since both statistics are backed by summed-area tables, they can be computed in constant time
+/// for each pixel, regardless of the radius.
+pub(crate) struct LocalStatistics {
+    width: u32,
+    height: u32,
+    radius: u32,
+    sums: SummedAreaTable,
+    squares: SummedAreaTable,
+}
+
+impl LocalStatistics {
+    pub(crate) fn new(image: &GrayImage, radius: u32) -> Self {
+        let (width, height) = image.dimensions();
+        let value = |x, y| u64::from(image.get_pixel(x, y)[0]);
+
+        Self {
+            width,
+            height,
+            radius,
+            sums: SummedAreaTable::from_fn(width, height, value),
+            squares: SummedAreaTable::from_fn(width, height, |x, y| value(x, y).pow(2)),
+        }
+    }
+
+    pub(crate) fn mean(&self, x: u32, y: u32) -> f64 {
+        let window = self.window(x, y);
+
+        self.sums.sum(&window) as f64 / window.area() as f64
+    }
+
+    pub(crate) fn variance(&self, x: u32, y: u32) -> f64 {
+        let window = self.window(x, y);
+        let area = window.area() as f64;
+        let mean = self.sums.sum(&window) as f64 / area;
+
+        (self.squares.sum(&window) as f64 / area - mean * mean).max(0.0)
+    }
+
+    fn window(&self, x: u32, y: u32) -> Window {
+        Window::around(x, y, self.radius, self.width, self.height)
+    }
+}
+
+/// Replaces each pixel by the mean luminance of its neighbourhood.
+pub(crate) fn local_mean(image: &DynamicImage, radius: u32) -> DynamicImage {
+    let gray = image.to_luma();
+    let statistics = LocalStatistics::new(&gray, radius);
+
+    DynamicImage::ImageLuma8(GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        Luma([statistics.mean(x, y).round() as u8])
+    }))
+}
+
+/// Replaces each pixel by the variance of the luminance of its neighbourhood, where black
+/// represents no variance and white the largest possible variance.
+pub(crate) fn local_variance(image: &DynamicImage, radius: u32) -> DynamicImage {
+    let gray = image.to_luma();
+    let statistics = LocalStatistics::new(&gray, radius);
+
+    DynamicImage::ImageLuma8(GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        Luma([(statistics.variance(x, y) * 255.0 / MAX_VARIANCE).round() as u8])
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    // Vertical stripes of black and white pixels.
+    fn stripes() -> GrayImage {
+        GrayImage::from_fn(
+            4,
+            4,
+            |x, _| if x & 1 == 0 { Luma([0]) } else { Luma([255]) },
+        )
+    }
+
+    #[test]
+    fn mean_and_variance() {
+        let statistics = LocalStatistics::new(&stripes(), 1);
+
+        // window x in 0..2: two black and two white columns
+        approx_eq_f32!(statistics.mean(0, 0) as f32, 127.5);
+        approx_eq_f32!(statistics.variance(0, 0) as f32, MAX_VARIANCE as f32);
+
+        // window x in 0..3: two black and one white column
+        approx_eq_f32!(statistics.mean(1, 1) as f32, 85.0);
+    }
+
+    #[pm(radius = { 0, 1, 2, 100 })]
+    fn flat_image_has_no_variance(radius: u32) {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(5, 3, Luma([42])));
+
+        let mean = local_mean(&image, radius);
+        let variance = local_variance(&image, radius);
+
+        assert!(mean.as_luma8().unwrap().pixels().all(|px| px[0] == 42));
+        assert!(variance.as_luma8().unwrap().pixels().all(|px| px[0] == 0));
+    }
+
+    #[test]
+    fn variance_of_stripes_is_maximal() {
+        let image = DynamicImage::ImageLuma8(stripes());
+
+        let variance = local_variance(&image, 1);
+
+        assert_eq!(variance.as_luma8().unwrap().get_pixel(0, 0)[0], 255);
+        assert_eq!(variance.as_luma8().unwrap().get_pixel(3, 3)[0], 255);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::operations::{apply_to_color_samples, ColorSamplesOperation};
+use sic_core::image::{DynamicImage, GrayImage, ImageBuffer, Luma, Pixel};
+
+/// Replaces the luminance of an image by the luminance produced by `map`, which receives the
+/// current luminance of the image.
+///
+/// For color images, each color channel is shifted by the change in luminance of the pixel, so
+/// hue and saturation are mostly preserved. The alpha channel is left untouched.
+/// Images with 16-bit samples are converted to 8-bit samples.
+pub(crate) fn map_luminance<F>(image: &DynamicImage, map: F) -> DynamicImage
+where
+    F: FnOnce(&GrayImage) -> GrayImage,
+{
+    apply_to_color_samples(image, MapLuminance(map))
+}
+
+struct MapLuminance<F>(F);
+
+impl<F> ColorSamplesOperation for MapLuminance<F>
+where
+    F: FnOnce(&GrayImage) -> GrayImage,
+{
+    fn apply<P>(
+        self,
+        buffer: ImageBuffer<P, Vec<u8>>,
+        color_channels: usize,
+    ) -> ImageBuffer<P, Vec<u8>>
+    where
+        P: Pixel<Subpixel = u8> + 'static,
+    {
+        map_buffer(buffer, color_channels, self.0)
+    }
+}
+
+fn map_buffer<P, F>(
+    mut buffer: ImageBuffer<P, Vec<u8>>,
+    color_channels: usize,
+    map: F,
+) -> ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8> + 'static,
+    F: FnOnce(&GrayImage) -> GrayImage,
+{
+    let current = GrayImage::from_fn(buffer.width(), buffer.height(), |x, y| {
+        Luma([luminance(buffer.get_pixel(x, y).channels(), color_channels)])
+    });
+
+    let mapped = map(&current);
+
+    for ((pixel, before), after) in buffer
+        .pixels_mut()
+        .zip(current.pixels())
+        .zip(mapped.pixels())
+    {
+        let delta = i16::from(after[0]) - i16::from(before[0]);
+
+        for sample in pixel.channels_mut().iter_mut().take(color_channels) {
+            *sample = (i16::from(*sample) + delta).clamp(0, 255) as u8;
+        }
+    }
+
+    buffer
+}
+
+fn luminance(samples: &[u8], color_channels: usize) -> u8 {
+    if color_channels == 1 {
+        samples[0]
+    } else {
+        (0.299 * f32::from(samples[0])
+            + 0.587 * f32::from(samples[1])
+            + 0.114 * f32::from(samples[2]))
+        .round() as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{LumaA, Rgba, RgbaImage};
+
+    #[test]
+    fn shifts_color_channels_and_keeps_alpha() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba([100, 50, 250, 7])));
+
+        let out = map_luminance(&image, |current| {
+            GrayImage::from_fn(current.width(), current.height(), |x, y| {
+                Luma([current.get_pixel(x, y)[0] + 20])
+            })
+        });
+
+        assert!(out
+            .as_rgba8()
+            .unwrap()
+            .pixels()
+            .all(|px| px == &Rgba([120, 70, 255, 7])));
+    }
+
+    #[test]
+    fn gray_with_alpha() {
+        let image = DynamicImage::ImageLumaA8(ImageBuffer::from_pixel(1, 1, LumaA([10, 20])));
+
+        let out = map_luminance(&image, |current| {
+            GrayImage::from_pixel(current.width(), current.height(), Luma([200]))
+        });
+
+        assert_eq!(
+            out.as_luma_alpha8().unwrap().get_pixel(0, 0),
+            &LumaA([200, 20])
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use crate::errors::SicImageEngineError;
+use sic_core::image::{DynamicImage, ImageBuffer, Rgb, Rgba};
+
+/// A 3D color lookup table, as stored in the Adobe (Resolve) .cube format.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CubeLut {
+    size: usize,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    // Output colors, with the red input varying fastest, then green, then blue.
+    table: Vec<[f32; 3]>,
+}
+
+impl CubeLut {
+    /// The largest LUT size we accept; real world LUTs rarely exceed 65 entries per axis.
+    const MAX_SIZE: usize = 256;
+
+    pub(crate) fn parse(contents: &str) -> Result<Self, SicImageEngineError> {
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+
+        for (index, line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |message: &str| {
+                SicImageEngineError::LutParseError(line_number, message.to_string())
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let keyword = words.next().unwrap_or_default();
+
+            match keyword {
+                "TITLE" => {}
+                "LUT_1D_SIZE" => return Err(error("only 3D LUTs are supported")),
+                "LUT_3D_SIZE" => {
+                    let value = words
+                        .next()
+                        .and_then(|word| word.parse::<usize>().ok())
+                        .filter(|value| (2..=Self::MAX_SIZE).contains(value))
+                        .ok_or_else(|| error("LUT_3D_SIZE should be a number between 2 and 256"))?;
+                    size = Some(value);
+                }
+                "DOMAIN_MIN" => {
+                    domain_min =
+                        parse_triple(words).ok_or_else(|| error("DOMAIN_MIN requires 3 numbers"))?
+                }
+                "DOMAIN_MAX" => {
+                    domain_max =
+                        parse_triple(words).ok_or_else(|| error("DOMAIN_MAX requires 3 numbers"))?
+                }
+                "LUT_3D_INPUT_RANGE" => {
+                    let range = parse_numbers(words, 2)
+                        .ok_or_else(|| error("LUT_3D_INPUT_RANGE requires 2 numbers"))?;
+                    domain_min = [range[0]; 3];
+                    domain_max = [range[1]; 3];
+                }
+                _ if keyword.parse::<f32>().is_ok() => {
+                    let entry = parse_triple(line.split_whitespace())
+                        .ok_or_else(|| error("a table entry requires 3 numbers"))?;
+                    table.push(entry);
+                }
+                _ => return Err(error(&format!("unknown keyword '{}'", keyword))),
+            }
+        }
+
+        let size = size.ok_or_else(|| {
+            SicImageEngineError::LutParseError(0, "LUT_3D_SIZE is missing".to_string())
+        })?;
+
+        if table.len() != size.pow(3) {
+            return Err(SicImageEngineError::LutParseError(
+                0,
+                format!(
+                    "expected {} table entries for a LUT of size {}, but found {}",
+                    size.pow(3),
+                    size,
+                    table.len()
+                ),
+            ));
+        }
+
+        if (0..3).any(|channel| domain_max[channel] <= domain_min[channel]) {
+            return Err(SicImageEngineError::LutParseError(
+                0,
+                "DOMAIN_MAX should be larger than DOMAIN_MIN".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            size,
+            domain_min,
+            domain_max,
+            table,
+        })
+    }
+
+    /// Looks up the output color of the given input color, which is interpolated trilinearly
+    /// between the 8 surrounding table entries.
+    fn lookup(&self, color: [f32; 3]) -> [f32; 3] {
+        let last = (self.size - 1) as f32;
+
+        let mut lower = [0; 3];
+        let mut fraction = [0.0; 3];
+
+        for channel in 0..3 {
+            let (min, max) = (self.domain_min[channel], self.domain_max[channel]);
+            let position = ((color[channel] - min) / (max - min)).clamp(0.0, 1.0) * last;
+
+            // At the upper edge, interpolate fully towards the last entry from its predecessor
+            let floor = position.floor().min(last - 1.0);
+            lower[channel] = floor as usize;
+            fraction[channel] = position - floor;
+        }
+
+        let entry = |r: usize, g: usize, b: usize| {
+            self.table[(lower[0] + r)
+                + (lower[1] + g) * self.size
+                + (lower[2] + b) * self.size * self.size]
+        };
+
+        let mut out = [0.0; 3];
+
+        for (channel, value) in out.iter_mut().enumerate() {
+            let lerp = |lhs: f32, rhs: f32, t: f32| lhs + (rhs - lhs) * t;
+            let along_red = |g, b| {
+                lerp(
+                    entry(0, g, b)[channel],
+                    entry(1, g, b)[channel],
+                    fraction[0],
+                )
+            };
+            let along_green = |b| lerp(along_red(0, b), along_red(1, b), fraction[1]);
+
+            *value = lerp(along_green(0), along_green(1), fraction[2]);
+        }
+
+        out
+    }
+}
+
+/// Parses exactly `count` numbers.
+fn parse_numbers<'a>(words: impl Iterator<Item = &'a str>, count: usize) -> Option<Vec<f32>> {
+    let numbers = words
+        .map(|word| word.parse::<f32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(numbers).filter(|numbers| numbers.len() == count)
+}
+
+fn parse_triple<'a>(words: impl Iterator<Item = &'a str>) -> Option<[f32; 3]> {
+    parse_numbers(words, 3).map(|numbers| [numbers[0], numbers[1], numbers[2]])
+}
+
+/// Maps the color of each pixel through the 3D LUT. The alpha channel is left as is; images
+/// without an alpha channel produce an RGB image.
+pub(crate) fn apply_lut(image: &DynamicImage, lut: &CubeLut) -> DynamicImage {
+    let buffer = image.to_rgba();
+    let (width, height) = buffer.dimensions();
+
+    let map = |pixel: &Rgba<u8>| {
+        let color = [
+            f32::from(pixel[0]) / 255.0,
+            f32::from(pixel[1]) / 255.0,
+            f32::from(pixel[2]) / 255.0,
+        ];
+        let out = lut.lookup(color);
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        [channel(out[0]), channel(out[1]), channel(out[2])]
+    };
+
+    if buffer.pixels().all(|pixel| pixel[3] == u8::MAX) {
+        DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+            Rgb(map(buffer.get_pixel(x, y)))
+        }))
+    } else {
+        DynamicImage::ImageRgba8(ImageBuffer::from_fn(width, height, |x, y| {
+            let pixel = buffer.get_pixel(x, y);
+            let [r, g, b] = map(pixel);
+            Rgba([r, g, b, pixel[3]])
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    // Swaps the red and blue channels; the entries are listed with red varying fastest.
+    const SWAP_RED_BLUE: &str = r#"
+        # Created by hand
+        TITLE "swap red and blue"
+        LUT_3D_SIZE 2
+
+        0 0 0
+        0 0 1
+        0 1 0
+        0 1 1
+        1 0 0
+        1 0 1
+        1 1 0
+        1 1 1
+    "#;
+
+    #[test]
+    fn parse() {
+        let lut = CubeLut::parse(SWAP_RED_BLUE).unwrap();
+
+        assert_eq!(lut.size, 2);
+        assert_eq!(lut.domain_min, [0.0; 3]);
+        assert_eq!(lut.domain_max, [1.0; 3]);
+        assert_eq!(lut.table[1], [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn parse_domain() {
+        let lut = CubeLut::parse(
+            "LUT_3D_SIZE 2\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0",
+        )
+        .unwrap();
+
+        assert_eq!(lut.domain_max, [2.0; 3]);
+    }
+
+    #[test]
+    fn parse_input_range() {
+        let lut = CubeLut::parse(
+            "LUT_3D_SIZE 2\nLUT_3D_INPUT_RANGE 0.5 1\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0",
+        )
+        .unwrap();
+
+        assert_eq!(lut.domain_min, [0.5; 3]);
+        assert_eq!(lut.domain_max, [1.0; 3]);
+    }
+
+    #[pm(contents = {
+        "",
+        "0 0 0",
+        "LUT_3D_SIZE 1",
+        "LUT_3D_SIZE 2\n0 0 0",
+        "LUT_3D_SIZE 2\n0 0",
+        "LUT_1D_SIZE 2\n0 0 0\n1 1 1",
+        "LUT_3D_SIZE 2\nUNKNOWN 1",
+        "LUT_3D_SIZE 2\nDOMAIN_MIN 1 1 1\nDOMAIN_MAX 0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n0 0 0",
+    })]
+    fn parse_invalid(contents: &str) {
+        assert!(CubeLut::parse(contents).is_err());
+    }
+
+    #[test]
+    fn lookup_interpolates() {
+        let lut = CubeLut::parse(SWAP_RED_BLUE).unwrap();
+
+        assert_eq!(lut.lookup([1.0, 0.0, 0.0]), [0.0, 0.0, 1.0]);
+        assert_eq!(lut.lookup([0.25, 0.5, 0.75]), [0.75, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn lookup_clamps_to_domain() {
+        let lut = CubeLut::parse(SWAP_RED_BLUE).unwrap();
+
+        assert_eq!(lut.lookup([2.0, -1.0, 0.0]), [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn lookup_larger_lut() {
+        // An identity LUT of size 3, which has a table entry at the center of the cube
+        let mut contents = String::from("LUT_3D_SIZE 3\n");
+        for b in 0..3 {
+            for g in 0..3 {
+                for r in 0..3 {
+                    contents.push_str(&format!(
+                        "{} {} {}\n",
+                        r as f32 / 2.0,
+                        g as f32 / 2.0,
+                        b as f32 / 2.0
+                    ));
+                }
+            }
+        }
+
+        let lut = CubeLut::parse(&contents).unwrap();
+
+        assert_eq!(lut.lookup([0.5, 0.5, 0.5]), [0.5, 0.5, 0.5]);
+        assert_eq!(lut.lookup([0.75, 0.25, 1.0]), [0.75, 0.25, 1.0]);
+    }
+
+    #[test]
+    fn apply_swap() {
+        let lut = CubeLut::parse(SWAP_RED_BLUE).unwrap();
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+        let out = apply_lut(&image, &lut);
+
+        assert!(matches!(out, DynamicImage::ImageRgb8(_)));
+
+        for (x, y, pixel) in image.pixels() {
+            assert_eq!(
+                out.get_pixel(x, y),
+                Rgba([pixel[2], pixel[1], pixel[0], 255])
+            );
+        }
+    }
+
+    #[test]
+    fn apply_keeps_alpha() {
+        let lut = CubeLut::parse(SWAP_RED_BLUE).unwrap();
+        let image = RgbaImage::from_pixel(1, 1, Rgba([10, 20, 30, 40]));
+        let out = apply_lut(&DynamicImage::ImageRgba8(image), &lut);
+
+        assert_eq!(out.get_pixel(0, 0), Rgba([30, 20, 10, 40]));
+    }
+}
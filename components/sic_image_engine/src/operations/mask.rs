@@ -0,0 +1,68 @@
+use sic_core::image::{DynamicImage, GrayImage};
+
+/// Blends the result of an operation with the image as it was before the operation, by a
+/// grayscale mask of the same size: where the mask is white the result is used, where it is
+/// black the original is kept, and shades of gray blend both partially.
+///
+/// The blended image has an alpha channel if either the original or the result has one.
+pub(crate) fn blend_with_mask(
+    original: &DynamicImage,
+    result: &DynamicImage,
+    mask: &GrayImage,
+) -> DynamicImage {
+    let before = original.to_rgba();
+    let mut blended = result.to_rgba();
+
+    for ((after, before), weight) in blended.pixels_mut().zip(before.pixels()).zip(mask.pixels()) {
+        let weight = f32::from(weight[0]) / 255.0;
+
+        for (after, &before) in after.0.iter_mut().zip(before.0.iter()) {
+            let before = f32::from(before);
+            *after = (before + (f32::from(*after) - before) * weight).round() as u8;
+        }
+    }
+
+    let blended = DynamicImage::ImageRgba8(blended);
+
+    if original.color().has_alpha() || result.color().has_alpha() {
+        blended
+    } else {
+        DynamicImage::ImageRgb8(blended.to_rgb())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+
+    #[test]
+    fn blends_by_mask() {
+        let original = DynamicImage::ImageRgb8(RgbImage::from_pixel(3, 1, Rgb([0, 0, 0])));
+        let result = DynamicImage::ImageRgb8(RgbImage::from_pixel(3, 1, Rgb([200, 100, 50])));
+
+        let mut mask = GrayImage::new(3, 1);
+        mask.put_pixel(0, 0, Luma([255]));
+        mask.put_pixel(1, 0, Luma([0]));
+        mask.put_pixel(2, 0, Luma([51]));
+
+        let out = blend_with_mask(&original, &result, &mask);
+
+        assert!(out.as_rgb8().is_some());
+        assert_eq!(out.get_pixel(0, 0), Rgba([200, 100, 50, 255]));
+        assert_eq!(out.get_pixel(1, 0), Rgba([0, 0, 0, 255]));
+        assert_eq!(out.get_pixel(2, 0), Rgba([40, 20, 10, 255]));
+    }
+
+    #[test]
+    fn blends_alpha() {
+        let original = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb([0, 255, 0])));
+        let result = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([0, 255, 0, 0])));
+        let mask = GrayImage::from_pixel(1, 1, Luma([102]));
+
+        let out = blend_with_mask(&original, &result, &mask);
+
+        assert!(out.color().has_alpha());
+        assert_eq!(out.get_pixel(0, 0), Rgba([0, 255, 0, 153]));
+    }
+}
@@ -0,0 +1,87 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::{apply_to_color_samples, ColorSamplesOperation};
+use imageproc::filter::median_filter;
+use sic_core::image::{DynamicImage, GenericImageView, ImageBuffer, Pixel};
+
+/// Larger radii are slow, and don't remove more noise than a smaller radius would.
+const MAX_RADIUS: u32 = 255;
+
+/// Replaces each sample by the median of the samples within a square of `2 * radius + 1` pixels
+/// around it. Removes salt-and-pepper noise, e.g. from scanned documents, while keeping edges
+/// sharp. Images with 16-bit samples are converted to 8-bit samples.
+///
+/// The radius should be smaller than the width or height of the image, and at most 255.
+pub(crate) fn median(
+    image: &DynamicImage,
+    radius: u32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+    let limit = MAX_RADIUS.min(width.max(height).saturating_sub(1));
+
+    if radius > limit {
+        return Err(SicImageEngineError::MedianInvalidRadius(radius, limit));
+    }
+
+    Ok(apply_to_color_samples(image, Median(radius)))
+}
+
+struct Median(u32);
+
+impl ColorSamplesOperation for Median {
+    fn apply<P>(self, buffer: ImageBuffer<P, Vec<u8>>, _: usize) -> ImageBuffer<P, Vec<u8>>
+    where
+        P: Pixel<Subpixel = u8> + 'static,
+    {
+        median_filter(&buffer, self.0, self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma, Rgba, RgbaImage};
+
+    #[test]
+    fn removes_salt_and_pepper_noise() {
+        let mut image = GrayImage::from_pixel(5, 5, Luma([128]));
+        image.put_pixel(1, 1, Luma([255]));
+        image.put_pixel(3, 2, Luma([0]));
+
+        let out = median(&DynamicImage::ImageLuma8(image), 1).unwrap();
+
+        assert!(out.as_luma8().unwrap().pixels().all(|px| px[0] == 128));
+    }
+
+    #[test]
+    fn keeps_edges() {
+        let image = GrayImage::from_fn(6, 6, |x, _| Luma([if x < 3 { 0 } else { 255 }]));
+
+        let out = median(&DynamicImage::ImageLuma8(image.clone()), 2).unwrap();
+
+        assert_eq!(out.as_luma8().unwrap(), &image);
+    }
+
+    #[test]
+    fn radius_zero_is_identity() {
+        let image = RgbaImage::from_fn(3, 3, |x, y| Rgba([x as u8, y as u8, 7, 200]));
+
+        let out = median(&DynamicImage::ImageRgba8(image.clone()), 0).unwrap();
+
+        assert_eq!(out.as_rgba8().unwrap(), &image);
+    }
+
+    #[test]
+    fn radius_is_limited() {
+        let image = DynamicImage::ImageLuma8(GrayImage::new(4, 2));
+
+        assert!(median(&image, 3).is_ok());
+        assert!(matches!(
+            median(&image, 4),
+            Err(SicImageEngineError::MedianInvalidRadius(4, 3))
+        ));
+        assert!(matches!(
+            median(&DynamicImage::ImageLuma8(GrayImage::new(1000, 1)), 100_000),
+            Err(SicImageEngineError::MedianInvalidRadius(100_000, 255))
+        ));
+    }
+}
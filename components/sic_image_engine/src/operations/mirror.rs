@@ -0,0 +1,118 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::mirror::MirrorMode;
+use sic_core::image::{DynamicImage, GenericImageView, ImageBuffer, Pixel};
+
+/// Appends reflections of the image to the image, e.g. to create seamless textures: a reflection
+/// to its right, below it, or both (with a reflection in both directions in the bottom right
+/// corner), which doubles the width, the height, or both.
+pub(crate) fn mirror(
+    image: &DynamicImage,
+    mode: MirrorMode,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+
+    let double = |size: u32| {
+        size.checked_mul(2)
+            .ok_or(SicImageEngineError::MirrorTooLarge)
+    };
+
+    let size = match mode {
+        MirrorMode::Horizontal => (double(width)?, height),
+        MirrorMode::Vertical => (width, double(height)?),
+        MirrorMode::Quad => (double(width)?, double(height)?),
+    };
+
+    let out = match image {
+        DynamicImage::ImageLuma8(buffer) => DynamicImage::ImageLuma8(mirror_buffer(buffer, size)),
+        DynamicImage::ImageLumaA8(buffer) => DynamicImage::ImageLumaA8(mirror_buffer(buffer, size)),
+        DynamicImage::ImageRgb8(buffer) => DynamicImage::ImageRgb8(mirror_buffer(buffer, size)),
+        DynamicImage::ImageRgba8(buffer) => DynamicImage::ImageRgba8(mirror_buffer(buffer, size)),
+        DynamicImage::ImageBgr8(buffer) => DynamicImage::ImageBgr8(mirror_buffer(buffer, size)),
+        DynamicImage::ImageBgra8(buffer) => DynamicImage::ImageBgra8(mirror_buffer(buffer, size)),
+        DynamicImage::ImageLuma16(buffer) => DynamicImage::ImageLuma16(mirror_buffer(buffer, size)),
+        DynamicImage::ImageLumaA16(buffer) => {
+            DynamicImage::ImageLumaA16(mirror_buffer(buffer, size))
+        }
+        DynamicImage::ImageRgb16(buffer) => DynamicImage::ImageRgb16(mirror_buffer(buffer, size)),
+        DynamicImage::ImageRgba16(buffer) => DynamicImage::ImageRgba16(mirror_buffer(buffer, size)),
+    };
+
+    Ok(out)
+}
+
+/// Pixels beyond the width or height of the buffer are reflected back into it.
+fn mirror_buffer<P>(
+    buffer: &ImageBuffer<P, Vec<P::Subpixel>>,
+    (width, height): (u32, u32),
+) -> ImageBuffer<P, Vec<P::Subpixel>>
+where
+    P: Pixel + 'static,
+{
+    let (w, h) = buffer.dimensions();
+    let reflect = |v: u32, size: u32| if v < size { v } else { 2 * size - 1 - v };
+
+    ImageBuffer::from_fn(width, height, |x, y| {
+        *buffer.get_pixel(reflect(x, w), reflect(y, h))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma};
+
+    fn rows(image: &DynamicImage) -> Vec<Vec<u8>> {
+        image
+            .as_luma8()
+            .unwrap()
+            .rows()
+            .map(|row| row.map(|px| px[0]).collect())
+            .collect()
+    }
+
+    fn image() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(2, 2, |x, y| Luma([(y * 2 + x) as u8])))
+    }
+
+    #[test]
+    fn horizontal() {
+        let out = mirror(&image(), MirrorMode::Horizontal).unwrap();
+
+        assert_eq!(rows(&out), vec![vec![0, 1, 1, 0], vec![2, 3, 3, 2]]);
+    }
+
+    #[test]
+    fn vertical() {
+        let out = mirror(&image(), MirrorMode::Vertical).unwrap();
+
+        assert_eq!(
+            rows(&out),
+            vec![vec![0, 1], vec![2, 3], vec![2, 3], vec![0, 1]]
+        );
+    }
+
+    #[test]
+    fn quad() {
+        let out = mirror(&image(), MirrorMode::Quad).unwrap();
+
+        assert_eq!(
+            rows(&out),
+            vec![
+                vec![0, 1, 1, 0],
+                vec![2, 3, 3, 2],
+                vec![2, 3, 3, 2],
+                vec![0, 1, 1, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_color_type() {
+        let image = DynamicImage::new_rgba16(3, 1);
+
+        let out = mirror(&image, MirrorMode::Horizontal).unwrap();
+
+        assert_eq!(out.color(), image.color());
+        assert_eq!(out.dimensions(), (6, 1));
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod adaptive_threshold;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod clahe;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod color_font;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod corners;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod distance_transform;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod draw_shape;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod draw_text;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod font;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod label_components;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod median;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod morphology;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod noise;
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod perspective;
+
+pub(crate) mod alpha;
+pub(crate) mod auto_enhance;
+pub(crate) mod border;
+pub(crate) mod box_blur;
+pub(crate) mod canvas;
+pub(crate) mod channel;
+pub(crate) mod chroma_key;
+pub(crate) mod composite;
+pub(crate) mod contours;
+pub(crate) mod convolve;
+pub(crate) mod crop_gravity;
+pub(crate) mod dither;
+pub(crate) mod duotone;
+pub(crate) mod equalize;
+pub(crate) mod extend;
+pub(crate) mod find;
+pub(crate) mod fisheye;
+pub(crate) mod flatten_checker;
+pub(crate) mod gradient_fill;
+pub(crate) mod gradient_map;
+pub(crate) mod grayscale;
+pub(crate) mod histogram;
+pub(crate) mod local_statistics;
+pub(crate) mod luminance;
+pub(crate) mod lut;
+pub(crate) mod mask;
+pub(crate) mod mirror;
+pub(crate) mod nine_slice;
+pub(crate) mod oil_paint;
+pub(crate) mod pad_to_ratio;
+pub(crate) mod palette;
+pub(crate) mod pixel_upscale;
+pub(crate) mod radial_blur;
+pub(crate) mod redact;
+pub(crate) mod region;
+pub(crate) mod remap;
+pub(crate) mod resample;
+pub(crate) mod sharpen;
+pub(crate) mod smart_crop;
+pub(crate) mod stack;
+pub(crate) mod stamp;
+pub(crate) mod summed_area_table;
+pub(crate) mod tonemap;
+pub(crate) mod trim;
+
+use sic_core::image::{DynamicImage, ImageBuffer, Pixel};
+
+/// An operation on the samples of an image with 8-bit samples, which treats the color channels of
+/// grayscale and RGB images alike; see [`apply_to_color_samples`].
+pub(crate) trait ColorSamplesOperation {
+    /// The first `color_channels` samples of each pixel describe its color, the remaining sample
+    /// (if any) is the alpha channel.
+    fn apply<P>(
+        self,
+        buffer: ImageBuffer<P, Vec<u8>>,
+        color_channels: usize,
+    ) -> ImageBuffer<P, Vec<u8>>
+    where
+        P: Pixel<Subpixel = u8> + 'static;
+}
+
+/// Applies the operation to the image as a grayscale, grayscale with alpha, RGB or RGBA buffer,
+/// whichever is closest to its color type. Images with 16-bit samples are converted to 8-bit
+/// samples.
+pub(crate) fn apply_to_color_samples(
+    image: &DynamicImage,
+    operation: impl ColorSamplesOperation,
+) -> DynamicImage {
+    match image {
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLuma16(_) => {
+            DynamicImage::ImageLuma8(operation.apply(image.to_luma(), 1))
+        }
+        DynamicImage::ImageLumaA8(_) | DynamicImage::ImageLumaA16(_) => {
+            DynamicImage::ImageLumaA8(operation.apply(image.to_luma_alpha(), 1))
+        }
+        DynamicImage::ImageRgb8(_) | DynamicImage::ImageBgr8(_) | DynamicImage::ImageRgb16(_) => {
+            DynamicImage::ImageRgb8(operation.apply(image.to_rgb(), 3))
+        }
+        _ => DynamicImage::ImageRgba8(operation.apply(image.to_rgba(), 3)),
+    }
+}
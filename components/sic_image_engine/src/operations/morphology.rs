@@ -0,0 +1,156 @@
+use sic_core::image::{DynamicImage, GrayImage, Luma};
+
+/// Grows the bright areas of the image: each pixel becomes the brightest pixel within a square of
+/// `2 * radius + 1` pixels around it.
+///
+/// Like the other morphological operations, it works on grayscale images, so colored images are
+/// converted to grayscale first. On binarized images, such as the output of adaptive-threshold,
+/// this is the usual binary dilation with a square structuring element.
+pub(crate) fn dilate(image: &DynamicImage, radius: u32) -> DynamicImage {
+    DynamicImage::ImageLuma8(extremum_filter(&image.to_luma(), radius, u8::max))
+}
+
+/// Shrinks the bright areas of the image: each pixel becomes the darkest pixel within a square of
+/// `2 * radius + 1` pixels around it.
+pub(crate) fn erode(image: &DynamicImage, radius: u32) -> DynamicImage {
+    DynamicImage::ImageLuma8(extremum_filter(&image.to_luma(), radius, u8::min))
+}
+
+/// Erodes and then dilates the image, which removes bright specks smaller than the square, while
+/// other bright areas keep their size.
+pub(crate) fn open(image: &DynamicImage, radius: u32) -> DynamicImage {
+    let eroded = extremum_filter(&image.to_luma(), radius, u8::min);
+
+    DynamicImage::ImageLuma8(extremum_filter(&eroded, radius, u8::max))
+}
+
+/// Dilates and then erodes the image, which fills dark holes and gaps smaller than the square,
+/// while other dark areas keep their size.
+pub(crate) fn close(image: &DynamicImage, radius: u32) -> DynamicImage {
+    let dilated = extremum_filter(&image.to_luma(), radius, u8::max);
+
+    DynamicImage::ImageLuma8(extremum_filter(&dilated, radius, u8::min))
+}
+
+/// Picks the extremum of the square window around each pixel; the window is separable, so the
+/// rows are filtered first, and then the columns of the result. The window is clipped at the
+/// edges of the image.
+fn extremum_filter(image: &GrayImage, radius: u32, pick: fn(u8, u8) -> u8) -> GrayImage {
+    let (width, height) = image.dimensions();
+
+    let rows = GrayImage::from_fn(width, height, |x, y| {
+        let (left, right) = (
+            x.saturating_sub(radius),
+            x.saturating_add(radius).min(width - 1),
+        );
+
+        Luma([(left..=right)
+            .map(|nx| image.get_pixel(nx, y)[0])
+            .fold(image.get_pixel(x, y)[0], pick)])
+    });
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let (top, bottom) = (
+            y.saturating_sub(radius),
+            y.saturating_add(radius).min(height - 1),
+        );
+
+        Luma([(top..=bottom)
+            .map(|ny| rows.get_pixel(x, ny)[0])
+            .fold(rows.get_pixel(x, y)[0], pick)])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_rows(rows: &[&[u8]]) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(
+            rows[0].len() as u32,
+            rows.len() as u32,
+            |x, y| Luma([rows[y as usize][x as usize]]),
+        ))
+    }
+
+    fn to_rows(image: &DynamicImage) -> Vec<Vec<u8>> {
+        let image = image.as_luma8().unwrap();
+
+        image
+            .rows()
+            .map(|row| row.map(|px| px[0]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn dilate_grows_bright_areas() {
+        let image = from_rows(&[&[0, 0, 0, 0], &[0, 0, 0, 0], &[0, 0, 200, 0], &[0, 0, 0, 0]]);
+
+        let out = dilate(&image, 1);
+
+        assert_eq!(
+            to_rows(&out),
+            vec![
+                vec![0, 0, 0, 0],
+                vec![0, 200, 200, 200],
+                vec![0, 200, 200, 200],
+                vec![0, 200, 200, 200],
+            ]
+        );
+    }
+
+    #[test]
+    fn erode_shrinks_bright_areas() {
+        let image = from_rows(&[
+            &[255, 255, 255, 0],
+            &[255, 255, 255, 0],
+            &[255, 255, 255, 0],
+            &[255, 255, 255, 0],
+        ]);
+
+        let out = erode(&image, 1);
+
+        assert_eq!(
+            to_rows(&out),
+            vec![
+                vec![255, 255, 0, 0],
+                vec![255, 255, 0, 0],
+                vec![255, 255, 0, 0],
+                vec![255, 255, 0, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn open_removes_specks() {
+        let mut rows = vec![vec![0u8; 8]; 8];
+        // a speck, and a block which is larger than the square
+        rows[0][7] = 255;
+        (2..6).for_each(|y| (0..4).for_each(|x| rows[y][x] = 255));
+        let image = from_rows(&rows.iter().map(Vec::as_slice).collect::<Vec<_>>());
+
+        let out = open(&image, 1);
+
+        rows[0][7] = 0;
+        assert_eq!(to_rows(&out), rows);
+    }
+
+    #[test]
+    fn close_fills_holes() {
+        let mut rows = vec![vec![255u8; 5]; 5];
+        rows[2][2] = 0;
+        let image = from_rows(&rows.iter().map(Vec::as_slice).collect::<Vec<_>>());
+
+        let out = close(&image, 1);
+
+        assert!(to_rows(&out).iter().flatten().all(|&px| px == 255));
+    }
+
+    #[test]
+    fn radius_zero_is_identity() {
+        let image = from_rows(&[&[0, 50, 100], &[150, 200, 250]]);
+
+        assert_eq!(to_rows(&dilate(&image, 0)), to_rows(&image));
+        assert_eq!(to_rows(&erode(&image, 0)), to_rows(&image));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::resample::resize;
+use crate::wrapper::filter_type::FilterTypeWrap;
+use crate::wrapper::nine_slice::NineSliceInputs;
+use sic_core::image::{imageops, DynamicImage, GenericImageView, RgbaImage};
+
+/// Scales the image to the size of the inputs, while keeping its borders crisp, like a nine-slice
+/// (or 9-patch) image of a UI toolkit does.
+///
+/// The insets divide the image in a grid of nine regions: the corners are copied as they are,
+/// the top and bottom edges are only scaled horizontally, the left and right edges only
+/// vertically, and the center is scaled in both directions. Each region is scaled on its own,
+/// by the given sampling filter.
+pub(crate) fn nine_slice(
+    image: &DynamicImage,
+    inputs: &NineSliceInputs,
+    filter: FilterTypeWrap,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+    let (out_width, out_height) = inputs.size();
+
+    let horizontal = inputs.left().checked_add(inputs.right());
+    let vertical = inputs.top().checked_add(inputs.bottom());
+
+    let insets_fit = matches!(
+        (horizontal, vertical),
+        (Some(h), Some(v)) if h <= width && v <= height && h <= out_width && v <= out_height
+    );
+
+    if !insets_fit || out_width == 0 || out_height == 0 {
+        return Err(SicImageEngineError::NineSliceInvalidInsets(
+            width, height, out_width, out_height,
+        ));
+    }
+
+    // the offsets and lengths of the left/top edge, the center and the right/bottom edge
+    let columns = slices(inputs.left(), inputs.right(), width, out_width);
+    let rows = slices(inputs.top(), inputs.bottom(), height, out_height);
+
+    let mut canvas = RgbaImage::new(out_width, out_height);
+
+    for &((y, h), (out_y, out_h)) in &rows {
+        for &((x, w), (out_x, out_w)) in &columns {
+            if w == 0 || h == 0 || out_w == 0 || out_h == 0 {
+                continue;
+            }
+
+            let region = image.crop_imm(x, y, w, h);
+            let region = if (w, h) == (out_w, out_h) {
+                region
+            } else {
+                resize(&region, out_w, out_h, filter, false)?
+            };
+
+            imageops::replace(&mut canvas, &region.to_rgba(), out_x, out_y);
+        }
+    }
+
+    let canvas = DynamicImage::ImageRgba8(canvas);
+
+    if image.color().has_alpha() {
+        Ok(canvas)
+    } else {
+        Ok(DynamicImage::ImageRgb8(canvas.to_rgb()))
+    }
+}
+
+/// Divides a dimension of the image and of the output in three slices, which are given as pairs
+/// of an offset and a length.
+fn slices(start: u32, end: u32, length: u32, out_length: u32) -> [((u32, u32), (u32, u32)); 3] {
+    [
+        ((0, start), (0, start)),
+        (
+            (start, length - start - end),
+            (start, out_length - start - end),
+        ),
+        ((length - end, end), (out_length - end, end)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{imageops::FilterType, Rgb, RgbImage};
+
+    fn nearest() -> FilterTypeWrap {
+        FilterTypeWrap::new(FilterType::Nearest)
+    }
+
+    // a 4x4 image with a 1 pixel black border around a white center, of which the top left corner
+    // is red
+    fn framed() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(4, 4, |x, y| match (x, y) {
+            (0, 0) => Rgb([255, 0, 0]),
+            (1..=2, 1..=2) => Rgb([255, 255, 255]),
+            _ => Rgb([0, 0, 0]),
+        }))
+    }
+
+    #[test]
+    fn keeps_borders_crisp() {
+        let inputs = NineSliceInputs::new((1, 1, 1, 1), (10, 6));
+
+        let out = nine_slice(&framed(), &inputs, nearest()).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (10, 6));
+        assert_eq!(out.get_pixel(0, 0), &Rgb([255, 0, 0]));
+        assert_eq!(out.get_pixel(1, 0), &Rgb([0, 0, 0]));
+
+        for (x, y, pixel) in out.enumerate_pixels() {
+            let border = x == 0 || y == 0 || x == 9 || y == 5;
+
+            if !border {
+                assert_eq!(pixel, &Rgb([255, 255, 255]), "({}, {})", x, y);
+            } else if (x, y) != (0, 0) {
+                assert_eq!(pixel, &Rgb([0, 0, 0]), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn shrinks_center() {
+        let inputs = NineSliceInputs::new((1, 1, 1, 1), (2, 3));
+
+        let out = nine_slice(&framed(), &inputs, nearest()).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (2, 3));
+        assert_eq!(out.get_pixel(0, 0), &Rgb([255, 0, 0]));
+        assert_eq!(out.get_pixel(0, 1), &Rgb([0, 0, 0]));
+        assert_eq!(out.get_pixel(1, 2), &Rgb([0, 0, 0]));
+    }
+
+    #[test]
+    fn insets_larger_than_image() {
+        let inputs = NineSliceInputs::new((3, 1, 2, 1), (10, 10));
+
+        assert!(nine_slice(&framed(), &inputs, nearest()).is_err());
+    }
+
+    #[test]
+    fn insets_larger_than_output() {
+        let inputs = NineSliceInputs::new((1, 1, 1, 1), (1, 10));
+
+        assert!(nine_slice(&framed(), &inputs, nearest()).is_err());
+    }
+
+    #[test]
+    fn insets_overflow() {
+        let inputs = NineSliceInputs::new((u32::MAX, 1, 1, 1), (10, 10));
+
+        assert!(nine_slice(&framed(), &inputs, nearest()).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::{apply_to_color_samples, ColorSamplesOperation};
+use crate::wrapper::noise::{NoiseInputs, NoiseKind};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use sic_core::image::{DynamicImage, ImageBuffer, Pixel};
+
+/// Adds random noise to the image, e.g. to test how well other operations cope with it. The amount
+/// should be between 0 and 1; see `NoiseInputs::amount`.
+///
+/// When a seed is given, the noise only depends on the seed and the image, so the output can be
+/// reproduced. The alpha channel is left untouched. Images with 16-bit samples are converted to
+/// 8-bit samples.
+pub(crate) fn noise(
+    image: &DynamicImage,
+    inputs: &NoiseInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let amount = inputs.amount();
+    if !(0.0..=1.0).contains(&amount) {
+        return Err(SicImageEngineError::NoiseInvalidAmount(amount));
+    }
+
+    let mut rng = match inputs.seed() {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let noise = Noise {
+        kind: inputs.kind(),
+        amount,
+        rng: &mut rng,
+    };
+
+    Ok(apply_to_color_samples(image, noise))
+}
+
+struct Noise<'r> {
+    kind: NoiseKind,
+    amount: f32,
+    rng: &'r mut StdRng,
+}
+
+impl ColorSamplesOperation for Noise<'_> {
+    fn apply<P>(
+        self,
+        buffer: ImageBuffer<P, Vec<u8>>,
+        color_channels: usize,
+    ) -> ImageBuffer<P, Vec<u8>>
+    where
+        P: Pixel<Subpixel = u8> + 'static,
+    {
+        noise_buffer(buffer, color_channels, self.kind, self.amount, self.rng)
+    }
+}
+
+fn noise_buffer<P>(
+    mut buffer: ImageBuffer<P, Vec<u8>>,
+    color_channels: usize,
+    kind: NoiseKind,
+    amount: f32,
+    rng: &mut StdRng,
+) -> ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8> + 'static,
+{
+    match kind {
+        NoiseKind::Gaussian => {
+            // the amount was checked to be between 0 and 1, so the standard deviation is valid
+            let normal = Normal::new(0.0, amount * f32::from(u8::MAX)).unwrap();
+
+            for pixel in buffer.pixels_mut() {
+                for sample in &mut pixel.channels_mut()[..color_channels] {
+                    let value = f32::from(*sample) + normal.sample(rng);
+                    *sample = value.round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+        NoiseKind::SaltPepper => {
+            for pixel in buffer.pixels_mut() {
+                if rng.gen::<f32>() < amount {
+                    let value = if rng.gen::<bool>() { u8::MAX } else { 0 };
+                    pixel.channels_mut()[..color_channels]
+                        .iter_mut()
+                        .for_each(|sample| *sample = value);
+                }
+            }
+        }
+    }
+
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma, Rgba, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    fn gray() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(16, 16, Luma([128])))
+    }
+
+    #[pm(kind = { NoiseKind::Gaussian, NoiseKind::SaltPepper })]
+    fn same_seed_same_noise(kind: NoiseKind) {
+        let first = noise(&gray(), &NoiseInputs::new(kind, 0.5, Some(7))).unwrap();
+        let second = noise(&gray(), &NoiseInputs::new(kind, 0.5, Some(7))).unwrap();
+        let other = noise(&gray(), &NoiseInputs::new(kind, 0.5, Some(8))).unwrap();
+
+        assert_eq!(first.as_luma8().unwrap(), second.as_luma8().unwrap());
+        assert_ne!(first.as_luma8().unwrap(), other.as_luma8().unwrap());
+    }
+
+    #[pm(kind = { NoiseKind::Gaussian, NoiseKind::SaltPepper })]
+    fn amount_zero_is_identity(kind: NoiseKind) {
+        let out = noise(&gray(), &NoiseInputs::new(kind, 0.0, None)).unwrap();
+
+        assert_eq!(out.as_luma8().unwrap(), gray().as_luma8().unwrap());
+    }
+
+    #[test]
+    fn salt_pepper_only_black_or_white() {
+        let out = noise(
+            &gray(),
+            &NoiseInputs::new(NoiseKind::SaltPepper, 1.0, Some(1)),
+        )
+        .unwrap();
+
+        assert!(out
+            .as_luma8()
+            .unwrap()
+            .pixels()
+            .all(|px| px[0] == 0 || px[0] == 255));
+    }
+
+    #[pm(kind = { NoiseKind::Gaussian, NoiseKind::SaltPepper })]
+    fn keeps_alpha(kind: NoiseKind) {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(8, 8, |x, _| {
+            Rgba([100, 100, 100, x as u8 * 30])
+        }));
+
+        let out = noise(&image, &NoiseInputs::new(kind, 1.0, Some(3))).unwrap();
+        let out = out.as_rgba8().unwrap();
+
+        assert!(out
+            .enumerate_pixels()
+            .all(|(x, _, px)| px[3] == x as u8 * 30));
+    }
+
+    #[pm(amount = { -0.1, 1.1, f32::NAN })]
+    fn invalid_amount(amount: f32) {
+        assert!(noise(
+            &gray(),
+            &NoiseInputs::new(NoiseKind::Gaussian, amount, None)
+        )
+        .is_err());
+    }
+}
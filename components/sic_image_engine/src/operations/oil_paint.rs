@@ -0,0 +1,161 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::{apply_to_color_samples, ColorSamplesOperation};
+use sic_core::image::{DynamicImage, ImageBuffer, Pixel};
+
+/// Gives the image the look of an oil painting. The pixels within `radius` pixels around each
+/// pixel are sorted into `levels` buckets by their intensity. The pixel then takes the mean color
+/// of the most common bucket, which flattens details into strokes of a single color, while edges
+/// between differently lit areas are kept.
+///
+/// The number of levels should be between 1 and 256; fewer levels give broader strokes. The alpha
+/// channel is left untouched. Images with 16-bit samples are converted to 8-bit samples.
+pub(crate) fn oil_paint(
+    image: &DynamicImage,
+    radius: u32,
+    levels: u32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if !(1..=256).contains(&levels) {
+        return Err(SicImageEngineError::OilPaintInvalidLevels(levels));
+    }
+
+    Ok(apply_to_color_samples(image, OilPaint { radius, levels }))
+}
+
+struct OilPaint {
+    radius: u32,
+    levels: u32,
+}
+
+impl ColorSamplesOperation for OilPaint {
+    fn apply<P>(
+        self,
+        buffer: ImageBuffer<P, Vec<u8>>,
+        color_channels: usize,
+    ) -> ImageBuffer<P, Vec<u8>>
+    where
+        P: Pixel<Subpixel = u8> + 'static,
+    {
+        paint_buffer(buffer, color_channels, self.radius, self.levels)
+    }
+}
+
+fn paint_buffer<P>(
+    buffer: ImageBuffer<P, Vec<u8>>,
+    color_channels: usize,
+    radius: u32,
+    levels: u32,
+) -> ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8> + 'static,
+{
+    let (width, height) = buffer.dimensions();
+    let mut out = buffer.clone();
+
+    // the bucket of each pixel only depends on the pixel itself, so it's computed once
+    let buckets = buffer
+        .pixels()
+        .map(|pixel| (u32::from(pixel.to_luma()[0]) * levels / 256) as usize)
+        .collect::<Vec<_>>();
+
+    let mut counts = vec![0u32; levels as usize];
+    let mut sums = vec![[0u32; 3]; levels as usize];
+
+    for y in 0..height {
+        for x in 0..width {
+            counts.iter_mut().for_each(|count| *count = 0);
+            sums.iter_mut().for_each(|sum| *sum = [0; 3]);
+
+            let (top, bottom) = (y.saturating_sub(radius), (y + radius).min(height - 1));
+            let (left, right) = (x.saturating_sub(radius), (x + radius).min(width - 1));
+
+            for ny in top..=bottom {
+                for nx in left..=right {
+                    let bucket = buckets[(ny * width + nx) as usize];
+                    let channels = buffer.get_pixel(nx, ny).channels();
+
+                    counts[bucket] += 1;
+                    for (sum, sample) in sums[bucket].iter_mut().zip(&channels[..color_channels]) {
+                        *sum += u32::from(*sample);
+                    }
+                }
+            }
+
+            // on a tie, the darkest of the most common buckets wins
+            let (bucket, count) =
+                counts
+                    .iter()
+                    .enumerate()
+                    .fold((0, 0), |best, (bucket, &count)| {
+                        if count > best.1 {
+                            (bucket, count)
+                        } else {
+                            best
+                        }
+                    });
+
+            let pixel = out.get_pixel_mut(x, y);
+            for (sample, sum) in pixel.channels_mut()[..color_channels]
+                .iter_mut()
+                .zip(&sums[bucket])
+            {
+                *sample = ((sum + count / 2) / count) as u8;
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma, Rgba, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    #[test]
+    fn takes_mean_of_most_common_intensity() {
+        // mostly dark pixels with a similar intensity, and a single bright one
+        let image = DynamicImage::ImageLuma8(GrayImage::from_fn(3, 3, |x, y| match (x, y) {
+            (1, 1) => Luma([250]),
+            (0, _) => Luma([10]),
+            _ => Luma([20]),
+        }));
+
+        let out = oil_paint(&image, 1, 4).unwrap();
+        let out = out.as_luma8().unwrap();
+
+        // the center takes the mean of the 8 dark pixels around it
+        assert_eq!(out.get_pixel(1, 1)[0], 16);
+    }
+
+    #[test]
+    fn radius_zero_is_identity() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(4, 3, |x, y| {
+            Rgba([x as u8 * 60, y as u8 * 80, 7, 200])
+        }));
+
+        let out = oil_paint(&image, 0, 8).unwrap();
+
+        assert_eq!(out.as_rgba8().unwrap(), image.as_rgba8().unwrap());
+    }
+
+    #[test]
+    fn keeps_alpha() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(3, 3, |x, _| {
+            Rgba([100, 100, 100, x as u8 * 100])
+        }));
+
+        let out = oil_paint(&image, 1, 8).unwrap();
+
+        assert_eq!(out.as_rgba8().unwrap(), image.as_rgba8().unwrap());
+    }
+
+    #[pm(levels = { 0, 257 })]
+    fn invalid_levels(levels: u32) {
+        let image = DynamicImage::ImageLuma8(GrayImage::new(2, 2));
+
+        assert!(oil_paint(&image, 1, levels).is_err());
+    }
+}
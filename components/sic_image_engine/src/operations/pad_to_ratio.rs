@@ -0,0 +1,100 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::canvas::place_on_canvas;
+use crate::wrapper::aspect_ratio::AspectRatio;
+use crate::wrapper::gravity::Gravity;
+use crate::wrapper::pad_to_ratio::PadToRatioInputs;
+use sic_core::image::{DynamicImage, GenericImageView, Rgba};
+use std::convert::TryFrom;
+
+/// Pads the image at both sides of either its width or its height, by the least amount of pixels
+/// which make it match the aspect ratio. The image is kept in the center of the padded canvas. The
+/// padding has the color of the inputs, or the fallback color if they have none.
+pub(crate) fn pad_to_ratio(
+    image: &DynamicImage,
+    inputs: &PadToRatioInputs,
+    fallback_color: Rgba<u8>,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let size = padded_size(image.dimensions(), inputs.ratio())?;
+    let position = Gravity::Center.position(size, image.dimensions(), 0);
+
+    Ok(place_on_canvas(
+        image,
+        size,
+        position,
+        inputs.color().unwrap_or(fallback_color),
+    ))
+}
+
+/// The smallest size which is at least as large as the given size, and matches the ratio; the
+/// padded side is rounded up to whole pixels.
+pub(crate) fn padded_size(
+    (width, height): (u32, u32),
+    ratio: AspectRatio,
+) -> Result<(u32, u32), SicImageEngineError> {
+    let (w, h) = (u64::from(width), u64::from(height));
+    let (rw, rh) = (u64::from(ratio.width()), u64::from(ratio.height()));
+
+    // compare width / height to rw / rh, without rounding
+    let size = if w * rh > h * rw {
+        // too wide: the height grows
+        (w, (w * rh).div_ceil(rw))
+    } else {
+        // too tall, or already matching: the width grows (if at all)
+        ((h * rw).div_ceil(rh), h)
+    };
+
+    match (u32::try_from(size.0), u32::try_from(size.1)) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(SicImageEngineError::PadToRatioTooLarge),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage};
+    use sic_testing::*;
+
+    ide!();
+
+    fn ratio(val: &str) -> AspectRatio {
+        AspectRatio::try_from_str(val).unwrap()
+    }
+
+    #[pm(
+        size = { (1600, 900), (1600, 800), (1600, 1000), (100, 100), (200, 100), (1, 1) },
+        expected = { (1600, 900), (1600, 900), (1778, 1000), (178, 100), (200, 113), (2, 1) }
+    )]
+    fn padded_size_16_9(size: (u32, u32), expected: (u32, u32)) {
+        assert_eq!(padded_size(size, ratio("16:9")).unwrap(), expected);
+    }
+
+    #[pm(
+        size = { (100, 50), (50, 100), (3, 7) },
+        expected = { (100, 100), (100, 100), (7, 7) }
+    )]
+    fn padded_size_square(size: (u32, u32), expected: (u32, u32)) {
+        assert_eq!(padded_size(size, ratio("1:1")).unwrap(), expected);
+    }
+
+    #[test]
+    fn padded_size_too_large() {
+        assert!(padded_size((u32::MAX, 1), ratio("1:2")).is_err());
+    }
+
+    #[test]
+    fn pads_both_sides() {
+        let red = Rgb([255, 0, 0]);
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 2, red));
+        let inputs = PadToRatioInputs::new(ratio("1:1"), None);
+
+        let out = pad_to_ratio(&image, &inputs, Rgba([0, 0, 0, 255])).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (4, 4));
+        assert_eq!(out.get_pixel(0, 0), &Rgb([0, 0, 0]));
+        assert_eq!(out.get_pixel(0, 1), &red);
+        assert_eq!(out.get_pixel(3, 2), &red);
+        assert_eq!(out.get_pixel(3, 3), &Rgb([0, 0, 0]));
+    }
+}
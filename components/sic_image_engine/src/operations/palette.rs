@@ -0,0 +1,183 @@
+/// A palette of at most 256 colors, which approximates the colors of an image.
+#[derive(Debug)]
+pub(crate) struct Palette {
+    colors: Vec<[u8; 3]>,
+}
+
+impl Palette {
+    /// Creates a palette of at most `size` colors using the median cut algorithm: the set of
+    /// colors is repeatedly split at the median of the channel with the largest range, until there
+    /// are `size` sets. Each set is then represented by its mean color.
+    ///
+    /// The palette contains fewer colors when there are fewer distinct colors than `size`.
+    pub(crate) fn median_cut(mut colors: Vec<[u8; 3]>, size: usize) -> Self {
+        if colors.is_empty() || size == 0 {
+            return Self { colors: Vec::new() };
+        }
+
+        let mut boxes = vec![ColorBox::new(&mut colors[..])];
+
+        while boxes.len() < size {
+            let widest = boxes
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| b.range > 0)
+                .max_by_key(|(_, b)| b.range)
+                .map(|(i, _)| i);
+
+            let index = match widest {
+                Some(index) => index,
+                None => break,
+            };
+
+            let (lower, upper) = boxes.swap_remove(index).split();
+            boxes.push(lower);
+            boxes.push(upper);
+        }
+
+        Self {
+            colors: boxes.iter().map(ColorBox::mean).collect(),
+        }
+    }
+
+    /// The color of the palette which is closest to the given color.
+    pub(crate) fn nearest(&self, color: [f32; 3]) -> [u8; 3] {
+        let distance = |candidate: &[u8; 3]| {
+            candidate
+                .iter()
+                .zip(color.iter())
+                .map(|(&c, &v)| (f32::from(c) - v).powi(2))
+                .sum::<f32>()
+        };
+
+        self.colors
+            .iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .copied()
+            .unwrap_or([0, 0, 0])
+    }
+}
+
+/// A set of colors, together with the channel over which its colors vary the most.
+struct ColorBox<'c> {
+    colors: &'c mut [[u8; 3]],
+    channel: usize,
+    range: u8,
+}
+
+impl<'c> ColorBox<'c> {
+    fn new(colors: &'c mut [[u8; 3]]) -> Self {
+        let (channel, range) = (0..3)
+            .map(|channel| {
+                let (min, max) = colors.iter().fold((u8::MAX, u8::MIN), |(min, max), c| {
+                    (min.min(c[channel]), max.max(c[channel]))
+                });
+
+                (channel, max - min)
+            })
+            .max_by_key(|&(_, range)| range)
+            .unwrap_or((0, 0));
+
+        Self {
+            colors,
+            channel,
+            range,
+        }
+    }
+
+    /// Splits the box at the median of its widest channel. Both halves are non-empty, since a
+    /// box is only split when its range is larger than zero.
+    fn split(self) -> (ColorBox<'c>, ColorBox<'c>) {
+        let channel = self.channel;
+        self.colors.sort_unstable_by_key(|c| c[channel]);
+
+        // don't split between equal values, so a color never ends up in both halves
+        let first = self.colors[0][channel];
+        let median = self.colors[self.colors.len() / 2][channel].max(first + 1);
+        let at = self.colors.partition_point(|c| c[channel] < median);
+
+        let (lower, upper) = self.colors.split_at_mut(at);
+
+        (ColorBox::new(lower), ColorBox::new(upper))
+    }
+
+    fn mean(&self) -> [u8; 3] {
+        let mut sums = [0u64; 3];
+
+        for color in self.colors.iter() {
+            for (sum, &sample) in sums.iter_mut().zip(color.iter()) {
+                *sum += u64::from(sample);
+            }
+        }
+
+        let count = self.colors.len() as u64;
+        let mean = |sum: u64| ((sum + count / 2) / count) as u8;
+
+        [mean(sums[0]), mean(sums[1]), mean(sums[2])]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[test]
+    fn distinct_colors_fit() {
+        let colors = vec![[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 0, 0]];
+
+        let palette = Palette::median_cut(colors, 3);
+
+        let mut found = palette.colors.to_vec();
+        found.sort_unstable();
+        assert_eq!(found, vec![[0, 0, 255], [0, 255, 0], [255, 0, 0]]);
+    }
+
+    #[test]
+    fn fewer_distinct_colors_than_size() {
+        let colors = vec![[10, 20, 30]; 16];
+
+        let palette = Palette::median_cut(colors, 8);
+
+        assert_eq!(palette.colors, vec![[10, 20, 30]]);
+    }
+
+    #[test]
+    fn splits_along_widest_channel() {
+        let colors = (0..=255u8).map(|v| [v, 100, 100]).collect();
+
+        let palette = Palette::median_cut(colors, 2);
+
+        let mut found = palette.colors.to_vec();
+        found.sort_unstable();
+        assert_eq!(found, vec![[64, 100, 100], [192, 100, 100]]);
+    }
+
+    #[pm(size = { 1, 2, 16, 256 })]
+    fn at_most_size_colors(size: usize) {
+        let colors = (0..4096u32)
+            .map(|v| [(v % 256) as u8, (v / 16) as u8, (v * 7 % 256) as u8])
+            .collect();
+
+        let palette = Palette::median_cut(colors, size);
+
+        assert!(palette.colors.len() <= size);
+        assert!(!palette.colors.is_empty());
+    }
+
+    #[test]
+    fn empty() {
+        assert!(Palette::median_cut(Vec::new(), 4).colors.is_empty());
+    }
+
+    #[test]
+    fn nearest() {
+        let palette = Palette::median_cut(vec![[0, 0, 0], [255, 255, 255]], 2);
+
+        assert_eq!(palette.nearest([100.0, 100.0, 100.0]), [0, 0, 0]);
+        assert_eq!(palette.nearest([200.0, 130.0, 140.0]), [255, 255, 255]);
+        assert_eq!(palette.nearest([-50.0, 300.0, 0.0]), [0, 0, 0]);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::remap::remap;
+use crate::wrapper::perspective::PerspectiveInputs;
+use sic_core::image::{DynamicImage, Rgba};
+
+/// Maps the quadrilateral given by the four corners onto a rectangle, e.g. to correct the
+/// keystone distortion of a photographed document.
+///
+/// The size of the rectangle is given by the longest of the opposing edges of the quadrilateral.
+/// Parts of the quadrilateral outside of the image take the background color, without its alpha
+/// channel for images without one.
+pub(crate) fn perspective(
+    image: &DynamicImage,
+    inputs: &PerspectiveInputs,
+    background: Rgba<u8>,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let corners = inputs.corners();
+    let (width, height) = output_size(inputs)?;
+
+    let projection =
+        SquareToQuad::new(corners).ok_or(SicImageEngineError::PerspectiveInvalidCorners)?;
+    let (w, h) = (width as f32, height as f32);
+
+    Ok(remap(image, (width, height), background, |x, y| {
+        Some(projection.map(x / w, y / h))
+    }))
+}
+
+/// The size of the rectangle onto which the quadrilateral is mapped.
+pub(crate) fn output_size(inputs: &PerspectiveInputs) -> Result<(u32, u32), SicImageEngineError> {
+    let corners = inputs.corners();
+
+    if corners
+        .iter()
+        .any(|(x, y)| !x.is_finite() || !y.is_finite())
+    {
+        return Err(SicImageEngineError::PerspectiveInvalidCorners);
+    }
+
+    let [top_left, top_right, bottom_right, bottom_left] = corners;
+    let width = distance(top_left, top_right).max(distance(bottom_left, bottom_right));
+    let height = distance(top_left, bottom_left).max(distance(top_right, bottom_right));
+    let (width, height) = (width.round() as u32, height.round() as u32);
+
+    if width == 0 || height == 0 {
+        return Err(SicImageEngineError::PerspectiveInvalidCorners);
+    }
+
+    Ok((width, height))
+}
+
+fn distance(from: (f32, f32), to: (f32, f32)) -> f32 {
+    (to.0 - from.0).hypot(to.1 - from.1)
+}
+
+/// The projective mapping of the unit square onto a quadrilateral, which maps the corners (0, 0),
+/// (1, 0), (1, 1) and (0, 1) onto the corners of the quadrilateral, in that order.
+///
+/// The mapping is solved in closed form (Heckbert, "Fundamentals of Texture Mapping and Image
+/// Warping", 1989), instead of with `Projection::from_control_points` of imageproc, which rejects
+/// some valid quadrilaterals.
+struct SquareToQuad {
+    // x = (a u + b v + c) / (g u + h v + 1), y = (d u + e v + f) / (g u + h v + 1)
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    e: f32,
+    f: f32,
+    g: f32,
+    h: f32,
+}
+
+impl SquareToQuad {
+    /// Returns `None` if the quadrilateral is degenerate, i.e. if three of its corners lie on a
+    /// line, or if it is not convex, in which case the mapping would fold over.
+    fn new(corners: [(f32, f32); 4]) -> Option<Self> {
+        let [(x0, y0), (x1, y1), (x2, y2), (x3, y3)] = corners;
+
+        let (dx1, dy1) = (x1 - x2, y1 - y2);
+        let (dx2, dy2) = (x3 - x2, y3 - y2);
+        let (dx3, dy3) = (x0 - x1 + x2 - x3, y0 - y1 + y2 - y3);
+
+        let den = dx1 * dy2 - dx2 * dy1;
+        if den == 0.0 {
+            return None;
+        }
+
+        let g = (dx3 * dy2 - dx2 * dy3) / den;
+        let h = (dx1 * dy3 - dx3 * dy1) / den;
+
+        let mapping = Self {
+            a: x1 - x0 + g * x1,
+            b: x3 - x0 + h * x3,
+            c: x0,
+            d: y1 - y0 + g * y1,
+            e: y3 - y0 + h * y3,
+            f: y0,
+            g,
+            h,
+        };
+
+        // the denominator is linear in u and v, so it is positive within the whole square if it is
+        // positive at its corners
+        let positive = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+            .iter()
+            .all(|&(u, v)| mapping.g * u + mapping.h * v + 1.0 > 0.0);
+
+        let det = mapping.a * (mapping.e - mapping.f * mapping.h)
+            - mapping.b * (mapping.d - mapping.f * mapping.g)
+            + mapping.c * (mapping.d * mapping.h - mapping.e * mapping.g);
+
+        if positive && det.is_normal() {
+            Some(mapping)
+        } else {
+            None
+        }
+    }
+
+    fn map(&self, u: f32, v: f32) -> (f32, f32) {
+        let w = self.g * u + self.h * v + 1.0;
+
+        (
+            (self.a * u + self.b * v + self.c) / w,
+            (self.d * u + self.e * v + self.f) / w,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Rgb, RgbImage, RgbaImage};
+
+    const TRANSPARENT: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+    /// An image with a red left half and a blue right half.
+    fn halves() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(40, 20, |x, _| {
+            if x < 20 {
+                Rgb([255, 0, 0])
+            } else {
+                Rgb([0, 0, 255])
+            }
+        }))
+    }
+
+    #[test]
+    fn identity() {
+        let corners = [(0.0, 0.0), (40.0, 0.0), (40.0, 20.0), (0.0, 20.0)];
+
+        let out = perspective(&halves(), &PerspectiveInputs::new(corners), TRANSPARENT).unwrap();
+
+        assert_eq!(out.dimensions(), (40, 20));
+        assert_eq!(out.get_pixel(5, 10), Rgba([255, 0, 0, 255]));
+        assert_eq!(out.get_pixel(35, 10), Rgba([0, 0, 255, 255]));
+        assert_eq!(out.get_pixel(39, 19), Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn maps_quadrilateral_onto_rectangle() {
+        // a trapezoid which is narrower at the top, around the center of the image
+        let corners = [(15.0, 2.0), (25.0, 2.0), (30.0, 18.0), (10.0, 18.0)];
+
+        let out = perspective(&halves(), &PerspectiveInputs::new(corners), TRANSPARENT).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (20, 17));
+        // the left and right halves of the output come from the left and right halves of the
+        // image, at the top as well as at the bottom
+        assert_eq!(out.get_pixel(2, 1), &Rgb([255, 0, 0]));
+        assert_eq!(out.get_pixel(17, 1), &Rgb([0, 0, 255]));
+        assert_eq!(out.get_pixel(2, 14), &Rgb([255, 0, 0]));
+        assert_eq!(out.get_pixel(17, 14), &Rgb([0, 0, 255]));
+    }
+
+    #[test]
+    fn outside_of_image_is_transparent() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(10, 10, Rgba([0, 255, 0, 255])));
+        let corners = [(-10.0, 0.0), (10.0, 0.0), (10.0, 10.0), (-10.0, 10.0)];
+
+        let out = perspective(&image, &PerspectiveInputs::new(corners), TRANSPARENT).unwrap();
+
+        assert_eq!(out.get_pixel(2, 5), Rgba([0, 0, 0, 0]));
+        assert_eq!(out.get_pixel(17, 5), Rgba([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn outside_of_image_takes_background() {
+        let corners = [(-10.0, 0.0), (40.0, 0.0), (40.0, 20.0), (-10.0, 20.0)];
+
+        let out = perspective(
+            &halves(),
+            &PerspectiveInputs::new(corners),
+            Rgba([0, 255, 0, 255]),
+        )
+        .unwrap();
+
+        assert_eq!(out.get_pixel(2, 10), Rgba([0, 255, 0, 255]));
+        assert_eq!(out.get_pixel(15, 10), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn collapsed_corners() {
+        let corners = [(5.0, 5.0); 4];
+
+        assert!(perspective(&halves(), &PerspectiveInputs::new(corners), TRANSPARENT).is_err());
+    }
+
+    #[test]
+    fn collinear_corners() {
+        let corners = [(0.0, 0.0), (10.0, 0.0), (20.0, 0.0), (30.0, 0.0)];
+
+        assert!(perspective(&halves(), &PerspectiveInputs::new(corners), TRANSPARENT).is_err());
+    }
+
+    #[test]
+    fn not_convex() {
+        let corners = [(0.0, 0.0), (40.0, 0.0), (10.0, 5.0), (0.0, 20.0)];
+
+        assert!(perspective(&halves(), &PerspectiveInputs::new(corners), TRANSPARENT).is_err());
+    }
+
+    #[test]
+    fn keystone() {
+        let corners = [(-20.0, 0.0), (400.0, -10.0), (447.0, 217.0), (0.0, 200.0)];
+
+        let out = perspective(&halves(), &PerspectiveInputs::new(corners), TRANSPARENT).unwrap();
+
+        assert_eq!(out.dimensions(), (447, 232));
+    }
+
+    #[test]
+    fn not_finite() {
+        let corners = [(0.0, 0.0), (f32::INFINITY, 0.0), (10.0, 10.0), (0.0, 10.0)];
+
+        assert!(perspective(&halves(), &PerspectiveInputs::new(corners), TRANSPARENT).is_err());
+    }
+}
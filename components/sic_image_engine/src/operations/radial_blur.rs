@@ -0,0 +1,140 @@
+use crate::errors::SicImageEngineError;
+use sic_core::image::{DynamicImage, ImageBuffer, Pixel};
+
+/// The largest number of samples taken for a single pixel, which bounds the time it takes to blur
+/// large images.
+const MAX_SAMPLES: u32 = 128;
+
+/// Blurs the image outward from the center `(cx, cy)`, as if zooming in while taking the picture.
+/// Each pixel becomes the mean of the pixels on the line from itself towards the center, which
+/// covers `strength` times the distance to the center: pixels far from the center are blurred
+/// more than pixels close to it, and the center itself stays sharp.
+///
+/// The strength should be between 0 (unchanged) and 1 (blur all the way to the center). The
+/// center may lie outside of the image. Images with 16-bit samples are converted to 8-bit samples.
+pub(crate) fn radial_blur(
+    image: &DynamicImage,
+    center: (u32, u32),
+    strength: f32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if !(0.0..=1.0).contains(&strength) {
+        return Err(SicImageEngineError::RadialBlurInvalidStrength(strength));
+    }
+
+    let out = match image {
+        DynamicImage::ImageLuma8(buffer) => {
+            DynamicImage::ImageLuma8(blur_buffer(buffer, center, strength))
+        }
+        DynamicImage::ImageLumaA8(buffer) => {
+            DynamicImage::ImageLumaA8(blur_buffer(buffer, center, strength))
+        }
+        DynamicImage::ImageRgb8(buffer) => {
+            DynamicImage::ImageRgb8(blur_buffer(buffer, center, strength))
+        }
+        DynamicImage::ImageRgba8(buffer) => {
+            DynamicImage::ImageRgba8(blur_buffer(buffer, center, strength))
+        }
+        _ => DynamicImage::ImageRgba8(blur_buffer(&image.to_rgba(), center, strength)),
+    };
+
+    Ok(out)
+}
+
+fn blur_buffer<P>(
+    buffer: &ImageBuffer<P, Vec<u8>>,
+    center: (u32, u32),
+    strength: f32,
+) -> ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8> + 'static,
+{
+    let (width, height) = buffer.dimensions();
+    let mut out = buffer.clone();
+
+    if width == 0 || height == 0 || strength == 0.0 {
+        return out;
+    }
+
+    let (cx, cy) = (center.0 as f32, center.1 as f32);
+    let channels = usize::from(P::CHANNEL_COUNT);
+    let mut sums = vec![0u32; channels];
+
+    for (x, y, pixel) in out.enumerate_pixels_mut() {
+        let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+        let length = strength * dx.hypot(dy);
+
+        // one sample per pixel of the line, including both of its ends
+        let samples = (length.ceil() as u32 + 1).min(MAX_SAMPLES);
+
+        if samples == 1 {
+            continue;
+        }
+
+        sums.iter_mut().for_each(|sum| *sum = 0);
+
+        for i in 0..samples {
+            let scale = 1.0 - strength * i as f32 / (samples - 1) as f32;
+            let sx = (cx + dx * scale).round().clamp(0.0, (width - 1) as f32) as u32;
+            let sy = (cy + dy * scale).round().clamp(0.0, (height - 1) as f32) as u32;
+
+            for (sum, sample) in sums.iter_mut().zip(buffer.get_pixel(sx, sy).channels()) {
+                *sum += u32::from(*sample);
+            }
+        }
+
+        for (sample, sum) in pixel.channels_mut().iter_mut().zip(&sums) {
+            *sample = ((sum + samples / 2) / samples) as u8;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, GrayImage, Luma, Rgba, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    fn bright_end() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(5, 1, |x, _| {
+            Luma([if x == 4 { 200 } else { 0 }])
+        }))
+    }
+
+    #[pm(
+        strength = { 1.0, 0.5 },
+        expected = { [0, 0, 0, 0, 40], [0, 0, 0, 0, 67] }
+    )]
+    fn averages_towards_center(strength: f32, expected: [u8; 5]) {
+        let out = radial_blur(&bright_end(), (0, 0), strength).unwrap();
+        let out = out.as_luma8().unwrap();
+
+        assert_eq!(out.pixels().map(|px| px[0]).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn strength_zero_is_identity() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(4, 3, |x, y| {
+            Rgba([x as u8 * 60, y as u8 * 80, 7, 200])
+        }));
+
+        let out = radial_blur(&image, (1, 1), 0.0).unwrap();
+
+        assert_eq!(out.as_rgba8().unwrap(), image.as_rgba8().unwrap());
+    }
+
+    #[test]
+    fn center_outside_of_image() {
+        let out = radial_blur(&bright_end(), (100, 100), 1.0).unwrap();
+
+        assert_eq!(out.dimensions(), (5, 1));
+    }
+
+    #[pm(strength = { -0.1, 1.1, f32::NAN })]
+    fn invalid_strength(strength: f32) {
+        assert!(radial_blur(&bright_end(), (0, 0), strength).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::redact::{RedactInputs, RedactStyle};
+use rand::Rng;
+use sic_core::image::{DynamicImage, GenericImage, GenericImageView, Rgba};
+
+/// The largest amount by which the color of a pixelated block is shifted, per channel.
+const PIXELATE_NOISE: i16 = 48;
+
+/// The smallest size of the blocks of a pixelated region, in pixels.
+const PIXELATE_MIN_BLOCK_SIZE: u32 = 8;
+
+/// Destroys the contents of a region of the image, e.g. to hide private information in a
+/// screenshot. The redacted pixels are opaque, so the original can't show through a transparent
+/// fill either.
+///
+/// A pixelated region consists of blocks of half the shorter side of the region (but at least
+/// `PIXELATE_MIN_BLOCK_SIZE` pixels), each filled with the average color of its pixels, shifted by
+/// a random amount. Without the shift, the averages would still leak some information about the
+/// original.
+pub(crate) fn redact(
+    image: &mut DynamicImage,
+    inputs: &RedactInputs,
+) -> Result<(), SicImageEngineError> {
+    let region = inputs.region();
+
+    let ((x, y), (width, height)) = region.clip(image.dimensions()).ok_or_else(|| {
+        let (image_width, image_height) = image.dimensions();
+
+        SicImageEngineError::RedactOutsideImage(
+            region.x(),
+            region.y(),
+            region.width(),
+            region.height(),
+            image_width,
+            image_height,
+        )
+    })?;
+
+    match inputs.style() {
+        RedactStyle::Fill => fill(image, (x, y), (width, height), Rgba([0, 0, 0, 255])),
+        RedactStyle::Pixelate => {
+            let block = (width.min(height) / 2).max(PIXELATE_MIN_BLOCK_SIZE);
+            let mut rng = rand::thread_rng();
+
+            for by in (y..y + height).step_by(block as usize) {
+                for bx in (x..x + width).step_by(block as usize) {
+                    let size = (block.min(x + width - bx), block.min(y + height - by));
+
+                    let mut color = average(image, (bx, by), size);
+                    for sample in &mut color.0[..3] {
+                        let shift = rng.gen_range(-PIXELATE_NOISE, PIXELATE_NOISE + 1);
+                        *sample = (i16::from(*sample) + shift).clamp(0, 255) as u8;
+                    }
+                    color[3] = u8::MAX;
+
+                    fill(image, (bx, by), size, color);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn fill(
+    image: &mut DynamicImage,
+    (x, y): (u32, u32),
+    (width, height): (u32, u32),
+    color: Rgba<u8>,
+) {
+    for py in y..y + height {
+        for px in x..x + width {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
+
+fn average(image: &DynamicImage, (x, y): (u32, u32), (width, height): (u32, u32)) -> Rgba<u8> {
+    let mut sums = [0u64; 4];
+
+    for py in y..y + height {
+        for px in x..x + width {
+            let pixel = image.get_pixel(px, py);
+            sums.iter_mut()
+                .zip(pixel.0.iter())
+                .for_each(|(sum, &sample)| *sum += u64::from(sample));
+        }
+    }
+
+    let count = u64::from(width) * u64::from(height);
+
+    Rgba([
+        (sums[0] / count) as u8,
+        (sums[1] / count) as u8,
+        (sums[2] / count) as u8,
+        (sums[3] / count) as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wrapper::region::Region;
+    use sic_core::image::{Rgb, RgbImage, RgbaImage};
+
+    fn striped() -> DynamicImage {
+        // alternating white and red columns, so the average of a block differs from its pixels
+        DynamicImage::ImageRgb8(RgbImage::from_fn(32, 32, |x, _| {
+            if x % 2 == 0 {
+                Rgb([255, 255, 255])
+            } else {
+                Rgb([255, 0, 0])
+            }
+        }))
+    }
+
+    #[test]
+    fn fill_region() {
+        let mut image = striped();
+        let inputs = RedactInputs::new(Region::new((4, 2), (8, 6)), RedactStyle::Fill);
+
+        redact(&mut image, &inputs).unwrap();
+        let out = image.as_rgb8().unwrap();
+
+        assert_eq!(out.get_pixel(4, 2), &Rgb([0, 0, 0]));
+        assert_eq!(out.get_pixel(11, 7), &Rgb([0, 0, 0]));
+        assert_eq!(out.get_pixel(12, 7), &Rgb([255, 255, 255]));
+        assert_eq!(out.get_pixel(11, 8), &Rgb([255, 0, 0]));
+    }
+
+    #[test]
+    fn fill_is_opaque() {
+        let mut image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 4, Rgba([9, 9, 9, 0])));
+        let inputs = RedactInputs::new(Region::new((0, 0), (2, 2)), RedactStyle::Fill);
+
+        redact(&mut image, &inputs).unwrap();
+
+        assert_eq!(image.get_pixel(1, 1), Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(2, 2), Rgba([9, 9, 9, 0]));
+    }
+
+    #[test]
+    fn pixelate_region_in_blocks() {
+        let mut image = striped();
+        let inputs = RedactInputs::new(Region::new((0, 0), (32, 16)), RedactStyle::Pixelate);
+
+        redact(&mut image, &inputs).unwrap();
+        let out = image.as_rgb8().unwrap();
+
+        // blocks of 8 by 8 pixels, each of a single color near the average of the stripes, which
+        // is (255, 127, 127)
+        for (bx, by) in &[(0, 0), (8, 0), (24, 8)] {
+            let color = *out.get_pixel(*bx, *by);
+
+            assert!((255 - 48..=255).contains(&color[0]));
+            assert!((127 - 48..=127 + 48).contains(&color[1]));
+
+            for y in *by..by + 8 {
+                for x in *bx..bx + 8 {
+                    assert_eq!(out.get_pixel(x, y), &color);
+                }
+            }
+        }
+
+        // outside of the region
+        assert_eq!(out.get_pixel(1, 16), &Rgb([255, 0, 0]));
+    }
+
+    #[test]
+    fn clipped_to_image() {
+        let mut image = striped();
+        let inputs = RedactInputs::new(Region::new((30, 30), (100, 100)), RedactStyle::Pixelate);
+
+        redact(&mut image, &inputs).unwrap();
+
+        assert_eq!(image.dimensions(), (32, 32));
+    }
+
+    #[test]
+    fn outside_image() {
+        let mut image = striped();
+        let inputs = RedactInputs::new(Region::new((32, 0), (10, 10)), RedactStyle::Fill);
+
+        assert!(redact(&mut image, &inputs).is_err());
+    }
+}
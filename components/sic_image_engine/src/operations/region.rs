@@ -0,0 +1,44 @@
+use sic_core::image::{imageops, DynamicImage};
+
+/// Replaces the pixels of the image, starting at `(x, y)`, by those of the region, which was
+/// cut from the image and operated on by itself. The image gets an alpha channel if the region
+/// has one, e.g. after a chroma key, so its transparency isn't lost.
+pub(crate) fn paste_region(image: &mut DynamicImage, region: &DynamicImage, (x, y): (u32, u32)) {
+    if region.color().has_alpha() && !image.color().has_alpha() {
+        *image = DynamicImage::ImageRgba8(image.to_rgba());
+    }
+
+    imageops::replace(image, region, x, y);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, GrayImage, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+
+    #[test]
+    fn replaces_pixels_at_offset() {
+        let mut image = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, Rgb([255, 0, 0])));
+        let region = DynamicImage::ImageLuma8(GrayImage::from_pixel(2, 1, Luma([100])));
+
+        paste_region(&mut image, &region, (1, 2));
+
+        assert!(image.as_rgb8().is_some());
+        assert_eq!(image.get_pixel(1, 2), Rgba([100, 100, 100, 255]));
+        assert_eq!(image.get_pixel(2, 2), Rgba([100, 100, 100, 255]));
+        assert_eq!(image.get_pixel(3, 2), Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(1, 1), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn keeps_transparency_of_region() {
+        let mut image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([255, 0, 0])));
+        let region = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([0, 0, 0, 0])));
+
+        paste_region(&mut image, &region, (1, 1));
+
+        assert!(image.color().has_alpha());
+        assert_eq!(image.get_pixel(1, 1), Rgba([0, 0, 0, 0]));
+        assert_eq!(image.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+    }
+}
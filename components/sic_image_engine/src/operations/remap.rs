@@ -0,0 +1,119 @@
+use sic_core::image::{DynamicImage, Rgba, RgbaImage};
+
+/// Creates an image of `width` by `height` pixels, of which each pixel is sampled from the image at
+/// the position `source` gives for the center of the pixel. Positions are continuous: the pixel
+/// `(x, y)` covers the area from `(x, y)` to `(x + 1, y + 1)`, so its center lies at
+/// `(x + 0.5, y + 0.5)`. The image is sampled bilinearly, where the pixels at the edges of the
+/// image extend up to the edges.
+///
+/// Pixels for which `source` gives no position, or a position outside of the image, take the
+/// background color, without its alpha channel if the image has none. The result has an alpha
+/// channel if the image has one.
+pub(crate) fn remap<F>(
+    image: &DynamicImage,
+    (width, height): (u32, u32),
+    background: Rgba<u8>,
+    source: F,
+) -> DynamicImage
+where
+    F: Fn(f32, f32) -> Option<(f32, f32)>,
+{
+    let input = image.to_rgba();
+
+    let out = RgbaImage::from_fn(width, height, |x, y| {
+        source(x as f32 + 0.5, y as f32 + 0.5)
+            .and_then(|position| sample(&input, position))
+            .unwrap_or(background)
+    });
+
+    let out = DynamicImage::ImageRgba8(out);
+
+    if image.color().has_alpha() {
+        out
+    } else {
+        DynamicImage::ImageRgb8(out.to_rgb())
+    }
+}
+
+fn sample(image: &RgbaImage, (x, y): (f32, f32)) -> Option<Rgba<u8>> {
+    let (width, height) = image.dimensions();
+
+    if !(0.0..=width as f32).contains(&x) || !(0.0..=height as f32).contains(&y) {
+        return None;
+    }
+
+    // relative to the pixel centers
+    let (x, y) = (x - 0.5, y - 0.5);
+    let (left, top) = (x.floor(), y.floor());
+    let (fx, fy) = (x - left, y - top);
+
+    let clamp = |v: f32, size: u32| v.max(0.0).min((size - 1) as f32) as u32;
+    let (x0, x1) = (clamp(left, width), clamp(left + 1.0, width));
+    let (y0, y1) = (clamp(top, height), clamp(top + 1.0, height));
+
+    let (tl, tr) = (image.get_pixel(x0, y0), image.get_pixel(x1, y0));
+    let (bl, br) = (image.get_pixel(x0, y1), image.get_pixel(x1, y1));
+
+    let mut pixel = Rgba([0; 4]);
+    for c in 0..4 {
+        let top = f32::from(tl[c]) * (1.0 - fx) + f32::from(tr[c]) * fx;
+        let bottom = f32::from(bl[c]) * (1.0 - fx) + f32::from(br[c]) * fx;
+        pixel[c] = (top * (1.0 - fy) + bottom * fy).round() as u8;
+    }
+
+    Some(pixel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Rgb, RgbImage};
+
+    const TRANSPARENT: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+    #[test]
+    fn identity() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(3, 2, |x, y| {
+            Rgb([x as u8 * 100, y as u8 * 100, 0])
+        }));
+
+        let out = remap(&image, (3, 2), TRANSPARENT, |x, y| Some((x, y)));
+
+        assert_eq!(out.as_rgb8(), image.as_rgb8());
+    }
+
+    #[test]
+    fn interpolates_between_pixel_centers() {
+        let image =
+            DynamicImage::ImageRgb8(RgbImage::from_fn(2, 1, |x, _| Rgb([x as u8 * 200, 0, 0])));
+
+        let out = remap(&image, (1, 1), TRANSPARENT, |_, _| Some((1.0, 0.5)));
+
+        assert_eq!(out.get_pixel(0, 0), Rgba([100, 0, 0, 255]));
+    }
+
+    #[test]
+    fn outside_of_image() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba([255; 4])));
+
+        let out = remap(&image, (3, 1), TRANSPARENT, |x, _| match x as u32 {
+            0 => Some((-0.5, 1.0)),
+            1 => None,
+            _ => Some((1.0, 1.0)),
+        });
+
+        assert_eq!(out.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(out.get_pixel(1, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(out.get_pixel(2, 0), Rgba([255; 4]));
+    }
+
+    #[test]
+    fn background_without_alpha() {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(2, 2));
+
+        let out = remap(&image, (1, 1), Rgba([0, 0, 255, 128]), |_, _| None);
+
+        assert!(matches!(out, DynamicImage::ImageRgb8(_)));
+        assert_eq!(out.get_pixel(0, 0), Rgba([0, 0, 255, 255]));
+    }
+}
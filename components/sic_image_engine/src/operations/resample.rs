@@ -0,0 +1,457 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::filter_type::{CustomFilterType, FilterTypeWrap};
+use sic_core::image::{DynamicImage, GenericImageView, ImageBuffer, Rgba, RgbaImage};
+
+/// Resizes the image to exactly `width` by `height` pixels, or, if `preserve_aspect_ratio` is set,
+/// to the largest size which fits within `width` by `height` while keeping the aspect ratio.
+///
+/// Either `width` or `height` may be 0, in which case it is derived from the other dimension and
+/// the aspect ratio of the image.
+///
+/// Sampling filters provided by the image crate are delegated to it; the custom filters are
+/// applied by a separable convolution, first horizontally and then vertically.
+pub(crate) fn resize(
+    image: &DynamicImage,
+    width: u32,
+    height: u32,
+    filter: FilterTypeWrap,
+    preserve_aspect_ratio: bool,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = fill_wildcard(image.dimensions(), (width, height))?;
+
+    if let Some(filter) = filter.image_filter() {
+        return Ok(if preserve_aspect_ratio {
+            image.resize(width, height, filter)
+        } else {
+            image.resize_exact(width, height, filter)
+        });
+    }
+
+    let filter = filter
+        .custom_filter()
+        .expect("a sampling filter is either an image filter or a custom filter");
+
+    let (width, height) = if preserve_aspect_ratio {
+        fit_within(image.dimensions(), (width, height))
+    } else {
+        (width, height)
+    };
+
+    let resized = DynamicImage::ImageRgba8(resample(&image.to_rgba(), width, height, filter));
+
+    Ok(match image {
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLuma16(_) => {
+            DynamicImage::ImageLuma8(resized.to_luma())
+        }
+        DynamicImage::ImageLumaA8(_) | DynamicImage::ImageLumaA16(_) => {
+            DynamicImage::ImageLumaA8(resized.to_luma_alpha())
+        }
+        DynamicImage::ImageRgb8(_) | DynamicImage::ImageBgr8(_) | DynamicImage::ImageRgb16(_) => {
+            DynamicImage::ImageRgb8(resized.to_rgb())
+        }
+        _ => resized,
+    })
+}
+
+/// The dimensions to which `resize` resizes an image of the given dimensions.
+pub(crate) fn resized_dimensions(
+    source: (u32, u32),
+    target: (u32, u32),
+    preserve_aspect_ratio: bool,
+) -> Result<(u32, u32), SicImageEngineError> {
+    let target = fill_wildcard(source, target)?;
+
+    Ok(if preserve_aspect_ratio {
+        fit_within(source, target)
+    } else {
+        target
+    })
+}
+
+/// Replaces a 0 (wildcard) dimension by the dimension which keeps the aspect ratio of `source`,
+/// rounded to the nearest pixel, but at least 1.
+fn fill_wildcard(
+    (width, height): (u32, u32),
+    target: (u32, u32),
+) -> Result<(u32, u32), SicImageEngineError> {
+    let scale = |length: u32, numerator: u32, denominator: u32| {
+        let scaled = (f64::from(length) * f64::from(numerator) / f64::from(denominator)).round();
+        scaled.max(1.0).min(f64::from(u32::MAX)) as u32
+    };
+
+    match target {
+        (0, 0) => Err(SicImageEngineError::ResizeBothDimensionsZero),
+        (0, target_height) => Ok((scale(width, target_height, height.max(1)), target_height)),
+        (target_width, 0) => Ok((target_width, scale(height, target_width, width.max(1)))),
+        target => Ok(target),
+    }
+}
+
+/// The largest dimensions with the aspect ratio of `source` which fit within `bounds`.
+/// Matches the dimensions chosen by `DynamicImage::resize`.
+fn fit_within((width, height): (u32, u32), (max_width, max_height): (u32, u32)) -> (u32, u32) {
+    if width == 0 || height == 0 {
+        return (max_width, max_height);
+    }
+
+    let ratio = u64::from(width) * u64::from(max_height);
+    let max_ratio = u64::from(max_width) * u64::from(height);
+
+    if max_ratio <= ratio {
+        let scaled = u64::from(height) * u64::from(max_width) / u64::from(width);
+        (max_width, scaled.min(u64::from(u32::MAX)) as u32)
+    } else {
+        let scaled = u64::from(width) * u64::from(max_height) / u64::from(height);
+        (scaled.min(u64::from(u32::MAX)) as u32, max_height)
+    }
+}
+
+fn resample(image: &RgbaImage, width: u32, height: u32, filter: CustomFilterType) -> RgbaImage {
+    let (source_width, source_height) = image.dimensions();
+
+    if source_width == 0 || source_height == 0 || width == 0 || height == 0 {
+        return RgbaImage::new(width, height);
+    }
+
+    let source = image
+        .pixels()
+        .map(|pixel| {
+            let [r, g, b, a] = pixel.0;
+            [f32::from(r), f32::from(g), f32::from(b), f32::from(a)]
+        })
+        .collect::<Vec<_>>();
+
+    // Resample the rows first: (source_width, source_height) -> (width, source_height)
+    let horizontal_weights = weights(source_width, width, filter);
+    let mut horizontal = Vec::with_capacity((width * source_height) as usize);
+
+    for row in source.chunks(source_width as usize) {
+        horizontal.extend(
+            horizontal_weights
+                .iter()
+                .map(|contributions| convolve(contributions, |x| row[x])),
+        );
+    }
+
+    // Then the columns: (width, source_height) -> (width, height)
+    let vertical_weights = weights(source_height, height, filter);
+
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let [r, g, b, a] = convolve(&vertical_weights[y as usize], |row| {
+            horizontal[row * width as usize + x as usize]
+        });
+        Rgba([channel(r), channel(g), channel(b), channel(a)])
+    })
+}
+
+/// A source pixel index and the (normalized) weight with which it contributes to a target pixel.
+type Contributions = Vec<(usize, f32)>;
+
+/// Computes for each of the `target` pixels along an axis which of the `source` pixels contribute
+/// to it, and by how much.
+fn weights(source: u32, target: u32, filter: CustomFilterType) -> Vec<Contributions> {
+    let ratio = source as f32 / target as f32;
+    // When downscaling, the kernel is stretched so every source pixel contributes.
+    let scale = ratio.max(1.0);
+    let support = support(filter) * scale;
+
+    (0..target)
+        .map(|t| {
+            let center = (t as f32 + 0.5) * ratio;
+            let left = ((center - support).floor().max(0.0) as u32).min(source - 1);
+            let right = ((center + support).ceil() as u32).min(source).max(left + 1);
+
+            let mut contributions = (left..right)
+                .map(|s| {
+                    let distance = (s as f32 + 0.5 - center) / scale;
+                    (s as usize, kernel(filter, distance))
+                })
+                .collect::<Contributions>();
+
+            let sum: f32 = contributions.iter().map(|(_, weight)| weight).sum();
+
+            if sum.abs() > f32::EPSILON {
+                contributions
+                    .iter_mut()
+                    .for_each(|(_, weight)| *weight /= sum);
+            } else {
+                // The kernel doesn't reach a pixel center (can only happen for the box filter),
+                // so fall back to the nearest source pixel.
+                let nearest = (center.floor() as usize).min(source as usize - 1);
+                contributions = vec![(nearest, 1.0)];
+            }
+
+            contributions
+        })
+        .collect()
+}
+
+fn convolve(contributions: &[(usize, f32)], pixel: impl Fn(usize) -> [f32; 4]) -> [f32; 4] {
+    contributions
+        .iter()
+        .fold([0.0; 4], |mut acc, &(index, weight)| {
+            let value = pixel(index);
+            for (sum, channel) in acc.iter_mut().zip(value.iter()) {
+                *sum += channel * weight;
+            }
+            acc
+        })
+}
+
+fn channel(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+/// The distance from the center beyond which the kernel of the filter is zero.
+fn support(filter: CustomFilterType) -> f32 {
+    match filter {
+        CustomFilterType::Box => 0.5,
+        CustomFilterType::Hermite => 1.0,
+        CustomFilterType::Mitchell => 2.0,
+    }
+}
+
+fn kernel(filter: CustomFilterType, x: f32) -> f32 {
+    let x = x.abs();
+
+    match filter {
+        CustomFilterType::Box => {
+            if x <= 0.5 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        CustomFilterType::Hermite => {
+            if x < 1.0 {
+                (2.0 * x - 3.0) * x * x + 1.0
+            } else {
+                0.0
+            }
+        }
+        CustomFilterType::Mitchell => bc_cubic(x, 1.0 / 3.0, 1.0 / 3.0),
+    }
+}
+
+/// The family of cubic filters described by Mitchell and Netravali, for |x|.
+fn bc_cubic(x: f32, b: f32, c: f32) -> f32 {
+    let value = if x < 1.0 {
+        (12.0 - 9.0 * b - 6.0 * c) * x.powi(3)
+            + (-18.0 + 12.0 * b + 6.0 * c) * x.powi(2)
+            + (6.0 - 2.0 * b)
+    } else if x < 2.0 {
+        (-b - 6.0 * c) * x.powi(3)
+            + (6.0 * b + 30.0 * c) * x.powi(2)
+            + (-12.0 * b - 48.0 * c) * x
+            + (8.0 * b + 24.0 * c)
+    } else {
+        0.0
+    };
+
+    value / 6.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::imageops::FilterType;
+    use sic_core::image::{GrayImage, Luma, Rgb, RgbImage};
+    use sic_testing::*;
+
+    ide!();
+
+    const CUSTOM_FILTERS: [CustomFilterType; 3] = [
+        CustomFilterType::Box,
+        CustomFilterType::Hermite,
+        CustomFilterType::Mitchell,
+    ];
+
+    #[pm(filter = {
+        CustomFilterType::Box,
+        CustomFilterType::Hermite,
+        CustomFilterType::Mitchell,
+    })]
+    fn kernels_peak_at_center_and_are_zero_outside_support(filter: CustomFilterType) {
+        assert!(kernel(filter, 0.0) > 0.0);
+        assert!(kernel(filter, 0.0) >= kernel(filter, 0.25));
+        approx_eq_f32!(kernel(filter, support(filter) + 0.01), 0.0);
+        approx_eq_f32!(kernel(filter, -support(filter) - 0.01), 0.0);
+    }
+
+    #[test]
+    fn mitchell_has_negative_lobes() {
+        assert!(kernel(CustomFilterType::Mitchell, 1.5) < 0.0);
+        approx_eq_f32!(kernel(CustomFilterType::Mitchell, 0.0), 8.0 / 9.0);
+    }
+
+    #[test]
+    fn hermite_is_smooth() {
+        approx_eq_f32!(kernel(CustomFilterType::Hermite, 0.5), 0.5);
+        approx_eq_f32!(kernel(CustomFilterType::Hermite, -0.5), 0.5);
+    }
+
+    #[test]
+    fn weights_are_normalized() {
+        for filter in CUSTOM_FILTERS.iter() {
+            for (source, target) in [(10, 3), (3, 10), (7, 7), (1, 5), (5, 1)].iter() {
+                for contributions in weights(*source, *target, *filter) {
+                    let sum: f32 = contributions.iter().map(|(_, weight)| weight).sum();
+                    approx_eq_f32!(sum, 1.0);
+                    assert!(contributions.iter().all(|(s, _)| *s < *source as usize));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn box_downscale_takes_mean() {
+        let image = GrayImage::from_raw(4, 1, vec![0, 100, 200, 50]).unwrap();
+        let image = DynamicImage::ImageLuma8(image);
+
+        let out = resize(
+            &image,
+            2,
+            1,
+            FilterTypeWrap::custom(CustomFilterType::Box),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(out.to_luma().into_raw(), vec![50, 125]);
+    }
+
+    #[test]
+    fn uniform_image_stays_uniform() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(5, 7, Rgb([20, 140, 230])));
+
+        for filter in CUSTOM_FILTERS.iter() {
+            let out = resize(&image, 13, 3, FilterTypeWrap::custom(*filter), false).unwrap();
+
+            assert_eq!(out.dimensions(), (13, 3));
+            assert!(out.to_rgb().pixels().all(|p| *p == Rgb([20, 140, 230])));
+        }
+    }
+
+    #[test]
+    fn keeps_color_type() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(4, 4, Luma([10])));
+        let out = resize(
+            &image,
+            2,
+            2,
+            FilterTypeWrap::custom(CustomFilterType::Hermite),
+            false,
+        )
+        .unwrap();
+
+        assert!(matches!(out, DynamicImage::ImageLuma8(_)));
+    }
+
+    #[pm(dimensions = {
+        (100, 100),
+        (80, 100),
+        (100, 30),
+        (1, 1),
+        (500, 17),
+    })]
+    fn preserve_aspect_ratio_matches_image_crate(dimensions: (u32, u32)) {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(37, 64));
+
+        let expected = image.resize(dimensions.0, dimensions.1, FilterType::Nearest);
+        let actual = resize(
+            &image,
+            dimensions.0,
+            dimensions.1,
+            FilterTypeWrap::custom(CustomFilterType::Mitchell),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(actual.dimensions(), expected.dimensions());
+    }
+
+    #[pm(filter = {
+        FilterTypeWrap::custom(CustomFilterType::Box),
+        FilterTypeWrap::new(FilterType::Triangle),
+    })]
+    fn wildcard_width(filter: FilterTypeWrap) {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(8, 6));
+        let out = resize(&image, 0, 3, filter, false).unwrap();
+
+        assert_eq!(out.dimensions(), (4, 3));
+    }
+
+    #[pm(filter = {
+        FilterTypeWrap::custom(CustomFilterType::Box),
+        FilterTypeWrap::new(FilterType::Triangle),
+    })]
+    fn wildcard_height(filter: FilterTypeWrap) {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(8, 6));
+        let out = resize(&image, 20, 0, filter, false).unwrap();
+
+        assert_eq!(out.dimensions(), (20, 15));
+    }
+
+    #[pm(source = { (8, 6), (8, 6), (1000, 1), (3, 7), (0, 5) }, target = {
+        (0, 1),
+        (3, 0),
+        (100, 0),
+        (0, 100),
+        (0, 5),
+    }, expected = {
+        (1, 1),    // 1.33 rounds down
+        (3, 2),    // 2.25 rounds down
+        (100, 1),  // at least 1
+        (43, 100), // 42.86 rounds up
+        (1, 5),    // empty source
+    })]
+    fn fill_wildcards(source: (u32, u32), target: (u32, u32), expected: (u32, u32)) {
+        assert_eq!(fill_wildcard(source, target).unwrap(), expected);
+    }
+
+    #[pm(target = { (5, 5), (20, 0), (0, 3), (7, 1) }, preserve_aspect_ratio = {
+        true, true, true, false,
+    })]
+    fn resized_dimensions_match_resize(target: (u32, u32), preserve_aspect_ratio: bool) {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(8, 6));
+
+        for filter in &[
+            FilterTypeWrap::custom(CustomFilterType::Box),
+            FilterTypeWrap::new(FilterType::Triangle),
+        ] {
+            let out = resize(&image, target.0, target.1, *filter, preserve_aspect_ratio).unwrap();
+
+            assert_eq!(
+                resized_dimensions((8, 6), target, preserve_aspect_ratio).unwrap(),
+                out.dimensions()
+            );
+        }
+    }
+
+    #[test]
+    fn wildcard_with_preserve_aspect_ratio() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(8, 6));
+        let out = resize(&image, 0, 3, FilterTypeWrap::default(), true).unwrap();
+
+        assert_eq!(out.dimensions(), (4, 3));
+    }
+
+    #[test]
+    fn both_wildcards() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(8, 6));
+        let out = resize(&image, 0, 0, FilterTypeWrap::default(), false);
+
+        assert!(out.is_err());
+    }
+
+    #[test]
+    fn image_filters_are_delegated() {
+        let image = open_test_image(in_!("rainbow_8x6.bmp"));
+        let filter = FilterTypeWrap::new(FilterType::Triangle);
+
+        let expected = image.resize_exact(4, 3, FilterType::Triangle);
+        let actual = resize(&image, 4, 3, filter, false).unwrap();
+
+        assert_eq!(actual.to_rgba().into_raw(), expected.to_rgba().into_raw());
+    }
+}
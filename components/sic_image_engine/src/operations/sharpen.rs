@@ -0,0 +1,136 @@
+use crate::errors::SicImageEngineError;
+use crate::operations::{apply_to_color_samples, ColorSamplesOperation};
+use sic_core::image::{DynamicImage, ImageBuffer, Pixel};
+
+/// Sharpens the image with a Laplacian kernel, which adds the difference between each pixel and
+/// its four direct neighbours, multiplied by the amount. An amount of 1 gives the well known
+/// kernel below, an amount of 0 leaves the image unchanged.
+///
+/// ```text
+///  0 -1  0
+/// -1  5 -1
+///  0 -1  0
+/// ```
+///
+/// Pixels beyond the edges take the value of the nearest edge pixel. The alpha channel is left
+/// untouched. Images with 16-bit samples are converted to 8-bit samples.
+pub(crate) fn sharpen(
+    image: &DynamicImage,
+    amount: f32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if !(amount >= 0.0 && amount.is_finite()) {
+        return Err(SicImageEngineError::SharpenInvalidAmount(amount));
+    }
+
+    Ok(apply_to_color_samples(image, Sharpen(amount)))
+}
+
+struct Sharpen(f32);
+
+impl ColorSamplesOperation for Sharpen {
+    fn apply<P>(
+        self,
+        buffer: ImageBuffer<P, Vec<u8>>,
+        color_channels: usize,
+    ) -> ImageBuffer<P, Vec<u8>>
+    where
+        P: Pixel<Subpixel = u8> + 'static,
+    {
+        sharpen_buffer(buffer, color_channels, self.0)
+    }
+}
+
+fn sharpen_buffer<P>(
+    buffer: ImageBuffer<P, Vec<u8>>,
+    color_channels: usize,
+    amount: f32,
+) -> ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8> + 'static,
+{
+    let (width, height) = buffer.dimensions();
+    let mut out = buffer.clone();
+
+    if width == 0 || height == 0 {
+        return out;
+    }
+
+    let sample =
+        |x: u32, y: u32, channel: usize| f32::from(buffer.get_pixel(x, y).channels()[channel]);
+
+    for y in 0..height {
+        for x in 0..width {
+            let neighbours = [
+                (x.saturating_sub(1), y),
+                ((x + 1).min(width - 1), y),
+                (x, y.saturating_sub(1)),
+                (x, (y + 1).min(height - 1)),
+            ];
+
+            let pixel = out.get_pixel_mut(x, y);
+
+            for channel in 0..color_channels {
+                let center = sample(x, y, channel);
+                let laplacian = neighbours
+                    .iter()
+                    .map(|&(nx, ny)| center - sample(nx, ny, channel))
+                    .sum::<f32>();
+
+                pixel.channels_mut()[channel] =
+                    (center + amount * laplacian).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma, Rgba, RgbaImage};
+    use sic_testing::*;
+
+    ide!();
+
+    fn step() -> DynamicImage {
+        // a dark left half and a light right half
+        DynamicImage::ImageLuma8(GrayImage::from_fn(4, 1, |x, _| {
+            Luma([if x < 2 { 100 } else { 150 }])
+        }))
+    }
+
+    #[pm(
+        amount = { 0.0, 1.0, 0.5, 4.0 },
+        expected = { [100, 100, 150, 150], [100, 50, 200, 150], [100, 75, 175, 150], [100, 0, 255, 150] }
+    )]
+    fn sharpens_edges(amount: f32, expected: [u8; 4]) {
+        let out = sharpen(&step(), amount).unwrap();
+        let out = out.as_luma8().unwrap();
+
+        assert_eq!(out.pixels().map(|px| px[0]).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn flat_image_is_unchanged() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(3, 3, Rgba([10, 20, 30, 40])));
+        let out = sharpen(&image, 2.0).unwrap();
+
+        assert_eq!(out.as_rgba8().unwrap(), image.as_rgba8().unwrap());
+    }
+
+    #[test]
+    fn keeps_alpha() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(2, 1, |x, _| {
+            Rgba([0, 0, 0, if x == 0 { 0 } else { 255 }])
+        }));
+        let out = sharpen(&image, 1.0).unwrap();
+
+        assert_eq!(out.as_rgba8().unwrap(), image.as_rgba8().unwrap());
+    }
+
+    #[pm(amount = { -1.0, f32::NAN, f32::INFINITY })]
+    fn invalid_amount(amount: f32) {
+        assert!(sharpen(&step(), amount).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::analysis::saliency::most_salient_area;
+use crate::errors::SicImageEngineError;
+use sic_core::image::{DynamicImage, GenericImageView};
+
+/// Crops a selection of `width` by `height` pixels from the most interesting area of the image,
+/// e.g. to generate thumbnails without cutting off the subject, as a center crop may do.
+pub(crate) fn smart_crop(
+    image: &DynamicImage,
+    (width, height): (u32, u32),
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (image_width, image_height) = image.dimensions();
+
+    if width == 0 || height == 0 || width > image_width || height > image_height {
+        return Err(SicImageEngineError::CropInvalidSize(
+            width,
+            height,
+            image_width,
+            image_height,
+        ));
+    }
+
+    let (x, y) = most_salient_area(image, (width, height));
+
+    Ok(image.crop_imm(x, y, width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma};
+    use sic_testing::*;
+
+    ide!();
+
+    #[test]
+    fn crops_around_the_subject() {
+        // a flat background, with a bright subject at the left
+        let image = DynamicImage::ImageLuma8(GrayImage::from_fn(30, 10, |x, y| {
+            if (2..6).contains(&x) && (3..7).contains(&y) {
+                Luma([255])
+            } else {
+                Luma([40])
+            }
+        }));
+
+        let out = smart_crop(&image, (10, 10)).unwrap();
+        let out = out.as_luma8().unwrap();
+
+        assert_eq!(out.dimensions(), (10, 10));
+        assert_eq!(out.pixels().filter(|px| px[0] == 255).count(), 16);
+    }
+
+    #[pm(size = { (31, 10), (30, 11), (0, 10), (10, 0) })]
+    fn invalid_size(size: (u32, u32)) {
+        let image = DynamicImage::ImageLuma8(GrayImage::new(30, 10));
+
+        assert!(smart_crop(&image, size).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::errors::SicImageEngineError;
+use sic_core::image::{imageops, DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+/// Appends `other` to the right of the image. The lower of both images is centered vertically,
+/// and padded with transparent pixels.
+pub(crate) fn hstack(
+    image: &DynamicImage,
+    other: &DynamicImage,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+    let (other_width, other_height) = other.dimensions();
+
+    let canvas_width = width
+        .checked_add(other_width)
+        .ok_or(SicImageEngineError::StackTooLarge)?;
+    let canvas_height = height.max(other_height);
+
+    Ok(stack(
+        (image, (0, (canvas_height - height) / 2)),
+        (other, (width, (canvas_height - other_height) / 2)),
+        (canvas_width, canvas_height),
+    ))
+}
+
+/// Appends `other` below the image. The narrower of both images is centered horizontally, and
+/// padded with transparent pixels.
+pub(crate) fn vstack(
+    image: &DynamicImage,
+    other: &DynamicImage,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let (width, height) = image.dimensions();
+    let (other_width, other_height) = other.dimensions();
+
+    let canvas_width = width.max(other_width);
+    let canvas_height = height
+        .checked_add(other_height)
+        .ok_or(SicImageEngineError::StackTooLarge)?;
+
+    Ok(stack(
+        (image, ((canvas_width - width) / 2, 0)),
+        (other, ((canvas_width - other_width) / 2, height)),
+        (canvas_width, canvas_height),
+    ))
+}
+
+/// Places both images on a transparent canvas, at the given positions. The result has an alpha
+/// channel if either image has one, or if the canvas isn't covered by the images.
+fn stack(
+    (first, (first_x, first_y)): (&DynamicImage, (u32, u32)),
+    (second, (second_x, second_y)): (&DynamicImage, (u32, u32)),
+    (width, height): (u32, u32),
+) -> DynamicImage {
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 0]));
+    imageops::replace(&mut canvas, &first.to_rgba(), first_x, first_y);
+    imageops::replace(&mut canvas, &second.to_rgba(), second_x, second_y);
+
+    let covered = u64::from(first.width()) * u64::from(first.height())
+        + u64::from(second.width()) * u64::from(second.height())
+        == u64::from(width) * u64::from(height);
+
+    let canvas = DynamicImage::ImageRgba8(canvas);
+
+    if first.color().has_alpha() || second.color().has_alpha() || !covered {
+        canvas
+    } else {
+        DynamicImage::ImageRgb8(canvas.to_rgb())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage};
+
+    fn filled(width: u32, height: u32, value: u8) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(
+            width,
+            height,
+            Rgb([value, value, value]),
+        ))
+    }
+
+    #[test]
+    fn hstack_same_height() {
+        let out = hstack(&filled(2, 2, 10), &filled(3, 2, 20)).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (5, 2));
+        assert_eq!(out.get_pixel(1, 1), &Rgb([10, 10, 10]));
+        assert_eq!(out.get_pixel(2, 0), &Rgb([20, 20, 20]));
+        assert_eq!(out.get_pixel(4, 1), &Rgb([20, 20, 20]));
+    }
+
+    #[test]
+    fn hstack_centers_lower_image() {
+        let out = hstack(&filled(1, 3, 10), &filled(1, 1, 20)).unwrap();
+
+        assert_eq!(out.dimensions(), (2, 3));
+        assert_eq!(out.get_pixel(0, 0), Rgba([10, 10, 10, 255]));
+        assert_eq!(out.get_pixel(1, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(out.get_pixel(1, 1), Rgba([20, 20, 20, 255]));
+        assert_eq!(out.get_pixel(1, 2), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn vstack_same_width() {
+        let out = vstack(&filled(2, 1, 10), &filled(2, 2, 20)).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (2, 3));
+        assert_eq!(out.get_pixel(1, 0), &Rgb([10, 10, 10]));
+        assert_eq!(out.get_pixel(0, 1), &Rgb([20, 20, 20]));
+        assert_eq!(out.get_pixel(1, 2), &Rgb([20, 20, 20]));
+    }
+
+    #[test]
+    fn vstack_centers_narrower_image() {
+        let out = vstack(&filled(1, 1, 10), &filled(3, 1, 20)).unwrap();
+
+        assert_eq!(out.dimensions(), (3, 2));
+        assert_eq!(out.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(out.get_pixel(1, 0), Rgba([10, 10, 10, 255]));
+        assert_eq!(out.get_pixel(2, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(out.get_pixel(0, 1), Rgba([20, 20, 20, 255]));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::wrapper::gravity::Gravity;
+use sic_core::image::{imageops, DynamicImage, GenericImageView, Pixel, Rgba, RgbaImage};
+
+/// The smallest difference in mean luminance between the stamp and the area below it at which
+/// the stamp is considered to be readable.
+const MIN_CONTRAST: f64 = 128.0;
+
+/// Areas with a luminance standard deviation above this value are considered too busy for a
+/// stamp to be readable without a scrim.
+const MAX_BUSYNESS: f64 = 48.0;
+
+/// The opacity of the scrim which is drawn below a stamp when neither variant of the stamp stands
+/// out enough.
+const SCRIM_ALPHA: u8 = 160;
+
+/// The distance in pixels by which the scrim extends beyond the stamp.
+const SCRIM_PADDING: u32 = 4;
+
+/// How a stamp is drawn so it stays visible on the area below it.
+#[derive(Debug, PartialEq)]
+enum Treatment {
+    Original,
+    Inverted,
+    Scrim { inverted: bool },
+}
+
+/// Overlays a stamp, such as a signature or a logo, on the image, at the side or corner given by
+/// `gravity`, `margin` pixels away from the edges.
+///
+/// Before the stamp is drawn, the luminance of the area below it is compared against the
+/// luminance of the stamp. If the stamp doesn't stand out, its colors are inverted, e.g. a dark
+/// signature becomes a light one on a dark photo. If neither variant stands out, because the area
+/// below has a medium luminance or is busy, a translucent scrim is drawn below the stamp first.
+pub(crate) fn stamp(image: &mut DynamicImage, stamp: &DynamicImage, gravity: Gravity, margin: u32) {
+    let mut stamp = stamp.to_rgba();
+    let (x, y) = gravity.position(image.dimensions(), stamp.dimensions(), margin);
+
+    let background = Luminance::of_area(image, x, y, stamp.width(), stamp.height());
+    let treatment = choose_treatment(&background, stamp_luminance(&stamp));
+
+    if matches!(
+        treatment,
+        Treatment::Inverted | Treatment::Scrim { inverted: true }
+    ) {
+        invert_colors(&mut stamp);
+    }
+
+    if let Treatment::Scrim { .. } = treatment {
+        // the scrim has the opposite tone of the stamp
+        let tone = if stamp_luminance(&stamp) < 128.0 {
+            255
+        } else {
+            0
+        };
+
+        let scrim = RgbaImage::from_pixel(
+            stamp.width() + 2 * SCRIM_PADDING,
+            stamp.height() + 2 * SCRIM_PADDING,
+            Rgba([tone, tone, tone, SCRIM_ALPHA]),
+        );
+
+        draw(
+            image,
+            &scrim,
+            i64::from(x) - i64::from(SCRIM_PADDING),
+            i64::from(y) - i64::from(SCRIM_PADDING),
+        );
+    }
+
+    draw(image, &stamp, i64::from(x), i64::from(y));
+}
+
+fn choose_treatment(background: &Luminance, stamp: f64) -> Treatment {
+    let original = (background.mean - stamp).abs();
+    let inverted = (background.mean - (255.0 - stamp)).abs();
+
+    if background.deviation > MAX_BUSYNESS || original.max(inverted) < MIN_CONTRAST {
+        Treatment::Scrim {
+            inverted: inverted > original,
+        }
+    } else if original >= MIN_CONTRAST {
+        Treatment::Original
+    } else {
+        Treatment::Inverted
+    }
+}
+
+/// Blends `top` over `image` with its top left corner at `(x, y)`, which may lie outside of the
+/// image.
+fn draw(image: &mut DynamicImage, top: &RgbaImage, x: i64, y: i64) {
+    let (left, up) = ((-x).max(0) as u32, (-y).max(0) as u32);
+
+    if left >= top.width() || up >= top.height() {
+        return;
+    }
+
+    let visible = imageops::crop_imm(top, left, up, top.width() - left, top.height() - up);
+
+    imageops::overlay(image, &visible.to_image(), x.max(0) as u32, y.max(0) as u32);
+}
+
+/// The mean luminance of a stamp, where each pixel contributes by its opacity.
+fn stamp_luminance(stamp: &RgbaImage) -> f64 {
+    let (sum, weight) = stamp.pixels().fold((0.0, 0.0), |(sum, weight), pixel| {
+        let alpha = f64::from(pixel[3]) / 255.0;
+        (sum + f64::from(pixel.to_luma()[0]) * alpha, weight + alpha)
+    });
+
+    if weight > 0.0 {
+        sum / weight
+    } else {
+        0.0
+    }
+}
+
+fn invert_colors(stamp: &mut RgbaImage) {
+    for pixel in stamp.pixels_mut() {
+        pixel[0] = 255 - pixel[0];
+        pixel[1] = 255 - pixel[1];
+        pixel[2] = 255 - pixel[2];
+    }
+}
+
+struct Luminance {
+    mean: f64,
+    deviation: f64,
+}
+
+impl Luminance {
+    /// The luminance statistics of the part of the given area which lies within the image.
+    fn of_area(image: &DynamicImage, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let right = x.saturating_add(width).min(image.width());
+        let bottom = y.saturating_add(height).min(image.height());
+
+        let (mut sum, mut squares, mut count) = (0.0, 0.0, 0.0);
+
+        for py in y..bottom {
+            for px in x..right {
+                let value = f64::from(image.get_pixel(px, py).to_luma()[0]);
+                sum += value;
+                squares += value * value;
+                count += 1.0;
+            }
+        }
+
+        if count == 0.0 {
+            return Self {
+                mean: 0.0,
+                deviation: 0.0,
+            };
+        }
+
+        let mean = sum / count;
+
+        Self {
+            mean,
+            deviation: (squares / count - mean * mean).max(0.0).sqrt(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GrayImage, Luma};
+    use sic_testing::*;
+
+    ide!();
+
+    fn plain(width: u32, height: u32, value: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(width, height, Luma([value])))
+    }
+
+    fn signature() -> DynamicImage {
+        // a dark line on a transparent background
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(6, 3, |_, y| {
+            if y == 1 {
+                Rgba([20, 20, 20, 255])
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        }))
+    }
+
+    #[test]
+    fn dark_stamp_on_light_background_is_kept() {
+        let mut image = plain(20, 10, 230);
+
+        stamp(&mut image, &signature(), Gravity::BottomRight, 2);
+
+        assert_eq!(image.get_pixel(13, 6), Rgba([20, 20, 20, 255]));
+        assert_eq!(image.get_pixel(13, 5), Rgba([230, 230, 230, 255]));
+    }
+
+    #[test]
+    fn dark_stamp_on_dark_background_is_inverted() {
+        let mut image = plain(20, 10, 30);
+
+        stamp(&mut image, &signature(), Gravity::TopLeft, 0);
+
+        assert_eq!(image.get_pixel(0, 1), Rgba([235, 235, 235, 255]));
+        assert_eq!(image.get_pixel(0, 0), Rgba([30, 30, 30, 255]));
+    }
+
+    #[test]
+    fn medium_background_gets_scrim() {
+        let mut image = plain(20, 20, 128);
+
+        stamp(&mut image, &signature(), Gravity::Center, 0);
+
+        // the stamp is placed at (7, 8), the scrim extends around it
+        assert_eq!(image.get_pixel(8, 9), Rgba([20, 20, 20, 255]));
+        assert!(image.get_pixel(8, 8)[0] > 128);
+        assert!(image.get_pixel(3, 4)[0] > 128);
+        assert_eq!(image.get_pixel(2, 4), Rgba([128, 128, 128, 255]));
+    }
+
+    #[test]
+    fn busy_background_gets_scrim() {
+        let background = Luminance {
+            mean: 200.0,
+            deviation: 100.0,
+        };
+
+        assert_eq!(
+            choose_treatment(&background, 20.0),
+            Treatment::Scrim { inverted: false }
+        );
+    }
+
+    #[pm(
+        background = { 0.0, 40.0, 120.0, 200.0, 255.0 },
+        expected = {
+            Treatment::Inverted,
+            Treatment::Inverted,
+            Treatment::Scrim { inverted: true },
+            Treatment::Original,
+            Treatment::Original,
+        }
+    )]
+    fn treatment_of_dark_stamp(background: f64, expected: Treatment) {
+        let background = Luminance {
+            mean: background,
+            deviation: 0.0,
+        };
+
+        assert_eq!(choose_treatment(&background, 20.0), expected);
+    }
+
+    #[test]
+    fn stamp_larger_than_image() {
+        let mut image = plain(4, 2, 128);
+
+        stamp(&mut image, &signature(), Gravity::BottomRight, 3);
+
+        assert_eq!(image.dimensions(), (4, 2));
+    }
+
+    #[test]
+    fn fully_transparent_stamp() {
+        let mut image = plain(4, 4, 200);
+        let transparent = DynamicImage::ImageRgba8(RgbaImage::new(2, 2));
+
+        stamp(&mut image, &transparent, Gravity::Center, 0);
+
+        assert_eq!(image.get_pixel(1, 1), Rgba([200, 200, 200, 255]));
+    }
+}
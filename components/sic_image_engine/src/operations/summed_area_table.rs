@@ -0,0 +1,127 @@
+/// A summed-area table (also known as an integral image), which can be used to compute the sum of
+/// the values within any rectangular area in constant time.
+pub(crate) struct SummedAreaTable {
+    width: u32,
+    sums: Vec<u64>,
+}
+
+impl SummedAreaTable {
+    /// Creates a summed-area table for a `width` by `height` grid, where the value of each
+    /// cell is provided by `value`.
+    pub(crate) fn from_fn<F: Fn(u32, u32) -> u64>(width: u32, height: u32, value: F) -> Self {
+        let stride = width as usize + 1;
+        let mut sums = vec![0u64; stride * (height as usize + 1)];
+
+        for y in 0..height {
+            let mut row = 0;
+
+            for x in 0..width {
+                row += value(x, y);
+
+                let index = (y as usize + 1) * stride + x as usize + 1;
+                sums[index] = sums[index - stride] + row;
+            }
+        }
+
+        Self { width, sums }
+    }
+
+    /// The sum of all values within the given window.
+    pub(crate) fn sum(&self, window: &Window) -> u64 {
+        let stride = self.width as usize + 1;
+        let at = |x: u32, y: u32| self.sums[y as usize * stride + x as usize];
+
+        at(window.right, window.bottom) + at(window.left, window.top)
+            - at(window.right, window.top)
+            - at(window.left, window.bottom)
+    }
+}
+
+/// A rectangular area, including its `left` and `top` edges, but excluding its `right` and `bottom`
+/// edges.
+pub(crate) struct Window {
+    left: u32,
+    top: u32,
+    right: u32,
+    bottom: u32,
+}
+
+impl Window {
+    /// The window of `width` by `height` cells with its top left corner at `(left, top)`.
+    pub(crate) fn new(left: u32, top: u32, width: u32, height: u32) -> Self {
+        Self {
+            left,
+            top,
+            right: left + width,
+            bottom: top + height,
+        }
+    }
+
+    /// The square window of `2 * radius + 1` by `2 * radius + 1` cells around the given center,
+    /// clipped to the bounds of a `width` by `height` grid.
+    pub(crate) fn around(x: u32, y: u32, radius: u32, width: u32, height: u32) -> Self {
+        Self {
+            left: x.saturating_sub(radius),
+            top: y.saturating_sub(radius),
+            right: x.saturating_add(radius).saturating_add(1).min(width),
+            bottom: y.saturating_add(radius).saturating_add(1).min(height),
+        }
+    }
+
+    /// The amount of cells within the window.
+    pub(crate) fn area(&self) -> u64 {
+        u64::from(self.right - self.left) * u64::from(self.bottom - self.top)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    // 1 2 3
+    // 4 5 6
+    fn table() -> SummedAreaTable {
+        SummedAreaTable::from_fn(3, 2, |x, y| u64::from(y * 3 + x + 1))
+    }
+
+    #[pm(
+        window = {
+            Window { left: 0, top: 0, right: 3, bottom: 2 },
+            Window { left: 1, top: 0, right: 3, bottom: 2 },
+            Window { left: 1, top: 1, right: 2, bottom: 2 },
+            Window { left: 0, top: 0, right: 1, bottom: 1 },
+            Window { left: 2, top: 0, right: 2, bottom: 2 },
+        },
+        expected = { 21, 16, 5, 1, 0 }
+    )]
+    fn sums(window: Window, expected: u64) {
+        assert_eq!(table().sum(&window), expected);
+    }
+
+    #[test]
+    fn window_from_size() {
+        let window = Window::new(1, 0, 2, 2);
+
+        assert_eq!(window.area(), 4);
+        assert_eq!(table().sum(&window), 16);
+    }
+
+    #[test]
+    fn window_is_clipped() {
+        let window = Window::around(0, 1, 2, 3, 2);
+
+        assert_eq!(window.area(), 6);
+        assert_eq!(table().sum(&window), 21);
+    }
+
+    #[test]
+    fn window_of_radius_zero() {
+        let window = Window::around(2, 1, 0, 3, 2);
+
+        assert_eq!(window.area(), 1);
+        assert_eq!(table().sum(&window), 6);
+    }
+}
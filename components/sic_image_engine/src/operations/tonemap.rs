@@ -0,0 +1,120 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::tonemap::{ToneMapInputs, ToneMapOperator};
+use sic_core::hdr::{self, Rgba32FImage};
+use sic_core::image::{DynamicImage, Rgba};
+
+/// Maps the linear samples of a high dynamic range image to the range [0, 1], so its highlights
+/// remain visible instead of being clipped when it is converted to a low dynamic range image.
+/// The samples are first multiplied by 2 to the power of the exposure. The alpha channel is left
+/// untouched. The result is a 16-bit sRGB encoded image.
+pub(crate) fn tonemap(
+    image: &Rgba32FImage,
+    inputs: &ToneMapInputs,
+) -> Result<DynamicImage, SicImageEngineError> {
+    let exposure = inputs.exposure();
+
+    if !exposure.is_finite() {
+        return Err(SicImageEngineError::ToneMapInvalidExposure(exposure));
+    }
+
+    let scale = exposure.exp2();
+    let operator = match inputs.operator() {
+        ToneMapOperator::Reinhard => reinhard,
+        ToneMapOperator::Aces => aces,
+    };
+
+    let mut mapped = image.clone();
+
+    for pixel in mapped.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        *pixel = Rgba([
+            operator(r * scale),
+            operator(g * scale),
+            operator(b * scale),
+            a,
+        ]);
+    }
+
+    Ok(hdr::to_dynamic_image(&mapped))
+}
+
+fn reinhard(value: f32) -> f32 {
+    let value = value.max(0.0);
+    value / (1.0 + value)
+}
+
+/// Krzysztof Narkowicz' fit of the ACES filmic tone mapping curve.
+fn aces(value: f32) -> f32 {
+    let value = value.max(0.0);
+    let mapped = (value * (2.51 * value + 0.03)) / (value * (2.43 * value + 0.59) + 0.14);
+    mapped.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(samples: &[f32]) -> Rgba32FImage {
+        let data = samples
+            .iter()
+            .flat_map(|&sample| vec![sample, sample, sample, 1.0])
+            .collect::<Vec<_>>();
+
+        Rgba32FImage::from_raw(samples.len() as u32, 1, data).unwrap()
+    }
+
+    fn red_samples(image: &DynamicImage) -> Vec<u16> {
+        match image {
+            DynamicImage::ImageRgba16(buffer) => buffer.pixels().map(|pixel| pixel[0]).collect(),
+            _ => panic!("Expected a 16 bit RGBA image."),
+        }
+    }
+
+    #[test]
+    fn reinhard_keeps_highlights() {
+        let inputs = ToneMapInputs::new(ToneMapOperator::Reinhard, 0.0);
+        let out = tonemap(&image(&[0.0, 1.0, 4.0, 100.0]), &inputs).unwrap();
+        let samples = red_samples(&out);
+
+        assert_eq!(samples[0], 0);
+        assert!(samples.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(samples[3] < u16::MAX);
+    }
+
+    #[test]
+    fn aces_clips_the_brightest_highlights() {
+        let inputs = ToneMapInputs::new(ToneMapOperator::Aces, 0.0);
+        let out = tonemap(&image(&[0.0, 0.18, 1.0, 100.0]), &inputs).unwrap();
+        let samples = red_samples(&out);
+
+        assert_eq!(samples[0], 0);
+        assert!(samples[1] < samples[2]);
+        assert_eq!(samples[3], u16::MAX);
+    }
+
+    #[test]
+    fn exposure_in_stops() {
+        let brighter = ToneMapInputs::new(ToneMapOperator::Reinhard, 1.0);
+        let default = ToneMapInputs::new(ToneMapOperator::Reinhard, 0.0);
+
+        assert_eq!(
+            tonemap(&image(&[0.5]), &brighter).unwrap().to_bytes(),
+            tonemap(&image(&[1.0]), &default).unwrap().to_bytes()
+        );
+    }
+
+    #[test]
+    fn negative_samples_are_black() {
+        let inputs = ToneMapInputs::new(ToneMapOperator::Aces, 0.0);
+        let out = tonemap(&image(&[-2.0]), &inputs).unwrap();
+
+        assert_eq!(red_samples(&out), vec![0]);
+    }
+
+    #[test]
+    fn invalid_exposure() {
+        let inputs = ToneMapInputs::new(ToneMapOperator::Reinhard, f32::NAN);
+
+        assert!(tonemap(&image(&[1.0]), &inputs).is_err());
+    }
+}
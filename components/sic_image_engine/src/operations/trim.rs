@@ -0,0 +1,138 @@
+use crate::errors::SicImageEngineError;
+use sic_core::image::{DynamicImage, Rgba, RgbaImage};
+
+/// Removes the uniform borders of the image, such as the white margins of a scanned page or the
+/// transparent margins of a screenshot.
+///
+/// The color of the border is taken from the top left pixel. Pixels whose euclidean distance to
+/// that color in RGBA space is at most `tolerance` belong to the border; fully transparent pixels
+/// all have the same color, regardless of their RGB values. The image is cropped to the smallest
+/// rectangle which holds all other pixels. Images which only consist of border are left as is.
+pub(crate) fn trim(
+    image: &DynamicImage,
+    tolerance: f32,
+) -> Result<DynamicImage, SicImageEngineError> {
+    if tolerance.is_nan() || tolerance < 0.0 {
+        return Err(SicImageEngineError::TrimInvalidTolerance(tolerance));
+    }
+
+    let buffer = image.to_rgba();
+
+    match content_bounds(&buffer, tolerance) {
+        Some((x, y, width, height)) => Ok(image.crop_imm(x, y, width, height)),
+        None => Ok(image.clone()),
+    }
+}
+
+/// The `(x, y, width, height)` of the smallest rectangle which holds all pixels which don't
+/// belong to the border, if there are any.
+fn content_bounds(buffer: &RgbaImage, tolerance: f32) -> Option<(u32, u32, u32, u32)> {
+    let (width, height) = buffer.dimensions();
+
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let border = *buffer.get_pixel(0, 0);
+    let is_content = |x: u32, y: u32| distance(buffer.get_pixel(x, y), &border) > tolerance;
+
+    let top = (0..height).find(|&y| (0..width).any(|x| is_content(x, y)))?;
+    let bottom = (top..height)
+        .rev()
+        .find(|&y| (0..width).any(|x| is_content(x, y)))?;
+    let left = (0..width).find(|&x| (top..=bottom).any(|y| is_content(x, y)))?;
+    let right = (left..width)
+        .rev()
+        .find(|&x| (top..=bottom).any(|y| is_content(x, y)))?;
+
+    Some((left, top, right - left + 1, bottom - top + 1))
+}
+
+fn distance(pixel: &Rgba<u8>, border: &Rgba<u8>) -> f32 {
+    if pixel[3] == 0 && border[3] == 0 {
+        return 0.0;
+    }
+
+    (0..4)
+        .map(|channel| (f32::from(pixel[channel]) - f32::from(border[channel])).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Rgb, RgbImage};
+
+    const WHITE: Rgb<u8> = Rgb([255, 255, 255]);
+    const RED: Rgb<u8> = Rgb([255, 0, 0]);
+
+    #[test]
+    fn trims_uniform_border() {
+        let mut buffer = RgbImage::from_pixel(8, 6, WHITE);
+        buffer.put_pixel(2, 1, RED);
+        buffer.put_pixel(5, 3, RED);
+
+        let out = trim(&DynamicImage::ImageRgb8(buffer), 0.0).unwrap();
+        let out = out.as_rgb8().unwrap();
+
+        assert_eq!(out.dimensions(), (4, 3));
+        assert_eq!(out.get_pixel(0, 0), &RED);
+        assert_eq!(out.get_pixel(3, 2), &RED);
+    }
+
+    #[test]
+    fn tolerance() {
+        let mut buffer = RgbImage::from_pixel(5, 5, WHITE);
+        // noise from scanning, close to white
+        buffer.put_pixel(0, 4, Rgb([250, 250, 250]));
+        buffer.put_pixel(2, 2, RED);
+        let image = DynamicImage::ImageRgb8(buffer);
+
+        assert_eq!(trim(&image, 0.0).unwrap().dimensions(), (3, 3));
+        assert_eq!(trim(&image, 10.0).unwrap().dimensions(), (1, 1));
+    }
+
+    #[test]
+    fn transparent_border() {
+        // the rgb values of transparent pixels differ, but they're all transparent
+        let mut buffer = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 0]));
+        buffer.put_pixel(3, 3, Rgba([255, 255, 255, 0]));
+        buffer.put_pixel(1, 1, Rgba([0, 0, 0, 255]));
+        buffer.put_pixel(1, 2, Rgba([0, 0, 0, 255]));
+
+        let out = trim(&DynamicImage::ImageRgba8(buffer), 0.0).unwrap();
+
+        assert_eq!(out.dimensions(), (1, 2));
+        assert!(out.color().has_alpha());
+    }
+
+    #[test]
+    fn only_border() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(3, 2, WHITE));
+
+        assert_eq!(trim(&image, 0.0).unwrap().dimensions(), (3, 2));
+    }
+
+    #[test]
+    fn no_uniform_border() {
+        // the top left pixel differs from the other pixels at the edges
+        let mut buffer = RgbImage::from_pixel(3, 3, WHITE);
+        buffer.put_pixel(0, 0, RED);
+
+        assert_eq!(
+            trim(&DynamicImage::ImageRgb8(buffer), 0.0)
+                .unwrap()
+                .dimensions(),
+            (3, 3)
+        );
+    }
+
+    #[test]
+    fn invalid_tolerance() {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(1, 1));
+
+        assert!(trim(&image, -1.0).is_err());
+        assert!(trim(&image, f32::NAN).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//! Serialization of the types of the image crate and of rusttype, which don't implement `Serialize`
+//! and `Deserialize` themselves, for use with `#[serde(with = "...")]`.
+//!
+//! Colors are written as their channels, e.g. `[255, 0, 0, 255]`.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sic_core::image::Rgba;
+
+pub(crate) mod rgba {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        color: &Rgba<u8>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        color.0.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Rgba<u8>, D::Error> {
+        <[u8; 4]>::deserialize(deserializer).map(Rgba)
+    }
+}
+
+pub(crate) mod option_rgba {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        color: &Option<Rgba<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        color.map(|color| color.0).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Rgba<u8>>, D::Error> {
+        Option::<[u8; 4]>::deserialize(deserializer).map(|color| color.map(Rgba))
+    }
+}
+
+pub(crate) mod rgba_pair {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        (first, second): &(Rgba<u8>, Rgba<u8>),
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        (first.0, second.0).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<(Rgba<u8>, Rgba<u8>), D::Error> {
+        <([u8; 4], [u8; 4])>::deserialize(deserializer)
+            .map(|(first, second)| (Rgba(first), Rgba(second)))
+    }
+}
+
+/// A scale is written as its horizontal and vertical scale, e.g. `[16.0, 16.0]`.
+#[cfg(feature = "imageproc-ops")]
+pub(crate) mod scale {
+    use super::*;
+    use rusttype::Scale;
+
+    pub(crate) fn serialize<S: Serializer>(
+        scale: &Scale,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        (scale.x, scale.y).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Scale, D::Error> {
+        <(f32, f32)>::deserialize(deserializer).map(|(x, y)| Scale { x, y })
+    }
+}
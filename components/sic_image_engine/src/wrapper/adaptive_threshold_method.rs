@@ -0,0 +1,26 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// Determines how the local threshold of each pixel is computed by the adaptive threshold
+/// operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AdaptiveThresholdMethod {
+    /// The threshold is the mean of the neighbourhood of a pixel.
+    #[default]
+    Mean,
+    /// The threshold is the Gaussian weighted sum of the neighbourhood of a pixel.
+    Gaussian,
+}
+
+impl AdaptiveThresholdMethod {
+    pub fn try_from_str(val: &str) -> Result<AdaptiveThresholdMethod, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "mean" => Ok(AdaptiveThresholdMethod::Mean),
+            "gaussian" => Ok(AdaptiveThresholdMethod::Gaussian),
+            fail => Err(SicImageEngineError::UnknownAdaptiveThresholdMethod(
+                fail.to_string(),
+            )),
+        }
+    }
+}
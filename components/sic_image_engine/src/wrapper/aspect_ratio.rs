@@ -0,0 +1,78 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// An aspect ratio, such as 16:9.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AspectRatio {
+    width: u32,
+    height: u32,
+}
+
+impl AspectRatio {
+    /// Parses a ratio of the form `<width>:<height>`, e.g. `16:9`; both sides should be larger
+    /// than 0.
+    pub fn try_from_str(val: &str) -> Result<AspectRatio, SicImageEngineError> {
+        let invalid = || SicImageEngineError::InvalidAspectRatio(val.to_string());
+
+        let (width, height) = match val.split(':').collect::<Vec<_>>().as_slice() {
+            [width, height] => (
+                width.trim().parse::<u32>().map_err(|_| invalid())?,
+                height.trim().parse::<u32>().map_err(|_| invalid())?,
+            ),
+            _ => return Err(invalid()),
+        };
+
+        if width == 0 || height == 0 {
+            return Err(invalid());
+        }
+
+        Ok(AspectRatio { width, height })
+    }
+
+    pub fn width(self) -> u32 {
+        self.width
+    }
+
+    pub fn height(self) -> u32 {
+        self.height
+    }
+}
+
+impl TryFrom<String> for AspectRatio {
+    type Error = SicImageEngineError;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        AspectRatio::try_from_str(&val)
+    }
+}
+
+impl From<AspectRatio> for String {
+    fn from(ratio: AspectRatio) -> Self {
+        format!("{}:{}", ratio.width, ratio.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        input = { "16:9", "1:1", "4:3", "9:16" },
+        expected = { (16, 9), (1, 1), (4, 3), (9, 16) }
+    )]
+    fn from_str(input: &str, expected: (u32, u32)) {
+        let ratio = AspectRatio::try_from_str(input).unwrap();
+
+        assert_eq!((ratio.width(), ratio.height()), expected);
+    }
+
+    #[pm(input = { "16", "16:", ":9", "16:9:1", "0:9", "16:0", "-16:9", "1.5:1", "a:b", "" })]
+    fn invalid(input: &str) {
+        assert!(AspectRatio::try_from_str(input).is_err());
+    }
+}
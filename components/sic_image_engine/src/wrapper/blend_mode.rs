@@ -0,0 +1,97 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// How the colors of an image which is composited over another image are combined with the
+/// colors below it, as defined by the W3C "Compositing and Blending" specification.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BlendMode {
+    /// Plain alpha compositing: the image covers the image below it.
+    #[default]
+    Normal,
+    /// Multiplies the colors, which darkens; white is neutral.
+    Multiply,
+    /// Multiplies the inverted colors, which lightens; black is neutral.
+    Screen,
+    /// Multiplies the dark and screens the light colors of the image below, which increases the
+    /// contrast.
+    Overlay,
+    /// Picks the darker of the colors, per channel.
+    Darken,
+    /// Picks the lighter of the colors, per channel.
+    Lighten,
+}
+
+impl BlendMode {
+    pub fn try_from_str(val: &str) -> Result<BlendMode, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "normal" => Ok(BlendMode::Normal),
+            "multiply" => Ok(BlendMode::Multiply),
+            "screen" => Ok(BlendMode::Screen),
+            "overlay" => Ok(BlendMode::Overlay),
+            "darken" => Ok(BlendMode::Darken),
+            "lighten" => Ok(BlendMode::Lighten),
+            fail => Err(SicImageEngineError::UnknownBlendMode(fail.to_string())),
+        }
+    }
+
+    /// Blends a channel of the color below (`backdrop`) with a channel of the color on top
+    /// (`source`); both are between 0 and 1.
+    pub(crate) fn blend(self, backdrop: f32, source: f32) -> f32 {
+        match self {
+            BlendMode::Normal => source,
+            BlendMode::Multiply => backdrop * source,
+            BlendMode::Screen => backdrop + source - backdrop * source,
+            BlendMode::Overlay if backdrop <= 0.5 => 2.0 * backdrop * source,
+            BlendMode::Overlay => 1.0 - 2.0 * (1.0 - backdrop) * (1.0 - source),
+            BlendMode::Darken => backdrop.min(source),
+            BlendMode::Lighten => backdrop.max(source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        name = { "normal", "Multiply", "SCREEN", "overlay", "darken", "lighten" },
+        expected = {
+            BlendMode::Normal,
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Overlay,
+            BlendMode::Darken,
+            BlendMode::Lighten,
+        }
+    )]
+    fn from_str(name: &str, expected: BlendMode) {
+        assert_eq!(BlendMode::try_from_str(name).unwrap(), expected);
+    }
+
+    #[pm(name = { "", "add", "difference" })]
+    fn unknown(name: &str) {
+        assert!(BlendMode::try_from_str(name).is_err());
+    }
+
+    #[pm(
+        mode = {
+            BlendMode::Normal,
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Overlay,
+            BlendMode::Overlay,
+            BlendMode::Darken,
+            BlendMode::Lighten,
+        },
+        backdrop = { 0.5, 0.5, 0.5, 0.25, 0.75, 0.5, 0.5 },
+        source = { 0.2, 0.2, 0.2, 0.5, 0.5, 0.2, 0.2 },
+        expected = { 0.2, 0.1, 0.6, 0.25, 0.75, 0.2, 0.5 }
+    )]
+    fn blend(mode: BlendMode, backdrop: f32, source: f32, expected: f32) {
+        assert!((mode.blend(backdrop, source) - expected).abs() < 1e-6);
+    }
+}
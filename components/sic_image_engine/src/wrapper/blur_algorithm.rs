@@ -0,0 +1,24 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// Determines which algorithm is used by the blur operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BlurAlgorithm {
+    /// A Gaussian blur, which looks best, but becomes slow for large sigmas.
+    #[default]
+    Gaussian,
+    /// A box blur with about the same spread as the Gaussian blur, which takes the same time
+    /// regardless of the sigma.
+    Box,
+}
+
+impl BlurAlgorithm {
+    pub fn try_from_str(val: &str) -> Result<BlurAlgorithm, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "gaussian" => Ok(BlurAlgorithm::Gaussian),
+            "box" => Ok(BlurAlgorithm::Box),
+            fail => Err(SicImageEngineError::UnknownBlurAlgorithm(fail.to_string())),
+        }
+    }
+}
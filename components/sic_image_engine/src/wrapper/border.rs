@@ -0,0 +1,86 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+/// The widths and color of a solid border around the image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BorderInputs {
+    top: u32,
+    right: u32,
+    bottom: u32,
+    left: u32,
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+}
+
+impl BorderInputs {
+    /// Takes 1 to 4 widths, which are assigned to the sides like the CSS `border-width` property
+    /// does: one width is used for all sides; two widths are used for the top and bottom, and the
+    /// left and right; three widths are used for the top, the left and right, and the bottom; and
+    /// four widths are used for the top, right, bottom and left, in that order.
+    pub fn new(widths: &[u32], color: Rgba<u8>) -> Result<Self, SicImageEngineError> {
+        let (top, right, bottom, left) = match *widths {
+            [all] => (all, all, all, all),
+            [vertical, horizontal] => (vertical, horizontal, vertical, horizontal),
+            [top, horizontal, bottom] => (top, horizontal, bottom, horizontal),
+            [top, right, bottom, left] => (top, right, bottom, left),
+            _ => return Err(SicImageEngineError::BorderInvalidWidthCount(widths.len())),
+        };
+
+        Ok(Self {
+            top,
+            right,
+            bottom,
+            left,
+            color,
+        })
+    }
+
+    pub fn top(&self) -> u32 {
+        self.top
+    }
+
+    pub fn right(&self) -> u32 {
+        self.right
+    }
+
+    pub fn bottom(&self) -> u32 {
+        self.bottom
+    }
+
+    pub fn left(&self) -> u32 {
+        self.left
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    #[pm(
+        widths = { &[1], &[1, 2], &[1, 2, 3], &[1, 2, 3, 4] },
+        expected = { (1, 1, 1, 1), (1, 2, 1, 2), (1, 2, 3, 2), (1, 2, 3, 4) }
+    )]
+    fn widths_like_css(widths: &[u32], expected: (u32, u32, u32, u32)) {
+        let border = BorderInputs::new(widths, BLACK).unwrap();
+
+        assert_eq!(
+            (border.top(), border.right(), border.bottom(), border.left()),
+            expected
+        );
+    }
+
+    #[pm(widths = { &[], &[1, 2, 3, 4, 5] })]
+    fn invalid_width_count(widths: &[u32]) {
+        assert!(BorderInputs::new(widths, BLACK).is_err());
+    }
+}
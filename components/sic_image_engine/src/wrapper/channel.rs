@@ -0,0 +1,55 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// A channel of an RGBA image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Channel {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+impl Channel {
+    pub fn try_from_str(val: &str) -> Result<Channel, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "r" | "red" => Ok(Channel::Red),
+            "g" | "green" => Ok(Channel::Green),
+            "b" | "blue" => Ok(Channel::Blue),
+            "a" | "alpha" => Ok(Channel::Alpha),
+            fail => Err(SicImageEngineError::UnknownChannel(fail.to_string())),
+        }
+    }
+
+    /// The index of the channel within an RGBA pixel.
+    pub fn index(self) -> usize {
+        match self {
+            Channel::Red => 0,
+            Channel::Green => 1,
+            Channel::Blue => 2,
+            Channel::Alpha => 3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        name = { "r", "green", "B", "Alpha" },
+        expected = { Channel::Red, Channel::Green, Channel::Blue, Channel::Alpha }
+    )]
+    fn from_str(name: &str, expected: Channel) {
+        assert_eq!(Channel::try_from_str(name).unwrap(), expected);
+    }
+
+    #[pm(name = { "", "x", "rgb", "luma" })]
+    fn unknown(name: &str) {
+        assert!(Channel::try_from_str(name).is_err());
+    }
+}
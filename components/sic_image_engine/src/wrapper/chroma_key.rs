@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChromaKeyInputs {
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+    tolerance: f32,
+    feather: f32,
+}
+
+impl ChromaKeyInputs {
+    pub fn new(color: Rgba<u8>, tolerance: f32, feather: f32) -> Self {
+        Self {
+            color,
+            tolerance,
+            feather,
+        }
+    }
+
+    /// The key color; its alpha channel is not used.
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+
+    pub fn tolerance(&self) -> f32 {
+        self.tolerance
+    }
+
+    pub fn feather(&self) -> f32 {
+        self.feather
+    }
+}
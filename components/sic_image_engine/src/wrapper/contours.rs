@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+/// The color in which contours are drawn, if no color is given.
+pub const CONTOURS_DEFAULT_COLOR: Rgba<u8> = Rgba([0, 255, 0, 255]);
+
+/// The threshold by which the image is binarized before its contours are traced, and the color in
+/// which they are drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContoursInputs {
+    threshold: u8,
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+}
+
+impl ContoursInputs {
+    pub fn new(threshold: u8, color: Rgba<u8>) -> Self {
+        Self { threshold, color }
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+}
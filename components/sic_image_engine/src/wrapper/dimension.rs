@@ -0,0 +1,132 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::expression::{Expression, ImageProperties};
+use serde::{Deserialize, Serialize};
+
+/// A length along the width or the height of the image: a number of pixels, a percentage of the
+/// width or height, or an expression of the properties of the image; the latter two are only
+/// known when the operation is applied.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dimension {
+    Pixels(u32),
+    Percentage(f32),
+    Expression(Expression),
+}
+
+impl Dimension {
+    /// Parses a natural number of pixels, e.g. `640`, a percentage, e.g. `50%` or `12.5%`, or an
+    /// expression, e.g. `$height/2`.
+    pub fn try_from_str(val: &str) -> Result<Dimension, SicImageEngineError> {
+        let invalid = || SicImageEngineError::InvalidDimension(val.to_string());
+
+        match val.strip_suffix('%') {
+            Some(percentage) if percentage.bytes().all(|b| b.is_ascii_digit() || b == b'.') => {
+                percentage
+                    .parse::<f32>()
+                    .map(Dimension::Percentage)
+                    .map_err(|_| invalid())
+            }
+            Some(_) => Err(invalid()),
+            None if val.bytes().all(|b| b.is_ascii_digit()) => val
+                .parse::<u32>()
+                .map(Dimension::Pixels)
+                .map_err(|_| invalid()),
+            None => Expression::try_from_str(val).map(Dimension::Expression),
+        }
+    }
+
+    /// The number of pixels, given the width or height of the image against which a percentage
+    /// is resolved, and the properties of the image against which an expression is evaluated;
+    /// rounded to the nearest pixel.
+    pub fn resolve(
+        &self,
+        length: u32,
+        properties: &ImageProperties,
+    ) -> Result<u32, SicImageEngineError> {
+        let pixels = match self {
+            Dimension::Pixels(pixels) => return Ok(*pixels),
+            Dimension::Percentage(percentage) => f64::from(length) * f64::from(*percentage) / 100.0,
+            Dimension::Expression(expression) => expression.evaluate(properties),
+        }
+        .round();
+
+        if pixels >= 0.0 && pixels <= f64::from(u32::MAX) {
+            Ok(pixels as u32)
+        } else {
+            Err(SicImageEngineError::DimensionOutOfRange(
+                self.to_string(),
+                pixels,
+            ))
+        }
+    }
+}
+
+impl std::fmt::Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dimension::Pixels(pixels) => write!(f, "{}", pixels),
+            Dimension::Percentage(percentage) => write!(f, "{}%", percentage),
+            Dimension::Expression(expression) => f.write_str(expression.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    const PROPERTIES: ImageProperties = ImageProperties {
+        width: 100,
+        height: 50,
+        frames: 1,
+    };
+
+    fn expression(val: &str) -> Dimension {
+        Dimension::Expression(Expression::try_from_str(val).unwrap())
+    }
+
+    #[pm(
+        input = { "640", "0", "50%", "12.5%", "0%", "150%", "$height/2", "1.5" },
+        expected = {
+            Dimension::Pixels(640),
+            Dimension::Pixels(0),
+            Dimension::Percentage(50.0),
+            Dimension::Percentage(12.5),
+            Dimension::Percentage(0.0),
+            Dimension::Percentage(150.0),
+            expression("$height/2"),
+            expression("1.5"),
+        }
+    )]
+    fn from_str(input: &str, expected: Dimension) {
+        assert_eq!(Dimension::try_from_str(input).unwrap(), expected);
+    }
+
+    #[pm(input = { "-1", "-5%", "+5%", "5 %", "%", ".%", "5%%", "inf%", "a", "$height%", "" })]
+    fn invalid(input: &str) {
+        assert!(Dimension::try_from_str(input).is_err());
+    }
+
+    #[pm(
+        dimension = {
+            Dimension::Pixels(10),
+            Dimension::Percentage(50.0),
+            Dimension::Percentage(12.5),
+            Dimension::Percentage(200.0),
+            expression("$height/2"),
+            expression("$width-0.4"),
+        },
+        expected = { 10, 50, 13, 200, 25, 100 }
+    )]
+    fn resolve(dimension: Dimension, expected: u32) {
+        assert_eq!(dimension.resolve(100, &PROPERTIES).unwrap(), expected);
+    }
+
+    #[pm(input = { "$width-$height*3", "$width/0" })]
+    fn resolve_out_of_range(input: &str) {
+        assert!(expression(input).resolve(100, &PROPERTIES).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+/// The color in which corners are marked, if no color is given.
+pub const DRAW_CORNERS_DEFAULT_COLOR: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+/// The threshold by which corners are detected, and the color in which they are marked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawCornersInputs {
+    threshold: u8,
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+}
+
+impl DrawCornersInputs {
+    pub fn new(threshold: u8, color: Rgba<u8>) -> Self {
+        Self { threshold, color }
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+}
@@ -0,0 +1,178 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+use std::convert::TryFrom;
+
+/// How a shape is drawn: filled, or only its outline, with a width in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShapeStyle {
+    Filled,
+    Stroke(u32),
+}
+
+/// Shapes are outlined with a width of 1 pixel, if no style is given.
+pub const SHAPE_DEFAULT_STYLE: ShapeStyle = ShapeStyle::Stroke(1);
+
+/// A rectangle with its top left corner at `(x, y)`, and its color and style.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawRectInputs {
+    rect: (u32, u32, u32, u32),
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+    style: ShapeStyle,
+}
+
+impl DrawRectInputs {
+    /// The rectangle is given as `(x, y, width, height)`.
+    pub fn new(rect: (u32, u32, u32, u32), color: Rgba<u8>, style: ShapeStyle) -> Self {
+        Self { rect, color, style }
+    }
+
+    pub fn rect(&self) -> (u32, u32, u32, u32) {
+        self.rect
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+
+    pub fn style(&self) -> ShapeStyle {
+        self.style
+    }
+}
+
+/// A line segment from one point to another, and its color and width in pixels. Used to draw
+/// lines and arrows; an arrow points at the second point.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawLineInputs {
+    from: (u32, u32),
+    to: (u32, u32),
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+    width: u32,
+}
+
+impl DrawLineInputs {
+    pub fn new(from: (u32, u32), to: (u32, u32), color: Rgba<u8>, width: u32) -> Self {
+        Self {
+            from,
+            to,
+            color,
+            width,
+        }
+    }
+
+    pub fn from(&self) -> (u32, u32) {
+        self.from
+    }
+
+    pub fn to(&self) -> (u32, u32) {
+        self.to
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+}
+
+/// An axis aligned ellipse around a center point, with a horizontal and vertical radius, and its
+/// color and style. A circle is an ellipse with equal radii.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawEllipseInputs {
+    center: (u32, u32),
+    radii: (u32, u32),
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+    style: ShapeStyle,
+}
+
+impl DrawEllipseInputs {
+    pub fn new(center: (u32, u32), radii: (u32, u32), color: Rgba<u8>, style: ShapeStyle) -> Self {
+        Self {
+            center,
+            radii,
+            color,
+            style,
+        }
+    }
+
+    pub fn center(&self) -> (u32, u32) {
+        self.center
+    }
+
+    pub fn radii(&self) -> (u32, u32) {
+        self.radii
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+
+    pub fn style(&self) -> ShapeStyle {
+        self.style
+    }
+}
+
+/// A closed polygon through three or more points, and its color and style.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "DrawPolygonFields")]
+pub struct DrawPolygonInputs {
+    points: Vec<(u32, u32)>,
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+    style: ShapeStyle,
+}
+
+/// The fields of polygon inputs, which are checked by `DrawPolygonInputs::new` when they're
+/// deserialized.
+#[derive(Deserialize)]
+struct DrawPolygonFields {
+    points: Vec<(u32, u32)>,
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+    style: ShapeStyle,
+}
+
+impl TryFrom<DrawPolygonFields> for DrawPolygonInputs {
+    type Error = SicImageEngineError;
+
+    fn try_from(fields: DrawPolygonFields) -> Result<Self, Self::Error> {
+        DrawPolygonInputs::new(fields.points, fields.color, fields.style)
+    }
+}
+
+impl DrawPolygonInputs {
+    /// The last point is connected to the first point, so it should not be repeated.
+    pub fn new(
+        points: Vec<(u32, u32)>,
+        color: Rgba<u8>,
+        style: ShapeStyle,
+    ) -> Result<Self, SicImageEngineError> {
+        if points.len() < 3 {
+            return Err(SicImageEngineError::DrawPolygonTooFewPoints(points.len()));
+        }
+
+        Ok(Self {
+            points,
+            color,
+            style,
+        })
+    }
+
+    pub fn points(&self) -> &[(u32, u32)] {
+        &self.points
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+
+    pub fn style(&self) -> ShapeStyle {
+        self.style
+    }
+}
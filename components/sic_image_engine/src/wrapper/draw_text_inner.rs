@@ -1,8 +1,9 @@
 #![cfg(feature = "imageproc-ops")]
 
 use crate::wrapper::font_options::FontOptions;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DrawTextInner {
     text: String,
     coord: (u32, u32),
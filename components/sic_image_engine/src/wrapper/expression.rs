@@ -0,0 +1,372 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The properties of the image which expressions can refer to, as they are when an operation is
+/// applied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageProperties {
+    pub width: u32,
+    pub height: u32,
+    /// The number of frames of the input image; 1 for images which are not animated.
+    pub frames: usize,
+}
+
+/// A property of the image, written as `$width`, `$height`, `$aspect` (the width divided by the
+/// height) or `$frames`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variable {
+    Width,
+    Height,
+    Aspect,
+    Frames,
+}
+
+impl Variable {
+    pub fn try_from_str(val: &str) -> Result<Variable, SicImageEngineError> {
+        match val.to_ascii_lowercase().as_str() {
+            "$width" => Ok(Variable::Width),
+            "$height" => Ok(Variable::Height),
+            "$aspect" => Ok(Variable::Aspect),
+            "$frames" => Ok(Variable::Frames),
+            _ => Err(SicImageEngineError::UnknownVariable(val.to_string())),
+        }
+    }
+
+    fn value(self, properties: &ImageProperties) -> f64 {
+        match self {
+            Variable::Width => f64::from(properties.width),
+            Variable::Height => f64::from(properties.height),
+            Variable::Aspect => f64::from(properties.width) / f64::from(properties.height),
+            Variable::Frames => properties.frames as f64,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Min,
+    Max,
+}
+
+impl Operator {
+    fn apply(self, lhs: f64, rhs: f64) -> f64 {
+        match self {
+            Operator::Add => lhs + rhs,
+            Operator::Subtract => lhs - rhs,
+            Operator::Multiply => lhs * rhs,
+            Operator::Divide => lhs / rhs,
+            Operator::Min => lhs.min(rhs),
+            Operator::Max => lhs.max(rhs),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Number(f64),
+    Variable(Variable),
+    Apply(Operator, Box<Node>, Box<Node>),
+}
+
+impl Node {
+    fn evaluate(&self, properties: &ImageProperties) -> f64 {
+        match self {
+            Node::Number(number) => *number,
+            Node::Variable(variable) => variable.value(properties),
+            Node::Apply(operator, lhs, rhs) => {
+                operator.apply(lhs.evaluate(properties), rhs.evaluate(properties))
+            }
+        }
+    }
+}
+
+/// An arithmetic expression of numbers and properties of the image, e.g. `($width-10)/2` or
+/// `min($width, $height)`. `*` and `/` take precedence over `+` and `-`; otherwise the expression
+/// is evaluated from left to right.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Expression {
+    text: String,
+    root: Node,
+}
+
+/// Expressions are equal if they compute the same thing in the same way, however they were
+/// written, e.g. `$WIDTH/2` and `( $width / 2 )`.
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+    }
+}
+
+impl Expression {
+    /// Parses an expression of numbers, variables, the operators `+`, `-`, `*` and `/`,
+    /// parentheses and the functions `min(a, b)` and `max(a, b)`, e.g. `max($width/2, 100)`.
+    pub fn try_from_str(val: &str) -> Result<Expression, SicImageEngineError> {
+        let mut parser = Parser {
+            text: val,
+            rest: val,
+        };
+        let root = parser.sum()?;
+
+        if parser.skip_whitespace().is_empty() {
+            Ok(Expression {
+                text: val.trim().to_string(),
+                root,
+            })
+        } else {
+            Err(parser.invalid())
+        }
+    }
+
+    pub fn evaluate(&self, properties: &ImageProperties) -> f64 {
+        self.root.evaluate(properties)
+    }
+
+    /// The expression as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+/// A recursive descent parser of expressions, which consumes the text from the front.
+impl TryFrom<String> for Expression {
+    type Error = SicImageEngineError;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        Expression::try_from_str(&val)
+    }
+}
+
+impl From<Expression> for String {
+    fn from(expression: Expression) -> Self {
+        expression.text
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn invalid(&self) -> SicImageEngineError {
+        SicImageEngineError::InvalidExpression(self.text.to_string())
+    }
+
+    fn skip_whitespace(&mut self) -> &'a str {
+        self.rest = self.rest.trim_start();
+        self.rest
+    }
+
+    /// Consumes the given token if it's next, ignoring case.
+    fn eat(&mut self, token: &str) -> bool {
+        let rest = self.skip_whitespace();
+
+        match rest.get(..token.len()) {
+            Some(next) if next.eq_ignore_ascii_case(token) => {
+                self.rest = &rest[token.len()..];
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), SicImageEngineError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.invalid())
+        }
+    }
+
+    // sum = product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<Node, SicImageEngineError> {
+        let mut node = self.product()?;
+
+        loop {
+            let operator = if self.eat("+") {
+                Operator::Add
+            } else if self.eat("-") {
+                Operator::Subtract
+            } else {
+                return Ok(node);
+            };
+
+            node = Node::Apply(operator, Box::new(node), Box::new(self.product()?));
+        }
+    }
+
+    // product = operand (('*' | '/') operand)*
+    fn product(&mut self) -> Result<Node, SicImageEngineError> {
+        let mut node = self.operand()?;
+
+        loop {
+            let operator = if self.eat("*") {
+                Operator::Multiply
+            } else if self.eat("/") {
+                Operator::Divide
+            } else {
+                return Ok(node);
+            };
+
+            node = Node::Apply(operator, Box::new(node), Box::new(self.operand()?));
+        }
+    }
+
+    // operand = number | variable | '(' sum ')' | ('min' | 'max') '(' sum ',' sum ')'
+    fn operand(&mut self) -> Result<Node, SicImageEngineError> {
+        if self.eat("(") {
+            let node = self.sum()?;
+            self.expect(")")?;
+
+            return Ok(node);
+        }
+
+        for &(name, operator) in &[("min", Operator::Min), ("max", Operator::Max)] {
+            if self.eat(name) {
+                self.expect("(")?;
+                let lhs = self.sum()?;
+                self.expect(",")?;
+                let rhs = self.sum()?;
+                self.expect(")")?;
+
+                return Ok(Node::Apply(operator, Box::new(lhs), Box::new(rhs)));
+            }
+        }
+
+        let rest = self.skip_whitespace();
+        let end = rest
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| !(c.is_ascii_alphanumeric() || c == '.'))
+            .map_or(rest.len(), |(i, _)| i);
+        let token = &rest[..end];
+        self.rest = &rest[end..];
+
+        if token.starts_with('$') {
+            Variable::try_from_str(token).map(Node::Variable)
+        } else if !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+            token
+                .parse::<f64>()
+                .map(Node::Number)
+                .map_err(|_| self.invalid())
+        } else {
+            Err(self.invalid())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    const PROPERTIES: ImageProperties = ImageProperties {
+        width: 640,
+        height: 480,
+        frames: 8,
+    };
+
+    #[pm(
+        input = {
+            "$width",
+            "$HEIGHT",
+            "$aspect",
+            "$frames",
+            "$height/2",
+            "$width-10",
+            "$width*0.5+10",
+            "10+$width*0.5",
+            "$width-$height/4*2",
+            "100/$aspect",
+            "7",
+            "$width-100-100",
+            "($width-40)/2",
+            "( $width - 40 ) / 2",
+            "(($width))",
+            "min($width, $height)",
+            "MAX($width/4,200)",
+            "max(min($width, 100), 50)*2",
+        },
+        expected = {
+            640.0,
+            480.0,
+            640.0 / 480.0,
+            8.0,
+            240.0,
+            630.0,
+            330.0,
+            330.0,
+            400.0,
+            75.0,
+            7.0,
+            440.0,
+            300.0,
+            300.0,
+            640.0,
+            480.0,
+            200.0,
+            200.0,
+        }
+    )]
+    fn evaluate(input: &str, expected: f64) {
+        let expression = Expression::try_from_str(input).unwrap();
+
+        assert!((expression.evaluate(&PROPERTIES) - expected).abs() < 1e-9);
+    }
+
+    #[pm(input = {
+        "",
+        "$",
+        "$width/",
+        "/2",
+        "$width//2",
+        "$width*-1",
+        "2$width",
+        "(1+2",
+        "1+2)",
+        "1..5",
+        "min(1)",
+        "min(1, 2, 3)",
+        "avg(1, 2)",
+        "1 2",
+    })]
+    fn invalid(input: &str) {
+        assert!(Expression::try_from_str(input).is_err());
+    }
+
+    #[test]
+    fn unknown_variable() {
+        assert!(matches!(
+            Expression::try_from_str("($depth+1)"),
+            Err(SicImageEngineError::UnknownVariable(variable)) if variable == "$depth"
+        ));
+    }
+
+    #[test]
+    fn equal_however_written() {
+        assert_eq!(
+            Expression::try_from_str("$WIDTH/2").unwrap(),
+            Expression::try_from_str("( $width / 2 )").unwrap()
+        );
+        assert_ne!(
+            Expression::try_from_str("$width/2").unwrap(),
+            Expression::try_from_str("$width*0.5").unwrap()
+        );
+    }
+
+    #[test]
+    fn as_str() {
+        assert_eq!(
+            Expression::try_from_str("$height/2").unwrap().as_str(),
+            "$height/2"
+        );
+    }
+}
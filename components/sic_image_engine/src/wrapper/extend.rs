@@ -0,0 +1,45 @@
+use crate::wrapper::gravity::Gravity;
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+/// The gravity by which the image is placed on the larger canvas, if none is given, neither to
+/// extend itself nor by the gravity modifier.
+pub const EXTEND_DEFAULT_GRAVITY: Gravity = Gravity::Center;
+
+/// The color of the canvas, if none is given, neither to extend itself nor by the background
+/// modifier: fully transparent.
+pub const EXTEND_DEFAULT_COLOR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+/// The size of the larger canvas on which the image is placed, where on the canvas it's placed,
+/// and the color of the canvas.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendInputs {
+    size: (u32, u32),
+    gravity: Option<Gravity>,
+    #[serde(default, with = "crate::serialization::option_rgba")]
+    color: Option<Rgba<u8>>,
+}
+
+impl ExtendInputs {
+    pub fn new(size: (u32, u32), gravity: Option<Gravity>, color: Option<Rgba<u8>>) -> Self {
+        Self {
+            size,
+            gravity,
+            color,
+        }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// The gravity given to extend itself, which takes precedence over the gravity modifier.
+    pub fn gravity(&self) -> Option<Gravity> {
+        self.gravity
+    }
+
+    /// The color given to extend itself, which takes precedence over the background modifier.
+    pub fn color(&self) -> Option<Rgba<u8>> {
+        self.color
+    }
+}
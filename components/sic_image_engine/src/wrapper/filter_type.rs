@@ -1,30 +1,80 @@
 use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
 use sic_core::image::imageops::FilterType;
+use std::convert::TryFrom;
 use std::fmt::{Debug, Formatter};
 use std::hash::Hash;
 
+/// Sampling filters which are not provided by the image crate, and are implemented by the
+/// image engine instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CustomFilterType {
+    /// Takes the unweighted mean of the source pixels covered by a target pixel.
+    Box,
+    /// Cubic interpolation without overshoot; smoother than `triangle`, sharper than `gaussian`.
+    Hermite,
+    /// Mitchell-Netravali cubic (B = C = 1/3), a well balanced trade-off between blurring and
+    /// ringing.
+    Mitchell,
+}
+
 #[derive(Clone, Copy)]
+enum Filter {
+    Image(FilterType),
+    Custom(CustomFilterType),
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct FilterTypeWrap {
-    inner: FilterType,
+    inner: Filter,
 }
 
 impl FilterTypeWrap {
     pub fn new(with: FilterType) -> Self {
-        Self { inner: with }
+        Self {
+            inner: Filter::Image(with),
+        }
+    }
+
+    pub fn custom(with: CustomFilterType) -> Self {
+        Self {
+            inner: Filter::Custom(with),
+        }
+    }
+
+    /// The sampling filter of the image crate, if this is not a custom filter.
+    pub fn image_filter(&self) -> Option<FilterType> {
+        match self.inner {
+            Filter::Image(filter) => Some(filter),
+            Filter::Custom(_) => None,
+        }
+    }
+
+    /// The sampling filter implemented by the image engine, if this is a custom filter.
+    pub fn custom_filter(&self) -> Option<CustomFilterType> {
+        match self.inner {
+            Filter::Image(_) => None,
+            Filter::Custom(filter) => Some(filter),
+        }
     }
 }
 
 impl Default for FilterTypeWrap {
     fn default() -> Self {
-        Self {
-            inner: FilterType::Lanczos3,
-        }
+        Self::new(FilterType::Lanczos3)
     }
 }
 
 impl PartialEq<FilterTypeWrap> for FilterTypeWrap {
     fn eq(&self, other: &FilterTypeWrap) -> bool {
-        std::mem::discriminant(&self.inner) == std::mem::discriminant(&other.inner)
+        match (self.inner, other.inner) {
+            (Filter::Image(lhs), Filter::Image(rhs)) => {
+                std::mem::discriminant(&lhs) == std::mem::discriminant(&rhs)
+            }
+            (Filter::Custom(lhs), Filter::Custom(rhs)) => lhs == rhs,
+            _ => false,
+        }
     }
 }
 
@@ -32,36 +82,39 @@ impl Eq for FilterTypeWrap {}
 
 impl Hash for FilterTypeWrap {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        std::mem::discriminant(&self.inner).hash(state)
+        match self.inner {
+            Filter::Image(filter) => std::mem::discriminant(&filter).hash(state),
+            Filter::Custom(filter) => filter.hash(state),
+        }
     }
 }
 
 impl Debug for FilterTypeWrap {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
         let msg = match self.inner {
-            FilterType::CatmullRom => "image::FilterType::CatmullRom (Wrapper)",
-            FilterType::Gaussian => "image::FilterType::Gaussian (Wrapper)",
-            FilterType::Lanczos3 => "image::FilterType::Lanczos3 (Wrapper)",
-            FilterType::Nearest => "image::FilterType::Nearest (Wrapper)",
-            FilterType::Triangle => "image::FilterType::Triangle (Wrapper)",
+            Filter::Image(FilterType::CatmullRom) => "image::FilterType::CatmullRom (Wrapper)",
+            Filter::Image(FilterType::Gaussian) => "image::FilterType::Gaussian (Wrapper)",
+            Filter::Image(FilterType::Lanczos3) => "image::FilterType::Lanczos3 (Wrapper)",
+            Filter::Image(FilterType::Nearest) => "image::FilterType::Nearest (Wrapper)",
+            Filter::Image(FilterType::Triangle) => "image::FilterType::Triangle (Wrapper)",
+            Filter::Custom(CustomFilterType::Box) => "CustomFilterType::Box (Wrapper)",
+            Filter::Custom(CustomFilterType::Hermite) => "CustomFilterType::Hermite (Wrapper)",
+            Filter::Custom(CustomFilterType::Mitchell) => "CustomFilterType::Mitchell (Wrapper)",
         };
 
         f.write_str(msg)
     }
 }
 
-impl From<FilterTypeWrap> for FilterType {
-    fn from(wrap: FilterTypeWrap) -> Self {
-        wrap.inner
-    }
-}
-
 impl FilterTypeWrap {
     pub fn try_from_str(val: &str) -> Result<FilterTypeWrap, SicImageEngineError> {
         match val.to_lowercase().as_str() {
+            "box" => Ok(FilterTypeWrap::custom(CustomFilterType::Box)),
             "catmullrom" | "cubic" => Ok(FilterTypeWrap::new(FilterType::CatmullRom)),
             "gaussian" => Ok(FilterTypeWrap::new(FilterType::Gaussian)),
+            "hermite" => Ok(FilterTypeWrap::custom(CustomFilterType::Hermite)),
             "lanczos3" => Ok(FilterTypeWrap::new(FilterType::Lanczos3)),
+            "mitchell" => Ok(FilterTypeWrap::custom(CustomFilterType::Mitchell)),
             "nearest" => Ok(FilterTypeWrap::new(FilterType::Nearest)),
             "triangle" => Ok(FilterTypeWrap::new(FilterType::Triangle)),
             fail => Err(SicImageEngineError::UnknownFilterType(fail.to_string())),
@@ -69,6 +122,32 @@ impl FilterTypeWrap {
     }
 }
 
+impl TryFrom<String> for FilterTypeWrap {
+    type Error = SicImageEngineError;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        FilterTypeWrap::try_from_str(&val)
+    }
+}
+
+/// The name by which `try_from_str` recognizes the filter.
+impl From<FilterTypeWrap> for String {
+    fn from(filter: FilterTypeWrap) -> Self {
+        let name = match filter.inner {
+            Filter::Image(FilterType::CatmullRom) => "catmullrom",
+            Filter::Image(FilterType::Gaussian) => "gaussian",
+            Filter::Image(FilterType::Lanczos3) => "lanczos3",
+            Filter::Image(FilterType::Nearest) => "nearest",
+            Filter::Image(FilterType::Triangle) => "triangle",
+            Filter::Custom(CustomFilterType::Box) => "box",
+            Filter::Custom(CustomFilterType::Hermite) => "hermite",
+            Filter::Custom(CustomFilterType::Mitchell) => "mitchell",
+        };
+
+        name.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,4 +167,38 @@ mod tests {
 
         assert!(wrapped_cat.ne(&wrapped_gauss));
     }
+
+    #[test]
+    fn partial_eq_custom() {
+        let wrapped_mitchell1 = FilterTypeWrap::custom(CustomFilterType::Mitchell);
+        let wrapped_mitchell2 = FilterTypeWrap::custom(CustomFilterType::Mitchell);
+
+        assert!(wrapped_mitchell1.eq(&wrapped_mitchell2));
+    }
+
+    #[test]
+    fn partial_ne_custom() {
+        let wrapped_box = FilterTypeWrap::custom(CustomFilterType::Box);
+        let wrapped_hermite = FilterTypeWrap::custom(CustomFilterType::Hermite);
+        let wrapped_nearest = FilterTypeWrap::new(FilterType::Nearest);
+
+        assert!(wrapped_box.ne(&wrapped_hermite));
+        assert!(wrapped_box.ne(&wrapped_nearest));
+    }
+
+    #[test]
+    fn from_str_custom() {
+        assert_eq!(
+            FilterTypeWrap::try_from_str("Mitchell").unwrap(),
+            FilterTypeWrap::custom(CustomFilterType::Mitchell)
+        );
+        assert_eq!(
+            FilterTypeWrap::try_from_str("box").unwrap(),
+            FilterTypeWrap::custom(CustomFilterType::Box)
+        );
+        assert_eq!(
+            FilterTypeWrap::try_from_str("hermite").unwrap(),
+            FilterTypeWrap::custom(CustomFilterType::Hermite)
+        );
+    }
 }
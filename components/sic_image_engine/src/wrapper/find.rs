@@ -0,0 +1,26 @@
+use crate::wrapper::image_path::ImageFromPath;
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+/// The template to find within the image, and optionally the color in which the area where it was
+/// found is outlined.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FindInputs {
+    template: ImageFromPath,
+    #[serde(default, with = "crate::serialization::option_rgba")]
+    outline: Option<Rgba<u8>>,
+}
+
+impl FindInputs {
+    pub fn new(template: ImageFromPath, outline: Option<Rgba<u8>>) -> Self {
+        Self { template, outline }
+    }
+
+    pub fn template(&self) -> &ImageFromPath {
+        &self.template
+    }
+
+    pub fn outline(&self) -> Option<Rgba<u8>> {
+        self.outline
+    }
+}
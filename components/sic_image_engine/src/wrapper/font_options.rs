@@ -1,6 +1,8 @@
+use crate::errors::SicImageEngineError;
 use rusttype::Scale;
+use serde::{Deserialize, Serialize};
 use sic_core::image::Rgba;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 type FontColor = Rgba<u8>;
 
@@ -9,17 +11,139 @@ pub enum FontScale {
     Scaling(f32, f32),
 }
 
-#[derive(Debug, Clone)]
+/// Where a font is loaded from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FontSource {
+    /// A TrueType or OpenType font file.
+    File(PathBuf),
+    /// The name of a font family which is installed on the system, e.g. `Lato`.
+    Family(String),
+}
+
+impl FontSource {
+    const FONT_FILE_EXTENSIONS: [&'static str; 4] = ["otc", "otf", "ttc", "ttf"];
+
+    /// A name is taken to be a font file if it has a font file extension or contains a path
+    /// separator; otherwise it is taken to be the name of a font family.
+    pub fn from_name(name: &str) -> Self {
+        let path = Path::new(name);
+
+        let has_font_extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| {
+                Self::FONT_FILE_EXTENSIONS
+                    .iter()
+                    .any(|known| ext.eq_ignore_ascii_case(known))
+            })
+            .unwrap_or(false);
+
+        if has_font_extension || name.contains('/') || name.contains('\\') {
+            FontSource::File(path.to_path_buf())
+        } else {
+            FontSource::Family(name.to_string())
+        }
+    }
+}
+
+impl From<PathBuf> for FontSource {
+    fn from(path: PathBuf) -> Self {
+        FontSource::File(path)
+    }
+}
+
+/// Selects a face of a font family.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FontStyle {
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl FontStyle {
+    /// Parses a style, which consists of the words `bold` and/or `italic`, or is `regular`,
+    /// e.g. `bold italic`.
+    pub fn try_from_str(val: &str) -> Result<FontStyle, SicImageEngineError> {
+        let mut style = FontStyle::default();
+
+        for word in val.split(|c: char| c.is_whitespace() || c == '-') {
+            match word.to_lowercase().as_str() {
+                "" | "regular" | "normal" => {}
+                "bold" => style.bold = true,
+                "italic" => style.italic = true,
+                _ => return Err(SicImageEngineError::UnknownFontStyle(val.to_string())),
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+/// How the lines of a text are aligned horizontally: within the width the text is wrapped at, or
+/// otherwise relative to its widest line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    pub fn try_from_str(val: &str) -> Result<TextAlign, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "left" => Ok(TextAlign::Left),
+            "center" => Ok(TextAlign::Center),
+            "right" => Ok(TextAlign::Right),
+            _ => Err(SicImageEngineError::UnknownTextAlign(val.to_string())),
+        }
+    }
+}
+
+/// An outline drawn around each glyph.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TextOutline {
+    /// Width of the outline in pixels.
+    pub width: f32,
+    #[serde(with = "crate::serialization::rgba")]
+    pub color: FontColor,
+}
+
+/// A shadow drawn below the text (and its outline), offset by a number of pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TextShadow {
+    pub offset: (i32, i32),
+    #[serde(with = "crate::serialization::rgba")]
+    pub color: FontColor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FontOptions {
-    pub font_path: PathBuf,
+    pub font: FontSource,
+    pub style: FontStyle,
+    #[serde(with = "crate::serialization::rgba")]
     pub color: FontColor,
+    #[serde(with = "crate::serialization::scale")]
     pub scale: Scale,
+    pub outline: Option<TextOutline>,
+    pub shadow: Option<TextShadow>,
+    /// Additional space between glyphs, in pixels.
+    pub letter_spacing: f32,
+    /// Additional space between lines, in pixels.
+    pub line_spacing: f32,
+    /// Clockwise rotation of the text around its center, in degrees.
+    pub angle: f32,
+    /// Width in pixels at which lines are wrapped between words, if any.
+    pub wrap_width: Option<f32>,
+    pub align: TextAlign,
 }
 
 impl FontOptions {
-    pub fn new(font_path: PathBuf, color: FontColor, scale: FontScale) -> Self {
+    pub fn new<F: Into<FontSource>>(font: F, color: FontColor, scale: FontScale) -> Self {
         Self {
-            font_path,
+            font: font.into(),
+            style: FontStyle::default(),
             color,
             scale: match scale {
                 FontScale::Uniform(value) => Scale::uniform(value),
@@ -28,13 +152,115 @@ impl FontOptions {
                     y: vertical,
                 },
             },
+            outline: None,
+            shadow: None,
+            letter_spacing: 0.0,
+            line_spacing: 0.0,
+            angle: 0.0,
+            wrap_width: None,
+            align: TextAlign::default(),
         }
     }
+
+    pub fn with_style(mut self, style: FontStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_outline(mut self, outline: TextOutline) -> Self {
+        self.outline = Some(outline);
+        self
+    }
+
+    pub fn with_shadow(mut self, shadow: TextShadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    pub fn with_spacing(mut self, letter_spacing: f32, line_spacing: f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    pub fn with_angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    pub fn with_wrap_width(mut self, width: f32) -> Self {
+        self.wrap_width = Some(width);
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
 }
 
 impl PartialEq for FontOptions {
     fn eq(&self, other: &Self) -> bool {
-        // Equality for these font options is defined by the font, specifically its path.
-        self.font_path.eq(&other.font_path)
+        // Equality for these font options is defined by the font, specifically its source and style,
+        // and by the decorations of the text.
+        self.font.eq(&other.font)
+            && self.style.eq(&other.style)
+            && self.outline.eq(&other.outline)
+            && self.shadow.eq(&other.shadow)
+            && self.letter_spacing.eq(&other.letter_spacing)
+            && self.line_spacing.eq(&other.line_spacing)
+            && self.angle.eq(&other.angle)
+            && self.wrap_width.eq(&other.wrap_width)
+            && self.align.eq(&other.align)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        name = { "Lato", "DejaVu Sans", "resources/font/Lato-Regular.ttf", "Lato.OTF", "fonts/Lato" },
+        expected = {
+            FontSource::Family("Lato".to_string()),
+            FontSource::Family("DejaVu Sans".to_string()),
+            FontSource::File(PathBuf::from("resources/font/Lato-Regular.ttf")),
+            FontSource::File(PathBuf::from("Lato.OTF")),
+            FontSource::File(PathBuf::from("fonts/Lato")),
+        }
+    )]
+    fn font_source_from_name(name: &str, expected: FontSource) {
+        assert_eq!(FontSource::from_name(name), expected);
+    }
+
+    #[pm(
+        input = { "", "regular", "bold", "Italic", "bold italic", "bold-italic" },
+        expected = { (false, false), (false, false), (true, false), (false, true), (true, true), (true, true) }
+    )]
+    fn font_style(input: &str, expected: (bool, bool)) {
+        let style = FontStyle::try_from_str(input).unwrap();
+
+        assert_eq!((style.bold, style.italic), expected);
+    }
+
+    #[test]
+    fn font_style_unknown() {
+        assert!(FontStyle::try_from_str("bold condensed").is_err());
+    }
+
+    #[pm(
+        input = { "left", "Center", "RIGHT" },
+        expected = { TextAlign::Left, TextAlign::Center, TextAlign::Right }
+    )]
+    fn text_align(input: &str, expected: TextAlign) {
+        assert_eq!(TextAlign::try_from_str(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn text_align_unknown() {
+        assert!(TextAlign::try_from_str("justify").is_err());
     }
 }
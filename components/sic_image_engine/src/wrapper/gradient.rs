@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColorStop {
+    position: f32,
+    #[serde(with = "crate::serialization::rgba")]
+    color: Rgba<u8>,
+}
+
+impl ColorStop {
+    pub fn new(position: f32, color: Rgba<u8>) -> Self {
+        Self { position, color }
+    }
+
+    /// The position of the stop on the gradient, from 0 (start) to 1 (end).
+    pub fn position(&self) -> f32 {
+        self.position
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        self.color
+    }
+}
+
+/// A linear gradient between two or more color stops.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<ColorStop>", into = "Vec<ColorStop>")]
+pub struct Gradient {
+    stops: Vec<ColorStop>,
+}
+
+impl Gradient {
+    /// Creates a gradient from the given stops. The stops are sorted by their position; stops with
+    /// an equal position keep their order, which allows for hard transitions.
+    pub fn new(mut stops: Vec<ColorStop>) -> Self {
+        stops.sort_by(|lhs, rhs| {
+            lhs.position
+                .partial_cmp(&rhs.position)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Self { stops }
+    }
+
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops
+    }
+
+    /// The color at the given position, interpolated linearly between the surrounding stops.
+    /// Before the first stop the color of the first stop is used, and after the last stop the
+    /// color of the last stop. A gradient without stops is transparent everywhere.
+    pub fn color_at(&self, position: f32) -> Rgba<u8> {
+        let after = self.stops.iter().position(|stop| stop.position > position);
+
+        let (from, to) = match after {
+            None => match self.stops.last() {
+                Some(last) => return last.color,
+                None => return Rgba([0, 0, 0, 0]),
+            },
+            Some(0) => return self.stops[0].color,
+            Some(i) => (&self.stops[i - 1], &self.stops[i]),
+        };
+
+        let t = (position - from.position) / (to.position - from.position);
+        let mut color = [0; 4];
+
+        for (channel, sample) in color.iter_mut().enumerate() {
+            let (lhs, rhs) = (f32::from(from.color[channel]), f32::from(to.color[channel]));
+            *sample = (lhs + (rhs - lhs) * t).round() as u8;
+        }
+
+        Rgba(color)
+    }
+}
+
+/// A gradient is serialized as its stops, which are sorted again when it's deserialized.
+impl From<Vec<ColorStop>> for Gradient {
+    fn from(stops: Vec<ColorStop>) -> Self {
+        Gradient::new(stops)
+    }
+}
+
+impl From<Gradient> for Vec<ColorStop> {
+    fn from(gradient: Gradient) -> Self {
+        gradient.stops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    #[test]
+    fn sorts_stops() {
+        let gradient = Gradient::new(vec![ColorStop::new(1.0, WHITE), ColorStop::new(0.0, BLACK)]);
+
+        assert_eq!(gradient.stops()[0], ColorStop::new(0.0, BLACK));
+    }
+
+    #[test]
+    fn interpolates() {
+        let gradient = Gradient::new(vec![
+            ColorStop::new(0.0, BLACK),
+            ColorStop::new(0.5, RED),
+            ColorStop::new(1.0, WHITE),
+        ]);
+
+        assert_eq!(gradient.color_at(0.0), BLACK);
+        assert_eq!(gradient.color_at(0.25), Rgba([128, 0, 0, 255]));
+        assert_eq!(gradient.color_at(0.5), RED);
+        assert_eq!(gradient.color_at(0.75), Rgba([255, 128, 128, 255]));
+        assert_eq!(gradient.color_at(1.0), WHITE);
+    }
+
+    #[test]
+    fn clamps_outside_stops() {
+        let gradient = Gradient::new(vec![ColorStop::new(0.2, BLACK), ColorStop::new(0.8, WHITE)]);
+
+        assert_eq!(gradient.color_at(0.1), BLACK);
+        assert_eq!(gradient.color_at(0.9), WHITE);
+    }
+
+    #[test]
+    fn hard_transition() {
+        let gradient = Gradient::new(vec![
+            ColorStop::new(0.0, BLACK),
+            ColorStop::new(0.5, BLACK),
+            ColorStop::new(0.5, WHITE),
+            ColorStop::new(1.0, WHITE),
+        ]);
+
+        assert_eq!(gradient.color_at(0.49), BLACK);
+        assert_eq!(gradient.color_at(0.5), WHITE);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(Gradient::new(vec![]).color_at(0.5), Rgba([0, 0, 0, 0]));
+    }
+}
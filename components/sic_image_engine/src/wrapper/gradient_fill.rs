@@ -0,0 +1,81 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+/// The angle of a linear gradient, if none is given: from left to right.
+pub const GRADIENT_DEFAULT_ANGLE: f32 = 0.0;
+
+/// The shape of the gradient drawn by the gradient operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GradientKind {
+    /// Changes color along a straight line across the image.
+    Linear,
+    /// Changes color from the center of the image towards its corners.
+    Radial,
+}
+
+impl GradientKind {
+    pub fn try_from_str(val: &str) -> Result<GradientKind, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "linear" => Ok(GradientKind::Linear),
+            "radial" => Ok(GradientKind::Radial),
+            fail => Err(SicImageEngineError::UnknownGradientKind(fail.to_string())),
+        }
+    }
+}
+
+/// A gradient between two colors, which is drawn over the whole image.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradientFillInputs {
+    kind: GradientKind,
+    #[serde(with = "crate::serialization::rgba_pair")]
+    colors: (Rgba<u8>, Rgba<u8>),
+    angle: f32,
+}
+
+impl GradientFillInputs {
+    pub fn new(kind: GradientKind, colors: (Rgba<u8>, Rgba<u8>), angle: f32) -> Self {
+        Self {
+            kind,
+            colors,
+            angle,
+        }
+    }
+
+    pub fn kind(&self) -> GradientKind {
+        self.kind
+    }
+
+    /// The color at the start of the gradient, and the color at its end.
+    pub fn colors(&self) -> (Rgba<u8>, Rgba<u8>) {
+        self.colors
+    }
+
+    /// The direction of a linear gradient, in degrees clockwise, where 0 runs from left to right
+    /// and 90 from top to bottom. Not used by radial gradients.
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        name = { "linear", "RADIAL" },
+        expected = { GradientKind::Linear, GradientKind::Radial }
+    )]
+    fn from_str(name: &str, expected: GradientKind) {
+        assert_eq!(GradientKind::try_from_str(name).unwrap(), expected);
+    }
+
+    #[pm(name = { "", "conic", "circle" })]
+    fn unknown(name: &str) {
+        assert!(GradientKind::try_from_str(name).is_err());
+    }
+}
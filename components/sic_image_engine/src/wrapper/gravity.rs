@@ -0,0 +1,199 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// The side or corner of an image towards which another item is placed. Besides by their names,
+/// e.g. `top-left`, sides and corners can be given by their compass directions, e.g. `north-west`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Gravity {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Gravity {
+    pub fn try_from_str(val: &str) -> Result<Gravity, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "top-left" | "north-west" => Ok(Gravity::TopLeft),
+            "top" | "north" => Ok(Gravity::Top),
+            "top-right" | "north-east" => Ok(Gravity::TopRight),
+            "left" | "west" => Ok(Gravity::Left),
+            "center" => Ok(Gravity::Center),
+            "right" | "east" => Ok(Gravity::Right),
+            "bottom-left" | "south-west" => Ok(Gravity::BottomLeft),
+            "bottom" | "south" => Ok(Gravity::Bottom),
+            "bottom-right" | "south-east" => Ok(Gravity::BottomRight),
+            fail => Err(SicImageEngineError::UnknownGravity(fail.to_string())),
+        }
+    }
+
+    /// The top left position of an item of size `item` within an area of size `area`, kept `margin`
+    /// pixels away from the edges towards which it gravitates. Items which don't fit are placed
+    /// at the top or left edge instead.
+    pub fn position(self, area: (u32, u32), item: (u32, u32), margin: u32) -> (u32, u32) {
+        let place = |area: u32, item: u32, alignment: Alignment| {
+            let free = area.saturating_sub(item);
+
+            match alignment {
+                Alignment::Start => margin.min(free),
+                Alignment::Middle => free / 2,
+                Alignment::End => free.saturating_sub(margin),
+            }
+        };
+
+        let (horizontal, vertical) = self.alignment();
+
+        (
+            place(area.0, item.0, horizontal),
+            place(area.1, item.1, vertical),
+        )
+    }
+
+    /// The top left position of an item of size `item` within an area of size `area`, which is
+    /// `offset` pixels away from the edges towards which it gravitates; in the middle, the offset
+    /// moves the item right or down instead. Unlike [`Gravity::position`], items are placed where
+    /// they would be even if they don't fit, so the position may lie outside of the area.
+    ///
+    /// With the top left gravity, the position is the offset itself.
+    pub fn offset_position(
+        self,
+        area: (u32, u32),
+        item: (u32, u32),
+        offset: (u32, u32),
+    ) -> (i64, i64) {
+        let place = |area: u32, item: u32, offset: u32, alignment: Alignment| {
+            let (area, item, offset) = (i64::from(area), i64::from(item), i64::from(offset));
+
+            match alignment {
+                Alignment::Start => offset,
+                Alignment::Middle => (area - item).div_euclid(2) + offset,
+                Alignment::End => area - item - offset,
+            }
+        };
+
+        let (horizontal, vertical) = self.alignment();
+
+        (
+            place(area.0, item.0, offset.0, horizontal),
+            place(area.1, item.1, offset.1, vertical),
+        )
+    }
+
+    fn alignment(self) -> (Alignment, Alignment) {
+        use Alignment::*;
+
+        match self {
+            Gravity::TopLeft => (Start, Start),
+            Gravity::Top => (Middle, Start),
+            Gravity::TopRight => (End, Start),
+            Gravity::Left => (Start, Middle),
+            Gravity::Center => (Middle, Middle),
+            Gravity::Right => (End, Middle),
+            Gravity::BottomLeft => (Start, End),
+            Gravity::Bottom => (Middle, End),
+            Gravity::BottomRight => (End, End),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Alignment {
+    Start,
+    Middle,
+    End,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        name = { "top-left", "TOP", "top-right", "left", "center", "right", "bottom-left", "bottom", "Bottom-Right" },
+        expected = {
+            Gravity::TopLeft,
+            Gravity::Top,
+            Gravity::TopRight,
+            Gravity::Left,
+            Gravity::Center,
+            Gravity::Right,
+            Gravity::BottomLeft,
+            Gravity::Bottom,
+            Gravity::BottomRight,
+        }
+    )]
+    fn from_str(name: &str, expected: Gravity) {
+        assert_eq!(Gravity::try_from_str(name).unwrap(), expected);
+    }
+
+    #[pm(
+        name = { "north-west", "NORTH", "north-east", "west", "east", "south-west", "south", "South-East" },
+        expected = {
+            Gravity::TopLeft,
+            Gravity::Top,
+            Gravity::TopRight,
+            Gravity::Left,
+            Gravity::Right,
+            Gravity::BottomLeft,
+            Gravity::Bottom,
+            Gravity::BottomRight,
+        }
+    )]
+    fn from_compass_direction(name: &str, expected: Gravity) {
+        assert_eq!(Gravity::try_from_str(name).unwrap(), expected);
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(Gravity::try_from_str("up").is_err());
+    }
+
+    #[pm(
+        gravity = { Gravity::TopLeft, Gravity::Top, Gravity::Center, Gravity::Right, Gravity::BottomRight },
+        expected = { (5, 5), (40, 5), (40, 20), (75, 20), (75, 35) }
+    )]
+    fn position(gravity: Gravity, expected: (u32, u32)) {
+        assert_eq!(gravity.position((100, 50), (20, 10), 5), expected);
+    }
+
+    #[pm(gravity = { Gravity::TopLeft, Gravity::Center, Gravity::BottomRight })]
+    fn position_item_larger_than_area(gravity: Gravity) {
+        assert_eq!(gravity.position((10, 10), (20, 30), 5), (0, 0));
+    }
+
+    #[test]
+    fn margin_larger_than_free_space() {
+        assert_eq!(Gravity::TopLeft.position((10, 10), (8, 8), 5), (2, 2));
+        assert_eq!(Gravity::BottomRight.position((10, 10), (8, 8), 5), (0, 0));
+    }
+
+    #[pm(
+        gravity = { Gravity::TopLeft, Gravity::Top, Gravity::Center, Gravity::Right, Gravity::BottomRight },
+        expected = { (5, 2), (45, 2), (45, 22), (75, 22), (75, 38) }
+    )]
+    fn offset_position(gravity: Gravity, expected: (i64, i64)) {
+        assert_eq!(
+            gravity.offset_position((100, 50), (20, 10), (5, 2)),
+            expected
+        );
+    }
+
+    #[pm(
+        gravity = { Gravity::TopLeft, Gravity::Center, Gravity::BottomRight },
+        expected = { (1, 1), (-4, -9), (-11, -21) }
+    )]
+    fn offset_position_item_larger_than_area(gravity: Gravity, expected: (i64, i64)) {
+        assert_eq!(
+            gravity.offset_position((10, 10), (20, 30), (1, 1)),
+            expected
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// The histogram which is rendered, if no channels are given.
+pub const HISTOGRAM_DEFAULT_CHANNELS: HistogramChannels = HistogramChannels::Luma;
+
+/// Where the histogram is placed, if no placement is given.
+pub const HISTOGRAM_DEFAULT_PLACEMENT: HistogramPlacement = HistogramPlacement::Append;
+
+/// The histogram(s) which the draw-histogram operation renders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistogramChannels {
+    /// A single histogram of the luminance of the image.
+    Luma,
+    /// A histogram per color channel, drawn on top of each other in red, green and blue.
+    Rgb,
+}
+
+impl HistogramChannels {
+    pub fn try_from_str(val: &str) -> Result<HistogramChannels, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "luma" => Ok(HistogramChannels::Luma),
+            "rgb" => Ok(HistogramChannels::Rgb),
+            fail => Err(SicImageEngineError::UnknownHistogramChannels(
+                fail.to_string(),
+            )),
+        }
+    }
+}
+
+/// Where the draw-histogram operation places the histogram.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistogramPlacement {
+    /// Appends the histogram below the image, which grows its height.
+    Append,
+    /// Draws the histogram on top of the bottom of the image, on a translucent background.
+    Overlay,
+}
+
+impl HistogramPlacement {
+    pub fn try_from_str(val: &str) -> Result<HistogramPlacement, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "append" => Ok(HistogramPlacement::Append),
+            "overlay" => Ok(HistogramPlacement::Overlay),
+            fail => Err(SicImageEngineError::UnknownHistogramPlacement(
+                fail.to_string(),
+            )),
+        }
+    }
+}
+
+/// The histogram(s) to render, and where to place them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawHistogramInputs {
+    channels: HistogramChannels,
+    placement: HistogramPlacement,
+}
+
+impl DrawHistogramInputs {
+    pub fn new(channels: HistogramChannels, placement: HistogramPlacement) -> Self {
+        Self {
+            channels,
+            placement,
+        }
+    }
+
+    pub fn channels(&self) -> HistogramChannels {
+        self.channels
+    }
+
+    pub fn placement(&self) -> HistogramPlacement {
+        self.placement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        name = { "luma", "Luma", "RGB" },
+        expected = { HistogramChannels::Luma, HistogramChannels::Luma, HistogramChannels::Rgb }
+    )]
+    fn channels_from_str(name: &str, expected: HistogramChannels) {
+        assert_eq!(HistogramChannels::try_from_str(name).unwrap(), expected);
+    }
+
+    #[pm(name = { "", "r", "rgba", "luminance" })]
+    fn unknown_channels(name: &str) {
+        assert!(HistogramChannels::try_from_str(name).is_err());
+    }
+
+    #[pm(
+        name = { "append", "OVERLAY" },
+        expected = { HistogramPlacement::Append, HistogramPlacement::Overlay }
+    )]
+    fn placement_from_str(name: &str, expected: HistogramPlacement) {
+        assert_eq!(HistogramPlacement::try_from_str(name).unwrap(), expected);
+    }
+
+    #[pm(name = { "", "below", "over" })]
+    fn unknown_placement(name: &str) {
+        assert!(HistogramPlacement::try_from_str(name).is_err());
+    }
+}
@@ -1,10 +1,12 @@
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
+use sic_core::image::io::Reader;
 use sic_io::load::{file_reader, load_image, ImportConfig};
 
 use crate::errors::SicImageEngineError;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ImageFromPath {
     path: PathBuf,
 }
@@ -14,6 +16,34 @@ impl ImageFromPath {
         Self { path }
     }
 
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Checks that the file holds an image of a supported format, by its header (or otherwise its
+    /// extension), without decoding it.
+    pub fn check(&self) -> Result<(), SicImageEngineError> {
+        let reader = Reader::open(&self.path)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|err| SicImageEngineError::ImageFromPathNotReadable(self.path.clone(), err))?;
+
+        match reader.format() {
+            Some(_) => Ok(()),
+            None => Err(SicImageEngineError::ImageFromPathUnknownFormat(
+                self.path.clone(),
+            )),
+        }
+    }
+
+    /// The dimensions of the image, read from its header, without decoding it.
+    pub(crate) fn dimensions(&self) -> Result<(u32, u32), SicImageEngineError> {
+        Reader::open(&self.path)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|err| SicImageEngineError::ImageFromPathNotReadable(self.path.clone(), err))?
+            .into_dimensions()
+            .map_err(|_err| SicImageEngineError::LoadImageFromPath)
+    }
+
     pub fn open_image(&self) -> Result<sic_core::image::DynamicImage, SicImageEngineError> {
         file_reader(self.path.as_path())
             .and_then(|mut file| load_image(&mut file, &ImportConfig::default()))
@@ -34,4 +64,19 @@ mod tests {
         let actual = image_from_path.open_image().unwrap();
         assert!(image_eq(actual, open_test_image(path)));
     }
+
+    #[test]
+    fn check() {
+        assert!(ImageFromPath::new(in_!("palette_4x4.png").into())
+            .check()
+            .is_ok());
+        assert!(matches!(
+            ImageFromPath::new(in_!("missing.png").into()).check(),
+            Err(SicImageEngineError::ImageFromPathNotReadable(..))
+        ));
+        assert!(matches!(
+            ImageFromPath::new(in_!("lut/invert.cube").into()).check(),
+            Err(SicImageEngineError::ImageFromPathUnknownFormat(_))
+        ));
+    }
 }
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::path::PathBuf;
+
+use crate::errors::SicImageEngineError;
+
+/// A square convolution kernel with an odd size, so it has a center.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "KernelFields")]
+pub struct Kernel {
+    size: u32,
+    // The weights, row by row.
+    values: Vec<f32>,
+}
+
+/// The fields of a kernel, which are checked by `Kernel::new` when a kernel is deserialized.
+#[derive(Deserialize)]
+struct KernelFields {
+    size: u32,
+    values: Vec<f32>,
+}
+
+impl TryFrom<KernelFields> for Kernel {
+    type Error = SicImageEngineError;
+
+    fn try_from(fields: KernelFields) -> Result<Self, Self::Error> {
+        Kernel::new(fields.size, fields.values)
+    }
+}
+
+impl Kernel {
+    /// The largest kernel size we accept; convolving with larger kernels takes very long.
+    const MAX_SIZE: u32 = 31;
+
+    pub fn new(size: u32, values: Vec<f32>) -> Result<Self, SicImageEngineError> {
+        if size & 1 == 0 || size > Self::MAX_SIZE {
+            return Err(SicImageEngineError::ConvolveInvalidKernelSize(size));
+        }
+
+        let expected = (size * size) as usize;
+        if values.len() != expected {
+            return Err(SicImageEngineError::ConvolveKernelValueCount(
+                size,
+                values.len(),
+            ));
+        }
+
+        if !values.iter().all(|value| value.is_finite()) {
+            return Err(SicImageEngineError::ConvolveKernelNotFinite);
+        }
+
+        Ok(Self { size, values })
+    }
+
+    /// Parses a kernel from text, with one row of the kernel per line. The weights of a row are
+    /// separated by whitespace or commas. Empty lines, and lines starting with `#`, are ignored.
+    pub(crate) fn parse(contents: &str) -> Result<Self, SicImageEngineError> {
+        let mut size = None;
+        let mut values = Vec::new();
+
+        for (index, line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |message: &str| {
+                SicImageEngineError::ConvolveKernelParseError(line_number, message.to_string())
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let row = line
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|word| !word.is_empty())
+                .map(|word| word.parse::<f32>().ok().filter(|value| value.is_finite()))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| error("expected a row of numbers"))?;
+
+            let row_size = *size.get_or_insert(row.len());
+            if row.len() != row_size {
+                return Err(error("all rows should have the same number of weights"));
+            }
+
+            values.extend(row);
+        }
+
+        let size = size.ok_or_else(|| {
+            SicImageEngineError::ConvolveKernelParseError(0, "the kernel is empty".to_string())
+        })?;
+
+        Self::new(size as u32, values)
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+/// Path to a text file holding a convolution kernel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelFromPath {
+    path: PathBuf,
+}
+
+impl KernelFromPath {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub(crate) fn open_kernel(&self) -> Result<Kernel, SicImageEngineError> {
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(SicImageEngineError::ConvolveKernelFileLoadError)?;
+
+        Kernel::parse(&contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(size = { 1, 3, 5 })]
+    fn new_kernel(size: u32) {
+        let values = vec![1.0; (size * size) as usize];
+
+        assert!(Kernel::new(size, values).is_ok());
+    }
+
+    #[pm(size = { 0, 2, 33 }, count = { 0, 4, 1089 })]
+    fn new_kernel_invalid_size(size: u32, count: usize) {
+        assert!(Kernel::new(size, vec![1.0; count]).is_err());
+    }
+
+    #[test]
+    fn new_kernel_invalid_value_count() {
+        assert!(Kernel::new(3, vec![1.0; 8]).is_err());
+    }
+
+    #[test]
+    fn new_kernel_not_finite() {
+        assert!(Kernel::new(1, vec![f32::NAN]).is_err());
+    }
+
+    #[test]
+    fn parse_kernel() {
+        let kernel = Kernel::parse("# sharpen\n 0 -1  0\n-1, 5, -1\n\n 0 -1  0\n").unwrap();
+
+        assert_eq!(kernel.size(), 3);
+        assert_eq!(
+            kernel.values(),
+            &[0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0]
+        );
+    }
+
+    #[pm(contents = {
+        "",                     // empty
+        "1 1\n1 1",             // even size
+        "1 1 1\n1 1\n1 1 1",    // uneven rows
+        "1 1 1\n1 1 1",         // not square
+        "1 x 1\n1 1 1\n1 1 1",  // not a number
+    })]
+    fn parse_kernel_invalid(contents: &str) {
+        assert!(Kernel::parse(contents).is_err());
+    }
+
+    #[test]
+    fn open_from_path() {
+        let kernel_from_path = KernelFromPath::new(PathBuf::from(in_!("kernel/emboss_5x5.txt")));
+
+        assert_eq!(kernel_from_path.open_kernel().unwrap().size(), 5);
+    }
+
+    #[test]
+    fn open_missing() {
+        let kernel_from_path = KernelFromPath::new(PathBuf::from(in_!("kernel/missing.txt")));
+
+        assert!(kernel_from_path.open_kernel().is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::errors::SicImageEngineError;
+use crate::operations::lut::CubeLut;
+
+/// Path to a 3D LUT in the Adobe .cube format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LutFromPath {
+    path: PathBuf,
+}
+
+impl LutFromPath {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub(crate) fn open_lut(&self) -> Result<CubeLut, SicImageEngineError> {
+        let contents =
+            std::fs::read_to_string(&self.path).map_err(SicImageEngineError::LutFileLoadError)?;
+
+        CubeLut::parse(&contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::in_;
+
+    #[test]
+    fn open_from_path() {
+        let lut_from_path = LutFromPath::new(PathBuf::from(in_!("lut/invert.cube")));
+
+        assert!(lut_from_path.open_lut().is_ok());
+    }
+
+    #[test]
+    fn open_missing() {
+        let lut_from_path = LutFromPath::new(PathBuf::from(in_!("lut/missing.cube")));
+
+        assert!(lut_from_path.open_lut().is_err());
+    }
+}
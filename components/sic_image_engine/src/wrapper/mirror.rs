@@ -0,0 +1,47 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// The direction(s) in which the mirror operation appends a reflection of the image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MirrorMode {
+    /// Appends a reflection to the right of the image, which doubles its width.
+    Horizontal,
+    /// Appends a reflection below the image, which doubles its height.
+    Vertical,
+    /// Tiles the image and its reflections in a 2 by 2 grid, which doubles both the width and the
+    /// height.
+    Quad,
+}
+
+impl MirrorMode {
+    pub fn try_from_str(val: &str) -> Result<MirrorMode, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "horizontal" => Ok(MirrorMode::Horizontal),
+            "vertical" => Ok(MirrorMode::Vertical),
+            "quad" => Ok(MirrorMode::Quad),
+            fail => Err(SicImageEngineError::UnknownMirrorMode(fail.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        name = { "horizontal", "Vertical", "QUAD" },
+        expected = { MirrorMode::Horizontal, MirrorMode::Vertical, MirrorMode::Quad }
+    )]
+    fn from_str(name: &str, expected: MirrorMode) {
+        assert_eq!(MirrorMode::try_from_str(name).unwrap(), expected);
+    }
+
+    #[pm(name = { "", "h", "both", "diagonal" })]
+    fn unknown(name: &str) {
+        assert!(MirrorMode::try_from_str(name).is_err());
+    }
+}
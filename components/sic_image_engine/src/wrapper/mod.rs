@@ -1,9 +1,47 @@
+pub mod adaptive_threshold_method;
+pub mod aspect_ratio;
+pub mod blend_mode;
+pub mod blur_algorithm;
+pub mod border;
+pub mod channel;
+pub mod chroma_key;
+pub mod contours;
+pub mod dimension;
+pub mod expression;
+pub mod extend;
 pub mod filter_type;
+pub mod find;
+pub mod gradient;
+pub mod gradient_fill;
+pub mod gravity;
+pub mod histogram;
 pub mod image_path;
+pub mod kernel;
+pub mod lut;
+pub mod mirror;
+pub mod nine_slice;
 pub mod overlay;
+pub mod pad_to_ratio;
+pub mod pixel_upscale;
+pub mod redact;
+pub mod region;
+pub mod stamp;
+pub mod tonemap;
 
 #[cfg(feature = "imageproc-ops")]
 pub mod font_options;
 
+#[cfg(feature = "imageproc-ops")]
+pub mod draw_corners;
+
+#[cfg(feature = "imageproc-ops")]
+pub mod draw_shape;
+
 #[cfg(feature = "imageproc-ops")]
 pub mod draw_text_inner;
+
+#[cfg(feature = "imageproc-ops")]
+pub mod noise;
+
+#[cfg(feature = "imageproc-ops")]
+pub mod perspective;
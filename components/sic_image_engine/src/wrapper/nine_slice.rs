@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// The insets of the borders of a nine-slice image, and the size to which it's scaled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NineSliceInputs {
+    left: u32,
+    top: u32,
+    right: u32,
+    bottom: u32,
+    size: (u32, u32),
+}
+
+impl NineSliceInputs {
+    pub fn new((left, top, right, bottom): (u32, u32, u32, u32), size: (u32, u32)) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+            size,
+        }
+    }
+
+    pub fn left(&self) -> u32 {
+        self.left
+    }
+
+    pub fn top(&self) -> u32 {
+        self.top
+    }
+
+    pub fn right(&self) -> u32 {
+        self.right
+    }
+
+    pub fn bottom(&self) -> u32 {
+        self.bottom
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+}
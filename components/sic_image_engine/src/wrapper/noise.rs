@@ -0,0 +1,50 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// The kind of noise added by the noise operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NoiseKind {
+    /// Adds normally distributed noise to each channel of each pixel.
+    Gaussian,
+    /// Turns random pixels black or white.
+    SaltPepper,
+}
+
+impl NoiseKind {
+    pub fn try_from_str(val: &str) -> Result<NoiseKind, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "gaussian" => Ok(NoiseKind::Gaussian),
+            "salt-pepper" => Ok(NoiseKind::SaltPepper),
+            fail => Err(SicImageEngineError::UnknownNoiseKind(fail.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseInputs {
+    kind: NoiseKind,
+    amount: f32,
+    seed: Option<u64>,
+}
+
+impl NoiseInputs {
+    pub fn new(kind: NoiseKind, amount: f32, seed: Option<u64>) -> Self {
+        Self { kind, amount, seed }
+    }
+
+    pub fn kind(&self) -> NoiseKind {
+        self.kind
+    }
+
+    /// For Gaussian noise, the standard deviation relative to the full range of a sample; for
+    /// salt-and-pepper noise, the fraction of pixels which is replaced.
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+
+    /// The seed of the random number generator; when given, the same noise is added each time.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
@@ -1,24 +1,63 @@
+use crate::wrapper::blend_mode::BlendMode;
 use crate::wrapper::image_path::ImageFromPath;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
-pub struct OverlayInputs(ImageFromPath, (u32, u32));
+/// The opacity of the overlay, if none is given: fully opaque.
+pub const OVERLAY_DEFAULT_OPACITY: f32 = 1.0;
+
+/// The image which is overlaid, its position, and how it's composited over the image below it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OverlayInputs {
+    image_path: ImageFromPath,
+    position: (u32, u32),
+    opacity: f32,
+    blend_mode: BlendMode,
+}
 
 impl OverlayInputs {
     pub fn new(image_path: ImageFromPath, pos: (u32, u32)) -> Self {
-        OverlayInputs(image_path, pos)
+        OverlayInputs {
+            image_path,
+            position: pos,
+            opacity: OVERLAY_DEFAULT_OPACITY,
+            blend_mode: BlendMode::default(),
+        }
+    }
+
+    /// The opacity should be between 0 (invisible) and 1 (opaque); it's multiplied with the alpha
+    /// channel of the overlay.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
     }
 
     pub fn image_path(&self) -> &ImageFromPath {
-        &self.0
+        &self.image_path
     }
 
     pub fn position(&self) -> (u32, u32) {
-        self.1
+        self.position
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
     }
 }
 
 impl PartialEq for OverlayInputs {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0 && self.1 == other.1
+        self.image_path == other.image_path
+            && self.position == other.position
+            && self.opacity == other.opacity
+            && self.blend_mode == other.blend_mode
     }
 }
@@ -0,0 +1,31 @@
+use crate::wrapper::aspect_ratio::AspectRatio;
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+/// The color of the padding, if none is given, neither to pad-to-ratio itself nor by the background
+/// modifier: fully transparent.
+pub const PAD_TO_RATIO_DEFAULT_COLOR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+/// The aspect ratio the image should be padded to, and the color of the padding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PadToRatioInputs {
+    ratio: AspectRatio,
+    #[serde(default, with = "crate::serialization::option_rgba")]
+    color: Option<Rgba<u8>>,
+}
+
+impl PadToRatioInputs {
+    pub fn new(ratio: AspectRatio, color: Option<Rgba<u8>>) -> Self {
+        Self { ratio, color }
+    }
+
+    pub fn ratio(&self) -> AspectRatio {
+        self.ratio
+    }
+
+    /// The color given to pad-to-ratio itself, which takes precedence over the background
+    /// modifier.
+    pub fn color(&self) -> Option<Rgba<u8>> {
+        self.color
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use sic_core::image::Rgba;
+
+/// The color of the parts of the quadrilateral outside of the image, if none is given by the
+/// background modifier: fully transparent.
+pub const PERSPECTIVE_DEFAULT_BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+/// The four corners of the area of the image which the perspective operation maps onto a
+/// rectangle: the top left, top right, bottom right and bottom left corners, in that order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerspectiveInputs {
+    corners: [(f32, f32); 4],
+}
+
+impl PerspectiveInputs {
+    pub fn new(corners: [(f32, f32); 4]) -> Self {
+        Self { corners }
+    }
+
+    pub fn corners(&self) -> [(f32, f32); 4] {
+        self.corners
+    }
+}
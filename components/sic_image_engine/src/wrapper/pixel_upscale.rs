@@ -0,0 +1,67 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// The algorithm used to upscale pixel art, if none is given.
+pub const PIXEL_UPSCALE_DEFAULT_ALGORITHM: PixelUpscaleAlgorithm = PixelUpscaleAlgorithm::Scale2x;
+
+/// The algorithm with which the pixel-upscale operation upscales pixel art.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PixelUpscaleAlgorithm {
+    /// The Scale2x family of algorithms, also known as EPX and AdvMAME2x/3x.
+    Scale2x,
+}
+
+impl PixelUpscaleAlgorithm {
+    pub fn try_from_str(val: &str) -> Result<PixelUpscaleAlgorithm, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "scale2x" => Ok(PixelUpscaleAlgorithm::Scale2x),
+            fail => Err(SicImageEngineError::UnknownPixelUpscaleAlgorithm(
+                fail.to_string(),
+            )),
+        }
+    }
+}
+
+/// The factor by which to upscale, and the algorithm to upscale with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PixelUpscaleInputs {
+    factor: u32,
+    algorithm: PixelUpscaleAlgorithm,
+}
+
+impl PixelUpscaleInputs {
+    pub fn new(factor: u32, algorithm: PixelUpscaleAlgorithm) -> Self {
+        Self { factor, algorithm }
+    }
+
+    /// The factor by which the width and height are multiplied; 2, 3 or 4.
+    pub fn factor(&self) -> u32 {
+        self.factor
+    }
+
+    pub fn algorithm(&self) -> PixelUpscaleAlgorithm {
+        self.algorithm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        name = { "scale2x", "Scale2x", "SCALE2X" },
+        expected = { PixelUpscaleAlgorithm::Scale2x, PixelUpscaleAlgorithm::Scale2x, PixelUpscaleAlgorithm::Scale2x }
+    )]
+    fn algorithm_from_str(name: &str, expected: PixelUpscaleAlgorithm) {
+        assert_eq!(PixelUpscaleAlgorithm::try_from_str(name).unwrap(), expected);
+    }
+
+    #[pm(name = { "", "scale3x", "xbr", "hqx", "hq2x" })]
+    fn unknown_algorithm(name: &str) {
+        assert!(PixelUpscaleAlgorithm::try_from_str(name).is_err());
+    }
+}
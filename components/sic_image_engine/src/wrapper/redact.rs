@@ -0,0 +1,68 @@
+use crate::errors::SicImageEngineError;
+use crate::wrapper::region::Region;
+use serde::{Deserialize, Serialize};
+
+/// How a region is redacted, if no style is given.
+pub const REDACT_DEFAULT_STYLE: RedactStyle = RedactStyle::Fill;
+
+/// How the redact operation destroys the contents of a region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RedactStyle {
+    /// Fills the region with opaque black.
+    Fill,
+    /// Replaces the region by a few large blocks, of which the colors are randomly shifted.
+    Pixelate,
+}
+
+impl RedactStyle {
+    pub fn try_from_str(val: &str) -> Result<RedactStyle, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "fill" => Ok(RedactStyle::Fill),
+            "pixelate" => Ok(RedactStyle::Pixelate),
+            fail => Err(SicImageEngineError::UnknownRedactStyle(fail.to_string())),
+        }
+    }
+}
+
+/// The region to redact, and how.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactInputs {
+    region: Region,
+    style: RedactStyle,
+}
+
+impl RedactInputs {
+    pub fn new(region: Region, style: RedactStyle) -> Self {
+        Self { region, style }
+    }
+
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    pub fn style(&self) -> RedactStyle {
+        self.style
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        input = { "fill", "Pixelate", "PIXELATE" },
+        expected = { RedactStyle::Fill, RedactStyle::Pixelate, RedactStyle::Pixelate }
+    )]
+    fn style_from_str(input: &str, expected: RedactStyle) {
+        assert_eq!(RedactStyle::try_from_str(input).unwrap(), expected);
+    }
+
+    #[pm(input = { "", "blur", "pixel" })]
+    fn style_from_str_unknown(input: &str) {
+        assert!(RedactStyle::try_from_str(input).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+/// A rectangular part of the image, given by its top left corner and its size, to which
+/// operations are restricted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Region {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Region {
+    pub fn new((x, y): (u32, u32), (width, height): (u32, u32)) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The part of the region which lies within an image of the given size, as its top left
+    /// corner and its size; `None` if no part of the region lies within the image.
+    pub fn clip(&self, (width, height): (u32, u32)) -> Option<((u32, u32), (u32, u32))> {
+        let right = self.x.saturating_add(self.width).min(width);
+        let bottom = self.y.saturating_add(self.height).min(height);
+
+        if self.x < right && self.y < bottom {
+            Some(((self.x, self.y), (right - self.x, bottom - self.y)))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        region = { ((0, 0), (10, 10)), ((2, 3), (4, 5)), ((8, 8), (10, 10)), ((5, 0), (u32::MAX, 1)) },
+        expected = { ((0, 0), (10, 10)), ((2, 3), (4, 5)), ((8, 8), (2, 2)), ((5, 0), (5, 1)) }
+    )]
+    fn clip(region: ((u32, u32), (u32, u32)), expected: ((u32, u32), (u32, u32))) {
+        let region = Region::new(region.0, region.1);
+
+        assert_eq!(region.clip((10, 10)).unwrap(), expected);
+    }
+
+    #[pm(region = { ((10, 0), (1, 1)), ((0, 10), (1, 1)), ((2, 2), (0, 4)), ((2, 2), (4, 0)) })]
+    fn clip_outside_image(region: ((u32, u32), (u32, u32))) {
+        let region = Region::new(region.0, region.1);
+
+        assert!(region.clip((10, 10)).is_none());
+    }
+}
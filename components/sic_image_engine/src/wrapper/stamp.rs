@@ -0,0 +1,32 @@
+use crate::wrapper::gravity::Gravity;
+use crate::wrapper::image_path::ImageFromPath;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StampInputs {
+    image_path: ImageFromPath,
+    gravity: Gravity,
+    margin: u32,
+}
+
+impl StampInputs {
+    pub fn new(image_path: ImageFromPath, gravity: Gravity, margin: u32) -> Self {
+        Self {
+            image_path,
+            gravity,
+            margin,
+        }
+    }
+
+    pub fn image_path(&self) -> &ImageFromPath {
+        &self.image_path
+    }
+
+    pub fn gravity(&self) -> Gravity {
+        self.gravity
+    }
+
+    pub fn margin(&self) -> u32 {
+        self.margin
+    }
+}
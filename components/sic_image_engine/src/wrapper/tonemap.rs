@@ -0,0 +1,72 @@
+use crate::errors::SicImageEngineError;
+use serde::{Deserialize, Serialize};
+
+/// The exposure of the tonemap operation, if none is given: the samples are not scaled.
+pub const TONEMAP_DEFAULT_EXPOSURE: f32 = 0.0;
+
+/// The curve with which the tonemap operation compresses high dynamic range samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToneMapOperator {
+    /// The global Reinhard operator, `x / (1 + x)`, which never clips highlights.
+    Reinhard,
+    /// An approximation of the ACES filmic curve, which has more contrast than Reinhard and
+    /// clips the brightest highlights.
+    Aces,
+}
+
+impl ToneMapOperator {
+    pub fn try_from_str(val: &str) -> Result<ToneMapOperator, SicImageEngineError> {
+        match val.to_lowercase().as_str() {
+            "reinhard" => Ok(ToneMapOperator::Reinhard),
+            "aces" => Ok(ToneMapOperator::Aces),
+            fail => Err(SicImageEngineError::UnknownToneMapOperator(
+                fail.to_string(),
+            )),
+        }
+    }
+}
+
+/// Maps high dynamic range samples to the range [0, 1] with a tone mapping operator, after
+/// scaling them by an exposure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToneMapInputs {
+    operator: ToneMapOperator,
+    exposure: f32,
+}
+
+impl ToneMapInputs {
+    pub fn new(operator: ToneMapOperator, exposure: f32) -> Self {
+        Self { operator, exposure }
+    }
+
+    pub fn operator(&self) -> ToneMapOperator {
+        self.operator
+    }
+
+    /// The exposure in stops; each stop doubles the samples before they are tone mapped.
+    pub fn exposure(&self) -> f32 {
+        self.exposure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        name = { "reinhard", "ACES", "Aces" },
+        expected = { ToneMapOperator::Reinhard, ToneMapOperator::Aces, ToneMapOperator::Aces }
+    )]
+    fn from_str(name: &str, expected: ToneMapOperator) {
+        assert_eq!(ToneMapOperator::try_from_str(name).unwrap(), expected);
+    }
+
+    #[pm(name = { "", "filmic", "reinhard2" })]
+    fn unknown(name: &str) {
+        assert!(ToneMapOperator::try_from_str(name).is_err());
+    }
+}
@@ -1,9 +1,19 @@
+use crate::dds;
 use crate::errors::SicIoError;
+use crate::format::{EncodingFormat, JPEGQuality, TiffCompression, WebPQuality};
+use crate::gif_encoder;
+use crate::openexr;
+use crate::pdf::PdfDocument;
 use image::buffer::ConvertBuffer;
 use image::DynamicImage;
+use sic_core::animation::AnimatedImage;
+use sic_core::hdr::{self, Rgba32FImage};
 use sic_core::image;
-use sic_core::image::GenericImageView;
-use std::io::Write;
+use sic_core::image::{GenericImageView, ImageEncoder};
+use std::convert::TryFrom;
+use std::io::{Cursor, Seek, Write};
+use tiff::encoder::compression::{Compression, Deflate, Lzw, Packbits, Uncompressed};
+use tiff::encoder::{colortype, TiffEncoder};
 
 #[derive(Clone, Copy, Debug)]
 pub enum AutomaticColorTypeAdjustment {
@@ -21,19 +31,81 @@ impl Default for AutomaticColorTypeAdjustment {
 /// Use the ConversionWriter to convert and write image buffers to an output.
 pub struct ConversionWriter<'a> {
     image: &'a image::DynamicImage,
+    hdr: Option<&'a Rgba32FImage>,
+    animation: Option<&'a AnimatedImage>,
 }
 
 impl<'a> ConversionWriter<'a> {
     pub fn new(image: &image::DynamicImage) -> ConversionWriter {
-        ConversionWriter { image }
+        ConversionWriter {
+            image,
+            hdr: None,
+            animation: None,
+        }
+    }
+
+    /// The unclamped samples of the image, if it is a high dynamic range image; these are
+    /// written instead of the image, if the output format supports them.
+    pub fn with_hdr(mut self, hdr: Option<&'a Rgba32FImage>) -> Self {
+        self.hdr = hdr;
+        self
+    }
+
+    /// The frames of the image, if it is an animated image; these are written instead of the
+    /// image, if the output format supports animated images.
+    pub fn with_animation(mut self, animation: Option<&'a AnimatedImage>) -> Self {
+        self.animation = animation;
+        self
     }
 
     pub fn write<W: Write>(
         &self,
         writer: &mut W,
-        output_format: image::ImageOutputFormat,
+        output_format: EncodingFormat,
         color_type_adjustment: AutomaticColorTypeAdjustment,
     ) -> Result<(), SicIoError> {
+        let output_format = match (output_format, self.animation) {
+            (EncodingFormat::Image(format), _) => format,
+            (EncodingFormat::Gif(settings), Some(animation)) => {
+                return gif_encoder::encode(
+                    writer,
+                    animation.frames(),
+                    animation.repeat(),
+                    settings,
+                )
+            }
+            (EncodingFormat::Gif(settings), None) => {
+                let frame = image::Frame::new(self.image.to_rgba());
+                return gif_encoder::encode(writer, &[frame], None, settings);
+            }
+            (EncodingFormat::ProgressiveJpeg(quality), _) => {
+                return ConversionWriter::save_to_progressive_jpeg(writer, self.image, quality)
+            }
+            (EncodingFormat::WebP(quality), _) => {
+                return ConversionWriter::save_to_webp(writer, self.image, quality)
+            }
+            (EncodingFormat::Tiff(compression), _) => {
+                return ConversionWriter::save_to_tiff(writer, self.image, compression)
+            }
+            (EncodingFormat::Dds(settings), _) => return dds::encode(writer, self.image, settings),
+            (EncodingFormat::RawRgba8, _) => {
+                return writer
+                    .write_all(self.image.to_rgba().as_raw())
+                    .map_err(SicIoError::Io)
+            }
+            (EncodingFormat::Pdf, _) => {
+                let mut document = PdfDocument::new();
+                document.add_page(self.image)?;
+                return document.write(writer);
+            }
+            (EncodingFormat::Exr, _) => {
+                return match self.hdr {
+                    Some(hdr) => openexr::encode(writer, hdr),
+                    None => openexr::encode(writer, &hdr::from_dynamic_image(self.image)),
+                }
+            }
+        };
+
         let color_processing = &ConversionWriter::pre_process_color_type(
             &self.image,
             &output_format,
@@ -107,9 +179,149 @@ impl<'a> ConversionWriter<'a> {
         buffer: &image::DynamicImage,
         format: image::ImageOutputFormat,
     ) -> Result<(), SicIoError> {
-        buffer
-            .write_to(writer, format)
-            .map_err(SicIoError::ImageError)
+        match (format, buffer) {
+            // `write_to` gives the encoder the 16 bit samples in native byte order, while PNG
+            // images are big endian; `write_image` reorders them
+            (
+                image::ImageOutputFormat::Png,
+                DynamicImage::ImageLuma16(_)
+                | DynamicImage::ImageLumaA16(_)
+                | DynamicImage::ImageRgb16(_)
+                | DynamicImage::ImageRgba16(_),
+            ) => image::png::PngEncoder::new(writer).write_image(
+                &buffer.to_bytes(),
+                buffer.width(),
+                buffer.height(),
+                buffer.color(),
+            ),
+            (format, _) => buffer.write_to(writer, format),
+        }
+        .map_err(SicIoError::ImageError)
+    }
+
+    /// WebP is encoded by libwebp, which takes 8 bit RGB or RGBA samples; the alpha channel is
+    /// kept if the image has one.
+    fn save_to_webp<W: Write>(
+        writer: &mut W,
+        buffer: &image::DynamicImage,
+        quality: WebPQuality,
+    ) -> Result<(), SicIoError> {
+        let (width, height) = buffer.dimensions();
+
+        let samples;
+        let encoder = if buffer.color().has_alpha() {
+            samples = buffer.to_rgba().into_raw();
+            webp::Encoder::from_rgba(&samples, width, height)
+        } else {
+            samples = buffer.to_rgb().into_raw();
+            webp::Encoder::from_rgb(&samples, width, height)
+        };
+
+        let encoded = encoder
+            .encode_simple(quality.is_lossless(), f32::from(quality.as_u8()))
+            .map_err(SicIoError::WebPEncodingError)?;
+
+        writer.write_all(&encoded).map_err(SicIoError::Io)
+    }
+
+    /// Progressive JPEG is encoded by the jpeg-encoder crate, since the JPEG encoder of the image
+    /// crate only writes baseline JPEG images. Like baseline JPEG images, the alpha channel is
+    /// dropped.
+    fn save_to_progressive_jpeg<W: Write>(
+        writer: &mut W,
+        buffer: &image::DynamicImage,
+        quality: JPEGQuality,
+    ) -> Result<(), SicIoError> {
+        let (width, height) = buffer.dimensions();
+        let too_large = || SicIoError::JpegDimensionsTooLarge(width, height);
+        let jpeg_width = u16::try_from(width).map_err(|_| too_large())?;
+        let jpeg_height = u16::try_from(height).map_err(|_| too_large())?;
+
+        let (samples, color_type) = if buffer.color().has_color() {
+            (buffer.to_rgb().into_raw(), jpeg_encoder::ColorType::Rgb)
+        } else {
+            (buffer.to_luma().into_raw(), jpeg_encoder::ColorType::Luma)
+        };
+
+        let mut encoder = jpeg_encoder::Encoder::new(writer, quality.as_u8());
+        encoder.set_progressive(true);
+        encoder
+            .encode(&samples, jpeg_width, jpeg_height, color_type)
+            .map_err(SicIoError::JpegEncodingError)
+    }
+
+    /// TIFF is encoded by the tiff crate, since the version used by the image crate can't
+    /// compress. The encoder needs to seek, so the image is encoded in memory first.
+    fn save_to_tiff<W: Write>(
+        writer: &mut W,
+        buffer: &image::DynamicImage,
+        compression: TiffCompression,
+    ) -> Result<(), SicIoError> {
+        let mut encoded = Cursor::new(Vec::new());
+        let mut encoder = TiffEncoder::new(&mut encoded).map_err(SicIoError::TiffEncodingError)?;
+
+        match compression {
+            TiffCompression::None => write_tiff_image(&mut encoder, buffer, Uncompressed),
+            TiffCompression::Lzw => write_tiff_image(&mut encoder, buffer, Lzw),
+            TiffCompression::Deflate => write_tiff_image(&mut encoder, buffer, Deflate::default()),
+            TiffCompression::Packbits => write_tiff_image(&mut encoder, buffer, Packbits),
+        }
+        .map_err(SicIoError::TiffEncodingError)?;
+
+        writer.write_all(encoded.get_ref()).map_err(SicIoError::Io)
+    }
+}
+
+/// Writes the image with its own color type, if TIFF supports it, and as 8 bit RGB(A) otherwise.
+fn write_tiff_image<W: Write + Seek, D: Compression>(
+    encoder: &mut TiffEncoder<W>,
+    buffer: &image::DynamicImage,
+    compression: D,
+) -> tiff::TiffResult<()> {
+    let (width, height) = buffer.dimensions();
+
+    match buffer {
+        DynamicImage::ImageLuma8(image) => encoder
+            .write_image_with_compression::<colortype::Gray8, D>(
+                width,
+                height,
+                compression,
+                image.as_raw(),
+            ),
+        DynamicImage::ImageLuma16(image) => encoder
+            .write_image_with_compression::<colortype::Gray16, D>(
+                width,
+                height,
+                compression,
+                image.as_raw(),
+            ),
+        DynamicImage::ImageRgb16(image) => encoder
+            .write_image_with_compression::<colortype::RGB16, D>(
+                width,
+                height,
+                compression,
+                image.as_raw(),
+            ),
+        DynamicImage::ImageRgba16(image) => encoder
+            .write_image_with_compression::<colortype::RGBA16, D>(
+                width,
+                height,
+                compression,
+                image.as_raw(),
+            ),
+        image if image.color().has_alpha() => encoder
+            .write_image_with_compression::<colortype::RGBA8, D>(
+                width,
+                height,
+                compression,
+                &image.to_rgba().into_raw(),
+            ),
+        image => encoder.write_image_with_compression::<colortype::RGB8, D>(
+            width,
+            height,
+            compression,
+            &image.to_rgb().into_raw(),
+        ),
     }
 }
 
@@ -118,12 +330,172 @@ mod tests {
     use std::fs::File;
     use std::io::{self, Read};
 
+    use parameterized::parameterized;
+    use sic_core::animation::Repeat;
     use sic_testing::{clean_up_output_path, setup_output_path, setup_test_image};
 
     use super::*;
+    use crate::load::{decode_image, file_reader, ImportConfig};
+    use crate::repeat::gif_repeat;
 
     // Individual tests:
 
+    #[test]
+    fn png_16_bit_samples_roundtrip() {
+        let buffer = DynamicImage::ImageRgba16(image::ImageBuffer::from_fn(3, 2, |x, y| {
+            image::Rgba([x as u16 * 300 + 1, y as u16 * 20_000, 0x1234, u16::MAX])
+        }));
+
+        let mut bytes = Vec::new();
+        ConversionWriter::new(&buffer)
+            .write(
+                &mut bytes,
+                image::ImageOutputFormat::Png.into(),
+                AutomaticColorTypeAdjustment::Enabled,
+            )
+            .unwrap();
+
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), buffer.to_bytes());
+    }
+
+    fn animation() -> AnimatedImage {
+        let frames = [[255, 0, 0, 255], [0, 0, 255, 255]]
+            .iter()
+            .map(|&color| image::Frame::new(image::RgbaImage::from_pixel(2, 2, image::Rgba(color))))
+            .collect();
+
+        AnimatedImage::from_frames(frames).unwrap()
+    }
+
+    fn write_animation(format: EncodingFormat) -> Vec<u8> {
+        let animation = animation();
+        let mut bytes = Vec::new();
+        ConversionWriter::new(&animation.first_frame())
+            .with_animation(Some(&animation))
+            .write(&mut bytes, format, AutomaticColorTypeAdjustment::Enabled)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn animation_written_as_gif() {
+        use image::AnimationDecoder;
+
+        let bytes = write_animation(EncodingFormat::Gif(Default::default()));
+        let decoder = image::gif::GifDecoder::new(bytes.as_slice()).unwrap();
+
+        assert_eq!(decoder.into_frames().collect_frames().unwrap().len(), 2);
+    }
+
+    #[parameterized(
+        path = { "loop.gif", "noloop.gif" },
+        expected = { Some(Repeat::Infinite), None },
+    )]
+    fn animation_repeat_roundtrip(path: &str, expected: Option<Repeat>) {
+        let config = ImportConfig {
+            keep_animation: true,
+            ..Default::default()
+        };
+        let mut reader = file_reader(setup_test_image(path)).unwrap();
+        let decoded = decode_image(&mut reader, &config).unwrap();
+
+        let mut bytes = Vec::new();
+        ConversionWriter::new(&decoded.image)
+            .with_animation(decoded.animation.as_ref())
+            .write(
+                &mut bytes,
+                EncodingFormat::Gif(Default::default()),
+                AutomaticColorTypeAdjustment::Enabled,
+            )
+            .unwrap();
+
+        assert_eq!(gif_repeat(&bytes), expected);
+    }
+
+    #[test]
+    fn animation_written_as_still_image() {
+        let bytes = write_animation(image::ImageOutputFormat::Png.into());
+        let decoded = image::load_from_memory(&bytes).unwrap();
+
+        assert_eq!(decoded.to_rgba(), animation().first_frame().to_rgba());
+    }
+
+    fn write_progressive_jpeg(buffer: &DynamicImage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ConversionWriter::new(buffer)
+            .write(
+                &mut bytes,
+                EncodingFormat::ProgressiveJpeg(JPEGQuality::default()),
+                AutomaticColorTypeAdjustment::Enabled,
+            )
+            .unwrap();
+        bytes
+    }
+
+    // the start of frame marker of progressive JPEG images (SOF2)
+    fn is_progressive(bytes: &[u8]) -> bool {
+        bytes.windows(2).any(|marker| marker == [0xff, 0xc2])
+    }
+
+    #[test]
+    fn progressive_jpeg() {
+        let buffer = image::open(setup_test_image("rainbow_8x6.bmp")).unwrap();
+
+        let bytes = write_progressive_jpeg(&buffer);
+        let decoded =
+            image::load_from_memory_with_format(&bytes, image::ImageFormat::Jpeg).unwrap();
+
+        assert!(is_progressive(&bytes));
+        assert_eq!(decoded.dimensions(), (8, 6));
+        assert_eq!(decoded.color(), image::ColorType::Rgb8);
+    }
+
+    #[test]
+    fn progressive_jpeg_grayscale() {
+        let buffer =
+            DynamicImage::ImageLuma8(image::GrayImage::from_pixel(4, 4, image::Luma([90])));
+
+        let bytes = write_progressive_jpeg(&buffer);
+        let decoded = image::load_from_memory(&bytes).unwrap();
+
+        assert!(is_progressive(&bytes));
+        assert_eq!(decoded.color(), image::ColorType::L8);
+    }
+
+    #[test]
+    fn baseline_jpeg_is_not_progressive() {
+        let buffer = image::open(setup_test_image("rainbow_8x6.bmp")).unwrap();
+
+        let mut bytes = Vec::new();
+        ConversionWriter::new(&buffer)
+            .write(
+                &mut bytes,
+                image::ImageOutputFormat::Jpeg(80).into(),
+                AutomaticColorTypeAdjustment::Enabled,
+            )
+            .unwrap();
+
+        assert!(!is_progressive(&bytes));
+    }
+
+    #[test]
+    fn raw_rgba8_written_unencoded() {
+        let buffer = image::open(setup_test_image("rainbow_8x6.bmp")).unwrap();
+
+        let mut bytes = Vec::new();
+        ConversionWriter::new(&buffer)
+            .write(
+                &mut bytes,
+                EncodingFormat::RawRgba8,
+                AutomaticColorTypeAdjustment::Enabled,
+            )
+            .unwrap();
+
+        assert_eq!(bytes.len(), 8 * 6 * 4);
+        assert_eq!(bytes, buffer.to_rgba().into_raw());
+    }
+
     const INPUT: &str = "rainbow_8x6.bmp";
     const OUTPUT: &str = "_out.png";
 
@@ -138,7 +510,7 @@ mod tests {
         conversion_processor
             .write(
                 &mut File::create(&output_path)?,
-                example_output_format,
+                example_output_format.into(),
                 AutomaticColorTypeAdjustment::Enabled,
             )
             .expect("Unable to save file to the test computer.");
@@ -160,7 +532,7 @@ mod tests {
         conversion_processor
             .write(
                 &mut File::create(&output_path)?,
-                example_output_format,
+                example_output_format.into(),
                 AutomaticColorTypeAdjustment::Enabled,
             )
             .expect("Unable to save file to the test computer.");
@@ -185,7 +557,7 @@ mod tests {
         conversion_processor
             .write(
                 &mut File::create(&output_path)?,
-                example_output_format,
+                example_output_format.into(),
                 AutomaticColorTypeAdjustment::Enabled,
             )
             .expect("Unable to save file to the test computer.");
@@ -205,6 +577,80 @@ mod tests {
         Ok(())
     }
 
+    #[parameterized(input = { "rainbow_8x6.bmp", "palette_4x4.png" }, lossless = { false, true })]
+    fn is_webp_file(input: &str, lossless: bool) {
+        let buffer = image::open(setup_test_image(input)).expect("Can't open test file.");
+        let mut bytes = Vec::new();
+
+        ConversionWriter::new(&buffer)
+            .write(
+                &mut bytes,
+                EncodingFormat::WebP(WebPQuality::try_from(75, lossless).unwrap()),
+                AutomaticColorTypeAdjustment::Enabled,
+            )
+            .expect("Unable to encode WebP.");
+
+        assert_eq!(
+            image::ImageFormat::WebP,
+            image::guess_format(&bytes).expect("Format could not be guessed.")
+        );
+    }
+
+    #[parameterized(compression = {
+        TiffCompression::None,
+        TiffCompression::Lzw,
+        TiffCompression::Deflate,
+        TiffCompression::Packbits,
+    })]
+    fn is_tiff_file(compression: TiffCompression) {
+        let buffer = image::open(setup_test_image(INPUT)).expect("Can't open test file.");
+        let mut bytes = Vec::new();
+
+        ConversionWriter::new(&buffer)
+            .write(
+                &mut bytes,
+                EncodingFormat::Tiff(compression),
+                AutomaticColorTypeAdjustment::Enabled,
+            )
+            .expect("Unable to encode TIFF.");
+
+        assert_eq!(
+            image::ImageFormat::Tiff,
+            image::guess_format(&bytes).expect("Format could not be guessed.")
+        );
+    }
+
+    // the tiff decoder of the image crate can't decompress deflate, so the tiff crate is used
+    #[parameterized(compression = {
+        TiffCompression::None,
+        TiffCompression::Lzw,
+        TiffCompression::Deflate,
+        TiffCompression::Packbits,
+    })]
+    fn tiff_compression_is_lossless(compression: TiffCompression) {
+        let buffer = image::open(setup_test_image(INPUT)).expect("Can't open test file.");
+        let mut bytes = Vec::new();
+
+        ConversionWriter::new(&buffer)
+            .write(
+                &mut bytes,
+                EncodingFormat::Tiff(compression),
+                AutomaticColorTypeAdjustment::Enabled,
+            )
+            .expect("Unable to encode TIFF.");
+
+        let mut decoder = tiff::decoder::Decoder::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(decoder.dimensions().unwrap(), buffer.dimensions());
+        assert_eq!(decoder.colortype().unwrap(), tiff::ColorType::RGB(8));
+
+        match decoder.read_image().unwrap() {
+            tiff::decoder::DecodingResult::U8(samples) => {
+                assert_eq!(samples, buffer.to_rgb().into_raw())
+            }
+            _ => panic!("Expected 8 bit samples."),
+        }
+    }
+
     // Multi tests:
     // Below all supported formats are testsed using the inputs listed below.
 
@@ -260,7 +706,11 @@ mod tests {
         let mut writer = File::create(&output_path)?;
 
         conversion_processor
-            .write(&mut writer, format, AutomaticColorTypeAdjustment::Enabled)
+            .write(
+                &mut writer,
+                format.into(),
+                AutomaticColorTypeAdjustment::Enabled,
+            )
             .expect("Unable to save file to the test computer.");
 
         let mut file = std::fs::File::open(setup_output_path(our_output))
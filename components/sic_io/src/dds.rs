@@ -0,0 +1,318 @@
+//! DDS (DirectDraw Surface) textures are written by sic_io itself, since the image crate can only
+//! decode them. The blocks of BC1 and BC3 compressed textures are encoded by the DXT encoder of
+//! the image crate.
+
+use std::io::Write;
+
+use sic_core::image::dxt::{DXTVariant, DxtEncoder};
+use sic_core::image::imageops::{self, FilterType};
+use sic_core::image::{DynamicImage, ImageBuffer, RgbaImage};
+
+use crate::errors::SicIoError;
+use crate::format::{DdsCompression, DdsSettings};
+
+const MAGIC_NUMBER: &[u8] = b"DDS ";
+const HEADER_SIZE: u32 = 124;
+const PIXEL_FORMAT_SIZE: u32 = 32;
+
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x2_0000;
+const DDSD_LINEARSIZE: u32 = 0x8_0000;
+
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x40_0000;
+
+/// Encodes an image as DDS texture, with 8 bit RGBA samples or block compressed, optionally
+/// followed by its mipmaps.
+pub(crate) fn encode<W: Write>(
+    writer: &mut W,
+    image: &DynamicImage,
+    settings: DdsSettings,
+) -> Result<(), SicIoError> {
+    let levels = mipmap_chain(image.to_rgba(), settings.mipmaps);
+
+    writer
+        .write_all(&header(
+            &levels[0],
+            levels.len() as u32,
+            settings.compression,
+        ))
+        .map_err(SicIoError::Io)?;
+
+    for level in &levels {
+        encode_level(writer, level, settings.compression)?;
+    }
+
+    Ok(())
+}
+
+/// The image, followed by its mipmaps if requested; each mipmap is half the size of the previous
+/// level, rounded down, until both dimensions are 1.
+fn mipmap_chain(image: RgbaImage, mipmaps: bool) -> Vec<RgbaImage> {
+    let levels = if mipmaps { usize::MAX } else { 1 };
+
+    std::iter::successors(Some(image), |level| match level.dimensions() {
+        (1, 1) => None,
+        (width, height) => Some(imageops::resize(
+            level,
+            (width / 2).max(1),
+            (height / 2).max(1),
+            FilterType::Triangle,
+        )),
+    })
+    .take(levels)
+    .collect()
+}
+
+fn header(image: &RgbaImage, mipmap_count: u32, compression: DdsCompression) -> Vec<u8> {
+    let (width, height) = image.dimensions();
+    let blocks = width.div_ceil(4).saturating_mul(height.div_ceil(4));
+
+    // size, flags, fourcc, rgb bit count, and the red, green, blue and alpha bit masks
+    let (size_flag, pitch_or_linear_size, pixel_format) = match compression {
+        DdsCompression::None => (
+            DDSD_PITCH,
+            width.saturating_mul(4),
+            [
+                PIXEL_FORMAT_SIZE,
+                DDPF_RGB | DDPF_ALPHAPIXELS,
+                0,
+                32,
+                0x0000_00ff,
+                0x0000_ff00,
+                0x00ff_0000,
+                0xff00_0000,
+            ],
+        ),
+        DdsCompression::Bc1 => (
+            DDSD_LINEARSIZE,
+            blocks.saturating_mul(8),
+            [
+                PIXEL_FORMAT_SIZE,
+                DDPF_FOURCC,
+                u32::from_le_bytes(*b"DXT1"),
+                0,
+                0,
+                0,
+                0,
+                0,
+            ],
+        ),
+        DdsCompression::Bc3 => (
+            DDSD_LINEARSIZE,
+            blocks.saturating_mul(16),
+            [
+                PIXEL_FORMAT_SIZE,
+                DDPF_FOURCC,
+                u32::from_le_bytes(*b"DXT5"),
+                0,
+                0,
+                0,
+                0,
+                0,
+            ],
+        ),
+    };
+
+    let (mipmap_flag, mipmap_caps) = if mipmap_count > 1 {
+        (DDSD_MIPMAPCOUNT, DDSCAPS_COMPLEX | DDSCAPS_MIPMAP)
+    } else {
+        (0, 0)
+    };
+
+    let flags = DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT | size_flag | mipmap_flag;
+
+    let mut fields = vec![
+        HEADER_SIZE,
+        flags,
+        height,
+        width,
+        pitch_or_linear_size,
+        0, // depth
+        mipmap_count,
+    ];
+    fields.extend_from_slice(&[0; 11]); // reserved
+    fields.extend_from_slice(&pixel_format);
+    fields.extend_from_slice(&[DDSCAPS_TEXTURE | mipmap_caps, 0, 0, 0, 0]);
+
+    let mut header = MAGIC_NUMBER.to_vec();
+    for field in fields {
+        header.extend_from_slice(&field.to_le_bytes());
+    }
+
+    header
+}
+
+fn encode_level<W: Write>(
+    writer: &mut W,
+    level: &RgbaImage,
+    compression: DdsCompression,
+) -> Result<(), SicIoError> {
+    match compression {
+        DdsCompression::None => writer.write_all(level.as_raw()).map_err(SicIoError::Io),
+        DdsCompression::Bc1 => {
+            let blocks = DynamicImage::ImageRgba8(pad_to_blocks(level)).to_rgb();
+            DxtEncoder::new(writer)
+                .encode(
+                    blocks.as_raw(),
+                    blocks.width(),
+                    blocks.height(),
+                    DXTVariant::DXT1,
+                )
+                .map_err(SicIoError::ImageError)
+        }
+        DdsCompression::Bc3 => {
+            let blocks = pad_to_blocks(level);
+            DxtEncoder::new(writer)
+                .encode(
+                    blocks.as_raw(),
+                    blocks.width(),
+                    blocks.height(),
+                    DXTVariant::DXT5,
+                )
+                .map_err(SicIoError::ImageError)
+        }
+    }
+}
+
+/// Block compression works on blocks of 4 by 4 pixels, so the image is extended to a multiple of
+/// 4 pixels in both dimensions by repeating its last column and row.
+fn pad_to_blocks(image: &RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let padded = |size: u32| size.div_ceil(4) * 4;
+
+    ImageBuffer::from_fn(padded(width), padded(height), |x, y| {
+        *image.get_pixel(x.min(width - 1), y.min(height - 1))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::dds::DdsDecoder;
+    use sic_core::image::Rgba;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const CLEAR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+    // 4 by 4 pixel blocks of colors which BC1 and BC3 represent exactly
+    fn quadrants() -> RgbaImage {
+        ImageBuffer::from_fn(8, 8, |x, y| match (x < 4, y < 4) {
+            (true, true) => RED,
+            (false, true) => BLUE,
+            (true, false) => WHITE,
+            (false, false) => CLEAR,
+        })
+    }
+
+    fn encoded(image: RgbaImage, compression: DdsCompression, mipmaps: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let settings = DdsSettings {
+            compression,
+            mipmaps,
+        };
+        encode(&mut bytes, &DynamicImage::ImageRgba8(image), settings).unwrap();
+        bytes
+    }
+
+    fn field(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    }
+
+    fn decoded(bytes: &[u8]) -> RgbaImage {
+        let decoder = DdsDecoder::new(bytes).unwrap();
+        DynamicImage::from_decoder(decoder).unwrap().to_rgba()
+    }
+
+    #[test]
+    fn uncompressed() {
+        let bytes = encoded(quadrants(), DdsCompression::None, false);
+
+        assert_eq!(&bytes[..4], b"DDS ");
+        assert_eq!(field(&bytes, 4), HEADER_SIZE);
+        assert_eq!(field(&bytes, 12), 8); // height
+        assert_eq!(field(&bytes, 16), 8); // width
+        assert_eq!(field(&bytes, 20), 8 * 4); // pitch
+        assert_eq!(field(&bytes, 80), DDPF_RGB | DDPF_ALPHAPIXELS);
+        assert_eq!(&bytes[128..], quadrants().as_raw().as_slice());
+    }
+
+    #[test]
+    fn bc1() {
+        let bytes = encoded(quadrants(), DdsCompression::Bc1, false);
+        let expected = ImageBuffer::from_fn(8, 8, |x, y| match quadrants().get_pixel(x, y) {
+            &CLEAR => Rgba([0, 0, 0, 255]),
+            other => *other,
+        });
+
+        assert_eq!(&bytes[84..88], b"DXT1");
+        assert_eq!(bytes.len(), 128 + 4 * 8);
+        assert_eq!(decoded(&bytes), expected);
+    }
+
+    #[test]
+    fn bc3_keeps_alpha() {
+        let bytes = encoded(quadrants(), DdsCompression::Bc3, false);
+
+        assert_eq!(&bytes[84..88], b"DXT5");
+        assert_eq!(bytes.len(), 128 + 4 * 16);
+        assert_eq!(decoded(&bytes), quadrants());
+    }
+
+    #[test]
+    fn blocks_are_padded() {
+        let image = ImageBuffer::from_pixel(6, 5, RED);
+        let bytes = encoded(image, DdsCompression::Bc3, false);
+
+        assert_eq!(field(&bytes, 12), 5);
+        assert_eq!(field(&bytes, 16), 6);
+        assert_eq!(field(&bytes, 20), 4 * 16); // linear size
+        assert_eq!(bytes.len(), 128 + 4 * 16);
+    }
+
+    #[test]
+    fn mipmaps() {
+        let bytes = encoded(quadrants(), DdsCompression::None, true);
+
+        // 8x8, 4x4, 2x2 and 1x1
+        assert_eq!(field(&bytes, 28), 4);
+        assert_ne!(field(&bytes, 8) & DDSD_MIPMAPCOUNT, 0);
+        assert_ne!(field(&bytes, 108) & DDSCAPS_MIPMAP, 0);
+        assert_eq!(bytes.len(), 128 + 4 * (64 + 16 + 4 + 1));
+    }
+
+    #[test]
+    fn mipmaps_of_a_non_square_image() {
+        let levels = mipmap_chain(ImageBuffer::from_pixel(8, 2, RED), true);
+        let sizes = levels
+            .iter()
+            .map(|level| level.dimensions())
+            .collect::<Vec<_>>();
+
+        assert_eq!(sizes, vec![(8, 2), (4, 1), (2, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn no_mipmaps() {
+        let bytes = encoded(quadrants(), DdsCompression::Bc1, false);
+
+        assert_eq!(field(&bytes, 28), 1);
+        assert_eq!(field(&bytes, 8) & DDSD_MIPMAPCOUNT, 0);
+    }
+}
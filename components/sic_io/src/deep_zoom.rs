@@ -0,0 +1,275 @@
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::errors::SicIoError;
+
+const DEEP_ZOOM_NAMESPACE: &str = "http://schemas.microsoft.com/deepzoom/2008";
+
+/// Describes how an image is cut into tiles for each level of a Deep Zoom (DZI) image pyramid.
+///
+/// The highest level contains the image at full size; each lower level halves the size of the
+/// level above it (rounded up), down to level 0, which is 1 by 1 pixel. Each level is cut into
+/// tiles of `tile_size` by `tile_size` pixels, and each tile additionally contains `overlap`
+/// pixels of its neighbouring tiles, on each side which has a neighbour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeepZoomLayout {
+    width: u32,
+    height: u32,
+    tile_size: u32,
+    overlap: u32,
+}
+
+/// A single tile of a level of the image pyramid. The tile is stored as `{column}_{row}.{ext}`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+    pub column: u32,
+    pub row: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl DeepZoomLayout {
+    pub fn new(width: u32, height: u32, tile_size: u32, overlap: u32) -> Result<Self, SicIoError> {
+        if tile_size == 0 {
+            return Err(SicIoError::DeepZoomInvalidTileSize);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            tile_size,
+            overlap,
+        })
+    }
+
+    /// The level at which the image has its full size.
+    pub fn max_level(&self) -> u32 {
+        let mut size = self.width.max(self.height);
+        let mut level = 0;
+
+        while size > 1 {
+            size = size.div_ceil(2);
+            level += 1;
+        }
+
+        level
+    }
+
+    /// The width and height of the image at the given level.
+    pub fn level_dimensions(&self, level: u32) -> (u32, u32) {
+        let scale = self.max_level().saturating_sub(level);
+
+        (
+            halve_times(self.width, scale),
+            halve_times(self.height, scale),
+        )
+    }
+
+    /// The tiles of the given level, from left to right and top to bottom.
+    pub fn tiles(&self, level: u32) -> Vec<Tile> {
+        let (width, height) = self.level_dimensions(level);
+        let columns = width.div_ceil(self.tile_size);
+        let rows = height.div_ceil(self.tile_size);
+
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let (x, tile_width) = self.span(column, width);
+                let (y, tile_height) = self.span(row, height);
+
+                Tile {
+                    column,
+                    row,
+                    x,
+                    y,
+                    width: tile_width,
+                    height: tile_height,
+                }
+            })
+            .collect()
+    }
+
+    /// The start and length of the tile with the given index along a single axis, including the
+    /// overlap with its neighbours.
+    fn span(&self, index: u32, length: u32) -> (u32, u32) {
+        let start = (index * self.tile_size).saturating_sub(self.overlap);
+        let end = ((index + 1) * self.tile_size)
+            .saturating_add(self.overlap)
+            .min(length);
+
+        (start, end - start)
+    }
+
+    /// The descriptor which viewers, like OpenSeadragon, use to locate the tiles.
+    /// `format` is the extension of the tiles.
+    pub fn descriptor<'f>(&self, format: &'f str) -> DeepZoomDescriptor<'f> {
+        DeepZoomDescriptor {
+            format,
+            overlap: self.overlap,
+            tile_size: self.tile_size,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+fn halve_times(size: u32, times: u32) -> u32 {
+    if times >= 32 {
+        return 1;
+    }
+
+    size.div_ceil(1 << times).max(1)
+}
+
+/// The `.dzi` descriptor of a Deep Zoom image, which can be written as XML or as JSON.
+#[derive(Debug)]
+pub struct DeepZoomDescriptor<'f> {
+    format: &'f str,
+    overlap: u32,
+    tile_size: u32,
+    width: u32,
+    height: u32,
+}
+
+// The JSON flavour of the descriptor, as understood by OpenSeadragon, which uses strings for all
+// values, like the XML attributes they mirror.
+#[derive(Serialize)]
+struct JsonDescriptor<'f> {
+    #[serde(rename = "Image")]
+    image: JsonImage<'f>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct JsonImage<'f> {
+    xmlns: &'static str,
+    format: &'f str,
+    overlap: String,
+    tile_size: String,
+    size: JsonSize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct JsonSize {
+    width: String,
+    height: String,
+}
+
+impl DeepZoomDescriptor<'_> {
+    pub fn write_xml<W: Write>(&self, writer: &mut W) -> Result<(), SicIoError> {
+        write!(
+            writer,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <Image xmlns=\"{}\" Format=\"{}\" Overlap=\"{}\" TileSize=\"{}\">\n  \
+             <Size Width=\"{}\" Height=\"{}\"/>\n\
+             </Image>\n",
+            DEEP_ZOOM_NAMESPACE, self.format, self.overlap, self.tile_size, self.width, self.height
+        )
+        .map_err(SicIoError::Io)
+    }
+
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> Result<(), SicIoError> {
+        let descriptor = JsonDescriptor {
+            image: JsonImage {
+                xmlns: DEEP_ZOOM_NAMESPACE,
+                format: self.format,
+                overlap: self.overlap.to_string(),
+                tile_size: self.tile_size.to_string(),
+                size: JsonSize {
+                    width: self.width.to_string(),
+                    height: self.height.to_string(),
+                },
+            },
+        };
+
+        serde_json::to_writer_pretty(writer, &descriptor).map_err(|err| SicIoError::Io(err.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::*;
+
+    ide!();
+
+    #[pm(
+        size = { (1, 1), (2, 1), (3, 2), (256, 100), (257, 100), (1000, 4000) },
+        expected = { 0, 1, 2, 8, 9, 12 }
+    )]
+    fn max_level(size: (u32, u32), expected: u32) {
+        let layout = DeepZoomLayout::new(size.0, size.1, 254, 1).unwrap();
+
+        assert_eq!(layout.max_level(), expected);
+    }
+
+    #[test]
+    fn level_dimensions() {
+        let layout = DeepZoomLayout::new(1000, 301, 254, 1).unwrap();
+
+        assert_eq!(layout.max_level(), 10);
+        assert_eq!(layout.level_dimensions(10), (1000, 301));
+        assert_eq!(layout.level_dimensions(9), (500, 151));
+        assert_eq!(layout.level_dimensions(8), (250, 76));
+        assert_eq!(layout.level_dimensions(1), (2, 1));
+        assert_eq!(layout.level_dimensions(0), (1, 1));
+    }
+
+    #[test]
+    fn tiles_with_overlap() {
+        let layout = DeepZoomLayout::new(600, 200, 254, 1).unwrap();
+        let tiles = layout.tiles(layout.max_level());
+
+        assert_eq!(tiles.len(), 3);
+        assert_eq!((tiles[0].x, tiles[0].width), (0, 255));
+        assert_eq!((tiles[1].x, tiles[1].width), (253, 256));
+        assert_eq!((tiles[2].x, tiles[2].width), (507, 93));
+        assert!(tiles.iter().all(|tile| tile.y == 0 && tile.height == 200));
+        assert_eq!((tiles[2].column, tiles[2].row), (2, 0));
+    }
+
+    #[test]
+    fn tiles_without_overlap_cover_level() {
+        let layout = DeepZoomLayout::new(600, 520, 256, 0).unwrap();
+        let tiles = layout.tiles(layout.max_level());
+
+        assert_eq!(tiles.len(), 9);
+
+        let area: u32 = tiles.iter().map(|tile| tile.width * tile.height).sum();
+        assert_eq!(area, 600 * 520);
+    }
+
+    #[test]
+    fn invalid_tile_size() {
+        assert!(DeepZoomLayout::new(10, 10, 0, 1).is_err());
+    }
+
+    #[test]
+    fn descriptor_xml() {
+        let layout = DeepZoomLayout::new(600, 200, 254, 1).unwrap();
+
+        let mut out = Vec::new();
+        layout.descriptor("png").write_xml(&mut out).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+
+        assert!(xml.contains(r#"Format="png" Overlap="1" TileSize="254""#));
+        assert!(xml.contains(r#"<Size Width="600" Height="200"/>"#));
+    }
+
+    #[test]
+    fn descriptor_json() {
+        let layout = DeepZoomLayout::new(600, 200, 254, 1).unwrap();
+
+        let mut out = Vec::new();
+        layout.descriptor("jpg").write_json(&mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(json["Image"]["Format"], "jpg");
+        assert_eq!(json["Image"]["TileSize"], "254");
+        assert_eq!(json["Image"]["Size"]["Width"], "600");
+    }
+}
@@ -22,6 +22,23 @@ pub enum SicIoError {
     #[error("Unable to extract frame {0} from the (animated) image; please use a frame index between 0 and {1}.")]
     NoSuchFrame(usize, usize),
 
+    #[error(
+        "Unable to decode the input image: based on its file extension, the image was expected to \
+         be in the {expected} format, but its contents indicate the {detected} format. \
+         Perhaps the file has the wrong extension? ({source})"
+    )]
+    InputFormatMismatch {
+        expected: String,
+        detected: String,
+        source: Box<SicIoError>,
+    },
+
+    #[error("Unable to create a sprite sheet: the image does not contain any frames.")]
+    NoFramesForSpriteSheet,
+
+    #[error("Unable to create Deep Zoom tiles: the tile size should be larger than 0.")]
+    DeepZoomInvalidTileSize,
+
     #[error(
         "No supported image output format was found. The following identifier was provided: {0}."
     )]
@@ -31,6 +48,56 @@ pub enum SicIoError {
         "Unable to determine the image format from the file extension. The following path was given: {0}."
     )]
     UnableToDetermineImageFormatFromFileExtension(PathBuf),
+
+    #[error("Unable to encode the image as WebP: {0:?}.")]
+    WebPEncodingError(webp::WebPEncodingError),
+
+    #[error("Unable to encode the image as TIFF: {0}")]
+    TiffEncodingError(tiff::TiffError),
+
+    #[error("sic io error > {0}")]
+    ExrError(exr::error::Error),
+
+    #[error("Unable to encode the animated image as GIF: {0}")]
+    GifEncodingError(gif::EncodingError),
+
+    #[error(
+        "Unable to encode the animated image as GIF: its size of {0}x{1} pixels exceeds the \
+         maximum size of 65535x65535 pixels."
+    )]
+    GifDimensionsTooLarge(u32, u32),
+
+    #[error("Unable to encode the image as progressive JPEG: {0}")]
+    JpegEncodingError(jpeg_encoder::EncodingError),
+
+    #[error(
+        "Unable to encode the image as JPEG: its size of {0}x{1} pixels exceeds the maximum size \
+         of 65535x65535 pixels."
+    )]
+    JpegDimensionsTooLarge(u32, u32),
+
+    #[error("Unable to decode the Photoshop document: {0}")]
+    PsdError(psd::PsdError),
+
+    #[error(
+        "Unable to decode the Photoshop document: documents in the {0} color mode are not \
+         supported; only RGB and grayscale documents are."
+    )]
+    UnsupportedPsdColorMode(String),
+
+    #[error("Unable to decode the Photoshop document: its composite image is incomplete.")]
+    IncompletePsdImage,
+
+    #[error(
+        "Unable to read the raw RGBA image: an image of {width}x{height} pixels consists of \
+         {expected} bytes, but {actual} bytes were given."
+    )]
+    RawImageSizeMismatch {
+        width: u32,
+        height: u32,
+        expected: usize,
+        actual: usize,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -41,6 +108,35 @@ pub enum FormatError {
     #[error("JPEG Quality should range between 1 and 100 (inclusive).")]
     JPEGQualityLevelNotInRange,
 
+    #[error("Unable to determine WebP quality.")]
+    WebPQualityLevelNotSet,
+
+    #[error("WebP Quality should range between 0 and 100 (inclusive).")]
+    WebPQualityLevelNotInRange,
+
+    #[error("Unable to determine TIFF compression.")]
+    TiffCompressionNotSet,
+
+    #[error(
+        "Unknown TIFF compression '{0}'. Expected one of 'none', 'lzw', 'deflate' or 'packbits'."
+    )]
+    UnknownTiffCompression(String),
+
+    #[error("Unable to determine DDS settings.")]
+    DdsSettingsNotSet,
+
+    #[error("Unknown DDS compression '{0}'. Expected one of 'none', 'bc1' or 'bc3'.")]
+    UnknownDdsCompression(String),
+
+    #[error("Unable to determine GIF settings.")]
+    GifSettingsNotSet,
+
+    #[error("The number of GIF colors should range between 2 and 256 (inclusive).")]
+    GifColorsNotInRange,
+
+    #[error("Unknown GIF palette '{0}'. Expected one of 'global' or 'local'.")]
+    UnknownGifPalette(String),
+
     #[error("Using PNM requires the sample encoding to be set.")]
     PNMSamplingEncodingNotSet,
 }
@@ -6,14 +6,13 @@ use crate::errors::{FormatError, SicIoError};
 
 pub trait EncodingFormatByExtension {
     /// Determine the encoding format based on the extension of a file path.
-    fn by_extension<P: AsRef<Path>>(&self, path: P)
-        -> Result<image::ImageOutputFormat, SicIoError>;
+    fn by_extension<P: AsRef<Path>>(&self, path: P) -> Result<EncodingFormat, SicIoError>;
 }
 
 pub trait EncodingFormatByIdentifier {
     /// Determine the encoding format based on the method of exporting.
     /// Determine the encoding format based on a recognized given identifier.
-    fn by_identifier(&self, identifier: &str) -> Result<image::ImageOutputFormat, SicIoError>;
+    fn by_identifier(&self, identifier: &str) -> Result<EncodingFormat, SicIoError>;
 }
 
 pub trait EncodingFormatJPEGQuality {
@@ -22,6 +21,30 @@ pub trait EncodingFormatJPEGQuality {
     fn jpeg_quality(&self) -> Result<JPEGQuality, SicIoError>;
 }
 
+pub trait EncodingFormatWebPQuality {
+    /// Returns a validated WebP quality value.
+    /// If no such value exists, it will return an error instead.
+    fn webp_quality(&self) -> Result<WebPQuality, SicIoError>;
+}
+
+pub trait EncodingFormatTiffCompression {
+    /// Returns the compression method used to encode TIFF images.
+    /// If no such value exists, it will return an error instead.
+    fn tiff_compression(&self) -> Result<TiffCompression, SicIoError>;
+}
+
+pub trait EncodingFormatDdsSettings {
+    /// Returns the settings used to encode DDS textures.
+    /// If no such value exists, it will return an error instead.
+    fn dds_settings(&self) -> Result<DdsSettings, SicIoError>;
+}
+
+pub trait EncodingFormatGifSettings {
+    /// Returns the settings used to encode GIF images.
+    /// If no such value exists, it will return an error instead.
+    fn gif_settings(&self) -> Result<GifSettings, SicIoError>;
+}
+
 pub trait EncodingFormatPNMSampleEncoding {
     /// Returns a pnm sample encoding type.
     /// If no such value exists, it will return an error instead.
@@ -61,15 +84,217 @@ impl JPEGQuality {
     }
 }
 
+/// This struct ensures no invalid WebP qualities can be stored.
+/// For lossy encoding, the quality trades off image quality against file size; for lossless
+/// encoding, it trades off encoding speed against file size.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
+pub struct WebPQuality {
+    quality: u8,
+    lossless: bool,
+}
+
+impl Default for WebPQuality {
+    /// The default WebP quality is `75`, encoded lossy.
+    fn default() -> Self {
+        Self {
+            quality: 75,
+            lossless: false,
+        }
+    }
+}
+
+impl WebPQuality {
+    /// Returns an Ok result if the quality requested is between 0 and 100 (inclusive).
+    pub fn try_from(quality: u8, lossless: bool) -> Result<Self, SicIoError> {
+        if quality <= 100 {
+            Ok(WebPQuality { quality, lossless })
+        } else {
+            Err(SicIoError::FormatError(
+                FormatError::WebPQualityLevelNotInRange,
+            ))
+        }
+    }
+
+    /// Return the valid quality value.
+    pub fn as_u8(self) -> u8 {
+        self.quality
+    }
+
+    /// Whether the image should be encoded lossless.
+    pub fn is_lossless(self) -> bool {
+        self.lossless
+    }
+}
+
+/// The compression method used to encode TIFF images. All methods are lossless.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TiffCompression {
+    None,
+    Lzw,
+    Deflate,
+    Packbits,
+}
+
+impl Default for TiffCompression {
+    /// TIFF images are not compressed by default.
+    fn default() -> Self {
+        TiffCompression::None
+    }
+}
+
+impl TiffCompression {
+    pub fn try_from_str(value: &str) -> Result<Self, SicIoError> {
+        match value.to_ascii_lowercase().as_str() {
+            "none" => Ok(TiffCompression::None),
+            "lzw" => Ok(TiffCompression::Lzw),
+            "deflate" => Ok(TiffCompression::Deflate),
+            "packbits" => Ok(TiffCompression::Packbits),
+            _ => Err(SicIoError::FormatError(
+                FormatError::UnknownTiffCompression(value.to_string()),
+            )),
+        }
+    }
+}
+
+/// The block compression used to encode DDS textures.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DdsCompression {
+    /// Uncompressed 8 bit RGBA samples.
+    None,
+    /// BC1 (DXT1) block compression, for opaque textures; the alpha channel is dropped.
+    Bc1,
+    /// BC3 (DXT5) block compression, which keeps the alpha channel.
+    Bc3,
+}
+
+impl Default for DdsCompression {
+    /// DDS textures are not compressed by default.
+    fn default() -> Self {
+        DdsCompression::None
+    }
+}
+
+impl DdsCompression {
+    pub fn try_from_str(value: &str) -> Result<Self, SicIoError> {
+        match value.to_ascii_lowercase().as_str() {
+            "none" => Ok(DdsCompression::None),
+            "bc1" | "dxt1" => Ok(DdsCompression::Bc1),
+            "bc3" | "dxt5" => Ok(DdsCompression::Bc3),
+            _ => Err(SicIoError::FormatError(FormatError::UnknownDdsCompression(
+                value.to_string(),
+            ))),
+        }
+    }
+}
+
+/// The settings used to encode DDS textures.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct DdsSettings {
+    pub compression: DdsCompression,
+    /// Whether to add the chain of mipmaps, each half the size of the previous one, down to 1 by 1
+    /// pixels.
+    pub mipmaps: bool,
+}
+
+/// How the palettes of the frames of a GIF are chosen.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GifPalette {
+    /// A single palette, chosen from the colors of all frames, and shared by all frames.
+    Global,
+    /// A palette for each frame, chosen from the colors of that frame only. Frames are
+    /// represented more accurately, at the cost of a palette per frame.
+    Local,
+}
+
+impl Default for GifPalette {
+    /// All frames share a single palette by default.
+    fn default() -> Self {
+        GifPalette::Global
+    }
+}
+
+impl GifPalette {
+    pub fn try_from_str(value: &str) -> Result<Self, SicIoError> {
+        match value.to_ascii_lowercase().as_str() {
+            "global" => Ok(GifPalette::Global),
+            "local" => Ok(GifPalette::Local),
+            _ => Err(SicIoError::FormatError(FormatError::UnknownGifPalette(
+                value.to_string(),
+            ))),
+        }
+    }
+}
+
+/// The settings used to encode GIF images. This struct ensures no invalid number of colors can be
+/// stored.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct GifSettings {
+    palette: GifPalette,
+    colors: u16,
+}
+
+impl Default for GifSettings {
+    /// By default, all frames share a palette of at most 256 colors.
+    fn default() -> Self {
+        Self {
+            palette: GifPalette::default(),
+            colors: 256,
+        }
+    }
+}
+
+impl GifSettings {
+    /// Returns an Ok result if the number of colors of each palette is between 2 and 256
+    /// (inclusive). The transparent color, if any, is one of these colors. Fewer colors result in
+    /// smaller images, at the cost of quality.
+    pub fn try_from(palette: GifPalette, colors: u16) -> Result<Self, SicIoError> {
+        if (2..=256).contains(&colors) {
+            Ok(GifSettings { palette, colors })
+        } else {
+            Err(SicIoError::FormatError(FormatError::GifColorsNotInRange))
+        }
+    }
+
+    pub fn palette(self) -> GifPalette {
+        self.palette
+    }
+
+    /// The maximum number of colors of each palette.
+    pub fn colors(self) -> u16 {
+        self.colors
+    }
+}
+
+/// An image output format. Most formats are encoded by the image crate; the others are encoded
+/// by sic_io itself.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodingFormat {
+    Image(image::ImageOutputFormat),
+    /// JPEG images which are encoded progressive, i.e. in several scans of increasing detail,
+    /// which the image crate can't encode.
+    ProgressiveJpeg(JPEGQuality),
+    WebP(WebPQuality),
+    Tiff(TiffCompression),
+    Exr,
+    Dds(DdsSettings),
+    Gif(GifSettings),
+    Pdf,
+    /// Unencoded pixels with 8 bit RGBA samples, row by row, without a header.
+    RawRgba8,
+}
+
+impl From<image::ImageOutputFormat> for EncodingFormat {
+    fn from(format: image::ImageOutputFormat) -> Self {
+        EncodingFormat::Image(format)
+    }
+}
+
 impl EncodingFormatByExtension for DetermineEncodingFormat {
     /// Determines the encoding format based on the extension of the given path.
     /// If the path has no extension, it will return an error.
     /// The extension if existing is matched against the identifiers, which currently
     /// are the extensions used.
-    fn by_extension<P: AsRef<Path>>(
-        &self,
-        path: P,
-    ) -> Result<image::ImageOutputFormat, SicIoError> {
+    fn by_extension<P: AsRef<Path>>(&self, path: P) -> Result<EncodingFormat, SicIoError> {
         let extension = path.as_ref().extension().and_then(|v| v.to_str());
 
         match extension {
@@ -84,41 +309,82 @@ impl EncodingFormatByExtension for DetermineEncodingFormat {
 impl EncodingFormatByIdentifier for DetermineEncodingFormat {
     /// Determines an image output format based on a given `&str` identifier.
     /// Identifiers are based on common output file extensions.
-    fn by_identifier(&self, identifier: &str) -> Result<image::ImageOutputFormat, SicIoError> {
-        match identifier.to_ascii_lowercase().as_str() {
+    fn by_identifier(&self, identifier: &str) -> Result<EncodingFormat, SicIoError> {
+        let format = match identifier.to_ascii_lowercase().as_str() {
             "avif" => {
                 // FIXME: Dirty hack
                 //  - https://github.com/foresterre/sic/issues/597
                 std::env::set_var("SIC_AVIF_HACK", "1");
-                Ok(image::ImageOutputFormat::Farbfeld)
+                image::ImageOutputFormat::Farbfeld
             }
-            "bmp" => Ok(image::ImageOutputFormat::Bmp),
-            "farbfeld" => Ok(image::ImageOutputFormat::Farbfeld),
-            "gif" => Ok(image::ImageOutputFormat::Gif),
-            "ico" => Ok(image::ImageOutputFormat::Ico),
-            "jpeg" | "jpg" => Ok(image::ImageOutputFormat::Jpeg(self.jpeg_quality()?.as_u8())),
-            "pam" => Ok(image::ImageOutputFormat::Pnm(
-                image::pnm::PNMSubtype::ArbitraryMap,
-            )),
-            "pbm" => Ok(image::ImageOutputFormat::Pnm(
-                image::pnm::PNMSubtype::Bitmap(self.pnm_encoding_type()?),
+            "bmp" => image::ImageOutputFormat::Bmp,
+            "dds" => return Ok(EncodingFormat::Dds(self.dds_settings()?)),
+            "farbfeld" => image::ImageOutputFormat::Farbfeld,
+            "gif" => return Ok(EncodingFormat::Gif(self.gif_settings()?)),
+            "ico" => image::ImageOutputFormat::Ico,
+            "jpeg" | "jpg" if self.jpeg_progressive => {
+                return Ok(EncodingFormat::ProgressiveJpeg(self.jpeg_quality()?))
+            }
+            "jpeg" | "jpg" => image::ImageOutputFormat::Jpeg(self.jpeg_quality()?.as_u8()),
+            "pam" => image::ImageOutputFormat::Pnm(image::pnm::PNMSubtype::ArbitraryMap),
+            "pdf" => return Ok(EncodingFormat::Pdf),
+            "pbm" => image::ImageOutputFormat::Pnm(image::pnm::PNMSubtype::Bitmap(
+                self.pnm_encoding_type()?,
             )),
-            "pgm" => Ok(image::ImageOutputFormat::Pnm(
-                image::pnm::PNMSubtype::Graymap(self.pnm_encoding_type()?),
+            "pgm" => image::ImageOutputFormat::Pnm(image::pnm::PNMSubtype::Graymap(
+                self.pnm_encoding_type()?,
             )),
-            "png" => Ok(image::ImageOutputFormat::Png),
-            "ppm" => Ok(image::ImageOutputFormat::Pnm(
-                image::pnm::PNMSubtype::Pixmap(self.pnm_encoding_type()?),
+            "png" => image::ImageOutputFormat::Png,
+            "raw-rgba8" | "rgba" | "raw" => return Ok(EncodingFormat::RawRgba8),
+            "ppm" => image::ImageOutputFormat::Pnm(image::pnm::PNMSubtype::Pixmap(
+                self.pnm_encoding_type()?,
             )),
-            "tga" => Ok(image::ImageOutputFormat::Tga),
-            _ => Err(SicIoError::UnknownImageIdentifier(identifier.to_string())),
-        }
+            "tga" => image::ImageOutputFormat::Tga,
+            "tif" | "tiff" => return Ok(EncodingFormat::Tiff(self.tiff_compression()?)),
+            "exr" => return Ok(EncodingFormat::Exr),
+            "webp" => return Ok(EncodingFormat::WebP(self.webp_quality()?)),
+            _ => return Err(SicIoError::UnknownImageIdentifier(identifier.to_string())),
+        };
+
+        Ok(EncodingFormat::Image(format))
+    }
+}
+
+/// Returns the canonical file extension for an image output format identifier (the same
+/// identifiers as accepted by [EncodingFormatByIdentifier]), or `None` if the identifier is not
+/// recognized.
+pub fn canonical_extension(identifier: &str) -> Option<&'static str> {
+    match identifier.to_ascii_lowercase().as_str() {
+        "avif" => Some("avif"),
+        "bmp" => Some("bmp"),
+        "dds" => Some("dds"),
+        "exr" => Some("exr"),
+        "farbfeld" | "ff" => Some("ff"),
+        "gif" => Some("gif"),
+        "ico" => Some("ico"),
+        "jpeg" | "jpg" => Some("jpg"),
+        "pam" => Some("pam"),
+        "pbm" => Some("pbm"),
+        "pdf" => Some("pdf"),
+        "pgm" => Some("pgm"),
+        "png" => Some("png"),
+        "ppm" => Some("ppm"),
+        "raw-rgba8" | "rgba" | "raw" => Some("rgba"),
+        "tga" => Some("tga"),
+        "tif" | "tiff" => Some("tiff"),
+        "webp" => Some("webp"),
+        _ => None,
     }
 }
 
 pub struct DetermineEncodingFormat {
     pub pnm_sample_encoding: Option<image::pnm::SampleEncoding>,
     pub jpeg_quality: Option<JPEGQuality>,
+    pub jpeg_progressive: bool,
+    pub webp_quality: Option<WebPQuality>,
+    pub tiff_compression: Option<TiffCompression>,
+    pub dds_settings: Option<DdsSettings>,
+    pub gif_settings: Option<GifSettings>,
 }
 
 impl Default for DetermineEncodingFormat {
@@ -126,6 +392,11 @@ impl Default for DetermineEncodingFormat {
         Self {
             pnm_sample_encoding: Some(image::pnm::SampleEncoding::Binary),
             jpeg_quality: Some(Default::default()),
+            jpeg_progressive: false,
+            webp_quality: Some(Default::default()),
+            tiff_compression: Some(Default::default()),
+            dds_settings: Some(Default::default()),
+            gif_settings: Some(Default::default()),
         }
     }
 }
@@ -144,20 +415,48 @@ impl EncodingFormatJPEGQuality for DetermineEncodingFormat {
     }
 }
 
+impl EncodingFormatWebPQuality for DetermineEncodingFormat {
+    fn webp_quality(&self) -> Result<WebPQuality, SicIoError> {
+        self.webp_quality
+            .ok_or_else(|| SicIoError::FormatError(FormatError::WebPQualityLevelNotSet))
+    }
+}
+
+impl EncodingFormatTiffCompression for DetermineEncodingFormat {
+    fn tiff_compression(&self) -> Result<TiffCompression, SicIoError> {
+        self.tiff_compression
+            .ok_or_else(|| SicIoError::FormatError(FormatError::TiffCompressionNotSet))
+    }
+}
+
+impl EncodingFormatDdsSettings for DetermineEncodingFormat {
+    fn dds_settings(&self) -> Result<DdsSettings, SicIoError> {
+        self.dds_settings
+            .ok_or_else(|| SicIoError::FormatError(FormatError::DdsSettingsNotSet))
+    }
+}
+
+impl EncodingFormatGifSettings for DetermineEncodingFormat {
+    fn gif_settings(&self) -> Result<GifSettings, SicIoError> {
+        self.gif_settings
+            .ok_or_else(|| SicIoError::FormatError(FormatError::GifSettingsNotSet))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use parameterized::parameterized;
 
     const INPUT_FORMATS: &[&str] = &[
         //"avif",
-        "bmp", "farbfeld", "gif", "ico", "jpg", "jpeg", "png", "pbm", "pgm", "ppm", "pam", "tga",
+        "bmp", "farbfeld", "ico", "jpg", "jpeg", "png", "pbm", "pgm", "ppm", "pam", "tga",
     ];
 
     const EXPECTED_VALUES: &[image::ImageOutputFormat] = &[
         // image::ImageOutputFormat::Avif,
         image::ImageOutputFormat::Bmp,
         image::ImageOutputFormat::Farbfeld,
-        image::ImageOutputFormat::Gif,
         image::ImageOutputFormat::Ico,
         image::ImageOutputFormat::Jpeg(80),
         image::ImageOutputFormat::Jpeg(80),
@@ -179,6 +478,11 @@ mod tests {
         DetermineEncodingFormat {
             pnm_sample_encoding: Some(image::pnm::SampleEncoding::Binary),
             jpeg_quality: Some(JPEGQuality::try_from(80).unwrap()),
+            jpeg_progressive: false,
+            webp_quality: Some(WebPQuality::default()),
+            tiff_compression: Some(TiffCompression::default()),
+            dds_settings: Some(DdsSettings::default()),
+            gif_settings: Some(GifSettings::default()),
         }
     }
 
@@ -189,7 +493,7 @@ mod tests {
         let format_determiner = setup_default_format_determiner();
         let result = format_determiner.by_extension(path.as_str());
 
-        assert_eq!(result.unwrap(), *expected);
+        assert_eq!(result.unwrap(), EncodingFormat::Image(expected.clone()));
     }
 
     #[test]
@@ -239,7 +543,7 @@ mod tests {
         let format_determiner = setup_default_format_determiner();
         let result = format_determiner.by_identifier(identifier);
 
-        assert_eq!(result.unwrap(), *expected);
+        assert_eq!(result.unwrap(), EncodingFormat::Image(expected.clone()));
     }
 
     #[test]
@@ -278,6 +582,11 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: Some(image::pnm::SampleEncoding::Ascii),
             jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
         };
 
         let result = format_determiner.by_identifier("pbm").unwrap();
@@ -285,7 +594,7 @@ mod tests {
             image::pnm::SampleEncoding::Ascii,
         ));
 
-        assert_eq!(result, expected);
+        assert_eq!(result, EncodingFormat::Image(expected));
     }
 
     // non default: pnm ascii + "pgm"
@@ -294,6 +603,11 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: Some(image::pnm::SampleEncoding::Ascii),
             jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
         };
 
         let result = format_determiner.by_identifier("pgm").unwrap();
@@ -301,7 +615,7 @@ mod tests {
             image::pnm::SampleEncoding::Ascii,
         ));
 
-        assert_eq!(result, expected);
+        assert_eq!(result, EncodingFormat::Image(expected));
     }
 
     // non default: pnm ascii + "ppm"
@@ -310,6 +624,11 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: Some(image::pnm::SampleEncoding::Ascii),
             jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
         };
 
         let result = format_determiner.by_identifier("ppm").unwrap();
@@ -317,7 +636,7 @@ mod tests {
             image::pnm::SampleEncoding::Ascii,
         ));
 
-        assert_eq!(result, expected);
+        assert_eq!(result, EncodingFormat::Image(expected));
     }
 
     // non default: jpeg custom, quality lower bound
@@ -326,12 +645,17 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: Some(JPEGQuality::try_from(1).unwrap()),
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
         };
 
         let result = format_determiner.by_identifier("jpg").unwrap();
         let expected = image::ImageOutputFormat::Jpeg(1);
 
-        assert_eq!(result, expected);
+        assert_eq!(result, EncodingFormat::Image(expected));
     }
 
     // non default: jpeg custom, quality upper bound
@@ -340,12 +664,17 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: Some(JPEGQuality::try_from(100).unwrap()),
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
         };
 
         let result = format_determiner.by_identifier("jpg").unwrap();
         let expected = image::ImageOutputFormat::Jpeg(100);
 
-        assert_eq!(result, expected);
+        assert_eq!(result, EncodingFormat::Image(expected));
     }
 
     // if we were to test 'identifier_custom_jpeg_quality_OUT_range_[lower/upper]'
@@ -391,6 +720,256 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    // non default: webp custom quality, lossless
+    #[test]
+    fn identifier_custom_webp_quality() {
+        let format_determiner = DetermineEncodingFormat {
+            pnm_sample_encoding: None,
+            jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: Some(WebPQuality::try_from(100, true).unwrap()),
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
+        };
+
+        let result = format_determiner.by_identifier("webp").unwrap();
+        let expected = EncodingFormat::WebP(WebPQuality {
+            quality: 100,
+            lossless: true,
+        });
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn webp_by_extension() {
+        let result = setup_default_format_determiner()
+            .by_extension("w_ext.WEBP")
+            .unwrap();
+
+        assert_eq!(result, EncodingFormat::WebP(WebPQuality::default()));
+    }
+
+    #[parameterized(quality = { 0, 75, 100 })]
+    fn webp_quality_in_range(quality: u8) {
+        assert_eq!(
+            WebPQuality::try_from(quality, false).unwrap().as_u8(),
+            quality
+        );
+    }
+
+    #[test]
+    fn webp_quality_out_range_upper() {
+        assert!(WebPQuality::try_from(101, false).is_err());
+    }
+
+    // DetermineEncodingFormat has None, while Some required: webp
+    #[test]
+    fn identifier_requires_webp_quality_to_be_set() {
+        let format_determiner = DetermineEncodingFormat {
+            pnm_sample_encoding: None,
+            jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
+        };
+
+        assert!(format_determiner.by_identifier("webp").is_err());
+    }
+
+    #[parameterized(identifier = { "tif", "tiff", "TIFF" })]
+    fn tiff_by_identifier(identifier: &str) {
+        let format_determiner = DetermineEncodingFormat {
+            pnm_sample_encoding: None,
+            jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: Some(TiffCompression::Deflate),
+            dds_settings: None,
+            gif_settings: None,
+        };
+
+        let result = format_determiner.by_identifier(identifier).unwrap();
+
+        assert_eq!(result, EncodingFormat::Tiff(TiffCompression::Deflate));
+    }
+
+    #[parameterized(value = { "none", "lzw", "deflate", "packbits", "LZW" }, expected = {
+        TiffCompression::None,
+        TiffCompression::Lzw,
+        TiffCompression::Deflate,
+        TiffCompression::Packbits,
+        TiffCompression::Lzw,
+    })]
+    fn tiff_compression_from_str(value: &str, expected: TiffCompression) {
+        assert_eq!(TiffCompression::try_from_str(value).unwrap(), expected);
+    }
+
+    #[test]
+    fn tiff_compression_unknown() {
+        assert!(TiffCompression::try_from_str("jpeg").is_err());
+    }
+
+    #[test]
+    fn exr_by_extension() {
+        let result = setup_default_format_determiner()
+            .by_extension("w_ext.exr")
+            .unwrap();
+
+        assert_eq!(result, EncodingFormat::Exr);
+    }
+
+    #[test]
+    fn pdf_by_identifier() {
+        let result = setup_default_format_determiner()
+            .by_identifier("PDF")
+            .unwrap();
+
+        assert_eq!(result, EncodingFormat::Pdf);
+    }
+
+    #[test]
+    fn progressive_jpeg_by_identifier() {
+        let format_determiner = DetermineEncodingFormat {
+            jpeg_progressive: true,
+            ..setup_default_format_determiner()
+        };
+
+        for identifier in &["jpg", "jpeg"] {
+            assert_eq!(
+                format_determiner.by_identifier(identifier).unwrap(),
+                EncodingFormat::ProgressiveJpeg(JPEGQuality::try_from(80).unwrap())
+            );
+        }
+    }
+
+    #[parameterized(identifier = { "raw-rgba8", "RGBA", "raw" })]
+    fn raw_rgba8_by_identifier(identifier: &str) {
+        let result = setup_default_format_determiner()
+            .by_identifier(identifier)
+            .unwrap();
+
+        assert_eq!(result, EncodingFormat::RawRgba8);
+    }
+
+    // DetermineEncodingFormat has None, while Some required: tiff
+    #[test]
+    fn identifier_requires_tiff_compression_to_be_set() {
+        let format_determiner = DetermineEncodingFormat {
+            pnm_sample_encoding: None,
+            jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
+        };
+
+        assert!(format_determiner.by_identifier("tiff").is_err());
+    }
+
+    #[test]
+    fn dds_by_identifier() {
+        let settings = DdsSettings {
+            compression: DdsCompression::Bc3,
+            mipmaps: true,
+        };
+        let format_determiner = DetermineEncodingFormat {
+            pnm_sample_encoding: None,
+            jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: Some(settings),
+            gif_settings: None,
+        };
+
+        let result = format_determiner.by_identifier("DDS").unwrap();
+
+        assert_eq!(result, EncodingFormat::Dds(settings));
+    }
+
+    #[parameterized(value = { "none", "bc1", "BC3", "dxt1", "dxt5" }, expected = {
+        DdsCompression::None,
+        DdsCompression::Bc1,
+        DdsCompression::Bc3,
+        DdsCompression::Bc1,
+        DdsCompression::Bc3,
+    })]
+    fn dds_compression_from_str(value: &str, expected: DdsCompression) {
+        assert_eq!(DdsCompression::try_from_str(value).unwrap(), expected);
+    }
+
+    #[test]
+    fn dds_compression_unknown() {
+        assert!(DdsCompression::try_from_str("bc7").is_err());
+    }
+
+    // DetermineEncodingFormat has None, while Some required: dds
+    #[test]
+    fn identifier_requires_dds_settings_to_be_set() {
+        let format_determiner = DetermineEncodingFormat {
+            pnm_sample_encoding: None,
+            jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
+        };
+
+        assert!(format_determiner.by_identifier("dds").is_err());
+    }
+
+    #[test]
+    fn gif_by_identifier() {
+        let settings = GifSettings::try_from(GifPalette::Local, 16).unwrap();
+        let format_determiner = DetermineEncodingFormat {
+            gif_settings: Some(settings),
+            ..setup_default_format_determiner()
+        };
+
+        let result = format_determiner.by_extension("w_ext.gif").unwrap();
+
+        assert_eq!(result, EncodingFormat::Gif(settings));
+        assert_eq!(settings.palette(), GifPalette::Local);
+        assert_eq!(settings.colors(), 16);
+    }
+
+    #[parameterized(colors = { 0, 1, 2, 256, 257 }, valid = { false, false, true, true, false })]
+    fn gif_settings_colors_range(colors: u16, valid: bool) {
+        let settings = GifSettings::try_from(GifPalette::Global, colors);
+
+        assert_eq!(settings.is_ok(), valid);
+    }
+
+    #[test]
+    fn gif_palette_from_str() {
+        assert_eq!(
+            GifPalette::try_from_str("Global").unwrap(),
+            GifPalette::Global
+        );
+        assert_eq!(
+            GifPalette::try_from_str("local").unwrap(),
+            GifPalette::Local
+        );
+        assert!(GifPalette::try_from_str("adaptive").is_err());
+    }
+
+    // DetermineEncodingFormat has None, while Some required: gif
+    #[test]
+    fn identifier_requires_gif_settings_to_be_set() {
+        let format_determiner = DetermineEncodingFormat {
+            gif_settings: None,
+            ..setup_default_format_determiner()
+        };
+
+        assert!(format_determiner.by_identifier("gif").is_err());
+    }
+
     // DetermineEncodingFormat has None, while Some required: pbm
     #[test]
     #[should_panic]
@@ -398,6 +977,11 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
         };
 
         format_determiner.by_identifier("pbm").unwrap();
@@ -410,6 +994,11 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
         };
 
         format_determiner.by_identifier("pgm").unwrap();
@@ -422,6 +1011,11 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
         };
 
         format_determiner.by_identifier("ppm").unwrap();
@@ -434,8 +1028,32 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: None,
+            jpeg_progressive: false,
+            webp_quality: None,
+            tiff_compression: None,
+            dds_settings: None,
+            gif_settings: None,
         };
 
         format_determiner.by_identifier("jpg").unwrap();
     }
+
+    #[test]
+    fn canonical_extensions() {
+        assert_eq!(canonical_extension("jpeg"), Some("jpg"));
+        assert_eq!(canonical_extension("JPG"), Some("jpg"));
+        assert_eq!(canonical_extension("farbfeld"), Some("ff"));
+        assert_eq!(canonical_extension("png"), Some("png"));
+        assert_eq!(canonical_extension("WebP"), Some("webp"));
+        assert_eq!(canonical_extension("tif"), Some("tiff"));
+        assert_eq!(canonical_extension("DDS"), Some("dds"));
+        assert_eq!(canonical_extension("pdf"), Some("pdf"));
+        assert_eq!(canonical_extension("raw-rgba8"), Some("rgba"));
+        assert_eq!(canonical_extension("raw"), Some("rgba"));
+        assert_eq!(canonical_extension("jpeg2000"), None);
+
+        for format in INPUT_FORMATS {
+            assert!(canonical_extension(format).is_some());
+        }
+    }
 }
@@ -0,0 +1,788 @@
+//! GIF images are written with the gif crate directly: the GIF encoder of the image crate can't
+//! make an animation loop, and stores each frame in full, with a palette of its own. Instead, the
+//! frames are optimized before they're encoded:
+//!
+//! * frames which are identical to the previous frame are merged into it, adding up their delays;
+//! * only the area which changed since the previous frame is stored, and the pixels within that
+//!   area which didn't change are stored as transparent pixels, which compress well;
+//! * unless local palettes are requested, all frames share a single global palette.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::Write;
+
+use color_quant::NeuQuant;
+use sic_core::animation::Repeat;
+use sic_core::image::{imageops, Delay, Frame, Rgba, RgbaImage};
+
+use crate::errors::SicIoError;
+use crate::format::{GifPalette, GifSettings};
+
+/// The maximum number of pixels from which a palette is chosen; if there are more pixels, the
+/// palette is chosen from an evenly spread sample.
+const MAX_PALETTE_SAMPLES: usize = 1 << 18;
+
+const TRANSPARENT: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+/// Encodes the frames as GIF. If there is more than one frame, the animation is repeated as given;
+/// without a repeat count, it's written without the NETSCAPE extension, so it's shown once.
+pub(crate) fn encode<W: Write>(
+    writer: &mut W,
+    frames: &[Frame],
+    repeat: Option<Repeat>,
+    settings: GifSettings,
+) -> Result<(), SicIoError> {
+    let (width, height) = screen_size(frames);
+    let (screen_width, screen_height) = gif_dimensions(width, height)?;
+
+    let screens = merge_identical(
+        frames
+            .iter()
+            .map(|frame| Screen::new(frame, width, height))
+            .collect(),
+    );
+    let patches = patches(&screens);
+
+    let global = match settings.palette() {
+        GifPalette::Global => Some(global_palette(&screens, settings)),
+        GifPalette::Local => None,
+    };
+    let global_colors = global.as_ref().map(Palette::colors).unwrap_or_default();
+
+    let mut encoder = gif::Encoder::new(writer, screen_width, screen_height, &global_colors)
+        .map_err(SicIoError::GifEncodingError)?;
+
+    let repeat = match repeat {
+        _ if screens.len() < 2 => None,
+        // a loop count of 0 would make the animation loop forever instead
+        None | Some(Repeat::Finite(0)) => None,
+        Some(Repeat::Finite(count)) => Some(gif::Repeat::Finite(count)),
+        Some(Repeat::Infinite) => Some(gif::Repeat::Infinite),
+    };
+
+    if let Some(repeat) = repeat {
+        encoder
+            .set_repeat(repeat)
+            .map_err(SicIoError::GifEncodingError)?;
+    }
+
+    for (index, (screen, patch)) in screens.iter().zip(&patches).enumerate() {
+        let previous = if patch.over_previous {
+            Some(&screens[index - 1])
+        } else {
+            None
+        };
+        let pixels = stored_pixels(screen, previous, patch.area);
+
+        let frame = match &global {
+            Some(palette) => patch.encode(screen.delay, &pixels, palette, None),
+            None => {
+                let palette = local_palette(&pixels, settings);
+                patch.encode(screen.delay, &pixels, &palette, Some(palette.colors()))
+            }
+        };
+
+        encoder
+            .write_frame(&frame)
+            .map_err(SicIoError::GifEncodingError)?;
+    }
+
+    Ok(())
+}
+
+/// A frame as it is shown on the screen, with its delay in hundredths of a second. Pixels are
+/// either opaque or transparent, since GIF doesn't support partial transparency.
+struct Screen {
+    pixels: RgbaImage,
+    delay: u16,
+}
+
+impl Screen {
+    fn new(frame: &Frame, width: u32, height: u32) -> Self {
+        let mut pixels = RgbaImage::from_pixel(width, height, TRANSPARENT);
+        imageops::replace(&mut pixels, frame.buffer(), frame.left(), frame.top());
+
+        for pixel in pixels.pixels_mut() {
+            *pixel = match pixel.0 {
+                [_, _, _, 0] => TRANSPARENT,
+                [r, g, b, _] => Rgba([r, g, b, 255]),
+            };
+        }
+
+        Self {
+            pixels,
+            delay: centiseconds(frame.delay()),
+        }
+    }
+}
+
+/// Merges frames which are identical to the previous frame into that frame.
+fn merge_identical(screens: Vec<Screen>) -> Vec<Screen> {
+    let mut merged: Vec<Screen> = Vec::with_capacity(screens.len());
+
+    for screen in screens {
+        match merged.last_mut() {
+            Some(last) if last.pixels == screen.pixels => {
+                last.delay = last.delay.saturating_add(screen.delay)
+            }
+            _ => merged.push(screen),
+        }
+    }
+
+    merged
+}
+
+/// A rectangular area of the screen.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Area {
+    left: u32,
+    top: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Area {
+    fn of(image: &RgbaImage) -> Self {
+        Self {
+            left: 0,
+            top: 0,
+            width: image.width(),
+            height: image.height(),
+        }
+    }
+
+    /// The smallest area which contains all pixels for which the predicate holds, or the top left
+    /// pixel if there are no such pixels.
+    fn bounding<P: Fn(u32, u32) -> bool>(image: &RgbaImage, predicate: P) -> Self {
+        let bounds = (0..image.height())
+            .flat_map(|y| (0..image.width()).map(move |x| (x, y)))
+            .filter(|&(x, y)| predicate(x, y))
+            .fold(None, |bounds, (x, y)| match bounds {
+                None => Some((x, y, x, y)),
+                Some((left, top, right, bottom)) => {
+                    Some((left.min(x), top.min(y), right.max(x), bottom.max(y)))
+                }
+            });
+
+        match bounds {
+            Some((left, top, right, bottom)) => Self {
+                left,
+                top,
+                width: right - left + 1,
+                height: bottom - top + 1,
+            },
+            None => Self {
+                left: 0,
+                top: 0,
+                width: 1,
+                height: 1,
+            },
+        }
+    }
+
+    fn pixels(self) -> impl Iterator<Item = (u32, u32)> {
+        let Area {
+            left,
+            top,
+            width,
+            height,
+        } = self;
+
+        (top..top + height).flat_map(move |y| (left..left + width).map(move |x| (x, y)))
+    }
+}
+
+/// The part of a frame which is stored.
+#[derive(Debug, Eq, PartialEq)]
+struct Patch {
+    area: Area,
+    /// Whether the frame is drawn over the previous frame, in which case the pixels which didn't
+    /// change are stored as transparent pixels.
+    over_previous: bool,
+    /// What happens to the frame once its delay has passed.
+    dispose: gif::DisposalMethod,
+}
+
+impl Patch {
+    fn encode(
+        &self,
+        delay: u16,
+        pixels: &[Option<[u8; 3]>],
+        palette: &Palette,
+        local_colors: Option<Vec<u8>>,
+    ) -> gif::Frame<'static> {
+        let transparent = palette.transparent_index();
+        let buffer = pixels
+            .iter()
+            .map(|pixel| match (pixel, transparent) {
+                (None, Some(index)) => index,
+                (Some(color), _) => palette.index_of(*color),
+                (None, None) => 0,
+            })
+            .collect::<Vec<_>>();
+
+        // the area fits, since it is within the screen
+        gif::Frame {
+            left: self.area.left as u16,
+            top: self.area.top as u16,
+            width: self.area.width as u16,
+            height: self.area.height as u16,
+            delay,
+            dispose: self.dispose,
+            transparent,
+            palette: local_colors,
+            buffer: Cow::Owned(buffer),
+            ..gif::Frame::default()
+        }
+    }
+}
+
+/// Decides which area of each frame is stored, and what happens to the frame once the next frame
+/// is shown. A frame is usually drawn over the previous frame, so only the area which changed has
+/// to be stored. This doesn't work if pixels become transparent: the previous frame is then stored
+/// in full and cleared afterwards, and only the opaque area of the frame is stored. Since the
+/// animation may loop, the last frame is followed by the first frame.
+fn patches(screens: &[Screen]) -> Vec<Patch> {
+    let count = screens.len();
+    let clears = (0..count)
+        .map(|i| count > 1 && becomes_transparent(&screens[i], &screens[(i + 1) % count]))
+        .collect::<Vec<_>>();
+
+    (0..count)
+        .map(|i| {
+            let current = &screens[i].pixels;
+            let over_previous = i > 0 && !clears[i - 1];
+
+            let area = if i == 0 || clears[i] {
+                Area::of(current)
+            } else if over_previous {
+                let previous = &screens[i - 1].pixels;
+                Area::bounding(current, |x, y| {
+                    current.get_pixel(x, y) != previous.get_pixel(x, y)
+                })
+            } else {
+                Area::bounding(current, |x, y| current.get_pixel(x, y)[3] != 0)
+            };
+
+            let dispose = if clears[i] {
+                gif::DisposalMethod::Background
+            } else {
+                gif::DisposalMethod::Keep
+            };
+
+            Patch {
+                area,
+                over_previous,
+                dispose,
+            }
+        })
+        .collect()
+}
+
+fn becomes_transparent(current: &Screen, next: &Screen) -> bool {
+    current
+        .pixels
+        .pixels()
+        .zip(next.pixels.pixels())
+        .any(|(current, next)| current[3] != 0 && next[3] == 0)
+}
+
+/// The colors of the pixels of the area which are stored, in order; `None` for transparent pixels,
+/// and for pixels which are the same as in the previous frame, if the frame is drawn over it.
+fn stored_pixels(screen: &Screen, previous: Option<&Screen>, area: Area) -> Vec<Option<[u8; 3]>> {
+    area.pixels()
+        .map(|(x, y)| {
+            let pixel = screen.pixels.get_pixel(x, y);
+            let unchanged =
+                previous.is_some_and(|previous| previous.pixels.get_pixel(x, y) == pixel);
+
+            match pixel.0 {
+                [_, _, _, 0] => None,
+                _ if unchanged => None,
+                [r, g, b, _] => Some([r, g, b]),
+            }
+        })
+        .collect()
+}
+
+/// A palette of at most 256 colors, of which the last one is the transparent color, if needed.
+enum Palette {
+    /// All colors fit in the palette, so they're represented exactly.
+    Exact {
+        colors: Vec<[u8; 3]>,
+        indices: HashMap<[u8; 3], u8>,
+        transparent: bool,
+    },
+    /// The colors are approximated by a neural network.
+    Approximate {
+        quantizer: NeuQuant,
+        size: usize,
+        transparent: bool,
+    },
+}
+
+impl Palette {
+    /// Chooses a palette of at most `colors` colors, including the transparent color, if needed.
+    fn new(colors: &[[u8; 3]], settings: GifSettings, transparent: bool) -> Self {
+        let size = usize::from(settings.colors()) - usize::from(transparent);
+        let mut palette = Vec::new();
+        let mut indices = HashMap::new();
+
+        for &color in colors {
+            if indices.contains_key(&color) {
+                continue;
+            }
+
+            if palette.len() == size {
+                let pixels = colors
+                    .iter()
+                    .flat_map(|&[r, g, b]| vec![r, g, b, 255])
+                    .collect::<Vec<_>>();
+
+                return Palette::Approximate {
+                    quantizer: NeuQuant::new(1, size, &pixels),
+                    size,
+                    transparent,
+                };
+            }
+
+            indices.insert(color, palette.len() as u8);
+            palette.push(color);
+        }
+
+        Palette::Exact {
+            colors: palette,
+            indices,
+            transparent,
+        }
+    }
+
+    /// The colors of the palette as RGB triples, followed by the transparent color, if any.
+    fn colors(&self) -> Vec<u8> {
+        let mut colors = match self {
+            Palette::Exact { colors, .. } => colors.iter().flatten().copied().collect(),
+            Palette::Approximate { quantizer, .. } => quantizer.color_map_rgb(),
+        };
+
+        if self.transparent_index().is_some() {
+            colors.extend_from_slice(&[0, 0, 0]);
+        }
+
+        colors
+    }
+
+    fn transparent_index(&self) -> Option<u8> {
+        match self {
+            Palette::Exact {
+                colors,
+                transparent: true,
+                ..
+            } => Some(colors.len() as u8),
+            Palette::Approximate {
+                size,
+                transparent: true,
+                ..
+            } => Some(*size as u8),
+            _ => None,
+        }
+    }
+
+    fn index_of(&self, color: [u8; 3]) -> u8 {
+        match self {
+            Palette::Exact {
+                colors, indices, ..
+            } => indices
+                .get(&color)
+                .copied()
+                .unwrap_or_else(|| nearest(colors, color)),
+            Palette::Approximate { quantizer, .. } => {
+                let [r, g, b] = color;
+                quantizer.index_of(&[r, g, b, 255]) as u8
+            }
+        }
+    }
+}
+
+// Colors which weren't sampled when the palette was chosen are represented by the closest color.
+fn nearest(colors: &[[u8; 3]], color: [u8; 3]) -> u8 {
+    let distance = |candidate: &[u8; 3]| -> u32 {
+        candidate
+            .iter()
+            .zip(&color)
+            .map(|(&a, &b)| (i32::from(a) - i32::from(b)).pow(2) as u32)
+            .sum()
+    };
+
+    colors
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, candidate)| distance(candidate))
+        .map_or(0, |(index, _)| index as u8)
+}
+
+/// The palette which is shared by all frames, chosen from the colors of all frames. A transparent
+/// color is needed if any frame is transparent, or drawn over the previous frame.
+fn global_palette(screens: &[Screen], settings: GifSettings) -> Palette {
+    let pixels = screens.iter().flat_map(|screen| screen.pixels.pixels());
+    let count = screens.iter().map(|screen| screen.pixels.len() / 4).sum();
+
+    let colors = sample(
+        pixels
+            .filter(|pixel| pixel[3] != 0)
+            .map(|pixel| [pixel[0], pixel[1], pixel[2]]),
+        count,
+    );
+    let transparent = screens.len() > 1
+        || screens
+            .iter()
+            .any(|screen| screen.pixels.pixels().any(|pixel| pixel[3] == 0));
+
+    Palette::new(&colors, settings, transparent)
+}
+
+/// The palette of a single frame, chosen from the colors of its stored pixels only.
+fn local_palette(pixels: &[Option<[u8; 3]>], settings: GifSettings) -> Palette {
+    let colors = sample(pixels.iter().flatten().copied(), pixels.len());
+    let transparent = pixels.iter().any(Option::is_none);
+
+    Palette::new(&colors, settings, transparent)
+}
+
+/// At most [MAX_PALETTE_SAMPLES] of the colors, evenly spread, given at most `count` colors.
+fn sample<I: Iterator<Item = [u8; 3]>>(colors: I, count: usize) -> Vec<[u8; 3]> {
+    colors.step_by(count / MAX_PALETTE_SAMPLES + 1).collect()
+}
+
+/// The size of the area which covers all frames.
+fn screen_size(frames: &[Frame]) -> (u32, u32) {
+    frames.iter().fold((0, 0), |(width, height), frame| {
+        (
+            width.max(frame.left().saturating_add(frame.buffer().width())),
+            height.max(frame.top().saturating_add(frame.buffer().height())),
+        )
+    })
+}
+
+fn gif_dimensions(width: u32, height: u32) -> Result<(u16, u16), SicIoError> {
+    match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(w), Ok(h)) => Ok((w, h)),
+        _ => Err(SicIoError::GifDimensionsTooLarge(width, height)),
+    }
+}
+
+// GIF delays are given in hundredths of a second.
+fn centiseconds(delay: Delay) -> u16 {
+    let (numerator, denominator) = delay.numer_denom_ms();
+    let centiseconds = f64::from(numerator) / f64::from(denominator.max(1)) / 10.0;
+
+    centiseconds.round().min(f64::from(u16::MAX)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::gif::{GifDecoder, GifEncoder};
+    use sic_core::image::AnimationDecoder;
+    use sic_testing::setup_test_image;
+    use std::collections::HashSet;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    fn frame(buffer: RgbaImage, delay_ms: u32) -> Frame {
+        Frame::from_parts(buffer, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1))
+    }
+
+    fn filled(color: Rgba<u8>, delay_ms: u32) -> Frame {
+        frame(RgbaImage::from_pixel(8, 8, color), delay_ms)
+    }
+
+    fn encoded_with(frames: &[Frame], settings: GifSettings) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        encode(&mut bytes, frames, None, settings).unwrap();
+        bytes
+    }
+
+    fn encoded(frames: &[Frame]) -> Vec<u8> {
+        encoded_with(frames, GifSettings::default())
+    }
+
+    fn encoded_repeating(frames: &[Frame], repeat: Repeat) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        encode(&mut bytes, frames, Some(repeat), GifSettings::default()).unwrap();
+        bytes
+    }
+
+    /// The loop count sub-block of the NETSCAPE application extension, if any.
+    fn loop_count(bytes: &[u8]) -> Option<&[u8]> {
+        bytes
+            .windows(11)
+            .position(|window| window == b"NETSCAPE2.0")
+            .map(|position| &bytes[position + 11..position + 15])
+    }
+
+    fn decoded(bytes: &[u8]) -> Vec<Frame> {
+        GifDecoder::new(bytes)
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap()
+    }
+
+    /// The frames as stored, without applying them to the screen.
+    fn raw_frames(bytes: &[u8]) -> Vec<gif::Frame<'static>> {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(bytes).unwrap();
+
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames.push(frame.clone());
+        }
+        frames
+    }
+
+    fn buffers(frames: &[Frame]) -> Vec<RgbaImage> {
+        frames.iter().map(|frame| frame.buffer().clone()).collect()
+    }
+
+    #[test]
+    fn frames_and_delays() {
+        let bytes = encoded(&[filled(RED, 100), filled(BLUE, 250)]);
+        let frames = decoded(&bytes);
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].buffer(), &RgbaImage::from_pixel(8, 8, RED));
+        assert_eq!(frames[1].buffer(), &RgbaImage::from_pixel(8, 8, BLUE));
+        assert_eq!(frames[0].delay().numer_denom_ms(), (100, 1));
+        assert_eq!(frames[1].delay().numer_denom_ms(), (250, 1));
+    }
+
+    #[test]
+    fn loops_forever() {
+        let bytes = encoded_repeating(&[filled(RED, 100), filled(BLUE, 100)], Repeat::Infinite);
+
+        // a loop count of 0 makes the animation loop forever
+        assert_eq!(loop_count(&bytes), Some(&[3, 1, 0, 0][..]));
+    }
+
+    #[test]
+    fn repeats_finite() {
+        let bytes = encoded_repeating(&[filled(RED, 100), filled(BLUE, 100)], Repeat::Finite(2));
+
+        assert_eq!(loop_count(&bytes), Some(&[3, 1, 2, 0][..]));
+    }
+
+    #[test]
+    fn shown_once_does_not_loop() {
+        let bytes = encoded_repeating(&[filled(RED, 100), filled(BLUE, 100)], Repeat::Finite(0));
+
+        assert_eq!(loop_count(&bytes), None);
+    }
+
+    #[test]
+    fn without_repeat_does_not_loop() {
+        let bytes = encoded(&[filled(RED, 100), filled(BLUE, 100)]);
+
+        assert_eq!(loop_count(&bytes), None);
+        assert_eq!(decoded(&bytes).len(), 2);
+    }
+
+    #[test]
+    fn still_image_does_not_loop() {
+        let bytes = encoded_repeating(&[filled(RED, 0)], Repeat::Infinite);
+
+        assert_eq!(loop_count(&bytes), None);
+        assert_eq!(
+            buffers(&decoded(&bytes)),
+            vec![RgbaImage::from_pixel(8, 8, RED)]
+        );
+    }
+
+    #[test]
+    fn identical_frames_are_merged() {
+        let bytes = encoded(&[filled(RED, 100), filled(RED, 50), filled(BLUE, 100)]);
+        let frames = decoded(&bytes);
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].delay().numer_denom_ms(), (150, 1));
+        assert_eq!(frames[1].buffer(), &RgbaImage::from_pixel(8, 8, BLUE));
+    }
+
+    #[test]
+    fn only_the_changed_area_is_stored() {
+        let mut changed = RgbaImage::from_pixel(8, 8, RED);
+        for (x, y) in &[(3, 4), (4, 4), (3, 5), (4, 6)] {
+            changed.put_pixel(*x, *y, BLUE);
+        }
+
+        let frames = [filled(RED, 100), frame(changed.clone(), 100)];
+        let bytes = encoded(&frames);
+        let raw = raw_frames(&bytes);
+
+        assert_eq!(
+            (raw[1].left, raw[1].top, raw[1].width, raw[1].height),
+            (3, 4, 2, 3)
+        );
+        // the unchanged pixel within the area is transparent
+        assert_eq!(Some(raw[1].buffer[3]), raw[1].transparent);
+        assert_eq!(raw[1].dispose, gif::DisposalMethod::Keep);
+
+        assert_eq!(buffers(&decoded(&bytes)), buffers(&frames));
+    }
+
+    #[test]
+    fn pixels_which_become_transparent() {
+        let mut hole = RgbaImage::from_pixel(8, 8, RED);
+        hole.put_pixel(2, 2, TRANSPARENT);
+
+        let frames = [filled(RED, 100), frame(hole, 100), filled(BLUE, 100)];
+        let bytes = encoded(&frames);
+        let raw = raw_frames(&bytes);
+
+        assert_eq!(raw[0].dispose, gif::DisposalMethod::Background);
+        // the last frame is followed by the first frame, which isn't transparent
+        assert_eq!(raw[2].dispose, gif::DisposalMethod::Keep);
+        assert_eq!(buffers(&decoded(&bytes)), buffers(&frames));
+    }
+
+    #[test]
+    fn global_palette() {
+        let bytes = encoded(&[filled(RED, 100), filled(BLUE, 100)]);
+
+        assert!(raw_frames(&bytes)
+            .iter()
+            .all(|frame| frame.palette.is_none()));
+    }
+
+    #[test]
+    fn local_palettes() {
+        let frames = [filled(RED, 100), filled(BLUE, 100)];
+        let settings = GifSettings::try_from(GifPalette::Local, 256).unwrap();
+        let bytes = encoded_with(&frames, settings);
+
+        assert!(raw_frames(&bytes)
+            .iter()
+            .all(|frame| frame.palette.is_some()));
+        assert_eq!(buffers(&decoded(&bytes)), buffers(&frames));
+    }
+
+    fn gradient() -> RgbaImage {
+        RgbaImage::from_fn(16, 2, |x, _| {
+            Rgba([x as u8 * 16, 0, 255 - x as u8 * 16, 255])
+        })
+    }
+
+    #[test]
+    fn colors_are_exact_if_they_fit() {
+        let frames = [frame(gradient(), 0)];
+        let bytes = encoded(&frames);
+
+        assert_eq!(buffers(&decoded(&bytes)), buffers(&frames));
+    }
+
+    #[parameterized::parameterized(palette = { GifPalette::Global, GifPalette::Local })]
+    fn fewer_colors(palette: GifPalette) {
+        let frames = [frame(gradient(), 100), filled(RED, 100)];
+        let settings = GifSettings::try_from(palette, 4).unwrap();
+        let bytes = encoded_with(&frames, settings);
+
+        let first = decoded(&bytes).remove(0).into_buffer();
+        let colors = first.pixels().collect::<HashSet<_>>();
+        // one of the colors is the transparent color
+        assert!(colors.len() <= 3);
+    }
+
+    #[test]
+    fn two_colors_with_transparency() {
+        let frames = [frame(gradient(), 100), frame(RgbaImage::new(16, 2), 100)];
+        let settings = GifSettings::try_from(GifPalette::Global, 2).unwrap();
+        let bytes = encoded_with(&frames, settings);
+
+        let decoded = decoded(&bytes);
+        assert_eq!(
+            decoded[0].buffer().pixels().collect::<HashSet<_>>().len(),
+            1
+        );
+        assert_eq!(decoded[1].buffer(), &RgbaImage::new(16, 2));
+    }
+
+    fn loop_gif() -> Vec<Frame> {
+        let file = std::fs::File::open(setup_test_image("loop.gif")).unwrap();
+        GifDecoder::new(file)
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap()
+    }
+
+    // the frames have fewer than 256 colors each, but not together
+    #[test]
+    fn animation_roundtrip_with_local_palettes() {
+        let frames = loop_gif();
+        let settings = GifSettings::try_from(GifPalette::Local, 256).unwrap();
+        let bytes = encoded_with(&frames, settings);
+
+        assert_eq!(buffers(&decoded(&bytes)), buffers(&frames));
+    }
+
+    #[test]
+    fn smaller_than_frames_stored_in_full() {
+        let frames = loop_gif();
+
+        let mut naive = Vec::new();
+        GifEncoder::new(&mut naive)
+            .encode_frames(frames.clone())
+            .unwrap();
+
+        assert!(encoded(&frames).len() < naive.len());
+    }
+
+    #[test]
+    fn screen_covers_all_frames() {
+        let frames = vec![
+            Frame::from_parts(RgbaImage::new(3, 2), 0, 0, Delay::from_numer_denom_ms(0, 1)),
+            Frame::from_parts(RgbaImage::new(2, 4), 2, 1, Delay::from_numer_denom_ms(0, 1)),
+        ];
+
+        assert_eq!(screen_size(&frames), (4, 5));
+    }
+
+    #[test]
+    fn bounding_area_without_pixels() {
+        let image = RgbaImage::new(4, 4);
+
+        assert_eq!(
+            Area::bounding(&image, |_, _| false),
+            Area {
+                left: 0,
+                top: 0,
+                width: 1,
+                height: 1
+            }
+        );
+    }
+
+    #[test]
+    fn delay_rounding() {
+        assert_eq!(centiseconds(Delay::from_numer_denom_ms(104, 1)), 10);
+        assert_eq!(centiseconds(Delay::from_numer_denom_ms(105, 1)), 11);
+        assert_eq!(
+            centiseconds(Delay::from_numer_denom_ms(u32::MAX, 1)),
+            u16::MAX
+        );
+    }
+
+    #[test]
+    fn too_large() {
+        let frames = vec![Frame::new(RgbaImage::new(70_000, 1))];
+        let mut bytes = Vec::new();
+        let result = encode(&mut bytes, &frames, None, GifSettings::default());
+
+        assert!(matches!(
+            result,
+            Err(SicIoError::GifDimensionsTooLarge(70_000, 1))
+        ));
+    }
+}
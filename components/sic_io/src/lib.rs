@@ -4,8 +4,17 @@
 pub mod load;
 
 // exporting
+pub mod deep_zoom;
 pub mod save;
+pub mod sprite_sheet;
 
 pub mod conversion;
+pub(crate) mod dds;
 pub mod errors;
 pub mod format;
+pub(crate) mod gif_encoder;
+pub mod openexr;
+pub mod pdf;
+pub mod psd;
+pub(crate) mod radiance;
+pub(crate) mod repeat;
@@ -3,6 +3,12 @@ use std::io::{BufReader, Cursor, Read};
 use std::path::Path;
 
 use crate::errors::SicIoError;
+use crate::openexr;
+use crate::psd;
+use crate::radiance;
+use crate::repeat;
+use sic_core::animation::{AnimatedImage, Repeat};
+use sic_core::hdr::{self, Rgba32FImage};
 use sic_core::image;
 use sic_core::image::{AnimationDecoder, ImageFormat};
 
@@ -12,20 +18,195 @@ pub fn load_image<R: Read>(
     reader: &mut R,
     config: &ImportConfig,
 ) -> ImportResult<image::DynamicImage> {
-    let reader = image::io::Reader::new(Cursor::new(load(reader)?))
+    load_image_with_frame_count(reader, config).map(|(image, _)| image)
+}
+
+/// Load an image using a reader, together with the number of frames of the image it was
+/// selected from. Images which are not animated have a single frame.
+pub fn load_image_with_frame_count<R: Read>(
+    reader: &mut R,
+    config: &ImportConfig,
+) -> ImportResult<(image::DynamicImage, usize)> {
+    decode_image(reader, config).map(|decoded| (decoded.image, decoded.frames))
+}
+
+/// An image as loaded by [decode_image].
+pub struct DecodedImage {
+    pub image: image::DynamicImage,
+    /// The number of frames of the image it was selected from.
+    pub frames: usize,
+    /// The unclamped samples of the image, if it is a high dynamic range image.
+    pub hdr: Option<Rgba32FImage>,
+    /// All frames of the image, if it is an animated image and [ImportConfig::keep_animation] is
+    /// set.
+    pub animation: Option<AnimatedImage>,
+}
+
+/// Load an image using a reader, like [load_image_with_frame_count]. High dynamic range images
+/// are also loaded with their unclamped samples.
+pub fn decode_image<R: Read>(reader: &mut R, config: &ImportConfig) -> ImportResult<DecodedImage> {
+    if let Some(format) = config.forced_format {
+        return decode_forced(reader, format).map(still_image);
+    }
+
+    let reader = guess_format(reader)?;
+    let format = reader.format();
+
+    match format {
+        Some(ImageFormat::Png) => decode_png(reader, config),
+        Some(ImageFormat::Gif) => decode_gif(reader, config),
+        Some(ImageFormat::Hdr) => decode_radiance(reader.into_inner()),
+        Some(_) => reader
+            .decode()
+            .map(still_image)
+            .map_err(SicIoError::ImageError),
+        None => decode_unrecognized(reader.into_inner()),
+    }
+    .map_err(|err| explain_format_mismatch(err, config.format_hint, format))
+}
+
+/// Load all frames of an image using a reader.
+/// Images which are not animated are loaded as a single frame without delay.
+pub fn load_frames<R: Read>(
+    reader: &mut R,
+    config: &ImportConfig,
+) -> ImportResult<Vec<image::Frame>> {
+    if let Some(format) = config.forced_format {
+        return decode_forced(reader, format).map(|image| vec![image::Frame::new(image.to_rgba())]);
+    }
+
+    let reader = guess_format(reader)?;
+    let format = reader.format();
+
+    match format {
+        Some(ImageFormat::Png) => {
+            let decoder =
+                image::png::PngDecoder::new(reader.into_inner()).map_err(SicIoError::ImageError)?;
+
+            if decoder.is_apng() {
+                frames(decoder.apng())
+            } else {
+                image::DynamicImage::from_decoder(decoder)
+                    .map(|image| vec![image::Frame::new(image.to_rgba())])
+                    .map_err(SicIoError::ImageError)
+            }
+        }
+        Some(ImageFormat::Gif) => image::gif::GifDecoder::new(reader.into_inner())
+            .map_err(SicIoError::ImageError)
+            .and_then(frames),
+        Some(ImageFormat::Hdr) => decode_radiance(reader.into_inner())
+            .map(|decoded| vec![image::Frame::new(decoded.image.to_rgba())]),
+        Some(_) => reader
+            .decode()
+            .map(|image| vec![image::Frame::new(image.to_rgba())])
+            .map_err(SicIoError::ImageError),
+        None => decode_unrecognized(reader.into_inner())
+            .map(|decoded| vec![image::Frame::new(decoded.image.to_rgba())]),
+    }
+    .map_err(|err| explain_format_mismatch(err, config.format_hint, format))
+}
+
+// The image crate doesn't recognize the formats which are decoded by sic_io itself: OpenEXR
+// high dynamic range images and Photoshop documents.
+fn decode_unrecognized(reader: Cursor<Vec<u8>>) -> ImportResult<DecodedImage> {
+    let bytes = reader.into_inner();
+
+    if openexr::is_exr(&bytes) {
+        let hdr = openexr::decode(bytes)?;
+
+        Ok(DecodedImage {
+            image: hdr::to_dynamic_image(&hdr),
+            frames: 1,
+            hdr: Some(hdr),
+            animation: None,
+        })
+    } else if psd::is_psd(&bytes) {
+        psd::decode(&bytes).map(still_image)
+    } else {
+        Err(unknown_format())
+    }
+}
+
+// The image crate would convert the samples of a Radiance HDR image to 8 bit samples.
+fn decode_radiance(reader: Cursor<Vec<u8>>) -> ImportResult<DecodedImage> {
+    let hdr = radiance::decode(reader.into_inner())?;
+
+    Ok(DecodedImage {
+        image: hdr::to_dynamic_image(&hdr),
+        frames: 1,
+        hdr: Some(hdr),
+        animation: None,
+    })
+}
+
+// Formats which can't be recognized from their contents are only decoded if they're given
+// explicitly.
+fn decode_forced<R: Read>(
+    reader: &mut R,
+    format: ForcedInputFormat,
+) -> ImportResult<image::DynamicImage> {
+    let bytes = load(reader)?;
+
+    match format {
+        ForcedInputFormat::RawRgba8 { width, height } => {
+            let expected = (width as usize)
+                .saturating_mul(height as usize)
+                .saturating_mul(4);
+
+            if bytes.len() != expected {
+                return Err(SicIoError::RawImageSizeMismatch {
+                    width,
+                    height,
+                    expected,
+                    actual: bytes.len(),
+                });
+            }
+
+            image::RgbaImage::from_raw(width, height, bytes)
+                .map(image::DynamicImage::ImageRgba8)
+                .ok_or_else(unknown_format)
+        }
+    }
+}
+
+fn still_image(image: image::DynamicImage) -> DecodedImage {
+    DecodedImage {
+        image,
+        frames: 1,
+        hdr: None,
+        animation: None,
+    }
+}
+
+fn guess_format<R: Read>(reader: &mut R) -> ImportResult<image::io::Reader<Cursor<Vec<u8>>>> {
+    image::io::Reader::new(Cursor::new(load(reader)?))
         .with_guessed_format()
-        .map_err(SicIoError::Io)?;
-
-    match reader.format() {
-        Some(ImageFormat::Png) => decode_png(reader, config.selected_frame),
-        Some(ImageFormat::Gif) => decode_gif(reader, config.selected_frame),
-        Some(_) => reader.decode().map_err(SicIoError::ImageError),
-        None => Err(SicIoError::ImageError(image::error::ImageError::Decoding(
-            image::error::DecodingError::from_format_hint(image::error::ImageFormatHint::Unknown),
-        ))),
+        .map_err(SicIoError::Io)
+}
+
+// If decoding failed, and the format we expected (e.g. based on the file extension) differs from
+// the format detected from the contents of the image, the mismatch is the likely cause.
+fn explain_format_mismatch(
+    err: SicIoError,
+    expected: Option<ImageFormat>,
+    detected: Option<ImageFormat>,
+) -> SicIoError {
+    match expected {
+        Some(expected) if Some(expected) != detected => SicIoError::InputFormatMismatch {
+            expected: format!("{:?}", expected),
+            detected: detected.map_or_else(|| "unknown".to_string(), |f| format!("{:?}", f)),
+            source: Box::new(err),
+        },
+        _ => err,
     }
 }
 
+fn unknown_format() -> SicIoError {
+    SicIoError::ImageError(image::error::ImageError::Decoding(
+        image::error::DecodingError::from_format_hint(image::error::ImageFormatHint::Unknown),
+    ))
+}
+
 /// Result which is returned for operations within this module.
 type ImportResult<T> = Result<T, SicIoError>;
 
@@ -52,6 +233,25 @@ fn load<R: Read>(reader: &mut R) -> ImportResult<Vec<u8>> {
 pub struct ImportConfig {
     /// For animated images; decides which frame will be used as static image.
     pub selected_frame: FrameIndex,
+
+    /// The format we expect the image to have, for example based on the extension of the input
+    /// path. Only used to provide a more helpful error if the image can't be decoded.
+    pub format_hint: Option<ImageFormat>,
+
+    /// For animated images; whether all frames are kept, in addition to the selected frame.
+    pub keep_animation: bool,
+
+    /// The format of the image, if it can't be recognized from the contents of the image and thus
+    /// has to be given explicitly.
+    pub forced_format: Option<ForcedInputFormat>,
+}
+
+/// An input image format which can't be recognized from the contents of the image.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ForcedInputFormat {
+    /// Unencoded pixels with 8 bit RGBA samples, row by row, without a header; the size of the
+    /// image is thus given as well.
+    RawRgba8 { width: u32, height: u32 },
 }
 
 /// Decode an image into frames
@@ -90,27 +290,55 @@ impl Default for FrameIndex {
     }
 }
 
-fn decode_gif<R: Read>(
-    reader: image::io::Reader<R>,
-    frame: FrameIndex,
-) -> ImportResult<image::DynamicImage> {
-    let decoder =
-        image::gif::GifDecoder::new(reader.into_inner()).map_err(SicIoError::ImageError)?;
+/// Selects a frame of an animated image, and keeps all frames if requested. An animation of a
+/// single frame is kept as still image.
+fn decode_animation(
+    frames: Vec<image::Frame>,
+    repeat: Option<Repeat>,
+    config: &ImportConfig,
+) -> ImportResult<DecodedImage> {
+    let image = select_frame(&frames, config.selected_frame)?;
+    let count = frames.len();
+
+    let animation = if config.keep_animation && count > 1 {
+        AnimatedImage::from_frames(frames).map(|animation| animation.with_repeat(repeat))
+    } else {
+        None
+    };
 
-    frames(decoder).and_then(|f| select_frame(&f, frame))
+    Ok(DecodedImage {
+        image,
+        frames: count,
+        hdr: None,
+        animation,
+    })
 }
 
-fn decode_png<R: Read>(
-    reader: image::io::Reader<R>,
-    frame: FrameIndex,
-) -> ImportResult<image::DynamicImage> {
-    let decoder =
-        image::png::PngDecoder::new(reader.into_inner()).map_err(SicIoError::ImageError)?;
+fn decode_gif(
+    reader: image::io::Reader<Cursor<Vec<u8>>>,
+    config: &ImportConfig,
+) -> ImportResult<DecodedImage> {
+    let reader = reader.into_inner();
+    let repeat = repeat::gif_repeat(reader.get_ref());
+    let decoder = image::gif::GifDecoder::new(reader).map_err(SicIoError::ImageError)?;
+
+    frames(decoder).and_then(|f| decode_animation(f, repeat, config))
+}
+
+fn decode_png(
+    reader: image::io::Reader<Cursor<Vec<u8>>>,
+    config: &ImportConfig,
+) -> ImportResult<DecodedImage> {
+    let reader = reader.into_inner();
+    let repeat = repeat::apng_repeat(reader.get_ref());
+    let decoder = image::png::PngDecoder::new(reader).map_err(SicIoError::ImageError)?;
 
     if decoder.is_apng() {
-        frames(decoder.apng()).and_then(|f| select_frame(&f, frame))
+        frames(decoder.apng()).and_then(|f| decode_animation(f, repeat, config))
     } else {
-        image::DynamicImage::from_decoder(decoder).map_err(SicIoError::ImageError)
+        image::DynamicImage::from_decoder(decoder)
+            .map(still_image)
+            .map_err(SicIoError::ImageError)
     }
 }
 
@@ -131,6 +359,9 @@ mod tests {
 
         let config = ImportConfig {
             selected_frame: FrameIndex::First,
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let image = load_image(&mut file_reader(load_path).unwrap(), &config).unwrap();
@@ -146,10 +377,16 @@ mod tests {
 
         let first = ImportConfig {
             selected_frame: FrameIndex::First,
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let zero = ImportConfig {
             selected_frame: FrameIndex::Nth(0),
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let first = load_image(&mut file_reader(&load_path).unwrap(), &first).unwrap();
@@ -164,10 +401,16 @@ mod tests {
 
         let first = ImportConfig {
             selected_frame: FrameIndex::First,
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let zero = ImportConfig {
             selected_frame: FrameIndex::Nth(0),
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let first = load_image(&mut file_reader(&load_path).unwrap(), &first).unwrap();
@@ -196,6 +439,9 @@ mod tests {
 
             let config = ImportConfig {
                 selected_frame: FrameIndex::Nth(i),
+                format_hint: None,
+                keep_animation: false,
+                forced_format: None,
             };
 
             let image = load_image(&mut file_reader(load_path).unwrap(), &config).unwrap();
@@ -211,6 +457,9 @@ mod tests {
 
             let config = ImportConfig {
                 selected_frame: FrameIndex::Nth(i),
+                format_hint: None,
+                keep_animation: false,
+                forced_format: None,
             };
 
             let image = load_image(&mut file_reader(load_path).unwrap(), &config).unwrap();
@@ -225,6 +474,9 @@ mod tests {
 
         let config = ImportConfig {
             selected_frame: FrameIndex::Nth(8),
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let result = load_image(&mut file_reader(load_path).unwrap(), &config);
@@ -238,6 +490,9 @@ mod tests {
 
         let config = ImportConfig {
             selected_frame: FrameIndex::Nth(8),
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let result = load_image(&mut file_reader(load_path).unwrap(), &config);
@@ -250,10 +505,16 @@ mod tests {
 
         let last = ImportConfig {
             selected_frame: FrameIndex::Last,
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let seven = ImportConfig {
             selected_frame: FrameIndex::Nth(7),
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let last = load_image(&mut file_reader(&load_path).unwrap(), &last).unwrap();
@@ -268,10 +529,16 @@ mod tests {
 
         let last = ImportConfig {
             selected_frame: FrameIndex::Last,
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let seven = ImportConfig {
             selected_frame: FrameIndex::Nth(7),
+            format_hint: None,
+            keep_animation: false,
+            forced_format: None,
         };
 
         let last = load_image(&mut file_reader(&load_path).unwrap(), &last).unwrap();
@@ -296,6 +563,55 @@ mod tests {
         }
     }
 
+    mod keep_animation {
+        use super::*;
+
+        fn decode(path: &str, keep_animation: bool) -> DecodedImage {
+            let config = ImportConfig {
+                keep_animation,
+                ..ImportConfig::default()
+            };
+
+            decode_image(&mut file_reader(setup_test_image(path)).unwrap(), &config).unwrap()
+        }
+
+        #[test]
+        fn gif() {
+            let decoded = decode(GIF_LOOP, true);
+            let animation = decoded.animation.unwrap();
+
+            assert_eq!(animation.frames().len(), 8);
+            assert_eq!(decoded.frames, 8);
+            assert_eq!(animation.first_frame().to_bytes(), decoded.image.to_bytes());
+        }
+
+        #[test]
+        fn apng() {
+            let decoded = decode("apng_sample.png", true);
+
+            assert_eq!(decoded.animation.unwrap().frames().len(), 3);
+        }
+
+        #[test]
+        fn gif_repeat() {
+            let looping = decode(GIF_LOOP, true).animation.unwrap();
+            let not_looping = decode(GIF_NO_LOOP, true).animation.unwrap();
+
+            assert_eq!(looping.repeat(), Some(Repeat::Infinite));
+            assert_eq!(not_looping.repeat(), None);
+        }
+
+        #[test]
+        fn not_requested() {
+            assert!(decode(GIF_LOOP, false).animation.is_none());
+        }
+
+        #[test]
+        fn still_image() {
+            assert!(decode("bwlines.png", true).animation.is_none());
+        }
+    }
+
     mod apng {
         use super::*;
 
@@ -324,6 +640,9 @@ mod tests {
 
             let config = ImportConfig {
                 selected_frame: frame,
+                format_hint: None,
+                keep_animation: false,
+                forced_format: None,
             };
 
             let image = load_image(&mut file_reader(load_path).unwrap(), &config);
@@ -335,4 +654,293 @@ mod tests {
             }
         }
     }
+
+    mod frames {
+        use super::*;
+
+        #[parameterized(
+            path = {
+                "loop.gif",
+                "noloop.gif",
+                "apng_sample.png",
+                "bwlines.png",
+                "unsplash_763569_cropped.jpg",
+            },
+            expected_frames = {
+                8,
+                8,
+                3,
+                1,
+                1,
+            }
+        )]
+        fn load_frames_count(path: &str, expected_frames: usize) {
+            let load_path = setup_test_image(path);
+            let frames = load_frames(
+                &mut file_reader(load_path).unwrap(),
+                &ImportConfig::default(),
+            )
+            .unwrap();
+
+            assert_eq!(frames.len(), expected_frames);
+        }
+
+        #[parameterized(
+            path = {
+                "loop.gif",
+                "apng_sample.png",
+                "bwlines.png",
+                "unsplash_763569_cropped.jpg",
+            },
+            expected_frames = {
+                8,
+                3,
+                1,
+                1,
+            }
+        )]
+        fn load_image_frame_count(path: &str, expected_frames: usize) {
+            let load_path = setup_test_image(path);
+            let (_, frames) = load_image_with_frame_count(
+                &mut file_reader(load_path).unwrap(),
+                &ImportConfig::default(),
+            )
+            .unwrap();
+
+            assert_eq!(frames, expected_frames);
+        }
+
+        #[test]
+        fn load_frames_gif_colors() {
+            let load_path = setup_test_image(GIF_LOOP);
+            let frames = load_frames(
+                &mut file_reader(load_path).unwrap(),
+                &ImportConfig::default(),
+            )
+            .unwrap();
+
+            for (frame, expected) in frames.iter().zip(FRAME_COLORS.iter()) {
+                assert_eq!(&frame.buffer().get_pixel(XY, XY).0, expected);
+            }
+        }
+    }
+
+    mod format_mismatch {
+        use super::*;
+
+        fn truncated_jpeg() -> Vec<u8> {
+            let load_path = setup_test_image("unsplash_763569_cropped.jpg");
+            let bytes = std::fs::read(load_path).unwrap();
+            bytes[..256].to_vec()
+        }
+
+        #[test]
+        fn mismatch_is_explained() {
+            let config = ImportConfig {
+                format_hint: Some(ImageFormat::Png),
+                keep_animation: false,
+                ..ImportConfig::default()
+            };
+
+            let result = load_image(&mut Cursor::new(truncated_jpeg()), &config);
+
+            assert!(matches!(
+                result,
+                Err(SicIoError::InputFormatMismatch { .. })
+            ));
+        }
+
+        #[test]
+        fn matching_format_is_not_a_mismatch() {
+            let config = ImportConfig {
+                format_hint: Some(ImageFormat::Jpeg),
+                keep_animation: false,
+                ..ImportConfig::default()
+            };
+
+            let result = load_image(&mut Cursor::new(truncated_jpeg()), &config);
+
+            assert!(matches!(result, Err(SicIoError::ImageError(_))));
+        }
+
+        #[test]
+        fn mismatch_without_decoding_error_is_ok() {
+            let load_path = setup_test_image("unsplash_763569_cropped.jpg");
+            let config = ImportConfig {
+                format_hint: Some(ImageFormat::Png),
+                keep_animation: false,
+                ..ImportConfig::default()
+            };
+
+            let result = load_image(&mut file_reader(load_path).unwrap(), &config);
+
+            assert!(result.is_ok());
+        }
+    }
+
+    mod exr {
+        use super::*;
+
+        fn exr_image() -> (Rgba32FImage, Vec<u8>) {
+            let hdr = Rgba32FImage::from_fn(4, 2, |x, y| {
+                image::Rgba([x as f32 * 2.0, y as f32 * 0.5, 0.0, 1.0])
+            });
+            let mut bytes = Vec::new();
+            openexr::encode(&mut bytes, &hdr).unwrap();
+
+            (hdr, bytes)
+        }
+
+        #[test]
+        fn decode_exr_keeps_hdr_samples() {
+            let (hdr, bytes) = exr_image();
+
+            let decoded = decode_image(&mut Cursor::new(bytes), &ImportConfig::default()).unwrap();
+
+            assert_eq!(decoded.frames, 1);
+            assert_eq!(decoded.image.dimensions(), (4, 2));
+            assert_eq!(decoded.hdr, Some(hdr));
+        }
+
+        #[test]
+        fn load_exr_frames() {
+            let (_, bytes) = exr_image();
+
+            let frames = load_frames(&mut Cursor::new(bytes), &ImportConfig::default()).unwrap();
+
+            assert_eq!(frames.len(), 1);
+        }
+
+        #[test]
+        fn unknown_format_is_not_hdr() {
+            let result = decode_image(&mut Cursor::new(vec![0u8; 16]), &ImportConfig::default());
+
+            assert!(matches!(result, Err(SicIoError::ImageError(_))));
+        }
+    }
+
+    mod radiance {
+        use super::*;
+
+        #[test]
+        fn decode_radiance_keeps_hdr_samples() {
+            let load_path = setup_test_image("hdr_8x6.hdr");
+
+            let decoded = decode_image(
+                &mut file_reader(load_path).unwrap(),
+                &ImportConfig::default(),
+            )
+            .unwrap();
+            let hdr = decoded.hdr.unwrap();
+
+            assert_eq!(decoded.image.dimensions(), (8, 6));
+            assert_eq!(hdr.get_pixel(0, 0).0, [0.0, 0.0, 0.5, 1.0]);
+            assert_eq!(hdr.get_pixel(7, 4).0, [7.0, 1.0, 0.5, 1.0]);
+        }
+
+        #[test]
+        fn load_radiance_frames() {
+            let load_path = setup_test_image("hdr_8x6.hdr");
+
+            let frames = load_frames(
+                &mut file_reader(load_path).unwrap(),
+                &ImportConfig::default(),
+            )
+            .unwrap();
+
+            assert_eq!(frames.len(), 1);
+        }
+    }
+
+    mod psd {
+        use super::*;
+
+        #[test]
+        fn decode_psd_composite() {
+            let load_path = setup_test_image("rgba_4x3.psd");
+
+            let decoded = decode_image(
+                &mut file_reader(load_path).unwrap(),
+                &ImportConfig::default(),
+            )
+            .unwrap();
+            let image = decoded.image.to_rgba();
+
+            assert_eq!(decoded.frames, 1);
+            assert_eq!(image.dimensions(), (4, 3));
+            assert_eq!(image.get_pixel(1, 2), &image::Rgba([80, 240, 200, 255]));
+            assert_eq!(image.get_pixel(3, 0), &image::Rgba([240, 0, 200, 128]));
+        }
+
+        #[test]
+        fn load_psd_frames() {
+            let load_path = setup_test_image("rgba_4x3.psd");
+
+            let frames = load_frames(
+                &mut file_reader(load_path).unwrap(),
+                &ImportConfig::default(),
+            )
+            .unwrap();
+
+            assert_eq!(frames.len(), 1);
+        }
+    }
+
+    mod raw_rgba8 {
+        use super::*;
+
+        fn config(width: u32, height: u32) -> ImportConfig {
+            ImportConfig {
+                forced_format: Some(ForcedInputFormat::RawRgba8 { width, height }),
+                ..ImportConfig::default()
+            }
+        }
+
+        #[test]
+        fn decode_raw_rgba8() {
+            let bytes = vec![1, 2, 3, 4, 5, 6, 7, 8];
+
+            let decoded = decode_image(&mut Cursor::new(bytes), &config(1, 2)).unwrap();
+            let image = decoded.image.to_rgba();
+
+            assert_eq!(image.dimensions(), (1, 2));
+            assert_eq!(image.get_pixel(0, 0), &image::Rgba([1, 2, 3, 4]));
+            assert_eq!(image.get_pixel(0, 1), &image::Rgba([5, 6, 7, 8]));
+        }
+
+        #[test]
+        fn load_raw_rgba8_frames() {
+            let frames = load_frames(&mut Cursor::new(vec![0u8; 16]), &config(2, 2)).unwrap();
+
+            assert_eq!(frames.len(), 1);
+            assert_eq!(frames[0].buffer().dimensions(), (2, 2));
+        }
+
+        #[test]
+        fn size_mismatch() {
+            let result = decode_image(&mut Cursor::new(vec![0u8; 15]), &config(2, 2));
+
+            assert!(matches!(
+                result,
+                Err(SicIoError::RawImageSizeMismatch {
+                    expected: 16,
+                    actual: 15,
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        fn recognizable_image_is_read_as_raw_pixels() {
+            let load_path = setup_test_image("rainbow_8x6.bmp");
+            let size = std::fs::metadata(&load_path).unwrap().len() as usize;
+
+            let result = decode_image(&mut file_reader(load_path).unwrap(), &config(8, 6));
+
+            assert!(matches!(
+                result,
+                Err(SicIoError::RawImageSizeMismatch { actual, .. }) if actual == size
+            ));
+        }
+    }
 }
@@ -0,0 +1,87 @@
+//! OpenEXR images are decoded and encoded by the exr crate, since the image crate doesn't support
+//! them. Their samples are kept as linear, unclamped floating point values; see [sic_core::hdr].
+
+use std::io::{Cursor, Write};
+
+use exr::prelude::traits::*;
+use exr::prelude::{Image, SpecificChannels, Vec2};
+use sic_core::hdr::Rgba32FImage;
+use sic_core::image;
+
+use crate::errors::SicIoError;
+
+/// The magic number with which every OpenEXR image starts.
+const MAGIC_NUMBER: &[u8] = &[0x76, 0x2f, 0x31, 0x01];
+
+/// Whether the bytes start like an OpenEXR image.
+pub fn is_exr(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC_NUMBER)
+}
+
+/// Decodes the RGBA channels of the first layer of an OpenEXR image, at its largest resolution.
+/// Images without an alpha channel are opaque.
+pub(crate) fn decode(bytes: Vec<u8>) -> Result<Rgba32FImage, SicIoError> {
+    let image = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .rgba_channels(
+            |resolution, _| {
+                Rgba32FImage::new(resolution.width() as u32, resolution.height() as u32)
+            },
+            |image, position, (r, g, b, a): (f32, f32, f32, f32)| {
+                image.put_pixel(
+                    position.x() as u32,
+                    position.y() as u32,
+                    image::Rgba([r, g, b, a]),
+                )
+            },
+        )
+        .first_valid_layer()
+        .all_attributes()
+        .from_buffered(Cursor::new(bytes))
+        .map_err(SicIoError::ExrError)?;
+
+    Ok(image.layer_data.channel_data.pixels)
+}
+
+/// Encodes an image as OpenEXR image with 32 bit floating point RGBA channels.
+pub(crate) fn encode<W: Write>(writer: &mut W, image: &Rgba32FImage) -> Result<(), SicIoError> {
+    let channels = SpecificChannels::rgba(|position: Vec2<usize>| {
+        let [r, g, b, a] = image.get_pixel(position.x() as u32, position.y() as u32).0;
+        (r, g, b, a)
+    });
+
+    // the encoder needs to seek, so the image is encoded in memory first
+    let mut encoded = Cursor::new(Vec::new());
+
+    Image::from_channels((image.width() as usize, image.height() as usize), channels)
+        .write()
+        .to_buffered(&mut encoded)
+        .map_err(SicIoError::ExrError)?;
+
+    writer.write_all(encoded.get_ref()).map_err(SicIoError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_keeps_unclamped_samples() {
+        let image = Rgba32FImage::from_fn(3, 2, |x, y| {
+            image::Rgba([x as f32 * 4.5, -(y as f32), 0.25, 1.0])
+        });
+
+        let mut bytes = Vec::new();
+        encode(&mut bytes, &image).unwrap();
+
+        assert!(is_exr(&bytes));
+        assert_eq!(decode(bytes).unwrap(), image);
+    }
+
+    #[test]
+    fn not_exr() {
+        assert!(!is_exr(b"\x89PNG\r\n\x1a\n"));
+        assert!(decode(b"\x89PNG\r\n\x1a\n".to_vec()).is_err());
+    }
+}
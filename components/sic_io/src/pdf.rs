@@ -0,0 +1,345 @@
+//! PDF documents are written by sic_io itself, with a page for each image. Every page is exactly
+//! the size of its image, at 72 pixels per inch, so each pixel is one point. The samples are
+//! stored lossless and compressed with deflate; transparency is kept as a soft mask.
+
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use sic_core::image::{DynamicImage, GenericImageView};
+
+use crate::errors::SicIoError;
+
+/// The object number of the document catalog.
+const CATALOG: usize = 1;
+/// The object number of the page tree, which refers to all pages.
+const PAGE_TREE: usize = 2;
+
+/// A PDF document, to which images are added as pages, in order. The samples of each page are
+/// compressed as soon as the page is added, so the images themselves aren't kept.
+pub struct PdfDocument {
+    // the objects of the document, where the object number of each object is its index plus one
+    objects: Vec<Vec<u8>>,
+    pages: Vec<usize>,
+}
+
+impl Default for PdfDocument {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        PdfDocument {
+            // the catalog and page tree are written once all pages are known
+            objects: vec![Vec::new(), Vec::new()],
+            pages: Vec::new(),
+        }
+    }
+
+    /// Adds the image as the next page of the document. Images with 16 bit samples are stored
+    /// with 8 bit samples.
+    pub fn add_page(&mut self, image: &DynamicImage) -> Result<(), SicIoError> {
+        let (width, height) = image.dimensions();
+
+        let soft_mask = if image.color().has_alpha() {
+            let alpha = image
+                .to_rgba()
+                .pixels()
+                .map(|pixel| pixel.0[3])
+                .collect::<Vec<_>>();
+
+            if alpha.iter().all(|&value| value == u8::MAX) {
+                None
+            } else {
+                Some(self.add_image(width, height, "DeviceGray", &alpha, None)?)
+            }
+        } else {
+            None
+        };
+
+        let image_object = if image.color().has_color() {
+            self.add_image(
+                width,
+                height,
+                "DeviceRGB",
+                image.to_rgb().as_raw(),
+                soft_mask,
+            )?
+        } else {
+            self.add_image(
+                width,
+                height,
+                "DeviceGray",
+                image.to_luma().as_raw(),
+                soft_mask,
+            )?
+        };
+
+        let content = format!("q\n{} 0 0 {} 0 0 cm\n/Im0 Do\nQ\n", width, height);
+        let contents = self.add(stream("", content.as_bytes()));
+
+        let page = self.add(
+            format!(
+                "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>",
+                PAGE_TREE, width, height, image_object, contents
+            )
+            .into_bytes(),
+        );
+
+        self.pages.push(page);
+        Ok(())
+    }
+
+    /// Writes the document, with all pages added so far.
+    pub fn write<W: Write>(mut self, writer: &mut W) -> Result<(), SicIoError> {
+        let kids = self
+            .pages
+            .iter()
+            .map(|page| format!("{} 0 R", page))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        self.objects[CATALOG - 1] =
+            format!("<< /Type /Catalog /Pages {} 0 R >>", PAGE_TREE).into_bytes();
+        self.objects[PAGE_TREE - 1] = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids,
+            self.pages.len()
+        )
+        .into_bytes();
+
+        // the comment with non-ASCII bytes marks the document as binary
+        let mut document = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.objects.len());
+
+        for (index, object) in self.objects.iter().enumerate() {
+            offsets.push(document.len());
+            document.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            document.extend_from_slice(object);
+            document.extend_from_slice(b"\nendobj\n");
+        }
+
+        // each entry of the cross-reference table is exactly 20 bytes long
+        let xref = document.len();
+        document.extend_from_slice(
+            format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).as_bytes(),
+        );
+        for offset in offsets {
+            document.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+
+        document.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+                self.objects.len() + 1,
+                CATALOG,
+                xref
+            )
+            .as_bytes(),
+        );
+
+        writer.write_all(&document).map_err(SicIoError::Io)
+    }
+
+    /// Adds an image with 8 bit samples, and returns its object number.
+    fn add_image(
+        &mut self,
+        width: u32,
+        height: u32,
+        color_space: &str,
+        samples: &[u8],
+        soft_mask: Option<usize>,
+    ) -> Result<usize, SicIoError> {
+        let soft_mask = soft_mask
+            .map(|object| format!(" /SMask {} 0 R", object))
+            .unwrap_or_default();
+
+        let dictionary = format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{} \
+             /BitsPerComponent 8 /Filter /FlateDecode{}",
+            width, height, color_space, soft_mask
+        );
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(samples).map_err(SicIoError::Io)?;
+        let compressed = encoder.finish().map_err(SicIoError::Io)?;
+
+        Ok(self.add(stream(&dictionary, &compressed)))
+    }
+
+    /// Adds an object, and returns its object number.
+    fn add(&mut self, object: Vec<u8>) -> usize {
+        self.objects.push(object);
+        self.objects.len()
+    }
+}
+
+/// A stream object; `dictionary` holds the entries of its dictionary, other than its length.
+fn stream(dictionary: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("<< {} /Length {} >>\nstream\n", dictionary, data.len()).into_bytes();
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::ZlibDecoder;
+    use sic_core::image::{GrayImage, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+    use std::io::Read;
+
+    fn written(document: PdfDocument) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        document.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn count(bytes: &[u8], pattern: &str) -> usize {
+        bytes
+            .windows(pattern.len())
+            .filter(|window| *window == pattern.as_bytes())
+            .count()
+    }
+
+    fn position(bytes: &[u8], pattern: &str) -> usize {
+        bytes
+            .windows(pattern.len())
+            .position(|window| window == pattern.as_bytes())
+            .unwrap()
+    }
+
+    /// The inflated data of the stream which starts after the given position.
+    fn stream_after(bytes: &[u8], from: usize) -> Vec<u8> {
+        let start = from + position(&bytes[from..], "stream\n") + "stream\n".len();
+        let end = start + position(&bytes[start..], "\nendstream");
+        let mut samples = Vec::new();
+        ZlibDecoder::new(&bytes[start..end])
+            .read_to_end(&mut samples)
+            .unwrap();
+        samples
+    }
+
+    fn rgb() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(3, 2, |x, y| {
+            Rgb([x as u8 * 100, y as u8 * 100, 50])
+        }))
+    }
+
+    #[test]
+    fn single_page() {
+        let mut document = PdfDocument::new();
+        document.add_page(&rgb()).unwrap();
+        let bytes = written(document);
+
+        assert!(bytes.starts_with(b"%PDF-1.4\n"));
+        assert!(bytes.ends_with(b"%%EOF\n"));
+        assert_eq!(count(&bytes, "/Type /Page "), 1);
+        assert_eq!(count(&bytes, "/Count 1 "), 1);
+        assert_eq!(count(&bytes, "/MediaBox [0 0 3 2]"), 1);
+        assert_eq!(count(&bytes, "/SMask"), 0);
+
+        let image = position(&bytes, "/ColorSpace /DeviceRGB");
+        assert_eq!(stream_after(&bytes, image), rgb().to_bytes());
+    }
+
+    #[test]
+    fn pages_in_order() {
+        let mut document = PdfDocument::new();
+        document
+            .add_page(&DynamicImage::ImageRgb8(RgbImage::new(1, 1)))
+            .unwrap();
+        document
+            .add_page(&DynamicImage::ImageRgb8(RgbImage::new(2, 2)))
+            .unwrap();
+        document
+            .add_page(&DynamicImage::ImageRgb8(RgbImage::new(3, 3)))
+            .unwrap();
+        let bytes = written(document);
+
+        assert_eq!(count(&bytes, "/Type /Page "), 3);
+        assert_eq!(count(&bytes, "/Count 3 "), 1);
+        assert!(position(&bytes, "[0 0 1 1]") < position(&bytes, "[0 0 2 2]"));
+        assert!(position(&bytes, "[0 0 2 2]") < position(&bytes, "[0 0 3 3]"));
+    }
+
+    #[test]
+    fn cross_reference_table() {
+        let mut document = PdfDocument::new();
+        document.add_page(&rgb()).unwrap();
+        document.add_page(&rgb()).unwrap();
+        let bytes = written(document);
+
+        let xref = position(&bytes, "\nxref\n") + 1;
+        let startxref = position(&bytes, "startxref\n") + "startxref\n".len();
+        let xref_offset = String::from_utf8_lossy(&bytes[startxref..])
+            .lines()
+            .next()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        assert_eq!(xref_offset, xref);
+
+        // catalog, page tree, and an image, contents and page object for each page
+        let table = String::from_utf8_lossy(&bytes[xref..]).into_owned();
+        let entries = table
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2 + 2 * 3);
+
+        for (index, entry) in entries.iter().enumerate() {
+            let offset = entry[..10].parse::<usize>().unwrap();
+            let header = format!("{} 0 obj\n", index + 1);
+            assert!(bytes[offset..].starts_with(header.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn transparency_as_soft_mask() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(2, 1, |x, _| {
+            Rgba([255, 0, 0, x as u8 * 255])
+        }));
+
+        let mut document = PdfDocument::new();
+        document.add_page(&image).unwrap();
+        let bytes = written(document);
+
+        assert_eq!(count(&bytes, "/SMask"), 1);
+
+        let mask = position(&bytes, "/ColorSpace /DeviceGray");
+        assert_eq!(stream_after(&bytes, mask), vec![0, 255]);
+        let image = position(&bytes, "/ColorSpace /DeviceRGB");
+        assert_eq!(stream_after(&bytes, image), vec![255, 0, 0, 255, 0, 0]);
+    }
+
+    #[test]
+    fn opaque_alpha_channel_without_soft_mask() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba([1, 2, 3, 255])));
+
+        let mut document = PdfDocument::new();
+        document.add_page(&image).unwrap();
+        let bytes = written(document);
+
+        assert_eq!(count(&bytes, "/SMask"), 0);
+    }
+
+    #[test]
+    fn grayscale() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_fn(2, 2, |x, y| {
+            Luma([(x + 2 * y) as u8 * 60])
+        }));
+
+        let mut document = PdfDocument::new();
+        document.add_page(&image).unwrap();
+        let bytes = written(document);
+
+        assert_eq!(count(&bytes, "/DeviceRGB"), 0);
+        let samples = position(&bytes, "/ColorSpace /DeviceGray");
+        assert_eq!(stream_after(&bytes, samples), vec![0, 60, 120, 180]);
+    }
+}
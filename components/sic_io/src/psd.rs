@@ -0,0 +1,133 @@
+//! Photoshop documents (PSD) are decoded by the psd crate, since the image crate doesn't support
+//! them. Only the flattened composite image, as stored by Photoshop next to the layers, is read;
+//! the layers themselves are not.
+
+use std::path::Path;
+
+use psd::{ColorMode, Psd};
+use sic_core::image::{DynamicImage, RgbaImage};
+
+use crate::errors::SicIoError;
+
+/// The signature with which every Photoshop document starts.
+const SIGNATURE: &[u8] = b"8BPS";
+
+/// Whether the bytes start like a Photoshop document.
+pub fn is_psd(bytes: &[u8]) -> bool {
+    bytes.starts_with(SIGNATURE)
+}
+
+/// Whether the path has the extension of a Photoshop document.
+pub fn has_psd_extension<P: AsRef<Path>>(path: P) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("psd"))
+}
+
+/// Decodes the flattened composite image of a Photoshop document, in the RGB or grayscale color
+/// mode. Documents without an alpha channel are opaque.
+pub(crate) fn decode(bytes: &[u8]) -> Result<DynamicImage, SicIoError> {
+    let document = Psd::from_bytes(bytes).map_err(SicIoError::PsdError)?;
+
+    match document.color_mode() {
+        ColorMode::Rgb | ColorMode::Grayscale => {}
+        other => return Err(SicIoError::UnsupportedPsdColorMode(format!("{:?}", other))),
+    }
+
+    RgbaImage::from_raw(document.width(), document.height(), document.rgba())
+        .map(DynamicImage::ImageRgba8)
+        .ok_or(SicIoError::IncompletePsdImage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::Rgba;
+
+    const RGB: u16 = 3;
+    const GRAYSCALE: u16 = 1;
+    const CMYK: u16 = 4;
+
+    /// A document without layers, of which the composite image has raw (uncompressed) channels,
+    /// each stored as a separate plane.
+    fn document(width: u32, height: u32, color_mode: u16, channels: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = SIGNATURE.to_vec();
+        bytes.extend_from_slice(&1u16.to_be_bytes()); // version
+        bytes.extend_from_slice(&[0; 6]); // reserved
+        bytes.extend_from_slice(&(channels.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&8u16.to_be_bytes()); // depth
+        bytes.extend_from_slice(&color_mode.to_be_bytes());
+
+        // color mode data, image resources, and layer and mask information
+        bytes.extend_from_slice(&[0; 12]);
+
+        bytes.extend_from_slice(&0u16.to_be_bytes()); // raw image data
+        for channel in channels {
+            bytes.extend_from_slice(channel);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn rgba_composite() {
+        let bytes = document(
+            2,
+            1,
+            RGB,
+            &[vec![255, 0], vec![0, 128], vec![10, 20], vec![255, 100]],
+        );
+
+        assert!(is_psd(&bytes));
+
+        let image = decode(&bytes).unwrap().to_rgba();
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.get_pixel(0, 0), &Rgba([255, 0, 10, 255]));
+        assert_eq!(image.get_pixel(1, 0), &Rgba([0, 128, 20, 100]));
+    }
+
+    #[test]
+    fn rgb_composite_is_opaque() {
+        let bytes = document(1, 2, RGB, &[vec![1, 2], vec![3, 4], vec![5, 6]]);
+
+        let image = decode(&bytes).unwrap().to_rgba();
+        assert_eq!(image.get_pixel(0, 0), &Rgba([1, 3, 5, 255]));
+        assert_eq!(image.get_pixel(0, 1), &Rgba([2, 4, 6, 255]));
+    }
+
+    #[test]
+    fn grayscale_composite() {
+        let bytes = document(2, 1, GRAYSCALE, &[vec![40, 200]]);
+
+        let image = decode(&bytes).unwrap().to_rgba();
+        assert_eq!(image.get_pixel(0, 0), &Rgba([40, 40, 40, 255]));
+        assert_eq!(image.get_pixel(1, 0), &Rgba([200, 200, 200, 255]));
+    }
+
+    #[test]
+    fn unsupported_color_mode() {
+        let bytes = document(1, 1, CMYK, &[vec![0], vec![0], vec![0], vec![0]]);
+
+        assert!(matches!(
+            decode(&bytes),
+            Err(SicIoError::UnsupportedPsdColorMode(_))
+        ));
+    }
+
+    #[test]
+    fn not_psd() {
+        assert!(!is_psd(b"\x89PNG\r\n\x1a\n"));
+        assert!(decode(b"8BPS").is_err());
+    }
+
+    #[test]
+    fn psd_extension() {
+        assert!(has_psd_extension("design.psd"));
+        assert!(has_psd_extension("DESIGN.PSD"));
+        assert!(!has_psd_extension("design.png"));
+        assert!(!has_psd_extension("psd"));
+    }
+}
@@ -0,0 +1,56 @@
+//! Radiance HDR images are decoded with the HDR decoder of the image crate, which otherwise
+//! converts them to 8 bit samples. Their samples are kept as linear, unclamped floating point
+//! values instead; see [sic_core::hdr].
+
+use std::io::Cursor;
+
+use sic_core::hdr::Rgba32FImage;
+use sic_core::image;
+
+use crate::errors::SicIoError;
+
+/// Decodes a Radiance HDR image. Radiance HDR images have no alpha channel, so the image is
+/// opaque.
+pub(crate) fn decode(bytes: Vec<u8>) -> Result<Rgba32FImage, SicIoError> {
+    let decoder =
+        image::hdr::HdrDecoder::new(Cursor::new(bytes)).map_err(SicIoError::ImageError)?;
+    let width = decoder.metadata().width;
+    let height = decoder.metadata().height;
+    let pixels = decoder.read_image_hdr().map_err(SicIoError::ImageError)?;
+
+    Ok(Rgba32FImage::from_fn(width, height, |x, y| {
+        let [r, g, b] = pixels[(y * width + x) as usize].0;
+        image::Rgba([r, g, b, 1.0])
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_unclamped_samples() {
+        let pixels = vec![
+            image::Rgb([4.0, 0.5, 0.0]),
+            image::Rgb([0.25, 16.0, 1.0]),
+            image::Rgb([0.0, 0.0, 0.0]),
+        ];
+
+        let mut bytes = Vec::new();
+        image::hdr::HdrEncoder::new(&mut bytes)
+            .encode(&pixels, 3, 1)
+            .unwrap();
+
+        let decoded = decode(bytes).unwrap();
+
+        assert_eq!(decoded.dimensions(), (3, 1));
+        assert_eq!(decoded.get_pixel(0, 0).0, [4.0, 0.5, 0.0, 1.0]);
+        assert_eq!(decoded.get_pixel(1, 0).0, [0.25, 16.0, 1.0, 1.0]);
+        assert_eq!(decoded.get_pixel(2, 0).0, [0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn not_radiance() {
+        assert!(decode(b"\x89PNG\r\n\x1a\n".to_vec()).is_err());
+    }
+}
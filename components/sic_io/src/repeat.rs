@@ -0,0 +1,149 @@
+//! The image crate doesn't tell how often an animation is repeated, so it's read from the encoded
+//! image instead: for GIF images from the NETSCAPE application extension, and for APNG images from
+//! the animation control chunk.
+
+use std::convert::{TryFrom, TryInto};
+
+use sic_core::animation::Repeat;
+
+const GIF_EXTENSION: u8 = 0x21;
+const GIF_IMAGE: u8 = 0x2C;
+const GIF_APPLICATION: u8 = 0xFF;
+
+/// The application identifier and authentication code of the NETSCAPE extension, preceded by the
+/// size of the block they're stored in.
+const NETSCAPE: &[u8] = b"\x0bNETSCAPE2.0";
+
+/// The repeat count of the NETSCAPE extension of a GIF image, or `None` if it has no such
+/// extension.
+pub(crate) fn gif_repeat(bytes: &[u8]) -> Option<Repeat> {
+    // header (6 bytes) and logical screen descriptor (7 bytes)
+    let mut position = 13 + gif_color_table_size(*bytes.get(10)?);
+
+    loop {
+        match *bytes.get(position)? {
+            GIF_EXTENSION => {
+                let blocks = position + 2;
+
+                if *bytes.get(position + 1)? == GIF_APPLICATION
+                    && bytes.get(blocks..blocks + NETSCAPE.len())? == NETSCAPE
+                {
+                    let data = blocks + NETSCAPE.len();
+
+                    // the sub-block with the loop count has 3 bytes, and is identified by 1
+                    if let [3, 1, low, high] = *bytes.get(data..data + 4)? {
+                        return Some(match u16::from_le_bytes([low, high]) {
+                            0 => Repeat::Infinite,
+                            count => Repeat::Finite(count),
+                        });
+                    }
+                }
+
+                position = gif_skip_sub_blocks(bytes, blocks)?;
+            }
+            GIF_IMAGE => {
+                // image descriptor (10 bytes), followed by the minimum code size of the image data
+                let flags = *bytes.get(position + 9)?;
+                position = gif_skip_sub_blocks(bytes, position + 11 + gif_color_table_size(flags))?;
+            }
+            // the trailer, or an invalid block
+            _ => return None,
+        }
+    }
+}
+
+fn gif_color_table_size(flags: u8) -> usize {
+    if flags & 0x80 != 0 {
+        3 << ((flags & 0x07) + 1)
+    } else {
+        0
+    }
+}
+
+/// The position after the sub-blocks which start at the given position.
+fn gif_skip_sub_blocks(bytes: &[u8], mut position: usize) -> Option<usize> {
+    loop {
+        let size = usize::from(*bytes.get(position)?);
+        position += 1 + size;
+
+        if size == 0 {
+            return Some(position);
+        }
+    }
+}
+
+/// How often an APNG image is repeated, according to the number of times it's played as stored in
+/// its animation control chunk, or `None` if it has no such chunk.
+pub(crate) fn apng_repeat(bytes: &[u8]) -> Option<Repeat> {
+    // the signature
+    let mut position = 8;
+
+    loop {
+        let length = u32::from_be_bytes(bytes.get(position..position + 4)?.try_into().ok()?);
+
+        match bytes.get(position + 4..position + 8)? {
+            b"acTL" => {
+                let data = position + 8;
+                let plays = u32::from_be_bytes(bytes.get(data + 4..data + 8)?.try_into().ok()?);
+
+                return Some(match plays {
+                    0 => Repeat::Infinite,
+                    plays => Repeat::Finite(u16::try_from(plays - 1).unwrap_or(u16::MAX)),
+                });
+            }
+            // the animation control chunk precedes the image data
+            b"IDAT" => return None,
+            // length, type, data and checksum
+            _ => position += 12 + usize::try_from(length).ok()?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::setup_test_image;
+
+    fn read(path: &str) -> Vec<u8> {
+        std::fs::read(setup_test_image(path)).unwrap()
+    }
+
+    #[test]
+    fn gif_looping() {
+        assert_eq!(gif_repeat(&read("loop.gif")), Some(Repeat::Infinite));
+    }
+
+    #[test]
+    fn gif_not_looping() {
+        assert_eq!(gif_repeat(&read("noloop.gif")), None);
+    }
+
+    #[test]
+    fn gif_finite() {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder =
+                gif::Encoder::new(&mut bytes, 1, 1, &[0, 0, 0, 255, 255, 255]).unwrap();
+            encoder.set_repeat(gif::Repeat::Finite(3)).unwrap();
+            encoder.write_frame(&gif::Frame::default()).unwrap();
+        }
+
+        assert_eq!(gif_repeat(&bytes), Some(Repeat::Finite(3)));
+    }
+
+    #[test]
+    fn apng() {
+        assert_eq!(gif_repeat(&read("bwlines.png")), None);
+        assert_eq!(apng_repeat(&read("bwlines.png")), None);
+        assert!(apng_repeat(&read("apng_sample.png")).is_some());
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = read("loop.gif");
+
+        assert_eq!(gif_repeat(&bytes[..20]), None);
+        assert_eq!(gif_repeat(&[]), None);
+        assert_eq!(apng_repeat(&[]), None);
+    }
+}
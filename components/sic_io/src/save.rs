@@ -1,18 +1,46 @@
 use std::io::Write;
 use std::path::Path;
 
+use sic_core::animation::AnimatedImage;
+use sic_core::hdr::Rgba32FImage;
 use sic_core::image;
 
 use crate::conversion::{AutomaticColorTypeAdjustment, ConversionWriter};
 use crate::errors::SicIoError;
+use crate::format::EncodingFormat;
 
 pub fn export<W: Write>(
     image: &image::DynamicImage,
     writer: &mut W,
-    format: image::ImageOutputFormat,
+    format: EncodingFormat,
     export_settings: ExportSettings,
 ) -> Result<(), SicIoError> {
-    let conv = ConversionWriter::new(image);
+    export_with_hdr(image, None, writer, format, export_settings)
+}
+
+/// Like [export], but if the output format supports high dynamic range images, the unclamped
+/// samples are written instead of the image, if given.
+pub fn export_with_hdr<W: Write>(
+    image: &image::DynamicImage,
+    hdr: Option<&Rgba32FImage>,
+    writer: &mut W,
+    format: EncodingFormat,
+    export_settings: ExportSettings,
+) -> Result<(), SicIoError> {
+    let conv = ConversionWriter::new(image).with_hdr(hdr);
+    conv.write(writer, format, export_settings.adjust_color_type)
+}
+
+/// Like [export], but if the output format supports animated images, all frames of the animation
+/// are written instead of the image. Otherwise, the image is written.
+pub fn export_with_animation<W: Write>(
+    image: &image::DynamicImage,
+    animation: &AnimatedImage,
+    writer: &mut W,
+    format: EncodingFormat,
+    export_settings: ExportSettings,
+) -> Result<(), SicIoError> {
+    let conv = ConversionWriter::new(image).with_animation(Some(animation));
     conv.write(writer, format, export_settings.adjust_color_type)
 }
 
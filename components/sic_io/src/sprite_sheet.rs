@@ -0,0 +1,160 @@
+use std::io::Write;
+
+use serde::Serialize;
+use sic_core::image;
+use sic_core::image::GenericImage;
+
+use crate::errors::SicIoError;
+
+/// All frames of an (animated) image, laid out on a single image in a grid, from left to right
+/// and top to bottom. Each cell of the grid has the size of the largest frame.
+#[derive(Debug)]
+pub struct SpriteSheet {
+    pub image: image::RgbaImage,
+    pub manifest: SpriteSheetManifest,
+}
+
+/// Describes where each frame is located on the sprite sheet, and how long it should be shown.
+#[derive(Debug, Serialize)]
+pub struct SpriteSheetManifest {
+    pub frame_width: u32,
+    pub frame_height: u32,
+    pub columns: u32,
+    pub rows: u32,
+    pub frames: Vec<SpriteFrame>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpriteFrame {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub duration_ms: u32,
+}
+
+impl SpriteSheet {
+    /// Creates a sprite sheet from the given frames.
+    /// If no amount of columns is given, all frames will be placed on a single row.
+    pub fn from_frames(frames: &[image::Frame], columns: Option<u32>) -> Result<Self, SicIoError> {
+        if frames.is_empty() {
+            return Err(SicIoError::NoFramesForSpriteSheet);
+        }
+
+        let count = frames.len() as u32;
+        let columns = columns.unwrap_or(count).max(1).min(count);
+        let rows = count.div_ceil(columns);
+
+        let frame_width = frames.iter().map(|f| f.buffer().width()).max().unwrap_or(0);
+        let frame_height = frames
+            .iter()
+            .map(|f| f.buffer().height())
+            .max()
+            .unwrap_or(0);
+
+        let mut image = image::RgbaImage::new(frame_width * columns, frame_height * rows);
+        let mut sprites = Vec::with_capacity(frames.len());
+
+        for (i, frame) in frames.iter().enumerate() {
+            let i = i as u32;
+            let x = (i % columns) * frame_width;
+            let y = (i / columns) * frame_height;
+
+            image
+                .copy_from(frame.buffer(), x, y)
+                .map_err(SicIoError::ImageError)?;
+
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
+
+            sprites.push(SpriteFrame {
+                x,
+                y,
+                width: frame.buffer().width(),
+                height: frame.buffer().height(),
+                duration_ms: numerator / denominator.max(1),
+            });
+        }
+
+        Ok(Self {
+            image,
+            manifest: SpriteSheetManifest {
+                frame_width,
+                frame_height,
+                columns,
+                rows,
+                frames: sprites,
+            },
+        })
+    }
+}
+
+impl SpriteSheetManifest {
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> Result<(), SicIoError> {
+        serde_json::to_writer_pretty(writer, self).map_err(|err| SicIoError::Io(err.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load::{file_reader, load_frames, ImportConfig};
+    use sic_testing::*;
+
+    const GIF_LOOP: &str = "loop.gif";
+
+    fn frames() -> Vec<image::Frame> {
+        let load_path = setup_test_image(GIF_LOOP);
+        load_frames(
+            &mut file_reader(load_path).unwrap(),
+            &ImportConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn horizontal() {
+        let frames = frames();
+        let (w, h) = frames[0].buffer().dimensions();
+        let sheet = SpriteSheet::from_frames(&frames, None).unwrap();
+
+        assert_eq!(sheet.image.dimensions(), (w * 8, h));
+        assert_eq!(sheet.manifest.columns, 8);
+        assert_eq!(sheet.manifest.rows, 1);
+        assert_eq!(sheet.manifest.frames[7].x, w * 7);
+        assert_eq!(sheet.manifest.frames[7].y, 0);
+    }
+
+    #[test]
+    fn grid() {
+        let frames = frames();
+        let (w, h) = frames[0].buffer().dimensions();
+        let sheet = SpriteSheet::from_frames(&frames, Some(3)).unwrap();
+
+        assert_eq!(sheet.image.dimensions(), (w * 3, h * 3));
+        assert_eq!(sheet.manifest.rows, 3);
+
+        let last = &sheet.manifest.frames[7];
+        assert_eq!((last.x, last.y), (w, h * 2));
+        assert_eq!(
+            sheet.image.get_pixel(last.x + 10, last.y + 10),
+            frames[7].buffer().get_pixel(10, 10)
+        );
+    }
+
+    #[test]
+    fn manifest_json() {
+        let sheet = SpriteSheet::from_frames(&frames(), Some(4)).unwrap();
+
+        let mut out = Vec::new();
+        sheet.manifest.write_json(&mut out).unwrap();
+        let json = String::from_utf8(out).unwrap();
+
+        assert!(json.contains("\"columns\": 4"));
+        assert!(json.contains("\"duration_ms\""));
+    }
+
+    #[test]
+    fn no_frames() {
+        assert!(SpriteSheet::from_frames(&[], None).is_err());
+    }
+}
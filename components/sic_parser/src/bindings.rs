@@ -0,0 +1,294 @@
+//! The bindings module substitutes the values bound by `let` statements, e.g. `let size = 640;`,
+//! for the references to them, e.g. `resize $size $size`, before a script is parsed.
+//!
+//! Values are substituted as written, so any argument can be bound, including strings and named
+//! values. A value may refer to bindings made before it. Names are case insensitive, and can't be
+//! those of the built-in variables, e.g. `$width`, which are resolved when the image operations
+//! are applied instead. References within strings aren't substituted.
+
+use std::collections::HashMap;
+
+use crate::errors::SicParserError;
+use crate::quoting::Quotes;
+use crate::source_map::Script;
+use sic_image_engine::wrapper::expression::Variable;
+
+/// Removes the `let` statements from a script, and substitutes the values they bind for the
+/// references to them in the other statements.
+pub fn substitute_bindings(script: &Script) -> Result<Script, SicParserError> {
+    let mut bindings = HashMap::new();
+    let mut substituted = Script::default();
+
+    for statement in statements(script.as_str()) {
+        match parse_binding(statement) {
+            Some(binding) => {
+                let (name, value) = binding?;
+                let value =
+                    substitute_references(script, value, |name| bound_value(&bindings, name))?;
+
+                bindings.insert(name, value.as_str().to_string());
+            }
+            None => {
+                let statement =
+                    substitute_references(script, statement, |name| bound_value(&bindings, name))?;
+
+                substituted.push(&statement, statement.as_str());
+            }
+        }
+    }
+
+    // a script may start with a binding, after which the line breaks which followed it remain
+    Ok(substituted.trim_start())
+}
+
+/// Splits a script into its statements, each with the `;` which ends it, if any. A `;` within a
+/// string, or within the braces of a macro definition, doesn't end a statement; the closing brace
+/// does.
+pub(crate) fn statements(script: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut quotes = Quotes::default();
+    let mut depth = 0usize;
+    let mut chars = script.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if quotes.within(c) {
+            continue;
+        }
+
+        match c {
+            '{' => depth += 1,
+            '}' if depth > 1 => depth -= 1,
+            '}' if depth == 1 => {
+                depth = 0;
+
+                // a `;` may follow the closing brace on the same line
+                let mut end = index;
+                while let Some(&(next, c)) = chars.peek() {
+                    if c == ';' {
+                        end = next;
+                        chars.next();
+                        break;
+                    } else if c == ' ' || c == '\t' {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                statements.push(&script[start..=end]);
+                start = end + 1;
+            }
+            ';' if depth == 0 => {
+                statements.push(&script[start..=index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if start < script.len() {
+        statements.push(&script[start..]);
+    }
+
+    statements
+}
+
+/// The name and value of a `let` statement, e.g. `let size = 640;`, or `None` for other
+/// statements. The name is lowercased.
+pub(crate) fn parse_binding(statement: &str) -> Option<Result<(String, &str), SicParserError>> {
+    let statement = statement.trim();
+    let statement = statement.strip_suffix(';').unwrap_or(statement);
+
+    match statement.get(..4) {
+        Some(keyword) if keyword.eq_ignore_ascii_case("let ") => {}
+        _ => return None,
+    }
+
+    let invalid = || SicParserError::InvalidBinding(statement.to_string());
+
+    let binding = statement[4..].splitn(2, '=').collect::<Vec<_>>();
+    let (name, value) = match binding.as_slice() {
+        [name, value] => (name.trim(), value.trim()),
+        _ => return Some(Err(invalid())),
+    };
+
+    let valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !valid_name || value.is_empty() || value.contains('\n') {
+        Some(Err(invalid()))
+    } else if is_built_in(name) {
+        Some(Err(SicParserError::ReservedBinding(name.to_string())))
+    } else {
+        Some(Ok((name.to_ascii_lowercase(), value)))
+    }
+}
+
+pub(crate) fn is_built_in(name: &str) -> bool {
+    Variable::try_from_str(&format!("${}", name)).is_ok()
+}
+
+/// Writes a statement of the given script which is replaced by other statements, keeping the
+/// whitespace before, and the `;` after the statement. Nothing is written if there are no
+/// replacing statements.
+pub(crate) fn replace_statement(
+    script: &mut Script,
+    from: &Script,
+    statement: &str,
+    replacement: &Script,
+) {
+    let replaced = replacement.as_str().trim();
+    let replaced = replaced.strip_suffix(';').unwrap_or(replaced);
+
+    if !replaced.is_empty() {
+        let indent = statement.len() - statement.trim_start().len();
+
+        script.push(from, &statement[..indent]);
+        script.push(replacement, replaced);
+
+        let end = statement.trim_end();
+        if end.ends_with(';') {
+            script.push(from, &end[end.len() - 1..]);
+        }
+    }
+}
+
+/// The value bound to a name, `None` for built-in variables, and an error otherwise.
+fn bound_value<'b>(
+    bindings: &'b HashMap<String, String>,
+    name: &str,
+) -> Result<Option<&'b str>, SicParserError> {
+    match bindings.get(&name.to_ascii_lowercase()) {
+        Some(value) => Ok(Some(value)),
+        None if name.is_empty() || is_built_in(name) => Ok(None),
+        None => Err(SicParserError::UndefinedBinding(name.to_string())),
+    }
+}
+
+/// Substitutes values for the references, e.g. `$size`, outside of strings, in a part of the text
+/// of the given script. The given function returns the value of a reference by its name, or `None`
+/// to keep the reference as written.
+pub(crate) fn substitute_references<'v, F>(
+    script: &Script,
+    text: &str,
+    value: F,
+) -> Result<Script, SicParserError>
+where
+    F: Fn(&str) -> Result<Option<&'v str>, SicParserError>,
+{
+    let mut substituted = Script::default();
+    let mut quotes = Quotes::default();
+    let mut chars = text.char_indices().peekable();
+    // the text up to here is written already
+    let mut written = 0;
+
+    while let Some((index, c)) = chars.next() {
+        if !quotes.within(c) && c == '$' {
+            let mut end = index + 1;
+
+            while let Some(&(next, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+
+                end = next + 1;
+                chars.next();
+            }
+
+            if let Some(value) = value(&text[index + 1..end])? {
+                substituted.push(script, &text[written..index]);
+                substituted.push_substituted(value, script, &text[index..end]);
+                written = end;
+            }
+        }
+    }
+
+    substituted.push(script, &text[written..]);
+
+    Ok(substituted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[parameterized(
+        input = {
+            "let size = 640; resize $size $size",
+            "let size = 640;\nresize $size $size;\n",
+            "LET Size=640; resize $SIZE $size",
+            "let w = 640; let size = $w; resize $size $w",
+            "let w = 640; resize $w 1; let w = 320; resize $w 1",
+            "let logo = 'logo.png'; stamp $logo bottom-right 8",
+            "let color = rgba(255, 0, 0, 255); border 1 $color",
+            "let half = $height/2; crop 0 0 $width $half",
+            "diff '$size.png'",
+            "let name = 'it\\'s $size'; diff $name",
+            "let size = 640",
+            "blur 1; let size = 640;\nresize $size $size",
+            "blur 1",
+        },
+        expected = {
+            "resize 640 640",
+            "resize 640 640;\n",
+            "resize 640 640",
+            "resize 640 640",
+            "resize 640 1; resize 320 1",
+            "stamp 'logo.png' bottom-right 8",
+            "border 1 rgba(255, 0, 0, 255)",
+            "crop 0 0 $width $height/2",
+            "diff '$size.png'",
+            "diff 'it\\'s $size'",
+            "",
+            "blur 1;\nresize 640 640",
+            "blur 1",
+        }
+    )]
+    fn substitute_bindings_ok(input: &str, expected: &str) {
+        assert_eq!(
+            substitute_bindings(&Script::from(input)).unwrap().as_str(),
+            expected
+        );
+    }
+
+    #[parameterized(
+        input = {
+            "resize $size $size",
+            "resize $size 1; let size = 640",
+            "let size = 640; resize $sizes 1",
+            "let = 640",
+            "let size 640",
+            "let size =",
+            "let 2size = 640",
+            "let my-size = 640",
+            "let width = 640",
+            "let Frames = 1",
+            "let size = $undefined",
+        }
+    )]
+    fn substitute_bindings_err(input: &str) {
+        assert!(substitute_bindings(&Script::from(input)).is_err());
+    }
+
+    #[test]
+    fn statements_keep_separators() {
+        assert_eq!(
+            statements("blur 1;\ndiff 'a;b';invert"),
+            vec!["blur 1;", "\ndiff 'a;b';", "invert"]
+        );
+    }
+
+    #[test]
+    fn statements_end_after_braces() {
+        assert_eq!(
+            statements("def a() { blur 1; invert }\na(); def b() { a() };invert"),
+            vec![
+                "def a() { blur 1; invert }",
+                "\na();",
+                " def b() { a() };",
+                "invert"
+            ]
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! The diagnostic module describes where an image operation of a script couldn't be parsed: the
+//! operation, the argument at which parsing failed if it's known, and the line and column at which
+//! it starts, with the line itself, to underline the operation or argument in an error message.
+//!
+//! Positions refer to the script as written, e.g. to the reference to a binding, rather than to
+//! its value, once the diagnostic is located by the source map of the script. Until then, they
+//! refer to the script as it's parsed, that is, after its includes, macros and bindings have been
+//! substituted. Within an included script file, positions name the file.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::errors::SicParserError;
+use crate::source_map::Script;
+use crate::syntax::{Span, Statement, Token};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    operation: String,
+    argument: Option<String>,
+    location: Location,
+}
+
+impl Diagnostic {
+    /// Points at the name of an operation.
+    pub(crate) fn at_operation(operation: &Statement<'_>) -> Self {
+        Self {
+            operation: String::new(),
+            argument: None,
+            location: Location::of(&operation.name().span()),
+        }
+        .of_operation(operation)
+    }
+
+    /// Points at an argument; the operation it belongs to is set by `of_operation`.
+    pub(crate) fn at_argument(argument: &Token<'_>) -> Self {
+        Self {
+            operation: String::new(),
+            argument: Some(argument.as_str().to_string()),
+            location: Location::of(&argument.span()),
+        }
+    }
+
+    /// Sets the operation the pointed at argument belongs to.
+    pub(crate) fn of_operation(mut self, operation: &Statement<'_>) -> Self {
+        self.operation = operation.name().as_str().to_string();
+        self
+    }
+
+    /// Points at the operation or argument as written in the script, of which the given script is
+    /// the source map; an argument is quoted as written too, e.g. `$size` rather than its value.
+    pub(crate) fn located(self, script: &Script) -> Self {
+        let bytes = self.location.bytes.clone();
+        let argument = self.argument.map(|argument| {
+            script.written(bytes).map_or(argument, |written| {
+                written.source[written.bytes].to_string()
+            })
+        });
+
+        Self {
+            operation: self.operation,
+            argument,
+            location: self.location.located(script),
+        }
+    }
+
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    pub fn argument(&self) -> Option<&str> {
+        self.argument.as_deref()
+    }
+
+    /// The path of the included script file the operation or argument is written in, if it's
+    /// written in one.
+    pub fn file(&self) -> Option<&str> {
+        self.location.file()
+    }
+
+    /// The line, counted from 1.
+    pub fn line(&self) -> usize {
+        self.location.line
+    }
+
+    /// The column, in characters and counted from 1.
+    pub fn column(&self) -> usize {
+        self.location.column
+    }
+
+    /// The line of the script, with the operation or argument underlined by carets, e.g.:
+    ///
+    /// ```text
+    ///   |
+    /// 2 | resize 10 $depth
+    ///   |           ^^^^^^
+    /// ```
+    pub fn snippet(&self) -> String {
+        self.location.snippet()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(argument) = &self.argument {
+            write!(f, "argument '{}' of ", argument)?;
+        }
+
+        write!(f, "operation '{}' at {}", self.operation, self.location)
+    }
+}
+
+/// The line and column at which a part of a script starts, e.g. an argument, with the line itself,
+/// to underline the part in an error message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    file: Option<String>,
+    line: usize,
+    column: usize,
+    source_line: String,
+    width: usize,
+    // the bytes of the part within the script as it's parsed, by which it's located
+    bytes: Range<usize>,
+}
+
+impl Location {
+    pub(crate) fn of(span: &Span<'_>) -> Self {
+        let (line, column) = span.line_col();
+
+        // the underline ends with the line, for text which spans several lines
+        let width = span
+            .as_str()
+            .lines()
+            .next()
+            .map_or(0, |first| first.chars().count());
+
+        Self {
+            file: None,
+            line,
+            column,
+            source_line: span.line_of().to_string(),
+            width: width.max(1),
+            bytes: span.bytes(),
+        }
+    }
+
+    /// The location within the script as written, of which the given script is the source map.
+    pub(crate) fn located(self, script: &Script) -> Self {
+        match script.written(self.bytes.clone()) {
+            Some(written) => Self {
+                file: written.name.map(str::to_string),
+                bytes: self.bytes,
+                ..Self::of(&Span::new(
+                    written.source,
+                    written.bytes.start,
+                    written.bytes.end,
+                ))
+            },
+            None => self,
+        }
+    }
+
+    /// The path of the included script file the part is written in, if it's written in one.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// The line, counted from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column, in characters and counted from 1.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The line of the script, with the part underlined by carets.
+    pub fn snippet(&self) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        // tabs are kept, so the carets line up with the line above them
+        let indent = self
+            .source_line
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        format!(
+            "{gutter} |\n{line} | {source}\n{gutter} | {indent}{carets}",
+            gutter = gutter,
+            line = self.line,
+            source = self.source_line,
+            indent = indent,
+            carets = "^".repeat(self.width),
+        )
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)?;
+
+        match &self.file {
+            Some(file) => write!(f, " of '{}'", file),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The arguments of an operation, which remembers the arguments it yielded, so the one at which
+/// parsing failed can be pointed at.
+pub(crate) struct Arguments<'i> {
+    tokens: Vec<Token<'i>>,
+    yielded: usize,
+    exhausted: bool,
+}
+
+impl<'i> Arguments<'i> {
+    pub(crate) fn new(tokens: Vec<Token<'i>>) -> Self {
+        Self {
+            tokens,
+            yielded: 0,
+            exhausted: false,
+        }
+    }
+
+    /// The arguments which haven't been yielded yet.
+    pub(crate) fn remaining(&self) -> &[Token<'i>] {
+        &self.tokens[self.yielded..]
+    }
+
+    /// Points at the argument at which parsing failed, or `None` if it's not known.
+    ///
+    /// While the arguments are parsed one by one, that's the last argument yielded. Some arguments
+    /// are parsed only after all of them have been collected though; then it's the first argument,
+    /// or value within an argument, e.g. the `300` of `rgba(300, 0, 0, 255)`, which is quoted by
+    /// the error.
+    pub(crate) fn diagnostic(&self, err: &SicParserError) -> Option<Diagnostic> {
+        let yielded = &self.tokens[..self.yielded];
+
+        if !self.exhausted {
+            return yielded.last().map(Diagnostic::at_argument);
+        }
+
+        let message = err.to_string();
+        let quoted = |token: &&Token<'_>| {
+            ['\'', '`', '"']
+                .iter()
+                .any(|quote| message.contains(&format!("{0}{1}{0}", quote, token.as_str())))
+        };
+
+        yielded
+            .iter()
+            .flat_map(flatten)
+            .find(quoted)
+            .map(Diagnostic::at_argument)
+    }
+}
+
+impl<'i> Iterator for Arguments<'i> {
+    type Item = Token<'i>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.tokens.get(self.yielded).cloned();
+
+        match &next {
+            Some(_) => self.yielded += 1,
+            None => self.exhausted = true,
+        }
+
+        next
+    }
+}
+
+/// A token, followed by the tokens within it, depth first.
+fn flatten<'t, 'i>(token: &'t Token<'i>) -> Box<dyn Iterator<Item = &'t Token<'i>> + 't> {
+    Box::new(std::iter::once(token).chain(token.inner().iter().flat_map(flatten)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::parse_statements;
+
+    fn operations(script: &str) -> Vec<Statement<'_>> {
+        parse_statements(script).unwrap()
+    }
+
+    #[test]
+    fn at_operation() {
+        let operations = operations("blur 1;\n  flip-horizontal");
+        let diagnostic = Diagnostic::at_operation(&operations[1]);
+
+        assert_eq!(diagnostic.operation(), "flip-horizontal");
+        assert_eq!(diagnostic.argument(), None);
+        assert_eq!((diagnostic.line(), diagnostic.column()), (2, 3));
+        assert_eq!(
+            diagnostic.to_string(),
+            "operation 'flip-horizontal' at line 2, column 3"
+        );
+        assert_eq!(
+            diagnostic.snippet(),
+            "  |\n2 |   flip-horizontal\n  |   ^^^^^^^^^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn at_argument() {
+        let operations = operations("blur 1;\nresize 10 $depth;\n");
+        let argument = &operations[1].arguments()[1];
+        let diagnostic = Diagnostic::at_argument(argument).of_operation(&operations[1]);
+
+        assert_eq!(diagnostic.argument(), Some("$depth"));
+        assert_eq!(
+            diagnostic.to_string(),
+            "argument '$depth' of operation 'resize' at line 2, column 11"
+        );
+        assert_eq!(
+            diagnostic.snippet(),
+            "  |\n2 | resize 10 $depth;\n  |           ^^^^^^"
+        );
+    }
+
+    #[test]
+    fn snippet_keeps_tabs() {
+        let operations = operations("stamp '\tlogo.png' top 8");
+        let argument = &operations[0].arguments()[1];
+
+        assert_eq!(
+            Diagnostic::at_argument(argument).snippet(),
+            "  |\n1 | stamp '\tlogo.png' top 8\n  |        \t          ^^^"
+        );
+    }
+
+    #[test]
+    fn arguments_point_at_last() {
+        let operations = operations("resize 10 20");
+        let mut arguments = Arguments::new(operations[0].arguments().to_vec());
+        let err = SicParserError::ValueParsingError("a dimension".to_string());
+
+        assert!(arguments.diagnostic(&err).is_none());
+
+        arguments.next();
+        arguments.next();
+        assert_eq!(arguments.diagnostic(&err).unwrap().argument(), Some("20"));
+    }
+
+    #[test]
+    fn arguments_point_at_quoted() {
+        let operations = operations("border 1 rgba(300, 0, 0, 255)");
+        let mut arguments = Arguments::new(operations[0].arguments().to_vec());
+        while arguments.next().is_some() {}
+
+        let err = SicParserError::ValueParsingError("300".to_string());
+        let diagnostic = arguments.diagnostic(&err).unwrap();
+        assert_eq!(diagnostic.argument(), Some("300"));
+        assert_eq!(diagnostic.column(), 15);
+
+        let err = SicParserError::ValueParsingError("a color".to_string());
+        assert!(arguments.diagnostic(&err).is_none());
+    }
+}
@@ -0,0 +1,144 @@
+//! The environment module substitutes the values of environment variables for the references to
+//! them, e.g. `resize ${WIDTH} 0` or `stamp '${LOGO}' bottom-right 8`, before a script is parsed,
+//! so a script can be parameterized without rewriting its text, e.g. in CI jobs.
+//!
+//! Unlike bindings, references are substituted within strings too, and names are case sensitive.
+//! Values are substituted as written. A reference to a variable which isn't set is an error; `$${`
+//! is written as `${`, without substituting anything.
+
+use std::env::VarError;
+
+use crate::errors::SicParserError;
+use crate::source_map::Script;
+
+/// Substitutes the values of the environment variables of the current process for the references
+/// to them.
+pub fn substitute_environment_variables(script: &Script) -> Result<Script, SicParserError> {
+    substitute_variables(script, |name| std::env::var(name))
+}
+
+/// Substitutes the values of variables for the references to them; the given function returns
+/// the value of a variable by its name.
+pub(crate) fn substitute_variables<F>(script: &Script, value: F) -> Result<Script, SicParserError>
+where
+    F: Fn(&str) -> Result<String, VarError>,
+{
+    let mut substituted = Script::default();
+    let mut rest = script.as_str();
+
+    while let Some(start) = rest.find("${") {
+        // `$${` escapes a reference
+        if rest[..start].ends_with('$') {
+            substituted.push(script, &rest[..start - 1]);
+            substituted.push(script, &rest[start..start + 2]);
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        substituted.push(script, &rest[..start]);
+
+        let reference = &rest[start..];
+        let end = reference
+            .find('}')
+            .ok_or_else(|| SicParserError::InvalidEnvironmentVariable(unclosed(reference)))?;
+        let name = &reference[2..end];
+
+        if !is_variable_name(name) {
+            return Err(SicParserError::InvalidEnvironmentVariable(
+                reference[..=end].to_string(),
+            ));
+        }
+
+        match value(name) {
+            Ok(value) => substituted.push_substituted(&value, script, &reference[..=end]),
+            Err(VarError::NotPresent) => {
+                return Err(SicParserError::UndefinedEnvironmentVariable(
+                    name.to_string(),
+                ))
+            }
+            Err(VarError::NotUnicode(_)) => {
+                return Err(SicParserError::EnvironmentVariableNotUnicode(
+                    name.to_string(),
+                ))
+            }
+        }
+
+        rest = &reference[end + 1..];
+    }
+
+    substituted.push(script, rest);
+
+    Ok(substituted)
+}
+
+fn is_variable_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The unclosed reference, up to the end of its line.
+fn unclosed(reference: &str) -> String {
+    reference.lines().next().unwrap_or(reference).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(name: &str) -> Result<String, VarError> {
+        match name {
+            "WIDTH" => Ok("640".to_string()),
+            "LOGO" => Ok("images/logo.png".to_string()),
+            "EMPTY" => Ok(String::new()),
+            _ => Err(VarError::NotPresent),
+        }
+    }
+
+    #[parameterized(
+        input = {
+            "resize ${WIDTH} 0",
+            "resize ${WIDTH} ${WIDTH}",
+            "stamp '${LOGO}' bottom-right 8",
+            "blur 1${EMPTY}",
+            "crop 0 0 $width $height/2",
+            "diff '$${WIDTH}.png'",
+            "blur 1",
+        },
+        expected = {
+            "resize 640 0",
+            "resize 640 640",
+            "stamp 'images/logo.png' bottom-right 8",
+            "blur 1",
+            "crop 0 0 $width $height/2",
+            "diff '${WIDTH}.png'",
+            "blur 1",
+        }
+    )]
+    fn substitute_variables_ok(input: &str, expected: &str) {
+        assert_eq!(
+            substitute_variables(&Script::from(input), value)
+                .unwrap()
+                .as_str(),
+            expected
+        );
+    }
+
+    #[test]
+    fn undefined_variable() {
+        assert!(matches!(
+            substitute_variables(&Script::from("resize ${WIDTH} ${HEIGHT}"), value),
+            Err(SicParserError::UndefinedEnvironmentVariable(name)) if name == "HEIGHT"
+        ));
+    }
+
+    #[parameterized(
+        input = { "resize ${WIDTH 0", "resize ${} 0", "resize ${1WIDTH} 0", "resize ${WID-TH} 0" },
+        reference = { "${WIDTH 0", "${}", "${1WIDTH}", "${WID-TH}" }
+    )]
+    fn invalid_reference(input: &str, reference: &str) {
+        assert!(matches!(
+            substitute_variables(&Script::from(input), value),
+            Err(SicParserError::InvalidEnvironmentVariable(text)) if text == reference
+        ));
+    }
+}
@@ -1,4 +1,6 @@
+use crate::diagnostic::{Diagnostic, Location};
 use crate::named_value::NamedValueError;
+use crate::source_map::Script;
 use sic_image_engine::errors::SicImageEngineError;
 use thiserror::Error;
 
@@ -13,17 +15,84 @@ pub enum SicParserError {
     #[error("unable to parse filter type: {0}")]
     FilterTypeError(SicImageEngineError),
 
+    #[error("unable to include script files which include each other: {0}")]
+    IncludeCycle(String),
+
+    #[error("unable to parse macro definition '{0}'; expected a name, parameters and a body, e.g. 'def thumb(w) {{ resize $w $w }}'")]
+    InvalidMacro(String),
+
+    #[error("unable to parse macro call '{0}'; expected a name and arguments, e.g. 'thumb(256)'")]
+    InvalidMacroCall(String),
+
+    #[error("unable to parse {0}: {1}\n{}", .0.snippet())]
+    InvalidOperation(Box<Diagnostic>, Box<SicParserError>),
+
+    #[error("unable to parse binding '{0}'; expected a name and a value, e.g. 'let size = 640'")]
+    InvalidBinding(String),
+
+    #[error(
+        "unable to parse include '{0}'; expected a quoted path, e.g. 'include \"common.sic\"'"
+    )]
+    InvalidInclude(String),
+
+    #[error("macro '{0}' takes {1} argument(s), but got {2}")]
+    MacroArgumentCount(String, usize, usize),
+
+    #[error("missing argument; expected {0}")]
+    MissingArgument(String),
+
+    #[error("a macro file may only define macros, but got '{0}'")]
+    MacroFileStatement(String),
+
+    #[error(
+        "unable to parse environment variable reference '{0}'; expected a name, e.g. '${{WIDTH}}'"
+    )]
+    InvalidEnvironmentVariable(String),
+
+    #[error("the value of environment variable '{0}' is not valid unicode")]
+    EnvironmentVariableNotUnicode(String),
+
+    #[error("environment variable '{0}' is not set; set it, or write '$${{{0}}}' to keep '${{{0}}}' as it is")]
+    UndefinedEnvironmentVariable(String),
+
     #[error("unable to parse named value: {0}")]
     NamedValueParsingError(NamedValueError),
 
-    #[error("string value expected an inner value, but none was found")]
-    NoInnerString,
+    #[error("macros can't be defined within macro '{0}'")]
+    NestedMacro(String),
+
+    #[error("unable to parse operation occurrence: {0}")]
+    OccurrenceError(SicImageEngineError),
 
     #[error("{0}")]
     OperationError(OperationParamError),
 
-    #[error("unable to parse script: {0}")]
-    PestGrammarError(String),
+    #[error("a '|' may only separate the rows of a kernel")]
+    RowSeparator,
+
+    #[error("macro '{0}' calls itself")]
+    RecursiveMacro(String),
+
+    #[error("unable to parse script file '{0}': {1}")]
+    ScriptFileParsingError(String, Box<SicParserError>),
+
+    #[error("unable to read script file '{0}': {1}")]
+    ScriptFileError(String, std::io::Error),
+
+    #[error("unable to parse script: {0} at {1}")]
+    SyntaxError(String, Box<Location>),
+
+    #[error("'${0}' is a built-in variable, so it can't be bound by 'let'")]
+    ReservedBinding(String),
+
+    #[error("expected a string enclosed in quotation marks, but got '{0}'")]
+    UnquotedString(String),
+
+    #[error("variable '${0}' is not defined; bind it before it's used, e.g. 'let {0} = 640', or use one of the built-in variables '$width', '$height', '$aspect' and '$frames'")]
+    UndefinedBinding(String),
+
+    #[error("macro '{0}' is not defined; define it before it's called, e.g. 'def {0}(w) {{ resize $w $w }}'")]
+    UndefinedMacro(String),
 
     #[error("parsing failed: operation doesn't exist")]
     UnknownOperationError,
@@ -35,6 +104,22 @@ pub enum SicParserError {
     ValueParsingErrorWithInnerError(String, Box<dyn std::error::Error + Send + Sync>),
 }
 
+impl SicParserError {
+    /// Points the error at the script as written, of which the given script is the source map,
+    /// rather than at the script as it's parsed.
+    pub(crate) fn located(self, script: &Script) -> Self {
+        match self {
+            SicParserError::InvalidOperation(diagnostic, err) => {
+                SicParserError::InvalidOperation(Box::new(diagnostic.located(script)), err)
+            }
+            SicParserError::SyntaxError(message, location) => {
+                SicParserError::SyntaxError(message, Box::new(location.located(script)))
+            }
+            err => err,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum OperationParamError {
     #[error(
@@ -45,9 +130,6 @@ pub enum OperationParamError {
     #[error("Unable to parse `set` environment command. Error on element: {0}")]
     SetEnvironmentElement(String),
 
-    #[error("Unable to parse operation argument(s): {0}")]
-    PestArgError(String),
-
     #[error(
         "Unable to parse `del` environment command. Error: expected a single `del` inner element."
     )]
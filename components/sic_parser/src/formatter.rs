@@ -0,0 +1,285 @@
+//! The formatter module reprints image operation scripts in a canonical form.
+//!
+//! Statements are put on their own line, each followed by a `;`. Operation and modifier names are
+//! lowercased, arguments are separated by a single space, and the arguments of named values by a
+//! comma and a space. Numbers lose superfluous leading and trailing zeros, e.g. `01.50` becomes
+//! `1.5`. Strings are kept as written. Bindings are written as `let <name> = <value>`; kernels
+//! which refer to a binding are kept as written, since their rows can't be told apart.
+//! Includes are written as `include <path>`, and the included script files aren't formatted.
+//! Macro definitions are written on a single line, as `def <name>(<parameters>) { <body> }`, and
+//! calls as `<name>(<arguments>)`.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::bindings::{parse_binding, statements};
+use crate::errors::SicParserError;
+use crate::include::{parse_include, resolve_includes};
+use crate::macros::{expand_macros, parse_call, parse_definition_parts, Macros};
+use crate::parse_expanded_script;
+use crate::quoting::quote;
+use crate::source_map::Script;
+use crate::syntax::{parse_statements, Statement, Token, TokenKind};
+
+/// Parses a script, and reprints it in canonical form. Scripts which can't be parsed to image
+/// operations are rejected, so formatting never changes what a script does. The script files it
+/// includes are relative to the given directory.
+pub fn format_script(script: &str, directory: &Path) -> Result<String, SicParserError> {
+    let resolved = resolve_includes(&Script::from(script), directory)?;
+    parse_expanded_script(&expand_macros(&resolved, &Macros::default())?)?;
+
+    let statements = statements(script)
+        .into_iter()
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .fold(String::new(), |mut formatted, statement| {
+            // writing to a String can't fail
+            let _ = writeln!(formatted, "{};", format_source_statement(statement));
+            formatted
+        });
+
+    Ok(statements)
+}
+
+fn format_source_statement(statement: &str) -> String {
+    if let Some(Ok((name, value))) = parse_binding(statement) {
+        return format!("let {} = {}", name, value);
+    }
+
+    if let Some(Ok(path)) = parse_include(statement) {
+        return format!("include {}", quote(&path));
+    }
+
+    if let Some(Ok((name, parameters, body))) = parse_definition_parts(statement) {
+        let body = statements(body)
+            .into_iter()
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty())
+            .map(format_source_statement)
+            .collect::<Vec<_>>();
+
+        return format!(
+            "def {}({}) {{ {} }}",
+            name.to_ascii_lowercase(),
+            parameters.join(", ").to_ascii_lowercase(),
+            body.join("; ")
+        );
+    }
+
+    if let Some(Ok((name, arguments))) = parse_call(statement) {
+        return format!("{}({})", name.to_ascii_lowercase(), arguments.join(", "));
+    }
+
+    let statement = statement.strip_suffix(';').unwrap_or(statement).trim_end();
+
+    match parse_statements(statement) {
+        Ok(parsed) => parsed
+            .iter()
+            .map(|parsed| format_statement(parsed).unwrap_or_else(|| statement.to_string()))
+            .collect(),
+        Err(_) => statement.to_string(),
+    }
+}
+
+// a kernel which refers to a binding is kept as written
+fn format_statement(statement: &Statement<'_>) -> Option<String> {
+    let name = statement.name().as_str().to_lowercase();
+    let arguments = statement.arguments();
+
+    let formatted = match name.as_str() {
+        // the first argument may be the occurrence of an operation, e.g. 'resize.1'
+        "set" => std::iter::once(name)
+            .chain(arguments.iter().enumerate().map(|(index, argument)| {
+                match argument.as_str().rsplit_once('.') {
+                    Some((operation, occurrence)) if index == 0 => {
+                        format!("{}.{}", operation.to_lowercase(), format_number(occurrence))
+                    }
+                    _ => format_value(argument),
+                }
+            }))
+            .collect::<Vec<_>>()
+            .join(" "),
+        // the rows of the kernel are kept apart, like in the documentation
+        "filter3x3" | "convolve" => {
+            let values = arguments
+                .iter()
+                .filter(|argument| argument.kind() != TokenKind::RowSeparator)
+                .map(format_value)
+                .collect::<Vec<_>>();
+
+            if values.iter().any(|value| value.starts_with('$')) {
+                return None;
+            }
+
+            // a convolution kernel is preceded by its size
+            let (size, weights) = match name.as_str() {
+                "convolve" => (values[0].parse::<usize>().unwrap_or(1).max(1), &values[1..]),
+                _ => (3, &values[..]),
+            };
+
+            let rows = weights
+                .chunks(size)
+                .map(|row| row.join(" "))
+                .collect::<Vec<_>>()
+                .join(" | ");
+
+            match name.as_str() {
+                "convolve" => format!("convolve {} {}", values[0], rows),
+                _ => format!("filter3x3 {}", rows),
+            }
+        }
+        _ => std::iter::once(name)
+            .chain(arguments.iter().map(format_value))
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+
+    Some(formatted)
+}
+
+fn format_value(token: &Token<'_>) -> String {
+    let text = token.as_str();
+
+    match token.kind() {
+        TokenKind::Word if is_number(text) => format_number(text),
+        // expected inner tokens: the identifier, followed by the arguments
+        TokenKind::NamedValue => {
+            let (ident, arguments) = token.inner().split_first().unwrap_or((token, &[]));
+            let arguments = arguments.iter().map(format_value).collect::<Vec<_>>();
+
+            format!("{}({})", ident.as_str(), arguments.join(", "))
+        }
+        TokenKind::String => text.to_string(),
+        // channels, gravities, modifier names and modifier values are case insensitive
+        _ => text.to_lowercase(),
+    }
+}
+
+/// Whether a word is a number, e.g. `-1.50`.
+fn is_number(word: &str) -> bool {
+    let digits = word.strip_prefix(&['-', '+'][..]).unwrap_or(word);
+    let mut parts = digits.splitn(2, '.');
+
+    parts.all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Removes the leading zeros of the integer part, and the trailing zeros of the fractional part of
+/// a number; a negative zero becomes `0`.
+fn format_number(number: &str) -> String {
+    let negative = number.starts_with('-');
+    let digits = number.trim_start_matches('-');
+
+    let mut parts = digits.splitn(2, '.');
+    let integer = parts.next().unwrap_or_default().trim_start_matches('0');
+    let fraction = parts.next().unwrap_or_default().trim_end_matches('0');
+
+    let integer = if integer.is_empty() { "0" } else { integer };
+
+    let formatted = if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{}.{}", integer, fraction)
+    };
+
+    if negative && formatted != "0" {
+        format!("-{}", formatted)
+    } else {
+        formatted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_script;
+
+    #[parameterized(
+        input = { "0", "-0", "007", "1.50", "-0.0", "-03.140", "10", "0.05" },
+        expected = { "0", "0", "7", "1.5", "0", "-3.14", "10", "0.05" }
+    )]
+    fn number(input: &str, expected: &str) {
+        assert_eq!(format_number(input), expected);
+    }
+
+    #[parameterized(
+        input = {
+            "blur 01.50",
+            "BLUR   1;flip-horizontal;",
+            "flip-vertical;\n\n\ninvert",
+            "resize 10 20;\nset  sampling-filter  Lanczos3;\ndel sampling-filter",
+            "set preserve-aspect-ratio true",
+            "SET Resize.01  sampling-filter Nearest",
+            "filter3x3 1 2 3 4 5 6 7 8 9;",
+            "filter3x3 0 1.0 2 | 3 4 5 | 6 7 8.50",
+            "convolve 3 0 -1 0 -1 5 -1 0 -1 0",
+            "CONVOLVE 1 | 1.0;",
+            "duotone rgba(0,0,0,255)   rgba(255 ,200 , 0,255)",
+            "gradient-map stop(0, rgba(0,0,0,255)) stop(1.000,rgba(255,255,255,255))",
+            "diff 'my image.png'; stamp \"logo.png\" Bottom-Right 08",
+            "channel-swap R b; hue-rotate -090",
+            "LET Size=0640;\nresize $size   $SIZE;crop 0 0 $Width $size",
+            "let radius = 1.5; BLUR   $radius",
+            "let row = 1 2 3; FILTER3X3 $row  $row $row",
+            "DEF Thumb( W ) {\n    resize $w $W;\n    BLUR 1;\n}\nTHUMB(2);Thumb(  3 )",
+            "def frame(w, color) { border $w $color }; frame(1,rgba(0,0,0,255))",
+            "",
+        },
+        expected = {
+            "blur 1.5;\n",
+            "blur 1;\nflip-horizontal;\n",
+            "flip-vertical;\ninvert;\n",
+            "resize 10 20;\nset sampling-filter lanczos3;\ndel sampling-filter;\n",
+            "set preserve-aspect-ratio true;\n",
+            "set resize.1 sampling-filter nearest;\n",
+            "filter3x3 1 2 3 | 4 5 6 | 7 8 9;\n",
+            "filter3x3 0 1 2 | 3 4 5 | 6 7 8.5;\n",
+            "convolve 3 0 -1 0 | -1 5 -1 | 0 -1 0;\n",
+            "convolve 1 1;\n",
+            "duotone rgba(0, 0, 0, 255) rgba(255, 200, 0, 255);\n",
+            "gradient-map stop(0, rgba(0, 0, 0, 255)) stop(1, rgba(255, 255, 255, 255));\n",
+            "diff 'my image.png';\nstamp \"logo.png\" bottom-right 8;\n",
+            "channel-swap r b;\nhue-rotate -90;\n",
+            "let size = 0640;\nresize $size $size;\ncrop 0 0 $width $size;\n",
+            "let radius = 1.5;\nblur $radius;\n",
+            "let row = 1 2 3;\nFILTER3X3 $row  $row $row;\n",
+            "def thumb(w) { resize $w $w; blur 1 };\nthumb(2);\nthumb(3);\n",
+            "def frame(w, color) { border $w $color };\nframe(1, rgba(0,0,0,255));\n",
+            "",
+        }
+    )]
+    fn format(input: &str, expected: &str) {
+        let formatted = format_script(input, Path::new("")).unwrap();
+
+        assert_eq!(formatted, expected);
+
+        // formatting is idempotent, and doesn't change the image operations
+        assert_eq!(format_script(&formatted, Path::new("")).unwrap(), formatted);
+        assert_eq!(
+            parse_script(&formatted).unwrap(),
+            parse_script(input).unwrap()
+        );
+    }
+
+    #[parameterized(input = { "blur", "blur 1 2", "set sampling-filter unknown", "rotate91" })]
+    fn format_invalid(input: &str) {
+        assert!(format_script(input, Path::new("")).is_err());
+    }
+
+    #[test]
+    fn format_includes() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/include_test/fmt");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("sizes.sic"), "let  size=640").unwrap();
+
+        // the included script file isn't formatted
+        assert_eq!(
+            format_script("INCLUDE  \"sizes.sic\";resize $size 1", &directory).unwrap(),
+            "include 'sizes.sic';\nresize $size 1;\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(directory.join("sizes.sic")).unwrap(),
+            "let  size=640"
+        );
+        assert!(format_script("include 'missing.sic'", &directory).is_err());
+    }
+}
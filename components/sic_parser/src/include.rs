@@ -0,0 +1,206 @@
+//! The include module replaces `include "<path>"` statements by the statements of the script file
+//! at the path, before a script is parsed, so a library of common image operations can be shared
+//! by several scripts.
+//!
+//! Paths are relative to the directory of the script which includes them; for scripts which
+//! aren't read from a file, e.g. those given with `--apply-operations`, to the current directory.
+//! Included scripts may include other scripts, but a script may not (indirectly) include itself.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use crate::bindings::{replace_statement, statements};
+use crate::errors::SicParserError;
+use crate::quoting::unquote;
+use crate::source_map::Script;
+
+/// Replaces the `include` statements of a script by the statements of the included script files,
+/// of which the paths are relative to the given directory.
+pub fn resolve_includes(script: &Script, directory: &Path) -> Result<Script, SicParserError> {
+    resolve(script, directory, &mut Vec::new())
+}
+
+/// Reads a script file, and replaces its `include` statements by the statements of the included
+/// script files, of which the paths are relative to the directory of the script file.
+pub fn read_script_file(path: &Path) -> Result<Script, SicParserError> {
+    read(path, false, &mut Vec::new())
+}
+
+/// Reads a script file like `read_script_file`, which is included by the script which is parsed,
+/// e.g. a script file of macros, so errors within it name the file.
+pub(crate) fn read_included_script_file(path: &Path) -> Result<Script, SicParserError> {
+    read(path, true, &mut Vec::new())
+}
+
+/// The path of an `include` statement, e.g. `include 'common.sic';`, without its quotation marks,
+/// or `None` for other statements.
+pub(crate) fn parse_include(statement: &str) -> Option<Result<Cow<'_, str>, SicParserError>> {
+    let statement = statement.trim();
+    let statement = statement.strip_suffix(';').unwrap_or(statement);
+
+    match statement.get(..8) {
+        Some(keyword) if keyword.eq_ignore_ascii_case("include ") => {}
+        _ => return None,
+    }
+
+    let path = unquote(statement[8..].trim()).filter(|path| !path.is_empty());
+
+    Some(path.ok_or_else(|| SicParserError::InvalidInclude(statement.to_string())))
+}
+
+/// `stack` holds the script files which are being included, outermost first.
+fn resolve(
+    script: &Script,
+    directory: &Path,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Script, SicParserError> {
+    let mut resolved = Script::default();
+
+    for statement in statements(script.as_str()) {
+        match parse_include(statement) {
+            Some(path) => {
+                let included = read(&directory.join(&*path?), true, stack)?;
+                replace_statement(&mut resolved, script, statement, &included);
+            }
+            None => resolved.push(script, statement),
+        }
+    }
+
+    // a script may start with an empty include, after which the line breaks which followed it
+    // remain
+    Ok(resolved.trim_start())
+}
+
+// `included` tells whether the script file is included by the script which is parsed, rather than
+// being that script itself
+fn read(path: &Path, included: bool, stack: &mut Vec<PathBuf>) -> Result<Script, SicParserError> {
+    let script_file_error = |err| SicParserError::ScriptFileError(path.display().to_string(), err);
+
+    let canonical = path.canonicalize().map_err(script_file_error)?;
+
+    if stack.contains(&canonical) {
+        let cycle = stack
+            .iter()
+            .skip_while(|included| **included != canonical)
+            .chain(std::iter::once(&canonical))
+            .map(|included| included.display().to_string())
+            .collect::<Vec<_>>();
+
+        return Err(SicParserError::IncludeCycle(cycle.join(" -> ")));
+    }
+
+    let script = std::fs::read_to_string(path).map_err(script_file_error)?;
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let script = if included {
+        Script::included(&script, path)
+    } else {
+        Script::from(script.as_str())
+    };
+
+    stack.push(canonical);
+    let resolved = resolve(&script, directory, stack)?;
+    stack.pop();
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes the script files to a new directory below the target directory.
+    fn scripts(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../target/include_test")
+            .join(name);
+
+        for (path, script) in files {
+            let path = directory.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, script).unwrap();
+        }
+
+        directory
+    }
+
+    #[parameterized(
+        input = {
+            "include 'common.sic'",
+            "blur 1; INCLUDE \"common.sic\"; invert",
+            "blur 1;\ninclude 'common.sic';\ninvert",
+            "include 'empty.sic';\ninvert",
+            "include 'nested/outer.sic'",
+            "diff 'include.png'",
+            "include 'it\\'s.sic'",
+        },
+        expected = {
+            "flip-horizontal;\nresize 10 10",
+            "blur 1; flip-horizontal;\nresize 10 10; invert",
+            "blur 1;\nflip-horizontal;\nresize 10 10;\ninvert",
+            "invert",
+            "rotate90; flip-horizontal;\nresize 10 10",
+            "diff 'include.png'",
+            "invert",
+        }
+    )]
+    fn resolve_includes_ok(input: &str, expected: &str) {
+        let directory = scripts(
+            "ok",
+            &[
+                ("common.sic", "flip-horizontal;\nresize 10 10;\n"),
+                ("empty.sic", "\n"),
+                ("nested/outer.sic", "rotate90; include '../common.sic'"),
+                ("it's.sic", "invert"),
+            ],
+        );
+
+        assert_eq!(
+            resolve_includes(&Script::from(input), &directory)
+                .unwrap()
+                .as_str(),
+            expected
+        );
+    }
+
+    #[test]
+    fn include_cycle() {
+        let directory = scripts(
+            "cycle",
+            &[
+                ("a.sic", "blur 1; include 'b.sic'"),
+                ("b.sic", "include 'a.sic'"),
+            ],
+        );
+
+        let err = read_script_file(&directory.join("a.sic")).unwrap_err();
+
+        assert!(matches!(err, SicParserError::IncludeCycle(cycle)
+            if cycle.ends_with(&format!("a.sic -> {} -> {}",
+                directory.join("b.sic").canonicalize().unwrap().display(),
+                directory.join("a.sic").canonicalize().unwrap().display()))));
+    }
+
+    #[test]
+    fn include_itself() {
+        let directory = scripts("itself", &[("self.sic", "include 'self.sic'")]);
+
+        assert!(matches!(
+            resolve_includes(&Script::from("include 'self.sic'"), &directory),
+            Err(SicParserError::IncludeCycle(_))
+        ));
+    }
+
+    #[parameterized(input = {
+        "include 'missing.sic'",
+        "include common.sic",
+        "include ''",
+        "include 'common.sic\"",
+        "include 'a' 'b'",
+    })]
+    fn resolve_includes_err(input: &str) {
+        let directory = scripts("err", &[("common.sic", "invert")]);
+
+        assert!(resolve_includes(&Script::from(input), &directory).is_err());
+    }
+}
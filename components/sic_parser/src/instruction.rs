@@ -0,0 +1,288 @@
+//! The instruction module constructs the instruction of an image operation or modifier from its
+//! name and its arguments. It's shared by scripts, which are split into operations and arguments by
+//! the syntax module, and by the image operations given as cli arguments, which are split by the
+//! shell, so both are parsed by the same implementation.
+
+use crate::errors::{OperationParamError, SicParserError};
+use crate::value_parser::ParseInputsFromIter;
+use sic_core::image::Rgba;
+use sic_image_engine::engine::{DeferredOp, EnvItem, Instr};
+use sic_image_engine::wrapper::adaptive_threshold_method::AdaptiveThresholdMethod;
+use sic_image_engine::wrapper::blur_algorithm::BlurAlgorithm;
+use sic_image_engine::wrapper::border::BorderInputs;
+use sic_image_engine::wrapper::channel::Channel;
+use sic_image_engine::wrapper::chroma_key::ChromaKeyInputs;
+use sic_image_engine::wrapper::contours::ContoursInputs;
+use sic_image_engine::wrapper::dimension::Dimension;
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::draw_corners::DrawCornersInputs;
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::draw_shape::{
+    DrawEllipseInputs, DrawLineInputs, DrawPolygonInputs, DrawRectInputs,
+};
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::draw_text_inner::DrawTextInner;
+use sic_image_engine::wrapper::extend::ExtendInputs;
+use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
+use sic_image_engine::wrapper::find::FindInputs;
+use sic_image_engine::wrapper::gradient::Gradient;
+use sic_image_engine::wrapper::gradient_fill::GradientFillInputs;
+use sic_image_engine::wrapper::gravity::Gravity;
+use sic_image_engine::wrapper::histogram::DrawHistogramInputs;
+use sic_image_engine::wrapper::image_path::ImageFromPath;
+use sic_image_engine::wrapper::kernel::{Kernel, KernelFromPath};
+use sic_image_engine::wrapper::lut::LutFromPath;
+use sic_image_engine::wrapper::mirror::MirrorMode;
+use sic_image_engine::wrapper::nine_slice::NineSliceInputs;
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::noise::NoiseInputs;
+use sic_image_engine::wrapper::overlay::OverlayInputs;
+use sic_image_engine::wrapper::pad_to_ratio::PadToRatioInputs;
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::perspective::PerspectiveInputs;
+use sic_image_engine::wrapper::pixel_upscale::PixelUpscaleInputs;
+use sic_image_engine::wrapper::redact::RedactInputs;
+use sic_image_engine::wrapper::region::Region;
+use sic_image_engine::wrapper::stamp::StampInputs;
+use sic_image_engine::wrapper::tonemap::ToneMapInputs;
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::LABEL_COMPONENTS_DEFAULT_CONNECTIVITY;
+use sic_image_engine::{ImgOp, SHARPEN_DEFAULT_AMOUNT, TRIM_DEFAULT_TOLERANCE};
+
+/// The arguments of an image operation or modifier, which are parsed as the inputs of its
+/// instruction.
+pub trait OperationArguments {
+    fn parse<T>(self) -> Result<T, SicParserError>
+    where
+        T: ParseInputsFromIter<Error = SicParserError>;
+}
+
+// for: the arguments given on the command line
+impl OperationArguments for &[&str] {
+    fn parse<T>(self) -> Result<T, SicParserError>
+    where
+        T: ParseInputsFromIter<Error = SicParserError>,
+    {
+        T::parse(self.iter().copied())
+    }
+}
+
+/// Constructs the instruction of an image operation, by its name, e.g. `hue-rotate`.
+pub fn parse_operation<A: OperationArguments>(
+    name: &str,
+    arguments: A,
+) -> Result<Instr, SicParserError> {
+    let operation = match name {
+        "alpha-add" => no_arguments(arguments, ImgOp::AlphaAdd)?,
+        "alpha-premultiply" => ImgOp::AlphaPremultiply,
+        "alpha-remove" => no_arguments(arguments, ImgOp::AlphaRemove)?,
+        "alpha-threshold" => ImgOp::AlphaThreshold(arguments.parse::<u8>()?),
+        "auto-enhance" => no_arguments(arguments, ImgOp::AutoEnhance)?,
+        "blur" => ImgOp::Blur(arguments.parse::<f32>()?),
+        "border" => ImgOp::Border(arguments.parse::<BorderInputs>()?),
+        "box-blur" => ImgOp::BoxBlur(arguments.parse::<u32>()?),
+        "brighten" => ImgOp::Brighten(arguments.parse::<i32>()?),
+        "channel-extract" => ImgOp::ChannelExtract(arguments.parse::<Channel>()?),
+        "channel-swap" => ImgOp::ChannelSwap(arguments.parse::<(Channel, Channel)>()?),
+        "chroma-key" => ImgOp::ChromaKey(arguments.parse::<ChromaKeyInputs>()?),
+        "contours" => ImgOp::Contours(arguments.parse::<ContoursInputs>()?),
+        "contrast" => ImgOp::Contrast(arguments.parse::<f32>()?),
+        "convolve" => ImgOp::Convolve(arguments.parse::<Kernel>()?),
+        "convolve-file" => ImgOp::ConvolveFile(arguments.parse::<KernelFromPath>()?),
+        "crop" => return Ok(DeferredOp::Crop(arguments.parse::<[Dimension; 4]>()?).into_instr()),
+        "crop-center" => {
+            let (width, height) = arguments.parse::<(u32, u32)>()?;
+            ImgOp::CropGravity((Gravity::Center, width, height))
+        }
+        "crop-gravity" => ImgOp::CropGravity(arguments.parse::<(Gravity, u32, u32)>()?),
+        "diff" => ImgOp::Diff(arguments.parse::<ImageFromPath>()?),
+        "dither" => ImgOp::Dither(arguments.parse::<u32>()?),
+        "draw-histogram" => ImgOp::DrawHistogram(arguments.parse::<DrawHistogramInputs>()?),
+        "duotone" => ImgOp::Duotone(arguments.parse::<(Rgba<u8>, Rgba<u8>)>()?),
+        "equalize" => no_arguments(arguments, ImgOp::Equalize)?,
+        "extend" => ImgOp::Extend(arguments.parse::<ExtendInputs>()?),
+        "filter3x3" => ImgOp::Filter3x3(arguments.parse::<[f32; 9]>()?),
+        "find" => ImgOp::Find(arguments.parse::<FindInputs>()?),
+        "fisheye" => ImgOp::Fisheye(arguments.parse::<f32>()?),
+        "flatten-checker" => ImgOp::FlattenChecker(arguments.parse::<u32>()?),
+        "flip-horizontal" => no_arguments(arguments, ImgOp::FlipHorizontal)?,
+        "flip-vertical" => ImgOp::FlipVertical,
+        "gradient" => ImgOp::Gradient(arguments.parse::<GradientFillInputs>()?),
+        "gradient-map" => ImgOp::GradientMap(arguments.parse::<Gradient>()?),
+        "grayscale" => no_arguments(arguments, ImgOp::GrayScale)?,
+        "grayscale-weighted" => ImgOp::GrayscaleWeighted(arguments.parse::<(f32, f32, f32)>()?),
+        "hstack" => ImgOp::Hstack(arguments.parse::<ImageFromPath>()?),
+        "hue-rotate" => ImgOp::HueRotate(arguments.parse::<i32>()?),
+        "invert" => no_arguments(arguments, ImgOp::Invert)?,
+        "local-mean" => ImgOp::LocalMean(arguments.parse::<u32>()?),
+        "local-variance" => ImgOp::LocalVariance(arguments.parse::<u32>()?),
+        "lut" => ImgOp::Lut(arguments.parse::<LutFromPath>()?),
+        "mirror" => ImgOp::Mirror(arguments.parse::<MirrorMode>()?),
+        "nine-slice" => ImgOp::NineSlice(arguments.parse::<NineSliceInputs>()?),
+        "oil-paint" => ImgOp::OilPaint(arguments.parse::<(u32, u32)>()?),
+        "opacity" => ImgOp::Opacity(arguments.parse::<f32>()?),
+        "overlay" => ImgOp::Overlay(arguments.parse::<OverlayInputs>()?),
+        "pad-to-ratio" => ImgOp::PadToRatio(arguments.parse::<PadToRatioInputs>()?),
+        "pixel-upscale" => ImgOp::PixelUpscale(arguments.parse::<PixelUpscaleInputs>()?),
+        "radial-blur" => ImgOp::RadialBlur(arguments.parse::<(u32, u32, f32)>()?),
+        "redact" => ImgOp::Redact(arguments.parse::<RedactInputs>()?),
+        "resize" => {
+            return Ok(DeferredOp::Resize(arguments.parse::<[Dimension; 2]>()?).into_instr())
+        }
+        "rotate90" => no_arguments(arguments, ImgOp::Rotate90)?,
+        "rotate180" => ImgOp::Rotate180,
+        "rotate270" => no_arguments(arguments, ImgOp::Rotate270)?,
+        "sharpen" => ImgOp::Sharpen(
+            arguments
+                .parse::<Option<f32>>()?
+                .unwrap_or(SHARPEN_DEFAULT_AMOUNT),
+        ),
+        "smart-crop" => ImgOp::SmartCrop(arguments.parse::<(u32, u32)>()?),
+        "stamp" => ImgOp::Stamp(arguments.parse::<StampInputs>()?),
+        "tonemap" => ImgOp::Tonemap(arguments.parse::<ToneMapInputs>()?),
+        "trim" => ImgOp::Trim(
+            arguments
+                .parse::<Option<f32>>()?
+                .unwrap_or(TRIM_DEFAULT_TOLERANCE),
+        ),
+        "unsharpen" => ImgOp::Unsharpen(arguments.parse::<(f32, i32)>()?),
+        "vstack" => ImgOp::Vstack(arguments.parse::<ImageFromPath>()?),
+
+        #[cfg(feature = "imageproc-ops")]
+        "adaptive-threshold" => ImgOp::AdaptiveThreshold(arguments.parse::<u32>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "clahe" => ImgOp::Clahe(arguments.parse::<(u32, f32)>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "dilate" => ImgOp::Dilate(arguments.parse::<u32>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "distance-transform" => no_arguments(arguments, ImgOp::DistanceTransform)?,
+        #[cfg(feature = "imageproc-ops")]
+        "draw-arrow" => ImgOp::DrawArrow(arguments.parse::<DrawLineInputs>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "draw-corners" => ImgOp::DrawCorners(arguments.parse::<DrawCornersInputs>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "draw-ellipse" => ImgOp::DrawEllipse(arguments.parse::<DrawEllipseInputs>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "draw-line" => ImgOp::DrawLine(arguments.parse::<DrawLineInputs>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "draw-polygon" => ImgOp::DrawPolygon(arguments.parse::<DrawPolygonInputs>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "draw-rect" => ImgOp::DrawRect(arguments.parse::<DrawRectInputs>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "draw-text" => ImgOp::DrawText(arguments.parse::<DrawTextInner>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "erode" => ImgOp::Erode(arguments.parse::<u32>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "label-components" => ImgOp::LabelComponents(
+            arguments
+                .parse::<Option<u32>>()?
+                .unwrap_or(LABEL_COMPONENTS_DEFAULT_CONNECTIVITY),
+        ),
+        #[cfg(feature = "imageproc-ops")]
+        "median" => ImgOp::Median(arguments.parse::<u32>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "morph-close" => ImgOp::MorphClose(arguments.parse::<u32>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "morph-open" => ImgOp::MorphOpen(arguments.parse::<u32>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "noise" => ImgOp::Noise(arguments.parse::<NoiseInputs>()?),
+        #[cfg(feature = "imageproc-ops")]
+        "perspective" => ImgOp::Perspective(arguments.parse::<PerspectiveInputs>()?),
+
+        _ => return Err(SicParserError::UnknownOperationError),
+    };
+
+    Ok(Instr::Operation(operation))
+}
+
+/// An image operation without arguments, e.g. `invert`, which fails if any are given.
+fn no_arguments<A: OperationArguments>(
+    arguments: A,
+    operation: ImgOp,
+) -> Result<ImgOp, SicParserError> {
+    arguments.parse::<()>().map(|()| operation)
+}
+
+/// Constructs the item which an image modifier sets, by its name, e.g. `sampling-filter`.
+pub fn parse_modifier<A: OperationArguments>(
+    name: &str,
+    arguments: A,
+) -> Result<EnvItem, SicParserError> {
+    let item = match name {
+        "adaptive-threshold-method" => {
+            EnvItem::AdaptiveThresholdMethod(arguments.parse::<AdaptiveThresholdMethod>()?)
+        }
+        "background" => EnvItem::BackgroundColor(arguments.parse::<Rgba<u8>>()?),
+        "blur-algorithm" => EnvItem::BlurAlgorithm(arguments.parse::<BlurAlgorithm>()?),
+        "gravity" => EnvItem::Gravity(arguments.parse::<Gravity>()?),
+        "mask" => EnvItem::Mask(arguments.parse::<ImageFromPath>()?),
+        "preserve-aspect-ratio" => EnvItem::PreserveAspectRatio(arguments.parse::<bool>()?),
+        "region" => EnvItem::Region(arguments.parse::<Region>()?),
+        "sampling-filter" => EnvItem::CustomSamplingFilter(arguments.parse::<FilterTypeWrap>()?),
+        name => {
+            return Err(SicParserError::OperationError(
+                OperationParamError::SetEnvironmentElement(name.to_string()),
+            ))
+        }
+    };
+
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operation_from_cli_arguments() {
+        let arguments: &[&str] = &["10", "20"];
+
+        assert_eq!(
+            parse_operation("smart-crop", arguments).unwrap(),
+            Instr::Operation(ImgOp::SmartCrop((10, 20)))
+        );
+    }
+
+    #[test]
+    fn operation_with_default_argument() {
+        let arguments: &[&str] = &[];
+
+        assert_eq!(
+            parse_operation("sharpen", arguments).unwrap(),
+            Instr::Operation(ImgOp::Sharpen(SHARPEN_DEFAULT_AMOUNT))
+        );
+    }
+
+    #[test]
+    fn operation_without_arguments() {
+        let arguments: &[&str] = &["1"];
+
+        assert!(parse_operation("invert", arguments).is_err());
+    }
+
+    #[test]
+    fn modifier_from_cli_arguments() {
+        let arguments: &[&str] = &["bottom-right"];
+
+        assert_eq!(
+            parse_modifier("gravity", arguments).unwrap(),
+            EnvItem::Gravity(Gravity::BottomRight)
+        );
+    }
+
+    #[test]
+    fn unknown_names() {
+        let arguments: &[&str] = &[];
+
+        assert!(matches!(
+            parse_operation("gravity", arguments),
+            Err(SicParserError::UnknownOperationError)
+        ));
+        assert!(matches!(
+            parse_modifier("blur", arguments),
+            Err(SicParserError::OperationError(
+                OperationParamError::SetEnvironmentElement(_)
+            ))
+        ));
+    }
+}
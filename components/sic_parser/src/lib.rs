@@ -1,35 +1,106 @@
 #![deny(clippy::all)]
 
-#[macro_use]
-extern crate pest_derive;
-
 #[cfg(test)]
 #[macro_use]
 extern crate parameterized;
 
-use pest::Parser;
+use std::path::Path;
 
+use crate::bindings::substitute_bindings;
+use crate::environment::substitute_environment_variables;
 use crate::errors::SicParserError;
+use crate::include::{read_script_file, resolve_includes};
+use crate::macros::{expand_macros, Macros};
 use crate::rule_parser::parse_image_operations;
+use crate::source_map::Script;
+use crate::syntax::parse_statements;
 use sic_image_engine::engine::Instr;
 
+pub mod bindings;
+pub mod diagnostic;
+pub mod environment;
 pub mod errors;
+pub mod formatter;
+pub mod include;
+pub mod instruction;
+pub mod macros;
+pub mod migrate;
 pub mod named_value;
+pub mod quoting;
 pub mod rule_parser;
+pub mod source_map;
+pub mod syntax;
 pub mod value_parser;
 
-const PARSER_RULE: Rule = Rule::main;
+/// Parses a script; the script files it includes are relative to the current directory.
+pub fn parse_script(script: &str) -> Result<Vec<Instr>, SicParserError> {
+    parse_script_with_macros(script, &Macros::default())
+}
 
-#[derive(Parser)]
-#[grammar = "grammar.pest"]
-pub struct SICParser;
+/// Parses a script, which may call the given macros besides those it defines itself.
+pub fn parse_script_with_macros(
+    script: &str,
+    macros: &Macros,
+) -> Result<Vec<Instr>, SicParserError> {
+    let script = resolve_includes(&Script::from(script), Path::new(""))?;
 
-pub fn parse_script(script: &str) -> Result<Vec<Instr>, SicParserError> {
-    let parsed_script = SICParser::parse(PARSER_RULE, script);
+    parse_expanded_script(&expand_macros(&script, macros)?)
+}
+
+/// Reads and parses a script file, which may call the given macros besides those it defines
+/// itself; the script files it includes are relative to its directory.
+pub fn parse_script_file(path: &Path, macros: &Macros) -> Result<Vec<Instr>, SicParserError> {
+    let script = read_script_file(path)?;
+
+    expand_macros(&script, macros)
+        .and_then(|script| parse_expanded_script(&script))
+        .map_err(|err| {
+            SicParserError::ScriptFileParsingError(path.display().to_string(), Box::new(err))
+        })
+}
+
+/// Parses a script like `parse_script_with_macros`, after substituting the values of environment
+/// variables for the references to them, e.g. `${WIDTH}`, including those of the included scripts.
+pub fn parse_script_with_environment_variables(
+    script: &str,
+    macros: &Macros,
+) -> Result<Vec<Instr>, SicParserError> {
+    let script = resolve_includes(&Script::from(script), Path::new(""))?;
+    let script = substitute_environment_variables(&script)?;
+
+    parse_expanded_script(&expand_macros(&script, macros)?)
+}
+
+/// Reads and parses a script file like `parse_script_file`, after substituting the values of
+/// environment variables for the references to them, e.g. `${WIDTH}`, including those of the
+/// included scripts.
+pub fn parse_script_file_with_environment_variables(
+    path: &Path,
+    macros: &Macros,
+) -> Result<Vec<Instr>, SicParserError> {
+    let script = read_script_file(path)?;
+
+    substitute_environment_variables(&script)
+        .and_then(|script| expand_macros(&script, macros))
+        .and_then(|script| parse_expanded_script(&script))
+        .map_err(|err| {
+            SicParserError::ScriptFileParsingError(path.display().to_string(), Box::new(err))
+        })
+}
+
+/// Parses a single macro call, e.g. `thumb(256)`, to the image operations of the called macro.
+pub fn parse_macro_call(call: &str, macros: &Macros) -> Result<Vec<Instr>, SicParserError> {
+    parse_expanded_script(&macros.expand_call(call)?)
+}
+
+/// Parses a script of which the includes and macros have been expanded; errors point at the
+/// script as written.
+pub(crate) fn parse_expanded_script(script: &Script) -> Result<Vec<Instr>, SicParserError> {
+    let script = substitute_bindings(script)?;
 
-    parsed_script
-        .map_err(|err| SicParserError::PestGrammarError(err.to_string()))
+    parse_statements(script.as_str())
         .and_then(parse_image_operations)
+        .map_err(|err| err.located(&script))
 }
 
 #[cfg(test)]
@@ -38,14 +109,13 @@ mod tests {
     use sic_image_engine::ImgOp;
 
     use super::*;
+    use crate::diagnostic::Diagnostic;
 
     #[test]
     fn test_too_many_args() {
         let input = "blur 15 28;";
-        let parsed = SICParser::parse(Rule::main, input);
+        let parsed = parse_script(input);
 
-        // Manually constructing a pest::Error::ParsingError is a hell, because of Position, which,
-        // except for the from_start() method is private.
         assert!(parsed.is_err());
     }
 
@@ -65,6 +135,233 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parsed_with_bindings() {
+        let input = "let size = 640;\nresize $size $size";
+        let parsed = parse_script(input);
+
+        assert_eq!(
+            parsed.unwrap(),
+            vec![Instr::Operation(ImgOp::Resize((640, 640)))]
+        );
+    }
+
+    #[test]
+    fn test_parsed_file_with_includes() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/include_test/lib");
+        std::fs::create_dir_all(directory.join("library")).unwrap();
+        std::fs::write(directory.join("library/sizes.sic"), "let size = 640;").unwrap();
+        std::fs::write(
+            directory.join("script.sic"),
+            "include 'library/sizes.sic';\nresize $size $size",
+        )
+        .unwrap();
+
+        assert_eq!(
+            parse_script_file(&directory.join("script.sic"), &Macros::default()).unwrap(),
+            vec![Instr::Operation(ImgOp::Resize((640, 640)))]
+        );
+    }
+
+    #[test]
+    fn test_parsed_file_with_environment_variables() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/include_test/env");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("sizes.sic"), "let size = ${SIC_TEST_SIZE};").unwrap();
+        std::fs::write(
+            directory.join("script.sic"),
+            "include 'sizes.sic';\nresize $size ${SIC_TEST_HEIGHT}",
+        )
+        .unwrap();
+        std::env::set_var("SIC_TEST_SIZE", "640");
+        std::env::set_var("SIC_TEST_HEIGHT", "480");
+
+        let path = directory.join("script.sic");
+        assert_eq!(
+            parse_script_file_with_environment_variables(&path, &Macros::default()).unwrap(),
+            vec![Instr::Operation(ImgOp::Resize((640, 480)))]
+        );
+
+        // without substituting environment variables, the references can't be parsed
+        assert!(parse_script_file(&path, &Macros::default()).is_err());
+    }
+
+    #[test]
+    fn test_parsed_file_error_names_file() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/include_test/lib");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("invalid.sic"), "blur 1;\nblur;").unwrap();
+
+        let err = parse_script_file(&directory.join("invalid.sic"), &Macros::default());
+
+        assert!(
+            matches!(err, Err(SicParserError::ScriptFileParsingError(path, _))
+            if path.ends_with("invalid.sic"))
+        );
+    }
+
+    #[test]
+    fn test_parse_error_points_at_argument() {
+        let err =
+            parse_script("blur 1;\n  border 1 rgba(0, 300, 0, 255);\nflip-vertical").unwrap_err();
+
+        assert!(
+            matches!(err, SicParserError::InvalidOperation(diagnostic, _)
+            if diagnostic.operation() == "border"
+                && diagnostic.argument() == Some("300")
+                && (diagnostic.line(), diagnostic.column()) == (2, 20))
+        );
+    }
+
+    fn diagnostic(err: SicParserError) -> Diagnostic {
+        match err {
+            SicParserError::InvalidOperation(diagnostic, _) => *diagnostic,
+            err => panic!("expected an invalid operation, but got: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_points_at_script_as_written() {
+        let err = parse_script("let size = 4;\nresize $size abc").unwrap_err();
+        let diagnostic = diagnostic(err);
+
+        assert_eq!(diagnostic.argument(), Some("abc"));
+        assert_eq!((diagnostic.line(), diagnostic.column()), (2, 14));
+        assert_eq!(
+            diagnostic.snippet(),
+            "  |\n2 | resize $size abc\n  |              ^^^"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_points_at_reference() {
+        let err = parse_script("let size = abc;\nresize $size 1").unwrap_err();
+        let diagnostic = diagnostic(err);
+
+        assert_eq!(diagnostic.argument(), Some("$size"));
+        assert_eq!((diagnostic.line(), diagnostic.column()), (2, 8));
+        assert_eq!(
+            diagnostic.snippet(),
+            "  |\n2 | resize $size 1\n  |        ^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_points_at_macro_definition() {
+        let err = parse_script("def t(w) { resize $w $w; blur x }\nt(3)").unwrap_err();
+        let diagnostic = diagnostic(err);
+
+        assert_eq!(diagnostic.operation(), "blur");
+        assert_eq!(diagnostic.argument(), Some("x"));
+        assert_eq!((diagnostic.line(), diagnostic.column()), (1, 31));
+        assert_eq!(
+            diagnostic.snippet(),
+            "  |\n1 | def t(w) { resize $w $w; blur x }\n  |                               ^"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_points_at_script_with_includes() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/include_test/lib");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("three.sic"), "blur 1;\nblur 2;\nblur 3;").unwrap();
+        std::fs::write(
+            directory.join("includes_three.sic"),
+            "include 'three.sic';\nresize 10 abc",
+        )
+        .unwrap();
+
+        let err = parse_script_file(&directory.join("includes_three.sic"), &Macros::default());
+
+        match err {
+            Err(SicParserError::ScriptFileParsingError(_, err)) => {
+                let diagnostic = diagnostic(*err);
+                assert_eq!((diagnostic.line(), diagnostic.column()), (2, 11));
+            }
+            _ => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_names_included_file() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/include_test/lib");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("inc2.sic"), "invert;\nblur x;").unwrap();
+        std::fs::write(
+            directory.join("main2.sic"),
+            "blur 1;\ninclude 'inc2.sic';\nflip-vertical",
+        )
+        .unwrap();
+
+        let err = parse_script_file(&directory.join("main2.sic"), &Macros::default());
+
+        match err {
+            Err(SicParserError::ScriptFileParsingError(_, err)) => {
+                let diagnostic = diagnostic(*err);
+                let file = directory.join("inc2.sic").display().to_string();
+
+                assert_eq!(diagnostic.file(), Some(file.as_str()));
+                assert_eq!(
+                    diagnostic.to_string(),
+                    format!(
+                        "argument 'x' of operation 'blur' at line 2, column 6 of '{}'",
+                        file
+                    )
+                );
+                assert_eq!(diagnostic.snippet(), "  |\n2 | blur x;\n  |      ^");
+            }
+            _ => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_names_macro_file() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/include_test/lib");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("macros.sic"), "def t() {\n    blur x\n}").unwrap();
+
+        let macros = Macros::from_script_file(&directory.join("macros.sic")).unwrap();
+        let diagnostic = diagnostic(parse_macro_call("t()", &macros).unwrap_err());
+
+        assert!(diagnostic.file().unwrap().ends_with("macros.sic"));
+        assert_eq!((diagnostic.line(), diagnostic.column()), (2, 10));
+    }
+
+    #[test]
+    fn test_syntax_error_points_at_script_as_written() {
+        let err = parse_script("let size = 4;\nresize $size 'abc").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "unable to parse script: expected the string to be closed at line 2, column 14"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_missing_argument() {
+        let err = parse_script("blur;").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "unable to parse operation 'blur' at line 1, column 1: missing argument; expected a \
+             value of type 'f32'\n  |\n1 | blur;\n  | ^^^^"
+        );
+    }
+
+    #[test]
+    fn test_parsed_with_macros() {
+        let input = "def thumb(w) { resize $w $w; blur 1 }\nlet size = 640;\nthumb($size)";
+        let parsed = parse_script(input);
+
+        assert_eq!(
+            parsed.unwrap(),
+            vec![
+                Instr::Operation(ImgOp::Resize((640, 640))),
+                Instr::Operation(ImgOp::Blur(1.0))
+            ]
+        );
+    }
+
     #[test]
     fn test_parsed_fail() {
         let input = "blur 15.7.; flipv";
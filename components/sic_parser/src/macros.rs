@@ -0,0 +1,394 @@
+//! The macros module expands calls of macros, e.g. `thumb(256)`, before a script is parsed. Macros
+//! are defined by `def` statements, e.g. `def thumb(w) { resize $w $w; sharpen 0.3 }`.
+//!
+//! A call is replaced by the statements of the body of the macro, in which the arguments are
+//! substituted for the references to the parameters, like bindings. Arguments are separated by
+//! commas; commas within strings and named values, e.g. `rgba(0, 0, 0, 255)`, don't separate them.
+//! Names are case insensitive. A macro may call other macros, but not (indirectly) itself.
+//! Macros can also be defined in a separate script file, which only contains definitions, so they
+//! can be called from the cli.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::bindings::{is_built_in, replace_statement, statements, substitute_references};
+use crate::errors::SicParserError;
+use crate::include::read_included_script_file;
+use crate::quoting::Quotes;
+use crate::source_map::Script;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Macro {
+    /// Lowercased parameter names.
+    parameters: Vec<String>,
+    body: Script,
+}
+
+/// A set of macros, by their lowercased name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Macros {
+    macros: HashMap<String, Macro>,
+}
+
+impl Macros {
+    /// Reads the macros defined by a script file, which may only contain macro definitions and
+    /// includes of other such files.
+    pub fn from_script_file(path: &Path) -> Result<Macros, SicParserError> {
+        Macros::from_definitions(&read_included_script_file(path)?)
+    }
+
+    /// The macros defined by a script, which may only contain macro definitions.
+    pub fn from_script(script: &str) -> Result<Macros, SicParserError> {
+        Macros::from_definitions(&Script::from(script))
+    }
+
+    fn from_definitions(script: &Script) -> Result<Macros, SicParserError> {
+        let mut macros = Macros::default();
+
+        for statement in statements(script.as_str()) {
+            match parse_definition(script, statement) {
+                Some(definition) => macros.define(definition?),
+                None if statement.trim().trim_end_matches(';').is_empty() => {}
+                None => {
+                    return Err(SicParserError::MacroFileStatement(
+                        statement.trim().to_string(),
+                    ))
+                }
+            }
+        }
+
+        Ok(macros)
+    }
+
+    /// Expands a call, e.g. `thumb(256)`, into the statements of the called macro.
+    pub fn expand_call(&self, call: &str) -> Result<Script, SicParserError> {
+        match parse_call(call) {
+            Some(call) => {
+                let (name, arguments) = call?;
+                self.expand(name, &arguments, &mut Vec::new())
+            }
+            None => Err(SicParserError::InvalidMacroCall(call.trim().to_string())),
+        }
+    }
+
+    fn define(&mut self, (name, definition): (String, Macro)) {
+        self.macros.insert(name, definition);
+    }
+
+    /// `stack` holds the macros which are being expanded, outermost first.
+    fn expand(
+        &self,
+        name: &str,
+        arguments: &[&str],
+        stack: &mut Vec<String>,
+    ) -> Result<Script, SicParserError> {
+        let key = name.to_ascii_lowercase();
+        let definition = self
+            .macros
+            .get(&key)
+            .ok_or_else(|| SicParserError::UndefinedMacro(name.to_string()))?;
+
+        if stack.contains(&key) {
+            return Err(SicParserError::RecursiveMacro(name.to_string()));
+        }
+
+        if arguments.len() != definition.parameters.len() {
+            return Err(SicParserError::MacroArgumentCount(
+                name.to_string(),
+                definition.parameters.len(),
+                arguments.len(),
+            ));
+        }
+
+        let body = &definition.body;
+        let body = substitute_references(body, body.as_str(), |reference| {
+            let reference = reference.to_ascii_lowercase();
+
+            Ok(definition
+                .parameters
+                .iter()
+                .position(|parameter| *parameter == reference)
+                .map(|index| arguments[index]))
+        })?;
+
+        let mut expanded = Script::default();
+
+        stack.push(key);
+        for statement in statements(body.as_str()) {
+            if parse_definition(&body, statement).is_some() {
+                return Err(SicParserError::NestedMacro(name.to_string()));
+            }
+
+            self.expand_statement(&mut expanded, &body, statement, stack)?;
+        }
+        stack.pop();
+
+        Ok(expanded)
+    }
+
+    /// Expands a statement of the given script, if it's a call.
+    fn expand_statement(
+        &self,
+        expanded: &mut Script,
+        script: &Script,
+        statement: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<(), SicParserError> {
+        match parse_call(statement) {
+            Some(call) => {
+                let (name, arguments) = call?;
+                let statements = self.expand(name, &arguments, stack)?;
+
+                replace_statement(expanded, script, statement, &statements);
+            }
+            None => expanded.push(script, statement),
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes the macro definitions from a script, and expands the calls of the given macros, and of
+/// those defined by the script.
+pub fn expand_macros(script: &Script, macros: &Macros) -> Result<Script, SicParserError> {
+    let mut macros = macros.clone();
+    let mut expanded = Script::default();
+
+    for statement in statements(script.as_str()) {
+        match parse_definition(script, statement) {
+            Some(definition) => macros.define(definition?),
+            None => macros.expand_statement(&mut expanded, script, statement, &mut Vec::new())?,
+        }
+    }
+
+    // a script may start with a definition, after which the line breaks which followed it remain
+    Ok(expanded.trim_start())
+}
+
+/// The lowercased name and the definition of a `def` statement of the given script, e.g.
+/// `def thumb(w) { resize $w $w }`, or `None` for other statements.
+fn parse_definition(
+    script: &Script,
+    statement: &str,
+) -> Option<Result<(String, Macro), SicParserError>> {
+    let definition = parse_definition_parts(statement)?.and_then(|(name, parameters, body)| {
+        let mut names = Vec::with_capacity(parameters.len());
+
+        for parameter in parameters {
+            let name = parameter.to_ascii_lowercase();
+
+            if is_built_in(parameter) {
+                return Err(SicParserError::ReservedBinding(parameter.to_string()));
+            } else if !is_parameter_name(parameter) || names.contains(&name) {
+                return Err(SicParserError::InvalidMacro(statement.trim().to_string()));
+            }
+
+            names.push(name);
+        }
+
+        let mut definition = Script::default();
+        definition.push(script, body.trim());
+
+        Ok((
+            name.to_ascii_lowercase(),
+            Macro {
+                parameters: names,
+                body: definition,
+            },
+        ))
+    });
+
+    Some(definition)
+}
+
+/// The name, the parameters and the body of a macro definition.
+pub(crate) type DefinitionParts<'s> = (&'s str, Vec<&'s str>, &'s str);
+
+/// The parts of a `def` statement, without validating the names of the parameters, or `None` for
+/// other statements.
+pub(crate) fn parse_definition_parts(
+    statement: &str,
+) -> Option<Result<DefinitionParts<'_>, SicParserError>> {
+    let statement = statement.trim();
+    let statement = statement.strip_suffix(';').unwrap_or(statement).trim_end();
+
+    match statement.get(..4) {
+        Some(keyword) if keyword.eq_ignore_ascii_case("def ") => {}
+        _ => return None,
+    }
+
+    let open = statement.find('{');
+    let body = open.and_then(|open| statement[open + 1..].strip_suffix('}'));
+    let signature = &statement[4..open.unwrap_or(statement.len())];
+
+    match (parse_call(signature), body) {
+        (Some(Ok((name, parameters))), Some(body)) => Some(Ok((name, parameters, body))),
+        _ => Some(Err(SicParserError::InvalidMacro(statement.to_string()))),
+    }
+}
+
+/// The name and the arguments of a macro call, e.g. `thumb(256);`, or `None` for statements which
+/// aren't calls. The `(` directly follows the name, unlike the arguments of an operation, e.g.
+/// `resize ($width/2) 100`.
+pub(crate) fn parse_call(statement: &str) -> Option<Result<(&str, Vec<&str>), SicParserError>> {
+    let statement = statement.trim();
+    let statement = statement.strip_suffix(';').unwrap_or(statement).trim_end();
+
+    let open = statement.find('(')?;
+    let name = &statement[..open];
+
+    if !is_macro_name(name) {
+        return None;
+    }
+
+    let arguments = statement[open + 1..]
+        .strip_suffix(')')
+        .and_then(split_arguments)
+        .filter(|arguments| arguments.iter().all(|argument| !argument.is_empty()));
+
+    Some(
+        arguments
+            .map(|arguments| (name, arguments))
+            .ok_or_else(|| SicParserError::InvalidMacroCall(statement.to_string())),
+    )
+}
+
+/// Splits the arguments of a call at the commas which aren't within strings or parentheses, or
+/// `None` if the parentheses aren't balanced.
+fn split_arguments(arguments: &str) -> Option<Vec<&str>> {
+    if arguments.trim().is_empty() {
+        return Some(Vec::new());
+    }
+
+    let mut split = Vec::new();
+    let mut start = 0;
+    let mut quotes = Quotes::default();
+    let mut depth = 0usize;
+
+    for (index, c) in arguments.char_indices() {
+        if quotes.within(c) {
+            continue;
+        }
+
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                split.push(arguments[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return None;
+    }
+
+    split.push(arguments[start..].trim());
+    Some(split)
+}
+
+fn is_macro_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn is_parameter_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[parameterized(
+        input = {
+            "def thumb(w) { resize $w $w; sharpen 0.3 }\nthumb(256)",
+            "def thumb(w) { resize $w $w; sharpen 0.3 };\nthumb(256);\ninvert",
+            "DEF Frame(size, color) { border $SIZE $color }; frame(2, rgba(0, 0, 0, 255))",
+            "def clean() { blur 1 }\nclean()",
+            "def a(x) { blur $x }\ndef b(x) { a($x); a(2) }\nb(1)",
+            "def stamp_logo(path) { stamp $path bottom-right 8 }\nstamp_logo('a, b.png')",
+            "def scale(w) { resize $w $height }\nscale($width/2)",
+            "def unused(w) { blur $w }",
+            "def thumb(w) { resize $w $w }\nresize $size 1",
+            "resize ($width/2) min($height, 2)",
+            "invert",
+        },
+        expected = {
+            "resize 256 256; sharpen 0.3",
+            "resize 256 256; sharpen 0.3;\ninvert",
+            "border 2 rgba(0, 0, 0, 255)",
+            "blur 1",
+            "blur 1; blur 2",
+            "stamp 'a, b.png' bottom-right 8",
+            "resize $width/2 $height",
+            "",
+            "resize $size 1",
+            "resize ($width/2) min($height, 2)",
+            "invert",
+        }
+    )]
+    fn expand_macros_ok(input: &str, expected: &str) {
+        assert_eq!(
+            expand_macros(&Script::from(input), &Macros::default())
+                .unwrap()
+                .as_str(),
+            expected
+        );
+    }
+
+    #[parameterized(
+        input = {
+            "thumb(256)",
+            "thumb(256); def thumb(w) { resize $w $w }",
+            "def thumb(w) { resize $w $w }\nthumb(1, 2)",
+            "def thumb(w) { resize $w $w }\nthumb()",
+            "def thumb(w) { resize $w $w }\nthumb(1,)",
+            "def thumb(w) { resize $w $w }\nthumb(1",
+            "def thumb(w) { resize $w $w }\nthumb(1) (2)",
+            "def thumb(w, w) { resize $w $w }",
+            "def thumb(width) { resize $width 1 }",
+            "def thumb(my-w) { resize 1 1 }",
+            "def thumb(w) resize $w $w",
+            "def (w) { resize $w $w }",
+            "def a() { a() }\na()",
+            "def a() { b() }\ndef b() { a() }\na()",
+            "def a() { def b() { blur 1 } }\na()",
+        }
+    )]
+    fn expand_macros_err(input: &str) {
+        assert!(expand_macros(&Script::from(input), &Macros::default()).is_err());
+    }
+
+    #[test]
+    fn macros_from_script_file() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/macros_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("macros.sic"),
+            "def thumb(w) {\n    resize $w $w;\n    sharpen 0.3;\n}\n",
+        )
+        .unwrap();
+        std::fs::write(directory.join("invalid.sic"), "def a() { blur 1 }\ninvert").unwrap();
+
+        let macros = Macros::from_script_file(&directory.join("macros.sic")).unwrap();
+
+        assert_eq!(
+            macros.expand_call("thumb(64)").unwrap().as_str(),
+            "resize 64 64;\n    sharpen 0.3;"
+        );
+        assert_eq!(
+            expand_macros(&Script::from("thumb(8); invert"), &macros)
+                .unwrap()
+                .as_str(),
+            "resize 8 8;\n    sharpen 0.3; invert"
+        );
+        assert!(macros.expand_call("blur 1").is_err());
+        assert!(Macros::from_script_file(&directory.join("invalid.sic")).is_err());
+    }
+}
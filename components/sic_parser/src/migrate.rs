@@ -0,0 +1,150 @@
+//! The migrate module converts scripts written for the image script syntax of earlier versions
+//! to the current syntax.
+//!
+//! Earlier versions named operations and modifiers in snake case (e.g. `flip_horizontal`), had a
+//! few abbreviated operation names (e.g. `fliph`), and grouped the resize modifiers under
+//! `set resize` (e.g. `set resize sampling_filter lanczos3`). The arguments of the operations are
+//! unchanged, so only the names of the statements are rewritten.
+
+use std::path::Path;
+
+use crate::errors::SicParserError;
+use crate::formatter::format_script;
+use crate::quoting::Quotes;
+
+/// Operation names which were abbreviated, with their current name.
+const RENAMED_OPERATIONS: &[(&str, &str)] = &[
+    ("fliph", "flip-horizontal"),
+    ("flipv", "flip-vertical"),
+    ("huerotate", "hue-rotate"),
+];
+
+/// Converts a script from an earlier image script syntax to the current syntax, and formats it.
+/// Scripts which already use the current syntax are only formatted. The script files it includes
+/// are relative to the given directory.
+pub fn migrate_script(script: &str, directory: &Path) -> Result<String, SicParserError> {
+    let migrated = split_statements(script)
+        .into_iter()
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .map(migrate_statement)
+        .collect::<Vec<_>>()
+        .join(";\n");
+
+    format_script(&migrated, directory)
+}
+
+fn migrate_statement(statement: &str) -> String {
+    let (name, rest) = split_word(statement);
+    let name = name.to_lowercase();
+
+    match name.as_str() {
+        "set" | "del" => {
+            let (item, rest) = split_word(rest);
+
+            // the resize modifiers were grouped as `set resize <modifier> <value>`
+            let (item, rest) = if item.eq_ignore_ascii_case("resize") {
+                split_word(rest)
+            } else {
+                (item, rest)
+            };
+
+            join_words(&[&name, &kebab_case(item), rest])
+        }
+        _ => {
+            let name = RENAMED_OPERATIONS
+                .iter()
+                .find(|(legacy, _)| *legacy == name)
+                .map(|(_, current)| current.to_string())
+                .unwrap_or_else(|| kebab_case(&name));
+
+            join_words(&[&name, rest])
+        }
+    }
+}
+
+/// Splits a script on statement separators (`;`) and line breaks, which are not part of a string.
+fn split_statements(script: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut quotes = Quotes::default();
+
+    for (index, c) in script.char_indices() {
+        if !quotes.within(c) && (c == ';' || c == '\n') {
+            statements.push(&script[start..index]);
+            start = index + c.len_utf8();
+        }
+    }
+
+    statements.push(&script[start..]);
+    statements
+}
+
+/// Splits off the first word; the remainder keeps its own whitespace, since it may hold strings.
+fn split_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+
+    match text.find(char::is_whitespace) {
+        Some(index) => (&text[..index], text[index..].trim_start()),
+        None => (text, ""),
+    }
+}
+
+fn join_words(words: &[&str]) -> String {
+    words
+        .iter()
+        .filter(|word| !word.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn kebab_case(name: &str) -> String {
+    name.to_lowercase().replace('_', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[parameterized(
+        input = {
+            "flip_horizontal; resize 100 100",
+            "fliph;flipv;huerotate -90",
+            "set resize sampling_filter lanczos3;\nset resize preserve_aspect_ratio true;",
+            "del resize sampling_filter; del resize preserve_aspect_ratio",
+            "set adaptive_threshold_method gaussian",
+            "Rotate90; flip_vertical\ngrayscale",
+            "diff 'some_file;name.png'",
+            "blur 1.5; flip-horizontal; set sampling-filter nearest",
+            "",
+        },
+        expected = {
+            "flip-horizontal;\nresize 100 100;\n",
+            "flip-horizontal;\nflip-vertical;\nhue-rotate -90;\n",
+            "set sampling-filter lanczos3;\nset preserve-aspect-ratio true;\n",
+            "del sampling-filter;\ndel preserve-aspect-ratio;\n",
+            "set adaptive-threshold-method gaussian;\n",
+            "rotate90;\nflip-vertical;\ngrayscale;\n",
+            "diff 'some_file;name.png';\n",
+            "blur 1.5;\nflip-horizontal;\nset sampling-filter nearest;\n",
+            "",
+        }
+    )]
+    fn migrate(input: &str, expected: &str) {
+        assert_eq!(migrate_script(input, Path::new("")).unwrap(), expected);
+    }
+
+    #[parameterized(input = { "flip_diagonal", "set resize sampling_filter", "blur 1 2" })]
+    fn migrate_invalid(input: &str) {
+        assert!(migrate_script(input, Path::new("")).is_err());
+    }
+
+    #[test]
+    fn split_statements_respects_strings() {
+        assert_eq!(
+            split_statements(r#"diff "a;\"b"; invert"#),
+            vec![r#"diff "a;\"b""#, " invert"]
+        );
+    }
+}
@@ -2,10 +2,9 @@
 //! where N is the identifier of the named value, and T is a comma separated tuple of values,
 //! like so: `a,b,c`. Dangling commas are not supported. A full example look like this: `rgb(4, 255, 0)`.
 
-use super::Rule;
-use pest::iterators::Pair;
+use crate::quoting::{unquote, Quotes};
+use std::borrow::Cow;
 use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -44,27 +43,6 @@ pub enum NamedValueError {
 
 type NVResult<T> = Result<T, NamedValueError>;
 
-pub fn parse_named_value(pair: Pair<'_, Rule>) -> NVResult<NamedValue> {
-    let mut pairs = pair.into_inner();
-
-    // identifier
-    let ident = pairs
-        .next()
-        .ok_or_else(|| NamedValueError::IdentifierNotFound)?;
-
-    let ident = match ident.as_rule() {
-        Rule::ident => parse_ident(ident.as_str())?,
-        _ => return Err(NamedValueError::IdentifierNotFound),
-    };
-
-    // arguments
-    let arguments = pairs
-        .map(|pair| Value::try_from_pair(pair, ident))
-        .collect::<NVResult<Vec<_>>>()?;
-
-    NamedValue::try_from_annotated(AnnotatedArgs { ident, arguments })
-}
-
 impl FromStr for NamedValue {
     type Err = NamedValueError;
 
@@ -82,8 +60,8 @@ impl FromStr for NamedValue {
             .and_then(|right_side| right_side.rsplitn(2, ')').last())
             .ok_or_else(|| NamedValueError::UnableToCreateNamedValueWithArgs(ident))?;
 
-        let arguments = arguments
-            .split(',')
+        let arguments = split_arguments(arguments)
+            .into_iter()
             .map(|arg| Value::try_from_str(arg.trim(), ident))
             .collect::<NVResult<Vec<_>>>()?;
 
@@ -99,11 +77,35 @@ pub enum Ident {
     // size(<u32>)
     Size,
 
-    // font("<path>")
+    // font("<path or family>") or font("<family>", "<style>")
     Font,
 
     // coord(<u32>, <u32>)
     Coord,
+
+    // stop(<f32>, rgba(<u8>,<u8>,<u8>,<u8>))
+    Stop,
+
+    // outline(<f32>, rgba(<u8>,<u8>,<u8>,<u8>))
+    Outline,
+
+    // shadow(<i32>, <i32>, rgba(<u8>,<u8>,<u8>,<u8>))
+    Shadow,
+
+    // spacing(<f32>, <f32>)
+    Spacing,
+
+    // angle(<f32>)
+    Angle,
+
+    // rect(<u32>, <u32>, <u32>, <u32>)
+    Rect,
+
+    // wrap(<f32>)
+    Wrap,
+
+    // align("<left, center or right>")
+    Align,
 }
 
 impl Display for Ident {
@@ -113,6 +115,14 @@ impl Display for Ident {
             Self::Size => f.write_str("Size"),
             Self::Font => f.write_str("Font"),
             Self::Coord => f.write_str("Coord"),
+            Self::Stop => f.write_str("Stop"),
+            Self::Outline => f.write_str("Outline"),
+            Self::Shadow => f.write_str("Shadow"),
+            Self::Spacing => f.write_str("Spacing"),
+            Self::Angle => f.write_str("Angle"),
+            Self::Rect => f.write_str("Rect"),
+            Self::Wrap => f.write_str("Wrap"),
+            Self::Align => f.write_str("Align"),
         }
     }
 }
@@ -123,6 +133,14 @@ fn parse_ident(ident: &str) -> NVResult<Ident> {
         "size" => Ident::Size,
         "font" => Ident::Font,
         "coord" => Ident::Coord,
+        "stop" => Ident::Stop,
+        "outline" => Ident::Outline,
+        "shadow" => Ident::Shadow,
+        "spacing" => Ident::Spacing,
+        "angle" => Ident::Angle,
+        "rect" => Ident::Rect,
+        "wrap" => Ident::Wrap,
+        "align" => Ident::Align,
         _ => return Err(NamedValueError::IdentifierInvalid(ident.to_string())),
     };
 
@@ -133,29 +151,26 @@ fn parse_ident(ident: &str) -> NVResult<Ident> {
 enum Value<'a> {
     Byte(u8),
     Float(f32),
-    #[allow(unused)]
     Integer(i32),
     NatNum(u32),
-    String(&'a str),
+    String(Cow<'a, str>),
+    Named(NamedValue),
 }
 
 impl<'a> Value<'a> {
-    pub fn try_from_pair(pair: Pair<'a, Rule>, ident: Ident) -> NVResult<Self> {
-        match (pair.as_rule(), ident) {
-            (Rule::fp, Ident::Rgba) => Ok(Value::parse_byte(pair.as_str())?),
-            (Rule::fp, Ident::Size) => Ok(Value::parse_float(pair.as_str())?),
-            (Rule::fp, Ident::Coord) => Ok(Value::parse_nat_num(pair.as_str())?),
-            (Rule::string_unicode, _) => Ok(Value::parse_string(pair.into_inner().as_str())?),
-            _ => Err(NamedValueError::InvalidArgumentType),
-        }
-    }
-
     pub fn try_from_str(s: &'a str, ident: Ident) -> NVResult<Self> {
         match ident {
             Ident::Rgba => Ok(Value::parse_byte(s)?),
             Ident::Size => Ok(Value::parse_float(s)?),
-            Ident::Coord => Ok(Value::parse_nat_num(s)?),
-            Ident::Font => Ok(Value::parse_string(slice_str_tokens(s)?)?),
+            Ident::Coord | Ident::Rect => Ok(Value::parse_nat_num(s)?),
+            Ident::Font | Ident::Align => Ok(Value::parse_string(slice_str_tokens(s)?)?),
+            Ident::Stop | Ident::Outline | Ident::Shadow if s.contains('(') => {
+                Ok(Value::Named(s.parse()?))
+            }
+            Ident::Stop | Ident::Outline | Ident::Spacing | Ident::Angle | Ident::Wrap => {
+                Ok(Value::parse_float(s)?)
+            }
+            Ident::Shadow => Ok(Value::parse_integer(s)?),
         }
     }
 
@@ -181,7 +196,6 @@ impl<'a> Value<'a> {
         }
     }
 
-    #[allow(unused)]
     pub fn extract_integer(&self) -> NVResult<i32> {
         if let Self::Integer(inner) = self {
             Ok(*inner)
@@ -206,7 +220,7 @@ impl<'a> Value<'a> {
 
     pub fn extract_string(&self) -> NVResult<&str> {
         if let Self::String(inner) = self {
-            Ok(inner)
+            Ok(inner.as_ref())
         } else {
             Err(NamedValueError::UnableToExtractValue(
                 String::from("String"),
@@ -215,6 +229,17 @@ impl<'a> Value<'a> {
         }
     }
 
+    pub fn extract_named_value(&self) -> NVResult<&NamedValue> {
+        if let Self::Named(inner) = self {
+            Ok(inner)
+        } else {
+            Err(NamedValueError::UnableToExtractValue(
+                String::from("NamedValue"),
+                self.error_type(),
+            ))
+        }
+    }
+
     fn parse_byte(value: &str) -> NVResult<Self> {
         value
             .parse::<u8>()
@@ -228,13 +253,19 @@ impl<'a> Value<'a> {
         })
     }
 
+    fn parse_integer(value: &str) -> NVResult<Self> {
+        value.parse::<i32>().map(Value::Integer).map_err(|_err| {
+            NamedValueError::UnableToParse(value.to_string(), String::from("Integer"))
+        })
+    }
+
     fn parse_nat_num(value: &str) -> NVResult<Self> {
         value.parse::<u32>().map(Value::NatNum).map_err(|_err| {
             NamedValueError::UnableToParse(value.to_string(), String::from("NatNum"))
         })
     }
 
-    fn parse_string(value: &'a str) -> NVResult<Self> {
+    fn parse_string(value: Cow<'a, str>) -> NVResult<Self> {
         Ok(Value::String(value))
     }
 
@@ -245,6 +276,7 @@ impl<'a> Value<'a> {
             Self::Integer(_) => "Integer",
             Self::NatNum(_) => "NatNum",
             Self::String(_) => "String",
+            Self::Named(_) => "NamedValue",
         };
 
         typ.to_string()
@@ -272,8 +304,16 @@ impl<'a> AnnotatedArgs<'a> {
 pub enum NamedValue {
     Rgba(u8, u8, u8, u8),
     Size(f32),
-    Font(PathBuf),
+    Font(String, Option<String>),
     Coord((u32, u32)),
+    Stop(f32, [u8; 4]),
+    Outline(f32, [u8; 4]),
+    Shadow((i32, i32), [u8; 4]),
+    Spacing(f32, f32),
+    Angle(f32),
+    Rect((u32, u32, u32, u32)),
+    Wrap(f32),
+    Align(String),
 }
 
 impl NamedValue {
@@ -283,6 +323,14 @@ impl NamedValue {
             Ident::Size => NamedValue::create_size(args.arguments()),
             Ident::Font => NamedValue::create_font(args.arguments()),
             Ident::Coord => NamedValue::create_coord(args.arguments()),
+            Ident::Stop => NamedValue::create_stop(args.arguments()),
+            Ident::Outline => NamedValue::create_outline(args.arguments()),
+            Ident::Shadow => NamedValue::create_shadow(args.arguments()),
+            Ident::Spacing => NamedValue::create_spacing(args.arguments()),
+            Ident::Angle => NamedValue::create_angle(args.arguments()),
+            Ident::Rect => NamedValue::create_rect(args.arguments()),
+            Ident::Wrap => NamedValue::create_wrap(args.arguments()),
+            Ident::Align => NamedValue::create_align(args.arguments()),
         }
     }
 
@@ -308,9 +356,10 @@ impl NamedValue {
         }
     }
 
-    pub fn extract_font(&self) -> NVResult<PathBuf> {
-        if let Self::Font(font) = self {
-            Ok(font.to_path_buf())
+    /// Extracts the font path or family name, and the style, if one was given.
+    pub fn extract_font(&self) -> NVResult<(&str, Option<&str>)> {
+        if let Self::Font(font, style) = self {
+            Ok((font, style.as_deref()))
         } else {
             Err(NamedValueError::UnableToExtractValue(
                 String::from("Font"),
@@ -330,6 +379,101 @@ impl NamedValue {
         }
     }
 
+    pub fn extract_stop(&self) -> NVResult<(f32, [u8; 4])> {
+        if let Self::Stop(position, color) = self {
+            Ok((*position, *color))
+        } else {
+            Err(NamedValueError::UnableToExtractValue(
+                String::from("Stop"),
+                self.error_type(),
+            ))
+        }
+    }
+
+    /// Extracts the width and color of a text outline.
+    pub fn extract_outline(&self) -> NVResult<(f32, [u8; 4])> {
+        if let Self::Outline(width, color) = self {
+            Ok((*width, *color))
+        } else {
+            Err(NamedValueError::UnableToExtractValue(
+                String::from("Outline"),
+                self.error_type(),
+            ))
+        }
+    }
+
+    /// Extracts the offset and color of a text shadow.
+    pub fn extract_shadow(&self) -> NVResult<((i32, i32), [u8; 4])> {
+        if let Self::Shadow(offset, color) = self {
+            Ok((*offset, *color))
+        } else {
+            Err(NamedValueError::UnableToExtractValue(
+                String::from("Shadow"),
+                self.error_type(),
+            ))
+        }
+    }
+
+    /// Extracts the letter and line spacing.
+    pub fn extract_spacing(&self) -> NVResult<(f32, f32)> {
+        if let Self::Spacing(letter, line) = self {
+            Ok((*letter, *line))
+        } else {
+            Err(NamedValueError::UnableToExtractValue(
+                String::from("Spacing"),
+                self.error_type(),
+            ))
+        }
+    }
+
+    /// Extracts an angle in degrees.
+    pub fn extract_angle(&self) -> NVResult<f32> {
+        if let Self::Angle(degrees) = self {
+            Ok(*degrees)
+        } else {
+            Err(NamedValueError::UnableToExtractValue(
+                String::from("Angle"),
+                self.error_type(),
+            ))
+        }
+    }
+
+    /// Extracts a rectangle as its x and y coordinate, width and height.
+    pub fn extract_rect(&self) -> NVResult<(u32, u32, u32, u32)> {
+        if let Self::Rect(rect) = self {
+            Ok(*rect)
+        } else {
+            Err(NamedValueError::UnableToExtractValue(
+                String::from("Rect"),
+                self.error_type(),
+            ))
+        }
+    }
+
+    /// Extracts the width in pixels at which text is wrapped.
+    pub fn extract_wrap(&self) -> NVResult<f32> {
+        if let Self::Wrap(width) = self {
+            Ok(*width)
+        } else {
+            Err(NamedValueError::UnableToExtractValue(
+                String::from("Wrap"),
+                self.error_type(),
+            ))
+        }
+    }
+
+    /// Extracts the name of a text alignment.
+    pub fn extract_align(&self) -> NVResult<&str> {
+        if let Self::Align(align) = self {
+            Ok(align)
+        } else {
+            Err(NamedValueError::UnableToExtractValue(
+                String::from("Align"),
+                self.error_type(),
+            ))
+        }
+    }
+
     fn create_rgba(args: &[Value]) -> NVResult<Self> {
         match args {
             [r, g, b, a] => Ok(Self::Rgba(
@@ -355,7 +499,11 @@ impl NamedValue {
 
     fn create_font(args: &[Value]) -> NVResult<Self> {
         match args {
-            [font_file] => Ok(Self::Font(font_file.extract_string()?.into())),
+            [font] => Ok(Self::Font(font.extract_string()?.to_string(), None)),
+            [font, style] => Ok(Self::Font(
+                font.extract_string()?.to_string(),
+                Some(style.extract_string()?.to_string()),
+            )),
             _ => Err(NamedValueError::UnableToCreateNamedValueWithArgs(
                 Ident::Font,
             )),
@@ -371,22 +519,311 @@ impl NamedValue {
         }
     }
 
+    fn create_stop(args: &[Value]) -> NVResult<Self> {
+        match args {
+            [position, color] => Ok(Self::Stop(
+                position.extract_float()?,
+                color.extract_named_value()?.extract_rgba()?,
+            )),
+            _ => Err(NamedValueError::UnableToCreateNamedValueWithArgs(
+                Ident::Stop,
+            )),
+        }
+    }
+
+    fn create_outline(args: &[Value]) -> NVResult<Self> {
+        match args {
+            [width, color] => Ok(Self::Outline(
+                width.extract_float()?,
+                color.extract_named_value()?.extract_rgba()?,
+            )),
+            _ => Err(NamedValueError::UnableToCreateNamedValueWithArgs(
+                Ident::Outline,
+            )),
+        }
+    }
+
+    fn create_shadow(args: &[Value]) -> NVResult<Self> {
+        match args {
+            [x, y, color] => Ok(Self::Shadow(
+                (x.extract_integer()?, y.extract_integer()?),
+                color.extract_named_value()?.extract_rgba()?,
+            )),
+            _ => Err(NamedValueError::UnableToCreateNamedValueWithArgs(
+                Ident::Shadow,
+            )),
+        }
+    }
+
+    fn create_spacing(args: &[Value]) -> NVResult<Self> {
+        match args {
+            [letter, line] => Ok(Self::Spacing(
+                letter.extract_float()?,
+                line.extract_float()?,
+            )),
+            _ => Err(NamedValueError::UnableToCreateNamedValueWithArgs(
+                Ident::Spacing,
+            )),
+        }
+    }
+
+    fn create_angle(args: &[Value]) -> NVResult<Self> {
+        match args {
+            [degrees] => Ok(Self::Angle(degrees.extract_float()?)),
+            _ => Err(NamedValueError::UnableToCreateNamedValueWithArgs(
+                Ident::Angle,
+            )),
+        }
+    }
+
+    fn create_rect(args: &[Value]) -> NVResult<Self> {
+        match args {
+            [x, y, width, height] => Ok(Self::Rect((
+                x.extract_nat_num()?,
+                y.extract_nat_num()?,
+                width.extract_nat_num()?,
+                height.extract_nat_num()?,
+            ))),
+            _ => Err(NamedValueError::UnableToCreateNamedValueWithArgs(
+                Ident::Rect,
+            )),
+        }
+    }
+
+    fn create_wrap(args: &[Value]) -> NVResult<Self> {
+        match args {
+            [width] => Ok(Self::Wrap(width.extract_float()?)),
+            _ => Err(NamedValueError::UnableToCreateNamedValueWithArgs(
+                Ident::Wrap,
+            )),
+        }
+    }
+
+    fn create_align(args: &[Value]) -> NVResult<Self> {
+        match args {
+            [align] => Ok(Self::Align(align.extract_string()?.to_string())),
+            _ => Err(NamedValueError::UnableToCreateNamedValueWithArgs(
+                Ident::Align,
+            )),
+        }
+    }
+
     fn error_type(&self) -> String {
         let typ = match self {
             Self::Rgba(_, _, _, _) => "Rgba",
             Self::Size(_) => "Size",
-            Self::Font(_) => "Font",
+            Self::Font(_, _) => "Font",
             Self::Coord(_) => "Coord",
+            Self::Stop(_, _) => "Stop",
+            Self::Outline(_, _) => "Outline",
+            Self::Shadow(_, _) => "Shadow",
+            Self::Spacing(_, _) => "Spacing",
+            Self::Angle(_) => "Angle",
+            Self::Rect(_) => "Rect",
+            Self::Wrap(_) => "Wrap",
+            Self::Align(_) => "Align",
         };
 
         typ.to_string()
     }
 }
 
-fn slice_str_tokens(s: &str) -> NVResult<&str> {
-    if (s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')) {
-        Ok(&s[1..s.len() - 1])
-    } else {
-        Err(NamedValueError::FaultyString(s.to_string()))
+fn slice_str_tokens(s: &str) -> NVResult<Cow<'_, str>> {
+    unquote(s).ok_or_else(|| NamedValueError::FaultyString(s.to_string()))
+}
+
+/// Splits the arguments of a named value at the commas which separate them, but not at the commas
+/// of nested named values or of strings, e.g. `0.5, rgba(0, 0, 0, 255)` is split into `0.5` and
+/// ` rgba(0, 0, 0, 255)`.
+fn split_arguments(arguments: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quotes = Quotes::default();
+    let mut start = 0;
+
+    for (i, c) in arguments.char_indices() {
+        if quotes.within(c) {
+            continue;
+        }
+
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&arguments[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&arguments[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_flat_arguments() {
+        assert_eq!(split_arguments("1, 2,3"), vec!["1", " 2", "3"]);
+    }
+
+    #[test]
+    fn split_nested_arguments() {
+        assert_eq!(
+            split_arguments("0.5, rgba(1, 2, 3, 4)"),
+            vec!["0.5", " rgba(1, 2, 3, 4)"]
+        );
+    }
+
+    #[test]
+    fn split_quoted_arguments() {
+        assert_eq!(split_arguments("'a,(b'"), vec!["'a,(b'"]);
+        assert_eq!(split_arguments(r"'a\',b', 'c'"), vec![r"'a\',b'", " 'c'"]);
+    }
+
+    #[test]
+    fn font_from_str_with_escaped_quotes() {
+        let font = r#"font("Bob's \"Sans\"", 'bold')"#.parse::<NamedValue>().unwrap();
+
+        assert!(matches!(font, NamedValue::Font(family, Some(style))
+            if family == r#"Bob's "Sans""# && style == "bold"));
+    }
+
+    #[test]
+    fn stop_from_str() {
+        let stop = "stop(0.25, rgba(10, 20, 30, 255))"
+            .parse::<NamedValue>()
+            .unwrap();
+
+        assert_eq!(stop.extract_stop().unwrap(), (0.25, [10, 20, 30, 255]));
+    }
+
+    #[test]
+    fn stop_from_str_requires_rgba() {
+        assert!("stop(0.25, coord(1, 2))".parse::<NamedValue>().is_err());
+        assert!("stop(0.25)".parse::<NamedValue>().is_err());
+        assert!("stop(rgba(1, 2, 3, 4), 0.5)".parse::<NamedValue>().is_err());
+    }
+
+    #[test]
+    fn font_from_str() {
+        let font = r#"font("resources/font/Lato-Regular.ttf")"#.parse::<NamedValue>().unwrap();
+
+        assert_eq!(
+            font.extract_font().unwrap(),
+            ("resources/font/Lato-Regular.ttf", None)
+        );
+    }
+
+    #[test]
+    fn font_with_style_from_str() {
+        let font = r#"font("DejaVu Sans", 'bold italic')"#.parse::<NamedValue>().unwrap();
+
+        assert_eq!(
+            font.extract_font().unwrap(),
+            ("DejaVu Sans", Some("bold italic"))
+        );
+    }
+
+    #[test]
+    fn font_from_str_requires_strings() {
+        assert!("font()".parse::<NamedValue>().is_err());
+        assert!(r#"font("Lato", 1)"#.parse::<NamedValue>().is_err());
+        assert!(r#"font("Lato", "bold", "italic")"#.parse::<NamedValue>().is_err());
+    }
+
+    #[test]
+    fn outline_from_str() {
+        let outline = "outline(1.5, rgba(0, 0, 0, 255))"
+            .parse::<NamedValue>()
+            .unwrap();
+
+        assert_eq!(outline.extract_outline().unwrap(), (1.5, [0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn shadow_from_str() {
+        let shadow = "shadow(2, -3, rgba(0, 0, 0, 128))"
+            .parse::<NamedValue>()
+            .unwrap();
+
+        assert_eq!(shadow.extract_shadow().unwrap(), ((2, -3), [0, 0, 0, 128]));
+    }
+
+    #[test]
+    fn shadow_from_str_requires_integer_offset() {
+        assert!("shadow(1.5, 2, rgba(0, 0, 0, 255))"
+            .parse::<NamedValue>()
+            .is_err());
+        assert!("shadow(1, rgba(0, 0, 0, 255))"
+            .parse::<NamedValue>()
+            .is_err());
+    }
+
+    #[test]
+    fn spacing_from_str() {
+        let spacing = "spacing(1.5, -2)".parse::<NamedValue>().unwrap();
+
+        assert_eq!(spacing.extract_spacing().unwrap(), (1.5, -2.0));
+    }
+
+    #[test]
+    fn angle_from_str() {
+        let angle = "angle(-45.5)".parse::<NamedValue>().unwrap();
+
+        assert_eq!(angle.extract_angle().unwrap(), -45.5);
+    }
+
+    #[test]
+    fn angle_from_str_requires_one_float() {
+        assert!("angle()".parse::<NamedValue>().is_err());
+        assert!("angle(1, 2)".parse::<NamedValue>().is_err());
+        assert!("angle('a')".parse::<NamedValue>().is_err());
+    }
+
+    #[test]
+    fn rect_from_str() {
+        let rect = "rect(10, 20, 300, 40)".parse::<NamedValue>().unwrap();
+
+        assert_eq!(rect.extract_rect().unwrap(), (10, 20, 300, 40));
+    }
+
+    #[test]
+    fn rect_from_str_requires_four_natural_numbers() {
+        assert!("rect(10, 20, 300)".parse::<NamedValue>().is_err());
+        assert!("rect(10, 20, 300, 40, 1)".parse::<NamedValue>().is_err());
+        assert!("rect(-10, 20, 300, 40)".parse::<NamedValue>().is_err());
+        assert!("rect(10, 20, 0.5, 40)".parse::<NamedValue>().is_err());
+    }
+
+    #[test]
+    fn wrap_from_str() {
+        let wrap = "wrap(120.5)".parse::<NamedValue>().unwrap();
+
+        assert_eq!(wrap.extract_wrap().unwrap(), 120.5);
+    }
+
+    #[test]
+    fn wrap_from_str_requires_one_float() {
+        assert!("wrap()".parse::<NamedValue>().is_err());
+        assert!("wrap(1, 2)".parse::<NamedValue>().is_err());
+        assert!("wrap('a')".parse::<NamedValue>().is_err());
+    }
+
+    #[test]
+    fn align_from_str() {
+        let align = "align('center')".parse::<NamedValue>().unwrap();
+
+        assert_eq!(align.extract_align().unwrap(), "center");
+    }
+
+    #[test]
+    fn align_from_str_requires_one_string() {
+        assert!("align()".parse::<NamedValue>().is_err());
+        assert!("align(center)".parse::<NamedValue>().is_err());
+        assert!("align('left', 'right')".parse::<NamedValue>().is_err());
     }
 }
@@ -0,0 +1,188 @@
+//! The quoting module recognizes the strings of scripts, and of the named values given on the
+//! command line, e.g. `font("Open Sans", "bold")`, so each parser splits text in the same way.
+//!
+//! A string is enclosed in single or double quotation marks. Within a string, a backslash escapes
+//! a quotation mark or another backslash, e.g. `'it\'s'` or `'ends with \\'`. Other backslashes are
+//! kept as they are, so paths like `'C:\images\logo.png'` can be written without escapes.
+
+use std::borrow::Cow;
+
+/// Tracks, character by character, whether text is within a string.
+#[derive(Debug, Default)]
+pub(crate) struct Quotes {
+    quote: Option<char>,
+    escaped: bool,
+}
+
+impl Quotes {
+    /// Takes the next character, and returns whether it's part of a string, including the
+    /// quotation marks which enclose it.
+    pub(crate) fn within(&mut self, c: char) -> bool {
+        match self.quote {
+            None if c == '"' || c == '\'' => self.quote = Some(c),
+            None => return false,
+            Some(_) if self.escaped => self.escaped = false,
+            Some(_) if c == '\\' => self.escaped = true,
+            Some(quote) if c == quote => self.quote = None,
+            Some(_) => {}
+        }
+
+        true
+    }
+
+    /// Whether a string has been opened, but not yet closed.
+    pub(crate) fn is_open(&self) -> bool {
+        self.quote.is_some()
+    }
+}
+
+/// The contents of a string, e.g. `'it\'s'`, without its quotation marks and with its escapes
+/// replaced, or `None` if the text isn't a single string.
+pub fn unquote(text: &str) -> Option<Cow<'_, str>> {
+    let mut quotes = Quotes::default();
+    let mut chars = text.char_indices();
+
+    match chars.next() {
+        Some((_, c)) if quotes.within(c) => {}
+        _ => return None,
+    }
+
+    // the string must be closed by the last character
+    let end = chars
+        .find(|&(_, c)| quotes.within(c) && !quotes.is_open())?
+        .0;
+
+    if end + 1 == text.len() {
+        Some(unescape(&text[1..end]))
+    } else {
+        None
+    }
+}
+
+/// Replaces the escaped quotation marks and backslashes within a string by themselves.
+pub fn unescape(inner: &str) -> Cow<'_, str> {
+    if !inner.contains('\\') {
+        return Cow::Borrowed(inner);
+    }
+
+    let mut unescaped = String::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(&next)) if next == '\\' || next == '\'' || next == '"' => {
+                chars.next();
+                unescaped.push(next);
+            }
+            (c, _) => unescaped.push(c),
+        }
+    }
+
+    Cow::Owned(unescaped)
+}
+
+/// Encloses text in quotation marks, so `unquote` gives back the text: single ones, unless the
+/// text only contains those, in which case double ones.
+pub fn quote(text: &str) -> String {
+    let quote = if text.contains('\'') && !text.contains('"') {
+        '"'
+    } else {
+        '\''
+    };
+
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push(quote);
+
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        // a backslash only needs to be escaped if it would otherwise escape what follows it
+        let escapes_next = matches!(chars.peek(), None | Some('\\') | Some('\'') | Some('"'));
+
+        if c == quote || (c == '\\' && escapes_next) {
+            quoted.push('\\');
+        }
+
+        quoted.push(c);
+    }
+
+    quoted.push(quote);
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_within() {
+        let text = r#"a 'b\' "c' d "e\\" f"#;
+        let mut quotes = Quotes::default();
+        let within = text
+            .chars()
+            .map(|c| if quotes.within(c) { '^' } else { ' ' })
+            .collect::<String>();
+
+        assert_eq!(within, "  ^^^^^^^^   ^^^^^  ");
+    }
+
+    #[parameterized(
+        input = {
+            "'logo.png'",
+            "\"logo.png\"",
+            "'say \"cheese\"'",
+            r"'it\'s'",
+            r#""say \"cheese\"""#,
+            r"'ends with \\'",
+            r"'C:\images\logo.png'",
+            "''",
+        },
+        expected = {
+            "logo.png",
+            "logo.png",
+            "say \"cheese\"",
+            "it's",
+            "say \"cheese\"",
+            r"ends with \",
+            r"C:\images\logo.png",
+            "",
+        }
+    )]
+    fn unquote_ok(input: &str, expected: &str) {
+        assert_eq!(unquote(input).unwrap(), expected);
+    }
+
+    #[parameterized(input = {
+        "logo.png",
+        "'logo.png",
+        "'logo.png\"",
+        "'a' 'b'",
+        r"'ends with \'",
+        "",
+    })]
+    fn unquote_none(input: &str) {
+        assert!(unquote(input).is_none());
+    }
+
+    #[parameterized(input = {
+        "logo.png",
+        "it's",
+        "say \"cheese\"",
+        "it's \"cheese\"",
+        r"C:\images\",
+        r"C:\images\logo.png",
+        r"\\server\share",
+        r"\'",
+    })]
+    fn quote_roundtrip(input: &str) {
+        assert_eq!(unquote(&quote(input)).unwrap(), input);
+    }
+
+    #[test]
+    fn quote_prefers_single_quotes() {
+        assert_eq!(quote("logo.png"), "'logo.png'");
+        assert_eq!(quote("it's"), "\"it's\"");
+        assert_eq!(quote("it's \"cheese\""), r#"'it\'s "cheese"'"#);
+        assert_eq!(quote(r"C:\images\logo.png"), r"'C:\images\logo.png'");
+    }
+}
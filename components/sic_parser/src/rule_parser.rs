@@ -1,140 +1,247 @@
-//! The rule parser module has a goal to parse pairs/span from Pest data structures to image operations.
+//! The rule parser module has a goal to parse the statements of a script to image operations.
 
-use super::Rule;
+use crate::diagnostic::{Arguments, Diagnostic};
 use crate::errors::{OperationParamError, SicParserError};
-use crate::value_parser::ParseInputsFromIter;
-use pest::iterators::{Pair, Pairs};
-use sic_image_engine::engine::{EnvItem, Instr, ItemName};
-#[cfg(feature = "imageproc-ops")]
-use sic_image_engine::wrapper::draw_text_inner::DrawTextInner;
-use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
-use sic_image_engine::wrapper::image_path::ImageFromPath;
-use sic_image_engine::wrapper::overlay::OverlayInputs;
-use sic_image_engine::ImgOp;
+use crate::instruction::{parse_modifier, parse_operation, OperationArguments};
+use crate::quoting::unescape;
+use crate::syntax::{Statement, Token, TokenKind};
+use crate::value_parser::{Describable, ParseInputsFromIter};
+use sic_image_engine::engine::{EnvItem, Instr, ItemName, Occurrence};
+use std::borrow::Cow;
 
 // This function parses statements provided as a single 'script' to an image operations program.
 // An image operations program is currently a linear list of image operations which are applied
 // in a left-to-right order.
-// Operations are parsed from Pairs and Rules, which are provided by the Pest parser library.
+// The statements are split into their names and arguments by the syntax module; the arguments are
+// then parsed like the arguments of the image operations given on the command line.
 //
-// In the event of any parse failure, an error shall be returned.
-// The error currently usually contains into_inner(), to provide detailed information about the
-// origins of the parsing rejection.
-//
-// FIXME: When the user facing errors will be reworked, the providing of or the how to providing of-
-//        the into_inner() parsing details should be reconsidered
-pub fn parse_image_operations(pairs: Pairs<'_, Rule>) -> Result<Vec<Instr>, SicParserError> {
-    pairs
-        .filter(|pair| pair.as_rule() != Rule::EOI)
-        .map(|pair| match pair.as_rule() {
-            Rule::blur => Blur(pair),
-            Rule::brighten => Brighten(pair),
-            Rule::contrast => Contrast(pair),
-            Rule::crop => Crop(pair),
-            Rule::diff => Diff(
-                pair.into_inner()
-                    .next()
-                    .ok_or_else(|| SicParserError::NoInnerString)?,
-            ),
-            Rule::filter3x3 => Filter3x3(pair),
-            Rule::flip_horizontal => Ok(Instr::Operation(ImgOp::FlipHorizontal)),
-            Rule::flip_vertical => Ok(Instr::Operation(ImgOp::FlipVertical)),
-            Rule::grayscale => Ok(Instr::Operation(ImgOp::GrayScale)),
-            Rule::huerotate => HueRotate(pair),
-            Rule::invert => Ok(Instr::Operation(ImgOp::Invert)),
-            Rule::overlay => parse_overlay(pair),
-            Rule::resize => Resize(pair),
-            Rule::rotate90 => Ok(Instr::Operation(ImgOp::Rotate90)),
-            Rule::rotate180 => Ok(Instr::Operation(ImgOp::Rotate180)),
-            Rule::rotate270 => Ok(Instr::Operation(ImgOp::Rotate270)),
-            Rule::unsharpen => Unsharpen(pair),
-            Rule::setopt => parse_set_environment(pair.into_inner().next().ok_or_else(|| {
-                SicParserError::OperationError(OperationParamError::SetEnvironment)
-            })?),
-            // this is called 'del' for users
-            Rule::unsetopt => {
-                parse_unset_environment(pair.into_inner().next().ok_or_else(|| {
-                    SicParserError::OperationError(OperationParamError::UnsetEnvironment)
-                })?)
-            }
-
-            #[cfg(feature = "imageproc-ops")]
-            Rule::draw_text => Ok(parse_draw_text(pair)?),
-
-            _ => Err(SicParserError::UnknownOperationError),
+// In the event of any parse failure, an error shall be returned, which points at the operation,
+// or if it's known, the argument at which parsing failed.
+pub fn parse_image_operations(
+    statements: Vec<Statement<'_>>,
+) -> Result<Vec<Instr>, SicParserError> {
+    statements
+        .into_iter()
+        .map(|statement| {
+            parse_image_operation(&statement).map_err(|err| match err {
+                SicParserError::InvalidOperation(diagnostic, err) => {
+                    SicParserError::InvalidOperation(
+                        Box::new(diagnostic.of_operation(&statement)),
+                        err,
+                    )
+                }
+                err => SicParserError::InvalidOperation(
+                    Box::new(Diagnostic::at_operation(&statement)),
+                    Box::new(err),
+                ),
+            })
         })
         .collect::<Result<Vec<_>, SicParserError>>()
 }
 
-macro_rules! parse_primitive_from_pair {
-    ($pair:expr, $ty:ty) => {{
-        let inner = $pair.into_inner();
-        let ty: Result<$ty, SicParserError> =
-            ParseInputsFromIter::parse(inner.map(|pair| pair.as_str()));
-        ty
-    }};
+fn parse_image_operation(statement: &Statement<'_>) -> Result<Instr, SicParserError> {
+    let name = statement.name().as_str().to_ascii_lowercase();
+
+    match name.as_str() {
+        "set" => parse_setopt(statement.arguments()),
+        // this is called 'del' for users
+        "del" => parse_unset_environment(statement.arguments()),
+        _ => parse_operation(
+            &name,
+            ScriptArguments::of_operation(&name, statement.arguments())?,
+        ),
+    }
+}
+
+/// The arguments of an operation or modifier of a script. On failure, the argument at which
+/// parsing failed is pointed at, if it's known; otherwise the operation is, by
+/// `parse_image_operations`.
+struct ScriptArguments<'i> {
+    arguments: Arguments<'i>,
+    // whether strings are given with their escapes, which are then replaced by the value parser
+    escaped: bool,
 }
 
-macro_rules! parse_op_from_pair {
-    ($what_op:tt, $inner_ty:ty) => {
-        #[allow(non_snake_case)]
-        fn $what_op(pair: Pair<'_, Rule>) -> Result<Instr, SicParserError> {
-            let val = parse_primitive_from_pair!(pair, $inner_ty)?;
-            let stmt = Instr::Operation(ImgOp::$what_op(val));
-            Ok(stmt)
+impl<'i> ScriptArguments<'i> {
+    fn of_operation(name: &str, arguments: &[Token<'i>]) -> Result<Self, SicParserError> {
+        let arguments = match name {
+            "convolve" | "filter3x3" => kernel_arguments(name, arguments)?,
+            _ => arguments.to_vec(),
+        };
+
+        Ok(Self {
+            arguments: Arguments::new(arguments),
+            // the text to draw has escapes of its own, e.g. `\n` for a line break
+            escaped: name == "draw-text",
+        })
+    }
+
+    fn of_modifier(arguments: &[Token<'i>]) -> Self {
+        Self {
+            arguments: Arguments::new(arguments.to_vec()),
+            escaped: false,
         }
-    };
+    }
+
+    /// The text of an argument: for a string, its contents without the surrounding quotation
+    /// marks, and unless they're kept, with its escapes replaced.
+    fn argument(&self, token: &Token<'i>) -> Cow<'i, str> {
+        match token.kind() {
+            TokenKind::String if self.escaped => Cow::Borrowed(token.contents()),
+            TokenKind::String => unescape(token.contents()),
+            _ => Cow::Borrowed(token.as_str()),
+        }
+    }
 }
 
-parse_op_from_pair!(Blur, f32);
-parse_op_from_pair!(Brighten, i32);
-parse_op_from_pair!(Contrast, f32);
-parse_op_from_pair!(Crop, (u32, u32, u32, u32));
-parse_op_from_pair!(Diff, ImageFromPath);
-parse_op_from_pair!(HueRotate, i32);
-parse_op_from_pair!(Resize, (u32, u32));
-parse_op_from_pair!(Unsharpen, (f32, i32));
-parse_op_from_pair!(Filter3x3, [f32; 9]);
+impl OperationArguments for ScriptArguments<'_> {
+    fn parse<T>(mut self) -> Result<T, SicParserError>
+    where
+        T: ParseInputsFromIter<Error = SicParserError>,
+    {
+        // the values are borrowed while they're parsed, so the escapes of strings are replaced first
+        let values = self
+            .arguments
+            .remaining()
+            .iter()
+            .map(|token| (token.kind(), self.argument(token)))
+            .collect::<Vec<_>>();
+
+        T::parse(
+            (&mut self.arguments)
+                .zip(&values)
+                .map(|(_, (kind, value))| match kind {
+                    TokenKind::String => Describable::from(value.as_ref()),
+                    _ => Describable::word(value),
+                }),
+        )
+        .map_err(|err| match self.arguments.diagnostic(&err) {
+            Some(diagnostic) => {
+                SicParserError::InvalidOperation(Box::new(diagnostic), Box::new(err))
+            }
+            None => err,
+        })
+    }
+}
 
-macro_rules! parse_setenv_from_pair {
-    ($env_item:tt, $ty:ty) => {
-        #[allow(non_snake_case)]
-        fn $env_item(pair: Pair<'_, Rule>) -> Result<Instr, SicParserError> {
-            let inner = pair.into_inner().skip(1);
+// The weights of a kernel, without the '|'s which may separate its rows. A '|' must be between two
+// weights, and if the rows of a 3x3 kernel are separated, each of them must be.
+fn kernel_arguments<'i>(
+    name: &str,
+    arguments: &[Token<'i>],
+) -> Result<Vec<Token<'i>>, SicParserError> {
+    let is_separator = |token: &Token<'_>| token.kind() == TokenKind::RowSeparator;
+    let separators = arguments
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| is_separator(token))
+        .collect::<Vec<_>>();
+
+    let misplaced = separators.iter().find(|(index, _)| {
+        let between_weights = *index > 0
+            && !is_separator(&arguments[index - 1])
+            && arguments
+                .get(index + 1)
+                .is_some_and(|next| !is_separator(next));
+
+        let between_rows = name != "filter3x3"
+            || (arguments.len() == 11 && separators.len() == 2 && (index + 1) % 4 == 0);
+
+        !(between_weights && between_rows)
+    });
+
+    if let Some((_, separator)) = misplaced {
+        return Err(SicParserError::InvalidOperation(
+            Box::new(Diagnostic::at_argument(separator)),
+            Box::new(SicParserError::RowSeparator),
+        ));
+    }
+
+    Ok(arguments
+        .iter()
+        .filter(|token| !is_separator(token))
+        .cloned()
+        .collect())
+}
 
-            let arg: Result<$ty, SicParserError> =
-                ParseInputsFromIter::parse(inner.map(|pair| pair.as_str()));
+// expected arguments:
+// - (optional) the occurrence of an operation the item is set for, e.g. 'resize.0'
+// - the name of the modifier, followed by its value
+fn parse_setopt(arguments: &[Token<'_>]) -> Result<Instr, SicParserError> {
+    let first = arguments.first().ok_or(SicParserError::OperationError(
+        OperationParamError::SetEnvironment,
+    ))?;
 
-            let stmt = Instr::EnvAdd(EnvItem::$env_item(arg?));
-            Ok(stmt)
-        }
-    };
+    // the names of modifiers don't contain a '.'
+    if first.as_str().contains('.') {
+        let occurrence = parse_occurrence(first.as_str())?;
+        let item = parse_set_environment(&arguments[1..])?;
+
+        Ok(Instr::EnvAddFor(occurrence, item))
+    } else {
+        Ok(Instr::EnvAdd(parse_set_environment(arguments)?))
+    }
+}
+
+// expected: the name of the operation, e.g. 'resize', and the index of the occurrence among the
+// operations with that name, separated by a '.'
+fn parse_occurrence(occurrence: &str) -> Result<Occurrence, SicParserError> {
+    let err_msg = || "an occurrence of an operation, e.g. 'resize.0'".to_string();
+
+    let (operation, index) = occurrence
+        .rsplit_once('.')
+        .filter(|(operation, index)| {
+            !operation.is_empty()
+                && operation
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && !index.is_empty()
+                && index.chars().all(|c| c.is_ascii_digit())
+        })
+        .ok_or_else(|| SicParserError::ValueParsingError(err_msg()))?;
+
+    let index = index
+        .parse::<usize>()
+        .map_err(|err| SicParserError::ValueParsingErrorWithInnerError(err_msg(), Box::new(err)))?;
+
+    Occurrence::new(&operation.to_lowercase(), index).map_err(SicParserError::OccurrenceError)
 }
 
-parse_setenv_from_pair!(CustomSamplingFilter, FilterTypeWrap);
-parse_setenv_from_pair!(PreserveAspectRatio, bool);
+fn parse_set_environment(arguments: &[Token<'_>]) -> Result<EnvItem, SicParserError> {
+    let (name, values) = arguments
+        .split_first()
+        .ok_or(SicParserError::OperationError(
+            OperationParamError::SetEnvironment,
+        ))?;
+
+    parse_modifier(
+        &name.as_str().to_ascii_lowercase(),
+        ScriptArguments::of_modifier(values),
+    )
+}
 
-fn parse_set_environment(pair: Pair<'_, Rule>) -> Result<Instr, SicParserError> {
-    let environment_item = match pair.as_rule() {
-        Rule::set_resize_sampling_filter => CustomSamplingFilter(pair)?,
-        Rule::set_resize_preserve_aspect_ratio => PreserveAspectRatio(pair)?,
+fn parse_unset_environment(arguments: &[Token<'_>]) -> Result<Instr, SicParserError> {
+    let name = match arguments {
+        [name] => name.as_str().to_ascii_lowercase(),
         _ => {
             return Err(SicParserError::OperationError(
-                OperationParamError::SetEnvironmentElement(format!("{}", pair)),
-            ));
+                OperationParamError::UnsetEnvironment,
+            ))
         }
     };
 
-    Ok(environment_item)
-}
-
-fn parse_unset_environment(pair: Pair<'_, Rule>) -> Result<Instr, SicParserError> {
-    let environment_item = match pair.as_rule() {
-        Rule::env_resize_sampling_filter_name => ItemName::CustomSamplingFilter,
-        Rule::env_resize_preserve_aspect_ratio_name => ItemName::PreserveAspectRatio,
+    let environment_item = match name.as_str() {
+        "adaptive-threshold-method" => ItemName::AdaptiveThresholdMethod,
+        "background" => ItemName::BackgroundColor,
+        "blur-algorithm" => ItemName::BlurAlgorithm,
+        "gravity" => ItemName::Gravity,
+        "sampling-filter" => ItemName::CustomSamplingFilter,
+        "preserve-aspect-ratio" => ItemName::PreserveAspectRatio,
+        "mask" => ItemName::Mask,
+        "region" => ItemName::Region,
         _ => {
             return Err(SicParserError::OperationError(
-                OperationParamError::UnsetEnvironmentElement(format!("{}", pair)),
+                OperationParamError::UnsetEnvironmentElement(name),
             ));
         }
     };
@@ -142,134 +249,87 @@ fn parse_unset_environment(pair: Pair<'_, Rule>) -> Result<Instr, SicParserError
     Ok(Instr::EnvRemove(environment_item))
 }
 
-fn parse_overlay(pair: Pair<'_, Rule>) -> Result<Instr, SicParserError> {
-    let mut pairs = pair.into_inner();
-
-    let image_path = parse_primitive_from_pair!(
-        pairs.next().ok_or_else(|| SicParserError::NoInnerString)?,
-        ImageFromPath
-    )?;
-
-    let x = pairs
-        .next()
-        .ok_or_else(|| SicParserError::ExpectedValue("uint".to_string()))?;
-    let y = pairs
-        .next()
-        .ok_or_else(|| SicParserError::ExpectedValue("uint".to_string()))?;
-
-    let position: (u32, u32) = ParseInputsFromIter::parse(&[x.as_str(), y.as_str()])?;
-
-    Ok(Instr::Operation(ImgOp::Overlay(OverlayInputs::new(
-        image_path, position,
-    ))))
-}
-
-#[cfg(feature = "imageproc-ops")]
-// expected pair with inner pairs:
-// - rule: 'string_unicode'; represents: text to draw
-// - rule: 'named_value'; which: rgba(r, g, b, a) with r,g,b,a =: u8; represents: color of the text
-// - rule: 'named_value'; which: size(s) with s =: u32; represents: size of the text
-// - rule: 'named_value'; which: font(f) with f =: string (->into path); represents: which font file to use
-fn parse_draw_text(pair: Pair<'_, Rule>) -> Result<Instr, SicParserError> {
-    use crate::named_value::parse_named_value;
-    use sic_core::image::Rgba;
-    use sic_image_engine::wrapper::font_options::{FontOptions, FontScale};
-
-    let mut pairs = pair.into_inner();
-
-    // text
-    let text_pair = pairs
-        .next()
-        .ok_or_else(|| SicParserError::ExpectedValue(String::from("String")))?
-        .into_inner()
-        .next()
-        .ok_or_else(|| SicParserError::ExpectedValue(String::from("String")))?
-        .as_str();
-
-    let coord = pairs.next().ok_or_else(|| {
-        SicParserError::ExpectedNamedValue(String::from("coord(x: NatNum, y: NatNum)"))
-    })?;
-
-    let coord = parse_named_value(coord).map_err(SicParserError::NamedValueParsingError)?;
-
-    let color = pairs.next().ok_or_else(|| {
-        SicParserError::ExpectedNamedValue(String::from("rgba(r: Byte, g: Byte, b: Byte, a: Byte)"))
-    })?;
-
-    let color = parse_named_value(color).map_err(SicParserError::NamedValueParsingError)?;
-
-    let size = pairs
-        .next()
-        .ok_or_else(|| SicParserError::ExpectedNamedValue(String::from("size(v: Float)")))?;
-
-    let size = parse_named_value(size).map_err(SicParserError::NamedValueParsingError)?;
-
-    let font_file = pairs.next().ok_or_else(|| {
-        SicParserError::ExpectedNamedValue(String::from("font(font_path: String)"))
-    })?;
-
-    let font_file = parse_named_value(font_file).map_err(SicParserError::NamedValueParsingError)?;
-
-    Ok(Instr::Operation(ImgOp::DrawText(DrawTextInner::new(
-        text_pair.to_string(),
-        (coord.extract_coord()).map_err(SicParserError::NamedValueParsingError)?,
-        FontOptions::new(
-            font_file
-                .extract_font()
-                .map_err(SicParserError::NamedValueParsingError)?,
-            Rgba(
-                color
-                    .extract_rgba()
-                    .map_err(SicParserError::NamedValueParsingError)?,
-            ),
-            FontScale::Uniform(
-                size.extract_size()
-                    .map_err(SicParserError::NamedValueParsingError)?,
-            ),
-        ),
-    ))))
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::SICParser;
-    use pest::Parser;
+    use crate::syntax::parse_statements;
     use sic_core::image::imageops::FilterType;
-    use sic_image_engine::engine::EnvItem;
+    use sic_core::image::Rgba;
+    use sic_image_engine::engine::{DeferredOp, EnvItem};
+    use sic_image_engine::wrapper::adaptive_threshold_method::AdaptiveThresholdMethod;
+    use sic_image_engine::wrapper::blur_algorithm::BlurAlgorithm;
+    use sic_image_engine::wrapper::border::BorderInputs;
+    use sic_image_engine::wrapper::channel::Channel;
+    use sic_image_engine::wrapper::chroma_key::ChromaKeyInputs;
+    use sic_image_engine::wrapper::contours::ContoursInputs;
+    use sic_image_engine::wrapper::dimension::Dimension;
+    #[cfg(feature = "imageproc-ops")]
+    use sic_image_engine::wrapper::draw_corners::DrawCornersInputs;
+    #[cfg(feature = "imageproc-ops")]
+    use sic_image_engine::wrapper::draw_shape::{
+        DrawEllipseInputs, DrawLineInputs, DrawPolygonInputs, DrawRectInputs,
+    };
+    #[cfg(feature = "imageproc-ops")]
+    use sic_image_engine::wrapper::draw_text_inner::DrawTextInner;
+    use sic_image_engine::wrapper::extend::ExtendInputs;
+    use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
+    use sic_image_engine::wrapper::find::FindInputs;
+    use sic_image_engine::wrapper::gradient::Gradient;
+    use sic_image_engine::wrapper::gradient_fill::GradientFillInputs;
+    use sic_image_engine::wrapper::gravity::Gravity;
+    use sic_image_engine::wrapper::histogram::{
+        DrawHistogramInputs, HistogramChannels, HistogramPlacement,
+    };
+    use sic_image_engine::wrapper::image_path::ImageFromPath;
+    use sic_image_engine::wrapper::kernel::{Kernel, KernelFromPath};
+    use sic_image_engine::wrapper::lut::LutFromPath;
+    use sic_image_engine::wrapper::mirror::MirrorMode;
+    use sic_image_engine::wrapper::nine_slice::NineSliceInputs;
+    #[cfg(feature = "imageproc-ops")]
+    use sic_image_engine::wrapper::noise::NoiseInputs;
+    use sic_image_engine::wrapper::overlay::OverlayInputs;
+    use sic_image_engine::wrapper::pad_to_ratio::PadToRatioInputs;
+    #[cfg(feature = "imageproc-ops")]
+    use sic_image_engine::wrapper::perspective::PerspectiveInputs;
+    use sic_image_engine::wrapper::pixel_upscale::PixelUpscaleInputs;
+    use sic_image_engine::wrapper::redact::RedactInputs;
+    use sic_image_engine::wrapper::region::Region;
+    use sic_image_engine::wrapper::stamp::StampInputs;
+    use sic_image_engine::wrapper::tonemap::ToneMapInputs;
+    use sic_image_engine::ImgOp;
 
     use super::*;
 
     #[test]
     fn test_parse_next_line_versions_fin_with_eoi() {
-        let pairs = SICParser::parse(Rule::main, "blur 1;\nbrighten 2")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
+        let statements =
+            parse_statements("blur 1;\nbrighten 2").unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
                 Instr::Operation(ImgOp::Blur(1.0)),
                 Instr::Operation(ImgOp::Brighten(2))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_parse_next_line_versions_fin_with_sep_eoi() {
-        let pairs = SICParser::parse(Rule::main, "blur 1;\nbrighten 2;")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
+        let statements =
+            parse_statements("blur 1;\nbrighten 2;").unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
                 Instr::Operation(ImgOp::Blur(1.0)),
                 Instr::Operation(ImgOp::Brighten(2))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_parse_next_line_versions_fin_with_sep_with_trailing_spaces_eoi() {
-        let pairs = SICParser::parse(Rule::main, "blur 1;\nbrighten 2;    ")
+        let statements = parse_statements("blur 1;\nbrighten 2;    ")
             .unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
@@ -277,139 +337,231 @@ mod tests {
                 Instr::Operation(ImgOp::Blur(1.0)),
                 Instr::Operation(ImgOp::Brighten(2))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_parse_single_line_versions_fin_with_eoi() {
-        let pairs = SICParser::parse(Rule::main, "blur 1; brighten 2")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
+        let statements =
+            parse_statements("blur 1; brighten 2").unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
                 Instr::Operation(ImgOp::Blur(1.0)),
                 Instr::Operation(ImgOp::Brighten(2))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_parse_single_line_versions_fin_with_eoi_2() {
-        let pairs = SICParser::parse(Rule::main, "blur 1;brighten 2")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
+        let statements =
+            parse_statements("blur 1;brighten 2").unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
                 Instr::Operation(ImgOp::Blur(1.0)),
                 Instr::Operation(ImgOp::Brighten(2))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_parse_single_line_versions_fin_with_sep_with_trailing_spaces_eoi() {
-        let pairs = SICParser::parse(Rule::main, "blur 1; brighten 2;   ")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
+        let statements =
+            parse_statements("blur 1; brighten 2;   ").unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
                 Instr::Operation(ImgOp::Blur(1.0)),
                 Instr::Operation(ImgOp::Brighten(2))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     #[should_panic]
     fn test_parse_single_line_versions_require_sep() {
-        SICParser::parse(Rule::main, "blur 4 blur 3").unwrap_or_else(|e| panic!("error: {:?}", e));
+        parse_statements("blur 4 blur 3")
+            .and_then(parse_image_operations)
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_parse_single_line_versions_require_sep_2() {
-        SICParser::parse(Rule::main, "blur 4\nblur 3").unwrap_or_else(|e| panic!("error: {:?}", e));
+        parse_statements("blur 4\nblur 3")
+            .and_then(parse_image_operations)
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_parse_single_line_versions_require_sep_3() {
-        SICParser::parse(Rule::main, "blur 4 blur 3;").unwrap_or_else(|e| panic!("error: {:?}", e));
+        parse_statements("blur 4 blur 3;")
+            .and_then(parse_image_operations)
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
     }
 
     #[test]
     fn test_parse_single_line_versions_fin_with_sep_eoi() {
-        let pairs = SICParser::parse(Rule::main, "blur 1;brighten 2;")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
+        let statements =
+            parse_statements("blur 1;brighten 2;").unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
                 Instr::Operation(ImgOp::Blur(1.0)),
                 Instr::Operation(ImgOp::Brighten(2))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     #[should_panic]
     fn test_parse_require_space_between_operation_id_and_value() {
-        SICParser::parse(Rule::main, "blur1; brighten 2")
+        parse_statements("blur1; brighten 2")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("error: {:?}", e));
     }
 
     #[test]
     fn test_blur_with_int_accept() {
-        let pairs = SICParser::parse(Rule::main, "blur 15;")
+        let statements = parse_statements("blur 15;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Blur(15.0))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     // related issue: https://github.com/foresterre/sic/issues/130
     #[test]
     fn test_blur_with_fp_accept() {
-        let pairs = SICParser::parse(Rule::main, "blur 15.0;")
+        let statements = parse_statements("blur 15.0;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Blur(15.0))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_blur_with_fp_neg_accept() {
-        let pairs = SICParser::parse(Rule::main, "blur -15.0;")
+        let statements = parse_statements("blur -15.0;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Blur(-15.0))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     #[should_panic]
     fn test_blur_with_fp_reject() {
-        SICParser::parse(Rule::main, "blur 15.;")
+        parse_statements("blur 15.;")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     fn test_crop_in_order_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "crop 1 2 3 4;")
+        let statements = parse_statements("crop 1 2 3 4;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Crop((1, 2, 3, 4)))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_crop_percentages_parse_correct() {
+        use sic_image_engine::wrapper::dimension::Dimension::{Percentage, Pixels};
+
+        let statements = parse_statements("crop 10% 12.5% 90% 100;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Deferred(DeferredOp::Crop([
+                Percentage(10.0),
+                Percentage(12.5),
+                Percentage(90.0),
+                Pixels(100)
+            ]))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[parameterized(input = {
+        "crop 10% 10% 90%% 90%;",
+        "crop -10% 10% 90% 90%;",
+        "crop 10 % 10% 90% 90%;",
+        "crop 10.% 10% 90% 90%;",
+    })]
+    fn test_crop_percentages_reject(input: &str) {
+        let statements = parse_statements(input).and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[test]
+    fn test_crop_variables_parse_correct() {
+        use sic_image_engine::wrapper::dimension::Dimension::{self, Pixels};
+
+        let statements = parse_statements("crop 0 0 $width $height/2;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Deferred(DeferredOp::Crop([
+                Pixels(0),
+                Pixels(0),
+                Dimension::try_from_str("$width").unwrap(),
+                Dimension::try_from_str("$height/2").unwrap(),
+            ]))],
+            parse_image_operations(statements).unwrap()
         );
     }
 
+    #[parameterized(input = {
+        "resize ($width/2) ($height/2);",
+        "resize ( $width / 2 ) min($height, 100);",
+        "resize MAX($width-10,0) (($height+1)*2);",
+    })]
+    fn test_resize_expressions_parse_correct(input: &str) {
+        let statements = parse_statements(input)
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        let instructions = parse_image_operations(statements).unwrap();
+
+        assert!(matches!(
+            instructions.as_slice(),
+            [Instr::Deferred(DeferredOp::Resize([
+                Dimension::Expression(_),
+                Dimension::Expression(_)
+            ]))]
+        ));
+    }
+
+    #[parameterized(input = {
+        "crop 0 0 $width $height / 2;",
+        "crop 0 0 $width $height/;",
+        "crop 0 0 $width ($height/2;",
+        "crop 0 0 $width min($height);",
+        "crop 0 0 $width avg($height, 2);",
+        "crop 0 0 $ $height;",
+    })]
+    fn test_crop_variables_reject(input: &str) {
+        let statements = parse_statements(input).and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[test]
+    fn test_crop_unknown_variable() {
+        let statements = parse_statements("crop 0 0 $depth 10;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert!(parse_image_operations(statements).is_err());
+    }
+
     #[test]
     fn test_crop_ones_parse_correct() {
         // Here we don't check that rX > lX and rY > lY
@@ -419,104 +571,379 @@ mod tests {
         // rX = bottom right X coordinate
         // rY = bottom right Y coordinate
 
-        let pairs = SICParser::parse(Rule::main, "crop 1 1 1 1;")
+        let statements = parse_statements("crop 1 1 1 1;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Crop((1, 1, 1, 1)))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_crop_zeros_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "crop 0 0 0 0;")
+        let statements = parse_statements("crop 0 0 0 0;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Crop((0, 0, 0, 0)))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     #[should_panic]
     fn test_crop_args_negative_parse_err() {
-        SICParser::parse(Rule::main, "crop -1 -1 -1 -1;")
+        parse_statements("crop -1 -1 -1 -1;")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_crop_arg_negative_p1_parse_err() {
-        SICParser::parse(Rule::main, "crop -1 0 0 0;")
+        parse_statements("crop -1 0 0 0;")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_crop_arg_negative_p2_parse_err() {
-        SICParser::parse(Rule::main, "crop 0 -1 0 0;")
+        parse_statements("crop 0 -1 0 0;")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_crop_arg_negative_p3_parse_err() {
-        SICParser::parse(Rule::main, "crop 0 0 -1 0;")
+        parse_statements("crop 0 0 -1 0;")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_crop_arg_negative_p4_parse_err() {
-        SICParser::parse(Rule::main, "crop 0 0 0 -1;")
+        parse_statements("crop 0 0 0 -1;")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     fn test_crop_arg_to_big_p4_parse_err() {
         // 4294967296 == std::u32::MAX + 1
-        let pairs = SICParser::parse(Rule::main, "crop 0 0 0 4294967296")
+        let statements = parse_statements("crop 0 0 0 4294967296")
             .unwrap_or_else(|_| panic!("Unable to parse sic image operations script."));
 
-        assert!(parse_image_operations(pairs).is_err())
+        assert!(parse_image_operations(statements).is_err())
     }
 
     #[test]
     fn test_crop_arg_just_in_range_p4_parse_ok() {
         // 4294967296 == std::u32::MAX
-        let pairs = SICParser::parse(Rule::main, "crop 0 0 0 4294967295")
+        let statements = parse_statements("crop 0 0 0 4294967295")
             .unwrap_or_else(|_| panic!("Unable to parse sic image operations script."));
 
         assert_eq!(
             vec![Instr::Operation(ImgOp::Crop((0, 0, 0, std::u32::MAX,)))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_contrast_single_stmt_int_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "contrast 15;")
+        let statements = parse_statements("contrast 15;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Contrast(15.0))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_contrast_single_stmt_f32_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "contrast 15.8;")
+        let statements = parse_statements("contrast 15.8;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Contrast(15.8))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_contrast_single_stmt_parse_fail_end_in_dot() {
-        let pairs = SICParser::parse(Rule::main, "contrast 15.;");
-        assert!(pairs.is_err());
+        let statements = parse_statements("contrast 15.;").and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[cfg(test)]
+    mod border_test {
+        use super::*;
+
+        ide!();
+
+        const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+        #[parameterized(
+            input = {
+                "border 4 rgba(255, 255, 255, 255);",
+                "border 4 8 rgba(255, 255, 255, 255)",
+                "BORDER 1 2 3 rgba(255,255,255,255);",
+                "border 1 2 3 4 rgba(255, 255, 255, 255);",
+            },
+            expected = {
+                BorderInputs::new(&[4], WHITE).unwrap(),
+                BorderInputs::new(&[4, 8], WHITE).unwrap(),
+                BorderInputs::new(&[1, 2, 3], WHITE).unwrap(),
+                BorderInputs::new(&[1, 2, 3, 4], WHITE).unwrap(),
+            }
+        )]
+        fn test_border_ok(input: &str, expected: BorderInputs) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::Border(expected))]
+            );
+        }
+
+        #[parameterized(input = {
+            "border rgba(255, 255, 255, 255);",
+            "border 1 2 3 4 5 rgba(255, 255, 255, 255);",
+            "border -1 rgba(255, 255, 255, 255);",
+            "border 4;",
+        })]
+        fn test_border_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+
+        #[test]
+        fn test_border_not_a_color() {
+            let statements = parse_statements("border 4 coord(1, 1);")
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert!(parse_image_operations(statements).is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod crop_gravity_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "crop-center 100 50;",
+                "CROP-CENTER 1 1",
+                "crop-gravity top-left 100 50;",
+                "crop-gravity Bottom-Right 20 10",
+                "crop-gravity north-east 20 10",
+            },
+            expected = {
+                (Gravity::Center, 100, 50),
+                (Gravity::Center, 1, 1),
+                (Gravity::TopLeft, 100, 50),
+                (Gravity::BottomRight, 20, 10),
+                (Gravity::TopRight, 20, 10),
+            }
+        )]
+        fn test_crop_gravity_ok(input: &str, expected: (Gravity, u32, u32)) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::CropGravity(expected))]
+            );
+        }
+
+        #[parameterized(input = {
+            "crop-center 100;",
+            "crop-center center 100 50;",
+            "crop-center -100 50;",
+            "crop-gravity 100 50;",
+            "crop-gravity up 100 50;",
+            "crop-gravity center 100;",
+        })]
+        fn test_crop_gravity_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod extend_test {
+        use super::*;
+
+        ide!();
+
+        const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+        #[parameterized(
+            input = {
+                "extend 640 480;",
+                "extend 640 480 top-left",
+                "EXTEND 640 480 rgba(255, 255, 255, 255);",
+                "extend 640 480 bottom rgba(255,255,255,255);",
+            },
+            expected = {
+                ExtendInputs::new((640, 480), None, None),
+                ExtendInputs::new((640, 480), Some(Gravity::TopLeft), None),
+                ExtendInputs::new((640, 480), None, Some(WHITE)),
+                ExtendInputs::new((640, 480), Some(Gravity::Bottom), Some(WHITE)),
+            }
+        )]
+        fn test_extend_ok(input: &str, expected: ExtendInputs) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::Extend(expected))]
+            );
+        }
+
+        #[parameterized(input = {
+            "extend 640;",
+            "extend -640 480;",
+            "extend 640 480 up;",
+            "extend 640 480 rgba(255, 255, 255, 255) center;",
+        })]
+        fn test_extend_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod pad_to_ratio_test {
+        use super::*;
+        use sic_image_engine::wrapper::aspect_ratio::AspectRatio;
+
+        ide!();
+
+        fn ratio(val: &str) -> AspectRatio {
+            AspectRatio::try_from_str(val).unwrap()
+        }
+
+        #[parameterized(
+            input = {
+                "pad-to-ratio 16:9;",
+                "PAD-TO-RATIO 1:1",
+                "pad-to-ratio 4:3 rgba(0, 0, 0, 255);",
+            },
+            expected = {
+                PadToRatioInputs::new(ratio("16:9"), None),
+                PadToRatioInputs::new(ratio("1:1"), None),
+                PadToRatioInputs::new(ratio("4:3"), Some(Rgba([0, 0, 0, 255]))),
+            }
+        )]
+        fn test_pad_to_ratio_ok(input: &str, expected: PadToRatioInputs) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::PadToRatio(expected))]
+            );
+        }
+
+        #[parameterized(input = {
+            "pad-to-ratio;",
+            "pad-to-ratio 16;",
+            "pad-to-ratio 16 9;",
+            "pad-to-ratio 1.5:1;",
+            "pad-to-ratio -16:9;",
+        })]
+        fn test_pad_to_ratio_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+
+        #[test]
+        fn test_pad_to_ratio_zero() {
+            let statements = parse_statements("pad-to-ratio 0:9;")
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert!(parse_image_operations(statements).is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod pixel_upscale_test {
+        use super::*;
+        use sic_image_engine::wrapper::pixel_upscale::PixelUpscaleAlgorithm;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "pixel-upscale 2;",
+                "PIXEL-UPSCALE 4",
+                "pixel-upscale 3 scale2x;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::PixelUpscale(PixelUpscaleInputs::new(2, PixelUpscaleAlgorithm::Scale2x)))],
+                vec![Instr::Operation(ImgOp::PixelUpscale(PixelUpscaleInputs::new(4, PixelUpscaleAlgorithm::Scale2x)))],
+                vec![Instr::Operation(ImgOp::PixelUpscale(PixelUpscaleInputs::new(3, PixelUpscaleAlgorithm::Scale2x)))],
+            }
+        )]
+        fn test_pixel_upscale_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "pixel-upscale;", "pixel-upscale 2.5;", "pixel-upscale -2;", "pixel-upscale 2 2;", "pixel-upscale 2 xbr;", "pixel-upscale scale2x;" })]
+        fn test_pixel_upscale_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod convolve_test {
+        use super::*;
+
+        ide!();
+
+        fn sharpen_kernel() -> Kernel {
+            Kernel::new(3, vec![0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0]).unwrap()
+        }
+
+        #[parameterized(
+            input = {
+                "convolve 3 0 -1 0 -1 5 -1 0 -1 0;",
+                "convolve 3 0 -1 0 | -1 5 -1 | 0 -1 0;",
+                "convolve 1 2.5",
+                "convolve-file \"kernels/emboss.txt\";",
+                "convolve-file 'C:\\Users\\Some Name\\emboss.txt';",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Convolve(sharpen_kernel()))],
+                vec![Instr::Operation(ImgOp::Convolve(sharpen_kernel()))],
+                vec![Instr::Operation(ImgOp::Convolve(Kernel::new(1, vec![2.5]).unwrap()))],
+                vec![Instr::Operation(ImgOp::ConvolveFile(KernelFromPath::new("kernels/emboss.txt".into())))],
+                vec![Instr::Operation(ImgOp::ConvolveFile(KernelFromPath::new("C:\\Users\\Some Name\\emboss.txt".into())))],
+            }
+        )]
+        fn test_convolve_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "convolve 3;", "convolve 3 1 1;", "convolve 2 1 1 1 1;", "convolve-file;" })]
+        fn test_convolve_err(input: &str) {
+            let result = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(result.is_err());
+        }
     }
 
     #[cfg(test)]
@@ -535,6 +962,10 @@ mod tests {
                 "diff 'input.jpg';",
                 "diff 'C:/Users/Some Name/input.jpg';",
                 "diff 'C:\\Users\\Some Name\\input.jpg';",
+                "diff \"Bob's image.jpg\";",
+                "diff 'the \"original\".jpg';",
+                "diff 'Bob\\'s \"original\".jpg';",
+                "diff 'C:\\Users\\\\';",
             },
             expected_ops = {
                 vec![Instr::Operation(ImgOp::Diff(ImageFromPath::new("/my/path/input.jpg".into())))],
@@ -545,13 +976,17 @@ mod tests {
                 vec![Instr::Operation(ImgOp::Diff(ImageFromPath::new("input.jpg".into())))],
                 vec![Instr::Operation(ImgOp::Diff(ImageFromPath::new("C:/Users/Some Name/input.jpg".into())))],
                 vec![Instr::Operation(ImgOp::Diff(ImageFromPath::new("C:\\Users\\Some Name\\input.jpg".into())))],
+                vec![Instr::Operation(ImgOp::Diff(ImageFromPath::new("Bob's image.jpg".into())))],
+                vec![Instr::Operation(ImgOp::Diff(ImageFromPath::new("the \"original\".jpg".into())))],
+                vec![Instr::Operation(ImgOp::Diff(ImageFromPath::new("Bob's \"original\".jpg".into())))],
+                vec![Instr::Operation(ImgOp::Diff(ImageFromPath::new("C:\\Users\\".into())))],
             }
         )]
         fn test_diff_ok(input: &str, expected_ops: Vec<Instr>) {
-            let pairs = SICParser::parse(Rule::main, input)
+            let statements = parse_statements(input)
                 .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
 
-            assert_eq!(parse_image_operations(pairs).unwrap(), expected_ops);
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
         }
 
         #[parameterized(
@@ -561,120 +996,204 @@ mod tests {
             }
         )]
         fn test_diff_err(input: &str) {
-            let pairs = SICParser::parse(Rule::main, input);
-            assert!(pairs.is_err());
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
         }
     }
 
-    #[test]
-    fn test_contrast_single_stmt_parse_fail_max_f32_1() {
-        let pairs = SICParser::parse(Rule::main, "340282200000000000000000000000000000000.0;");
-        assert!(pairs.is_err());
-    }
+    #[cfg(test)]
+    mod stack_test {
+        use super::*;
 
-    #[test]
-    fn test_brighten_pos_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "brighten 3579;")
-            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
-        assert_eq!(
-            vec![Instr::Operation(ImgOp::Brighten(3579))],
-            parse_image_operations(pairs).unwrap()
-        );
-    }
+        ide!();
 
-    #[test]
-    fn test_brighten_neg_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "brighten -3579;")
-            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
-        assert_eq!(
-            vec![Instr::Operation(ImgOp::Brighten(-3579))],
-            parse_image_operations(pairs).unwrap()
-        );
-    }
+        #[parameterized(
+            input = {
+                "hstack 'after.png';",
+                "hstack \"C:/Users/Some Name/after.png\";",
+                "vstack 'after.png';",
+                "HSTACK 'a.png'; vstack 'b.png';",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Hstack(ImageFromPath::new("after.png".into())))],
+                vec![Instr::Operation(ImgOp::Hstack(ImageFromPath::new("C:/Users/Some Name/after.png".into())))],
+                vec![Instr::Operation(ImgOp::Vstack(ImageFromPath::new("after.png".into())))],
+                vec![
+                    Instr::Operation(ImgOp::Hstack(ImageFromPath::new("a.png".into()))),
+                    Instr::Operation(ImgOp::Vstack(ImageFromPath::new("b.png".into()))),
+                ],
+            }
+        )]
+        fn test_stack_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
 
-    #[test]
-    #[should_panic]
-    fn test_filter3x3_triplets_f3_with_end_triplet_sep_fail() {
-        SICParser::parse(Rule::main, "filter3x3 0 0 0 | 1 1 1 | 2 2 2 |")
-            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(
+            input = {
+                "hstack;",
+                "hstack after.png;",
+                "vstack 'a.png' 'b.png';",
+            }
+        )]
+        fn test_stack_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
     }
 
-    #[test]
-    fn test_filter3x3_triplets_f3_no_end_triplet_sep() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "filter3x3 0 0.1 0.2 | 1.3 1.4 1.5 | 2.6 2.7 2.8",
-        )
-        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+    #[cfg(test)]
+    mod lut_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "lut \"/my/path/look.cube\";",
+                "lut 'C:\\Users\\Some Name\\look.cube';",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Lut(LutFromPath::new("/my/path/look.cube".into())))],
+                vec![Instr::Operation(ImgOp::Lut(LutFromPath::new("C:\\Users\\Some Name\\look.cube".into())))],
+            }
+        )]
+        fn test_lut_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[test]
+        fn test_lut_without_path() {
+            let statements = parse_statements("lut;").and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[test]
+    fn test_contrast_single_stmt_parse_fail_max_f32_1() {
+        let statements = parse_statements("340282200000000000000000000000000000000.0;")
+            .and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[test]
+    fn test_brighten_pos_single_stmt_parse_correct() {
+        let statements = parse_statements("brighten 3579;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Brighten(3579))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_brighten_explicit_pos_single_stmt_parse_correct() {
+        let statements = parse_statements("brighten +10;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Brighten(10))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_brighten_neg_single_stmt_parse_correct() {
+        let statements = parse_statements("brighten -3579;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Brighten(-3579))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_filter3x3_triplets_f3_with_end_triplet_sep_fail() {
+        parse_statements("filter3x3 0 0 0 | 1 1 1 | 2 2 2 |")
+            .and_then(parse_image_operations)
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+    }
+
+    #[test]
+    fn test_filter3x3_triplets_f3_no_end_triplet_sep() {
+        let statements = parse_statements("filter3x3 0 0.1 0.2 | 1.3 1.4 1.5 | 2.6 2.7 2.8")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Filter3x3([
                 0.0, 0.1, 0.2, 1.3, 1.4, 1.5, 2.6, 2.7, 2.8
             ]))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_filter3x3_triplets_f3_ensure_f32() {
-        let pairs = SICParser::parse(Rule::main, "filter3x3 0 0 0 | 1 1 1 | 2 2 2")
+        let statements = parse_statements("filter3x3 0 0 0 | 1 1 1 | 2 2 2")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Filter3x3([
                 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0
             ]))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_filter3x3_triplets_f3_no_sep() {
-        let pairs = SICParser::parse(Rule::main, "filter3x3 0 0 0 1 1 1 2 2 3.0")
+        let statements = parse_statements("filter3x3 0 0 0 1 1 1 2 2 3.0")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Filter3x3([
                 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 3.0
             ]))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_filter3x3_triplets_f3_end_op_sep() {
-        let pairs = SICParser::parse(Rule::main, "filter3x3 0 0 0 1 1 1 2 2 3.0;")
+        let statements = parse_statements("filter3x3 0 0 0 1 1 1 2 2 3.0;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::Filter3x3([
                 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 3.0
             ]))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_filter3x3_triplets_f3_sep_newline() {
-        let pairs = SICParser::parse(Rule::main, "filter3x3\n0 0 0\n1 1 1\n2 2 3.0;");
+        let statements =
+            parse_statements("filter3x3\n0 0 0\n1 1 1\n2 2 3.0;").and_then(parse_image_operations);
 
-        assert!(pairs.is_err())
+        assert!(statements.is_err())
     }
 
     #[test]
     fn test_filter3x3_triplets_f3_tabbed_spacing() {
-        let pairs = SICParser::parse(Rule::main, "filter3x3 0 0 0\t1 1 1\t2 2 3;");
+        let statements =
+            parse_statements("filter3x3 0 0 0\t1 1 1\t2 2 3;").and_then(parse_image_operations);
 
-        assert!(pairs.is_err())
+        assert!(statements.is_err())
     }
 
     #[test]
     fn test_filter3x3_triplets_f3_indented_newlines() {
-        let pairs = SICParser::parse(Rule::main, "filter3x3\n\t0 0 0\n\t1 1 1\n\t2 2 3");
+        let statements = parse_statements("filter3x3\n\t0 0 0\n\t1 1 1\n\t2 2 3")
+            .and_then(parse_image_operations);
 
-        assert!(pairs.is_err())
+        assert!(statements.is_err())
     }
 
     #[test]
     fn test_filter3x3_duo_filter3x3() {
-        let pairs = SICParser::parse(
-            Rule::main,
+        let statements = parse_statements(
             "filter3x3 1.9 2 3 | 4 5.9 6 | 7 8 9.9;\nfilter3x3 10.9 2 3 4 11.9 6 7 8 12.9",
         )
         .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
@@ -688,470 +1207,1446 @@ mod tests {
                     10.9, 2.0, 3.0, 4.0, 11.9, 6.0, 7.0, 8.0, 12.9
                 ])),
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_triplets_f3_require_spacing_on_triplet_sep_1() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 | 1 1.1 1|2.0 2 2")
+        parse_statements("filter3x3 0 0.9 0 | 1 1.1 1|2.0 2 2")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_triplets_f3_require_spacing_on_triplet_sep_2() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 | 1 1.1 1 |2.0 2 2")
+        parse_statements("filter3x3 0 0.9 0 | 1 1.1 1 |2.0 2 2")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_triplets_f3_require_spacing_on_triplet_sep_3() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 | 1 1.1 1| 2.0 2 2")
+        parse_statements("filter3x3 0 0.9 0 | 1 1.1 1| 2.0 2 2")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_triplets_f3_require_spacing_on_triplet_sep_end_fail_1() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 | 1 1.1 1 | 2.0 2 2|")
+        parse_statements("filter3x3 0 0.9 0 | 1 1.1 1 | 2.0 2 2|")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_triplets_f3_require_spacing_on_triplet_sep_end_fail_2() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 | 1 1.1 1 | 2.0 2 2 | ")
+        parse_statements("filter3x3 0 0.9 0 | 1 1.1 1 | 2.0 2 2 | ")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_triplets_f3_require_all_triplet_sep_1() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 1 1.1 1 | 2.0 2 2 | ")
+        parse_statements("filter3x3 0 0.9 0 1 1.1 1 | 2.0 2 2 | ")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_triplets_f3_require_all_triplet_sep_2() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 | 1 1.1 1 2.0 2 2 | ")
+        parse_statements("filter3x3 0 0.9 0 | 1 1.1 1 2.0 2 2 | ")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_insufficient_args() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 | 1 1.1 1 999 | 2.0 2 2 | ")
+        parse_statements("filter3x3 0 0.9 0 | 1 1.1 1 999 | 2.0 2 2 | ")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_insufficient_triplet_count_4() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 | 1 2.2 3 | 2.0 2 2 | 0 1 2")
+        parse_statements("filter3x3 0 0.9 0 | 1 2.2 3 | 2.0 2 2 | 0 1 2")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     #[should_panic]
     fn test_filter3x3_insufficient_triplet_count_2() {
-        SICParser::parse(Rule::main, "filter3x3 0 0.9 0 | 1 2.2 3")
+        parse_statements("filter3x3 0 0.9 0 | 1 2.2 3")
+            .and_then(parse_image_operations)
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
     }
 
     #[test]
     fn test_flip_horizontal_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "flip-horizontal;")
+        let statements = parse_statements("flip-horizontal;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::FlipHorizontal)],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_flip_horizontal_removed() {
-        let pairs = SICParser::parse(Rule::main, "flip_horizontal;");
+        let statements = parse_statements("flip_horizontal;").and_then(parse_image_operations);
 
-        assert!(pairs.is_err());
+        assert!(statements.is_err());
     }
 
     #[test]
     fn test_flip_vertical_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "flip-vertical;")
+        let statements = parse_statements("flip-vertical;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::FlipVertical)],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_flip_vertical_removed() {
-        let pairs = SICParser::parse(Rule::main, "flip_vertical;");
+        let statements = parse_statements("flip_vertical;").and_then(parse_image_operations);
 
-        assert!(pairs.is_err());
+        assert!(statements.is_err());
     }
 
     #[test]
     fn test_hue_rotate_pos_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "hue-rotate 3579;")
+        let statements = parse_statements("hue-rotate 3579;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::HueRotate(3579))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_hue_rotate_neg_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "hue-rotate -3579;")
+        let statements = parse_statements("hue-rotate -3579;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![Instr::Operation(ImgOp::HueRotate(-3579))],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_invert_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "invert;")
+    fn test_auto_enhance_single_stmt_parse_correct() {
+        let statements = parse_statements("auto-enhance;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![Instr::Operation(ImgOp::Invert)],
-            parse_image_operations(pairs).unwrap()
+            vec![Instr::Operation(ImgOp::AutoEnhance)],
+            parse_image_operations(statements).unwrap()
         );
     }
 
-    #[cfg(test)]
-    mod overlay_test {
-        use super::*;
-
-        ide!();
-
-        #[parameterized(
-            input = {
-                "overlay \"/my/path/input.jpg\" 0 0;",
-                "overlay \"/my/path/input.jpg\" 10 5;",
-                "overlay \"input.jpg\" 0 0;",
-                "overlay \"C:/Users/Some Name/input.jpg\" 0 0;",
-                "overlay \"C:\\Users\\Some Name\\input.jpg\" 0 0;",
-                "overlay '/my/path/input.jpg' 0 0;",
-                "overlay 'input.jpg' 0 0;",
-                "overlay 'C:/Users/Some Name/input.jpg' 0 0;",
-                "overlay 'C:\\Users\\Some Name\\input.jpg' 0 0;",
-            },
-            expected_ops = {
-                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("/my/path/input.jpg".into()), (0, 0))))],
-                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("/my/path/input.jpg".into()), (10, 5))))],
-                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("input.jpg".into()), (0, 0))))],
-                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("C:/Users/Some Name/input.jpg".into()), (0, 0))))],
-                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("C:\\Users\\Some Name\\input.jpg".into()), (0, 0))))],
-                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("/my/path/input.jpg".into()), (0, 0))))],
-                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("input.jpg".into()), (0, 0))))],
-                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("C:/Users/Some Name/input.jpg".into()), (0, 0))))],
-                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("C:\\Users\\Some Name\\input.jpg".into()), (0, 0))))],
-            }
-        )]
-        fn test_overlay_ok(input: &str, expected_ops: Vec<Instr>) {
-            let pairs = SICParser::parse(Rule::main, input)
-                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
-
-            assert_eq!(parse_image_operations(pairs).unwrap(), expected_ops);
-        }
-
-        #[parameterized(
-            input = {
-                "overlay \"/my/path/input.jpg\"",
-                "overlay '/my/path/input.jpg' (0, 0);",
-                "overlay '/my/path/input.jpg' 0, 0;",
-                "overlay '/my/path/input.jpg' -1 0;",
-                "overlay '/my/path/input.jpg' 0 -1;",
-            }
-        )]
-        fn test_overlay_err(input: &str) {
-            let pairs = SICParser::parse(Rule::main, input);
-            assert!(pairs.is_err());
-        }
-    }
-
     #[test]
-    fn test_resize_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "resize 99 88;")
+    fn test_box_blur_single_stmt_parse_correct() {
+        let statements = parse_statements("box-blur 15;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![Instr::Operation(ImgOp::Resize((99, 88)))],
-            parse_image_operations(pairs).unwrap()
+            vec![Instr::Operation(ImgOp::BoxBlur(15))],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_rotate90_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "rotate90;")
-            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
-        assert_eq!(
-            vec![Instr::Operation(ImgOp::Rotate90)],
-            parse_image_operations(pairs).unwrap()
-        );
+    fn test_box_blur_requires_uint() {
+        let statements = parse_statements("box-blur -1;").and_then(parse_image_operations);
+        assert!(statements.is_err());
     }
 
     #[test]
-    fn test_rotate180_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "rotate180;")
+    fn test_local_statistics_parse_correct() {
+        let statements = parse_statements("local-mean 3;\nlocal-variance 4;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![Instr::Operation(ImgOp::Rotate180)],
-            parse_image_operations(pairs).unwrap()
+            vec![
+                Instr::Operation(ImgOp::LocalMean(3)),
+                Instr::Operation(ImgOp::LocalVariance(4))
+            ],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_rotate270_single_stmt_parse_correct() {
-        let pairs = SICParser::parse(Rule::main, "rotate270;")
+    fn test_chroma_key_single_stmt_parse_correct() {
+        use sic_core::image::Rgba;
+
+        let statements = parse_statements("chroma-key rgba(0, 255, 0, 255) 60 12.5;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![Instr::Operation(ImgOp::Rotate270)],
-            parse_image_operations(pairs).unwrap()
+            vec![Instr::Operation(ImgOp::ChromaKey(ChromaKeyInputs::new(
+                Rgba([0, 255, 0, 255]),
+                60.0,
+                12.5
+            )))],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_unsharpen_single_stmt_parse_correct_ints() {
-        let pairs = SICParser::parse(Rule::main, "unsharpen 99 88;")
+    fn test_chroma_key_requires_rgba() {
+        let statements = parse_statements("chroma-key size(10) 60 10;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
-        assert_eq!(
-            vec![Instr::Operation(ImgOp::Unsharpen((99.0, 88)))],
-            parse_image_operations(pairs).unwrap()
-        );
+        assert!(parse_image_operations(statements).is_err());
     }
 
     #[test]
-    fn test_unsharpen_single_stmt_parse_correct_fp_int() {
-        let pairs = SICParser::parse(Rule::main, "unsharpen 99.0 88;")
-            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
-        assert_eq!(
-            vec![Instr::Operation(ImgOp::Unsharpen((99.0, 88)))],
-            parse_image_operations(pairs).unwrap()
-        );
+    fn test_chroma_key_requires_feather() {
+        let statements = parse_statements("chroma-key rgba(0, 255, 0, 255) 60;")
+            .and_then(parse_image_operations);
+        assert!(statements.is_err());
     }
 
     #[test]
-    fn test_unsharpen_single_stmt_parse_correct_fp_int_neg() {
-        let pairs = SICParser::parse(Rule::main, "unsharpen -99.0 -88;")
+    fn test_contours_single_stmt_parse_correct() {
+        use sic_core::image::Rgba;
+
+        let statements = parse_statements("contours 128; contours 60 rgba(255, 0, 0, 255);")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![Instr::Operation(ImgOp::Unsharpen((-99.0, -88)))],
-            parse_image_operations(pairs).unwrap()
+            vec![
+                Instr::Operation(ImgOp::Contours(ContoursInputs::new(
+                    128,
+                    Rgba([0, 255, 0, 255])
+                ))),
+                Instr::Operation(ImgOp::Contours(ContoursInputs::new(
+                    60,
+                    Rgba([255, 0, 0, 255])
+                )))
+            ],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_unsharpen_single_stmt_parse_correct_fp_fp_fail() {
-        let pairs = SICParser::parse(Rule::main, "unsharpen -99.0 -88.0;");
-        assert!(pairs.is_err());
+    fn test_contours_requires_threshold() {
+        let statements = parse_statements("contours;").and_then(parse_image_operations);
+        assert!(statements.is_err());
     }
 
     #[test]
-    fn test_multi_stmt_parse_correct() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "blur 10;flip-horizontal;flip-vertical;resize 100 200;",
-        )
-        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+    fn test_contours_threshold_out_of_range() {
+        let statements = parse_statements("contours 256;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert!(parse_image_operations(statements).is_err());
+    }
+
+    #[test]
+    fn test_dither_single_stmt_parse_correct() {
+        let statements = parse_statements("dither 16;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![
-                Instr::Operation(ImgOp::Blur(10.0)),
-                Instr::Operation(ImgOp::FlipHorizontal),
-                Instr::Operation(ImgOp::FlipVertical),
-                Instr::Operation(ImgOp::Resize((100, 200)))
-            ],
-            parse_image_operations(pairs).unwrap()
+            vec![Instr::Operation(ImgOp::Dither(16))],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_multi_stmt_parse_diff_order_correct() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "flip-horizontal;flip-vertical;resize 100 200;blur 10;",
-        )
-        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+    fn test_draw_histogram_single_stmt_parse_correct() {
+        let statements = parse_statements("draw-histogram luma; draw-histogram RGB overlay;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![
-                Instr::Operation(ImgOp::FlipHorizontal),
-                Instr::Operation(ImgOp::FlipVertical),
-                Instr::Operation(ImgOp::Resize((100, 200))),
-                Instr::Operation(ImgOp::Blur(10.0))
+                Instr::Operation(ImgOp::DrawHistogram(DrawHistogramInputs::new(
+                    HistogramChannels::Luma,
+                    HistogramPlacement::Append
+                ))),
+                Instr::Operation(ImgOp::DrawHistogram(DrawHistogramInputs::new(
+                    HistogramChannels::Rgb,
+                    HistogramPlacement::Overlay
+                )))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_multi_whitespace() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "flip-horizontal; flip-vertical; resize 100 200; blur 10;",
-        )
-        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+    fn test_draw_histogram_defaults_to_luma() {
+        let statements = parse_statements("draw-histogram; draw-histogram overlay;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![
-                Instr::Operation(ImgOp::FlipHorizontal),
-                Instr::Operation(ImgOp::FlipVertical),
-                Instr::Operation(ImgOp::Resize((100, 200))),
-                Instr::Operation(ImgOp::Blur(10.0))
+                Instr::Operation(ImgOp::DrawHistogram(DrawHistogramInputs::new(
+                    HistogramChannels::Luma,
+                    HistogramPlacement::Append
+                ))),
+                Instr::Operation(ImgOp::DrawHistogram(DrawHistogramInputs::new(
+                    HistogramChannels::Luma,
+                    HistogramPlacement::Overlay
+                )))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_multi_whitespace_2() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "flip-horizontal    ; flip-vertical   ;      resize 100 200; blur 10;",
-        )
-        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+    fn test_draw_histogram_requires_known_values() {
+        for input in &[
+            "draw-histogram rgba;",
+            "draw-histogram luma below;",
+            "draw-histogram overlay luma;",
+        ] {
+            assert!(parse_statements(input)
+                .and_then(parse_image_operations)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_duotone_single_stmt_parse_correct() {
+        let statements = parse_statements("duotone rgba(0, 0, 128, 255) rgba(255, 200, 0, 255);")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![
-                Instr::Operation(ImgOp::FlipHorizontal),
-                Instr::Operation(ImgOp::FlipVertical),
-                Instr::Operation(ImgOp::Resize((100, 200))),
-                Instr::Operation(ImgOp::Blur(10.0))
-            ],
-            parse_image_operations(pairs).unwrap()
+            vec![Instr::Operation(ImgOp::Duotone((
+                Rgba([0, 0, 128, 255]),
+                Rgba([255, 200, 0, 255])
+            )))],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_multi_whitespace_3() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "flip-horizontal;\nflip-vertical;\nresize 100 200;\nblur 10;",
-        )
-        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+    fn test_duotone_requires_two_colors() {
+        let statements =
+            parse_statements("duotone rgba(0, 0, 128, 255);").and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[test]
+    fn test_dither_requires_uint() {
+        let statements = parse_statements("dither -4;").and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[test]
+    fn test_equalize_single_stmt_parse_correct() {
+        let statements = parse_statements("equalize;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![
-                Instr::Operation(ImgOp::FlipHorizontal),
-                Instr::Operation(ImgOp::FlipVertical),
-                Instr::Operation(ImgOp::Resize((100, 200))),
-                Instr::Operation(ImgOp::Blur(10.0))
-            ],
-            parse_image_operations(pairs).unwrap()
+            vec![Instr::Operation(ImgOp::Equalize)],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_multi_should_no_longer_end_with_sep() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "flip-horizontal; flip-vertical; resize 100 200; blur 10",
-        )
-        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+    fn test_flatten_checker_single_stmt_parse_correct() {
+        let statements = parse_statements("flatten-checker 8;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![
-                Instr::Operation(ImgOp::FlipHorizontal),
-                Instr::Operation(ImgOp::FlipVertical),
-                Instr::Operation(ImgOp::Resize((100, 200))),
-                Instr::Operation(ImgOp::Blur(10.0))
-            ],
-            parse_image_operations(pairs).unwrap()
+            vec![Instr::Operation(ImgOp::FlattenChecker(8))],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_multi_sep() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "flip-horizontal; flip-vertical;  resize 100 200;\nblur 10",
+    fn test_flatten_checker_requires_uint() {
+        let statements = parse_statements("flatten-checker 1.5;").and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[test]
+    fn test_gradient_single_stmt_parse_correct() {
+        use sic_image_engine::wrapper::gradient_fill::GradientKind;
+
+        let statements = parse_statements(
+            "gradient linear rgba(0, 0, 0, 255) rgba(255, 255, 255, 255) 90;\
+             gradient RADIAL rgba(0, 0, 0, 0) rgba(0, 0, 0, 200);",
         )
         .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
             vec![
-                Instr::Operation(ImgOp::FlipHorizontal),
-                Instr::Operation(ImgOp::FlipVertical),
-                Instr::Operation(ImgOp::Resize((100, 200))),
-                Instr::Operation(ImgOp::Blur(10.0))
+                Instr::Operation(ImgOp::Gradient(GradientFillInputs::new(
+                    GradientKind::Linear,
+                    (Rgba([0, 0, 0, 255]), Rgba([255, 255, 255, 255])),
+                    90.0
+                ))),
+                Instr::Operation(ImgOp::Gradient(GradientFillInputs::new(
+                    GradientKind::Radial,
+                    (Rgba([0, 0, 0, 0]), Rgba([0, 0, 0, 200])),
+                    0.0
+                ))),
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_parse_setopt_resize_sampling_filter_catmullrom() {
-        let pairs = SICParser::parse(Rule::main, "set sampling-filter CatmullRom;")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
-
-        assert_eq!(
-            vec![Instr::EnvAdd(EnvItem::CustomSamplingFilter(
-                FilterTypeWrap::new(FilterType::CatmullRom)
-            ))],
-            parse_image_operations(pairs).unwrap()
-        );
+    fn test_gradient_requires_two_colors() {
+        for input in &[
+            "gradient linear rgba(0, 0, 0, 255);",
+            "gradient conic rgba(0, 0, 0, 255) rgba(255, 255, 255, 255);",
+        ] {
+            assert!(parse_statements(input)
+                .and_then(parse_image_operations)
+                .is_err());
+        }
     }
 
     #[test]
-    fn test_parse_setopt_resize_sampling_filter_gaussian() {
-        let pairs = SICParser::parse(Rule::main, "set sampling-filter GAUSSIAN;")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
-
-        assert_eq!(
-            vec![Instr::EnvAdd(EnvItem::CustomSamplingFilter(
-                FilterTypeWrap::new(FilterType::Gaussian)
-            )),],
-            parse_image_operations(pairs).unwrap()
-        );
+    fn test_gradient_radial_has_no_angle() {
+        let statements =
+            parse_statements("gradient radial rgba(0, 0, 0, 255) rgba(255, 255, 255, 255) 45;")
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert!(parse_image_operations(statements).is_err());
     }
 
     #[test]
-    fn test_parse_setopt_resize_sampling_filter_lanczos3() {
-        let pairs = SICParser::parse(Rule::main, "set sampling-filter Lanczos3;")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
+    fn test_gradient_map_single_stmt_parse_correct() {
+        use sic_image_engine::wrapper::gradient::ColorStop;
 
+        let statements = parse_statements("gradient-map stop(0, rgba(0, 0, 0, 255)) stop(0.5,rgba(255,0,0,255)) stop(1, rgba(255, 255, 0, 255));",
+        )
+        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            vec![Instr::EnvAdd(EnvItem::CustomSamplingFilter(
-                FilterTypeWrap::new(FilterType::Lanczos3)
-            )),],
-            parse_image_operations(pairs).unwrap()
+            vec![Instr::Operation(ImgOp::GradientMap(Gradient::new(vec![
+                ColorStop::new(0.0, Rgba([0, 0, 0, 255])),
+                ColorStop::new(0.5, Rgba([255, 0, 0, 255])),
+                ColorStop::new(1.0, Rgba([255, 255, 0, 255])),
+            ])))],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_parse_setopt_resize_sampling_filter_nearest() {
-        let pairs = SICParser::parse(Rule::main, "set sampling-filter nearest;")
-            .unwrap_or_else(|e| panic!("error: {:?}", e));
+    fn test_gradient_map_followed_by_op() {
+        let statements = parse_statements(
+            "gradient-map stop(0, rgba(0, 0, 0, 255)) stop(1, rgba(255, 255, 0, 255));\ninvert;",
+        )
+        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        let instructions = parse_image_operations(statements).unwrap();
 
-        assert_eq!(
-            vec![Instr::EnvAdd(EnvItem::CustomSamplingFilter(
-                FilterTypeWrap::new(FilterType::Nearest)
-            )),],
-            parse_image_operations(pairs).unwrap()
-        );
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[1], Instr::Operation(ImgOp::Invert));
+    }
+
+    #[test]
+    fn test_gradient_map_requires_two_stops() {
+        let statements = parse_statements("gradient-map stop(0, rgba(0, 0, 0, 255));")
+            .and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[test]
+    fn test_gradient_map_requires_stops() {
+        let statements =
+            parse_statements("gradient-map rgba(0, 0, 0, 255) rgba(255, 255, 255, 255);")
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert!(parse_image_operations(statements).is_err());
+    }
+
+    #[test]
+    fn test_invert_single_stmt_parse_correct() {
+        let statements = parse_statements("invert;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Invert)],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[cfg(test)]
+    mod overlay_test {
+        use super::*;
+        use sic_image_engine::wrapper::blend_mode::BlendMode;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "overlay \"/my/path/input.jpg\" 0 0;",
+                "overlay \"/my/path/input.jpg\" 10 5;",
+                "overlay \"input.jpg\" 0 0;",
+                "overlay \"C:/Users/Some Name/input.jpg\" 0 0;",
+                "overlay \"C:\\Users\\Some Name\\input.jpg\" 0 0;",
+                "overlay '/my/path/input.jpg' 0 0;",
+                "overlay 'input.jpg' 0 0;",
+                "overlay 'C:/Users/Some Name/input.jpg' 0 0;",
+                "overlay 'C:\\Users\\Some Name\\input.jpg' 0 0;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("/my/path/input.jpg".into()), (0, 0))))],
+                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("/my/path/input.jpg".into()), (10, 5))))],
+                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("input.jpg".into()), (0, 0))))],
+                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("C:/Users/Some Name/input.jpg".into()), (0, 0))))],
+                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("C:\\Users\\Some Name\\input.jpg".into()), (0, 0))))],
+                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("/my/path/input.jpg".into()), (0, 0))))],
+                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("input.jpg".into()), (0, 0))))],
+                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("C:/Users/Some Name/input.jpg".into()), (0, 0))))],
+                vec![Instr::Operation(ImgOp::Overlay(OverlayInputs::new(ImageFromPath::new("C:\\Users\\Some Name\\input.jpg".into()), (0, 0))))],
+            }
+        )]
+        fn test_overlay_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(
+            input = {
+                "overlay 'input.jpg' 1 2 0.5;",
+                "overlay 'input.jpg' 1 2 multiply;",
+                "overlay 'input.jpg' 1 2 0.75 overlay;",
+                "overlay 'input.jpg' 1 2 1 Lighten;",
+            },
+            expected = {
+                OverlayInputs::new(ImageFromPath::new("input.jpg".into()), (1, 2)).with_opacity(0.5),
+                OverlayInputs::new(ImageFromPath::new("input.jpg".into()), (1, 2)).with_blend_mode(BlendMode::Multiply),
+                OverlayInputs::new(ImageFromPath::new("input.jpg".into()), (1, 2)).with_opacity(0.75).with_blend_mode(BlendMode::Overlay),
+                OverlayInputs::new(ImageFromPath::new("input.jpg".into()), (1, 2)).with_blend_mode(BlendMode::Lighten),
+            }
+        )]
+        fn test_overlay_with_opacity_and_blend_mode_ok(input: &str, expected: OverlayInputs) {
+            let expected_ops = vec![Instr::Operation(ImgOp::Overlay(expected))];
+
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(
+            input = {
+                "overlay \"/my/path/input.jpg\"",
+                "overlay '/my/path/input.jpg' (0, 0);",
+                "overlay '/my/path/input.jpg' 0, 0;",
+                "overlay '/my/path/input.jpg' -1 0;",
+                "overlay '/my/path/input.jpg' 0 -1;",
+                "overlay '/my/path/input.jpg' 0 0 add;",
+                "overlay '/my/path/input.jpg' 0 0 screen 0.5;",
+            }
+        )]
+        fn test_overlay_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod alpha_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "alpha-add;",
+                "alpha-remove;",
+                "alpha-premultiply;",
+                "alpha-threshold 128;",
+                "alpha-add;\nalpha-threshold 0;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::AlphaAdd)],
+                vec![Instr::Operation(ImgOp::AlphaRemove)],
+                vec![Instr::Operation(ImgOp::AlphaPremultiply)],
+                vec![Instr::Operation(ImgOp::AlphaThreshold(128))],
+                vec![Instr::Operation(ImgOp::AlphaAdd), Instr::Operation(ImgOp::AlphaThreshold(0))],
+            }
+        )]
+        fn test_alpha_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(
+            input = {
+                "alpha-add 1;",
+                "alpha-threshold;",
+                "alpha-threshold -1;",
+                "alpha-threshold 0.5;",
+            }
+        )]
+        fn test_alpha_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+
+        #[test]
+        fn test_alpha_threshold_out_of_range() {
+            let statements = parse_statements("alpha-threshold 256;").unwrap();
+            assert!(parse_image_operations(statements).is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod mirror_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "mirror horizontal;",
+                "mirror vertical",
+                "MIRROR Quad;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Mirror(MirrorMode::Horizontal))],
+                vec![Instr::Operation(ImgOp::Mirror(MirrorMode::Vertical))],
+                vec![Instr::Operation(ImgOp::Mirror(MirrorMode::Quad))],
+            }
+        )]
+        fn test_mirror_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "mirror;", "mirror diagonal;", "mirror horizontal vertical;" })]
+        fn test_mirror_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod nine_slice_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "nine-slice 8 8 8 8 200 100;",
+                "nine-slice 0 4 12 16 64 64",
+                "NINE-SLICE 1 2 3 4 5 6;",
+            },
+            expected = {
+                NineSliceInputs::new((8, 8, 8, 8), (200, 100)),
+                NineSliceInputs::new((0, 4, 12, 16), (64, 64)),
+                NineSliceInputs::new((1, 2, 3, 4), (5, 6)),
+            }
+        )]
+        fn test_nine_slice_ok(input: &str, expected: NineSliceInputs) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::NineSlice(expected))]
+            );
+        }
+
+        #[parameterized(input = {
+            "nine-slice 8 8 8 8 200;",
+            "nine-slice 8 8 8 8 200 100 1;",
+            "nine-slice 8 8 -8 8 200 100;",
+            "nine-slice 8 8 8 8 200.5 100;",
+            "nine-slice;",
+        })]
+        fn test_nine_slice_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod oil_paint_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "oil-paint 3 20;",
+                "oil-paint 0 1",
+                "OIL-PAINT 5 256;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::OilPaint((3, 20)))],
+                vec![Instr::Operation(ImgOp::OilPaint((0, 1)))],
+                vec![Instr::Operation(ImgOp::OilPaint((5, 256)))],
+            }
+        )]
+        fn test_oil_paint_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "oil-paint 3;", "oil-paint -1 20;", "oil-paint 3 2.5;", "oil-paint;" })]
+        fn test_oil_paint_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod opacity_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "opacity 0.5;",
+                "opacity 1;",
+                "alpha-remove;\nopacity 0;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Opacity(0.5))],
+                vec![Instr::Operation(ImgOp::Opacity(1.0))],
+                vec![Instr::Operation(ImgOp::AlphaRemove), Instr::Operation(ImgOp::Opacity(0.0))],
+            }
+        )]
+        fn test_opacity_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "opacity;", "opacity a;", "opacity 0.5 0.5;" })]
+        fn test_opacity_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod grayscale_weighted_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "grayscale-weighted 0.2126 0.7152 0.0722;",
+                "grayscale-weighted 1 1 1;",
+                "grayscale;\ngrayscale-weighted 0 1 0;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::GrayscaleWeighted((0.2126, 0.7152, 0.0722)))],
+                vec![Instr::Operation(ImgOp::GrayscaleWeighted((1.0, 1.0, 1.0)))],
+                vec![Instr::Operation(ImgOp::GrayScale), Instr::Operation(ImgOp::GrayscaleWeighted((0.0, 1.0, 0.0)))],
+            }
+        )]
+        fn test_grayscale_weighted_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = {
+            "grayscale-weighted;",
+            "grayscale-weighted 1 1;",
+            "grayscale-weighted 1 1 a;",
+            "grayscale-weighted 1 1 1 1;",
+        })]
+        fn test_grayscale_weighted_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod channel_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "channel-extract g;",
+                "channel-extract Alpha;",
+                "channel-swap r b;",
+                "channel-swap green a;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::ChannelExtract(Channel::Green))],
+                vec![Instr::Operation(ImgOp::ChannelExtract(Channel::Alpha))],
+                vec![Instr::Operation(ImgOp::ChannelSwap((Channel::Red, Channel::Blue)))],
+                vec![Instr::Operation(ImgOp::ChannelSwap((Channel::Green, Channel::Alpha)))],
+            }
+        )]
+        fn test_channel_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(
+            input = {
+                "channel-extract;",
+                "channel-extract x;",
+                "channel-extract rg;",
+                "channel-swap r;",
+                "channel-swap r b g;",
+            }
+        )]
+        fn test_channel_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod fisheye_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "fisheye 0.5;",
+                "fisheye -1",
+                "FISHEYE 0;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Fisheye(0.5))],
+                vec![Instr::Operation(ImgOp::Fisheye(-1.0))],
+                vec![Instr::Operation(ImgOp::Fisheye(0.0))],
+            }
+        )]
+        fn test_fisheye_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "fisheye;", "fisheye a;", "fisheye 0.5 1;" })]
+        fn test_fisheye_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod radial_blur_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "radial-blur 10 20 0.5;",
+                "radial-blur 0 0 1",
+                "RADIAL-BLUR 10 20 0;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::RadialBlur((10, 20, 0.5)))],
+                vec![Instr::Operation(ImgOp::RadialBlur((0, 0, 1.0)))],
+                vec![Instr::Operation(ImgOp::RadialBlur((10, 20, 0.0)))],
+            }
+        )]
+        fn test_radial_blur_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "radial-blur 10 20;", "radial-blur -1 20 0.5;", "radial-blur 1.5 20 0.5;", "radial-blur;" })]
+        fn test_radial_blur_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod redact_test {
+        use super::*;
+        use sic_image_engine::wrapper::redact::RedactStyle;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "redact rect(10, 20, 30, 40);",
+                "redact rect(0, 0, 8, 8) pixelate",
+                "REDACT rect(10, 20, 30, 40) Fill;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Redact(RedactInputs::new(Region::new((10, 20), (30, 40)), RedactStyle::Fill)))],
+                vec![Instr::Operation(ImgOp::Redact(RedactInputs::new(Region::new((0, 0), (8, 8)), RedactStyle::Pixelate)))],
+                vec![Instr::Operation(ImgOp::Redact(RedactInputs::new(Region::new((10, 20), (30, 40)), RedactStyle::Fill)))],
+            }
+        )]
+        fn test_redact_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "redact;", "redact rect(10, 20, 30, 40) blur;", "redact 10 20 30 40;" })]
+        fn test_redact_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+
+        #[test]
+        fn test_redact_not_a_rect() {
+            let statements = parse_statements("redact coord(10, 20);").unwrap();
+            assert!(parse_image_operations(statements).is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod sharpen_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "sharpen 0.5;",
+                "sharpen;",
+                "sharpen;\nsharpen 2",
+                "sharpen; invert",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Sharpen(0.5))],
+                vec![Instr::Operation(ImgOp::Sharpen(1.0))],
+                vec![Instr::Operation(ImgOp::Sharpen(1.0)), Instr::Operation(ImgOp::Sharpen(2.0))],
+                vec![Instr::Operation(ImgOp::Sharpen(1.0)), Instr::Operation(ImgOp::Invert)],
+            }
+        )]
+        fn test_sharpen_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "sharpen a;", "sharpen 1 1;", "sharpen1;" })]
+        fn test_sharpen_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod smart_crop_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = { "smart-crop 100 50;", "SMART-CROP 1 1" },
+            expected = { (100, 50), (1, 1) }
+        )]
+        fn test_smart_crop_ok(input: &str, expected: (u32, u32)) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::SmartCrop(expected))]
+            );
+        }
+
+        #[parameterized(input = { "smart-crop 100;", "smart-crop -100 50;", "smart-crop 1.5 2;" })]
+        fn test_smart_crop_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod trim_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "trim 10;",
+                "trim;",
+                "TRIM 2.5",
+                "trim; invert",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Trim(10.0))],
+                vec![Instr::Operation(ImgOp::Trim(0.0))],
+                vec![Instr::Operation(ImgOp::Trim(2.5))],
+                vec![Instr::Operation(ImgOp::Trim(0.0)), Instr::Operation(ImgOp::Invert)],
+            }
+        )]
+        fn test_trim_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "trim a;", "trim 1 1;", "trim5;" })]
+        fn test_trim_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    #[cfg(test)]
+    mod stamp_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "stamp \"signature.png\" bottom-right 10;",
+                "stamp 'C:/Users/Some Name/logo.png' top-left 0;",
+                "stamp 'logo.png' CENTER 5;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Stamp(StampInputs::new(ImageFromPath::new("signature.png".into()), Gravity::BottomRight, 10)))],
+                vec![Instr::Operation(ImgOp::Stamp(StampInputs::new(ImageFromPath::new("C:/Users/Some Name/logo.png".into()), Gravity::TopLeft, 0)))],
+                vec![Instr::Operation(ImgOp::Stamp(StampInputs::new(ImageFromPath::new("logo.png".into()), Gravity::Center, 5)))],
+            }
+        )]
+        fn test_stamp_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(
+            input = {
+                "stamp 'logo.png';",
+                "stamp 'logo.png' 10;",
+                "stamp 'logo.png' up 10;",
+                "stamp 'logo.png' top -1;",
+                "stamp logo.png top 1;",
+            }
+        )]
+        fn test_stamp_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    mod tonemap_test {
+        use super::*;
+        use sic_image_engine::wrapper::tonemap::ToneMapOperator;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "tonemap reinhard;",
+                "tonemap ACES -1.5;",
+                "tonemap aces 2",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Tonemap(ToneMapInputs::new(ToneMapOperator::Reinhard, 0.0)))],
+                vec![Instr::Operation(ImgOp::Tonemap(ToneMapInputs::new(ToneMapOperator::Aces, -1.5)))],
+                vec![Instr::Operation(ImgOp::Tonemap(ToneMapInputs::new(ToneMapOperator::Aces, 2.0)))],
+            }
+        )]
+        fn test_tonemap_ok(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "tonemap;", "tonemap filmic;", "tonemap aces bright;" })]
+        fn test_tonemap_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+    }
+
+    mod find_test {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(
+            input = {
+                "find 'button.png';",
+                "FIND \"C:/Users/Some Name/button.png\"",
+                "find 'button.png' rgba(255, 0, 0, 255);",
+            },
+            expected = {
+                FindInputs::new(ImageFromPath::new("button.png".into()), None),
+                FindInputs::new(ImageFromPath::new("C:/Users/Some Name/button.png".into()), None),
+                FindInputs::new(ImageFromPath::new("button.png".into()), Some(Rgba([255, 0, 0, 255]))),
+            }
+        )]
+        fn test_find_ok(input: &str, expected: FindInputs) {
+            let statements = parse_statements(input)
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::Find(expected))]
+            );
+        }
+
+        #[parameterized(
+            input = {
+                "find;",
+                "find button.png;",
+                "find 'button.png' red;",
+                "find 'button.png' rgba(255, 0, 0, 255) 1;",
+            }
+        )]
+        fn test_find_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+            assert!(statements.is_err());
+        }
+
+        #[test]
+        fn test_find_not_a_color() {
+            let statements = parse_statements("find 'button.png' coord(1, 2);").unwrap();
+            assert!(parse_image_operations(statements).is_err());
+        }
+    }
+
+    #[test]
+    fn test_resize_percentages_parse_correct() {
+        use sic_image_engine::wrapper::dimension::Dimension::{Percentage, Pixels};
+
+        let statements = parse_statements("resize 50% 0;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Deferred(DeferredOp::Resize([
+                Percentage(50.0),
+                Pixels(0)
+            ]))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_resize_single_stmt_parse_correct() {
+        let statements = parse_statements("resize 99 88;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Resize((99, 88)))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rotate90_single_stmt_parse_correct() {
+        let statements = parse_statements("rotate90;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Rotate90)],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rotate180_single_stmt_parse_correct() {
+        let statements = parse_statements("rotate180;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Rotate180)],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rotate270_single_stmt_parse_correct() {
+        let statements = parse_statements("rotate270;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Rotate270)],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unsharpen_single_stmt_parse_correct_ints() {
+        let statements = parse_statements("unsharpen 99 88;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Unsharpen((99.0, 88)))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unsharpen_single_stmt_parse_correct_fp_int() {
+        let statements = parse_statements("unsharpen 99.0 88;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Unsharpen((99.0, 88)))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unsharpen_single_stmt_parse_correct_fp_int_neg() {
+        let statements = parse_statements("unsharpen -99.0 -88;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![Instr::Operation(ImgOp::Unsharpen((-99.0, -88)))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unsharpen_single_stmt_parse_correct_fp_fp_fail() {
+        let statements =
+            parse_statements("unsharpen -99.0 -88.0;").and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[test]
+    fn test_multi_stmt_parse_correct() {
+        let statements = parse_statements("blur 10;flip-horizontal;flip-vertical;resize 100 200;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![
+                Instr::Operation(ImgOp::Blur(10.0)),
+                Instr::Operation(ImgOp::FlipHorizontal),
+                Instr::Operation(ImgOp::FlipVertical),
+                Instr::Operation(ImgOp::Resize((100, 200)))
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multi_stmt_parse_diff_order_correct() {
+        let statements = parse_statements("flip-horizontal;flip-vertical;resize 100 200;blur 10;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![
+                Instr::Operation(ImgOp::FlipHorizontal),
+                Instr::Operation(ImgOp::FlipVertical),
+                Instr::Operation(ImgOp::Resize((100, 200))),
+                Instr::Operation(ImgOp::Blur(10.0))
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multi_whitespace() {
+        let statements =
+            parse_statements("flip-horizontal; flip-vertical; resize 100 200; blur 10;")
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![
+                Instr::Operation(ImgOp::FlipHorizontal),
+                Instr::Operation(ImgOp::FlipVertical),
+                Instr::Operation(ImgOp::Resize((100, 200))),
+                Instr::Operation(ImgOp::Blur(10.0))
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multi_whitespace_2() {
+        let statements = parse_statements(
+            "flip-horizontal    ; flip-vertical   ;      resize 100 200; blur 10;",
+        )
+        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![
+                Instr::Operation(ImgOp::FlipHorizontal),
+                Instr::Operation(ImgOp::FlipVertical),
+                Instr::Operation(ImgOp::Resize((100, 200))),
+                Instr::Operation(ImgOp::Blur(10.0))
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multi_whitespace_3() {
+        let statements =
+            parse_statements("flip-horizontal;\nflip-vertical;\nresize 100 200;\nblur 10;")
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![
+                Instr::Operation(ImgOp::FlipHorizontal),
+                Instr::Operation(ImgOp::FlipVertical),
+                Instr::Operation(ImgOp::Resize((100, 200))),
+                Instr::Operation(ImgOp::Blur(10.0))
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multi_should_no_longer_end_with_sep() {
+        let statements =
+            parse_statements("flip-horizontal; flip-vertical; resize 100 200; blur 10")
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![
+                Instr::Operation(ImgOp::FlipHorizontal),
+                Instr::Operation(ImgOp::FlipVertical),
+                Instr::Operation(ImgOp::Resize((100, 200))),
+                Instr::Operation(ImgOp::Blur(10.0))
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multi_sep() {
+        let statements =
+            parse_statements("flip-horizontal; flip-vertical;  resize 100 200;\nblur 10")
+                .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            vec![
+                Instr::Operation(ImgOp::FlipHorizontal),
+                Instr::Operation(ImgOp::FlipVertical),
+                Instr::Operation(ImgOp::Resize((100, 200))),
+                Instr::Operation(ImgOp::Blur(10.0))
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_for_occurrence() {
+        let statements =
+            parse_statements("set resize.1 sampling-filter nearest; resize 100 100; resize 50 50;")
+                .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![
+                Instr::EnvAddFor(
+                    Occurrence::new("resize", 1).unwrap(),
+                    EnvItem::CustomSamplingFilter(FilterTypeWrap::new(FilterType::Nearest))
+                ),
+                Instr::Operation(ImgOp::Resize((100, 100))),
+                Instr::Operation(ImgOp::Resize((50, 50))),
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_for_occurrence_case_insensitive() {
+        let statements = parse_statements("set Flip-Horizontal.0 region rect(0, 0, 2, 2);")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![Instr::EnvAddFor(
+                Occurrence::new("flip-horizontal", 0).unwrap(),
+                EnvItem::Region(Region::new((0, 0), (2, 2)))
+            )],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_for_unknown_operation() {
+        let statements = parse_statements("set resise.0 sampling-filter nearest;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert!(parse_image_operations(statements).is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_for_occurrence_requires_index() {
+        assert!(parse_statements("set resize sampling-filter nearest;")
+            .and_then(parse_image_operations)
+            .is_err());
+        assert!(parse_statements("set resize. sampling-filter nearest;")
+            .and_then(parse_image_operations)
+            .is_err());
+        assert!(parse_statements("set resize.-1 sampling-filter nearest;")
+            .and_then(parse_image_operations)
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_resize_sampling_filter_catmullrom() {
+        let statements = parse_statements("set sampling-filter CatmullRom;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![Instr::EnvAdd(EnvItem::CustomSamplingFilter(
+                FilterTypeWrap::new(FilterType::CatmullRom)
+            ))],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_resize_sampling_filter_gaussian() {
+        let statements = parse_statements("set sampling-filter GAUSSIAN;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![Instr::EnvAdd(EnvItem::CustomSamplingFilter(
+                FilterTypeWrap::new(FilterType::Gaussian)
+            )),],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_resize_sampling_filter_lanczos3() {
+        let statements = parse_statements("set sampling-filter Lanczos3;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![Instr::EnvAdd(EnvItem::CustomSamplingFilter(
+                FilterTypeWrap::new(FilterType::Lanczos3)
+            )),],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_resize_sampling_filter_nearest() {
+        let statements = parse_statements("set sampling-filter nearest;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![Instr::EnvAdd(EnvItem::CustomSamplingFilter(
+                FilterTypeWrap::new(FilterType::Nearest)
+            )),],
+            parse_image_operations(statements).unwrap()
+        );
     }
 
     #[test]
     fn test_parse_setopt_resize_sampling_filter_triangle() {
-        let pairs = SICParser::parse(Rule::main, "set sampling-filter triangle;")
+        let statements = parse_statements("set sampling-filter triangle;")
             .unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![Instr::EnvAdd(EnvItem::CustomSamplingFilter(
                 FilterTypeWrap::new(FilterType::Triangle)
             )),],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_parse_setopt_resize_sampling_filter_with_resize() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "set   sampling-filter   GAUSSIAN;\nresize 100 200",
-        )
-        .unwrap_or_else(|e| panic!("error: {:?}", e));
+        let statements = parse_statements("set   sampling-filter   GAUSSIAN;\nresize 100 200")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
@@ -1160,14 +2655,40 @@ mod tests {
                 ))),
                 Instr::Operation(ImgOp::Resize((100, 200)))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_resize_sampling_filter_custom() {
+        use sic_image_engine::wrapper::filter_type::CustomFilterType;
+
+        let statements = parse_statements(
+            "set sampling-filter box;\
+             set sampling-filter hermite;\
+             set sampling-filter Mitchell;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![
+                Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::custom(
+                    CustomFilterType::Box
+                ))),
+                Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::custom(
+                    CustomFilterType::Hermite
+                ))),
+                Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::custom(
+                    CustomFilterType::Mitchell
+                ))),
+            ],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_parse_setopt_resize_sampling_filter_multi() {
-        let pairs = SICParser::parse(
-            Rule::main,
+        let statements = parse_statements(
             "set sampling-filter catmullrom;\
              set sampling-filter gaussian;\
              set sampling-filter lanczos3;\
@@ -1194,114 +2715,447 @@ mod tests {
                     FilterType::Triangle
                 ))),
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_parse_setopt_resize_preserve_aspect_ratio_t() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "set preserve-aspect-ratio true;\nresize 100 200",
-        )
-        .unwrap_or_else(|e| panic!("error: {:?}", e));
+        let statements = parse_statements("set preserve-aspect-ratio true;\nresize 100 200")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
                 Instr::EnvAdd(EnvItem::PreserveAspectRatio(true)),
                 Instr::Operation(ImgOp::Resize((100, 200)))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     fn test_parse_setopt_resize_preserve_aspect_ratio_f() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "set preserve-aspect-ratio false;\nresize 100 200",
-        )
-        .unwrap_or_else(|e| panic!("error: {:?}", e));
+        let statements = parse_statements("set preserve-aspect-ratio false;\nresize 100 200")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
                 Instr::EnvAdd(EnvItem::PreserveAspectRatio(false)),
                 Instr::Operation(ImgOp::Resize((100, 200)))
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
     #[should_panic]
     fn test_parse_setopt_resize_preserve_aspect_ratio_no_value() {
-        SICParser::parse(
-            Rule::main,
-            "set resize preserve-aspect-ratio true;\nresize 100 200",
+        parse_statements("set resize preserve-aspect-ratio true;\nresize 100 200")
+            .and_then(parse_image_operations)
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+    }
+
+    #[test]
+    fn test_parse_delopt_resize_sampling_filter_single() {
+        let statements =
+            parse_statements("del sampling-filter;").unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![Instr::EnvRemove(ItemName::CustomSamplingFilter),],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_set_and_del_opt_resize_sampling_filter_multi() {
+        let statements = parse_statements(
+            "set sampling-filter catmullrom;\
+             set sampling-filter gaussian;\
+             del sampling-filter;\
+             del sampling-filter;",
         )
         .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![
+                Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::new(
+                    FilterType::CatmullRom
+                ))),
+                Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::new(
+                    FilterType::Gaussian
+                ))),
+                Instr::EnvRemove(ItemName::CustomSamplingFilter),
+                Instr::EnvRemove(ItemName::CustomSamplingFilter),
+            ],
+            parse_image_operations(statements).unwrap()
+        );
     }
 
     #[test]
-    fn test_parse_delopt_resize_sampling_filter_single() {
-        let pairs = SICParser::parse(Rule::main, "del sampling-filter;")
+    fn test_parse_delopt_resize_preserve_aspect_ratio_single() {
+        let statements = parse_statements("del preserve-aspect-ratio;")
             .unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
-            vec![Instr::EnvRemove(ItemName::CustomSamplingFilter),],
-            parse_image_operations(pairs).unwrap()
+            vec![Instr::EnvRemove(ItemName::PreserveAspectRatio),],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_adaptive_threshold_method() {
+        let statements = parse_statements(
+            "set adaptive-threshold-method gaussian;\
+             set adaptive-threshold-method mean;\
+             del adaptive-threshold-method;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![
+                Instr::EnvAdd(EnvItem::AdaptiveThresholdMethod(
+                    AdaptiveThresholdMethod::Gaussian
+                )),
+                Instr::EnvAdd(EnvItem::AdaptiveThresholdMethod(
+                    AdaptiveThresholdMethod::Mean
+                )),
+                Instr::EnvRemove(ItemName::AdaptiveThresholdMethod),
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_adaptive_threshold_method_unknown() {
+        let statements = parse_statements("set adaptive-threshold-method median;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert!(parse_image_operations(statements).is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_blur_algorithm() {
+        let statements = parse_statements(
+            "set blur-algorithm box;\
+             blur 2;\
+             set blur-algorithm GAUSSIAN;\
+             del blur-algorithm;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![
+                Instr::EnvAdd(EnvItem::BlurAlgorithm(BlurAlgorithm::Box)),
+                Instr::Operation(ImgOp::Blur(2.0)),
+                Instr::EnvAdd(EnvItem::BlurAlgorithm(BlurAlgorithm::Gaussian)),
+                Instr::EnvRemove(ItemName::BlurAlgorithm),
+            ],
+            parse_image_operations(statements).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_blur_algorithm_unknown() {
+        let statements = parse_statements("set blur-algorithm median;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert!(parse_image_operations(statements).is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_background() {
+        let statements = parse_statements(
+            "set background rgba(255, 255, 255, 255);\
+             extend 640 480;\
+             del background;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            parse_image_operations(statements).unwrap(),
+            vec![
+                Instr::EnvAdd(EnvItem::BackgroundColor(Rgba([255, 255, 255, 255]))),
+                Instr::Operation(ImgOp::Extend(ExtendInputs::new((640, 480), None, None))),
+                Instr::EnvRemove(ItemName::BackgroundColor),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_background_not_a_color() {
+        let statements = parse_statements("set background size(1);")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert!(parse_image_operations(statements).is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_gravity() {
+        let statements = parse_statements(
+            "set gravity bottom-right;\
+             crop 0 0 10 10;\
+             set gravity NORTH-WEST;\
+             del gravity;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            parse_image_operations(statements).unwrap(),
+            vec![
+                Instr::EnvAdd(EnvItem::Gravity(Gravity::BottomRight)),
+                Instr::Operation(ImgOp::Crop((0, 0, 10, 10))),
+                Instr::EnvAdd(EnvItem::Gravity(Gravity::TopLeft)),
+                Instr::EnvRemove(ItemName::Gravity),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_gravity_unknown() {
+        assert!(parse_statements("set gravity middle;")
+            .and_then(parse_image_operations)
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_region() {
+        let statements = parse_statements(
+            "set region rect(0, 240, 640, 240);\
+             brighten 20;\
+             del region;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            vec![
+                Instr::EnvAdd(EnvItem::Region(Region::new((0, 240), (640, 240)))),
+                Instr::Operation(ImgOp::Brighten(20)),
+                Instr::EnvRemove(ItemName::Region),
+            ],
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_parse_set_and_del_opt_resize_sampling_filter_multi() {
-        let pairs = SICParser::parse(
-            Rule::main,
-            "set sampling-filter catmullrom;\
-             set sampling-filter gaussian;\
-             del sampling-filter;\
-             del sampling-filter;",
+    fn test_parse_setopt_mask() {
+        let statements = parse_statements(
+            "set mask 'masks/sky.png';\
+             brighten 20;\
+             del mask;",
         )
         .unwrap_or_else(|e| panic!("error: {:?}", e));
 
         assert_eq!(
             vec![
-                Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::new(
-                    FilterType::CatmullRom
-                ))),
-                Instr::EnvAdd(EnvItem::CustomSamplingFilter(FilterTypeWrap::new(
-                    FilterType::Gaussian
-                ))),
-                Instr::EnvRemove(ItemName::CustomSamplingFilter),
-                Instr::EnvRemove(ItemName::CustomSamplingFilter),
+                Instr::EnvAdd(EnvItem::Mask(ImageFromPath::new("masks/sky.png".into()))),
+                Instr::Operation(ImgOp::Brighten(20)),
+                Instr::EnvRemove(ItemName::Mask),
             ],
-            parse_image_operations(pairs).unwrap()
+            parse_image_operations(statements).unwrap()
         );
     }
 
     #[test]
-    fn test_parse_delopt_resize_preserve_aspect_ratio_single() {
-        let pairs = SICParser::parse(Rule::main, "del preserve-aspect-ratio;")
+    fn test_parse_setopt_mask_requires_quoted_path() {
+        let statements =
+            parse_statements("set mask masks/sky.png;").and_then(parse_image_operations);
+        assert!(statements.is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_region_not_a_rect() {
+        let statements = parse_statements("set region coord(0, 240);")
             .unwrap_or_else(|e| panic!("error: {:?}", e));
 
-        assert_eq!(
-            vec![Instr::EnvRemove(ItemName::PreserveAspectRatio),],
-            parse_image_operations(pairs).unwrap()
-        );
+        assert!(parse_image_operations(statements).is_err());
     }
 
     #[cfg(feature = "imageproc-ops")]
     mod imageproc_ops_tests {
         use super::*;
         use sic_core::image::Rgba;
+        use sic_image_engine::wrapper::draw_shape::ShapeStyle;
         use sic_image_engine::wrapper::font_options::{FontOptions, FontScale};
+        use sic_image_engine::wrapper::noise::NoiseKind;
         use std::path::PathBuf;
 
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+        #[parameterized(
+            input = {
+                "draw-rect rect(10, 20, 300, 40) rgba(255, 0, 0, 255);",
+                "draw-rect rect(10, 20, 300, 40) rgba(255, 0, 0, 255) filled;",
+                "DRAW-RECT rect(10,20,300,40) rgba(255,0,0,255) STROKE 3",
+            },
+            expected = {
+                DrawRectInputs::new((10, 20, 300, 40), RED, ShapeStyle::Stroke(1)),
+                DrawRectInputs::new((10, 20, 300, 40), RED, ShapeStyle::Filled),
+                DrawRectInputs::new((10, 20, 300, 40), RED, ShapeStyle::Stroke(3)),
+            }
+        )]
+        fn draw_rect(input: &str, expected: DrawRectInputs) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::DrawRect(expected))]
+            );
+        }
+
+        #[parameterized(input = {
+            "draw-rect;",
+            "draw-rect rect(10, 20, 300, 40);",
+            "draw-rect rect(10, 20, 300, 40) rgba(255, 0, 0, 255) stroke;",
+            "draw-rect rect(10, 20, 300, 40) rgba(255, 0, 0, 255) stroke -1;",
+            "draw-rect rect(10, 20, 300, 40) rgba(255, 0, 0, 255) dotted;",
+        })]
+        fn draw_rect_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[parameterized(input = {
+            "draw-rect coord(10, 20) rgba(255, 0, 0, 255);",
+            "draw-rect rect(10, 20, 300) rgba(255, 0, 0, 255);",
+            "draw-rect rect(10, 20, 300, 40) coord(1, 1);",
+        })]
+        fn draw_rect_named_value_err(input: &str) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert!(parse_image_operations(statements).is_err());
+        }
+
+        #[parameterized(
+            input = {
+                "draw-ellipse coord(10, 20) 30 40 rgba(255, 0, 0, 255);",
+                "draw-ellipse coord(10, 20) 30 30 rgba(255, 0, 0, 255) filled;",
+                "DRAW-ELLIPSE coord(10,20) 30 40 rgba(255,0,0,255) stroke 2",
+            },
+            expected = {
+                DrawEllipseInputs::new((10, 20), (30, 40), RED, ShapeStyle::Stroke(1)),
+                DrawEllipseInputs::new((10, 20), (30, 30), RED, ShapeStyle::Filled),
+                DrawEllipseInputs::new((10, 20), (30, 40), RED, ShapeStyle::Stroke(2)),
+            }
+        )]
+        fn draw_ellipse(input: &str, expected: DrawEllipseInputs) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::DrawEllipse(expected))]
+            );
+        }
+
+        #[parameterized(input = {
+            "draw-ellipse coord(10, 20) 30 rgba(255, 0, 0, 255);",
+            "draw-ellipse coord(10, 20) 30 -40 rgba(255, 0, 0, 255);",
+            "draw-ellipse coord(10, 20) 30 40 rgba(255, 0, 0, 255) hollow;",
+            "draw-ellipse 30 40 rgba(255, 0, 0, 255);",
+        })]
+        fn draw_ellipse_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[parameterized(
+            input = {
+                "draw-polygon coord(0, 0) coord(10, 0) coord(0, 10) rgba(255, 0, 0, 255);",
+                "draw-polygon coord(0, 0) coord(10, 0) coord(10, 10) coord(0, 10) rgba(255, 0, 0, 255) filled;",
+                "DRAW-POLYGON coord(0,0) coord(10,0) coord(0,10) rgba(255,0,0,255) STROKE 4",
+            },
+            expected = {
+                DrawPolygonInputs::new(vec![(0, 0), (10, 0), (0, 10)], RED, ShapeStyle::Stroke(1)).unwrap(),
+                DrawPolygonInputs::new(vec![(0, 0), (10, 0), (10, 10), (0, 10)], RED, ShapeStyle::Filled).unwrap(),
+                DrawPolygonInputs::new(vec![(0, 0), (10, 0), (0, 10)], RED, ShapeStyle::Stroke(4)).unwrap(),
+            }
+        )]
+        fn draw_polygon(input: &str, expected: DrawPolygonInputs) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::DrawPolygon(expected))]
+            );
+        }
+
+        #[parameterized(input = {
+            "draw-polygon coord(0, 0) coord(10, 0) rgba(255, 0, 0, 255);",
+            "draw-polygon coord(0, 0) coord(10, 0) coord(0, 10) rgba(255, 0, 0, 255) 3;",
+        })]
+        fn draw_polygon_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[parameterized(input = {
+            "draw-polygon coord(0, 0) coord(10, 0) rgba(255, 0, 0, 255) coord(0, 10);",
+            "draw-polygon coord(0, 0) coord(10, 0) coord(0, 10) coord(10, 10);",
+            "draw-polygon coord(0, 0) coord(10, 0) coord(0, 10) rgba(255, 0, 0, 255) rgba(255, 0, 0, 255);",
+        })]
+        fn draw_polygon_named_value_err(input: &str) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert!(parse_image_operations(statements).is_err());
+        }
+
+        #[parameterized(
+            input = {
+                "draw-line coord(10, 20) coord(30, 40) rgba(255, 0, 0, 255) 3;",
+                "DRAW-LINE coord(10,20) coord(30,40) rgba(255,0,0,255) 1",
+            },
+            expected = {
+                DrawLineInputs::new((10, 20), (30, 40), RED, 3),
+                DrawLineInputs::new((10, 20), (30, 40), RED, 1),
+            }
+        )]
+        fn draw_line(input: &str, expected: DrawLineInputs) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::DrawLine(expected.clone())),]
+            );
+
+            let input = input
+                .replacen("line", "arrow", 1)
+                .replacen("LINE", "ARROW", 1);
+            let statements = parse_statements(&input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::DrawArrow(expected))]
+            );
+        }
+
+        #[parameterized(input = {
+            "draw-line coord(10, 20) coord(30, 40) rgba(255, 0, 0, 255);",
+            "draw-line coord(10, 20) rgba(255, 0, 0, 255) 3;",
+            "draw-line coord(10, 20) coord(30, 40) rgba(255, 0, 0, 255) -3;",
+            "draw-arrow coord(10, 20) coord(30, 40) rgba(255, 0, 0, 255) 1.5;",
+            "draw-arrow coord(10, 20) coord(30, 40) rgba(255, 0, 0, 255) 3 3;",
+        })]
+        fn draw_line_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[parameterized(input = {
+            "draw-line coord(10, 20) coord(30, 40) coord(1, 1) 3;",
+            "draw-arrow rgba(255, 0, 0, 255) coord(10, 20) coord(30, 40) 3;",
+        })]
+        fn draw_line_named_value_err(input: &str) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert!(parse_image_operations(statements).is_err());
+        }
+
         #[test]
         fn draw_text() {
-            let pairs = SICParser::parse(
-                Rule::main,
-                r#"draw-text "my text" coord(0,1) rgba(10, 10, 255, 255) size(16.0) font("resources/font/Lato-Regular.ttf");"#,
+            let statements = parse_statements(r#"draw-text "my text" coord(0,1) rgba(10, 10, 255, 255) size(16.0) font("resources/font/Lato-Regular.ttf");"#,
             )
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
 
@@ -1317,21 +3171,380 @@ mod tests {
                 (0, 1),
                 font_options,
             )))];
-            let actual = parse_image_operations(pairs).unwrap();
+            let actual = parse_image_operations(statements).unwrap();
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn draw_text_font_family() {
+            use sic_image_engine::wrapper::font_options::{FontSource, FontStyle};
+
+            let statements = parse_statements(r#"draw-text "my text" coord(0, 1) rgba(10, 10, 255, 255) size(16.0) font("DejaVu Sans", "bold italic");"#,
+            )
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            let font_options = FontOptions::new(
+                FontSource::Family("DejaVu Sans".to_string()),
+                Rgba([10, 10, 255, 255]),
+                FontScale::Uniform(16.0),
+            )
+            .with_style(FontStyle {
+                bold: true,
+                italic: true,
+            });
+
+            let expected = vec![Instr::Operation(ImgOp::DrawText(DrawTextInner::new(
+                "my text".to_string(),
+                (0, 1),
+                font_options,
+            )))];
+            let actual = parse_image_operations(statements).unwrap();
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn draw_text_font_style_err() {
+            for font in &[
+                r#"font("Lato", "condensed")"#,
+                r#"font("resources/font/Lato-Regular.ttf", "bold")"#,
+            ] {
+                let script = format!(
+                    r#"draw-text "my text" coord(0, 1) rgba(10, 10, 255, 255) size(16.0) {};"#,
+                    font
+                );
+                let statements = parse_statements(&script).unwrap_or_else(|e| {
+                    panic!("Unable to parse sic image operations script: {:?}", e)
+                });
+
+                assert!(parse_image_operations(statements).is_err());
+            }
+        }
+
+        #[test]
+        fn draw_text_outline_shadow_spacing() {
+            use sic_image_engine::wrapper::font_options::{TextOutline, TextShadow};
+
+            let statements = parse_statements(r#"draw-text "my text" coord(0, 1) rgba(10, 10, 255, 255) size(16.0) font("resources/font/Lato-Regular.ttf") spacing(1.5, -2) shadow(2, -3, rgba(0, 0, 0, 128)) outline(2, rgba(255, 255, 255, 255));"#,
+            )
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            let font_options = FontOptions::new(
+                PathBuf::from("resources/font/Lato-Regular.ttf"),
+                Rgba([10, 10, 255, 255]),
+                FontScale::Uniform(16.0),
+            )
+            .with_outline(TextOutline {
+                width: 2.0,
+                color: Rgba([255, 255, 255, 255]),
+            })
+            .with_shadow(TextShadow {
+                offset: (2, -3),
+                color: Rgba([0, 0, 0, 128]),
+            })
+            .with_spacing(1.5, -2.0);
+
+            let expected = vec![Instr::Operation(ImgOp::DrawText(DrawTextInner::new(
+                "my text".to_string(),
+                (0, 1),
+                font_options,
+            )))];
+            let actual = parse_image_operations(statements).unwrap();
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn draw_text_angle() {
+            let statements = parse_statements(r#"draw-text "my text" coord(0, 1) rgba(10, 10, 255, 255) size(16.0) font("resources/font/Lato-Regular.ttf") angle(-30);"#,
+            )
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            let font_options = FontOptions::new(
+                PathBuf::from("resources/font/Lato-Regular.ttf"),
+                Rgba([10, 10, 255, 255]),
+                FontScale::Uniform(16.0),
+            )
+            .with_angle(-30.0);
+
+            let expected = vec![Instr::Operation(ImgOp::DrawText(DrawTextInner::new(
+                "my text".to_string(),
+                (0, 1),
+                font_options,
+            )))];
+            let actual = parse_image_operations(statements).unwrap();
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn draw_text_wrap_align() {
+            use sic_image_engine::wrapper::font_options::TextAlign;
+
+            let statements = parse_statements(r#"draw-text "my\ntext\\n" coord(0, 1) rgba(10, 10, 255, 255) size(16.0) font("resources/font/Lato-Regular.ttf") align('Center') wrap(80);"#,
+            )
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+
+            let font_options = FontOptions::new(
+                PathBuf::from("resources/font/Lato-Regular.ttf"),
+                Rgba([10, 10, 255, 255]),
+                FontScale::Uniform(16.0),
+            )
+            .with_wrap_width(80.0)
+            .with_align(TextAlign::Center);
+
+            let expected = vec![Instr::Operation(ImgOp::DrawText(DrawTextInner::new(
+                "my\ntext\\n".to_string(),
+                (0, 1),
+                font_options,
+            )))];
+            let actual = parse_image_operations(statements).unwrap();
 
             assert_eq!(actual, expected);
         }
 
+        #[test]
+        fn draw_text_extras_err() {
+            for extras in &[
+                "outline(1, rgba(0, 0, 0, 255)) outline(2, rgba(0, 0, 0, 255))",
+                "size(12)",
+                "shadow(1.5, 1, rgba(0, 0, 0, 255))",
+                "outline(1)",
+                "angle(10) angle(20)",
+                "align('justify')",
+                "wrap(80) wrap(40)",
+            ] {
+                let script = format!(
+                    r#"draw-text "my text" coord(0, 1) rgba(10, 10, 255, 255) size(16.0) font("resources/font/Lato-Regular.ttf") {};"#,
+                    extras
+                );
+                let statements = parse_statements(&script).unwrap_or_else(|e| {
+                    panic!("Unable to parse sic image operations script: {:?}", e)
+                });
+
+                assert!(parse_image_operations(statements).is_err());
+            }
+        }
+
         #[test]
         fn draw_text_ordering() {
-            let pairs = SICParser::parse(
-                Rule::main,
-                r#"draw-text "my text" coord(0, 1) size(16.0) rgba(10, 10, 255, 255) font("resources/font/Lato-Regular.ttf");"#,
+            let statements = parse_statements(r#"draw-text "my text" coord(0, 1) size(16.0) rgba(10, 10, 255, 255) font("resources/font/Lato-Regular.ttf");"#,
             ).unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
 
-            let actual = parse_image_operations(pairs);
+            let actual = parse_image_operations(statements);
 
             assert!(actual.is_err());
         }
+
+        #[test]
+        fn adaptive_threshold() {
+            let statements = parse_statements("adaptive-threshold 15;")
+                .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                vec![Instr::Operation(ImgOp::AdaptiveThreshold(15))],
+                parse_image_operations(statements).unwrap()
+            );
+        }
+
+        #[test]
+        fn adaptive_threshold_negative() {
+            let statements =
+                parse_statements("adaptive-threshold -15;").and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[test]
+        fn clahe() {
+            let statements =
+                parse_statements("clahe 8 2.5;").unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                vec![Instr::Operation(ImgOp::Clahe((8, 2.5)))],
+                parse_image_operations(statements).unwrap()
+            );
+        }
+
+        #[test]
+        fn clahe_missing_clip_limit() {
+            let statements = parse_statements("clahe 8;").and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[test]
+        fn distance_transform() {
+            let statements = parse_statements("adaptive-threshold 15; distance-transform;")
+                .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                vec![
+                    Instr::Operation(ImgOp::AdaptiveThreshold(15)),
+                    Instr::Operation(ImgOp::DistanceTransform)
+                ],
+                parse_image_operations(statements).unwrap()
+            );
+        }
+
+        #[test]
+        fn distance_transform_takes_no_arguments() {
+            let statements =
+                parse_statements("distance-transform 2;").and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[parameterized(
+            input = {
+                "draw-corners 40;",
+                "DRAW-CORNERS 0 rgba(0, 255, 0, 255)",
+            },
+            expected = {
+                DrawCornersInputs::new(40, RED),
+                DrawCornersInputs::new(0, Rgba([0, 255, 0, 255])),
+            }
+        )]
+        fn draw_corners(input: &str, expected: DrawCornersInputs) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                parse_image_operations(statements).unwrap(),
+                vec![Instr::Operation(ImgOp::DrawCorners(expected))]
+            );
+        }
+
+        #[parameterized(input = { "draw-corners;", "draw-corners -1;", "draw-corners 4.5;", "draw-corners 40 red;" })]
+        fn draw_corners_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[parameterized(input = { "draw-corners 256;", "draw-corners 40 coord(1, 2);" })]
+        fn draw_corners_invalid_value(input: &str) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert!(parse_image_operations(statements).is_err());
+        }
+
+        #[parameterized(
+            input = {
+                "label-components;",
+                "label-components 4;",
+                "LABEL-COMPONENTS 8",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::LabelComponents(8))],
+                vec![Instr::Operation(ImgOp::LabelComponents(4))],
+                vec![Instr::Operation(ImgOp::LabelComponents(8))],
+            }
+        )]
+        fn label_components(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "label-components -4;", "label-components 4.5;", "label-components 4 8;" })]
+        fn label_components_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[test]
+        fn median() {
+            let statements =
+                parse_statements("median 2;").unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(
+                vec![Instr::Operation(ImgOp::Median(2))],
+                parse_image_operations(statements).unwrap()
+            );
+        }
+
+        #[parameterized(input = { "median;", "median -1;", "median 1.5;" })]
+        fn median_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[parameterized(
+            input = {
+                "dilate 2;",
+                "erode 1",
+                "MORPH-OPEN 3;",
+                "adaptive-threshold 15;\nmorph-close 2;",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Dilate(2))],
+                vec![Instr::Operation(ImgOp::Erode(1))],
+                vec![Instr::Operation(ImgOp::MorphOpen(3))],
+                vec![Instr::Operation(ImgOp::AdaptiveThreshold(15)), Instr::Operation(ImgOp::MorphClose(2))],
+            }
+        )]
+        fn morphology(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "dilate;", "erode -1;", "morph-open 1.5;", "morph-close 1 1;", "morph 1;" })]
+        fn morphology_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[parameterized(
+            input = {
+                "noise gaussian 0.1;",
+                "noise salt-pepper 0.05 42;",
+                "NOISE GAUSSIAN 1 0",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Noise(NoiseInputs::new(NoiseKind::Gaussian, 0.1, None)))],
+                vec![Instr::Operation(ImgOp::Noise(NoiseInputs::new(NoiseKind::SaltPepper, 0.05, Some(42))))],
+                vec![Instr::Operation(ImgOp::Noise(NoiseInputs::new(NoiseKind::Gaussian, 1.0, Some(0))))],
+            }
+        )]
+        fn noise(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "noise;", "noise gaussian;", "noise perlin 0.1;", "noise gaussian 0.1 -1;", "noise gaussian 0.1 1 1;" })]
+        fn noise_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
+
+        #[parameterized(
+            input = {
+                "perspective 10 5 90 0 100 80 0 75;",
+                "PERSPECTIVE -1.5 0 10 0 10 10 0 10.5",
+            },
+            expected_ops = {
+                vec![Instr::Operation(ImgOp::Perspective(PerspectiveInputs::new([(10.0, 5.0), (90.0, 0.0), (100.0, 80.0), (0.0, 75.0)])))],
+                vec![Instr::Operation(ImgOp::Perspective(PerspectiveInputs::new([(-1.5, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.5)])))],
+            }
+        )]
+        fn perspective(input: &str, expected_ops: Vec<Instr>) {
+            let statements = parse_statements(input).unwrap_or_else(|e| panic!("error: {:?}", e));
+
+            assert_eq!(parse_image_operations(statements).unwrap(), expected_ops);
+        }
+
+        #[parameterized(input = { "perspective;", "perspective 0 0 1 0 1 1 0;", "perspective 0 0 1 0 1 1 0 1 1;", "perspective a 0 1 0 1 1 0 1;" })]
+        fn perspective_err(input: &str) {
+            let statements = parse_statements(input).and_then(parse_image_operations);
+
+            assert!(statements.is_err());
+        }
     }
 }
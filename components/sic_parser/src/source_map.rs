@@ -0,0 +1,274 @@
+//! The source map module keeps track of where the text of a script is written, while its includes,
+//! environment variables, macros and bindings are substituted, so an error can point at the script
+//! as written, rather than at the script as it's parsed.
+//!
+//! Text which is copied, e.g. a statement which isn't substituted, or the body of a macro, is
+//! located where the copied text is written. A substituted value, e.g. the value of a binding, is
+//! located at the reference it replaced, e.g. `$size`. Text of an included script file is located
+//! within that file, which is named by its path.
+
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A script, as written or with some of its parts substituted, which remembers where each of its
+/// parts is written.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Script {
+    text: String,
+    parts: Vec<Part>,
+}
+
+/// A part of a script, which is written at the same place.
+#[derive(Clone, Debug, PartialEq)]
+struct Part {
+    /// The bytes of the script.
+    bytes: Range<usize>,
+    source: Arc<Source>,
+    /// The bytes of the source at which the part is written.
+    written: Range<usize>,
+    /// Whether the part is a copy of the written text, or a value substituted for it.
+    copied: bool,
+}
+
+/// The text as written, e.g. a script file.
+#[derive(Debug, PartialEq, Eq)]
+struct Source {
+    /// The path of an included script file; `None` for the script which is parsed.
+    name: Option<String>,
+    text: String,
+}
+
+/// Where a part of a script is written: the text as written, the name of the included script file
+/// if it's written in one, and the bytes of the part within the text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Written<'s> {
+    pub(crate) source: &'s str,
+    pub(crate) name: Option<&'s str>,
+    pub(crate) bytes: Range<usize>,
+}
+
+impl Script {
+    /// A script read from the script file at the given path, which is included by the script
+    /// which is parsed.
+    pub(crate) fn included(text: &str, path: &Path) -> Self {
+        Self::from_source(Source {
+            name: Some(path.display().to_string()),
+            text: text.to_string(),
+        })
+    }
+
+    fn from_source(source: Source) -> Self {
+        let text = source.text.clone();
+        let mut script = Script::default();
+        script.append(&text, Arc::new(source), 0..text.len(), true);
+        script
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Appends a part of the text of the given script, e.g. one of its statements.
+    pub(crate) fn push(&mut self, from: &Script, text: &str) {
+        let start = from.offset_of(text);
+        let end = start + text.len();
+
+        for part in &from.parts {
+            let (part_start, part_end) = (part.bytes.start.max(start), part.bytes.end.min(end));
+
+            if part_start >= part_end {
+                continue;
+            }
+
+            let written = if part.copied {
+                let start = part.written.start + part_start - part.bytes.start;
+                start..start + part_end - part_start
+            } else {
+                part.written.clone()
+            };
+
+            self.append(
+                &from.text[part_start..part_end],
+                Arc::clone(&part.source),
+                written,
+                part.copied,
+            );
+        }
+    }
+
+    /// Appends a value which is substituted for a reference, which is a part of the text of the
+    /// given script, e.g. `$size`.
+    pub(crate) fn push_substituted(&mut self, value: &str, from: &Script, reference: &str) {
+        let start = from.offset_of(reference);
+
+        if let Some((part, written)) = from.written_part(start..start + reference.len()) {
+            self.append(value, Arc::clone(&part.source), written, false);
+        }
+    }
+
+    /// The script without the whitespace it starts with.
+    pub(crate) fn trim_start(&self) -> Script {
+        let mut trimmed = Script::default();
+        trimmed.push(self, self.text.trim_start());
+        trimmed
+    }
+
+    /// Where a part of the script, by its bytes, is written, or `None` for an empty script.
+    pub(crate) fn written(&self, bytes: Range<usize>) -> Option<Written<'_>> {
+        self.written_part(bytes).map(|(part, written)| Written {
+            source: &part.source.text,
+            name: part.source.name.as_deref(),
+            bytes: written,
+        })
+    }
+
+    fn written_part(&self, bytes: Range<usize>) -> Option<(&Part, Range<usize>)> {
+        // a part which ends where the bytes start only contains them if they're at the end
+        let first = self
+            .parts
+            .iter()
+            .find(|part| bytes.start < part.bytes.end)
+            .or_else(|| self.parts.last())?;
+
+        let last = self
+            .parts
+            .iter()
+            .rev()
+            .find(|part| part.bytes.start < bytes.end && Arc::ptr_eq(&part.source, &first.source))
+            .filter(|last| last.bytes.start >= first.bytes.start)
+            .unwrap_or(first);
+
+        let start = if first.copied {
+            first.written.start + bytes.start.max(first.bytes.start) - first.bytes.start
+        } else {
+            first.written.start
+        };
+
+        let end = if last.copied {
+            last.written.start + bytes.end.min(last.bytes.end) - last.bytes.start
+        } else {
+            last.written.end
+        };
+
+        Some((first, start..end.max(start)))
+    }
+
+    fn append(&mut self, text: &str, source: Arc<Source>, written: Range<usize>, copied: bool) {
+        if text.is_empty() {
+            return;
+        }
+
+        let bytes = self.text.len()..self.text.len() + text.len();
+        self.text.push_str(text);
+
+        // text which is copied from where the previous part ends, extends it
+        if let Some(last) = self.parts.last_mut() {
+            if copied
+                && last.copied
+                && Arc::ptr_eq(&last.source, &source)
+                && last.written.end == written.start
+            {
+                last.bytes.end = bytes.end;
+                last.written.end = written.end;
+                return;
+            }
+        }
+
+        self.parts.push(Part {
+            bytes,
+            source,
+            written,
+            copied,
+        });
+    }
+
+    // the byte at which a part of the text starts
+    fn offset_of(&self, text: &str) -> usize {
+        let offset = (text.as_ptr() as usize).wrapping_sub(self.text.as_ptr() as usize);
+
+        assert!(
+            offset <= self.text.len() && text.len() <= self.text.len() - offset,
+            "the text must be a part of the script"
+        );
+
+        offset
+    }
+}
+
+impl From<&str> for Script {
+    fn from(text: &str) -> Self {
+        Self::from_source(Source {
+            name: None,
+            text: text.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(script: &Script, text: &str) -> String {
+        let start = script.as_str().find(text).unwrap();
+        let written = script.written(start..start + text.len()).unwrap();
+
+        written.source[written.bytes].to_string()
+    }
+
+    #[test]
+    fn copied_text_is_written_where_it_is_copied_from() {
+        let from = Script::from("let size = 4;\nresize $size abc");
+        let mut script = Script::default();
+        script.push(&from, &from.as_str()[14..]);
+
+        assert_eq!(script.as_str(), "resize $size abc");
+        assert_eq!(script.written(13..16).unwrap().bytes, 27..30);
+        assert_eq!(written(&script, "abc"), "abc");
+    }
+
+    #[test]
+    fn substituted_value_is_written_at_reference() {
+        let from = Script::from("resize $size abc");
+        let mut script = Script::default();
+        script.push(&from, &from.as_str()[..7]);
+        script.push_substituted("640", &from, &from.as_str()[7..12]);
+        script.push(&from, &from.as_str()[12..]);
+
+        assert_eq!(script.as_str(), "resize 640 abc");
+        assert_eq!(written(&script, "640"), "$size");
+        assert_eq!(written(&script, "64"), "$size");
+        assert_eq!(written(&script, "abc"), "abc");
+        assert_eq!(written(&script, "resize 640"), "resize $size");
+    }
+
+    #[test]
+    fn included_text_is_written_in_file() {
+        let included = Script::included("invert;\nblur x", Path::new("inc2.sic"));
+        let from = Script::from("blur 1; include 'inc2.sic'; invert");
+        let mut script = Script::default();
+        script.push(&from, &from.as_str()[..8]);
+        script.push(&included, included.as_str());
+
+        let start = script.as_str().find('x').unwrap();
+        let written = script.written(start..start + 1).unwrap();
+
+        assert_eq!(written.name, Some("inc2.sic"));
+        assert_eq!(written.bytes, 13..14);
+        assert_eq!(script.written(0..4).unwrap().name, None);
+    }
+
+    #[test]
+    fn trim_start_keeps_where_text_is_written() {
+        let script = Script::from("\n\n  blur 1").trim_start();
+
+        assert_eq!(script.as_str(), "blur 1");
+        assert_eq!(script.written(5..6).unwrap().bytes, 9..10);
+        assert_eq!(script.written(6..6).unwrap().bytes, 10..10);
+    }
+
+    #[test]
+    fn empty_script_is_not_written() {
+        assert!(Script::default().written(0..0).is_none());
+    }
+}
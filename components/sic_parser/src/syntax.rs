@@ -0,0 +1,507 @@
+//! The syntax module splits a script into its statements, and each statement into its name and
+//! arguments, e.g. `resize 10 20;` into `resize`, `10` and `20`. What the arguments mean is up to
+//! the image operation or modifier they're given to, which parses them with the instruction
+//! module, like the arguments given on the command line.
+//!
+//! Statements are separated by a `;`, which may be followed by line breaks; the last statement
+//! doesn't need one. Arguments are separated by spaces. An argument is either a string, e.g.
+//! `'my image.png'`, a named value, e.g. `rgba(0, 0, 0, 255)`, a `|` which separates the rows of a
+//! kernel, or a word, e.g. `10`, `bottom-right` or `($width - 10)`. Spaces are allowed within
+//! parentheses and strings.
+
+use std::ops::Range;
+
+use crate::diagnostic::Location;
+use crate::errors::SicParserError;
+use crate::quoting::{unquote, Quotes};
+
+/// A part of a script, e.g. an argument, from the byte at which it starts up to the one at which
+/// it ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span<'i> {
+    script: &'i str,
+    start: usize,
+    end: usize,
+}
+
+impl<'i> Span<'i> {
+    pub(crate) fn new(script: &'i str, start: usize, end: usize) -> Self {
+        Self { script, start, end }
+    }
+
+    pub fn as_str(&self) -> &'i str {
+        &self.script[self.start..self.end]
+    }
+
+    /// The bytes of the script which the span covers.
+    pub fn bytes(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// The line and column at which the span starts; both are counted from 1, and the column in
+    /// characters.
+    pub fn line_col(&self) -> (usize, usize) {
+        let before = &self.script[..self.start];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+
+    /// The line of the script at which the span starts, without its line break.
+    pub fn line_of(&self) -> &'i str {
+        let line_start = self.script[..self.start]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let line_end = self.script[self.start..]
+            .find('\n')
+            .map_or(self.script.len(), |index| self.start + index);
+
+        self.script[line_start..line_end].trim_end_matches('\r')
+    }
+
+    // a part of this span, by the byte offsets within it
+    fn slice(&self, start: usize, end: usize) -> Self {
+        Self::new(self.script, self.start + start, self.start + end)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// e.g. `10`, `bottom-right` or `($width - 10)`
+    Word,
+    /// enclosed in quotation marks, e.g. `'my image.png'`
+    String,
+    /// an identifier followed by its arguments, e.g. `rgba(0, 0, 0, 255)`
+    NamedValue,
+    /// a `|`, which separates the rows of a kernel
+    RowSeparator,
+}
+
+/// The name of a statement, or one of its arguments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<'i> {
+    kind: TokenKind,
+    span: Span<'i>,
+    inner: Vec<Token<'i>>,
+}
+
+impl<'i> Token<'i> {
+    fn new(kind: TokenKind, span: Span<'i>) -> Self {
+        Self {
+            kind,
+            span,
+            inner: Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    pub fn span(&self) -> Span<'i> {
+        self.span
+    }
+
+    /// The token as written, e.g. a string with its quotation marks.
+    pub fn as_str(&self) -> &'i str {
+        self.span.as_str()
+    }
+
+    /// The contents of a string, without the quotation marks which enclose it, but with its
+    /// escapes; for other tokens, the token as written.
+    pub fn contents(&self) -> &'i str {
+        let text = self.as_str();
+
+        match self.kind {
+            TokenKind::String => &text[1..text.len() - 1],
+            _ => text,
+        }
+    }
+
+    /// For a named value, its identifier followed by its arguments; other tokens have none.
+    pub fn inner(&self) -> &[Token<'i>] {
+        &self.inner
+    }
+}
+
+/// An image operation, or a `set` or `del` statement, with its arguments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statement<'i> {
+    name: Token<'i>,
+    arguments: Vec<Token<'i>>,
+}
+
+impl<'i> Statement<'i> {
+    pub fn name(&self) -> &Token<'i> {
+        &self.name
+    }
+
+    pub fn arguments(&self) -> &[Token<'i>] {
+        &self.arguments
+    }
+}
+
+/// Splits a script into its statements. Fails if the statements aren't separated by a `;`, or if
+/// a string or parenthesis isn't closed.
+pub fn parse_statements(script: &str) -> Result<Vec<Statement<'_>>, SicParserError> {
+    let mut tokenizer = Tokenizer {
+        script,
+        position: 0,
+    };
+    let mut statements = Vec::new();
+
+    loop {
+        tokenizer.skip(|c| c == ' ' || is_line_break(c));
+
+        if tokenizer.peek().is_none() {
+            return Ok(statements);
+        }
+
+        statements.push(tokenizer.statement()?);
+
+        match tokenizer.peek() {
+            Some(';') => tokenizer.position += 1,
+            Some(_) => {
+                // a statement which ends at a line break must be the last one
+                tokenizer.skip(|c| c == ' ' || is_line_break(c));
+
+                if tokenizer.peek().is_some() {
+                    return Err(tokenizer.error("expected a ';' between statements"));
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+struct Tokenizer<'i> {
+    script: &'i str,
+    position: usize,
+}
+
+impl<'i> Tokenizer<'i> {
+    fn peek(&self) -> Option<char> {
+        self.script[self.position..].chars().next()
+    }
+
+    fn skip(&mut self, skipped: impl Fn(char) -> bool) {
+        while let Some(c) = self.peek().filter(|&c| skipped(c)) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    // a statement ends at a ';', a line break, or the end of the script, which are left to be
+    // taken by the caller
+    fn statement(&mut self) -> Result<Statement<'i>, SicParserError> {
+        if self.peek() == Some(';') {
+            return Err(self.error("expected an image operation"));
+        }
+
+        let name = self.token()?;
+
+        if name.kind != TokenKind::Word {
+            return Err(self.error_at(name.span, "expected the name of an image operation"));
+        }
+
+        let mut arguments = Vec::new();
+
+        loop {
+            self.skip(|c| c == ' ');
+
+            match self.peek() {
+                Some(c) if !ends_statement(c) => arguments.push(self.token()?),
+                _ => return Ok(Statement { name, arguments }),
+            }
+        }
+    }
+
+    fn token(&mut self) -> Result<Token<'i>, SicParserError> {
+        let start = self.position;
+        let rest = &self.script[start..];
+
+        let token = match rest.chars().next() {
+            Some('\'') | Some('"') => {
+                let mut quotes = Quotes::default();
+                let end = rest
+                    .char_indices()
+                    .find(|&(index, c)| quotes.within(c) && index > 0 && !quotes.is_open())
+                    .map(|(index, c)| index + c.len_utf8())
+                    .ok_or_else(|| self.error("expected the string to be closed"))?;
+
+                Token::new(TokenKind::String, self.span(start, start + end))
+            }
+            Some('|') if rest[1..].chars().next().is_none_or(ends_argument) => {
+                Token::new(TokenKind::RowSeparator, self.span(start, start + 1))
+            }
+            _ => {
+                let end = word_end(rest)
+                    .ok_or_else(|| self.error("expected a string or parenthesis to be closed"))?;
+
+                if !fractions_have_digits(&rest[..end]) {
+                    return Err(self.error("expected a digit after the '.' of a number"));
+                }
+
+                word(self.span(start, start + end))
+            }
+        };
+
+        self.position = token.span.end;
+
+        match self.peek() {
+            Some(c) if !ends_argument(c) => Err(self.error("expected a space between arguments")),
+            _ => Ok(token),
+        }
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span<'i> {
+        Span::new(self.script, start, end)
+    }
+
+    fn error(&self, message: &str) -> SicParserError {
+        self.error_at(self.span(self.position, self.position), message)
+    }
+
+    fn error_at(&self, span: Span<'_>, message: &str) -> SicParserError {
+        SicParserError::SyntaxError(message.to_string(), Box::new(Location::of(&span)))
+    }
+}
+
+fn is_line_break(c: char) -> bool {
+    c == '\n' || c == '\r'
+}
+
+fn ends_statement(c: char) -> bool {
+    c == ';' || is_line_break(c)
+}
+
+fn ends_argument(c: char) -> bool {
+    c == ' ' || ends_statement(c)
+}
+
+/// The length of the word with which the text starts, or `None` if a string or parenthesis within
+/// it isn't closed before the statement ends.
+fn word_end(text: &str) -> Option<usize> {
+    let mut quotes = Quotes::default();
+    let mut depth = 0usize;
+
+    for (index, c) in text.char_indices() {
+        if quotes.within(c) {
+            continue;
+        }
+
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ' ' if depth == 0 => return Some(index),
+            c if ends_statement(c) => return if depth == 0 { Some(index) } else { None },
+            _ => {}
+        }
+    }
+
+    if depth == 0 && !quotes.is_open() {
+        Some(text.len())
+    } else {
+        None
+    }
+}
+
+/// Whether the '.'s of a word which starts like a number, e.g. `-1.5` or `12.5%`, are followed by
+/// a digit; words which don't start like a number are.
+fn fractions_have_digits(word: &str) -> bool {
+    let unsigned = word.strip_prefix(&['-', '+'][..]).unwrap_or(word);
+
+    !unsigned.starts_with(|c: char| c.is_ascii_digit())
+        || unsigned
+            .split('.')
+            .skip(1)
+            .all(|fraction| fraction.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// A word, which is a named value if it's an identifier directly followed by its arguments within
+/// parentheses, e.g. `rgba(0, 0, 0, 255)`.
+fn word(span: Span<'_>) -> Token<'_> {
+    let text = span.as_str();
+
+    let named_value = text
+        .find('(')
+        .filter(|&open| open > 0 && text[..open].chars().all(|c| c.is_ascii_alphanumeric()))
+        .and_then(|open| Some((open, split_arguments(&text[open + 1..])?)));
+
+    match named_value {
+        // the arguments must end with the word, e.g. `min($width, $height)*2` is a word
+        Some((open, (arguments, end))) if open + 1 + end + 1 == text.len() => {
+            let ident = Token::new(TokenKind::Word, span.slice(0, open));
+            let arguments = arguments.into_iter().map(|(start, end)| {
+                let argument = span.slice(open + 1 + start, open + 1 + end);
+
+                if unquote(argument.as_str()).is_some() {
+                    Token::new(TokenKind::String, argument)
+                } else {
+                    word(argument)
+                }
+            });
+
+            Token {
+                kind: TokenKind::NamedValue,
+                span,
+                inner: std::iter::once(ident).chain(arguments).collect(),
+            }
+        }
+        _ => Token::new(TokenKind::Word, span),
+    }
+}
+
+/// Splits the arguments of a named value at the commas which aren't within strings or
+/// parentheses, up to the parenthesis which closes them. Gives the start and end of each argument
+/// without the spaces around it, and the offset of the closing parenthesis.
+fn split_arguments(text: &str) -> Option<(Vec<(usize, usize)>, usize)> {
+    let mut arguments = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let argument = &text[start..end];
+        let start = start + (argument.len() - argument.trim_start().len());
+        let end = end - (argument.len() - argument.trim_end().len());
+
+        if start < end {
+            arguments.push((start, end));
+        }
+    };
+
+    let mut start = 0;
+    let mut quotes = Quotes::default();
+    let mut depth = 0usize;
+
+    for (index, c) in text.char_indices() {
+        if quotes.within(c) {
+            continue;
+        }
+
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                push(start, index);
+                return Some((arguments, index));
+            }
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                push(start, index);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds<'i>(statement: &Statement<'i>) -> Vec<(TokenKind, &'i str)> {
+        statement
+            .arguments()
+            .iter()
+            .map(|argument| (argument.kind(), argument.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn statements() {
+        let statements = parse_statements("blur 1;\n\n  resize 10 20;").unwrap();
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].name().as_str(), "blur");
+        assert_eq!(statements[1].name().as_str(), "resize");
+        assert_eq!(statements[1].name().span().line_col(), (3, 3));
+        assert_eq!(
+            kinds(&statements[1]),
+            vec![(TokenKind::Word, "10"), (TokenKind::Word, "20")]
+        );
+    }
+
+    #[test]
+    fn arguments() {
+        let statements = parse_statements(
+            "draw-text 'a; b' rgba(0, 0, 0, 255) ($width - 10) min($width, 1)*2 | \"c\\\"\"",
+        )
+        .unwrap();
+
+        assert_eq!(
+            kinds(&statements[0]),
+            vec![
+                (TokenKind::String, "'a; b'"),
+                (TokenKind::NamedValue, "rgba(0, 0, 0, 255)"),
+                (TokenKind::Word, "($width - 10)"),
+                (TokenKind::Word, "min($width, 1)*2"),
+                (TokenKind::RowSeparator, "|"),
+                (TokenKind::String, "\"c\\\"\""),
+            ]
+        );
+        assert_eq!(statements[0].arguments()[0].contents(), "a; b");
+    }
+
+    #[test]
+    fn named_value_arguments() {
+        let statements =
+            parse_statements("shadow stop(0.5,  rgba(1, 2, 3, 4)) font('a, b')").unwrap();
+        let stop = statements[0].arguments()[0].inner();
+
+        assert_eq!(stop.len(), 3);
+        assert_eq!(stop[0].as_str(), "stop");
+        assert_eq!(stop[1].as_str(), "0.5");
+        assert_eq!(stop[2].kind(), TokenKind::NamedValue);
+        assert_eq!(stop[2].inner()[4].as_str(), "4");
+        assert_eq!(stop[2].inner()[4].span().line_col(), (1, 33));
+
+        let font = statements[0].arguments()[1].inner();
+        assert_eq!(font[1].kind(), TokenKind::String);
+        assert_eq!(font[1].contents(), "a, b");
+    }
+
+    #[parameterized(
+        input = {
+            "",
+            "  \n",
+            "invert",
+            "invert;",
+            "invert\n\n",
+            "blur 1;brighten 2; ",
+            "blur 1;\r\nbrighten 2",
+        },
+        expected = { 0, 0, 1, 1, 1, 2, 2 }
+    )]
+    fn statements_ok(input: &str, expected: usize) {
+        assert_eq!(parse_statements(input).unwrap().len(), expected);
+    }
+
+    #[parameterized(input = {
+        "blur 4\nblur 3",
+        "blur 1;;",
+        "; blur 1",
+        "diff 'a.png",
+        "diff 'a.png'b",
+        "resize ($width 10",
+        "'blur' 1",
+        "blur 15.",
+        "crop 10.% 10% 90% 90%",
+    })]
+    fn statements_err(input: &str) {
+        assert!(matches!(
+            parse_statements(input),
+            Err(SicParserError::SyntaxError(..))
+        ));
+    }
+
+    #[test]
+    fn error_position() {
+        let err = parse_statements("blur 1;\nresize 10 20\ninvert").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "unable to parse script: expected a ';' between statements at line 3, column 1"
+        );
+    }
+}
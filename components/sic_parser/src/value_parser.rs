@@ -1,43 +1,108 @@
 use crate::errors::SicParserError;
+use crate::named_value::NamedValue;
+use sic_core::image::Rgba;
+use sic_image_engine::wrapper::adaptive_threshold_method::AdaptiveThresholdMethod;
+use sic_image_engine::wrapper::aspect_ratio::AspectRatio;
+use sic_image_engine::wrapper::blend_mode::BlendMode;
+use sic_image_engine::wrapper::blur_algorithm::BlurAlgorithm;
+use sic_image_engine::wrapper::border::BorderInputs;
+use sic_image_engine::wrapper::channel::Channel;
+use sic_image_engine::wrapper::chroma_key::ChromaKeyInputs;
+use sic_image_engine::wrapper::contours::{ContoursInputs, CONTOURS_DEFAULT_COLOR};
+use sic_image_engine::wrapper::dimension::Dimension;
+use sic_image_engine::wrapper::extend::ExtendInputs;
 use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
+use sic_image_engine::wrapper::find::FindInputs;
+use sic_image_engine::wrapper::gradient::{ColorStop, Gradient};
+use sic_image_engine::wrapper::gradient_fill::{
+    GradientFillInputs, GradientKind, GRADIENT_DEFAULT_ANGLE,
+};
+use sic_image_engine::wrapper::gravity::Gravity;
+use sic_image_engine::wrapper::histogram::{
+    DrawHistogramInputs, HistogramChannels, HistogramPlacement, HISTOGRAM_DEFAULT_CHANNELS,
+    HISTOGRAM_DEFAULT_PLACEMENT,
+};
 use sic_image_engine::wrapper::image_path::ImageFromPath;
-use std::convert::TryFrom;
+use sic_image_engine::wrapper::kernel::{Kernel, KernelFromPath};
+use sic_image_engine::wrapper::lut::LutFromPath;
+use sic_image_engine::wrapper::mirror::MirrorMode;
+use sic_image_engine::wrapper::nine_slice::NineSliceInputs;
+use sic_image_engine::wrapper::redact::{RedactInputs, RedactStyle, REDACT_DEFAULT_STYLE};
+use sic_image_engine::wrapper::region::Region;
+use sic_image_engine::wrapper::stamp::StampInputs;
+use sic_image_engine::wrapper::tonemap::{
+    ToneMapInputs, ToneMapOperator, TONEMAP_DEFAULT_EXPOSURE,
+};
 use std::path::PathBuf;
 
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::draw_corners::{DrawCornersInputs, DRAW_CORNERS_DEFAULT_COLOR};
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::draw_shape::{
+    DrawEllipseInputs, DrawLineInputs, DrawPolygonInputs, DrawRectInputs, ShapeStyle,
+    SHAPE_DEFAULT_STYLE,
+};
 #[cfg(feature = "imageproc-ops")]
 use sic_image_engine::wrapper::draw_text_inner::DrawTextInner;
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::font_options::{
+    FontOptions, FontScale, FontSource, FontStyle, TextAlign, TextOutline, TextShadow,
+};
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::noise::{NoiseInputs, NoiseKind};
 use sic_image_engine::wrapper::overlay::OverlayInputs;
+use sic_image_engine::wrapper::pad_to_ratio::PadToRatioInputs;
+#[cfg(feature = "imageproc-ops")]
+use sic_image_engine::wrapper::perspective::PerspectiveInputs;
+use sic_image_engine::wrapper::pixel_upscale::{
+    PixelUpscaleAlgorithm, PixelUpscaleInputs, PIXEL_UPSCALE_DEFAULT_ALGORITHM,
+};
 
 /// The value parser module has a goal to parse image operation inputs.
 
+/// A value, and whether it's a word of a script, e.g. `logo.png`, rather than a string of a
+/// script, e.g. `'logo.png'`, or an argument given on the command line.
 #[derive(Clone, Debug)]
-pub struct Describable<'a>(&'a str);
+pub struct Describable<'a>(&'a str, bool);
+
+impl<'a> Describable<'a> {
+    pub(crate) fn word(v: &'a str) -> Self {
+        Describable(v, true)
+    }
+
+    // within a script, a string must be enclosed in quotation marks
+    fn string(self) -> Result<&'a str, SicParserError> {
+        if self.1 {
+            Err(SicParserError::UnquotedString(self.0.to_string()))
+        } else {
+            Ok(self.0)
+        }
+    }
+}
 
 impl<'a> From<&'a &'a str> for Describable<'a> {
     fn from(v: &'a &'a str) -> Self {
-        Describable(v)
+        Describable(v, false)
     }
 }
 
 impl<'a> From<&'a str> for Describable<'a> {
     fn from(v: &'a str) -> Self {
-        Describable(v)
+        Describable(v, false)
     }
 }
 
 impl<'a> From<&'a String> for Describable<'a> {
     fn from(v: &'a String) -> Self {
-        Describable(v.as_str())
+        Describable(v.as_str(), false)
     }
 }
 
-/// This iteration of the parse trait should help with unifying the
-/// Pest parsing and cli ops arguments directly from &str.
+/// This iteration of the parse trait unifies the parsing of the arguments of scripts and
+/// of cli ops, which are both given as &str values.
 ///
-/// From Pest we receive iterators, but we can use as_str, to request &str values.
-/// We'll try to use this to map the received values as_str, and then we'll have
-/// a similar structure as the image operation arguments from the cli (we receive these
-/// eventually as Vec<&str>, thus iterable.
+/// The arguments of a statement of a script are split by the syntax module, and those of
+/// the cli ops by the shell (we receive these eventually as Vec<&str>, thus iterable).
 pub trait ParseInputsFromIter {
     type Error;
 
@@ -52,7 +117,9 @@ macro_rules! parse_next {
     ($iter:expr, $ty:ty, $err_msg:expr) => {
         $iter
             .next()
-            .ok_or_else(|| SicParserError::ValueParsingError($err_msg.to_string()))
+            .ok_or_else(|| {
+                SicParserError::MissingArgument(format!("a value of type '{}'", stringify!($ty)))
+            })
             .and_then(|v| {
                 let v: Describable = v.into();
                 v.0.parse::<$ty>().map_err(|err| {
@@ -61,7 +128,7 @@ macro_rules! parse_next {
                         Box::new(err),
                     )
                 })
-            })?;
+            })?
     };
 }
 
@@ -99,9 +166,26 @@ macro_rules! define_parse_single_input {
 
 define_parse_single_input!(f32, "Unable to map a value to f32. v2");
 define_parse_single_input!(i32, "Unable to map a value to i32. v2");
+define_parse_single_input!(u8, "Unable to map a value to u8. v2");
 define_parse_single_input!(u32, "Unable to map a value to u32. v2");
 define_parse_single_input!(bool, "Unable to map a value to bool. v2");
 
+// for: operations without arguments, e.g. invert
+impl ParseInputsFromIter for () {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        return_if_complete!(iter, ())
+    }
+}
+
 const fn too_many_arguments_err_msg() -> &'static str {
     "Too many arguments found for image operation"
 }
@@ -165,7 +249,67 @@ impl ParseInputsFromIter for [f32; 9] {
     }
 }
 
-// for: resize
+// for: crop, of which the inputs may be percentages or expressions
+impl ParseInputsFromIter for [Dimension; 4] {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let res: [Dimension; 4] = [
+            next_dimension(&mut iter)?,
+            next_dimension(&mut iter)?,
+            next_dimension(&mut iter)?,
+            next_dimension(&mut iter)?,
+        ];
+
+        return_if_complete!(iter, res)
+    }
+}
+
+// for: resize, of which the inputs may be percentages or expressions
+impl ParseInputsFromIter for [Dimension; 2] {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let res: [Dimension; 2] = [next_dimension(&mut iter)?, next_dimension(&mut iter)?];
+
+        return_if_complete!(iter, res)
+    }
+}
+
+fn next_dimension<'a, I>(iter: &mut I) -> Result<Dimension, SicParserError>
+where
+    I: Iterator,
+    I::Item: Into<Describable<'a>>,
+{
+    let value: Describable = iter
+        .next()
+        .ok_or_else(|| {
+            SicParserError::MissingArgument(
+                "a number of pixels, a percentage or an expression".to_string(),
+            )
+        })?
+        .into();
+
+    Dimension::try_from_str(value.0).map_err(|err| {
+        SicParserError::ValueParsingErrorWithInnerError(value.0.to_string(), Box::new(err))
+    })
+}
+
+// for: crop-center and smart-crop
 impl ParseInputsFromIter for (u32, u32) {
     type Error = SicParserError;
 
@@ -187,6 +331,49 @@ impl ParseInputsFromIter for (u32, u32) {
     }
 }
 
+// for: duotone
+impl ParseInputsFromIter for (Rgba<u8>, Rgba<u8>) {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let shadows = parse_next!(iter, NamedValue, "Rgba")
+            .extract_rgba()
+            .map_err(SicParserError::NamedValueParsingError)?;
+        let highlights = parse_next!(iter, NamedValue, "Rgba")
+            .extract_rgba()
+            .map_err(SicParserError::NamedValueParsingError)?;
+
+        return_if_complete!(iter, (Rgba(shadows), Rgba(highlights)))
+    }
+}
+
+// for: the background modifier
+impl ParseInputsFromIter for Rgba<u8> {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let color = parse_next!(iter, NamedValue, "Rgba")
+            .extract_rgba()
+            .map_err(SicParserError::NamedValueParsingError)?;
+
+        return_if_complete!(iter, Rgba(color))
+    }
+}
+
 // for: unsharpen
 impl ParseInputsFromIter for (f32, i32) {
     type Error = SicParserError;
@@ -209,6 +396,118 @@ impl ParseInputsFromIter for (f32, i32) {
     }
 }
 
+// for: grayscale-weighted
+impl ParseInputsFromIter for (f32, f32, f32) {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (f32, f32, f32). v2";
+
+        let res: (f32, f32, f32) = (
+            parse_next!(iter, f32, ERR_MSG),
+            parse_next!(iter, f32, ERR_MSG),
+            parse_next!(iter, f32, ERR_MSG),
+        );
+
+        return_if_complete!(iter, res)
+    }
+}
+
+// for: sharpen, which takes an optional amount
+impl ParseInputsFromIter for Option<f32> {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter().peekable();
+
+        if iter.peek().is_none() {
+            return Ok(None);
+        }
+
+        let value = parse_next!(iter, f32, "Unable to map a value to f32. v2");
+
+        return_if_complete!(iter, Some(value))
+    }
+}
+
+// for: label-components, which takes an optional connectivity
+impl ParseInputsFromIter for Option<u32> {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter().peekable();
+
+        if iter.peek().is_none() {
+            return Ok(None);
+        }
+
+        let value = parse_next!(iter, u32, "Unable to map a value to u32. v2");
+
+        return_if_complete!(iter, Some(value))
+    }
+}
+
+// for: clahe
+impl ParseInputsFromIter for (u32, f32) {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u32, f32). v2";
+
+        let res: (u32, f32) = (
+            parse_next!(iter, u32, ERR_MSG),
+            parse_next!(iter, f32, ERR_MSG),
+        );
+
+        return_if_complete!(iter, res)
+    }
+}
+
+// for: radial-blur
+impl ParseInputsFromIter for (u32, u32, f32) {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u32, u32, f32). v2";
+
+        let res: (u32, u32, f32) = (
+            parse_next!(iter, u32, ERR_MSG),
+            parse_next!(iter, u32, ERR_MSG),
+            parse_next!(iter, f32, ERR_MSG),
+        );
+
+        return_if_complete!(iter, res)
+    }
+}
+
 impl ParseInputsFromIter for String {
     type Error = SicParserError;
 
@@ -247,6 +546,71 @@ impl ParseInputsFromIter for ImageFromPath {
     }
 }
 
+impl ParseInputsFromIter for LutFromPath {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let path = parse_to_path_buf(iter.next().map(Into::<Describable>::into))?;
+
+        return_if_complete!(iter, LutFromPath::new(path))
+    }
+}
+
+// for: convolve; the size of the kernel, followed by its weights, row by row
+impl ParseInputsFromIter for Kernel {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str =
+            "Unable to map a value to a kernel; expected a u32 size followed by f32 weights. v2";
+
+        let size = parse_next!(iter, u32, ERR_MSG);
+        let mut values = Vec::new();
+
+        while let Some(value) = iter.next().map(Into::<Describable>::into) {
+            let value = value.0.parse::<f32>().map_err(|err| {
+                SicParserError::ValueParsingErrorWithInnerError(ERR_MSG.to_string(), Box::new(err))
+            })?;
+
+            values.push(value);
+        }
+
+        Kernel::new(size, values).map_err(|err| {
+            SicParserError::ValueParsingErrorWithInnerError(size.to_string(), Box::new(err))
+        })
+    }
+}
+
+impl ParseInputsFromIter for KernelFromPath {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let path = parse_to_path_buf(iter.next().map(Into::<Describable>::into))?;
+
+        return_if_complete!(iter, KernelFromPath::new(path))
+    }
+}
+
 impl ParseInputsFromIter for OverlayInputs {
     type Error = SicParserError;
 
@@ -258,6 +622,9 @@ impl ParseInputsFromIter for OverlayInputs {
     {
         let mut iter = iterable.into_iter();
         let image_path = parse_to_path_buf(iter.next().map(Into::<Describable>::into))?;
+        let mut iter = iter
+            .map(|item| Into::<Describable>::into(item).0)
+            .peekable();
 
         let position: (u32, u32) = (
             parse_next!(
@@ -272,12 +639,70 @@ impl ParseInputsFromIter for OverlayInputs {
             ),
         );
 
-        let overlay_inputs = OverlayInputs::new(ImageFromPath::new(image_path), position);
+        let mut overlay_inputs = OverlayInputs::new(ImageFromPath::new(image_path), position);
+
+        if iter
+            .peek()
+            .is_some_and(|value| value.parse::<f32>().is_ok())
+        {
+            overlay_inputs = overlay_inputs.with_opacity(parse_next!(
+                iter,
+                f32,
+                "opacity value for overlay should be a number"
+            ));
+        }
+
+        if iter.peek().is_some() {
+            let blend_mode: BlendMode = ParseInputsFromIter::parse(iter.next())?;
+            overlay_inputs = overlay_inputs.with_blend_mode(blend_mode);
+        }
 
         return_if_complete!(iter, overlay_inputs)
     }
 }
 
+impl ParseInputsFromIter for PadToRatioInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0);
+
+        let ratio = iter
+            .next()
+            .ok_or_else(|| {
+                SicParserError::ValueParsingError(
+                    "An aspect ratio was expected but none was found.".to_string(),
+                )
+            })
+            .and_then(|ratio| {
+                AspectRatio::try_from_str(ratio).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(
+                        ratio.to_string(),
+                        Box::new(err),
+                    )
+                })
+            })?;
+
+        let color = match iter.next() {
+            Some(color) => color
+                .parse::<NamedValue>()
+                .and_then(|value| value.extract_rgba())
+                .map(|color| Some(Rgba(color)))
+                .map_err(SicParserError::NamedValueParsingError)?,
+            None => None,
+        };
+
+        return_if_complete!(iter, PadToRatioInputs::new(ratio, color))
+    }
+}
+
 impl ParseInputsFromIter for FilterTypeWrap {
     type Error = SicParserError;
 
@@ -304,187 +729,2511 @@ impl ParseInputsFromIter for FilterTypeWrap {
     }
 }
 
-fn parse_to_path_buf(value: Option<Describable>) -> Result<PathBuf, SicParserError> {
-    let err_msg_no_such_element = || "A path was expected but none was found.".to_string();
-    let err_msg_invalid_path =
-        || "Unable to construct a valid path for the current platform.".to_string();
-
-    value
-        .ok_or_else(|| SicParserError::ValueParsingError(err_msg_no_such_element()))
-        .and_then(|v: Describable| {
-            PathBuf::try_from(v.0)
-                .map_err(|_| SicParserError::ValueParsingError(err_msg_invalid_path()))
-        })
-}
+impl ParseInputsFromIter for AdaptiveThresholdMethod {
+    type Error = SicParserError;
 
-#[cfg(feature = "imageproc-ops")]
-impl ParseInputsFromIter for DrawTextInner {
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let err_msg_no_such_element =
+            || "An adaptive threshold method was expected but none was found.".to_string();
+
+        let method = iter
+            .next()
+            .map(Into::<Describable>::into)
+            .ok_or_else(|| SicParserError::ValueParsingError(err_msg_no_such_element()))
+            .and_then(|v: Describable| {
+                AdaptiveThresholdMethod::try_from_str(v.0).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(v.0.to_string(), Box::new(err))
+                })
+            })?;
+
+        return_if_complete!(iter, method)
+    }
+}
+
+impl ParseInputsFromIter for BlurAlgorithm {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let err_msg_no_such_element =
+            || "A blur algorithm was expected but none was found.".to_string();
+
+        let algorithm = iter
+            .next()
+            .map(Into::<Describable>::into)
+            .ok_or_else(|| SicParserError::ValueParsingError(err_msg_no_such_element()))
+            .and_then(|v: Describable| {
+                BlurAlgorithm::try_from_str(v.0).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(v.0.to_string(), Box::new(err))
+                })
+            })?;
+
+        return_if_complete!(iter, algorithm)
+    }
+}
+
+// for: border, which takes 1 to 4 widths followed by a color
+impl ParseInputsFromIter for BorderInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut values = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0)
+            .collect::<Vec<_>>();
+
+        let color = values
+            .pop()
+            .ok_or_else(|| SicParserError::ValueParsingError("Rgba".to_string()))?
+            .parse::<NamedValue>()
+            .and_then(|value| value.extract_rgba())
+            .map_err(SicParserError::NamedValueParsingError)?;
+
+        let widths = values
+            .into_iter()
+            .map(|width| {
+                width.parse::<u32>().map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(
+                        "width value for border should be a natural number".to_string(),
+                        Box::new(err),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, SicParserError>>()?;
+
+        BorderInputs::new(&widths, Rgba(color)).map_err(|err| {
+            SicParserError::ValueParsingErrorWithInnerError(
+                "invalid border widths".to_string(),
+                Box::new(err),
+            )
+        })
+    }
+}
+
+impl ParseInputsFromIter for Region {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let (x, y, width, height) = iter
+            .next()
+            .map(Into::<Describable>::into)
+            .ok_or_else(|| {
+                SicParserError::ValueParsingError(
+                    "A region (rect(x, y, width, height)) was expected but none was found."
+                        .to_string(),
+                )
+            })
+            .and_then(|v: Describable| {
+                v.0.parse::<NamedValue>()
+                    .and_then(|value| value.extract_rect())
+                    .map_err(SicParserError::NamedValueParsingError)
+            })?;
+
+        return_if_complete!(iter, Region::new((x, y), (width, height)))
+    }
+}
+
+impl ParseInputsFromIter for RedactInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0);
+
+        let (x, y, width, height) = iter
+            .next()
+            .ok_or_else(|| {
+                SicParserError::ValueParsingError(
+                    "A region to redact (rect(x, y, width, height)) was expected but none was found."
+                        .to_string(),
+                )
+            })
+            .and_then(|v| {
+                v.parse::<NamedValue>()
+                    .and_then(|value| value.extract_rect())
+                    .map_err(SicParserError::NamedValueParsingError)
+            })?;
+
+        let style = match iter.next() {
+            Some(v) => RedactStyle::try_from_str(v).map_err(|err| {
+                SicParserError::ValueParsingErrorWithInnerError(v.to_string(), Box::new(err))
+            })?,
+            None => REDACT_DEFAULT_STYLE,
+        };
+
+        return_if_complete!(
+            iter,
+            RedactInputs::new(Region::new((x, y), (width, height)), style)
+        )
+    }
+}
+
+impl ParseInputsFromIter for ChromaKeyInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let color = parse_next!(iter, NamedValue, "Rgba")
+            .extract_rgba()
+            .map_err(SicParserError::NamedValueParsingError)?;
+        let tolerance = parse_next!(
+            iter,
+            f32,
+            "tolerance value for chroma-key should be a number"
+        );
+        let feather = parse_next!(iter, f32, "feather value for chroma-key should be a number");
+
+        let inputs = ChromaKeyInputs::new(sic_core::image::Rgba(color), tolerance, feather);
+
+        return_if_complete!(iter, inputs)
+    }
+}
+
+// for: contours, which takes an optional color for the outlines
+impl ParseInputsFromIter for ContoursInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let threshold = parse_next!(
+            iter,
+            u8,
+            "threshold value for contours should be a natural number between 0 and 255"
+        );
+
+        let color = match iter.next().map(Into::<Describable>::into) {
+            Some(color) => color
+                .0
+                .parse::<NamedValue>()
+                .and_then(|value| value.extract_rgba())
+                .map(Rgba)
+                .map_err(SicParserError::NamedValueParsingError)?,
+            None => CONTOURS_DEFAULT_COLOR,
+        };
+
+        return_if_complete!(iter, ContoursInputs::new(threshold, color))
+    }
+}
+
+impl ParseInputsFromIter for Gradient {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let stops = iterable
+            .into_iter()
+            .map(|item| {
+                let stop: Describable = item.into();
+                let (position, color) = stop
+                    .0
+                    .parse::<NamedValue>()
+                    .and_then(|value| value.extract_stop())
+                    .map_err(SicParserError::NamedValueParsingError)?;
+
+                Ok(ColorStop::new(position, Rgba(color)))
+            })
+            .collect::<Result<Vec<_>, SicParserError>>()?;
+
+        if stops.len() < 2 {
+            return Err(SicParserError::ValueParsingError(
+                "A gradient map requires at least two color stops.".to_string(),
+            ));
+        }
+
+        Ok(Gradient::new(stops))
+    }
+}
+
+// The angle is optional, and may only be given for linear gradients.
+impl ParseInputsFromIter for GradientFillInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0)
+            .peekable();
+
+        let kind = iter
+            .next()
+            .ok_or_else(|| {
+                SicParserError::ValueParsingError(
+                    "A kind of gradient ('linear' or 'radial') was expected but none was found."
+                        .to_string(),
+                )
+            })
+            .and_then(|v| {
+                GradientKind::try_from_str(v).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(v.to_string(), Box::new(err))
+                })
+            })?;
+
+        let mut color = || {
+            iter.next()
+                .ok_or_else(|| {
+                    SicParserError::ValueParsingError(
+                        "Two colors were expected for the gradient.".to_string(),
+                    )
+                })
+                .and_then(|v| {
+                    v.parse::<NamedValue>()
+                        .and_then(|value| value.extract_rgba())
+                        .map(Rgba)
+                        .map_err(SicParserError::NamedValueParsingError)
+                })
+        };
+        let colors = (color()?, color()?);
+
+        let angle = match (kind, iter.peek()) {
+            (_, None) => GRADIENT_DEFAULT_ANGLE,
+            (GradientKind::Linear, Some(_)) => {
+                parse_next!(iter, f32, "angle value for gradient should be a number")
+            }
+            (GradientKind::Radial, Some(_)) => {
+                return Err(SicParserError::ValueParsingError(
+                    "An angle can only be given for a linear gradient.".to_string(),
+                ))
+            }
+        };
+
+        return_if_complete!(iter, GradientFillInputs::new(kind, colors, angle))
+    }
+}
+
+// The exposure is optional.
+impl ParseInputsFromIter for ToneMapInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0)
+            .peekable();
+
+        let operator = iter
+            .next()
+            .ok_or_else(|| {
+                SicParserError::ValueParsingError(
+                    "A tone mapping operator ('reinhard' or 'aces') was expected but none was found."
+                        .to_string(),
+                )
+            })
+            .and_then(|v| {
+                ToneMapOperator::try_from_str(v).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(v.to_string(), Box::new(err))
+                })
+            })?;
+
+        let exposure = match iter.peek() {
+            Some(_) => parse_next!(iter, f32, "exposure value for tonemap should be a number"),
+            None => TONEMAP_DEFAULT_EXPOSURE,
+        };
+
+        return_if_complete!(iter, ToneMapInputs::new(operator, exposure))
+    }
+}
+
+// The gravity and the color are both optional; a given color is recognized as a named value.
+impl ParseInputsFromIter for ExtendInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0)
+            .peekable();
+
+        let width = parse_next!(
+            iter,
+            u32,
+            "width value for extend should be a natural number"
+        );
+        let height = parse_next!(
+            iter,
+            u32,
+            "height value for extend should be a natural number"
+        );
+
+        let gravity = match iter.next_if(|value| value.parse::<NamedValue>().is_err()) {
+            Some(gravity) => Some(ParseInputsFromIter::parse(&[gravity])?),
+            None => None,
+        };
+
+        let color = match iter.next() {
+            Some(color) => color
+                .parse::<NamedValue>()
+                .and_then(|value| value.extract_rgba())
+                .map(|color| Some(Rgba(color)))
+                .map_err(SicParserError::NamedValueParsingError)?,
+            None => None,
+        };
+
+        return_if_complete!(iter, ExtendInputs::new((width, height), gravity, color))
+    }
+}
+
+impl ParseInputsFromIter for Gravity {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let err_msg_no_such_element = || "A gravity was expected but none was found.".to_string();
+
+        let gravity = iter
+            .next()
+            .map(Into::<Describable>::into)
+            .ok_or_else(|| SicParserError::ValueParsingError(err_msg_no_such_element()))
+            .and_then(|v: Describable| {
+                Gravity::try_from_str(v.0).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(v.0.to_string(), Box::new(err))
+                })
+            })?;
+
+        return_if_complete!(iter, gravity)
+    }
+}
+
+impl ParseInputsFromIter for Channel {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let err_msg_no_such_element = || "A channel was expected but none was found.".to_string();
+
+        let channel = iter
+            .next()
+            .map(Into::<Describable>::into)
+            .ok_or_else(|| SicParserError::ValueParsingError(err_msg_no_such_element()))
+            .and_then(|v: Describable| {
+                Channel::try_from_str(v.0).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(v.0.to_string(), Box::new(err))
+                })
+            })?;
+
+        return_if_complete!(iter, channel)
+    }
+}
+
+impl ParseInputsFromIter for MirrorMode {
     type Error = SicParserError;
 
-    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
-    where
-        T: IntoIterator,
-        T::Item: Into<Describable<'a>> + std::fmt::Debug,
-        Self: std::marker::Sized,
-    {
-        use crate::named_value::NamedValue;
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let err_msg_no_such_element =
+            || "A mirror mode was expected but none was found.".to_string();
+
+        let mode = iter
+            .next()
+            .map(Into::<Describable>::into)
+            .ok_or_else(|| SicParserError::ValueParsingError(err_msg_no_such_element()))
+            .and_then(|v: Describable| {
+                MirrorMode::try_from_str(v.0).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(v.0.to_string(), Box::new(err))
+                })
+            })?;
+
+        return_if_complete!(iter, mode)
+    }
+}
+
+// Both the channels and the placement are optional; if only one value is given, it's the
+// placement when it isn't a kind of channels.
+impl ParseInputsFromIter for DrawHistogramInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0)
+            .peekable();
+
+        let inner_err = |value: &str, err| {
+            SicParserError::ValueParsingErrorWithInnerError(value.to_string(), Box::new(err))
+        };
+
+        let channels = match iter.next_if(|v| HistogramPlacement::try_from_str(v).is_err()) {
+            Some(v) => HistogramChannels::try_from_str(v).map_err(|err| inner_err(v, err))?,
+            None => HISTOGRAM_DEFAULT_CHANNELS,
+        };
+
+        let placement = match iter.next() {
+            Some(v) => HistogramPlacement::try_from_str(v).map_err(|err| inner_err(v, err))?,
+            None => HISTOGRAM_DEFAULT_PLACEMENT,
+        };
+
+        return_if_complete!(iter, DrawHistogramInputs::new(channels, placement))
+    }
+}
+
+// for: pixel-upscale, which takes an optional algorithm
+impl ParseInputsFromIter for PixelUpscaleInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter().peekable();
+
+        let factor = parse_next!(
+            iter,
+            u32,
+            "factor value for pixel-upscale should be a natural number"
+        );
+
+        let algorithm = match iter.next().map(Into::<Describable>::into) {
+            Some(v) => PixelUpscaleAlgorithm::try_from_str(v.0).map_err(|err| {
+                SicParserError::ValueParsingErrorWithInnerError(v.0.to_string(), Box::new(err))
+            })?,
+            None => PIXEL_UPSCALE_DEFAULT_ALGORITHM,
+        };
+
+        return_if_complete!(iter, PixelUpscaleInputs::new(factor, algorithm))
+    }
+}
+
+impl ParseInputsFromIter for BlendMode {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let err_msg_no_such_element =
+            || "A blend mode was expected but none was found.".to_string();
+
+        let mode = iter
+            .next()
+            .map(Into::<Describable>::into)
+            .ok_or_else(|| SicParserError::ValueParsingError(err_msg_no_such_element()))
+            .and_then(|v: Describable| {
+                BlendMode::try_from_str(v.0).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(v.0.to_string(), Box::new(err))
+                })
+            })?;
+
+        return_if_complete!(iter, mode)
+    }
+}
+
+// for: nine-slice, which takes the left, top, right and bottom insets, and the output size
+impl ParseInputsFromIter for NineSliceInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG_INSET: &str = "inset value for nine-slice should be a natural number";
+        const ERR_MSG_SIZE: &str = "output size value for nine-slice should be a natural number";
+
+        let insets = (
+            parse_next!(iter, u32, ERR_MSG_INSET),
+            parse_next!(iter, u32, ERR_MSG_INSET),
+            parse_next!(iter, u32, ERR_MSG_INSET),
+            parse_next!(iter, u32, ERR_MSG_INSET),
+        );
+        let size = (
+            parse_next!(iter, u32, ERR_MSG_SIZE),
+            parse_next!(iter, u32, ERR_MSG_SIZE),
+        );
+
+        return_if_complete!(iter, NineSliceInputs::new(insets, size))
+    }
+}
+
+// for: channel swap
+impl ParseInputsFromIter for (Channel, Channel) {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let lhs: Channel = ParseInputsFromIter::parse(iter.next())?;
+        let rhs: Channel = ParseInputsFromIter::parse(iter.next())?;
+
+        return_if_complete!(iter, (lhs, rhs))
+    }
+}
+
+// for: crop gravity
+impl ParseInputsFromIter for (Gravity, u32, u32) {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let gravity: Gravity = ParseInputsFromIter::parse(iter.next())?;
+        let width = parse_next!(iter, u32, "width value for crop should be a natural number");
+        let height = parse_next!(
+            iter,
+            u32,
+            "height value for crop should be a natural number"
+        );
+
+        return_if_complete!(iter, (gravity, width, height))
+    }
+}
+
+impl ParseInputsFromIter for StampInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        let image_path = parse_to_path_buf(iter.next().map(Into::<Describable>::into))?;
+        let gravity: Gravity = ParseInputsFromIter::parse(iter.next())?;
+        let margin = parse_next!(
+            iter,
+            u32,
+            "margin value for stamp should be a natural number"
+        );
+
+        let stamp_inputs = StampInputs::new(ImageFromPath::new(image_path), gravity, margin);
+
+        return_if_complete!(iter, stamp_inputs)
+    }
+}
+
+impl ParseInputsFromIter for FindInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        let template = parse_to_path_buf(iter.next().map(Into::<Describable>::into))?;
+        let mut iter = iter.map(|item| Into::<Describable>::into(item).0);
+
+        let outline = match iter.next() {
+            Some(color) => Some(
+                color
+                    .parse::<NamedValue>()
+                    .and_then(|value| value.extract_rgba())
+                    .map(Rgba)
+                    .map_err(SicParserError::NamedValueParsingError)?,
+            ),
+            None => None,
+        };
+
+        return_if_complete!(iter, FindInputs::new(ImageFromPath::new(template), outline))
+    }
+}
+
+fn parse_to_path_buf(value: Option<Describable>) -> Result<PathBuf, SicParserError> {
+    let err_msg_no_such_element = || "A path was expected but none was found.".to_string();
+
+    value
+        .ok_or_else(|| SicParserError::ValueParsingError(err_msg_no_such_element()))
+        .and_then(Describable::string)
+        .map(PathBuf::from)
+}
+
+#[cfg(feature = "imageproc-ops")]
+impl ParseInputsFromIter for DrawTextInner {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let text = iter
+            .next()
+            .map(Into::<Describable>::into)
+            .ok_or_else(|| SicParserError::ExpectedValue(String::from("String")))?
+            .string()?;
+        let coord = parse_next!(iter, NamedValue, "Coord");
+        let color = parse_next!(iter, NamedValue, "Rgba");
+        let size = parse_next!(iter, NamedValue, "Float");
+        let font = parse_next!(iter, NamedValue, "String");
+
+        // any remaining named values decorate the text, e.g. outline(..) or shadow(..)
+        let extras = iter
+            .map(|item| {
+                let value: Describable = item.into();
+                value
+                    .0
+                    .parse::<NamedValue>()
+                    .map_err(SicParserError::NamedValueParsingError)
+            })
+            .collect::<Result<Vec<_>, SicParserError>>()?;
+
+        Ok(DrawTextInner::new(
+            unescape_text(text),
+            (coord.extract_coord()).map_err(SicParserError::NamedValueParsingError)?,
+            parse_font_options(&color, &size, &font, &extras)?,
+        ))
+    }
+}
+
+// for: draw shape operations, which are either filled, or outlined with a stroke of a given width
+#[cfg(feature = "imageproc-ops")]
+impl ParseInputsFromIter for ShapeStyle {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let style = iter
+            .next()
+            .map(|v| Into::<Describable>::into(v).0)
+            .ok_or_else(|| {
+                SicParserError::ValueParsingError(
+                    "A shape style ('filled' or 'stroke <width>') was expected but none was found."
+                        .to_string(),
+                )
+            })?;
+
+        let style = if style.eq_ignore_ascii_case("filled") {
+            ShapeStyle::Filled
+        } else if style.eq_ignore_ascii_case("stroke") {
+            ShapeStyle::Stroke(parse_next!(
+                iter,
+                u32,
+                "stroke width of a shape should be a natural number"
+            ))
+        } else {
+            return Err(SicParserError::ValueParsingError(format!(
+                "Unknown shape style '{}', expected 'filled' or 'stroke <width>'",
+                style
+            )));
+        };
+
+        return_if_complete!(iter, style)
+    }
+}
+
+// for: draw-rect, which takes a rect and rgba named value, and an optional shape style
+#[cfg(feature = "imageproc-ops")]
+impl ParseInputsFromIter for DrawRectInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0);
+
+        let rect = iter
+            .next()
+            .ok_or_else(|| SicParserError::ValueParsingError("Rect".to_string()))?
+            .parse::<NamedValue>()
+            .and_then(|value| value.extract_rect())
+            .map_err(SicParserError::NamedValueParsingError)?;
+
+        let color = iter
+            .next()
+            .ok_or_else(|| SicParserError::ValueParsingError("Rgba".to_string()))?
+            .parse::<NamedValue>()
+            .and_then(|value| value.extract_rgba())
+            .map_err(SicParserError::NamedValueParsingError)?;
+
+        let style = iter.collect::<Vec<_>>();
+        let style = if style.is_empty() {
+            SHAPE_DEFAULT_STYLE
+        } else {
+            ParseInputsFromIter::parse(&style)?
+        };
+
+        Ok(DrawRectInputs::new(rect, Rgba(color), style))
+    }
+}
+
+// for: draw-ellipse, which takes a coord named value, two radii, an rgba named value, and an
+// optional shape style
+#[cfg(feature = "imageproc-ops")]
+impl ParseInputsFromIter for DrawEllipseInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0);
+
+        let center = iter
+            .next()
+            .ok_or_else(|| SicParserError::ValueParsingError("Coord".to_string()))?
+            .parse::<NamedValue>()
+            .and_then(|value| value.extract_coord())
+            .map_err(SicParserError::NamedValueParsingError)?;
+
+        const ERR_MSG: &str = "radius of an ellipse should be a natural number";
+        let radii = (
+            parse_next!(iter, u32, ERR_MSG),
+            parse_next!(iter, u32, ERR_MSG),
+        );
+
+        let color = iter
+            .next()
+            .ok_or_else(|| SicParserError::ValueParsingError("Rgba".to_string()))?
+            .parse::<NamedValue>()
+            .and_then(|value| value.extract_rgba())
+            .map_err(SicParserError::NamedValueParsingError)?;
+
+        let style = iter.collect::<Vec<_>>();
+        let style = if style.is_empty() {
+            SHAPE_DEFAULT_STYLE
+        } else {
+            ParseInputsFromIter::parse(&style)?
+        };
+
+        Ok(DrawEllipseInputs::new(center, radii, Rgba(color), style))
+    }
+}
+
+// for: draw-polygon, which takes three or more coord named values, an rgba named value, and an
+// optional shape style
+#[cfg(feature = "imageproc-ops")]
+impl ParseInputsFromIter for DrawPolygonInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable
+            .into_iter()
+            .map(|item| Into::<Describable>::into(item).0)
+            .peekable();
+
+        let mut points = Vec::new();
+        while let Some(point) = iter.next_if(|value| {
+            value
+                .parse::<NamedValue>()
+                .is_ok_and(|value| value.extract_coord().is_ok())
+        }) {
+            points.push(
+                point
+                    .parse::<NamedValue>()
+                    .and_then(|value| value.extract_coord())
+                    .map_err(SicParserError::NamedValueParsingError)?,
+            );
+        }
+
+        let color = iter
+            .next()
+            .ok_or_else(|| SicParserError::ValueParsingError("Rgba".to_string()))?
+            .parse::<NamedValue>()
+            .and_then(|value| value.extract_rgba())
+            .map_err(SicParserError::NamedValueParsingError)?;
+
+        let style = iter.collect::<Vec<_>>();
+        let style = if style.is_empty() {
+            SHAPE_DEFAULT_STYLE
+        } else {
+            ParseInputsFromIter::parse(&style)?
+        };
+
+        DrawPolygonInputs::new(points, Rgba(color), style).map_err(|err| {
+            SicParserError::ValueParsingErrorWithInnerError(
+                "invalid polygon points".to_string(),
+                Box::new(err),
+            )
+        })
+    }
+}
+
+// for: draw-line and draw-arrow, which take two coord and an rgba named value, and a width
+#[cfg(feature = "imageproc-ops")]
+impl ParseInputsFromIter for DrawLineInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let mut named_value = |what: &str| -> Result<NamedValue, SicParserError> {
+            iter.next()
+                .map(|v| Into::<Describable>::into(v).0)
+                .ok_or_else(|| SicParserError::ValueParsingError(what.to_string()))?
+                .parse::<NamedValue>()
+                .map_err(SicParserError::NamedValueParsingError)
+        };
+
+        let from = named_value("Coord")?
+            .extract_coord()
+            .map_err(SicParserError::NamedValueParsingError)?;
+        let to = named_value("Coord")?
+            .extract_coord()
+            .map_err(SicParserError::NamedValueParsingError)?;
+        let color = named_value("Rgba")?
+            .extract_rgba()
+            .map_err(SicParserError::NamedValueParsingError)?;
+
+        let width = parse_next!(iter, u32, "width of a line should be a natural number");
+
+        return_if_complete!(iter, DrawLineInputs::new(from, to, Rgba(color), width))
+    }
+}
+
+// for: noise, which takes an optional seed
+#[cfg(feature = "imageproc-ops")]
+impl ParseInputsFromIter for NoiseInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter().peekable();
+
+        let err_msg_no_such_element =
+            || "A kind of noise was expected but none was found.".to_string();
+
+        let kind = iter
+            .next()
+            .map(Into::<Describable>::into)
+            .ok_or_else(|| SicParserError::ValueParsingError(err_msg_no_such_element()))
+            .and_then(|v: Describable| {
+                NoiseKind::try_from_str(v.0).map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(v.0.to_string(), Box::new(err))
+                })
+            })?;
+        let amount = parse_next!(iter, f32, "amount value for noise should be a number");
+
+        let seed = if iter.peek().is_some() {
+            Some(parse_next!(
+                iter,
+                u64,
+                "seed value for noise should be a natural number"
+            ))
+        } else {
+            None
+        };
+
+        return_if_complete!(iter, NoiseInputs::new(kind, amount, seed))
+    }
+}
+
+// for: draw-corners, which takes an optional color for the markers
+#[cfg(feature = "imageproc-ops")]
+impl ParseInputsFromIter for DrawCornersInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+
+        let threshold = parse_next!(
+            iter,
+            u8,
+            "threshold value for draw-corners should be a natural number between 0 and 255"
+        );
+
+        let color = match iter.next().map(Into::<Describable>::into) {
+            Some(color) => color
+                .0
+                .parse::<NamedValue>()
+                .and_then(|value| value.extract_rgba())
+                .map(Rgba)
+                .map_err(SicParserError::NamedValueParsingError)?,
+            None => DRAW_CORNERS_DEFAULT_COLOR,
+        };
+
+        return_if_complete!(iter, DrawCornersInputs::new(threshold, color))
+    }
+}
+
+// for: perspective, which takes the x and y coordinates of four corners
+#[cfg(feature = "imageproc-ops")]
+impl ParseInputsFromIter for PerspectiveInputs {
+    type Error = SicParserError;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "coordinate value for perspective should be a number";
+
+        let mut corner = || -> Result<(f32, f32), SicParserError> {
+            Ok((
+                parse_next!(iter, f32, ERR_MSG),
+                parse_next!(iter, f32, ERR_MSG),
+            ))
+        };
+
+        let corners = [corner()?, corner()?, corner()?, corner()?];
+
+        return_if_complete!(iter, PerspectiveInputs::new(corners))
+    }
+}
+
+/// Creates the font options of draw-text from its `rgba`, `size` and `font` named values, and
+/// the optional `outline`, `shadow`, `spacing`, `angle`, `wrap` and `align` named values which may
+/// follow them, in any order.
+#[cfg(feature = "imageproc-ops")]
+pub(crate) fn parse_font_options(
+    color: &NamedValue,
+    size: &NamedValue,
+    font: &NamedValue,
+    extras: &[NamedValue],
+) -> Result<FontOptions, SicParserError> {
+    let (font, style) = font
+        .extract_font()
+        .map_err(SicParserError::NamedValueParsingError)?;
+    let source = FontSource::from_name(font);
+
+    let style = match (style, &source) {
+        (None, _) => FontStyle::default(),
+        (Some(style), FontSource::Family(_)) => FontStyle::try_from_str(style).map_err(|err| {
+            SicParserError::ValueParsingErrorWithInnerError(style.to_string(), Box::new(err))
+        })?,
+        (Some(_), FontSource::File(_)) => {
+            return Err(SicParserError::ValueParsingError(format!(
+                "a font style can only be selected for a font family, but '{}' is a font file",
+                font
+            )))
+        }
+    };
+
+    let size = size
+        .extract_size()
+        .map_err(SicParserError::NamedValueParsingError)?;
+    if !(size > 0.0 && size.is_finite()) {
+        return Err(SicParserError::ValueParsingError(format!(
+            "draw-text expects a font size larger than 0, but size({}) was given",
+            size
+        )));
+    }
+
+    let options = FontOptions::new(
+        source,
+        Rgba(
+            color
+                .extract_rgba()
+                .map_err(SicParserError::NamedValueParsingError)?,
+        ),
+        FontScale::Uniform(size),
+    );
+
+    for (i, extra) in extras.iter().enumerate() {
+        let discriminant = std::mem::discriminant(extra);
+
+        if extras[..i]
+            .iter()
+            .any(|previous| std::mem::discriminant(previous) == discriminant)
+        {
+            return Err(SicParserError::ValueParsingError(format!(
+                "draw-text accepts each of outline(..), shadow(..), spacing(..), angle(..), \
+                 wrap(..) and align(..) at most once, but found a second {:?}",
+                extra
+            )));
+        }
+    }
+
+    extras
+        .iter()
+        .try_fold(options.with_style(style), |options, extra| match extra {
+            NamedValue::Outline(width, color) => Ok(options.with_outline(TextOutline {
+                width: *width,
+                color: Rgba(*color),
+            })),
+            NamedValue::Shadow(offset, color) => Ok(options.with_shadow(TextShadow {
+                offset: *offset,
+                color: Rgba(*color),
+            })),
+            NamedValue::Spacing(letter, line) => Ok(options.with_spacing(*letter, *line)),
+            NamedValue::Angle(degrees) => Ok(options.with_angle(*degrees)),
+            NamedValue::Wrap(width) => Ok(options.with_wrap_width(*width)),
+            NamedValue::Align(align) => TextAlign::try_from_str(align)
+                .map(|align| options.with_align(align))
+                .map_err(|err| {
+                    SicParserError::ValueParsingErrorWithInnerError(
+                        align.to_string(),
+                        Box::new(err),
+                    )
+                }),
+            _ => Err(SicParserError::ValueParsingError(format!(
+                "draw-text only accepts outline(..), shadow(..), spacing(..), angle(..), wrap(..) \
+                 and align(..) after its font, but found {:?}",
+                extra
+            ))),
+        })
+}
+
+/// Replaces the escape sequences of a text to draw: `\n` by a line break, and like within any
+/// string, `\\`, `\'` and `\"` by the backslash or quotation mark. Other backslashes are kept as
+/// they are.
+#[cfg(feature = "imageproc-ops")]
+pub(crate) fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                unescaped.push('\n');
+            }
+            ('\\', Some(&next)) if next == '\\' || next == '\'' || next == '"' => {
+                chars.next();
+                unescaped.push(next);
+            }
+            (c, _) => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests_parse_from_iter {
+    use super::*;
+    use sic_testing::*;
+
+    macro_rules! assert_iter_impl {
+        ($lhs_iter:expr, $rhs_iter:expr, $f:expr) => {
+            assert!($lhs_iter.into_iter().zip($rhs_iter.into_iter()).all($f));
+        };
+    }
+
+    macro_rules! assert_iter_f32 {
+        ($lhs_iter:expr, $rhs_iter:expr) => {
+            assert_iter_impl!($lhs_iter, $rhs_iter, |(l, r)| (l - r).abs()
+                < std::f32::EPSILON);
+        };
+    }
+
+    #[test]
+    fn a_f32() {
+        let some: f32 = ParseInputsFromIter::parse(&["-1.03"]).unwrap();
+        sic_testing::approx_eq_f32!(some, -1.03f32)
+    }
+
+    mod a_u8 {
+        use super::*;
+
+        #[test]
+        fn a_u8() {
+            let some: u8 = ParseInputsFromIter::parse(&["255"]).unwrap();
+            assert_eq!(some, 255u8);
+        }
+
+        #[pm(input = {
+            &["256"],       // out of range
+            &["-1"],        // negative
+            &["1", "2"],    // too many arguments
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<u8, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod tuple_u32_u32_u32_u32 {
+        use super::*;
+
+        #[test]
+        fn should_succeed_with() {
+            let some: (u32, u32, u32, u32) =
+                ParseInputsFromIter::parse(&["03579", "0", "1", "1"]).unwrap();
+            assert_eq!(some, (3579u32, 0u32, 1u32, 1u32));
+        }
+
+        #[pm(input = {
+            &["-4", "3", "2", "1"],         // &[a, _b, _c, _d]: a not u32 (neg)
+            &["4", "-3", "2", "1"],         // &[_a, b, _c, _d]: b not u32 (neg)
+            &["4", "3", "-2", "1"],         // &[_a, _b, c, _d]: c not u32 (neg)
+            &["4", "3", "2", "-1"],         // &[_a, _b, _c, d]: d not u32 (neg)
+            &["4", "3", "2", "o"],          // &[..., x]: x not f32 (not a number)
+            &["4", "3", "2"],               // len() == 4 expected
+            &["4", "3", "2", "1", "0"],     // len() == 4 expected
+            &[],                            // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<(u32, u32, u32, u32), SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod array_f32x9 {
+        use super::*;
+
+        #[test]
+        fn array_of_f32() {
+            let some: [f32; 9] = ParseInputsFromIter::parse(&[
+                "1", "2", "3", "4", "5.5", "-6.0", "7", "8", "-9.9999",
+            ])
+            .unwrap();
+            const EXPECTED: [f32; 9] = [
+                1f32, 2f32, 3f32, 4f32, 5.5f32, -6.0f32, 7f32, 8f32, -9.9999f32,
+            ];
+
+            assert_iter_f32!(&some, &EXPECTED);
+        }
+
+        #[pm(input = {
+            &["1", "2", "3", "4", "5.5", "-6.0", "7", "8", "lalala"],               // &[..., x]: x not f32 (not a number)
+            &["1", "2", "3", "4", "5.5", "-6.0", "7", "8"],                         // len() == 9 expected
+            &["1", "2", "3", "4", "5.5", "-6.0", "7", "8", "-9.9999", "1"],         // len() == 9 expected
+            &[],                                                                    // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<[f32; 9], SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err())
+        }
+    }
+
+    mod tuple_u32_u32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_u32() {
+            let some: (u32, u32) = ParseInputsFromIter::parse(&["03579", "0"]).unwrap();
+            assert_eq!(some, (3579u32, 0u32))
+        }
+
+        #[pm(input = {
+            &["4", "-0"],   // [_x, y]: y not u32 (neg 0)
+            &["4", "-4"],   // [_x, y]: y not u32 (neg number)
+            &["4", "f"],    // [_x, y]: y not u32 (not a number)
+            &["03579"],     // len() == 2 expected
+            &[],            // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<(u32, u32), SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod tuple_f32_i32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_f32_i32() {
+            let some: (f32, i32) = ParseInputsFromIter::parse(&["-03579.1", "-0"]).unwrap();
+            assert_eq!(some, (-3579.1f32, 0i32))
+        }
+
+        #[pm(input = {
+            &["-f", "-1"],      // [x, _y]: x not f32
+            &["-1.0", "f"],     // [_x, y]: y not i32
+            &["4"],             // len() == 2 expected
+            &["4", "4", "4"],   // len() == 2 expected
+            &[],                // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<(f32, i32), SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod tuple_f32_f32_f32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_f32_f32_f32() {
+            let some: (f32, f32, f32) =
+                ParseInputsFromIter::parse(&["0.2126", "0.7152", "-1"]).unwrap();
+            assert_eq!(some, (0.2126f32, 0.7152f32, -1f32))
+        }
+
+        #[pm(input = {
+            &["f", "1", "1"],           // [x, _y, _z]: x not f32
+            &["1", "1", "f"],           // [_x, _y, z]: z not f32
+            &["1", "1"],                // len() == 3 expected
+            &["1", "1", "1", "1"],      // len() == 3 expected
+            &[],                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<(f32, f32, f32), SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod option_f32 {
+        use super::*;
+
+        #[pm(input = { &[], &["0.5"] }, expected = { None, Some(0.5) })]
+        fn an_optional_f32(input: &[&str], expected: Option<f32>) {
+            let some: Option<f32> = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["f"],             // not f32
+            &["1", "1"],        // len() <= 1 expected
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<Option<f32>, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod option_u32 {
+        use super::*;
+
+        #[pm(input = { &[], &["4"] }, expected = { None, Some(4) })]
+        fn an_optional_u32(input: &[&str], expected: Option<u32>) {
+            let some: Option<u32> = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["-1"],            // not u32
+            &["0.5"],           // not u32
+            &["4", "8"],        // len() <= 1 expected
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<Option<u32>, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod tuple_u32_f32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_f32() {
+            let some: (u32, f32) = ParseInputsFromIter::parse(&["08", "2.5"]).unwrap();
+            assert_eq!(some, (8u32, 2.5f32))
+        }
+
+        #[pm(input = {
+            &["-8", "2.5"],     // [x, _y]: x not u32
+            &["8", "f"],        // [_x, y]: y not f32
+            &["8"],             // len() == 2 expected
+            &["8", "2", "2"],   // len() == 2 expected
+            &[],                // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<(u32, f32), SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod tuple_u32_u32_f32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_u32_f32() {
+            let some: (u32, u32, f32) = ParseInputsFromIter::parse(&["10", "20", "0.5"]).unwrap();
+            assert_eq!(some, (10u32, 20u32, 0.5f32))
+        }
+
+        #[pm(input = {
+            &["-1", "20", "0.5"],       // [x, _y, _z]: x not u32
+            &["10", "2.5", "0.5"],      // [_x, y, _z]: y not u32
+            &["10", "20", "f"],         // [_x, _y, z]: z not f32
+            &["10", "20"],              // len() == 3 expected
+            &["10", "20", "1", "1"],    // len() == 3 expected
+            &[],                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<(u32, u32, f32), SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod gravity {
+        use super::*;
+
+        #[test]
+        fn a_gravity() {
+            let some: Gravity = ParseInputsFromIter::parse(&["bottom-right"]).unwrap();
+            assert_eq!(some, Gravity::BottomRight)
+        }
+
+        #[pm(input = {
+            &["up"],                    // unknown gravity
+            &["top", "left"],           // len() == 1 expected
+            &[],                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<Gravity, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod tuple_gravity_u32_u32 {
+        use super::*;
+
+        #[test]
+        fn a_gravity_with_size() {
+            let some: (Gravity, u32, u32) =
+                ParseInputsFromIter::parse(&["top-right", "100", "50"]).unwrap();
+            assert_eq!(some, (Gravity::TopRight, 100, 50))
+        }
+
+        #[pm(input = {
+            &["100", "50"],                     // gravity missing
+            &["center", "100"],                 // height missing
+            &["center", "100", "-50"],          // height not u32
+            &["center", "100", "50", "1"],      // too many arguments
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<(Gravity, u32, u32), SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod mirror_mode {
+        use super::*;
+
+        #[test]
+        fn mirror_mode() {
+            let some: MirrorMode = ParseInputsFromIter::parse(&["quad"]).unwrap();
+            assert_eq!(some, MirrorMode::Quad)
+        }
+
+        #[pm(input = {
+            &["both"],                      // unknown mode
+            &["horizontal", "vertical"],    // len() == 1 expected
+            &[],                            // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<MirrorMode, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod draw_histogram_inputs {
+        use super::*;
+
+        #[pm(
+            input = { &[], &["overlay"], &["luma"], &["rgb", "append"], &["RGB", "Overlay"] },
+            expected = {
+                DrawHistogramInputs::new(HistogramChannels::Luma, HistogramPlacement::Append),
+                DrawHistogramInputs::new(HistogramChannels::Luma, HistogramPlacement::Overlay),
+                DrawHistogramInputs::new(HistogramChannels::Luma, HistogramPlacement::Append),
+                DrawHistogramInputs::new(HistogramChannels::Rgb, HistogramPlacement::Append),
+                DrawHistogramInputs::new(HistogramChannels::Rgb, HistogramPlacement::Overlay),
+            }
+        )]
+        fn draw_histogram_inputs(input: &[&str], expected: DrawHistogramInputs) {
+            let some: DrawHistogramInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["hsv"],                           // unknown channels
+            &["luma", "below"],                 // unknown placement
+            &["overlay", "luma"],               // channels come first
+            &["luma", "overlay", "append"],     // too many arguments
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<DrawHistogramInputs, SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod pixel_upscale_inputs {
+        use super::*;
+
+        #[pm(
+            input = { &["2"], &["3", "scale2x"], &["4", "SCALE2X"] },
+            expected = {
+                PixelUpscaleInputs::new(2, PixelUpscaleAlgorithm::Scale2x),
+                PixelUpscaleInputs::new(3, PixelUpscaleAlgorithm::Scale2x),
+                PixelUpscaleInputs::new(4, PixelUpscaleAlgorithm::Scale2x),
+            }
+        )]
+        fn pixel_upscale_inputs(input: &[&str], expected: PixelUpscaleInputs) {
+            let some: PixelUpscaleInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &[],                            // empty
+            &["2x"],                        // not a number
+            &["2", "xbr"],                  // unknown algorithm
+            &["2", "hqx"],                  // unsupported algorithm
+            &["scale2x", "2"],              // factor comes first
+            &["2", "scale2x", "scale2x"],   // too many arguments
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<PixelUpscaleInputs, SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod region {
+        use super::*;
+
+        #[test]
+        fn region() {
+            let some: Region = ParseInputsFromIter::parse(&["rect(1, 2, 30, 40)"]).unwrap();
+            assert_eq!(some, Region::new((1, 2), (30, 40)))
+        }
+
+        #[pm(input = {
+            &["coord(1, 2)"],                               // not a rect
+            &["1", "2", "30", "40"],                        // not a named value
+            &["rect(1, 2, 30, 40)", "rect(1, 2, 30, 40)"],  // len() == 1 expected
+            &[],                                            // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<Region, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod redact_inputs {
+        use super::*;
+
+        #[pm(
+            input = { &["rect(1, 2, 30, 40)"], &["rect(1, 2, 30, 40)", "fill"], &["rect(1, 2, 30, 40)", "Pixelate"] },
+            expected = {
+                RedactInputs::new(Region::new((1, 2), (30, 40)), RedactStyle::Fill),
+                RedactInputs::new(Region::new((1, 2), (30, 40)), RedactStyle::Fill),
+                RedactInputs::new(Region::new((1, 2), (30, 40)), RedactStyle::Pixelate),
+            }
+        )]
+        fn redact_inputs(input: &[&str], expected: RedactInputs) {
+            let some: RedactInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &[],                                        // empty
+            &["coord(1, 2)"],                           // not a rect
+            &["rect(1, 2, 30, 40)", "blur"],            // unknown style
+            &["pixelate", "rect(1, 2, 30, 40)"],        // region comes first
+            &["rect(1, 2, 30, 40)", "fill", "fill"],    // too many arguments
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<RedactInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod nine_slice_inputs {
+        use super::*;
+
+        #[test]
+        fn nine_slice_inputs() {
+            let some: NineSliceInputs =
+                ParseInputsFromIter::parse(&["1", "2", "3", "4", "100", "50"]).unwrap();
+            assert_eq!(some, NineSliceInputs::new((1, 2, 3, 4), (100, 50)))
+        }
+
+        #[pm(input = {
+            &["1", "2", "3", "4", "100"],               // len() == 6 expected
+            &["1", "2", "3", "4", "100", "50", "1"],    // len() == 6 expected
+            &["1", "2", "-3", "4", "100", "50"],        // not u32
+            &["1", "2", "3", "4", "100", "50.5"],       // not u32
+            &[],                                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<NineSliceInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod channel {
+        use super::*;
+
+        #[test]
+        fn channel() {
+            let some: Channel = ParseInputsFromIter::parse(&["g"]).unwrap();
+            assert_eq!(some, Channel::Green)
+        }
+
+        #[test]
+        fn channel_pair() {
+            let some: (Channel, Channel) = ParseInputsFromIter::parse(&["red", "a"]).unwrap();
+            assert_eq!(some, (Channel::Red, Channel::Alpha))
+        }
+
+        #[pm(input = {
+            &["x"],             // unknown channel
+            &["r", "g"],        // len() == 1 expected
+            &[],                // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<Channel, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+
+        #[pm(input = {
+            &["r", "x"],        // unknown channel
+            &["r"],             // len() == 2 expected
+            &["r", "g", "b"],   // len() == 2 expected
+        })]
+        fn expected_failures_pair(input: &[&str]) {
+            let result: Result<(Channel, Channel), SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod stamp_inputs {
+        use super::*;
+
+        #[test]
+        fn stamp_inputs() {
+            let some: StampInputs =
+                ParseInputsFromIter::parse(&["logo.png", "top-left", "16"]).unwrap();
+            assert_eq!(
+                some,
+                StampInputs::new(ImageFromPath::new("logo.png".into()), Gravity::TopLeft, 16)
+            )
+        }
+
+        #[pm(input = {
+            &["logo.png", "up", "16"],              // unknown gravity
+            &["logo.png", "top", "-1"],             // margin not u32
+            &["logo.png", "top"],                   // len() == 3 expected
+            &["logo.png", "top", "1", "1"],         // len() == 3 expected
+            &[],                                    // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<StampInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod find_inputs {
+        use super::*;
+
+        #[pm(
+            input = {
+                &["button.png"],
+                &["button.png", "rgba(255, 0, 0, 255)"],
+            },
+            expected = {
+                FindInputs::new(ImageFromPath::new("button.png".into()), None),
+                FindInputs::new(ImageFromPath::new("button.png".into()), Some(Rgba([255, 0, 0, 255]))),
+            }
+        )]
+        fn find_inputs(input: &[&str], expected: FindInputs) {
+            let some: FindInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["button.png", "coord(1, 1)"],                 // not a color
+            &["button.png", "rgba(255, 0, 0, 255)", "1"],   // too many arguments
+            &[],                                            // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<FindInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod overlay_inputs {
+        use super::*;
+
+        fn inputs(pos: (u32, u32)) -> OverlayInputs {
+            OverlayInputs::new(ImageFromPath::new("logo.png".into()), pos)
+        }
+
+        #[pm(
+            input = {
+                &["logo.png", "1", "2"],
+                &["logo.png", "1", "2", "0.5"],
+                &["logo.png", "1", "2", "screen"],
+                &["logo.png", "1", "2", "0.25", "Multiply"],
+            },
+            expected = {
+                inputs((1, 2)),
+                inputs((1, 2)).with_opacity(0.5),
+                inputs((1, 2)).with_blend_mode(BlendMode::Screen),
+                inputs((1, 2)).with_opacity(0.25).with_blend_mode(BlendMode::Multiply),
+            }
+        )]
+        fn overlay_inputs(input: &[&str], expected: OverlayInputs) {
+            let some: OverlayInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["logo.png", "1", "-2"],                   // y not u32
+            &["logo.png", "1", "2", "add"],             // unknown blend mode
+            &["logo.png", "1", "2", "screen", "0.5"],   // opacity before blend mode expected
+            &["logo.png", "1", "2", "1", "screen", "1"],// len() <= 5 expected
+            &["logo.png", "1"],                         // len() >= 3 expected
+            &[],                                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<OverlayInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod blend_mode {
+        use super::*;
+
+        #[test]
+        fn blend_mode() {
+            let some: BlendMode = ParseInputsFromIter::parse(&["lighten"]).unwrap();
+            assert_eq!(some, BlendMode::Lighten)
+        }
+
+        #[pm(input = {
+            &["burn"],                  // unknown mode
+            &["darken", "lighten"],     // len() == 1 expected
+            &[],                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<BlendMode, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod lut_from_path {
+        use super::*;
+
+        #[test]
+        fn lut_from_path() {
+            let some: LutFromPath = ParseInputsFromIter::parse(&["look.cube"]).unwrap();
+            assert_eq!(some, LutFromPath::new("look.cube".into()))
+        }
+
+        #[pm(input = {
+            &["look.cube", "other.cube"],   // len() == 1 expected
+            &[],                            // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<LutFromPath, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod kernel {
+        use super::*;
+
+        #[test]
+        fn a_kernel() {
+            let some: Kernel =
+                ParseInputsFromIter::parse(&["3", "0", "-1", "0", "-1", "5", "-1", "0", "-1", "0"])
+                    .unwrap();
+            assert_eq!(
+                some,
+                Kernel::new(3, vec![0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0]).unwrap()
+            )
+        }
+
+        #[pm(input = {
+            &["1", "f"],                // weight not f32
+            &["-1", "1"],               // size not u32
+            &["2", "1", "1", "1", "1"], // size not odd
+            &["3", "1", "1"],           // len() == 1 + 3 * 3 expected
+            &["1", "1", "1"],           // len() == 1 + 1 * 1 expected
+            &["1"],                     // no weights
+            &[],                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<Kernel, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod kernel_from_path {
+        use super::*;
+
+        #[test]
+        fn kernel_from_path() {
+            let some: KernelFromPath = ParseInputsFromIter::parse(&["kernel.txt"]).unwrap();
+            assert_eq!(some, KernelFromPath::new("kernel.txt".into()))
+        }
+
+        #[pm(input = {
+            &["kernel.txt", "other.txt"],   // len() == 1 expected
+            &[],                            // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<KernelFromPath, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod border_inputs {
+        use super::*;
+
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+        #[pm(
+            input = {
+                &["2", "rgba(255, 0, 0, 255)"],
+                &["2", "4", "rgba(255, 0, 0, 255)"],
+                &["1", "2", "3", "4", "rgba(255, 0, 0, 255)"],
+            },
+            expected = {
+                BorderInputs::new(&[2], RED).unwrap(),
+                BorderInputs::new(&[2, 4], RED).unwrap(),
+                BorderInputs::new(&[1, 2, 3, 4], RED).unwrap(),
+            }
+        )]
+        fn border_inputs(input: &[&str], expected: BorderInputs) {
+            let some: BorderInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["rgba(255, 0, 0, 255)"],                          // a width is required
+            &["1", "2", "3", "4", "5", "rgba(255, 0, 0, 255)"], // at most 4 widths
+            &["-1", "rgba(255, 0, 0, 255)"],                    // width not u32
+            &["1", "2"],                                        // color missing
+            &[],                                                // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<BorderInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod gradient_fill_inputs {
+        use super::*;
+
+        const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+        const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+        #[pm(
+            input = {
+                &["linear", "rgba(0, 0, 0, 255)", "rgba(255, 255, 255, 255)"],
+                &["linear", "rgba(0, 0, 0, 255)", "rgba(255, 255, 255, 255)", "-45.5"],
+                &["Radial", "rgba(255, 255, 255, 255)", "rgba(0, 0, 0, 255)"],
+            },
+            expected = {
+                GradientFillInputs::new(GradientKind::Linear, (BLACK, WHITE), 0.0),
+                GradientFillInputs::new(GradientKind::Linear, (BLACK, WHITE), -45.5),
+                GradientFillInputs::new(GradientKind::Radial, (WHITE, BLACK), 0.0),
+            }
+        )]
+        fn gradient_fill_inputs(input: &[&str], expected: GradientFillInputs) {
+            let some: GradientFillInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &[],                                                                        // empty
+            &["conic", "rgba(0, 0, 0, 255)", "rgba(255, 255, 255, 255)"],               // unknown kind
+            &["linear", "rgba(0, 0, 0, 255)"],                                          // one color
+            &["linear", "rgba(0, 0, 0, 255)", "coord(1, 1)"],                           // not a color
+            &["linear", "rgba(0, 0, 0, 255)", "rgba(255, 255, 255, 255)", "steep"],     // angle not f32
+            &["radial", "rgba(0, 0, 0, 255)", "rgba(255, 255, 255, 255)", "45"],        // angle of radial
+            &["linear", "rgba(0, 0, 0, 255)", "rgba(255, 255, 255, 255)", "45", "1"],   // too many arguments
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<GradientFillInputs, SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod tonemap_inputs {
+        use super::*;
+
+        #[pm(
+            input = {
+                &["reinhard"],
+                &["ACES", "-1.5"],
+                &["aces", "2"],
+            },
+            expected = {
+                ToneMapInputs::new(ToneMapOperator::Reinhard, TONEMAP_DEFAULT_EXPOSURE),
+                ToneMapInputs::new(ToneMapOperator::Aces, -1.5),
+                ToneMapInputs::new(ToneMapOperator::Aces, 2.0),
+            }
+        )]
+        fn tonemap_inputs(input: &[&str], expected: ToneMapInputs) {
+            let some: ToneMapInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &[],                        // empty
+            &["filmic"],                // unknown operator
+            &["aces", "bright"],        // exposure not f32
+            &["aces", "1", "2"],        // too many arguments
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<ToneMapInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod extend_inputs {
+        use super::*;
+
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+        #[pm(
+            input = {
+                &["640", "480"],
+                &["640", "480", "top-left"],
+                &["640", "480", "rgba(255, 0, 0, 255)"],
+                &["640", "480", "bottom", "rgba(255, 0, 0, 255)"],
+            },
+            expected = {
+                ExtendInputs::new((640, 480), None, None),
+                ExtendInputs::new((640, 480), Some(Gravity::TopLeft), None),
+                ExtendInputs::new((640, 480), None, Some(RED)),
+                ExtendInputs::new((640, 480), Some(Gravity::Bottom), Some(RED)),
+            }
+        )]
+        fn extend_inputs(input: &[&str], expected: ExtendInputs) {
+            let some: ExtendInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["640"],                                                  // height missing
+            &["640", "-480"],                                          // height not u32
+            &["640", "480", "up"],                                     // unknown gravity
+            &["640", "480", "coord(1, 1)"],                            // not a color
+            &["640", "480", "rgba(255, 0, 0, 255)", "bottom"],         // gravity comes first
+            &["640", "480", "bottom", "rgba(255, 0, 0, 255)", "1"],    // too many arguments
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<ExtendInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod pad_to_ratio_inputs {
+        use super::*;
+
+        fn ratio(val: &str) -> AspectRatio {
+            AspectRatio::try_from_str(val).unwrap()
+        }
+
+        #[pm(
+            input = {
+                &["16:9"],
+                &["4:3", "rgba(255, 0, 0, 255)"],
+            },
+            expected = {
+                PadToRatioInputs::new(ratio("16:9"), None),
+                PadToRatioInputs::new(ratio("4:3"), Some(Rgba([255, 0, 0, 255]))),
+            }
+        )]
+        fn pad_to_ratio_inputs(input: &[&str], expected: PadToRatioInputs) {
+            let some: PadToRatioInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["16/9"],                                  // not a ratio
+            &["0:9"],                                   // empty ratio
+            &["16:9", "coord(1, 1)"],                   // not a color
+            &["16:9", "rgba(255, 0, 0, 255)", "1"],     // too many arguments
+            &[],                                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<PadToRatioInputs, SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod chroma_key_inputs {
+        use super::*;
         use sic_core::image::Rgba;
-        use sic_image_engine::wrapper::font_options::{FontOptions, FontScale};
 
-        let mut iter = iterable.into_iter();
+        #[test]
+        fn chroma_key_inputs() {
+            let some: ChromaKeyInputs =
+                ParseInputsFromIter::parse(&["rgba(0, 255, 0, 255)", "60", "7.5"]).unwrap();
+            assert_eq!(
+                some,
+                ChromaKeyInputs::new(Rgba([0, 255, 0, 255]), 60.0, 7.5)
+            )
+        }
 
-        let text = iter
-            .next()
-            .map(Into::<Describable>::into)
-            .ok_or_else(|| SicParserError::ExpectedValue(String::from("String")))?
-            .0;
-        let coord = parse_next!(iter, NamedValue, "Coord");
-        let color = parse_next!(iter, NamedValue, "Rgba");
-        let size = parse_next!(iter, NamedValue, "Float");
-        let font_file = parse_next!(iter, NamedValue, "String");
+        #[pm(input = {
+            &["rgba(0, 255, 0)", "60", "10"],           // rgba requires 4 values
+            &["coord(0, 255)", "60", "10"],             // not an rgba value
+            &["rgba(0, 255, 0, 255)", "f", "10"],       // tolerance not f32
+            &["rgba(0, 255, 0, 255)", "60"],            // len() == 3 expected
+            &["rgba(0, 255, 0, 255)", "60", "1", "1"],  // len() == 3 expected
+            &[],                                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<ChromaKeyInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
 
-        let res = DrawTextInner::new(
-            text.to_string(),
-            (coord.extract_coord()).map_err(SicParserError::NamedValueParsingError)?,
-            FontOptions::new(
-                font_file
-                    .extract_font()
-                    .map_err(SicParserError::NamedValueParsingError)?,
-                Rgba(
-                    color
-                        .extract_rgba()
-                        .map_err(SicParserError::NamedValueParsingError)?,
-                ),
-                FontScale::Uniform(
-                    size.extract_size()
-                        .map_err(SicParserError::NamedValueParsingError)?,
-                ),
-            ),
-        );
+    mod contours_inputs {
+        use super::*;
 
-        return_if_complete!(iter, res)
+        #[pm(
+            input = {
+                &["128"],
+                &["0", "rgba(255, 0, 0, 255)"],
+            },
+            expected = {
+                ContoursInputs::new(128, Rgba([0, 255, 0, 255])),
+                ContoursInputs::new(0, Rgba([255, 0, 0, 255])),
+            }
+        )]
+        fn contours_inputs(input: &[&str], expected: ContoursInputs) {
+            let some: ContoursInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["256"],                                   // not u8
+            &["0.5"],                                   // not u8
+            &["128", "size(1)"],                        // not a color
+            &["128", "rgba(255, 0, 0, 255)", "1"],      // too many arguments
+            &[],                                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<ContoursInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
     }
-}
 
-#[cfg(test)]
-mod tests_parse_from_iter {
-    use super::*;
-    use sic_testing::*;
+    #[cfg(feature = "imageproc-ops")]
+    mod draw_rect_inputs {
+        use super::*;
 
-    macro_rules! assert_iter_impl {
-        ($lhs_iter:expr, $rhs_iter:expr, $f:expr) => {
-            assert!($lhs_iter.into_iter().zip($rhs_iter.into_iter()).all($f));
-        };
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+        #[pm(
+            input = {
+                &["rect(1, 2, 3, 4)", "rgba(255, 0, 0, 255)"],
+                &["rect(1, 2, 3, 4)", "rgba(255, 0, 0, 255)", "filled"],
+                &["rect(1, 2, 3, 4)", "rgba(255, 0, 0, 255)", "Stroke", "5"],
+            },
+            expected = {
+                DrawRectInputs::new((1, 2, 3, 4), RED, SHAPE_DEFAULT_STYLE),
+                DrawRectInputs::new((1, 2, 3, 4), RED, ShapeStyle::Filled),
+                DrawRectInputs::new((1, 2, 3, 4), RED, ShapeStyle::Stroke(5)),
+            }
+        )]
+        fn draw_rect_inputs(input: &[&str], expected: DrawRectInputs) {
+            let some: DrawRectInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["rect(1, 2, 3, 4)"],                                         // color missing
+            &["rgba(255, 0, 0, 255)", "rect(1, 2, 3, 4)"],                 // wrong order
+            &["rect(1, 2, 3, 4)", "rgba(255, 0, 0, 255)", "stroke"],       // width missing
+            &["rect(1, 2, 3, 4)", "rgba(255, 0, 0, 255)", "stroke", "a"],  // width not u32
+            &["rect(1, 2, 3, 4)", "rgba(255, 0, 0, 255)", "dashed"],       // unknown style
+            &["rect(1, 2, 3, 4)", "rgba(255, 0, 0, 255)", "filled", "1"],  // too many
+            &[],                                                           // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<DrawRectInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
     }
 
-    macro_rules! assert_iter_f32 {
-        ($lhs_iter:expr, $rhs_iter:expr) => {
-            assert_iter_impl!($lhs_iter, $rhs_iter, |(l, r)| (l - r).abs()
-                < std::f32::EPSILON);
-        };
+    #[cfg(feature = "imageproc-ops")]
+    mod draw_ellipse_inputs {
+        use super::*;
+
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+        #[pm(
+            input = {
+                &["coord(1, 2)", "3", "4", "rgba(255, 0, 0, 255)"],
+                &["coord(1, 2)", "3", "4", "rgba(255, 0, 0, 255)", "filled"],
+                &["coord(1, 2)", "3", "4", "rgba(255, 0, 0, 255)", "stroke", "2"],
+            },
+            expected = {
+                DrawEllipseInputs::new((1, 2), (3, 4), RED, SHAPE_DEFAULT_STYLE),
+                DrawEllipseInputs::new((1, 2), (3, 4), RED, ShapeStyle::Filled),
+                DrawEllipseInputs::new((1, 2), (3, 4), RED, ShapeStyle::Stroke(2)),
+            }
+        )]
+        fn draw_ellipse_inputs(input: &[&str], expected: DrawEllipseInputs) {
+            let some: DrawEllipseInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["coord(1, 2)", "3", "rgba(255, 0, 0, 255)"],                    // radius missing
+            &["coord(1, 2)", "3", "-4", "rgba(255, 0, 0, 255)"],              // radius not u32
+            &["rgba(255, 0, 0, 255)", "3", "4", "coord(1, 2)"],               // wrong order
+            &["coord(1, 2)", "3", "4", "rgba(255, 0, 0, 255)", "hollow"],     // unknown style
+            &[],                                                              // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<DrawEllipseInputs, SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
     }
 
-    #[test]
-    fn a_f32() {
-        let some: f32 = ParseInputsFromIter::parse(&["-1.03"]).unwrap();
-        sic_testing::approx_eq_f32!(some, -1.03f32)
+    #[cfg(feature = "imageproc-ops")]
+    mod draw_polygon_inputs {
+        use super::*;
+
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+        #[pm(
+            input = {
+                &["coord(0, 0)", "coord(10, 0)", "coord(0, 10)", "rgba(255, 0, 0, 255)"],
+                &["coord(0, 0)", "coord(10, 0)", "coord(10, 10)", "coord(0, 10)", "rgba(255, 0, 0, 255)", "filled"],
+                &["coord(0, 0)", "coord(10, 0)", "coord(0, 10)", "rgba(255, 0, 0, 255)", "stroke", "3"],
+            },
+            expected = {
+                DrawPolygonInputs::new(vec![(0, 0), (10, 0), (0, 10)], RED, SHAPE_DEFAULT_STYLE).unwrap(),
+                DrawPolygonInputs::new(vec![(0, 0), (10, 0), (10, 10), (0, 10)], RED, ShapeStyle::Filled).unwrap(),
+                DrawPolygonInputs::new(vec![(0, 0), (10, 0), (0, 10)], RED, ShapeStyle::Stroke(3)).unwrap(),
+            }
+        )]
+        fn draw_polygon_inputs(input: &[&str], expected: DrawPolygonInputs) {
+            let some: DrawPolygonInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["coord(0, 0)", "coord(10, 0)", "rgba(255, 0, 0, 255)"],                  // too few points
+            &["coord(0, 0)", "coord(10, 0)", "coord(0, 10)"],                          // color missing
+            &["coord(0, 0)", "coord(10, 0)", "coord(0, 10)", "rgba(255, 0, 0, 255)", "3"], // unknown style
+            &["coord(0, 0)", "coord(10, 0)", "coord(0, 10)", "coord(1)", "rgba(255, 0, 0, 255)"], // not a coord
+            &[],                                                                       // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<DrawPolygonInputs, SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
     }
 
-    mod tuple_u32_u32_u32_u32 {
+    #[cfg(feature = "imageproc-ops")]
+    mod draw_line_inputs {
         use super::*;
 
         #[test]
-        fn should_succeed_with() {
-            let some: (u32, u32, u32, u32) =
-                ParseInputsFromIter::parse(&["03579", "0", "1", "1"]).unwrap();
-            assert_eq!(some, (3579u32, 0u32, 1u32, 1u32));
+        fn draw_line_inputs() {
+            let some: DrawLineInputs = ParseInputsFromIter::parse(&[
+                "coord(1, 2)",
+                "coord(30, 40)",
+                "rgba(255, 0, 0, 255)",
+                "3",
+            ])
+            .unwrap();
+            assert_eq!(
+                some,
+                DrawLineInputs::new((1, 2), (30, 40), Rgba([255, 0, 0, 255]), 3)
+            )
         }
 
         #[pm(input = {
-            &["-4", "3", "2", "1"],         // &[a, _b, _c, _d]: a not u32 (neg)
-            &["4", "-3", "2", "1"],         // &[_a, b, _c, _d]: b not u32 (neg)
-            &["4", "3", "-2", "1"],         // &[_a, _b, c, _d]: c not u32 (neg)
-            &["4", "3", "2", "-1"],         // &[_a, _b, _c, d]: d not u32 (neg)
-            &["4", "3", "2", "o"],          // &[..., x]: x not f32 (not a number)
-            &["4", "3", "2"],               // len() == 4 expected
-            &["4", "3", "2", "1", "0"],     // len() == 4 expected
-            &[],                            // empty
+            &["coord(1, 2)", "coord(30, 40)", "rgba(255, 0, 0, 255)"],             // width missing
+            &["coord(1, 2)", "coord(30, 40)", "rgba(255, 0, 0, 255)", "-1"],       // width not u32
+            &["coord(1, 2)", "rgba(255, 0, 0, 255)", "coord(30, 40)", "1"],        // wrong order
+            &["coord(1, 2)", "coord(30, 40)", "rgba(255, 0, 0, 255)", "1", "1"],   // too many
+            &[],                                                                   // empty
         })]
         fn expected_failures(input: &[&str]) {
-            let result: Result<(u32, u32, u32, u32), SicParserError> =
+            let result: Result<DrawLineInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    #[cfg(feature = "imageproc-ops")]
+    mod draw_corners_inputs {
+        use super::*;
+
+        #[pm(
+            input = {
+                &["40"],
+                &["0", "rgba(0, 255, 0, 255)"],
+            },
+            expected = {
+                DrawCornersInputs::new(40, Rgba([255, 0, 0, 255])),
+                DrawCornersInputs::new(0, Rgba([0, 255, 0, 255])),
+            }
+        )]
+        fn draw_corners_inputs(input: &[&str], expected: DrawCornersInputs) {
+            let some: DrawCornersInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["256"],                                   // not u8
+            &["-1"],                                    // not u8
+            &["40", "coord(1, 1)"],                     // not a color
+            &["40", "rgba(255, 0, 0, 255)", "1"],       // too many arguments
+            &[],                                        // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<DrawCornersInputs, SicParserError> =
                 ParseInputsFromIter::parse(input);
             assert!(result.is_err());
         }
     }
 
-    mod array_f32x9 {
+    #[cfg(feature = "imageproc-ops")]
+    mod draw_text_inner {
         use super::*;
 
+        const FONT: &str = "font('resources/font/Lato-Regular.ttf')";
+
         #[test]
-        fn array_of_f32() {
-            let some: [f32; 9] = ParseInputsFromIter::parse(&[
-                "1", "2", "3", "4", "5.5", "-6.0", "7", "8", "-9.9999",
+        fn wrap_align() {
+            let some: DrawTextInner = ParseInputsFromIter::parse(&[
+                "my\\ntext",
+                "coord(0, 1)",
+                "rgba(10, 10, 255, 255)",
+                "size(16)",
+                FONT,
+                "wrap(80)",
+                "align('right')",
             ])
             .unwrap();
-            const EXPECTED: [f32; 9] = [
-                1f32, 2f32, 3f32, 4f32, 5.5f32, -6.0f32, 7f32, 8f32, -9.9999f32,
-            ];
 
-            assert_iter_f32!(&some, &EXPECTED)
+            let font_options = FontOptions::new(
+                PathBuf::from("resources/font/Lato-Regular.ttf"),
+                Rgba([10, 10, 255, 255]),
+                FontScale::Uniform(16.0),
+            )
+            .with_wrap_width(80.0)
+            .with_align(TextAlign::Right);
+
+            assert_eq!(
+                some,
+                DrawTextInner::new("my\ntext".to_string(), (0, 1), font_options)
+            )
         }
 
         #[pm(input = {
-            &["1", "2", "3", "4", "5.5", "-6.0", "7", "8", "lalala"],               // &[..., x]: x not f32 (not a number)
-            &["1", "2", "3", "4", "5.5", "-6.0", "7", "8"],                         // len() == 9 expected
-            &["1", "2", "3", "4", "5.5", "-6.0", "7", "8", "-9.9999", "1"],         // len() == 9 expected
-            &[],                                                                    // empty
+            &["a", "coord(0, 1)", "rgba(0, 0, 0, 255)", "size(16)", FONT, "align('justify')"],
+            &["a", "coord(0, 1)", "rgba(0, 0, 0, 255)", "size(16)", FONT, "align(1)"],
+            &["a", "coord(0, 1)", "rgba(0, 0, 0, 255)", "size(16)", FONT, "wrap('a')"],
+            &["a", "coord(0, 1)", "rgba(0, 0, 0, 255)", "size(0)", FONT],
+            &["a", "coord(0, 1)", "rgba(0, 0, 0, 255)", "size(-3)", FONT],
         })]
         fn expected_failures(input: &[&str]) {
-            let result: Result<[f32; 9], SicParserError> = ParseInputsFromIter::parse(input);
-            assert!(result.is_err())
+            let result: Result<DrawTextInner, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+
+        #[pm(
+            input = { "a\\nb", "a\\\\nb", "a\\tb", "a\\", "\\\\\\n", "it\\'s \\\"a\\\"" },
+            expected = { "a\nb", "a\\nb", "a\\tb", "a\\", "\\\n", "it's \"a\"" }
+        )]
+        fn unescape(input: &str, expected: &str) {
+            assert_eq!(unescape_text(input), expected);
         }
     }
 
-    mod tuple_u32_u32 {
+    #[cfg(feature = "imageproc-ops")]
+    mod noise_inputs {
+        use super::*;
+
+        #[pm(
+            input = {
+                &["gaussian", "0.1"],
+                &["salt-pepper", "0.05", "42"],
+                &["Salt-Pepper", "1", "0"],
+            },
+            expected = {
+                NoiseInputs::new(NoiseKind::Gaussian, 0.1, None),
+                NoiseInputs::new(NoiseKind::SaltPepper, 0.05, Some(42)),
+                NoiseInputs::new(NoiseKind::SaltPepper, 1.0, Some(0)),
+            }
+        )]
+        fn noise_inputs(input: &[&str], expected: NoiseInputs) {
+            let some: NoiseInputs = ParseInputsFromIter::parse(input).unwrap();
+            assert_eq!(some, expected)
+        }
+
+        #[pm(input = {
+            &["perlin", "0.1"],                     // unknown kind
+            &["gaussian"],                          // amount missing
+            &["gaussian", "a"],                     // amount not f32
+            &["gaussian", "0.1", "-1"],             // seed not u64
+            &["gaussian", "0.1", "1", "1"],         // len() <= 3 expected
+            &[],                                    // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<NoiseInputs, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    #[cfg(feature = "imageproc-ops")]
+    mod perspective_inputs {
         use super::*;
 
         #[test]
-        fn a_tuple_of_u32_u32() {
-            let some: (u32, u32) = ParseInputsFromIter::parse(&["03579", "0"]).unwrap();
-            assert_eq!(some, (3579u32, 0u32))
+        fn perspective_inputs() {
+            let some: PerspectiveInputs =
+                ParseInputsFromIter::parse(&["10", "5", "90", "0", "100", "80.5", "-2", "75"])
+                    .unwrap();
+            assert_eq!(
+                some,
+                PerspectiveInputs::new([(10.0, 5.0), (90.0, 0.0), (100.0, 80.5), (-2.0, 75.0)])
+            )
         }
 
         #[pm(input = {
-            &["4", "-0"],   // [_x, y]: y not u32 (neg 0)
-            &["4", "-4"],   // [_x, y]: y not u32 (neg number)
-            &["4", "f"],    // [_x, y]: y not u32 (not a number)
-            &["03579"],     // len() == 2 expected
-            &[],            // empty
+            &["0", "0", "1", "0", "1", "1", "0"],                  // len() == 8 expected
+            &["0", "0", "1", "0", "1", "1", "0", "1", "1"],        // len() == 8 expected
+            &["0", "0", "1", "0", "1", "1", "0", "a"],             // not f32
+            &[],                                                   // empty
         })]
         fn expected_failures(input: &[&str]) {
-            let result: Result<(u32, u32), SicParserError> = ParseInputsFromIter::parse(input);
+            let result: Result<PerspectiveInputs, SicParserError> =
+                ParseInputsFromIter::parse(input);
             assert!(result.is_err());
         }
     }
 
-    mod tuple_f32_i32 {
+    mod dimensions {
         use super::*;
+        use sic_image_engine::wrapper::dimension::Dimension::{Percentage, Pixels};
 
         #[test]
-        fn a_tuple_of_f32_i32() {
-            let some: (f32, i32) = ParseInputsFromIter::parse(&["-03579.1", "-0"]).unwrap();
-            assert_eq!(some, (-3579.1f32, 0i32))
+        fn crop_dimensions() {
+            let some: [Dimension; 4] =
+                ParseInputsFromIter::parse(&["0", "12.5%", "100%", "10"]).unwrap();
+            assert_eq!(
+                some,
+                [Pixels(0), Percentage(12.5), Percentage(100.0), Pixels(10)]
+            )
+        }
+
+        #[test]
+        fn resize_dimensions() {
+            let some: [Dimension; 2] = ParseInputsFromIter::parse(&["50%", "480"]).unwrap();
+            assert_eq!(some, [Percentage(50.0), Pixels(480)])
+        }
+
+        #[test]
+        fn expression_dimensions() {
+            let some: [Dimension; 2] = ParseInputsFromIter::parse(&["$width*0.5", "480"]).unwrap();
+            assert_eq!(
+                some,
+                [Dimension::try_from_str("$width*0.5").unwrap(), Pixels(480)]
+            )
         }
 
         #[pm(input = {
-            &["-f", "-1"],      // [x, _y]: x not f32
-            &["-1.0", "f"],     // [_x, y]: y not i32
-            &["4"],             // len() == 2 expected
-            &["4", "4", "4"],   // len() == 2 expected
-            &[],                // empty
+            &["50%"],               // len() == 2 expected
+            &["50%", "50%", "1"],   // len() == 2 expected
+            &["50%", "-1"],         // not a natural number
+            &["50", "%"],           // not a percentage
+            &["50%", "$depth"],     // not a variable
+            &[],                    // empty
         })]
         fn expected_failures(input: &[&str]) {
-            let result: Result<(f32, i32), SicParserError> = ParseInputsFromIter::parse(input);
+            let result: Result<[Dimension; 2], SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod duotone_colors {
+        use super::*;
+
+        #[test]
+        fn duotone_colors() {
+            let some: (Rgba<u8>, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["rgba(0, 0, 128, 255)", "rgba(255, 200, 0, 255)"])
+                    .unwrap();
+            assert_eq!(some, (Rgba([0, 0, 128, 255]), Rgba([255, 200, 0, 255])))
+        }
+
+        #[pm(input = {
+            &["rgba(0, 0, 128, 255)"],                                          // len() == 2 expected
+            &["rgba(0, 0, 128, 255)", "rgba(0, 0, 0, 255)", "rgba(0, 0, 0, 255)"], // len() == 2 expected
+            &["rgba(0, 0, 128, 255)", "size(1)"],                               // not an rgba value
+            &[],                                                                // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<(Rgba<u8>, Rgba<u8>), SicParserError> =
+                ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod background_color {
+        use super::*;
+
+        #[test]
+        fn background_color() {
+            let some: Rgba<u8> = ParseInputsFromIter::parse(&["rgba(255, 200, 0, 128)"]).unwrap();
+            assert_eq!(some, Rgba([255, 200, 0, 128]))
+        }
+
+        #[pm(input = {
+            &["rgba(255, 200, 0, 128)", "rgba(0, 0, 0, 255)"], // len() == 1 expected
+            &["size(1)"],                                      // not an rgba value
+            &[],                                               // empty
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<Rgba<u8>, SicParserError> = ParseInputsFromIter::parse(input);
+            assert!(result.is_err());
+        }
+    }
+
+    mod gradient {
+        use super::*;
+
+        #[test]
+        fn gradient() {
+            let some: Gradient = ParseInputsFromIter::parse(&[
+                "stop(1, rgba(255, 255, 255, 255))",
+                "stop(0,rgba(0,0,0,255))",
+            ])
+            .unwrap();
+            assert_eq!(
+                some,
+                Gradient::new(vec![
+                    ColorStop::new(0.0, Rgba([0, 0, 0, 255])),
+                    ColorStop::new(1.0, Rgba([255, 255, 255, 255])),
+                ])
+            )
+        }
+
+        #[pm(input = {
+            &["stop(0, rgba(0, 0, 0, 255))", "rgba(0, 0, 0, 255)"],  // not a stop
+            &["stop(0, rgba(0, 0, 0))"],                              // rgba requires 4 values
+            &["stop(rgba(0, 0, 0, 255))"],                            // position missing
+            &["stop(a, rgba(0, 0, 0, 255))"],                         // position not f32
+        })]
+        fn expected_failures(input: &[&str]) {
+            let result: Result<Gradient, SicParserError> = ParseInputsFromIter::parse(input);
             assert!(result.is_err());
         }
     }
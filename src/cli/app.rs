@@ -1,13 +1,18 @@
+use crate::cli::assertion::ImageAssertion;
 use crate::cli::config::{
-    validate_jpeg_quality, Config, ConfigBuilder, InputOutputModeType, SelectedLicenses,
+    parse_input_size, validate_jpeg_quality, AnalysisFormat, Config, ConfigBuilder,
+    DeepZoomSettings, InputOutputModeType, OutputLock, SelectedLicenses, SpriteSheetSettings,
 };
+use crate::cli::pipeline_file::read_pipeline_file;
 use anyhow::{anyhow, bail};
 use arg_names::*;
-use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches};
-use sic_cli_ops::create_image_ops;
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
+use sic_cli_ops::create_image_ops_with_macros;
 use sic_cli_ops::operations::OperationId;
-use sic_io::load::FrameIndex;
-use std::path::Path;
+use sic_io::format::{DdsCompression, GifPalette, TiffCompression};
+use sic_io::load::{ForcedInputFormat, FrameIndex};
+use sic_parser::macros::Macros;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use strum::VariantNames;
 
@@ -37,22 +42,95 @@ define_arg_consts!(arg_names, {
 
     // set specific configurations for decoding
     ARG_SELECT_FRAME,
+    ARG_INPUT_FORMAT,
+    ARG_INPUT_SIZE,
+    ARG_CREATE,
+
+    // validate the decoded input image
+    ARG_ASSERT,
+
+    // lay out all frames of an animated image on a sprite sheet
+    ARG_SPRITE_SHEET,
+    ARG_SPRITE_SHEET_COLUMNS,
+    ARG_SPRITE_SHEET_MANIFEST,
 
     // set specific configurations for encoding
     ARG_DISABLE_AUTOMATIC_COLOR_TYPE_ADJUSTMENT,
     ARG_FORCED_OUTPUT_FORMAT,
+    ARG_FIX_EXTENSION,
+    ARG_OUTPUT_LOCK,
+    ARG_NO_ATOMIC,
+    ARG_DEEP_ZOOM,
+    ARG_DEEP_ZOOM_TILE_SIZE,
+    ARG_DEEP_ZOOM_OVERLAP,
     ARG_JPEG_ENCODING_QUALITY,
+    ARG_JPEG_PROGRESSIVE,
     ARG_PNM_ENCODING_ASCII,
+    ARG_WEBP_QUALITY,
+    ARG_WEBP_LOSSLESS,
+    ARG_TIFF_COMPRESSION,
+    ARG_DDS_COMPRESSION,
+    ARG_DDS_MIPMAPS,
+    ARG_GIF_PALETTE,
+    ARG_GIF_COLORS,
     ARG_IMAGE_CRATE_FALLBACK,
 
+    // inspect intermediate results of image operations
+    ARG_EXPLAIN_DIR,
+
+    // report what analysing image operations found
+    ARG_ANALYSIS_FORMAT,
+
+    // validate the image operations without processing an image
+    ARG_CHECK,
+
+    // explain the image operations without processing an image
+    ARG_DRY_RUN,
+
+    // collect diagnostics when an error occurs
+    ARG_DEBUG_BUNDLE,
+    ARG_DEBUG_BUNDLE_INCLUDE_PIXELS,
+
     // provide image operations using image script
     ARG_APPLY_OPERATIONS,
-    ARG_OPERATIONS_SCRIPT,
+    ARG_APPLY_OPERATIONS_FILE,
+
+    // substitute environment variables in image scripts
+    ARG_EXPAND_ENV,
+
+    // store parsed image operations, and apply stored ones
+    ARG_EXPORT_PIPELINE,
+    ARG_PIPELINE,
+
+    // define macros which can be called by image scripts and cli image operations
+    ARG_MACROS,
+    ARG_MACRO_CALL,
 
     // group: image operations
     GROUP_IMAGE_OPERATIONS,
+
+    // subcommand: bench
+    ARG_BENCH_INPUT,
+    ARG_BENCH_ITERATIONS,
+    ARG_BENCH_OUTPUT_FORMAT,
+    ARG_BENCH_PIPELINE,
+
+    // subcommand: ops
+    ARG_OPS_JSON,
+    ARG_OPS_OPERATION,
+
+    // subcommands: fmt-script, migrate-script
+    ARG_SCRIPT_IN_PLACE,
+    ARG_SCRIPT_PATH,
 });
 
+pub const SUBCOMMAND_BENCH: &str = "bench";
+pub const SUBCOMMAND_OPS: &str = "ops";
+pub const SUBCOMMAND_OPS_LIST: &str = "list";
+pub const SUBCOMMAND_OPS_DESCRIBE: &str = "describe";
+pub const SUBCOMMAND_FMT_SCRIPT: &str = "fmt-script";
+pub const SUBCOMMAND_MIGRATE_SCRIPT: &str = "migrate-script";
+
 #[cfg(not(feature = "imageproc-ops"))]
 fn wrap_with(app: App<'static, 'static>) -> App<'static, 'static> {
     app
@@ -61,16 +139,203 @@ fn wrap_with(app: App<'static, 'static>) -> App<'static, 'static> {
 #[cfg(feature = "imageproc-ops")]
 fn wrap_with(app: App<'static, 'static>) -> App<'static, 'static> {
     app.arg(
+        Arg::with_name(OperationId::AdaptiveThreshold.as_str())
+            .help("Operation: binarize the input image by comparing each pixel to a threshold computed from its local \
+                   block-size x block-size neighbourhood. The block size should be an odd number larger than 1.")
+            .long(OperationId::AdaptiveThreshold.as_str())
+            .takes_value(true)
+            .value_name("block-size")
+            .number_of_values(1)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::AdaptiveThresholdMethod.as_str())
+            .help("Operation modifier for 'adaptive-threshold': compute the local threshold as the mean (default) \
+                   or as the Gaussian weighted sum of the neighbourhood")
+            .long(OperationId::AdaptiveThresholdMethod.as_str())
+            .takes_value(true)
+            .value_name("method")
+            .number_of_values(1)
+            .multiple(true)
+            .possible_values(&["mean", "gaussian"]),
+    )
+    .arg(
+        Arg::with_name(OperationId::Clahe.as_str())
+            .help("Operation: contrast limited adaptive histogram equalization; equalizes the luminance of each \
+                   tile-size x tile-size tile of the input image separately, while limiting the contrast \
+                   enhancement by the clip limit (as a multiple of the average histogram bin)")
+            .long(OperationId::Clahe.as_str())
+            .takes_value(true)
+            .value_names(&["tile-size", "clip-limit"])
+            .number_of_values(2)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::DrawArrow.as_str())
+            .help("Operation: draw-arrow. Draws a line of the given width, with an arrowhead at its end")
+            .long(OperationId::DrawArrow.as_str())
+            .takes_value(true)
+            .value_names(&["coord(x, y)", "coord(x, y)", "rgba(r,g,b,a)", "width"])
+            .number_of_values(4)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::DrawCorners.as_str())
+            .help("Operation: draw-corners. Marks the corners found by the FAST-9 corner detector with the given \
+                   threshold (0 to 255); optionally followed by the color of the markers (default: red)")
+            .long(OperationId::DrawCorners.as_str())
+            .takes_value(true)
+            .value_name("threshold [rgba(r,g,b,a)]")
+            .min_values(1)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::DrawEllipse.as_str())
+            .help("Operation: draw-ellipse. Draws the outline of an ellipse with a width of 1 pixel; optionally \
+                   followed by 'filled' to fill the ellipse, or 'stroke <width>' to set the width of the outline")
+            .long(OperationId::DrawEllipse.as_str())
+            .takes_value(true)
+            .value_name("<coord(x, y)> <radius-x> <radius-y> <rgba(r,g,b,a)> [filled | stroke <width>]")
+            .min_values(4)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::DrawLine.as_str())
+            .help("Operation: draw-line. Draws a line of the given width, with rounded ends")
+            .long(OperationId::DrawLine.as_str())
+            .takes_value(true)
+            .value_names(&["coord(x, y)", "coord(x, y)", "rgba(r,g,b,a)", "width"])
+            .number_of_values(4)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::DrawPolygon.as_str())
+            .help("Operation: draw-polygon. Draws the outline of a polygon through three or more points with a width \
+                   of 1 pixel; optionally followed by 'filled' to fill the polygon, or 'stroke <width>' to set the \
+                   width of the outline")
+            .long(OperationId::DrawPolygon.as_str())
+            .takes_value(true)
+            .value_name("<coord(x, y)> <coord(x, y)> <coord(x, y)> [coord(x, y)...] <rgba(r,g,b,a)> [filled | stroke <width>]")
+            .min_values(4)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::DrawRect.as_str())
+            .help("Operation: draw-rect. Draws the outline of a rectangle with a width of 1 pixel; optionally \
+                   followed by 'filled' to fill the rectangle, or 'stroke <width>' to set the width of the outline")
+            .long(OperationId::DrawRect.as_str())
+            .takes_value(true)
+            .value_name("<rect(x, y, w, h)> <rgba(r,g,b,a)> [filled | stroke <width>]")
+            .min_values(2)
+            .multiple(true),
+    )
+    .arg(
         Arg::with_name(OperationId::DrawText.as_str())
-            .help("Operation: draw-text.")
+            .help("Operation: draw-text. Lines are separated by '\\n'. Optionally followed by an outline, shadow, spacing, rotation, wrap width and alignment, e.g. 'outline(2, rgba(0, 0, 0, 255))' 'shadow(2, 2, rgba(0, 0, 0, 128))' 'spacing(1, 0)' 'angle(-30)' 'wrap(300)' 'align(\"center\")'")
             .long(OperationId::DrawText.as_str())
             .takes_value(true)
             .value_name(
-                "<text> <coord(x, y)> <rgba(r,g,b,a)> <size(s)> <font(\"path/to/font.ttf\">)",
+                "<text> <coord(x, y)> <rgba(r,g,b,a)> <size(s)> <font(\"path/to/font.ttf\" or \"Family\"[, \"style\"])> [outline(w, rgba)] [shadow(x, y, rgba)] [spacing(letter, line)] [angle(degrees)] [wrap(width)] [align(\"left\" | \"center\" | \"right\")]",
             )
-            .number_of_values(5)
+            .min_values(5)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::LabelComponents.as_str())
+            .help("Operation: draw each connected component of the bright areas of the input image in a distinct color, on \
+                   a black background; useful to count blobs after thresholding. Pixels with a luminance of at least 128 \
+                   are bright. With the optional connectivity of 4, pixels are only connected to the pixels above, below, \
+                   left and right of them; with a connectivity of 8 (default), also to their diagonal neighbours.")
+            .long(OperationId::LabelComponents.as_str())
+            .takes_value(true)
+            .value_name("4|8")
+            // at most one value is taken per occurrence; clap counts the values of all
+            // occurrences together, so it can't limit the values of each occurrence
+            .min_values(0)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::Median.as_str())
+            .help("Operation: replace each pixel of the input image by the median of the pixels within the \
+                   given radius around it; removes salt-and-pepper noise, e.g. from scanned documents")
+            .long(OperationId::Median.as_str())
+            .takes_value(true)
+            .value_name("radius")
+            .number_of_values(1)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::DistanceTransform.as_str())
+            .help("Operation: replace each pixel of the bright areas of the input image by its distance to the nearest dark \
+                   pixel, normalized so the pixel farthest from the dark areas becomes white; e.g. to find the centers or the \
+                   thickness of shapes after thresholding. Pixels with a luminance of at least 128 are bright.")
+            .long(OperationId::DistanceTransform.as_str())
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::Dilate.as_str())
+            .help("Operation: grow the bright areas of the input image; each pixel becomes the brightest pixel within the \
+                   given radius around it. Colored images are converted to grayscale first.")
+            .long(OperationId::Dilate.as_str())
+            .takes_value(true)
+            .value_name("radius")
+            .number_of_values(1)
             .multiple(true),
     )
+    .arg(
+        Arg::with_name(OperationId::Erode.as_str())
+            .help("Operation: shrink the bright areas of the input image; each pixel becomes the darkest pixel within the \
+                   given radius around it. Colored images are converted to grayscale first.")
+            .long(OperationId::Erode.as_str())
+            .takes_value(true)
+            .value_name("radius")
+            .number_of_values(1)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::MorphOpen.as_str())
+            .help("Operation: erode and then dilate the input image by the given radius, which removes small bright \
+                   specks, e.g. to clean up a mask after thresholding. Colored images are converted to grayscale first.")
+            .long(OperationId::MorphOpen.as_str())
+            .takes_value(true)
+            .value_name("radius")
+            .number_of_values(1)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::MorphClose.as_str())
+            .help("Operation: dilate and then erode the input image by the given radius, which fills small dark holes \
+                   and gaps, e.g. to clean up a mask after thresholding. Colored images are converted to grayscale first.")
+            .long(OperationId::MorphClose.as_str())
+            .takes_value(true)
+            .value_name("radius")
+            .number_of_values(1)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::Noise.as_str())
+            .help("Operation: add random noise to the input image; either 'gaussian' noise with the amount as its standard \
+                   deviation, or 'salt-pepper' noise which turns the given fraction of the pixels black or white. The \
+                   amount is between 0 and 1. With the optional seed, the same noise is added each time.")
+            .long(OperationId::Noise.as_str())
+            .takes_value(true)
+            .value_name("kind amount [seed]")
+            // at most one seed is taken per occurrence; clap counts the values of all
+            // occurrences together, so it can't limit the values of each occurrence
+            .min_values(2)
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name(OperationId::Perspective.as_str())
+            .help("Operation: map the quadrilateral with the given corners onto a rectangle, e.g. to correct the keystone \
+                   distortion of a photographed document. The corners are given clockwise, starting at the top left corner.")
+            .long(OperationId::Perspective.as_str())
+            .takes_value(true)
+            .value_names(&["x1", "y1", "x2", "y2", "x3", "y3", "x4", "y4"])
+            .number_of_values(8)
+            .multiple(true)
+            .allow_hyphen_values(true),
+    )
 }
 
 pub fn create_app(
@@ -90,6 +355,9 @@ pub fn create_app(
         .global_setting(AppSettings::ColorAuto)
         .global_setting(AppSettings::DontCollapseArgsInUsage)
         .global_setting(AppSettings::UnifiedHelpMessage)
+        // allows negative weights for operations which take a variable number of values, like
+        // 'convolve'; unlike 'allow_hyphen_values', options which follow aren't taken as values
+        .setting(AppSettings::AllowNegativeNumbers)
         .max_term_width(120)
 
         // cli arguments
@@ -139,7 +407,8 @@ pub fn create_app(
             .long("glob-output")
             .value_name("GLOB_OUTPUT_ROOT_FOLDER")
             .takes_value(true)
-            .help("This output should point to a folder in which the greatest root common directory of the glob input will be mirrored")
+            .help("This output should point to a folder in which the greatest root common directory of the glob input will be mirrored. \
+            If the output format is PDF, it should point to the PDF document instead, in which each input image becomes a page.")
             .conflicts_with_all(&[ARG_LICENSE, ARG_DEP_LICENSES, ARG_INPUT, ARG_OUTPUT])
         )
 
@@ -155,12 +424,75 @@ pub fn create_app(
         .arg(Arg::with_name(ARG_SELECT_FRAME)
             .long("select-frame")
             .value_name("#FRAME")
-            .help("Frame to be loaded as still image if the input image is an animated image.\
+            .help("Frame to be loaded as still image if the input image is an animated image. \
+                      By default, all frames are kept if the output is a GIF, and the first frame is used otherwise. \
                       To pick the first and last frame respectively, you can provide 'first' and 'last' as arguments. \
                       Otherwise provide a single one-indexed positive number which corresponds with the frame index. \
                       For example, to select the first frame, the argument would be '1', for the second '2', etc.")
             .takes_value(true))
 
+        // config(in)/input-format:
+        .arg(Arg::with_name(ARG_INPUT_FORMAT)
+            .long("input-format")
+            .value_name("FORMAT")
+            .help("Read the input image in the FORMAT format, instead of recognizing the format from the contents of the image. \
+                      Only formats which can't be recognized are supported: 'raw-rgba8' reads unencoded pixels with 8 bit RGBA samples, \
+                      row by row and without a header, of which the size is given by --input-size.")
+            .possible_values(&["raw-rgba8"])
+            .requires(ARG_INPUT_SIZE)
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_INPUT_SIZE)
+            .long("input-size")
+            .value_name("WxH")
+            .help("The width and height in pixels of input images which have no header, like 'raw-rgba8' images, e.g. '640x480'.")
+            .requires(ARG_INPUT_FORMAT)
+            .takes_value(true))
+
+        // config(in)/create:
+        .arg(Arg::with_name(ARG_CREATE)
+            .long("create")
+            .value_names(&["WIDTH", "HEIGHT"])
+            .number_of_values(2)
+            .help("Start from a blank, transparent canvas of WIDTH by HEIGHT pixels instead of reading an input image, \
+                      e.g. to draw a gradient on with `--create 640 480 --gradient linear \"rgba(0, 0, 0, 255)\" \"rgba(255, 255, 255, 255)\"`.")
+            .conflicts_with_all(&[ARG_INPUT, ARG_INPUT_GLOB, ARG_OUTPUT_GLOB, ARG_INPUT_FORMAT, ARG_SELECT_FRAME, ARG_SPRITE_SHEET])
+            .takes_value(true))
+
+        // config(in)/assert:
+        .arg(Arg::with_name(ARG_ASSERT)
+            .long("assert")
+            .value_name("ASSERTION")
+            .help("Check properties of the decoded input image before any image operation is applied, and exit with an error if the assertion does not hold. \
+                      Comparisons have the form `property operator value`, e.g. `width>=800`, and can be combined using `&&` and `||`. \
+                      Supported properties: width, height, channels, bit_depth, has_alpha, has_color and color_type. \
+                      Supported operators: ==, !=, <, <=, > and >=.")
+            .takes_value(true))
+
+        // config(in)/sprite-sheet:
+        .arg(Arg::with_name(ARG_SPRITE_SHEET)
+            .long("sprite-sheet")
+            .help("Lay out all frames of an animated input image on a single sprite sheet, instead of selecting a single frame. \
+                      Image operations are applied to each frame separately. \
+                      A JSON manifest, which describes the position and duration of each frame, is written next to the output image \
+                      (with the 'json' extension), unless the output is written to the stdout.")
+            .conflicts_with(ARG_SELECT_FRAME))
+
+        .arg(Arg::with_name(ARG_SPRITE_SHEET_COLUMNS)
+            .long("sprite-sheet-columns")
+            .value_name("COLUMNS")
+            .help("Amount of frames placed on each row of the sprite sheet. By default, all frames are placed on a single row.")
+            .requires(ARG_SPRITE_SHEET)
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_SPRITE_SHEET_MANIFEST)
+            .long("sprite-sheet-manifest")
+            .value_name("PATH")
+            .help("Write the JSON manifest of the sprite sheet to PATH.")
+            .requires(ARG_SPRITE_SHEET)
+            .conflicts_with(ARG_INPUT_GLOB)
+            .takes_value(true))
+
         // config(out):
         .arg(Arg::with_name(ARG_DISABLE_AUTOMATIC_COLOR_TYPE_ADJUSTMENT)
             .long("disable-automatic-color-type-adjustment")
@@ -173,7 +505,51 @@ pub fn create_app(
             .long("output-format")
             .value_name("FORMAT")
             .help("Force the output image format to use FORMAT, regardless of the (if any) extension of the given output file path. \
-                      Output formats (FORMAT values) supported: AVIF, BMP, Farbfeld, GIF, ICO, JPEG, PNG, PAM, PBM, PGM, PPM and TGA.")
+                      Output formats (FORMAT values) supported: AVIF, BMP, DDS, EXR, Farbfeld, GIF, ICO, JPEG, PDF, PNG, PAM, PBM, PGM, PPM, RAW-RGBA8, TGA, TIFF and WebP. \
+                      RAW-RGBA8 writes unencoded pixels with 8 bit RGBA samples, row by row and without a header.")
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_FIX_EXTENSION)
+            .long("fix-extension")
+            .help("If the extension of the output path does not match the format given by --output-format, \
+                      adjust the extension of the output path, instead of only warning about the mismatch.")
+            .requires(ARG_FORCED_OUTPUT_FORMAT))
+
+        .arg(Arg::with_name(ARG_OUTPUT_LOCK)
+            .long("output-lock")
+            .value_name("BEHAVIOUR")
+            .help("Output files are locked while being written, so concurrent invocations which target the same \
+                      output don't corrupt it. With 'wait' (default), sic waits until the other process is done and \
+                      overwrites the output afterwards. With 'skip', the output is left as is if it is locked.")
+            .possible_values(&["wait", "skip"])
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_NO_ATOMIC)
+            .long("no-atomic")
+            .help("By default, the output image is written to a temporary file in the output directory first, which \
+                      replaces the output once it has been written completely. With this flag, the output is written \
+                      in place instead, e.g. for file systems on which renaming files is problematic."))
+
+        .arg(Arg::with_name(ARG_DEEP_ZOOM)
+            .long("deep-zoom")
+            .help("Cut the output image into the tiles of a Deep Zoom (DZI) image pyramid, which can be shown by viewers \
+                      such as OpenSeadragon. The output path is used for the descriptor (e.g. 'photo.dzi', or 'photo.json' for \
+                      a JSON descriptor), and the tiles are written to the 'photo_files' directory next to it. \
+                      Tiles are stored as PNG, unless another format is given by --output-format.")
+            .conflicts_with(ARG_INPUT_GLOB))
+
+        .arg(Arg::with_name(ARG_DEEP_ZOOM_TILE_SIZE)
+            .long("deep-zoom-tile-size")
+            .value_name("SIZE")
+            .help("Width and height of each Deep Zoom tile, excluding the overlap. Defaults to 254.")
+            .requires(ARG_DEEP_ZOOM)
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_DEEP_ZOOM_OVERLAP)
+            .long("deep-zoom-overlap")
+            .value_name("PIXELS")
+            .help("Amount of pixels each Deep Zoom tile shares with its neighbouring tiles, on each side. Defaults to 1.")
+            .requires(ARG_DEEP_ZOOM)
             .takes_value(true))
 
         .arg(Arg::with_name(ARG_JPEG_ENCODING_QUALITY)
@@ -182,15 +558,118 @@ pub fn create_app(
             .value_name("QUALITY")
             .takes_value(true))
 
+        .arg(Arg::with_name(ARG_JPEG_PROGRESSIVE)
+            .long("jpeg-progressive")
+            .help("Encode jpeg images progressive, in several scans of increasing detail, which lets web browsers show them before they're loaded completely. \
+            Will only be used when the output format is determined to be jpeg."))
+
         .arg(Arg::with_name(ARG_PNM_ENCODING_ASCII)
             .long("pnm-encoding-ascii")
             .help("Use ascii based encoding when using a PNM image output format (pbm, pgm or ppm). Doesn't apply to 'pam' (PNM Arbitrary Map)."))
 
+        .arg(Arg::with_name(ARG_WEBP_QUALITY)
+            .long("webp-quality")
+            .help("Set the webp quality to QUALITY. Valid values are numbers from 0 up to and including 100; defaults to 75. \
+            When encoding lossless, the quality trades off encoding speed against file size instead. Will only be used when the output format is determined to be webp.")
+            .value_name("QUALITY")
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_WEBP_LOSSLESS)
+            .long("webp-lossless")
+            .help("Encode webp images lossless. Will only be used when the output format is determined to be webp."))
+
+        .arg(Arg::with_name(ARG_TIFF_COMPRESSION)
+            .long("tiff-compression")
+            .help("Set the compression method used to encode tiff images; all methods are lossless. Defaults to 'none'. \
+            Will only be used when the output format is determined to be tiff.")
+            .value_name("METHOD")
+            .possible_values(&["none", "lzw", "deflate", "packbits"])
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_DDS_COMPRESSION)
+            .long("dds-compression")
+            .help("Set the block compression used to encode dds textures: 'none' for uncompressed RGBA, 'bc1' (DXT1) for opaque textures, \
+            or 'bc3' (DXT5) to keep the alpha channel. Defaults to 'none'. Will only be used when the output format is determined to be dds.")
+            .value_name("METHOD")
+            .possible_values(&["none", "bc1", "bc3"])
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_DDS_MIPMAPS)
+            .long("dds-mipmaps")
+            .help("Add the chain of mipmaps to dds textures, each half the size of the previous one, down to 1 by 1 pixels. \
+            Will only be used when the output format is determined to be dds."))
+
+        .arg(Arg::with_name(ARG_GIF_PALETTE)
+            .long("gif-palette")
+            .help("Set how the colors of gif frames are chosen: 'global' for a single palette shared by all frames, \
+            or 'local' for a palette per frame, which keeps more colors of animations whose frames differ in color, at the cost of a larger image. \
+            Defaults to 'global'. Will only be used when the output format is determined to be gif.")
+            .value_name("PALETTE")
+            .possible_values(&["global", "local"])
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_GIF_COLORS)
+            .long("gif-colors")
+            .help("Set the maximum number of colors of each gif palette to COLORS. Valid values are numbers from 2 up to and including 256; defaults to 256. \
+            Fewer colors result in smaller images, at the cost of quality. Will only be used when the output format is determined to be gif.")
+            .value_name("COLORS")
+            .takes_value(true))
+
         .arg(Arg::with_name(ARG_IMAGE_CRATE_FALLBACK)
             .long("enable-output-format-decider-fallback")
             .help("[experimental] When this flag is set, sic will attempt to fallback to an alternative output format decider (image crate version), \
             *if* sic's own decider can't find a suitable format. Setting this flag may introduce unwanted behaviour; use with caution."))
 
+        // image-operations(explain):
+        .arg(Arg::with_name(ARG_EXPLAIN_DIR)
+            .long("explain-dir")
+            .value_name("DIR")
+            .help("Write a small thumbnail to DIR after each applied image operation, named after the step and operation, \
+                      e.g. '01-blur.png', '02-crop.png'. The unmodified input image is written as '00-input.png'. \
+                      Can be used to find out which operation caused an unexpected output.")
+            .conflicts_with_all(&[ARG_INPUT_GLOB, ARG_SPRITE_SHEET])
+            .takes_value(true))
+
+        // image-operations(analysis-format):
+        .arg(Arg::with_name(ARG_ANALYSIS_FORMAT)
+            .long("analysis-format")
+            .value_name("FORMAT")
+            .help("Image operations which analyse the image, such as --find and --contours, report what they found on the stdout, or on \
+                      the stderr if the output image is written to the stdout. With 'text' (default), each result is \
+                      written on a line of its own. With 'json', all results are written as a single JSON array.")
+            .possible_values(&["text", "json"])
+            .takes_value(true))
+
+        // diagnostics(check):
+        .arg(Arg::with_name(ARG_CHECK)
+            .long("check")
+            .help("Only parse and validate the image operations, including that the files they refer to (e.g. the image of \
+                      'diff' or the font of 'draw-text') can be used, and that the input file exists. No image is decoded or \
+                      encoded, so pipelines can be checked cheaply, e.g. in CI."))
+
+        // diagnostics(dry-run):
+        .arg(Arg::with_name(ARG_DRY_RUN)
+            .long("dry-run")
+            .help("Only print the image operations as they would be applied: their resolved arguments, the modifiers \
+                      they would be applied with, and the size of the image after each of them, where it can be predicted. \
+                      The size of the input image is read from its header, if an input is given. No image is decoded or \
+                      encoded, and '$frames' is taken to be 1.")
+            .conflicts_with_all(&[ARG_CHECK, ARG_EXPORT_PIPELINE]))
+
+        // diagnostics(debug-bundle):
+        .arg(Arg::with_name(ARG_DEBUG_BUNDLE)
+            .long("debug-bundle")
+            .value_name("PATH")
+            .help("When an error occurs, write a zip archive to PATH which contains the parsed image operations, metadata of \
+                      the input image, the version of sic and the full error chain, so it can be attached to a bug report. \
+                      Nothing is sent anywhere. The input image itself is only included if --include-pixels is provided.")
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_DEBUG_BUNDLE_INCLUDE_PIXELS)
+            .long("include-pixels")
+            .help("Include the input image in the debug bundle.")
+            .requires(ARG_DEBUG_BUNDLE))
+
         // image-operations(script):
         .arg(Arg::with_name(ARG_APPLY_OPERATIONS)
             .long("apply-operations")
@@ -199,20 +678,88 @@ pub fn create_app(
             .help(help_ops)
             .value_name("OPERATIONS")
             .takes_value(true)
-            .conflicts_with(ARG_OPERATIONS_SCRIPT))
+            .conflicts_with(ARG_APPLY_OPERATIONS_FILE))
 
-        .arg(Arg::with_name(ARG_OPERATIONS_SCRIPT)
-            .long("operations-script")
-            .help("Like '--apply-operations' but takes a file path where the file contains the script instead of taking it as value directly")
+        .arg(Arg::with_name(ARG_APPLY_OPERATIONS_FILE)
+            .long("apply-operations-file")
+            .alias("operations-script")
+            .help("Like '--apply-operations' but takes a file path where the file contains the script instead of taking it as value directly. \
+                   '--apply-operations @<SCRIPT_FILE>' is a shorthand")
             .value_name("SCRIPT_FILE")
             .takes_value(true)
             .conflicts_with(ARG_APPLY_OPERATIONS))
 
+        .arg(Arg::with_name(ARG_EXPAND_ENV)
+            .long("expand-env")
+            .help("Substitute the values of environment variables for the references to them in the image script, e.g. \
+                   'resize ${WIDTH} 0' or \"stamp '${LOGO}' bottom-right 8\", including in the scripts it includes. \
+                   A reference to a variable which isn't set is an error; write '$${' to keep '${' as it is")
+            .conflicts_with(ARG_PIPELINE))
+
+        // image-operations(pipeline-file):
+        .arg(Arg::with_name(ARG_EXPORT_PIPELINE)
+            .long("export-pipeline")
+            .help("Write the parsed image operations to a pipeline file, instead of applying them to an image. The file is \
+                   written in JSON or TOML, depending on its extension ('.json' or '.toml'). It can be applied later with \
+                   '--pipeline'")
+            .value_name("PIPELINE_FILE")
+            .takes_value(true)
+            .conflicts_with(ARG_CHECK))
+
+        .arg(Arg::with_name(ARG_PIPELINE)
+            .long("pipeline")
+            .help("Apply the image operations of a pipeline file, as written by '--export-pipeline'. The file is read as \
+                   JSON or TOML, depending on its extension ('.json' or '.toml')")
+            .value_name("PIPELINE_FILE")
+            .takes_value(true)
+            .conflicts_with_all(&[ARG_APPLY_OPERATIONS, ARG_APPLY_OPERATIONS_FILE, ARG_MACRO_CALL, GROUP_IMAGE_OPERATIONS]))
+
+        .arg(Arg::with_name(ARG_MACROS)
+            .long("macros")
+            .help("Takes a file path where the file contains macro definitions, e.g. 'def thumb(w) { resize $w $w; sharpen 0.3 }'. \
+                   The macros can be called by '--apply-operations' and '--apply-operations-file', and by '--macro'")
+            .value_name("MACROS_FILE")
+            .takes_value(true))
+
+        .arg(Arg::with_name(ARG_MACRO_CALL)
+            .long("macro")
+            .help("Operation: call a macro defined in the '--macros' file, e.g. 'thumb(256)'")
+            .value_name("CALL")
+            .takes_value(true)
+            .number_of_values(1)
+            .multiple(true)
+            .requires(ARG_MACROS)
+            .conflicts_with_all(&[ARG_APPLY_OPERATIONS, ARG_APPLY_OPERATIONS_FILE]))
+
         // image-operations(cli-arguments):
         .group(ArgGroup::with_name(GROUP_IMAGE_OPERATIONS)
             .args(&OperationId::VARIANTS)
             .conflicts_with(ARG_APPLY_OPERATIONS)
             .multiple(true))
+        .arg(Arg::with_name(OperationId::AlphaAdd.as_str())
+            .help("Operation: add an opaque alpha channel to the input image, if it doesn't have one yet")
+            .long(OperationId::AlphaAdd.as_str())
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::AlphaPremultiply.as_str())
+            .help("Operation: multiply the color channels of each pixel by its alpha value (premultiplied alpha)")
+            .long(OperationId::AlphaPremultiply.as_str())
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::AlphaRemove.as_str())
+            .help("Operation: drop the alpha channel of the input image; the colors of transparent pixels are kept as is")
+            .long(OperationId::AlphaRemove.as_str())
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::AlphaThreshold.as_str())
+            .help("Operation: make pixels with an alpha value below the threshold fully transparent, and all others fully opaque")
+            .long(OperationId::AlphaThreshold.as_str())
+            .takes_value(true)
+            .value_name("u8")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::AutoEnhance.as_str())
+            .help("Operation: improve the input image in a single step by stretching its contrast, mildly boosting its saturation \
+                   and sharpening it slightly; the adjustments are derived from the histogram of the image")
+            .long(OperationId::AutoEnhance.as_str())
+            .multiple(true))
         .arg(Arg::with_name(OperationId::Blur.as_str())
             .help("Operation: perform a gaussian blur on the input image")
             .long(OperationId::Blur.as_str())
@@ -221,6 +768,21 @@ pub fn create_app(
             .number_of_values(1)
             .multiple(true)
             .allow_hyphen_values(true))
+        .arg(Arg::with_name(OperationId::Border.as_str())
+            .help("Operation: add a solid border of the given color around the input image; 1 to 4 widths may be \
+                   given, which are assigned to the top, right, bottom and left sides like the CSS border-width property")
+            .long(OperationId::Border.as_str())
+            .takes_value(true)
+            .value_name("widths rgba(r,g,b,a)")
+            .min_values(2)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::BoxBlur.as_str())
+            .help("Operation: replace each pixel of the input image by the mean of the pixels within the given radius around it; fast, also for large radii")
+            .long(OperationId::BoxBlur.as_str())
+            .takes_value(true)
+            .value_name("uint")
+            .number_of_values(1)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::Brighten.as_str())
             .help("Operation: increase or decrease the brightness of the input image")
             .long(OperationId::Brighten.as_str())
@@ -229,6 +791,37 @@ pub fn create_app(
             .number_of_values(1)
             .multiple(true)
             .allow_hyphen_values(true))
+        .arg(Arg::with_name(OperationId::ChannelExtract.as_str())
+            .help("Operation: create a grayscale image from a single channel of the input image; channels are r (red), g (green), b (blue) and a (alpha)")
+            .long(OperationId::ChannelExtract.as_str())
+            .takes_value(true)
+            .value_name("channel")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::ChannelSwap.as_str())
+            .help("Operation: swap two channels of the input image, e.g. 'r b' to fix an image of which the red and blue channels were mixed up; channels are r (red), g (green), b (blue) and a (alpha)")
+            .long(OperationId::ChannelSwap.as_str())
+            .value_names(&["channel", "channel"])
+            .takes_value(true)
+            .number_of_values(2)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::ChromaKey.as_str())
+            .help("Operation: make the pixels which are within the given tolerance of the key color transparent; pixels within the feather distance beyond it are made partially transparent")
+            .long(OperationId::ChromaKey.as_str())
+            .takes_value(true)
+            .value_names(&["nv:rgba", "tolerance", "feather"])
+            .number_of_values(3)
+            .multiple(true)
+            .allow_hyphen_values(true))
+        .arg(Arg::with_name(OperationId::Contours.as_str())
+            .help("Operation: trace the borders of the areas with a luminance of at least the threshold (0 to 255), draw \
+                   them in green, and report the number of contours and points (see --analysis-format; the points are \
+                   only reported as JSON); optionally followed by the color in which the contours are drawn")
+            .long(OperationId::Contours.as_str())
+            .takes_value(true)
+            .value_name("threshold [rgba(r,g,b,a)]")
+            .min_values(1)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::Contrast.as_str())
             .help("Operation: increase or decrease the contrast of the input image")
             .long(OperationId::Contrast.as_str())
@@ -237,13 +830,46 @@ pub fn create_app(
             .number_of_values(1)
             .multiple(true)
             .allow_hyphen_values(true))
+        .arg(Arg::with_name(OperationId::Convolve.as_str())
+            .help("Operation: convolve the input image with a size x size kernel, where the size is an odd number; \
+                   the weights of the kernel follow row by row. Unless the weights sum to 0, the result is divided \
+                   by their sum.")
+            .long(OperationId::Convolve.as_str())
+            .takes_value(true)
+            .value_name("size weights")
+            .min_values(2)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::ConvolveFile.as_str())
+            .help("Operation: convolve the input image with a kernel loaded from a text file, which holds one row of \
+                   the kernel per line")
+            .long(OperationId::ConvolveFile.as_str())
+            .takes_value(true)
+            .value_name("path")
+            .number_of_values(1)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::Crop.as_str())
-            .help("Operation: crop the input image to a bounding rectangle ranging from top-left (lx, ly) to bottom-right (rx, ry) coordinates")
+            .help("Operation: crop the input image to a bounding rectangle ranging from top-left (lx, ly) to bottom-right (rx, ry) coordinates, \
+                   given in pixels or as percentages of the width and height of the image, e.g. '10%'")
             .long(OperationId::Crop.as_str())
             .takes_value(true)
             .value_names(&["lx", "ly", "rx", "ry"])
             .number_of_values(4)
             .multiple(true))
+        .arg(Arg::with_name(OperationId::CropCenter.as_str())
+            .help("Operation: crop a selection of width by height pixels from the center of the input image")
+            .long(OperationId::CropCenter.as_str())
+            .takes_value(true)
+            .value_names(&["width", "height"])
+            .number_of_values(2)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::CropGravity.as_str())
+            .help("Operation: crop a selection of width by height pixels from the side or corner of the input image given \
+                   by the gravity (top-left, top, top-right, left, center, right, bottom-left, bottom or bottom-right)")
+            .long(OperationId::CropGravity.as_str())
+            .takes_value(true)
+            .value_names(&["gravity", "width", "height"])
+            .number_of_values(3)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::Diff.as_str())
             .help("Operation: show ")
             .long(OperationId::Diff.as_str())
@@ -251,6 +877,42 @@ pub fn create_app(
             .value_name("path to image")
             .number_of_values(1)
             .multiple(true))
+        .arg(Arg::with_name(OperationId::Dither.as_str())
+            .help("Operation: reduce the colors of the input image to a palette of the given number of colors (2 to 256), using Floyd-Steinberg error diffusion dithering")
+            .long(OperationId::Dither.as_str())
+            .takes_value(true)
+            .value_name("colors")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::DrawHistogram.as_str())
+            .help("Operation: render a histogram of the luminance (luma, default) or of the red, green and blue channels (rgb) of the \
+                   input image; optionally followed by whether the histogram is appended below the image (append, default) \
+                   or drawn on top of the bottom of the image (overlay)")
+            .long(OperationId::DrawHistogram.as_str())
+            .takes_value(true)
+            .value_name("[luma|rgb] [append|overlay]")
+            .min_values(0)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Duotone.as_str())
+            .help("Operation: map the luminance of the input image onto a gradient from the first color (shadows) to the second color (highlights)")
+            .long(OperationId::Duotone.as_str())
+            .takes_value(true)
+            .value_names(&["nv:rgba", "nv:rgba"])
+            .number_of_values(2)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Equalize.as_str())
+            .help("Operation: equalize the histogram of the luminance of the input image, which improves the contrast of flat images")
+            .long(OperationId::Equalize.as_str())
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Extend.as_str())
+            .help("Operation: place the input image, without scaling it, on a larger canvas of the given width and height; \
+                   optionally followed by the gravity by which the image is placed (default: center) and the color of the \
+                   canvas (default: transparent)")
+            .long(OperationId::Extend.as_str())
+            .takes_value(true)
+            .value_name("width height [gravity] [rgba(r,g,b,a)]")
+            .min_values(2)
+            .multiple(true))
 
         .arg(Arg::with_name(OperationId::Filter3x3.as_str())
             .help("Operation: apply a 3x3 convolution filter to the input image (matrix arguments should be given left-to-right, top-to-bottom)")
@@ -260,6 +922,31 @@ pub fn create_app(
             .number_of_values(9)
             .multiple(true)
             .allow_hyphen_values(true))
+        .arg(Arg::with_name(OperationId::Find.as_str())
+            .help("Operation: find where the template image appears within the input image, by normalized cross-correlation \
+                   of their luminance, and report its position, size and score (see --analysis-format); optionally \
+                   followed by the color in which the found area is outlined")
+            .long(OperationId::Find.as_str())
+            .takes_value(true)
+            .value_name("path [rgba(r,g,b,a)]")
+            .min_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Fisheye.as_str())
+            .help("Operation: bulge the center of the input image, as if seen through a fisheye lens, or pinch it with a \
+                   negative strength; the strength is between -1 and 1")
+            .long(OperationId::Fisheye.as_str())
+            .takes_value(true)
+            .value_name("strength")
+            .number_of_values(1)
+            .multiple(true)
+            .allow_hyphen_values(true))
+        .arg(Arg::with_name(OperationId::FlattenChecker.as_str())
+            .help("Operation: composite the input image over a checkerboard with cells of the given size in pixels, so transparency can be judged in formats and viewers without alpha support")
+            .long(OperationId::FlattenChecker.as_str())
+            .takes_value(true)
+            .value_name("uint")
+            .number_of_values(1)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::FlipHorizontal.as_str())
             .help("Operation: flip the input image horizontally")
             .long(OperationId::FlipHorizontal.as_str())
@@ -268,11 +955,44 @@ pub fn create_app(
             .help("Operation: flip the input image vertically")
             .long(OperationId::FlipVertical.as_str())
             .multiple(true))
+        .arg(Arg::with_name(OperationId::Gradient.as_str())
+            .help("Operation: draw a 'linear' or 'radial' gradient between two colors over the input image, e.g. 'linear' 'rgba(0, 0, 0, 255)' 'rgba(0, 0, 0, 0)' '90'. \
+                   The optional angle of a linear gradient is in degrees clockwise, where 0 runs from left to right and 90 from top to bottom. \
+                   A radial gradient runs from the center of the image to its corners.")
+            .long(OperationId::Gradient.as_str())
+            .takes_value(true)
+            .value_name("linear|radial rgba(r,g,b,a) rgba(r,g,b,a) [angle]")
+            .min_values(3)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::GradientMap.as_str())
+            .help("Operation: remap the luminance of the input image through a gradient defined by two or more color stops, e.g. 'stop(0, rgba(0, 0, 0, 255))' 'stop(1, rgba(255, 200, 0, 255))'")
+            .long(OperationId::GradientMap.as_str())
+            .takes_value(true)
+            .value_name("nv:stop")
+            .min_values(2)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::Grayscale.as_str())
             .help("Operation: discard the chrominance signal from the input image, so it becomes achromatic")
             .long_help("Note that (depending on the provided settings flags), the processed image may still be stored in a format which encodes its chrominance")
             .long(OperationId::Grayscale.as_str())
             .multiple(true))
+        .arg(Arg::with_name(OperationId::GrayscaleWeighted.as_str())
+            .help("Operation: convert the input image to grayscale, with the luminance computed as the weighted sum of the red, green and blue channels, e.g. 0.2126 0.7152 0.0722 (Rec. 709) or 0.299 0.587 0.114 (Rec. 601)")
+            .long_help("The weights are normalized, so they don't have to sum to 1; they may not be negative")
+            .long(OperationId::GrayscaleWeighted.as_str())
+            .takes_value(true)
+            .value_names(&["wr", "wg", "wb"])
+            .number_of_values(3)
+            .multiple(true)
+            .allow_hyphen_values(true))
+        .arg(Arg::with_name(OperationId::Hstack.as_str())
+            .help("Operation: append the image loaded from the provided path to the right of the input image; the lower \
+                   of both images is centered vertically, and padded with transparent pixels")
+            .long(OperationId::Hstack.as_str())
+            .takes_value(true)
+            .value_name("path to image")
+            .number_of_values(1)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::HueRotate.as_str())
             .help("Operation: rotate the hue for each pixel of the input image by a provided degree")
             .long_help("Range is 0-360 degrees, any other value will be mapped to that range by rotation")
@@ -286,15 +1006,106 @@ pub fn create_app(
             .help("Operation: invert the each pixel of the input image ")
             .long(OperationId::Invert.as_str())
             .multiple(true))
+        .arg(Arg::with_name(OperationId::LocalMean.as_str())
+            .help("Operation: replace each pixel of the input image by the mean luminance of the pixels within the given radius around it")
+            .long(OperationId::LocalMean.as_str())
+            .takes_value(true)
+            .value_name("uint")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::LocalVariance.as_str())
+            .help("Operation: replace each pixel of the input image by the variance of the luminance of the pixels within the given radius around it")
+            .long(OperationId::LocalVariance.as_str())
+            .takes_value(true)
+            .value_name("uint")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Lut.as_str())
+            .help("Operation: map the colors of the input image through a 3D LUT, loaded from an Adobe .cube file at the provided path")
+            .long(OperationId::Lut.as_str())
+            .takes_value(true)
+            .value_name("path to .cube file")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Mirror.as_str())
+            .help("Operation: append a reflection of the input image to its right (horizontal), below it (vertical), or both \
+                   (quad), e.g. to create seamless textures; unlike flip, this doubles the dimensions of the image")
+            .long(OperationId::Mirror.as_str())
+            .takes_value(true)
+            .value_name("horizontal|vertical|quad")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::NineSlice.as_str())
+            .help("Operation: resize the input image to the given width and height like a nine-slice (9-patch) image: the \
+                   corners within the left, top, right and bottom insets are kept as they are, the edges are only scaled \
+                   along their length, and only the center is scaled in both directions")
+            .long(OperationId::NineSlice.as_str())
+            .takes_value(true)
+            .value_names(&["left", "top", "right", "bottom", "width", "height"])
+            .number_of_values(6)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::OilPaint.as_str())
+            .help("Operation: give the input image the look of an oil painting; each pixel takes the mean color of the most \
+                   common of the given number of intensity levels (1 to 256) within the given radius around it")
+            .long(OperationId::OilPaint.as_str())
+            .takes_value(true)
+            .value_names(&["radius", "levels"])
+            .number_of_values(2)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Opacity.as_str())
+            .help("Operation: multiply the alpha channel of the input image by the provided opacity, between 0 (fully transparent) and 1 (unchanged); an alpha channel is added if the image has none")
+            .long(OperationId::Opacity.as_str())
+            .takes_value(true)
+            .value_name("fp")
+            .number_of_values(1)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::Overlay.as_str())
-            .help("Operation: overlay an image loaded from the provided path argument, over the input image (at a certain position)")
+            .help("Operation: overlay an image loaded from the provided path argument, over the input image (at a certain position); \
+                   optionally followed by the opacity of the overlay, between 0 and 1 (default: 1), and the blend mode by which \
+                   its colors are combined with the input image: normal (default), multiply, screen, overlay, darken or lighten")
             .long(OperationId::Overlay.as_str())
-            .value_names(&["overlay image path", "x", "y"])
             .takes_value(true)
+            .value_name("path x y [opacity] [blend-mode]")
+            .min_values(3)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::PadToRatio.as_str())
+            .help("Operation: pad the input image at both sides of either its width or its height, by the least amount of \
+                   pixels needed to match the given aspect ratio, e.g. 16:9; optionally followed by the color of the \
+                   padding (default: transparent)")
+            .long(OperationId::PadToRatio.as_str())
+            .takes_value(true)
+            .value_name("w:h [rgba(r,g,b,a)]")
+            .min_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::PixelUpscale.as_str())
+            .help("Operation: upscale pixel art by a factor of 2, 3 or 4 with the Scale2x algorithm, which, unlike the \
+                   sampling filters of resize, keeps sprites crisp and smooths the jaggies of diagonal edges; optionally \
+                   followed by the algorithm: scale2x, which is the default and only algorithm")
+            .long(OperationId::PixelUpscale.as_str())
+            .takes_value(true)
+            .value_name("2|3|4 [scale2x]")
+            .min_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::RadialBlur.as_str())
+            .help("Operation: blur the input image outward from the center point (cx, cy), like a zoom burst; the strength \
+                   is between 0 (unchanged) and 1 (blur all the way to the center)")
+            .long(OperationId::RadialBlur.as_str())
+            .takes_value(true)
+            .value_names(&["cx", "cy", "strength"])
             .number_of_values(3)
             .multiple(true))
+        .arg(Arg::with_name(OperationId::Redact.as_str())
+            .help("Operation: irreversibly destroy the rectangle at (x, y) of width by height pixels, e.g. to hide private \
+                   information in a screenshot; optionally followed by whether the rectangle is filled with black (fill, \
+                   default) or replaced by a few large blocks with randomly shifted colors (pixelate)")
+            .long(OperationId::Redact.as_str())
+            .takes_value(true)
+            .value_name("rect(x,y,width,height) [fill|pixelate]")
+            .min_values(1)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::Resize.as_str())
-            .help("Operation: resize the input image to x by y pixels")
+            .help("Operation: resize the input image to x by y pixels, or to percentages of its width and height, e.g. '50%'; if either x or \
+                   y is 0, it is computed from the aspect ratio of the input image")
             .long(OperationId::Resize.as_str())
             .takes_value(true)
             .value_names(&["x", "y"])
@@ -312,6 +1123,50 @@ pub fn create_app(
             .help("Operation: rotate the input image by 270 degrees")
             .long(OperationId::Rotate270.as_str())
             .multiple(true))
+        .arg(Arg::with_name(OperationId::Sharpen.as_str())
+            .help("Operation: sharpen the input image with a Laplacian kernel, by an optional amount which defaults to 1; an amount of 0 leaves the image unchanged")
+            .long(OperationId::Sharpen.as_str())
+            .takes_value(true)
+            .value_name("amount")
+            // at most one value is taken per occurrence; clap counts the values of all
+            // occurrences together, so it can't limit the values of each occurrence
+            .min_values(0)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::SmartCrop.as_str())
+            .help("Operation: crop a selection of width by height pixels from the most interesting area of the input image, \
+                   which is estimated by the amount of detail (edges) within the area; useful for thumbnails, where a \
+                   center crop may cut off the subject")
+            .long(OperationId::SmartCrop.as_str())
+            .takes_value(true)
+            .value_names(&["width", "height"])
+            .number_of_values(2)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Stamp.as_str())
+            .help("Operation: overlay a stamp, such as a signature or logo, loaded from the provided path at the given gravity (top-left, top, top-right, left, center, right, bottom-left, bottom or bottom-right) and margin; its colors are inverted or a scrim is added below it when it would not stand out from the input image")
+            .long(OperationId::Stamp.as_str())
+            .value_names(&["stamp image path", "gravity", "margin"])
+            .takes_value(true)
+            .number_of_values(3)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Tonemap.as_str())
+            .help("Operation: map the high dynamic range samples of an HDR or OpenEXR input image to the displayable range with the 'reinhard' \
+                   or 'aces' tone mapping operator, so bright highlights aren't clipped. The samples are first scaled by the optional \
+                   exposure in stops (default: 0), e.g. 'aces' '-1.5'")
+            .long(OperationId::Tonemap.as_str())
+            .takes_value(true)
+            .value_name("reinhard|aces [exposure]")
+            .min_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OperationId::Trim.as_str())
+            .help("Operation: remove the uniform borders of the input image, such as the margins of a scan or screenshot; \
+                   the color of the borders is taken from the top left pixel, and pixels within the optional tolerance of \
+                   that color (default: 0, the euclidean distance between RGBA values) belong to the borders")
+            .long(OperationId::Trim.as_str())
+            .takes_value(true)
+            .value_name("tolerance")
+            // at most one value is taken per occurrence; see sharpen
+            .min_values(0)
+            .multiple(true))
         .arg(Arg::with_name(OperationId::Unsharpen.as_str())
             .help("Operation: sharpen an image by combining an unsharp (blurred) mask of the input image with the (original) input image, sharpening for pixels where the difference is bigger than the provided threshold")
             .long(OperationId::Unsharpen.as_str())
@@ -320,8 +1175,58 @@ pub fn create_app(
             .number_of_values(2)
             .multiple(true)
             .allow_hyphen_values(true))
+        .arg(Arg::with_name(OperationId::Vstack.as_str())
+            .help("Operation: append the image loaded from the provided path below the input image; the narrower of \
+                   both images is centered horizontally, and padded with transparent pixels")
+            .long(OperationId::Vstack.as_str())
+            .takes_value(true)
+            .value_name("path to image")
+            .number_of_values(1)
+            .multiple(true))
 
         // image-operations(cli-arguments/modifiers):
+        .arg(Arg::with_name(OperationId::Background.as_str())
+            .help("Operation modifier for 'extend', 'pad-to-ratio', 'perspective' and 'alpha-remove': fill the canvas \
+                   of 'extend' and 'pad-to-ratio', and the area of 'perspective' outside of the image, with the given \
+                   color, if they are given no color themselves, and blend transparent pixels with the color by \
+                   'alpha-remove', instead of dropping their transparency; e.g. 'rgba(255, 255, 255, 255)'")
+            .long(OperationId::Background.as_str())
+            .takes_value(true)
+            .value_name("rgba")
+            .number_of_values(1)
+            .multiple(true)
+        )
+        .arg(Arg::with_name(OperationId::BlurAlgorithm.as_str())
+            .help("Operation modifier for 'blur': blur the image using a Gaussian blur (default), or using a box blur \
+                   with about the same spread, which is much faster for large sigmas")
+            .long(OperationId::BlurAlgorithm.as_str())
+            .takes_value(true)
+            .value_name("algorithm")
+            .number_of_values(1)
+            .multiple(true)
+            .possible_values(&["gaussian", "box"])
+        )
+        .arg(Arg::with_name(OperationId::Gravity.as_str())
+            .help("Operation modifier for 'crop', 'overlay', 'draw-text' and 'extend': measure the coordinates of \
+                   'crop', 'overlay' and 'draw-text' from the given side or corner of the image, instead of from its \
+                   top left corner, and place the image by 'extend' towards it, if no gravity is given to 'extend' \
+                   itself; e.g. 'bottom-right' or 'south-east'")
+            .long(OperationId::Gravity.as_str())
+            .takes_value(true)
+            .value_name("gravity")
+            .number_of_values(1)
+            .multiple(true)
+        )
+        .arg(Arg::with_name(OperationId::Mask.as_str())
+            .help("Operation modifier for all operations: blend the results of the operations which follow it with the \
+                   image by a grayscale mask image of the same size as the image; operations apply where the mask is \
+                   white, not where it is black, and partially where it is gray")
+            .long(OperationId::Mask.as_str())
+            .takes_value(true)
+            .value_name("path to mask image")
+            .number_of_values(1)
+            .multiple(true)
+        )
         .arg(Arg::with_name(OperationId::PreserveAspectRatio.as_str())
             .help("Operation modifier for 'resize': preserve the aspect ratio of the original input image")
             .long(OperationId::PreserveAspectRatio.as_str())
@@ -331,15 +1236,105 @@ pub fn create_app(
             .multiple(true)
             .possible_values(&["true", "false"])
         )
+        .arg(Arg::with_name(OperationId::Region.as_str())
+            .help("Operation modifier for all operations: apply the operations which follow it only to the given \
+                   rectangle of the image, e.g. to blur a part of it; operations which change the size of the image \
+                   can't be restricted to a region")
+            .long(OperationId::Region.as_str())
+            .takes_value(true)
+            .value_name("rect(x, y, width, height)")
+            .number_of_values(1)
+            .multiple(true)
+        )
         .arg(Arg::with_name(OperationId::SamplingFilter.as_str())
-            .help("Operation modifier for 'resize': resize the image using a specific sampling-filter")
+            .help("Operation modifier for 'resize' and 'nine-slice': resize the image using a specific sampling-filter")
             .long(OperationId::SamplingFilter.as_str())
             .takes_value(true)
             .value_name("sampling filter")
             .number_of_values(1)
             .multiple(true)
-            .possible_values(&["catmullrom", "gaussian", "lanczos3", "nearest", "triangle"])
-        ))
+            .possible_values(&["box", "catmullrom", "gaussian", "hermite", "lanczos3", "mitchell", "nearest", "triangle"])
+        )
+
+        .subcommand(create_bench_subcommand())
+        .subcommand(create_ops_subcommand())
+        .subcommand(create_fmt_script_subcommand())
+        .subcommand(create_migrate_script_subcommand()))
+}
+
+fn create_bench_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND_BENCH)
+        .about("Repeatedly decodes the input image, applies the image operations and encodes the result, all in memory, \
+                and reports how long each stage took (minimum, percentiles and maximum).")
+        .arg(Arg::with_name(ARG_BENCH_PIPELINE)
+            .long("pipeline")
+            .value_name("SCRIPT")
+            .help("Image operations to apply, using the image script syntax (see --apply-operations).")
+            .takes_value(true))
+        .arg(Arg::with_name(ARG_BENCH_ITERATIONS)
+            .long("iterations")
+            .short("n")
+            .value_name("N")
+            .help("How many times the pipeline is run. Defaults to 10.")
+            .takes_value(true))
+        .arg(Arg::with_name(ARG_BENCH_OUTPUT_FORMAT)
+            .long("output-format")
+            .short("f")
+            .value_name("FORMAT")
+            .help("The format to which the image is encoded. Defaults to PNG.")
+            .takes_value(true))
+        .arg(Arg::with_name(ARG_BENCH_INPUT)
+            .value_name("INPUT_PATH")
+            .help("Input image path. If omitted, the image is read from the stdin.")
+            .index(1))
+}
+
+fn create_ops_subcommand() -> App<'static, 'static> {
+    let json = Arg::with_name(ARG_OPS_JSON)
+        .long("json")
+        .help("Outputs the operations as JSON, for use by other programs.");
+
+    SubCommand::with_name(SUBCOMMAND_OPS)
+        .about("Lists the available image operations and modifiers, or describes one of them.")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(SubCommand::with_name(SUBCOMMAND_OPS_LIST)
+            .about("Lists the syntax and a short description of each image operation and modifier.")
+            .arg(json.clone()))
+        .subcommand(SubCommand::with_name(SUBCOMMAND_OPS_DESCRIBE)
+            .about("Describes an image operation or modifier, including the types and accepted ranges of its arguments.")
+            .arg(Arg::with_name(ARG_OPS_OPERATION)
+                .value_name("OPERATION")
+                .help("Name of the operation, e.g. 'blur'.")
+                .required(true)
+                .index(1))
+            .arg(json))
+}
+
+fn create_fmt_script_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND_FMT_SCRIPT)
+        .about("Parses an image operations script and prints it in canonical form: one statement per line, with normalized \
+                whitespace and numbers.")
+        .args(&script_file_args("Overwrites the script file with the formatted script, instead of printing it."))
+}
+
+fn create_migrate_script_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND_MIGRATE_SCRIPT)
+        .about("Converts an image operations script written for an earlier version, e.g. 'fliph; set resize sampling_filter \
+                nearest', to the current syntax, and prints it in canonical form.")
+        .args(&script_file_args("Overwrites the script file with the migrated script, instead of printing it."))
+}
+
+fn script_file_args(in_place_help: &'static str) -> [Arg<'static, 'static>; 2] {
+    [
+        Arg::with_name(ARG_SCRIPT_IN_PLACE)
+            .long("in-place")
+            .help(in_place_help),
+        Arg::with_name(ARG_SCRIPT_PATH)
+            .value_name("SCRIPT_PATH")
+            .help("Path to the script file, e.g. 'pipeline.sic'.")
+            .required(true)
+            .index(1),
+    ]
 }
 
 // Here any argument should not panic when invalid.
@@ -365,7 +1360,10 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> anyhow::Result<Config<'a
         return Ok(builder.build());
     }
 
-    builder = builder.mode(InputOutputModeType::from_arg_matches(matches)?);
+    // exporting image operations doesn't involve an input image, and a dry run doesn't require one
+    if !matches.is_present(ARG_EXPORT_PIPELINE) && !matches.is_present(ARG_DRY_RUN) {
+        builder = builder.mode(InputOutputModeType::from_arg_matches(matches)?);
+    }
 
     // config(in)/gif-select-frame:
     if let Some(frame_in) = matches.value_of(ARG_SELECT_FRAME) {
@@ -395,6 +1393,62 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> anyhow::Result<Config<'a
         builder = builder.select_frame(frame_out);
     }
 
+    // config(in)/input-format:
+    if let Some(format) = matches.value_of(ARG_INPUT_FORMAT) {
+        let (width, height) = matches
+            .value_of(ARG_INPUT_SIZE)
+            .ok_or_else(|| anyhow!("The --input-format '{}' requires --input-size.", format))
+            .and_then(parse_input_size)?;
+
+        let format = match format {
+            "raw-rgba8" => ForcedInputFormat::RawRgba8 { width, height },
+            _ => bail!("Provided argument for --input-format is not valid. Expected 'raw-rgba8'."),
+        };
+
+        builder = builder.forced_input_format(format);
+    }
+
+    // config(in)/create:
+    if let Some(values) = matches.values_of(ARG_CREATE) {
+        let size = values
+            .map(|value| value.parse::<u32>().ok().filter(|value| *value > 0))
+            .collect::<Option<Vec<_>>>();
+
+        match size.as_deref() {
+            Some(&[width, height]) => builder = builder.canvas(width, height),
+            _ => bail!(
+                "Provided argument for --create is not valid: expected the width and height of \
+                 the canvas in pixels, both larger than 0, e.g. '--create 640 480'."
+            ),
+        }
+    }
+
+    // config(in)/assert:
+    if let Some(assertion) = matches.value_of(ARG_ASSERT) {
+        builder = builder.image_assertion(ImageAssertion::try_from_str(assertion)?);
+    }
+
+    // config(in)/sprite-sheet:
+    if matches.is_present(ARG_SPRITE_SHEET) {
+        let columns = matches
+            .value_of(ARG_SPRITE_SHEET_COLUMNS)
+            .map(|value| match value.parse::<u32>() {
+                Ok(columns) if columns > 0 => Ok(columns),
+                _ => Err(anyhow!(
+                    "Provided argument for --sprite-sheet-columns is not valid. \
+                     The amount of columns should be a positive number larger than 0."
+                )),
+            })
+            .transpose()?;
+
+        builder = builder.sprite_sheet(SpriteSheetSettings {
+            columns,
+            manifest_path: matches
+                .value_of(ARG_SPRITE_SHEET_MANIFEST)
+                .map(PathBuf::from),
+        });
+    }
+
     // config(out)/disable-automatic-color-type-adjustment:
     if matches.is_present(ARG_DISABLE_AUTOMATIC_COLOR_TYPE_ADJUSTMENT) {
         builder = builder.disable_automatic_color_type_adjustment(true);
@@ -405,6 +1459,49 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> anyhow::Result<Config<'a
         builder = builder.forced_output_format(format);
     }
 
+    // config(out)/fix-extension:
+    if matches.is_present(ARG_FIX_EXTENSION) {
+        builder = builder.fix_extension(true);
+    }
+
+    // config(out)/no-atomic:
+    if matches.is_present(ARG_NO_ATOMIC) {
+        builder = builder.atomic_output(false);
+    }
+
+    // config(out)/output-lock:
+    if let Some(lock) = matches.value_of(ARG_OUTPUT_LOCK) {
+        builder = builder.output_lock(OutputLock::try_from_str(lock)?);
+    }
+
+    // config(out)/deep-zoom:
+    if matches.is_present(ARG_DEEP_ZOOM) {
+        let defaults = DeepZoomSettings::default();
+
+        let tile_size = match matches.value_of(ARG_DEEP_ZOOM_TILE_SIZE) {
+            Some(value) => match value.parse::<u32>() {
+                Ok(size) if size > 0 => size,
+                _ => bail!(
+                    "Provided argument for --deep-zoom-tile-size is not valid. \
+                     The tile size should be a positive number larger than 0."
+                ),
+            },
+            None => defaults.tile_size,
+        };
+
+        let overlap = match matches.value_of(ARG_DEEP_ZOOM_OVERLAP) {
+            Some(value) => value.parse::<u32>().map_err(|_| {
+                anyhow!(
+                    "Provided argument for --deep-zoom-overlap is not valid. \
+                     The overlap should be a positive number."
+                )
+            })?,
+            None => defaults.overlap,
+        };
+
+        builder = builder.deep_zoom(DeepZoomSettings { tile_size, overlap });
+    }
+
     // config(out)/jpeg-encoding-quality:
     if let Some(value) = matches.value_of(ARG_JPEG_ENCODING_QUALITY) {
         let requested_jpeg_quality = u8::from_str(value)
@@ -415,6 +1512,58 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> anyhow::Result<Config<'a
         builder = builder.jpeg_quality(requested_jpeg_quality);
     }
 
+    // config(out)/jpeg-progressive:
+    if matches.is_present(ARG_JPEG_PROGRESSIVE) {
+        builder = builder.jpeg_progressive(true);
+    }
+
+    // config(out)/webp-quality:
+    if let Some(value) = matches.value_of(ARG_WEBP_QUALITY) {
+        let requested_webp_quality = u8::from_str(value)
+            .ok()
+            .filter(|quality| *quality <= 100)
+            .ok_or_else(|| {
+                anyhow!("WebP quality should be a value between 0 and 100 (inclusive).")
+            })?;
+        builder = builder.webp_quality(requested_webp_quality);
+    }
+
+    // config(out)/webp-lossless:
+    if matches.is_present(ARG_WEBP_LOSSLESS) {
+        builder = builder.webp_lossless(true);
+    }
+
+    // config(out)/tiff-compression:
+    if let Some(value) = matches.value_of(ARG_TIFF_COMPRESSION) {
+        builder = builder.tiff_compression(TiffCompression::try_from_str(value)?);
+    }
+
+    // config(out)/dds-compression:
+    if let Some(value) = matches.value_of(ARG_DDS_COMPRESSION) {
+        builder = builder.dds_compression(DdsCompression::try_from_str(value)?);
+    }
+
+    // config(out)/dds-mipmaps:
+    if matches.is_present(ARG_DDS_MIPMAPS) {
+        builder = builder.dds_mipmaps(true);
+    }
+
+    // config(out)/gif-palette:
+    if let Some(value) = matches.value_of(ARG_GIF_PALETTE) {
+        builder = builder.gif_palette(GifPalette::try_from_str(value)?);
+    }
+
+    // config(out)/gif-colors:
+    if let Some(value) = matches.value_of(ARG_GIF_COLORS) {
+        let requested_gif_colors = u16::from_str(value)
+            .ok()
+            .filter(|colors| (2..=256).contains(colors))
+            .ok_or_else(|| {
+                anyhow!("GIF colors should be a value between 2 and 256 (inclusive).")
+            })?;
+        builder = builder.gif_colors(requested_gif_colors);
+    }
+
     // config(out)/pnm-encoding-type:
     if matches.is_present(ARG_PNM_ENCODING_ASCII) {
         builder = builder.pnm_format_type(true);
@@ -424,6 +1573,16 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> anyhow::Result<Config<'a
     builder =
         builder.image_output_format_decider_fallback(matches.is_present(ARG_IMAGE_CRATE_FALLBACK));
 
+    // image-operations/explain-dir:
+    if let Some(dir) = matches.value_of(ARG_EXPLAIN_DIR) {
+        builder = builder.explain_dir(PathBuf::from(dir));
+    }
+
+    // image-operations/analysis-format:
+    if let Some(format) = matches.value_of(ARG_ANALYSIS_FORMAT) {
+        builder = builder.analysis_format(AnalysisFormat::try_from_str(format)?);
+    }
+
     // image-operations:
     //
     // Image operations are a bit more involved.
@@ -446,16 +1605,41 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> anyhow::Result<Config<'a
     // argv ourselves: --crop 0 0 1 1 --crop, is valid according to Clap. However, since we do not
     // receive the amount of times --crop was defined, but rather all the separate provided values for
     // the name of the argument, we just know that for `crop` we have values 0,0,1,1.
-    let program = if let Some(script) = matches.value_of(ARG_APPLY_OPERATIONS) {
-        sic_parser::parse_script(script)?
-    } else if let Some(path) = matches.value_of(ARG_OPERATIONS_SCRIPT) {
-        let contents = std::fs::read_to_string(Path::new(path))
-            .map_err(|err| anyhow::anyhow!("unable to read script file: {}", err))?;
-        sic_parser::parse_script(&contents)?
+    let macros = match matches.value_of(ARG_MACROS) {
+        Some(path) => Macros::from_script_file(Path::new(path))?,
+        None => Macros::default(),
+    };
+
+    // a script given as '@<path>' is read from the file at the path
+    let script_file = matches.value_of(ARG_APPLY_OPERATIONS_FILE).or_else(|| {
+        matches
+            .value_of(ARG_APPLY_OPERATIONS)
+            .and_then(|script| script.strip_prefix('@'))
+    });
+
+    let program = if let Some(path) = matches.value_of(ARG_PIPELINE) {
+        read_pipeline_file(Path::new(path))?
+    } else if let Some(path) = script_file {
+        builder = builder.image_operations_script(PathBuf::from(path));
+
+        if matches.is_present(ARG_EXPAND_ENV) {
+            sic_parser::parse_script_file_with_environment_variables(Path::new(path), &macros)?
+        } else {
+            sic_parser::parse_script_file(Path::new(path), &macros)?
+        }
+    } else if let Some(script) = matches.value_of(ARG_APPLY_OPERATIONS) {
+        if matches.is_present(ARG_EXPAND_ENV) {
+            sic_parser::parse_script_with_environment_variables(script, &macros)?
+        } else {
+            sic_parser::parse_script_with_macros(script, &macros)?
+        }
     } else {
-        create_image_ops(std::env::args())?
+        create_image_ops_with_macros(std::env::args(), &macros)?
     };
 
+    // a modifier set for an occurrence which isn't applied would otherwise be ignored silently
+    sic_image_engine::engine::check_occurrences(&program)?;
+
     builder = builder.image_operations_program(program);
 
     Ok(builder.build())
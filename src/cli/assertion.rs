@@ -0,0 +1,431 @@
+//! Image assertions are checked against the decoded input image, before any image operation is
+//! applied. If an assertion does not hold, sic exits with an error, which makes it possible to use
+//! sic as an image validator, for example: `--assert "width>=800 && has_alpha==false"`.
+//!
+//! An assertion consists of comparisons between an image property and a value, which can be
+//! combined with `&&` and `||`. The `&&` operator binds stronger than `||`.
+
+use anyhow::{anyhow, bail};
+use sic_core::image::{ColorType, DynamicImage, GenericImageView};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageAssertion {
+    source: String,
+    // Disjunction of conjunctions: `a && b || c` is represented as `[[a, b], [c]]`.
+    any_of: Vec<Vec<Comparison>>,
+}
+
+impl ImageAssertion {
+    pub fn try_from_str(source: &str) -> anyhow::Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut tokens = tokens.into_iter().peekable();
+
+        let mut any_of = Vec::new();
+        let mut all_of = Vec::new();
+
+        loop {
+            all_of.push(parse_comparison(&mut tokens)?);
+
+            match tokens.next() {
+                Some(Token::And) => continue,
+                Some(Token::Or) => any_of.push(std::mem::take(&mut all_of)),
+                None => {
+                    any_of.push(all_of);
+                    break;
+                }
+                Some(token) => bail!(
+                    "Unable to parse image assertion: expected '&&' or '||', but found '{}'",
+                    token
+                ),
+            }
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            any_of,
+        })
+    }
+
+    /// Returns an error describing the failed comparisons if the assertion does not hold for
+    /// the given image.
+    pub fn check(&self, image: &DynamicImage) -> anyhow::Result<()> {
+        let holds = self
+            .any_of
+            .iter()
+            .any(|all_of| all_of.iter().all(|cmp| cmp.holds_for(image)));
+
+        if holds {
+            return Ok(());
+        }
+
+        let failed = self
+            .any_of
+            .iter()
+            .flatten()
+            .filter(|cmp| !cmp.holds_for(image))
+            .map(|cmp| format!("{} (actual: {})", cmp, cmp.property.value_of(image)))
+            .collect::<Vec<_>>();
+
+        Err(anyhow!(
+            "Image assertion '{}' failed; the following conditions did not hold:\n\t{}",
+            self.source,
+            failed.join("\n\t")
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Property {
+    Width,
+    Height,
+    Channels,
+    BitDepth,
+    HasAlpha,
+    HasColor,
+    ColorType,
+}
+
+impl Property {
+    fn try_from_name(name: &str) -> anyhow::Result<Self> {
+        Ok(match name {
+            "width" => Property::Width,
+            "height" => Property::Height,
+            "channels" => Property::Channels,
+            "bit_depth" => Property::BitDepth,
+            "has_alpha" => Property::HasAlpha,
+            "has_color" => Property::HasColor,
+            "color_type" => Property::ColorType,
+            _ => bail!(
+                "Unable to parse image assertion: unknown property '{}' (valid properties are: \
+                 width, height, channels, bit_depth, has_alpha, has_color and color_type)",
+                name
+            ),
+        })
+    }
+
+    fn value_of(self, image: &DynamicImage) -> Value {
+        let color = image.color();
+
+        match self {
+            Property::Width => Value::Number(u64::from(image.width())),
+            Property::Height => Value::Number(u64::from(image.height())),
+            Property::Channels => Value::Number(u64::from(color.channel_count())),
+            Property::BitDepth => Value::Number(u64::from(
+                color.bits_per_pixel() / u16::from(color.channel_count()),
+            )),
+            Property::HasAlpha => Value::Bool(color.has_alpha()),
+            Property::HasColor => Value::Bool(color.has_color()),
+            Property::ColorType => Value::Ident(color_type_name(color).to_string()),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Property::Width => "width",
+            Property::Height => "height",
+            Property::Channels => "channels",
+            Property::BitDepth => "bit_depth",
+            Property::HasAlpha => "has_alpha",
+            Property::HasColor => "has_color",
+            Property::ColorType => "color_type",
+        }
+    }
+}
+
+fn color_type_name(color: ColorType) -> &'static str {
+    match color {
+        ColorType::L8 => "l8",
+        ColorType::La8 => "la8",
+        ColorType::Rgb8 => "rgb8",
+        ColorType::Rgba8 => "rgba8",
+        ColorType::L16 => "l16",
+        ColorType::La16 => "la16",
+        ColorType::Rgb16 => "rgb16",
+        ColorType::Rgba16 => "rgba16",
+        ColorType::Bgr8 => "bgr8",
+        ColorType::Bgra8 => "bgra8",
+        _ => "unknown",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Operator {
+    fn symbol(self) -> &'static str {
+        match self {
+            Operator::Eq => "==",
+            Operator::Ne => "!=",
+            Operator::Lt => "<",
+            Operator::Le => "<=",
+            Operator::Gt => ">",
+            Operator::Ge => ">=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(u64),
+    Bool(bool),
+    Ident(String),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Ident(ident) => f.write_str(ident),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    property: Property,
+    operator: Operator,
+    value: Value,
+}
+
+impl Comparison {
+    fn holds_for(&self, image: &DynamicImage) -> bool {
+        let actual = self.property.value_of(image);
+
+        match (&actual, &self.value) {
+            (Value::Number(lhs), Value::Number(rhs)) => match self.operator {
+                Operator::Eq => lhs == rhs,
+                Operator::Ne => lhs != rhs,
+                Operator::Lt => lhs < rhs,
+                Operator::Le => lhs <= rhs,
+                Operator::Gt => lhs > rhs,
+                Operator::Ge => lhs >= rhs,
+            },
+            (lhs, rhs) => match self.operator {
+                Operator::Eq => lhs == rhs,
+                Operator::Ne => lhs != rhs,
+                // rejected while parsing
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            self.property.name(),
+            self.operator.symbol(),
+            self.value
+        )
+    }
+}
+
+fn parse_comparison<I: Iterator<Item = Token>>(
+    tokens: &mut std::iter::Peekable<I>,
+) -> anyhow::Result<Comparison> {
+    let property = match tokens.next() {
+        Some(Token::Word(word)) => Property::try_from_name(&word)?,
+        Some(token) => bail!(
+            "Unable to parse image assertion: expected a property, but found '{}'",
+            token
+        ),
+        None => bail!("Unable to parse image assertion: expected a property, but found nothing"),
+    };
+
+    let operator = match tokens.next() {
+        Some(Token::Operator(op)) => op,
+        _ => bail!(
+            "Unable to parse image assertion: expected a comparison operator after '{}'",
+            property.name()
+        ),
+    };
+
+    let value = match tokens.next() {
+        Some(Token::Word(word)) => parse_value(property, &word)?,
+        _ => bail!(
+            "Unable to parse image assertion: expected a value after '{}{}'",
+            property.name(),
+            operator.symbol()
+        ),
+    };
+
+    let is_ordered = matches!(value, Value::Number(_));
+    if !is_ordered && !matches!(operator, Operator::Eq | Operator::Ne) {
+        bail!(
+            "Unable to parse image assertion: property '{}' can only be compared using '==' or '!='",
+            property.name()
+        );
+    }
+
+    Ok(Comparison {
+        property,
+        operator,
+        value,
+    })
+}
+
+fn parse_value(property: Property, word: &str) -> anyhow::Result<Value> {
+    let value = match property {
+        Property::Width | Property::Height | Property::Channels | Property::BitDepth => word
+            .parse::<u64>()
+            .map(Value::Number)
+            .map_err(|_| anyhow!("expected a positive number for '{}'", property.name())),
+        Property::HasAlpha | Property::HasColor => word
+            .parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| anyhow!("expected 'true' or 'false' for '{}'", property.name())),
+        Property::ColorType => Ok(Value::Ident(word.to_ascii_lowercase())),
+    };
+
+    value.map_err(|err| anyhow!("Unable to parse image assertion: {}", err))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Operator(Operator),
+    And,
+    Or,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(word) => f.write_str(word),
+            Token::Operator(op) => f.write_str(op.symbol()),
+            Token::And => f.write_str("&&"),
+            Token::Or => f.write_str("||"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_alphanumeric() || c == '_' {
+                    word.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Word(word));
+        } else {
+            chars.next();
+            let followed_by_eq = chars.peek() == Some(&'=');
+
+            let token = match (c, followed_by_eq) {
+                ('&', _) if chars.next_if_eq(&'&').is_some() => Token::And,
+                ('|', _) if chars.next_if_eq(&'|').is_some() => Token::Or,
+                ('=', true) => Token::Operator(Operator::Eq),
+                ('!', true) => Token::Operator(Operator::Ne),
+                ('<', true) => Token::Operator(Operator::Le),
+                ('>', true) => Token::Operator(Operator::Ge),
+                ('<', false) => Token::Operator(Operator::Lt),
+                ('>', false) => Token::Operator(Operator::Gt),
+                _ => bail!(
+                    "Unable to parse image assertion: unexpected character '{}'",
+                    c
+                ),
+            };
+
+            if let Token::Operator(Operator::Eq | Operator::Ne | Operator::Le | Operator::Ge) =
+                token
+            {
+                chars.next();
+            }
+
+            tokens.push(token);
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{DynamicImage, RgbImage, RgbaImage};
+
+    fn rgb_800x600() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::new(800, 600))
+    }
+
+    ide!();
+
+    #[parameterized(
+        assertion = {
+            "width>=800 && has_alpha==false",
+            "width == 800 && height == 600",
+            "width < 800 || height < 601",
+            "channels==3 && bit_depth==8",
+            "color_type == rgb8",
+            "color_type != RGBA8 && has_color == true",
+            "width > 1000 || height > 1000 || has_alpha == false",
+        }
+    )]
+    fn assertion_holds(assertion: &str) {
+        let assertion = ImageAssertion::try_from_str(assertion).unwrap();
+        assert!(assertion.check(&rgb_800x600()).is_ok());
+    }
+
+    #[parameterized(
+        assertion = {
+            "width>800",
+            "width>=800 && has_alpha==true",
+            "width < 800 || height < 600",
+            "color_type == rgba8",
+            "bit_depth != 8",
+        }
+    )]
+    fn assertion_does_not_hold(assertion: &str) {
+        let assertion = ImageAssertion::try_from_str(assertion).unwrap();
+        assert!(assertion.check(&rgb_800x600()).is_err());
+    }
+
+    #[parameterized(
+        assertion = {
+            "",
+            "width",
+            "width >=",
+            "width >= -1",
+            "width => 800",
+            "depth == 8",
+            "has_alpha < true",
+            "width >= 800 &&",
+            "width >= 800 & height >= 600",
+            "width >= 800 height >= 600",
+        }
+    )]
+    fn assertion_invalid(assertion: &str) {
+        assert!(ImageAssertion::try_from_str(assertion).is_err());
+    }
+
+    #[test]
+    fn failure_mentions_actual_value() {
+        let assertion = ImageAssertion::try_from_str("has_alpha == true").unwrap();
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(1, 1));
+        assert!(assertion.check(&image).is_ok());
+
+        let err = assertion.check(&rgb_800x600()).unwrap_err();
+        assert!(err.to_string().contains("has_alpha==true (actual: false)"));
+    }
+}
@@ -0,0 +1,242 @@
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context};
+use clap::ArgMatches;
+use sic_image_engine::engine::{ImageEngine, Instr};
+use sic_io::conversion::AutomaticColorTypeAdjustment;
+use sic_io::format::{DetermineEncodingFormat, EncodingFormat, EncodingFormatByIdentifier};
+use sic_io::{load, save};
+
+use crate::cli::app::arg_names::{
+    ARG_BENCH_INPUT, ARG_BENCH_ITERATIONS, ARG_BENCH_OUTPUT_FORMAT, ARG_BENCH_PIPELINE,
+};
+
+/// Settings of the `bench` subcommand, which repeatedly decodes an image, applies the image
+/// operations and encodes the result, all in memory, and reports how long each stage took.
+#[derive(Debug)]
+pub struct BenchSettings {
+    /// Image to decode; if `None`, the image is read from the stdin.
+    pub input: Option<PathBuf>,
+    pub program: Vec<Instr>,
+    pub iterations: u32,
+    pub output_format: String,
+}
+
+impl BenchSettings {
+    pub fn from_matches(matches: &ArgMatches) -> anyhow::Result<Self> {
+        let program = match matches.value_of(ARG_BENCH_PIPELINE) {
+            Some(script) => sic_parser::parse_script(script)?,
+            None => Vec::new(),
+        };
+
+        let iterations = match matches.value_of(ARG_BENCH_ITERATIONS) {
+            Some(value) => match value.parse::<u32>() {
+                Ok(iterations) if iterations > 0 => iterations,
+                _ => bail!(
+                    "Provided argument for --iterations is not valid. \
+                     The amount of iterations should be a positive number larger than 0."
+                ),
+            },
+            None => 10,
+        };
+
+        Ok(Self {
+            input: matches.value_of(ARG_BENCH_INPUT).map(PathBuf::from),
+            program,
+            iterations,
+            output_format: matches
+                .value_of(ARG_BENCH_OUTPUT_FORMAT)
+                .unwrap_or("png")
+                .to_string(),
+        })
+    }
+}
+
+/// Measured durations of each stage, one per iteration.
+#[derive(Debug, Default)]
+pub struct BenchReport {
+    pub decode: Vec<Duration>,
+    pub operations: Vec<Duration>,
+    pub encode: Vec<Duration>,
+    pub total: Vec<Duration>,
+}
+
+impl BenchReport {
+    /// Formats the report as a table, with a row for each stage.
+    pub fn to_table(&self) -> String {
+        let mut table = format!(
+            "{:<12}{:>12}{:>12}{:>12}{:>12}{:>12}\n",
+            "stage", "min", "p50", "p90", "p99", "max"
+        );
+
+        let stages = [
+            ("decode", &self.decode),
+            ("operations", &self.operations),
+            ("encode", &self.encode),
+            ("total", &self.total),
+        ];
+
+        for (stage, durations) in stages.iter() {
+            let mut sorted = durations.to_vec();
+            sorted.sort();
+
+            table.push_str(&format!("{:<12}", stage));
+
+            for &quantile in [0.0, 50.0, 90.0, 99.0, 100.0].iter() {
+                let value = percentile(&sorted, quantile).unwrap_or_default();
+                table.push_str(&format!("{:>10.3}ms", value.as_secs_f64() * 1000.0));
+            }
+
+            table.push('\n');
+        }
+
+        table
+    }
+}
+
+/// Nearest-rank percentile of sorted durations; `None` if there are no durations.
+fn percentile(sorted: &[Duration], quantile: f64) -> Option<Duration> {
+    let rank = (quantile / 100.0 * sorted.len() as f64).ceil() as usize;
+    let index = rank.saturating_sub(1).min(sorted.len().checked_sub(1)?);
+
+    sorted.get(index).copied()
+}
+
+pub fn run_bench(settings: &BenchSettings) -> anyhow::Result<()> {
+    let bytes = read_input(settings)?;
+    let format = DetermineEncodingFormat::default().by_identifier(&settings.output_format)?;
+
+    let report = bench(&bytes, &settings.program, settings.iterations, format)?;
+
+    println!(
+        "{} iterations of decode, image operations and encode (to {}):\n",
+        settings.iterations, settings.output_format
+    );
+    print!("{}", report.to_table());
+
+    Ok(())
+}
+
+fn read_input(settings: &BenchSettings) -> anyhow::Result<Vec<u8>> {
+    match &settings.input {
+        Some(path) => std::fs::read(path)
+            .with_context(|| format!("Unable to read input image '{}'.", path.display())),
+        None if atty::is(atty::Stream::Stdin) => Err(anyhow!(
+            "An input image should be given by providing a path or by piping an image to the stdin."
+        )),
+        None => {
+            let mut bytes = Vec::new();
+            std::io::stdin().read_to_end(&mut bytes)?;
+            Ok(bytes)
+        }
+    }
+}
+
+/// Runs the full pipeline `iterations` times. The input image is read once beforehand and the
+/// output is encoded in memory, so only the work done by sic itself is measured.
+pub fn bench(
+    bytes: &[u8],
+    program: &[Instr],
+    iterations: u32,
+    format: EncodingFormat,
+) -> anyhow::Result<BenchReport> {
+    let mut report = BenchReport::default();
+    let mut output = Vec::with_capacity(bytes.len());
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+
+        let (image, frames) = load::load_image_with_frame_count(
+            &mut Cursor::new(bytes),
+            &load::ImportConfig::default(),
+        )?;
+        let decoded = Instant::now();
+
+        let mut engine = ImageEngine::new(image).with_frame_count(frames);
+        let image = engine
+            .ignite(program)
+            .with_context(|| "Unable to apply image operations.")?;
+        let operated = Instant::now();
+
+        output.clear();
+        save::export(
+            image,
+            &mut output,
+            format.clone(),
+            save::ExportSettings {
+                adjust_color_type: AutomaticColorTypeAdjustment::default(),
+            },
+        )
+        .with_context(|| "Unable to encode image.")?;
+        let encoded = Instant::now();
+
+        report.decode.push(decoded - start);
+        report.operations.push(operated - decoded);
+        report.encode.push(encoded - operated);
+        report.total.push(encoded - start);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn percentiles() {
+        let sorted = millis(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+        assert_eq!(percentile(&sorted, 0.0), Some(Duration::from_millis(1)));
+        assert_eq!(percentile(&sorted, 50.0), Some(Duration::from_millis(5)));
+        assert_eq!(percentile(&sorted, 90.0), Some(Duration::from_millis(9)));
+        assert_eq!(percentile(&sorted, 99.0), Some(Duration::from_millis(10)));
+        assert_eq!(percentile(&sorted, 100.0), Some(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn percentile_single() {
+        let sorted = millis(&[3]);
+
+        assert_eq!(percentile(&sorted, 50.0), Some(Duration::from_millis(3)));
+    }
+
+    #[test]
+    fn percentile_empty() {
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn bench_pipeline() {
+        let bytes = std::fs::read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/resources/palette_4x4.png"
+        ))
+        .unwrap();
+        let program = sic_parser::parse_script("invert; resize 8 8;").unwrap();
+
+        let report = bench(&bytes, &program, 3, image::ImageOutputFormat::Png.into()).unwrap();
+
+        assert_eq!(report.total.len(), 3);
+        assert!(report.to_table().contains("operations"));
+    }
+
+    #[test]
+    fn bench_undecodable_input() {
+        let result = bench(
+            b"not an image",
+            &[],
+            1,
+            image::ImageOutputFormat::Png.into(),
+        );
+
+        assert!(result.is_err());
+    }
+}
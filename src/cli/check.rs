@@ -0,0 +1,53 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use clap::ArgMatches;
+use sic_image_engine::engine::Instr;
+
+use crate::cli::app::arg_names::ARG_INPUT;
+use crate::cli::config::Config;
+
+/// Runs `--check`, which validates the image operations, the files they refer to (e.g. the image
+/// of `diff` or the font of `draw-text`) and the input path, without decoding or encoding the
+/// input image, so pipelines can be linted cheaply, e.g. in CI.
+pub fn run_check(
+    matches: &ArgMatches,
+    config: &Config,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    if let Some(input) = matches.value_of(ARG_INPUT) {
+        if !Path::new(input).is_file() {
+            bail!("The input file '{}' doesn't exist.", input);
+        }
+    }
+
+    let program = &config.image_operations_program;
+
+    for (index, instruction) in program.iter().enumerate() {
+        instruction.check_files().with_context(|| {
+            format!(
+                "Unable to use the file of image operation {} ('{}').",
+                index + 1,
+                name(instruction)
+            )
+        })?;
+    }
+
+    writeln!(
+        writer,
+        "Checked {} image operation(s); no problems found.",
+        program.len()
+    )?;
+
+    Ok(())
+}
+
+pub(crate) fn name(instruction: &Instr) -> &str {
+    match instruction {
+        Instr::Operation(op) => op.as_ref(),
+        Instr::Deferred(op) => op.as_ref(),
+        Instr::EnvAdd(_) | Instr::EnvAddFor(..) => "set",
+        Instr::EnvRemove(_) => "del",
+    }
+}
@@ -2,13 +2,15 @@ use crate::cli::app::arg_names::{
     ARG_GLOB_NO_SKIP_UNSUPPORTED_EXTENSIONS, ARG_IMAGE_CRATE_FALLBACK, ARG_INPUT, ARG_INPUT_GLOB,
     ARG_OUTPUT, ARG_OUTPUT_GLOB,
 };
+use crate::cli::assertion::ImageAssertion;
 use crate::cli::common_dir::CommonDir;
 use crate::cli::glob_base_dir::glob_builder_base;
 use anyhow::{bail, Context};
 use clap::ArgMatches;
 use globwalk::{FileType, GlobWalker};
 use sic_image_engine::engine::Instr;
-use sic_io::load::FrameIndex;
+use sic_io::format::{DdsCompression, DdsSettings, GifPalette, TiffCompression};
+use sic_io::load::{ForcedInputFormat, FrameIndex};
 use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
@@ -128,6 +130,7 @@ fn filter_unsupported_paths(paths: Vec<PathBuf>, fallback_enabled: bool) -> Vec<
                 .by_extension(path)
                 .fallback_if(fallback_enabled, guess_output_by_path, path)
                 .is_ok()
+                || sic_io::psd::has_psd_extension(path)
         })
         .collect()
 }
@@ -145,7 +148,8 @@ impl InputOutputModeType {
                 matches.is_present(ARG_INPUT),
                 matches.is_present(ARG_INPUT_GLOB),
             ) {
-                (true, false) => InputOutputModeType::Simple,
+                // without an input path, the input image is read from the stdin
+                (_, false) => InputOutputModeType::Simple,
                 (false, true) => InputOutputModeType::Batch,
                 _ => {
                     bail!("Unable select input/output mode: mode should either be simple xor glob")
@@ -165,7 +169,21 @@ pub struct Config<'a> {
     /// Display license of this software or its dependencies.
     pub show_license_text_of: Option<SelectedLicenses>,
 
-    pub selected_frame: FrameIndex,
+    /// For animated images; the frame which is used as still image. If no frame is selected, all
+    /// frames are kept if the output is an animated GIF, and the first frame is used otherwise.
+    pub selected_frame: Option<FrameIndex>,
+
+    /// The format of the input images, if it can't be recognized from their contents.
+    pub forced_input_format: Option<ForcedInputFormat>,
+
+    /// The width and height of a blank canvas, which is used instead of an input image.
+    pub canvas: Option<(u32, u32)>,
+
+    /// Assertion which should hold for the decoded input image, before image operations are applied.
+    pub image_assertion: Option<ImageAssertion>,
+
+    /// Lay out all frames of an animated image on a sprite sheet, instead of selecting a single frame.
+    pub sprite_sheet: Option<SpriteSheetSettings>,
 
     /// Disable color type adjustments on save.
     pub disable_automatic_color_type_adjustment: bool,
@@ -173,6 +191,19 @@ pub struct Config<'a> {
     /// Format to which an image will be converted (enforced).
     pub forced_output_format: Option<&'a str>,
 
+    /// Adjust the extension of the output path if it does not match the forced output format.
+    pub fix_extension: bool,
+
+    /// Cut the output image into the tiles of a Deep Zoom image pyramid, instead of writing a
+    /// single image.
+    pub deep_zoom: Option<DeepZoomSettings>,
+
+    /// What to do if the output file is locked by another process which writes to it.
+    pub output_lock: OutputLock,
+
+    /// Write the output image to a temporary file first, which replaces the output once complete.
+    pub atomic_output: bool,
+
     /// Encoding settings for specific output formats.
     pub encoding_settings: FormatEncodingSettings,
 
@@ -181,6 +212,16 @@ pub struct Config<'a> {
     /// THe value set here should be presented as a [sic_image_engine::engine::Program].
     /// If no program is present, an empty vec should be provided.
     pub image_operations_program: Vec<Instr>,
+
+    /// The script file from which the image operations program was read, if any.
+    pub image_operations_script: Option<PathBuf>,
+
+    /// If set, a thumbnail of the intermediate image is written to this directory after each
+    /// image operation.
+    pub explain_dir: Option<PathBuf>,
+
+    /// How the results of image operations which analyse the image are reported.
+    pub analysis_format: AnalysisFormat,
 }
 
 impl Default for Config<'_> {
@@ -194,8 +235,21 @@ impl Default for Config<'_> {
             /// Defaults to no displayed license text.
             show_license_text_of: None,
 
-            /// By default the first frame of a gif is used.
-            selected_frame: FrameIndex::First,
+            /// By default all frames of a gif are kept for gif output, and the first frame is used
+            /// otherwise.
+            selected_frame: None,
+
+            /// By default, the format of the input images is recognized from their contents.
+            forced_input_format: None,
+
+            /// By default, an input image is read instead of creating a blank canvas.
+            canvas: None,
+
+            /// Defaults to not checking any properties of the input image.
+            image_assertion: None,
+
+            /// By default, a single frame is selected instead of creating a sprite sheet.
+            sprite_sheet: None,
 
             /// Defaults to using automatic color type adjustment where appropriate.
             disable_automatic_color_type_adjustment: false,
@@ -203,11 +257,40 @@ impl Default for Config<'_> {
             /// Defaults to not forcing a specific image output format.
             forced_output_format: None,
 
+            /// Defaults to warning about, but not adjusting, mismatching output extensions.
+            fix_extension: false,
+
+            /// By default, a single output image is written instead of Deep Zoom tiles.
+            deep_zoom: None,
+
+            /// By default, we wait until other processes are done writing to the output file.
+            output_lock: OutputLock::Wait,
+
+            /// By default, output images are replaced atomically.
+            atomic_output: true,
+
             /// Default format encoding settings.
             encoding_settings: FormatEncodingSettings {
                 /// Default JPEG quality is set to 80.
                 jpeg_quality: 80,
 
+                // JPEG images are encoded baseline by default.
+                jpeg_progressive: false,
+
+                // Default WebP quality is set to 75, encoded lossy.
+                webp_quality: 75,
+                webp_lossless: false,
+
+                // TIFF images are not compressed by default.
+                tiff_compression: TiffCompression::None,
+
+                // DDS textures are not compressed and have no mipmaps by default.
+                dds_settings: DdsSettings::default(),
+
+                // GIF frames share a single palette of up to 256 colors by default.
+                gif_palette: GifPalette::Global,
+                gif_colors: 256,
+
                 /// Default encoding type of PNM files (excluding PAM) is set to binary.
                 pnm_use_ascii_format: false,
 
@@ -217,6 +300,15 @@ impl Default for Config<'_> {
 
             /// Defaults to no provided image operations script.
             image_operations_program: Vec::new(),
+
+            /// Defaults to image operations which weren't read from a script file.
+            image_operations_script: None,
+
+            /// Defaults to not writing thumbnails of intermediate images.
+            explain_dir: None,
+
+            /// By default, analysis results are reported as lines of text.
+            analysis_format: AnalysisFormat::Text,
         }
     }
 }
@@ -250,7 +342,31 @@ impl<'a> ConfigBuilder<'a> {
 
     // config(in)
     pub fn select_frame(mut self, frame: FrameIndex) -> ConfigBuilder<'a> {
-        self.settings.selected_frame = frame;
+        self.settings.selected_frame = Some(frame);
+        self
+    }
+
+    // config(in)
+    pub fn forced_input_format(mut self, format: ForcedInputFormat) -> ConfigBuilder<'a> {
+        self.settings.forced_input_format = Some(format);
+        self
+    }
+
+    // config(in)
+    pub fn canvas(mut self, width: u32, height: u32) -> ConfigBuilder<'a> {
+        self.settings.canvas = Some((width, height));
+        self
+    }
+
+    // config(in)
+    pub fn image_assertion(mut self, assertion: ImageAssertion) -> ConfigBuilder<'a> {
+        self.settings.image_assertion = Some(assertion);
+        self
+    }
+
+    // config(in)
+    pub fn sprite_sheet(mut self, settings: SpriteSheetSettings) -> ConfigBuilder<'a> {
+        self.settings.sprite_sheet = Some(settings);
         self
     }
 
@@ -260,6 +376,30 @@ impl<'a> ConfigBuilder<'a> {
         self
     }
 
+    // config(out)
+    pub fn fix_extension(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.fix_extension = toggle;
+        self
+    }
+
+    // config(out)
+    pub fn deep_zoom(mut self, settings: DeepZoomSettings) -> ConfigBuilder<'a> {
+        self.settings.deep_zoom = Some(settings);
+        self
+    }
+
+    // config(out)
+    pub fn output_lock(mut self, lock: OutputLock) -> ConfigBuilder<'a> {
+        self.settings.output_lock = lock;
+        self
+    }
+
+    // config(out)
+    pub fn atomic_output(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.atomic_output = toggle;
+        self
+    }
+
     // config(out)
     pub fn disable_automatic_color_type_adjustment(mut self, toggle: bool) -> ConfigBuilder<'a> {
         self.settings.disable_automatic_color_type_adjustment = toggle;
@@ -272,6 +412,54 @@ impl<'a> ConfigBuilder<'a> {
         self
     }
 
+    // config(out)
+    pub fn jpeg_progressive(mut self, progressive: bool) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.jpeg_progressive = progressive;
+        self
+    }
+
+    // config(out)
+    pub fn webp_quality(mut self, quality: u8) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.webp_quality = quality;
+        self
+    }
+
+    // config(out)
+    pub fn webp_lossless(mut self, lossless: bool) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.webp_lossless = lossless;
+        self
+    }
+
+    // config(out)
+    pub fn tiff_compression(mut self, compression: TiffCompression) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.tiff_compression = compression;
+        self
+    }
+
+    // config(out)
+    pub fn dds_compression(mut self, compression: DdsCompression) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.dds_settings.compression = compression;
+        self
+    }
+
+    // config(out)
+    pub fn dds_mipmaps(mut self, mipmaps: bool) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.dds_settings.mipmaps = mipmaps;
+        self
+    }
+
+    // config(out)
+    pub fn gif_palette(mut self, palette: GifPalette) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.gif_palette = palette;
+        self
+    }
+
+    // config(out)
+    pub fn gif_colors(mut self, colors: u16) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.gif_colors = colors;
+        self
+    }
+
     // config(out)
     pub fn pnm_format_type(mut self, use_ascii: bool) -> ConfigBuilder<'a> {
         self.settings.encoding_settings.pnm_use_ascii_format = use_ascii;
@@ -292,6 +480,21 @@ impl<'a> ConfigBuilder<'a> {
         self
     }
 
+    pub fn image_operations_script(mut self, path: PathBuf) -> ConfigBuilder<'a> {
+        self.settings.image_operations_script = Some(path);
+        self
+    }
+
+    pub fn explain_dir(mut self, dir: PathBuf) -> ConfigBuilder<'a> {
+        self.settings.explain_dir = Some(dir);
+        self
+    }
+
+    pub fn analysis_format(mut self, format: AnalysisFormat) -> ConfigBuilder<'a> {
+        self.settings.analysis_format = format;
+        self
+    }
+
     pub fn build(self) -> Config<'a> {
         self.settings
     }
@@ -303,9 +506,90 @@ pub enum SelectedLicenses {
     Dependencies,
 }
 
+#[derive(Debug, Clone)]
+pub struct SpriteSheetSettings {
+    /// Amount of frames per row; if `None`, all frames are placed on a single row.
+    pub columns: Option<u32>,
+
+    /// Where to write the JSON manifest. If `None`, the manifest is written next to the output
+    /// image.
+    pub manifest_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeepZoomSettings {
+    /// Width and height of each tile, excluding the overlap.
+    pub tile_size: u32,
+
+    /// Amount of pixels each tile shares with its neighbouring tiles, on each side.
+    pub overlap: u32,
+}
+
+impl Default for DeepZoomSettings {
+    fn default() -> Self {
+        // The defaults of the Deep Zoom tools, which lead to tiles of at most 256 by 256 pixels.
+        Self {
+            tile_size: 254,
+            overlap: 1,
+        }
+    }
+}
+
+/// Output files are locked while they're written, so concurrent invocations which target the same
+/// output don't interleave their writes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OutputLock {
+    /// Wait until the lock is released, then overwrite the output.
+    Wait,
+    /// Leave the output as is, if it is locked by another process.
+    Skip,
+}
+
+impl OutputLock {
+    pub fn try_from_str(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "wait" => Ok(OutputLock::Wait),
+            "skip" => Ok(OutputLock::Skip),
+            _ => bail!(
+                "Provided argument for --output-lock is not valid. \
+                 Expected either 'wait' or 'skip'."
+            ),
+        }
+    }
+}
+
+/// How the results of image operations which analyse the image, such as `find`, are reported.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AnalysisFormat {
+    /// A line of text per result.
+    Text,
+    /// A single JSON array of all results.
+    Json,
+}
+
+impl AnalysisFormat {
+    pub fn try_from_str(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "text" => Ok(AnalysisFormat::Text),
+            "json" => Ok(AnalysisFormat::Json),
+            _ => bail!(
+                "Provided argument for --analysis-format is not valid. \
+                 Expected either 'text' or 'json'."
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FormatEncodingSettings {
     pub jpeg_quality: u8,
+    pub jpeg_progressive: bool,
+    pub webp_quality: u8,
+    pub webp_lossless: bool,
+    pub tiff_compression: TiffCompression,
+    pub dds_settings: DdsSettings,
+    pub gif_palette: GifPalette,
+    pub gif_colors: u16,
     pub pnm_use_ascii_format: bool,
 
     // Whether to fallback on the image crate to determine the output format if sic doesn't support it yet
@@ -330,6 +614,22 @@ pub fn validate_jpeg_quality(quality: u8) -> anyhow::Result<u8> {
     within_range(quality)
 }
 
+/// Parses the size of an input image as `WxH`, e.g. `640x480`, where both the width and height are
+/// larger than 0.
+pub fn parse_input_size(size: &str) -> anyhow::Result<(u32, u32)> {
+    let dimension = |value: &str| value.trim().parse::<u32>().ok().filter(|value| *value > 0);
+
+    size.split_once(['x', 'X'])
+        .and_then(|(width, height)| Some((dimension(width)?, dimension(height)?)))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Provided argument for --input-size is not valid: expected the width and height \
+                 in pixels as WxH, e.g. '640x480', but got '{}'.",
+                size
+            )
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -363,6 +663,28 @@ mod tests {
         assert!(validate_jpeg_quality(u8::from_str(value).unwrap()).is_err())
     }
 
+    #[test]
+    fn input_size() {
+        for size in &["640x480", "640X480", " 640 x 480 "] {
+            assert_eq!(parse_input_size(size).unwrap(), (640, 480));
+        }
+    }
+
+    #[test]
+    fn input_size_invalid() {
+        for size in &[
+            "640",
+            "640x",
+            "x480",
+            "0x480",
+            "640x0",
+            "-1x480",
+            "640x480x3",
+        ] {
+            assert!(parse_input_size(size).is_err());
+        }
+    }
+
     #[test]
     fn config_builder_override_defaults() {
         let mut builder = ConfigBuilder::new();
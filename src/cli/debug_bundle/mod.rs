@@ -0,0 +1,267 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use clap::ArgMatches;
+use serde_json::{json, Value};
+use sic_core::image;
+use sic_image_engine::engine::Instr;
+
+use crate::cli::app::arg_names::{
+    ARG_DEBUG_BUNDLE, ARG_DEBUG_BUNDLE_INCLUDE_PIXELS, ARG_INPUT, ARG_INPUT_GLOB,
+};
+use crate::cli::debug_bundle::zip::ZipWriter;
+use crate::cli::pipeline_file::{self, PipelineFormat};
+
+mod zip;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Where the input image was read from, as given on the command line.
+#[derive(Debug)]
+enum Input {
+    Path(PathBuf),
+    Glob(String),
+    Stdin,
+}
+
+/// A zip archive with the information needed to reproduce a failure: the command line arguments,
+/// the parsed image operations, metadata of the input image, the versions and the full error
+/// chain. It's only written to the local file system when an error occurred, and never sent
+/// anywhere.
+///
+/// The input image itself is only included on request, since it may be private.
+#[derive(Debug)]
+pub struct DebugBundle {
+    path: PathBuf,
+    include_pixels: bool,
+    input: Input,
+    arguments: Vec<String>,
+}
+
+impl DebugBundle {
+    /// Returns `None` if no debug bundle was requested.
+    pub fn from_matches(matches: &ArgMatches) -> Option<Self> {
+        let path = matches.value_of(ARG_DEBUG_BUNDLE)?;
+
+        let input = match (
+            matches.value_of(ARG_INPUT),
+            matches.value_of(ARG_INPUT_GLOB),
+        ) {
+            (Some(path), _) => Input::Path(path.into()),
+            (None, Some(pattern)) => Input::Glob(pattern.to_string()),
+            (None, None) => Input::Stdin,
+        };
+
+        Some(Self {
+            path: path.into(),
+            include_pixels: matches.is_present(ARG_DEBUG_BUNDLE_INCLUDE_PIXELS),
+            input,
+            arguments: std::env::args_os()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+        })
+    }
+
+    /// Writes the debug bundle for the given error, if one was requested, and hands back the
+    /// error. The image operations program is `None` when it couldn't be parsed.
+    pub fn report(
+        bundle: Option<&DebugBundle>,
+        program: Option<&[Instr]>,
+        error: anyhow::Error,
+    ) -> anyhow::Error {
+        if let Some(bundle) = bundle {
+            match bundle.write(program, &error) {
+                Ok(()) => eprintln!(
+                    "info: A debug bundle was written to '{}'. Please attach it to your bug report.",
+                    bundle.path.display()
+                ),
+                Err(err) => eprintln!("warn: Unable to write the debug bundle: {:?}", err),
+            }
+        }
+
+        error
+    }
+
+    fn write(&self, program: Option<&[Instr]>, error: &anyhow::Error) -> anyhow::Result<()> {
+        let file = File::create(&self.path)
+            .with_context(|| format!("Unable to create debug bundle '{}'.", self.path.display()))?;
+        let mut zip = ZipWriter::new(BufWriter::new(file));
+
+        zip.add_file("error.txt", format!("{:?}\n", error).as_bytes())?;
+        zip.add_file("arguments.json", &to_json(&json!(self.arguments))?)?;
+        zip.add_file("versions.json", &to_json(&versions())?)?;
+        zip.add_file("input.json", &to_json(&self.input_metadata())?)?;
+
+        if let Some(program) = program {
+            // written as by --export-pipeline, so it can be applied again with --pipeline
+            let pipeline = pipeline_file::to_string(program, PipelineFormat::Json)?;
+            zip.add_file("pipeline.json", pipeline.as_bytes())?;
+        }
+
+        if let (true, Input::Path(path)) = (self.include_pixels, &self.input) {
+            if let Ok(bytes) = std::fs::read(path) {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "image".to_string());
+
+                zip.add_file(&format!("input/{}", name), &bytes)?;
+            }
+        }
+
+        zip.finish()?;
+
+        Ok(())
+    }
+
+    fn input_metadata(&self) -> Value {
+        match &self.input {
+            Input::Path(path) => image_metadata(path),
+            Input::Glob(pattern) => json!({ "glob": pattern }),
+            Input::Stdin => json!({ "stdin": true }),
+        }
+    }
+}
+
+/// Metadata which can be read without decoding the pixels of the image.
+fn image_metadata(path: &Path) -> Value {
+    let size = match std::fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        Err(err) => return json!({ "path": path, "error": err.to_string() }),
+    };
+
+    let reader = image::io::Reader::open(path).and_then(|reader| reader.with_guessed_format());
+    let format = reader
+        .as_ref()
+        .ok()
+        .and_then(|reader| reader.format())
+        .map(|format| format!("{:?}", format));
+    let dimensions = reader
+        .map_err(image::ImageError::from)
+        .and_then(|reader| reader.into_dimensions());
+
+    match dimensions {
+        Ok((width, height)) => json!({
+            "path": path,
+            "size_bytes": size,
+            "format": format,
+            "width": width,
+            "height": height,
+        }),
+        Err(err) => json!({
+            "path": path,
+            "size_bytes": size,
+            "format": format,
+            "error": err.to_string(),
+        }),
+    }
+}
+
+fn versions() -> Value {
+    let features: &[&str] = if cfg!(feature = "imageproc-ops") {
+        &["imageproc-ops"]
+    } else {
+        &[]
+    };
+
+    json!({
+        "sic": VERSION,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "features": features,
+    })
+}
+
+fn to_json(value: &Value) -> anyhow::Result<Vec<u8>> {
+    Ok(serde_json::to_vec_pretty(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::DeflateDecoder;
+    use sic_image_engine::engine::{DeferredOp, EnvItem, ItemName, Occurrence};
+    use sic_image_engine::wrapper::dimension::Dimension::{Percentage, Pixels};
+    use sic_image_engine::ImgOp;
+    use std::io::Read;
+
+    /// The contents of the file with the given name in the zip archive.
+    fn file_in_zip(bytes: &[u8], name: &str) -> String {
+        // the name follows the 30 bytes of the local file header
+        let header = bytes
+            .windows(name.len())
+            .position(|window| window == name.as_bytes())
+            .unwrap()
+            - 30;
+        let compressed_size = u32::from_le_bytes([
+            bytes[header + 18],
+            bytes[header + 19],
+            bytes[header + 20],
+            bytes[header + 21],
+        ]) as usize;
+        let data = header + 30 + name.len();
+
+        let mut contents = String::new();
+        DeflateDecoder::new(&bytes[data..data + compressed_size])
+            .read_to_string(&mut contents)
+            .unwrap();
+        contents
+    }
+
+    #[test]
+    fn pipeline_can_be_read_back() {
+        let program = vec![
+            Instr::Operation(ImgOp::Blur(1.5)),
+            Instr::EnvAdd(EnvItem::PreserveAspectRatio(true)),
+            Instr::EnvRemove(ItemName::PreserveAspectRatio),
+            Instr::EnvAddFor(
+                Occurrence::new("resize", 1).unwrap(),
+                EnvItem::PreserveAspectRatio(false),
+            ),
+            Instr::Deferred(DeferredOp::Resize([Percentage(50.0), Pixels(0)])),
+        ];
+
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("target")
+            .join("debug_bundle");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let bundle = DebugBundle {
+            path: dir.join("pipeline_can_be_read_back.zip"),
+            include_pixels: false,
+            input: Input::Stdin,
+            arguments: Vec::new(),
+        };
+        bundle
+            .write(Some(&program), &anyhow::anyhow!("failure"))
+            .unwrap();
+
+        let bytes = std::fs::read(&bundle.path).unwrap();
+        let pipeline = file_in_zip(&bytes, "pipeline.json");
+
+        assert_eq!(
+            pipeline_file::from_str(&pipeline, PipelineFormat::Json).unwrap(),
+            program
+        );
+    }
+
+    #[test]
+    fn metadata_of_image() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/rainbow_8x6.bmp");
+
+        let metadata = image_metadata(&path);
+
+        assert_eq!(metadata["format"], "Bmp");
+        assert_eq!(metadata["width"], 8);
+        assert_eq!(metadata["height"], 6);
+    }
+
+    #[test]
+    fn metadata_of_missing_file() {
+        let metadata = image_metadata(Path::new("does-not-exist.png"));
+
+        assert!(metadata["error"].is_string());
+    }
+}
@@ -0,0 +1,230 @@
+//! A minimal writer for zip archives, which only supports what the debug bundle needs: a flat
+//! list of deflate compressed files, written at once.
+
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::convert::TryFrom;
+use std::io::{self, Write};
+
+const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_DIRECTORY_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+
+/// Version 2.0 of the zip specification introduced deflate compression.
+const VERSION: u16 = 20;
+/// General purpose flag which marks file names as UTF-8.
+const FLAG_UTF8: u16 = 1 << 11;
+const METHOD_DEFLATE: u16 = 8;
+/// MS-DOS date of 1980-01-01, the earliest date which can be stored; the bundle has no use for
+/// modification times.
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+pub(crate) struct ZipWriter<W: Write> {
+    writer: W,
+    offset: u32,
+    entries: Vec<Entry>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            offset: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    pub(crate) fn add_file(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let mut crc = Crc::new();
+        crc.update(data);
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc.sum(),
+            compressed_size: to_u32(compressed.len())?,
+            size: to_u32(data.len())?,
+            offset: self.offset,
+        };
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        put_u32(&mut header, LOCAL_FILE_HEADER_SIGNATURE);
+        put_u16(&mut header, VERSION);
+        put_common_fields(&mut header, &entry);
+        put_u16(&mut header, 0); // extra field length
+        header.extend_from_slice(name.as_bytes());
+
+        self.write(&header)?;
+        self.write(&compressed)?;
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    /// Writes the central directory, which lists all files of the archive.
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        let start = self.offset;
+        let mut directory = Vec::new();
+
+        for entry in &self.entries {
+            put_u32(&mut directory, CENTRAL_DIRECTORY_HEADER_SIGNATURE);
+            put_u16(&mut directory, VERSION); // version made by
+            put_u16(&mut directory, VERSION); // version needed to extract
+            put_common_fields(&mut directory, entry);
+            put_u16(&mut directory, 0); // extra field length
+            put_u16(&mut directory, 0); // file comment length
+            put_u16(&mut directory, 0); // disk number start
+            put_u16(&mut directory, 0); // internal file attributes
+            put_u32(&mut directory, 0); // external file attributes
+            put_u32(&mut directory, entry.offset);
+            directory.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let count = u16::try_from(self.entries.len()).map_err(|_| too_large())?;
+        let size = to_u32(directory.len())?;
+
+        put_u32(&mut directory, END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        put_u16(&mut directory, 0); // number of this disk
+        put_u16(&mut directory, 0); // disk where the central directory starts
+        put_u16(&mut directory, count); // entries on this disk
+        put_u16(&mut directory, count); // entries in total
+        put_u32(&mut directory, size);
+        put_u32(&mut directory, start);
+        put_u16(&mut directory, 0); // comment length
+
+        self.write(&directory)?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(bytes)?;
+        self.offset = self
+            .offset
+            .checked_add(to_u32(bytes.len())?)
+            .ok_or_else(too_large)?;
+
+        Ok(())
+    }
+}
+
+/// The fields which the local file header and the central directory header have in common, from
+/// the general purpose flags up to and including the file name length.
+fn put_common_fields(buffer: &mut Vec<u8>, entry: &Entry) {
+    put_u16(buffer, FLAG_UTF8);
+    put_u16(buffer, METHOD_DEFLATE);
+    put_u16(buffer, 0); // modification time
+    put_u16(buffer, DOS_DATE);
+    put_u32(buffer, entry.crc);
+    put_u32(buffer, entry.compressed_size);
+    put_u32(buffer, entry.size);
+    put_u16(buffer, entry.name.len() as u16);
+}
+
+fn put_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn to_u32(value: usize) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| too_large())
+}
+
+fn too_large() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "The debug bundle is too large to be stored as a zip archive.",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+    }
+
+    fn archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Vec::new());
+        for (name, data) in files {
+            zip.add_file(name, data).unwrap();
+        }
+        zip.finish().unwrap()
+    }
+
+    #[test]
+    fn end_of_central_directory() {
+        let bytes = archive(&[("a.txt", b"hello"), ("b/c.json", b"{}")]);
+        let end = bytes.len() - 22;
+
+        assert_eq!(u32_at(&bytes, end), END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        assert_eq!(u16_at(&bytes, end + 10), 2);
+
+        let size = u32_at(&bytes, end + 12) as usize;
+        let offset = u32_at(&bytes, end + 16) as usize;
+        assert_eq!(offset + size, end);
+        assert_eq!(u32_at(&bytes, offset), CENTRAL_DIRECTORY_HEADER_SIGNATURE);
+    }
+
+    #[test]
+    fn files_can_be_read_back() {
+        let text = "sic ".repeat(100);
+        let bytes = archive(&[("first.txt", b"1"), ("second.txt", text.as_bytes())]);
+
+        // the second local file header follows the first file
+        let first_size = u32_at(&bytes, 18) as usize;
+        let second = 30 + "first.txt".len() + first_size;
+        assert_eq!(u32_at(&bytes, second), LOCAL_FILE_HEADER_SIGNATURE);
+
+        let compressed_size = u32_at(&bytes, second + 18) as usize;
+        let size = u32_at(&bytes, second + 22) as usize;
+        let name_length = u16_at(&bytes, second + 26) as usize;
+        assert_eq!(
+            &bytes[second + 30..second + 30 + name_length],
+            b"second.txt"
+        );
+        assert_eq!(size, text.len());
+        assert!(compressed_size < size);
+
+        let data = second + 30 + name_length;
+        let mut decoded = String::new();
+        DeflateDecoder::new(&bytes[data..data + compressed_size])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, text);
+
+        let mut crc = Crc::new();
+        crc.update(text.as_bytes());
+        assert_eq!(u32_at(&bytes, second + 14), crc.sum());
+    }
+
+    #[test]
+    fn empty_archive() {
+        let bytes = archive(&[]);
+
+        assert_eq!(bytes.len(), 22);
+        assert_eq!(u32_at(&bytes, 0), END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+    }
+}
@@ -0,0 +1,314 @@
+use std::io::Write;
+
+use anyhow::Context;
+use clap::ArgMatches;
+use serde::Serialize;
+use serde_json::Value;
+use sic_core::image::io::Reader;
+use sic_image_engine::dry_run::{DryRun, Step};
+use sic_image_engine::engine::{EnvItem, Instr, ItemName};
+
+use crate::cli::app::arg_names::ARG_INPUT;
+use crate::cli::check::name;
+use crate::cli::config::Config;
+
+/// Runs `--dry-run`, which prints the image operations as they would be applied: their resolved
+/// arguments, the modifiers they would be applied with, and the dimensions of the image after
+/// each of them, where they can be predicted. The dimensions of the input image are read from its
+/// header, if an input is given, or are those of the canvas given by `--create`; no image is
+/// decoded or encoded.
+pub fn run_dry_run(
+    matches: &ArgMatches,
+    config: &Config,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let input_dimensions = match matches.value_of(ARG_INPUT) {
+        None if config.canvas.is_some() => config.canvas,
+        Some(input) => Some(
+            Reader::open(input)
+                .and_then(|reader| reader.with_guessed_format())
+                .map_err(anyhow::Error::from)
+                .and_then(|reader| Ok(reader.into_dimensions()?))
+                .with_context(|| {
+                    format!(
+                        "Unable to read the dimensions of the input file '{}'.",
+                        input
+                    )
+                })?,
+        ),
+        None => None,
+    };
+
+    writeln!(writer, "input: {}", dimensions(input_dimensions))?;
+
+    let mut dry_run = DryRun::new(input_dimensions);
+    let mut output_dimensions = input_dimensions;
+
+    for (index, instruction) in config.image_operations_program.iter().enumerate() {
+        let step = dry_run.step(instruction).with_context(|| {
+            format!(
+                "Image operation {} ('{}') would fail.",
+                index + 1,
+                name(instruction)
+            )
+        })?;
+
+        write_step(writer, index + 1, &step)?;
+        output_dimensions = step.dimensions;
+    }
+
+    writeln!(writer, "output: {}", dimensions(output_dimensions))?;
+
+    Ok(())
+}
+
+fn write_step(writer: &mut dyn Write, number: usize, step: &Step) -> anyhow::Result<()> {
+    match &step.instruction {
+        Instr::Operation(op) => writeln!(writer, "{}. {}", number, format_operation(op))?,
+        Instr::Deferred(op) => {
+            writeln!(writer, "{}. {} (unresolved)", number, format_operation(op))?
+        }
+        Instr::EnvAdd(item) => writeln!(writer, "{}. set {}", number, format_modifier(item))?,
+        Instr::EnvAddFor(occurrence, item) => writeln!(
+            writer,
+            "{}. set {} {}",
+            number,
+            occurrence,
+            format_modifier(item)
+        )?,
+        Instr::EnvRemove(key) => writeln!(writer, "{}. del {}", number, modifier_name(*key))?,
+    }
+
+    if let Instr::Operation(_) | Instr::Deferred(_) = step.instruction {
+        if !step.modifiers.is_empty() {
+            let modifiers = step
+                .modifiers
+                .iter()
+                .map(format_modifier)
+                .collect::<Vec<_>>();
+
+            writeln!(writer, "   modifiers: {}", modifiers.join(", "))?;
+        }
+
+        writeln!(writer, "   size: {}", dimensions(step.dimensions))?;
+    }
+
+    Ok(())
+}
+
+/// Formats an operation much like it's written in an image operations script, e.g. `resize 4 0`:
+/// its name, followed by its arguments, as they're serialized. Structured arguments are formatted
+/// like named values, in the order of their fields, e.g. `coord(0, 10)`.
+fn format_operation<T: Serialize>(operation: &T) -> String {
+    // serialized to text first, so 32 bit floating point numbers are not widened
+    let value = serde_json::to_string(operation)
+        .and_then(|text| serde_json::from_str::<Value>(&text))
+        .unwrap_or(Value::Null);
+
+    match value {
+        Value::String(name) => name,
+        Value::Object(operation) => operation
+            .iter()
+            .map(|(name, arguments)| match format_arguments(arguments) {
+                arguments if arguments.is_empty() => name.to_string(),
+                arguments => format!("{} {}", name, arguments),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        other => format_value(&other),
+    }
+}
+
+fn format_arguments(arguments: &Value) -> String {
+    match arguments {
+        Value::Array(values) => values
+            .iter()
+            .map(format_value)
+            .collect::<Vec<_>>()
+            .join(" "),
+        Value::Object(fields) if fields.len() == 1 => {
+            format_arguments(fields.values().next().unwrap())
+        }
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(name, value)| format_named_value(name, value))
+            .collect::<Vec<_>>()
+            .join(" "),
+        other => format_value(other),
+    }
+}
+
+fn format_named_value(name: &str, value: &Value) -> String {
+    let name = name.replace('_', "-");
+
+    match value {
+        Value::Object(fields) if fields.len() == 1 => {
+            format!("{}({})", name, format_list(fields.values()))
+        }
+        Value::Array(values) => format!("{}({})", name, format_list(values.iter())),
+        Value::Object(fields) => format!(
+            "{}({})",
+            name,
+            fields
+                .iter()
+                .map(|(name, value)| format_named_value(name, value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        other => format!("{}({})", name, format_value(other)),
+    }
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "none".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(value) => value.to_string(),
+        // identifiers, such as the names of modes, are written without quotes
+        Value::String(value)
+            if !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') =>
+        {
+            value.to_string()
+        }
+        Value::String(value) => format!("{:?}", value),
+        Value::Array(values) => format!("({})", format_list(values.iter())),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(name, value)| format_named_value(name, value))
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn format_list<'a>(values: impl Iterator<Item = &'a Value>) -> String {
+    values.map(format_value).collect::<Vec<_>>().join(", ")
+}
+
+/// Formats a modifier as it's set in an image operations script, e.g. `gravity center`.
+fn format_modifier(item: &EnvItem) -> String {
+    let value = match item {
+        EnvItem::AdaptiveThresholdMethod(method) => format_operation(method),
+        EnvItem::BackgroundColor(color) => format!(
+            "rgba({}, {}, {}, {})",
+            color[0], color[1], color[2], color[3]
+        ),
+        EnvItem::BlurAlgorithm(algorithm) => format_operation(algorithm),
+        EnvItem::CustomSamplingFilter(filter) => format_operation(filter),
+        EnvItem::Gravity(gravity) => format_operation(gravity),
+        EnvItem::Mask(mask) => format!("{:?}", mask.path().display().to_string()),
+        EnvItem::PreserveAspectRatio(preserve) => preserve.to_string(),
+        EnvItem::Region(region) => format!(
+            "rect({}, {}, {}, {})",
+            region.x(),
+            region.y(),
+            region.width(),
+            region.height()
+        ),
+    };
+
+    format!("{} {}", modifier_name(ItemName::from(item)), value)
+}
+
+/// The name of a modifier in an image operations script.
+fn modifier_name(modifier: ItemName) -> &'static str {
+    match modifier {
+        ItemName::AdaptiveThresholdMethod => "adaptive-threshold-method",
+        ItemName::BackgroundColor => "background",
+        ItemName::BlurAlgorithm => "blur-algorithm",
+        ItemName::CustomSamplingFilter => "sampling-filter",
+        ItemName::Gravity => "gravity",
+        ItemName::Mask => "mask",
+        ItemName::PreserveAspectRatio => "preserve-aspect-ratio",
+        ItemName::Region => "region",
+    }
+}
+
+fn dimensions(dimensions: Option<(u32, u32)>) -> String {
+    match dimensions {
+        Some((width, height)) => format!("{}x{}", width, height),
+        None => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::imageops::FilterType;
+    use sic_image_engine::engine::Occurrence;
+    use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
+    use sic_image_engine::wrapper::gravity::Gravity;
+    use sic_image_engine::ImgOp;
+
+    fn written(step: &Step) -> String {
+        let mut out = Vec::new();
+        write_step(&mut out, 2, step).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn write_operation() {
+        let step = Step {
+            instruction: Instr::Operation(ImgOp::Resize((10, 20))),
+            modifiers: vec![
+                EnvItem::CustomSamplingFilter(FilterTypeWrap::new(FilterType::Nearest)),
+                EnvItem::PreserveAspectRatio(true),
+            ],
+            dimensions: Some((10, 8)),
+        };
+
+        assert_eq!(
+            written(&step),
+            "2. resize 10 20\n   modifiers: sampling-filter nearest, preserve-aspect-ratio true\n   size: 10x8\n"
+        );
+    }
+
+    #[test]
+    fn write_operation_of_unknown_size() {
+        let step = Step {
+            instruction: Instr::Operation(ImgOp::Trim(0.0)),
+            modifiers: Vec::new(),
+            dimensions: None,
+        };
+
+        assert_eq!(written(&step), "2. trim 0.0\n   size: unknown\n");
+    }
+
+    #[test]
+    fn write_modifier() {
+        let step = Step {
+            instruction: Instr::EnvRemove(ItemName::Gravity),
+            modifiers: Vec::new(),
+            dimensions: Some((10, 8)),
+        };
+
+        assert_eq!(written(&step), "2. del gravity\n");
+    }
+
+    #[test]
+    fn write_operation_without_arguments() {
+        let step = Step {
+            instruction: Instr::Operation(ImgOp::Rotate90),
+            modifiers: Vec::new(),
+            dimensions: Some((8, 10)),
+        };
+
+        assert_eq!(written(&step), "2. rotate90\n   size: 8x10\n");
+    }
+
+    #[test]
+    fn write_modifier_for_occurrence() {
+        let step = Step {
+            instruction: Instr::EnvAddFor(
+                Occurrence::new("crop", 3).unwrap(),
+                EnvItem::Gravity(Gravity::BottomRight),
+            ),
+            modifiers: Vec::new(),
+            dimensions: Some((10, 8)),
+        };
+
+        assert_eq!(written(&step), "2. set crop.3 gravity bottom-right\n");
+    }
+}
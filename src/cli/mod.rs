@@ -1,6 +1,14 @@
 pub mod app;
+pub mod assertion;
+pub mod bench;
+pub mod check;
 pub mod common_dir;
 pub mod config;
+pub mod debug_bundle;
+pub mod dry_run;
 pub mod glob_base_dir;
 pub mod license;
+pub mod ops;
 pub mod pipeline;
+pub mod pipeline_file;
+pub mod script;
@@ -0,0 +1,148 @@
+use anyhow::anyhow;
+use clap::ArgMatches;
+use sic_cli_ops::metadata::{OperationKind, OperationMetadata};
+use sic_cli_ops::operations::OperationId;
+use strum::IntoEnumIterator;
+
+use crate::cli::app::arg_names::{ARG_OPS_JSON, ARG_OPS_OPERATION};
+use crate::cli::app::{SUBCOMMAND_OPS_DESCRIBE, SUBCOMMAND_OPS_LIST};
+
+/// Runs the `ops` subcommand, which lists the available operations, or describes one of them.
+pub fn run_ops(matches: &ArgMatches) -> anyhow::Result<()> {
+    let output = match matches.subcommand() {
+        (SUBCOMMAND_OPS_LIST, Some(list)) => {
+            let operations = OperationId::iter()
+                .map(OperationId::metadata)
+                .collect::<Vec<_>>();
+
+            if list.is_present(ARG_OPS_JSON) {
+                serde_json::to_string_pretty(&operations)?
+            } else {
+                list_table(&operations)
+            }
+        }
+        (SUBCOMMAND_OPS_DESCRIBE, Some(describe)) => {
+            let name = describe.value_of(ARG_OPS_OPERATION).unwrap_or_default();
+            let operation = find_operation(name)?;
+
+            if describe.is_present(ARG_OPS_JSON) {
+                serde_json::to_string_pretty(&operation)?
+            } else {
+                description(&operation)
+            }
+        }
+        _ => unreachable!("clap requires a subcommand for 'ops'"),
+    };
+
+    println!("{}", output);
+
+    Ok(())
+}
+
+fn find_operation(name: &str) -> anyhow::Result<OperationMetadata> {
+    // operations may also be named as their cli argument, e.g. `--blur`
+    let name = name.trim_start_matches("--");
+
+    OperationId::try_from_name(name)
+        .map(OperationId::metadata)
+        .map_err(|_| {
+            anyhow!(
+                "Unknown operation '{}'; use 'sic ops list' to see the available operations.",
+                name
+            )
+        })
+}
+
+/// Formats the operations as a table with their syntax and description, one per line.
+fn list_table(operations: &[OperationMetadata]) -> String {
+    let rows = operations
+        .iter()
+        .map(|operation| (operation.syntax(), operation.description))
+        .collect::<Vec<_>>();
+
+    let width = rows
+        .iter()
+        .map(|(syntax, _)| syntax.len())
+        .max()
+        .unwrap_or(0);
+
+    rows.iter()
+        .map(|(syntax, description)| format!("{:<width$}  {}", syntax, description, width = width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formats a full description of a single operation.
+fn description(operation: &OperationMetadata) -> String {
+    let kind = match operation.kind {
+        OperationKind::Operation => "operation",
+        OperationKind::Modifier => "modifier",
+    };
+
+    let mut text = format!(
+        "{} ({})\n\n{}\n\nsyntax: {}\n",
+        operation.name,
+        kind,
+        operation.description,
+        operation.syntax()
+    );
+
+    if !operation.arguments.is_empty() {
+        text.push_str("\narguments:\n");
+
+        for argument in operation.arguments {
+            text.push_str(&format!("  {:<12} {}", argument.name, argument.ty));
+
+            if let Some(range) = argument.range {
+                text.push_str(&format!(" ({})", range));
+            }
+
+            if argument.optional {
+                text.push_str(", optional");
+            }
+
+            text.push('\n');
+        }
+    }
+
+    if operation.variadic {
+        text.push_str("\nfurther arguments may follow the listed arguments\n");
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_operation_by_name() {
+        assert_eq!(find_operation("blur").unwrap().name, "blur");
+        assert_eq!(find_operation("--blur").unwrap().name, "blur");
+        assert!(find_operation("blurry").is_err());
+    }
+
+    #[test]
+    fn list_table_aligns_descriptions() {
+        let operations = [
+            OperationId::Invert.metadata(),
+            OperationId::Opacity.metadata(),
+        ];
+
+        assert_eq!(
+            list_table(&operations),
+            "invert                Inverts the colors of the image\n\
+             opacity <opacity:fp>  Multiplies the alpha channel by the opacity"
+        );
+    }
+
+    #[test]
+    fn describe_arguments() {
+        let text = description(&OperationId::Dither.metadata());
+
+        assert!(text.starts_with("dither (operation)"));
+        assert!(text.contains("syntax: dither <colors:uint>"));
+        assert!(text.contains("colors       uint (2..=256)"));
+    }
+}
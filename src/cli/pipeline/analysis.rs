@@ -0,0 +1,221 @@
+use std::io::Write;
+
+use anyhow::Context;
+use serde_json::{json, Value};
+use sic_image_engine::engine::{AnalysisResult, ContourKind};
+
+use crate::cli::config::AnalysisFormat;
+
+/// Writes the results of the image operations which analysed the image, e.g. the area found by
+/// `find`. Nothing is written if no such operations were applied. The text format summarizes the
+/// contours traced by `contours`; their points are only written in the JSON format.
+pub fn report_analysis_results(
+    results: &[AnalysisResult],
+    format: AnalysisFormat,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    if results.is_empty() {
+        return Ok(());
+    }
+
+    match format {
+        AnalysisFormat::Text => {
+            for result in results {
+                writeln!(writer, "{}", to_text(result))?;
+            }
+        }
+        AnalysisFormat::Json => {
+            let results = results.iter().map(to_json).collect::<Vec<_>>();
+            serde_json::to_writer_pretty(&mut *writer, &results)?;
+            writeln!(writer)?;
+        }
+    }
+
+    writer
+        .flush()
+        .with_context(|| "Unable to report the analysis results.")
+}
+
+fn to_text(result: &AnalysisResult) -> String {
+    match result {
+        AnalysisResult::TemplateMatch {
+            template,
+            x,
+            y,
+            width,
+            height,
+            score,
+        } => format!(
+            "find '{}': x={} y={} width={} height={} score={:.4}",
+            template.display(),
+            x,
+            y,
+            width,
+            height,
+            score
+        ),
+        AnalysisResult::Contours {
+            threshold,
+            contours,
+        } => {
+            let holes = contours
+                .iter()
+                .filter(|contour| contour.kind == ContourKind::Hole)
+                .count();
+            let points = contours
+                .iter()
+                .map(|contour| contour.points.len())
+                .sum::<usize>();
+
+            format!(
+                "contours threshold={}: contours={} outer={} holes={} points={}",
+                threshold,
+                contours.len(),
+                contours.len() - holes,
+                holes,
+                points
+            )
+        }
+    }
+}
+
+fn to_json(result: &AnalysisResult) -> Value {
+    match result {
+        AnalysisResult::TemplateMatch {
+            template,
+            x,
+            y,
+            width,
+            height,
+            score,
+        } => json!({
+            "operation": "find",
+            "template": template,
+            "x": x,
+            "y": y,
+            "width": width,
+            "height": height,
+            "score": rounded(*score),
+        }),
+        AnalysisResult::Contours {
+            threshold,
+            contours,
+        } => json!({
+            "operation": "contours",
+            "threshold": threshold,
+            "contours": contours
+                .iter()
+                .map(|contour| json!({
+                    "kind": match contour.kind {
+                        ContourKind::Outer => "outer",
+                        ContourKind::Hole => "hole",
+                    },
+                    "points": contour.points,
+                }))
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+/// Rounds a score to the 4 decimals of the text format, as scores are stored in single precision,
+/// and would otherwise be written with many meaningless digits, e.g. `0.8380504250526428`.
+fn rounded(score: f32) -> f64 {
+    (f64::from(score) * 1e4).round() / 1e4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_image_engine::engine::Contour;
+
+    fn template_match() -> AnalysisResult {
+        AnalysisResult::TemplateMatch {
+            template: "button.png".into(),
+            x: 10,
+            y: 20,
+            width: 30,
+            height: 40,
+            score: 0.123_456,
+        }
+    }
+
+    fn contours() -> AnalysisResult {
+        AnalysisResult::Contours {
+            threshold: 128,
+            contours: vec![
+                Contour {
+                    kind: ContourKind::Outer,
+                    points: vec![(0, 0), (0, 2), (2, 2), (2, 0)],
+                },
+                Contour {
+                    kind: ContourKind::Hole,
+                    points: vec![(1, 0), (2, 1), (1, 2), (0, 1)],
+                },
+            ],
+        }
+    }
+
+    fn report(results: &[AnalysisResult], format: AnalysisFormat) -> String {
+        let mut out = Vec::new();
+        report_analysis_results(results, format, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(
+            report(&[template_match(), template_match()], AnalysisFormat::Text),
+            "find 'button.png': x=10 y=20 width=30 height=40 score=0.1235\n".repeat(2)
+        );
+    }
+
+    #[test]
+    fn json() {
+        let out = report(&[template_match()], AnalysisFormat::Json);
+        let value: Value = serde_json::from_str(&out).unwrap();
+
+        assert_eq!(
+            value,
+            json!([{
+                "operation": "find",
+                "template": "button.png",
+                "x": 10,
+                "y": 20,
+                "width": 30,
+                "height": 40,
+                "score": 0.1235,
+            }])
+        );
+    }
+
+    #[test]
+    fn contours_text() {
+        assert_eq!(
+            report(&[contours()], AnalysisFormat::Text),
+            "contours threshold=128: contours=2 outer=1 holes=1 points=8\n"
+        );
+    }
+
+    #[test]
+    fn contours_json() {
+        let out = report(&[template_match(), contours()], AnalysisFormat::Json);
+        let value: Value = serde_json::from_str(&out).unwrap();
+
+        assert_eq!(
+            value[1],
+            json!({
+                "operation": "contours",
+                "threshold": 128,
+                "contours": [
+                    { "kind": "outer", "points": [[0, 0], [0, 2], [2, 2], [2, 0]] },
+                    { "kind": "hole", "points": [[1, 0], [2, 1], [1, 2], [0, 1]] },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn nothing_to_report() {
+        assert!(report(&[], AnalysisFormat::Json).is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use sic_core::image;
+use sic_core::image::imageops::FilterType;
+use sic_core::image::GenericImageView;
+use sic_io::conversion::AutomaticColorTypeAdjustment;
+use sic_io::deep_zoom::DeepZoomLayout;
+use sic_io::format::EncodingFormat;
+use sic_io::save;
+
+use crate::cli::config::DeepZoomSettings;
+
+/// Cuts the image into the tiles of a Deep Zoom (DZI) image pyramid, and writes the descriptor
+/// to `descriptor_path`. The tiles are written to the `{name}_files` directory next to the
+/// descriptor, with a directory for each level, e.g. `{name}_files/12/0_3.png`.
+///
+/// The descriptor is written as JSON if `descriptor_path` has the `json` extension, and as XML
+/// otherwise.
+pub fn export_deep_zoom(
+    image: &image::DynamicImage,
+    settings: &DeepZoomSettings,
+    descriptor_path: &Path,
+    tile_format: EncodingFormat,
+    tile_extension: &str,
+) -> anyhow::Result<()> {
+    let (width, height) = image.dimensions();
+    let layout = DeepZoomLayout::new(width, height, settings.tile_size, settings.overlap)?;
+    let tiles_dir = tiles_directory(descriptor_path)?;
+
+    let mut downscaled: Option<image::DynamicImage> = None;
+
+    for level in (0..=layout.max_level()).rev() {
+        let (level_width, level_height) = layout.level_dimensions(level);
+
+        // each level is downscaled from the level above it
+        let level_image = match downscaled.take() {
+            Some(previous) => {
+                previous.resize_exact(level_width, level_height, FilterType::Triangle)
+            }
+            None if (level_width, level_height) == (width, height) => image.clone(),
+            None => image.resize_exact(level_width, level_height, FilterType::Triangle),
+        };
+
+        let level_dir = tiles_dir.join(level.to_string());
+        std::fs::create_dir_all(&level_dir).with_context(|| {
+            format!("Unable to create tile directory '{}'.", level_dir.display())
+        })?;
+
+        for tile in layout.tiles(level) {
+            let path = level_dir.join(format!("{}_{}.{}", tile.column, tile.row, tile_extension));
+
+            let mut writer = File::create(&path)
+                .with_context(|| format!("Unable to create tile '{}'.", path.display()))?;
+
+            save::export(
+                &level_image.crop_imm(tile.x, tile.y, tile.width, tile.height),
+                &mut writer,
+                tile_format.clone(),
+                save::ExportSettings {
+                    adjust_color_type: AutomaticColorTypeAdjustment::default(),
+                },
+            )
+            .with_context(|| format!("Unable to save tile '{}'.", path.display()))?;
+        }
+
+        downscaled = Some(level_image);
+    }
+
+    let descriptor = layout.descriptor(tile_extension);
+    let mut writer = File::create(descriptor_path).with_context(|| {
+        format!(
+            "Unable to create Deep Zoom descriptor '{}'.",
+            descriptor_path.display()
+        )
+    })?;
+
+    match descriptor_path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("json") => descriptor.write_json(&mut writer),
+        _ => descriptor.write_xml(&mut writer),
+    }
+    .with_context(|| "Unable to save Deep Zoom descriptor.")
+}
+
+/// The directory in which the tiles are stored: for a descriptor `out/photo.dzi` this is
+/// `out/photo_files`, as expected by Deep Zoom viewers.
+fn tiles_directory(descriptor_path: &Path) -> anyhow::Result<PathBuf> {
+    let name = descriptor_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| anyhow!("Unable to determine the name of the Deep Zoom descriptor."))?;
+
+    Ok(descriptor_path.with_file_name(format!("{}_files", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_directory_next_to_descriptor() {
+        assert_eq!(
+            tiles_directory(Path::new("out/photo.dzi")).unwrap(),
+            PathBuf::from("out/photo_files")
+        );
+        assert_eq!(
+            tiles_directory(Path::new("photo.json")).unwrap(),
+            PathBuf::from("photo_files")
+        );
+    }
+}
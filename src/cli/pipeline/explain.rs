@@ -0,0 +1,92 @@
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::Context;
+use sic_core::image;
+use sic_image_engine::engine::{ImageEngine, Instr};
+use sic_io::conversion::AutomaticColorTypeAdjustment;
+use sic_io::save;
+
+const THUMBNAIL_SIZE: u32 = 256;
+
+/// Applies the image operations one by one, and writes a thumbnail of the intermediate image to
+/// the `explain_dir` after each operation, e.g. `01-blur.png`, `02-crop.png`, etc.
+/// The thumbnail of the unmodified input image is written as `00-input.png`.
+pub fn ignite_explained<'e>(
+    image_engine: &'e mut ImageEngine,
+    program: &[Instr],
+    explain_dir: &Path,
+) -> anyhow::Result<&'e image::DynamicImage> {
+    std::fs::create_dir_all(explain_dir)
+        .with_context(|| "Unable to create the directory for --explain-dir.")?;
+
+    write_thumbnail(image_engine.ignite(&[])?, &explain_dir.join("00-input.png"))?;
+
+    let mut step = 0;
+
+    for instruction in program {
+        let image = image_engine
+            .ignite(std::slice::from_ref(instruction))
+            .with_context(|| "Unable to apply image operations.")?;
+
+        let operation = match instruction {
+            Instr::Operation(op) => Some(op.as_ref()),
+            Instr::Deferred(op) => Some(op.as_ref()),
+            _ => None,
+        };
+
+        if let Some(operation) = operation {
+            step += 1;
+            let path = explain_dir.join(thumbnail_file_name(step, operation));
+            write_thumbnail(image, &path)?;
+        }
+    }
+
+    Ok(image_engine.ignite(&[])?)
+}
+
+fn thumbnail_file_name(step: usize, operation: &str) -> String {
+    format!("{:02}-{}.png", step, operation)
+}
+
+fn write_thumbnail(image: &image::DynamicImage, path: &Path) -> anyhow::Result<()> {
+    let thumbnail = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+    let mut writer = File::create(path)
+        .with_context(|| format!("Unable to create thumbnail '{}'.", path.display()))?;
+
+    save::export(
+        &thumbnail,
+        &mut writer,
+        image::ImageOutputFormat::Png.into(),
+        save::ExportSettings {
+            adjust_color_type: AutomaticColorTypeAdjustment::default(),
+        },
+    )
+    .with_context(|| format!("Unable to save thumbnail '{}'.", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_image_engine::ImgOp;
+
+    #[test]
+    fn file_names() {
+        assert_eq!(
+            thumbnail_file_name(1, ImgOp::Blur(1.0).as_ref()),
+            "01-blur.png"
+        );
+        assert_eq!(
+            thumbnail_file_name(2, ImgOp::FlipHorizontal.as_ref()),
+            "02-flip-horizontal.png"
+        );
+        assert_eq!(
+            thumbnail_file_name(12, ImgOp::GrayScale.as_ref()),
+            "12-grayscale.png"
+        );
+        assert_eq!(
+            thumbnail_file_name(3, ImgOp::Filter3x3([0.0; 9]).as_ref()),
+            "03-filter3x3.png"
+        );
+    }
+}
@@ -3,17 +3,17 @@ use std::path::Path;
 use sic_core::image::error::{ImageFormatHint, UnsupportedError};
 use sic_core::image::{ImageError, ImageFormat, ImageOutputFormat};
 use sic_io::errors::SicIoError;
+use sic_io::format::EncodingFormat;
 
-pub(crate) fn guess_output_by_identifier(id: &str) -> Result<ImageOutputFormat, SicIoError> {
+pub(crate) fn guess_output_by_identifier(id: &str) -> Result<EncodingFormat, SicIoError> {
     // HACK: image crate doesn't use identifiers, so we'll use an extension as identifier
     guess_output_by_path(Path::new(&format!("0.{}", id)))
 }
 
-pub(crate) fn guess_output_by_path<P: AsRef<Path>>(
-    path: P,
-) -> Result<ImageOutputFormat, SicIoError> {
+pub(crate) fn guess_output_by_path<P: AsRef<Path>>(path: P) -> Result<EncodingFormat, SicIoError> {
     ImageFormat::from_path(path)
         .and_then(into_image_output_format)
+        .map(EncodingFormat::from)
         .map_err(SicIoError::ImageError)
 }
 
@@ -55,7 +55,7 @@ mod tests {
         let by_path = guess_output_by_path(format!("my_file_name.{}", input)).unwrap();
 
         assert_eq!(by_id, by_path);
-        assert_eq!(by_id, expected);
+        assert_eq!(by_id, EncodingFormat::Image(expected));
     }
     #[parameterized(input = {
         "dds",
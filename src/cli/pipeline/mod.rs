@@ -1,21 +1,33 @@
 use std::fs::File;
 use std::io::{self, Read, Write};
 
-use crate::cli::config::{Config, InputOutputMode, InputOutputModeType, PathVariant};
+use crate::cli::common_dir::CommonDir;
+use crate::cli::config::{
+    Config, InputOutputMode, InputOutputModeType, PathVariant, SpriteSheetSettings,
+};
 use crate::cli::license::LicenseTexts;
 use crate::cli::license::PrintTextFor;
 use crate::cli::pipeline::fallback::{guess_output_by_identifier, guess_output_by_path};
+use crate::cli::pipeline::output::OutputWriter;
 use crate::combinators::FallbackIf;
 use anyhow::{anyhow, bail, Context};
 use sic_core::image;
-use sic_image_engine::engine::ImageEngine;
+use sic_image_engine::engine::{AnalysisResult, ImageEngine};
 use sic_io::conversion::AutomaticColorTypeAdjustment;
 use sic_io::format::{
-    DetermineEncodingFormat, EncodingFormatByExtension, EncodingFormatByIdentifier, JPEGQuality,
+    canonical_extension, DetermineEncodingFormat, EncodingFormat, EncodingFormatByExtension,
+    EncodingFormatByIdentifier, GifSettings, JPEGQuality, WebPQuality,
 };
+use sic_io::pdf::PdfDocument;
+use sic_io::sprite_sheet::SpriteSheet;
 use sic_io::{load, save};
+use std::path::Path;
 
+pub mod analysis;
+pub mod deep_zoom;
+pub mod explain;
 pub mod fallback;
+pub mod output;
 
 pub fn run_with_devices<'c>(
     in_and_output: InputOutputMode,
@@ -27,13 +39,33 @@ pub fn run_with_devices<'c>(
                 warn_default_std_output_format();
             }
 
-            run(
+            let output = check_output_extension(output, config);
+
+            let deep_zoom_descriptor = match (&config.deep_zoom, &output) {
+                (Some(_), PathVariant::StdStream) => bail!(
+                    "Deep Zoom tiles can't be written to the stdout. Please provide the path of \
+                     the Deep Zoom descriptor using --output."
+                ),
+                (Some(_), PathVariant::Path(path)) => Some(path.as_path()),
+                (None, _) => None,
+            };
+
+            let analysis_results = run(
                 || create_reader(&input),
-                |ext: Option<&str>| create_writer(&output, ext),
+                |ext: Option<&str>| create_writer(&output, ext, config),
                 || create_format_decider(&output, config),
+                || create_manifest_writer(&output, config),
+                input_format_hint(&input),
+                deep_zoom_descriptor,
                 config,
-            )
+            )?;
+
+            report_analysis_results(&analysis_results, &output, config)
         }
+        InputOutputMode::Batch {
+            inputs,
+            output_root_folder,
+        } if is_pdf_output(config) => run_batch_to_pdf(&inputs, &output_root_folder, config),
         InputOutputMode::Batch {
             inputs,
             output_root_folder,
@@ -44,12 +76,17 @@ pub fn run_with_devices<'c>(
                 let output = output_root_folder.join(branch);
                 let output = &PathVariant::Path(output);
 
-                run(
+                let analysis_results = run(
                     || create_reader(&input),
-                    |ext: Option<&str>| create_writer(&output, ext),
+                    |ext: Option<&str>| create_writer(&output, ext, config),
                     || create_format_decider(&output, config),
+                    || create_manifest_writer(output, config),
+                    input_format_hint(input),
+                    None,
                     config,
-                )?
+                )?;
+
+                report_analysis_results(&analysis_results, output, config)?;
             }
 
             Ok(())
@@ -57,6 +94,125 @@ pub fn run_with_devices<'c>(
     }
 }
 
+/// Whether the output format is forced to be PDF.
+fn is_pdf_output(config: &Config) -> bool {
+    config.forced_output_format.and_then(canonical_extension) == Some("pdf")
+}
+
+/// In batch mode, PDF output is a single document, with a page for each input image, in the order
+/// of the input paths. The glob output is the path of the document, instead of a folder.
+fn run_batch_to_pdf(
+    inputs: &CommonDir,
+    document_path: &Path,
+    config: &Config,
+) -> anyhow::Result<()> {
+    if config.sprite_sheet.is_some() {
+        bail!("Sprite sheets can't be combined into a single PDF document in glob mode.");
+    }
+
+    let output = PathVariant::Path(document_path.to_path_buf());
+
+    let mut input_paths = inputs
+        .path_combinations()
+        .into_iter()
+        .map(|(input, _)| input)
+        .collect::<Vec<_>>();
+    input_paths.sort();
+
+    let mut document = PdfDocument::new();
+
+    for path in input_paths {
+        let input = PathVariant::Path(path.to_path_buf());
+        let mut reader = create_reader(&input)?;
+
+        let image_engine = apply_operations(&mut reader, input_format_hint(&input), false, config)
+            .with_context(|| {
+                format!(
+                    "Unable to add the image '{}' to the PDF document.",
+                    path.display()
+                )
+            })?;
+
+        report_analysis_results(image_engine.analysis_results(), &output, config)?;
+        document.add_page(image_engine.image())?;
+    }
+
+    let mut export_writer = match create_writer(&output, None, config)? {
+        Some(writer) => writer,
+        None => return Ok(()),
+    };
+
+    document
+        .write(&mut export_writer)
+        .with_context(|| "Unable to save PDF document.")?;
+
+    export_writer
+        .finish()
+        .with_context(|| "Unable to save PDF document.")
+}
+
+/// Reports what the image operations which analyse the image found, on the stdout, or on the stderr
+/// if the output image is written to the stdout.
+fn report_analysis_results(
+    results: &[AnalysisResult],
+    output: &PathVariant,
+    config: &Config,
+) -> anyhow::Result<()> {
+    if output.is_std_stream() {
+        analysis::report_analysis_results(results, config.analysis_format, &mut io::stderr())
+    } else {
+        analysis::report_analysis_results(results, config.analysis_format, &mut io::stdout())
+    }
+}
+
+/// Warns if the forced output format does not match the extension of the output path.
+/// If `--fix-extension` is set, the extension of the output path is corrected instead.
+fn check_output_extension(output: PathVariant, config: &Config) -> PathVariant {
+    // the output path is used for the Deep Zoom descriptor, only the tiles use the output format
+    if config.deep_zoom.is_some() {
+        return output;
+    }
+
+    let (path, forced, expected) = match (&output, config.forced_output_format) {
+        (PathVariant::Path(path), Some(forced)) => match canonical_extension(forced) {
+            Some(expected) => (path, forced, expected),
+            // unknown formats are reported when the format is determined
+            None => return output,
+        },
+        _ => return output,
+    };
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if canonical_extension(ext) == Some(expected) => output,
+        _ if config.fix_extension => {
+            let fixed = path.with_extension(expected);
+            eprintln!(
+                "info: The output path was changed to '{}' to match the output format.",
+                fixed.display()
+            );
+            PathVariant::Path(fixed)
+        }
+        Some(ext) => {
+            eprintln!(
+                "warn: The output format '{}' does not match the extension '{}' of the output path. \
+                 The image will be written in the '{}' format regardless. \
+                 Provide --fix-extension to adjust the extension of the output path.",
+                forced, ext, forced
+            );
+            output
+        }
+        None => output,
+    }
+}
+
+/// The format of the input image as implied by the extension of its path, if any.
+fn input_format_hint(input: &PathVariant) -> Option<image::ImageFormat> {
+    match input {
+        PathVariant::Path(path) => image::ImageFormat::from_path(path).ok(),
+        PathVariant::StdStream => None,
+    }
+}
+
 fn warn_default_std_output_format() {
     eprintln!(
         "warn: The default output format when using stdout output (the current output mode) is \
@@ -64,29 +220,83 @@ fn warn_default_std_output_format() {
     );
 }
 
-fn run<R, W, F>(
+/// Loads, operates on and saves a single image. Returns the results of the image operations which
+/// analysed the image, which are reported by the caller.
+fn run<R, W, F, M>(
     supply_reader: R,
     supply_writer: W,
     format_decider: F,
+    supply_manifest_writer: M,
+    input_format_hint: Option<image::ImageFormat>,
+    deep_zoom_descriptor: Option<&Path>,
     config: &Config,
-) -> anyhow::Result<()>
+) -> anyhow::Result<Vec<AnalysisResult>>
 where
     R: Fn() -> anyhow::Result<Box<dyn Read>>,
-    W: Fn(Option<&str>) -> anyhow::Result<Box<dyn Write>>,
-    F: Fn() -> anyhow::Result<image::ImageOutputFormat>,
+    W: Fn(Option<&str>) -> anyhow::Result<Option<OutputWriter>>,
+    F: Fn() -> anyhow::Result<EncodingFormat>,
+    M: Fn() -> anyhow::Result<Option<Box<dyn Write>>>,
 {
-    let mut reader = supply_reader()?;
-    let img = load::load_image(
-        &mut reader,
-        &load::ImportConfig {
-            selected_frame: config.selected_frame,
-        },
-    )?;
+    // a blank canvas is created instead of reading an input image
+    let mut reader = match config.canvas {
+        Some(_) => Box::new(io::empty()),
+        None => supply_reader()?,
+    };
+
+    let sprite_sheet;
+    let image_engine;
+    let mut analysis_results = Vec::new();
+
+    let (buffer, hdr, hdr_dropped_by, animation) = if let Some(settings) = &config.sprite_sheet {
+        let sheet = create_sprite_sheet(
+            &mut reader,
+            settings,
+            input_format_hint,
+            config,
+            &mut analysis_results,
+        )?;
+
+        if let Some(mut manifest_writer) = supply_manifest_writer()? {
+            sheet
+                .manifest
+                .write_json(&mut manifest_writer)
+                .with_context(|| "Unable to save sprite sheet manifest.")?;
+        }
+
+        sprite_sheet = image::DynamicImage::ImageRgba8(sheet.image);
+        (&sprite_sheet, None, None, None)
+    } else {
+        image_engine = apply_operations(
+            &mut reader,
+            input_format_hint,
+            keep_animation(&format_decider, config),
+            config,
+        )?;
+
+        analysis_results.extend_from_slice(image_engine.analysis_results());
+        (
+            image_engine.image(),
+            image_engine.hdr_image(),
+            image_engine.hdr_dropped_by(),
+            image_engine.animation(),
+        )
+    };
+
+    if let (Some(settings), Some(descriptor)) = (&config.deep_zoom, deep_zoom_descriptor) {
+        // Tiles are stored as PNG, unless a format is given explicitly; the extension of the
+        // output path is the one of the descriptor.
+        let (tile_format, tile_extension) = match config.forced_output_format {
+            Some(format) => (
+                format_decider()?,
+                canonical_extension(format).unwrap_or(format),
+            ),
+            None => (image::ImageOutputFormat::Png.into(), "png"),
+        };
 
-    let mut image_engine = ImageEngine::new(img);
-    let buffer = image_engine
-        .ignite(&config.image_operations_program)
-        .with_context(|| "Unable to apply image operations.")?;
+        deep_zoom::export_deep_zoom(buffer, settings, descriptor, tile_format, tile_extension)?;
+
+        return Ok(analysis_results);
+    }
 
     // FIXME: decide whether in simple mode, extension should also change by default,
     //        unless an option is set e.g. --keep-extension-unmodified
@@ -95,18 +305,167 @@ where
     } else {
         None
     };
-    let mut export_writer = supply_writer(format)?;
+    let mut export_writer = match supply_writer(format)? {
+        Some(writer) => writer,
+        None => return Ok(analysis_results),
+    };
     let encoding_format = format_decider()?;
 
-    save::export(
-        buffer,
-        &mut export_writer,
-        encoding_format,
-        save::ExportSettings {
-            adjust_color_type: AutomaticColorTypeAdjustment::default(),
+    if let (Some(operation), EncodingFormat::Exr) = (hdr_dropped_by, &encoding_format) {
+        eprintln!(
+            "warn: The high dynamic range samples of the input image were clamped, since the \
+             '{}' operation can only be applied to the clamped image. Only flips, rotations and \
+             crops keep them.",
+            operation
+        );
+    }
+
+    let export_settings = save::ExportSettings {
+        adjust_color_type: AutomaticColorTypeAdjustment::default(),
+    };
+
+    match animation {
+        Some(animation) => save::export_with_animation(
+            buffer,
+            animation,
+            &mut export_writer,
+            encoding_format,
+            export_settings,
+        ),
+        None => save::export_with_hdr(
+            buffer,
+            hdr,
+            &mut export_writer,
+            encoding_format,
+            export_settings,
+        ),
+    }
+    .with_context(|| "Unable to save image.")?;
+
+    export_writer
+        .finish()
+        .with_context(|| "Unable to save image.")?;
+
+    Ok(analysis_results)
+}
+
+/// Loads a single image, or creates a blank canvas if `--create` was given, and applies the image
+/// operations to it. The engine holds the resulting image, and the results of the image operations
+/// which analysed the image.
+fn apply_operations(
+    reader: &mut Box<dyn Read>,
+    input_format_hint: Option<image::ImageFormat>,
+    keep_animation: bool,
+    config: &Config,
+) -> anyhow::Result<ImageEngine> {
+    let decoded = match config.canvas {
+        Some((width, height)) => load::DecodedImage {
+            image: image::DynamicImage::ImageRgba8(image::RgbaImage::new(width, height)),
+            frames: 1,
+            hdr: None,
+            animation: None,
+        },
+        None => load::decode_image(
+            reader,
+            &load::ImportConfig {
+                selected_frame: config.selected_frame.unwrap_or_default(),
+                format_hint: input_format_hint,
+                keep_animation,
+                forced_format: config.forced_input_format,
+            },
+        )?,
+    };
+
+    if let Some(assertion) = &config.image_assertion {
+        assertion.check(&decoded.image)?;
+    }
+
+    let mut image_engine = ImageEngine::new(decoded.image)
+        .with_frame_count(decoded.frames)
+        .with_hdr(decoded.hdr)
+        .with_animation(decoded.animation);
+
+    match &config.explain_dir {
+        Some(dir) => {
+            explain::ignite_explained(&mut image_engine, &config.image_operations_program, dir)?;
+        }
+        None => {
+            image_engine
+                .ignite(&config.image_operations_program)
+                .with_context(|| image_operations_error(config))?;
+        }
+    }
+
+    Ok(image_engine)
+}
+
+/// All frames of an animated input image are kept if the output is an animated GIF, unless a
+/// single frame was selected. If the output format can't be determined, the error is reported
+/// once the image is saved.
+fn keep_animation<F>(format_decider: F, config: &Config) -> bool
+where
+    F: Fn() -> anyhow::Result<EncodingFormat>,
+{
+    config.selected_frame.is_none()
+        && config.deep_zoom.is_none()
+        && matches!(format_decider(), Ok(EncodingFormat::Gif(_)))
+}
+
+/// Load all frames of the input image, apply the image operations to each frame separately, and
+/// lay out the resulting frames on a sprite sheet. The analysis results of all frames are collected
+/// in `analysis_results`, in the order of the frames.
+fn create_sprite_sheet(
+    reader: &mut Box<dyn Read>,
+    settings: &SpriteSheetSettings,
+    input_format_hint: Option<image::ImageFormat>,
+    config: &Config,
+    analysis_results: &mut Vec<AnalysisResult>,
+) -> anyhow::Result<SpriteSheet> {
+    let frames = load::load_frames(
+        reader,
+        &load::ImportConfig {
+            format_hint: input_format_hint,
+            forced_format: config.forced_input_format,
+            ..load::ImportConfig::default()
         },
-    )
-    .with_context(|| "Unable to save image.")
+    )?;
+
+    if let (Some(assertion), Some(first)) = (&config.image_assertion, frames.first()) {
+        assertion.check(&image::DynamicImage::ImageRgba8(first.buffer().clone()))?;
+    }
+
+    let frame_count = frames.len();
+    let frames = frames
+        .into_iter()
+        .map(|frame| {
+            let delay = frame.delay();
+            let mut image_engine =
+                ImageEngine::new(image::DynamicImage::ImageRgba8(frame.into_buffer()))
+                    .with_frame_count(frame_count);
+            let buffer = image_engine
+                .ignite(&config.image_operations_program)
+                .with_context(|| image_operations_error(config))?
+                .to_rgba();
+
+            analysis_results.extend_from_slice(image_engine.analysis_results());
+
+            Ok(image::Frame::from_parts(buffer, 0, 0, delay))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(SpriteSheet::from_frames(&frames, settings.columns)?)
+}
+
+/// Names the script file from which the image operations were read, if any, as the line numbers
+/// of the statements aren't known when they're applied.
+fn image_operations_error(config: &Config) -> String {
+    match &config.image_operations_script {
+        Some(path) => format!(
+            "Unable to apply the image operations of script file '{}'.",
+            path.display()
+        ),
+        None => "Unable to apply image operations.".to_string(),
+    }
 }
 
 /// Create a reader which will be used to load the image.
@@ -123,10 +482,13 @@ fn create_reader(io_device: &PathVariant) -> anyhow::Result<Box<dyn Read>> {
     }
 }
 
+/// Create a writer which will be used to save the image.
+/// If the output is locked by another process and should be skipped, no writer is created.
 fn create_writer(
     io_device: &PathVariant,
     adjust_ext: Option<&str>,
-) -> anyhow::Result<Box<dyn Write>> {
+    config: &Config,
+) -> anyhow::Result<Option<OutputWriter>> {
     match io_device {
         PathVariant::Path(out) => {
             let base = out.as_path().parent().ok_or_else(|| {
@@ -139,16 +501,39 @@ fn create_writer(
                 _ => out.to_path_buf(),
             };
 
-            Ok(Box::new(File::create(out)?))
+            Ok(OutputWriter::create(
+                &out,
+                config.output_lock,
+                config.atomic_output,
+            )?)
         }
-        PathVariant::StdStream => Ok(Box::new(io::stdout())),
+        PathVariant::StdStream => Ok(Some(OutputWriter::stdout())),
+    }
+}
+
+/// Create a writer for the sprite sheet manifest, if a sprite sheet will be created.
+/// Unless an explicit manifest path is given, the manifest is placed next to the output image.
+/// No manifest is written if the output image is written to the stdout.
+fn create_manifest_writer(
+    io_device: &PathVariant,
+    config: &Config,
+) -> anyhow::Result<Option<Box<dyn Write>>> {
+    let manifest_path = match (&config.sprite_sheet, io_device) {
+        (Some(settings), _) if settings.manifest_path.is_some() => settings.manifest_path.clone(),
+        (Some(_), PathVariant::Path(out)) => Some(out.with_extension("json")),
+        _ => None,
+    };
+
+    match manifest_path {
+        Some(path) => Ok(Some(Box::new(File::create(path)?))),
+        None => Ok(None),
     }
 }
 
 fn create_format_decider(
     io_device: &PathVariant,
     config: &Config,
-) -> anyhow::Result<image::ImageOutputFormat> {
+) -> anyhow::Result<EncodingFormat> {
     let format_resolver = DetermineEncodingFormat {
         pnm_sample_encoding: if config.encoding_settings.pnm_use_ascii_format {
             Some(image::pnm::SampleEncoding::Ascii)
@@ -160,6 +545,19 @@ fn create_format_decider(
                 config.encoding_settings.jpeg_quality,
             )?)
         },
+        jpeg_progressive: config.encoding_settings.jpeg_progressive,
+        webp_quality: {
+            Some(WebPQuality::try_from(
+                config.encoding_settings.webp_quality,
+                config.encoding_settings.webp_lossless,
+            )?)
+        },
+        tiff_compression: Some(config.encoding_settings.tiff_compression),
+        dds_settings: Some(config.encoding_settings.dds_settings),
+        gif_settings: Some(GifSettings::try_from(
+            config.encoding_settings.gif_palette,
+            config.encoding_settings.gif_colors,
+        )?),
     };
 
     let format = match &config.forced_output_format {
@@ -174,7 +572,7 @@ fn create_format_decider(
                 guess_output_by_path,
                 out,
             )?,
-            PathVariant::StdStream => image::ImageOutputFormat::Bmp,
+            PathVariant::StdStream => image::ImageOutputFormat::Bmp.into(),
         },
     };
 
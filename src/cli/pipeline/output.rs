@@ -0,0 +1,399 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::cli::config::OutputLock;
+use fs2::FileExt;
+use same_file::Handle;
+
+/// Writer for the output image.
+///
+/// Outputs are locked while they're written, so concurrent invocations which target the same
+/// output don't interleave their writes. The lock is taken on a hidden lock file next to the output,
+/// `.<name>.lock`, which is removed again once the output has been written (except on Windows, see
+/// [LockFile]): a new output can't be locked without creating it first, and an output which is
+/// replaced by a rename would leave waiting processes with a lock on the replaced file. When
+/// written atomically, the image is first written to a temporary file next to the output, which
+/// replaces the output only once the image has been written completely. Consumers which watch the
+/// output directory thus never observe a partially written image.
+pub struct OutputWriter {
+    destination: Destination,
+    // The locked lock file of the output, if any. Held until the output has been written.
+    _lock: Option<LockFile>,
+}
+
+enum Destination {
+    Stdout(io::Stdout),
+    InPlace(File),
+    Temporary(TemporaryFile),
+}
+
+impl OutputWriter {
+    pub fn stdout() -> Self {
+        Self {
+            destination: Destination::Stdout(io::stdout()),
+            _lock: None,
+        }
+    }
+
+    /// Creates a writer for the output file at `path`.
+    /// If the output is locked by another process and `lock` is set to skip, no writer is created.
+    pub fn create(path: &Path, lock: OutputLock, atomic: bool) -> io::Result<Option<Self>> {
+        let lock_file = match LockFile::acquire(path, lock)? {
+            Some(lock_file) => lock_file,
+            None => return Ok(None),
+        };
+
+        // The output is only created or truncated after the lock has been acquired, so we don't
+        // clobber the output of another process which is still writing to it.
+        let destination = if atomic {
+            Destination::Temporary(TemporaryFile::create(path)?)
+        } else {
+            Destination::InPlace(
+                OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(path)?,
+            )
+        };
+
+        // The lock is released when the writer is dropped.
+        Ok(Some(Self {
+            destination,
+            _lock: Some(lock_file),
+        }))
+    }
+
+    /// Completes the output. For atomic writes, the output is replaced by the temporary file.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.flush()?;
+
+        match &mut self.destination {
+            Destination::Temporary(temporary) => temporary.persist(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.destination {
+            Destination::Stdout(stdout) => stdout.write(buf),
+            Destination::InPlace(file) => file.write(buf),
+            Destination::Temporary(temporary) => temporary.file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.destination {
+            Destination::Stdout(stdout) => stdout.flush(),
+            Destination::InPlace(file) => file.flush(),
+            Destination::Temporary(temporary) => temporary.file.flush(),
+        }
+    }
+}
+
+/// The locked lock file of an output, which is removed when the lock is released.
+struct LockFile {
+    // Held until the lock is released.
+    _file: File,
+    path: PathBuf,
+}
+
+impl LockFile {
+    /// Locks the output at `path`. If the output is locked by another process and `lock` is set
+    /// to skip, no lock is taken.
+    fn acquire(output: &Path, lock: OutputLock) -> io::Result<Option<Self>> {
+        let path = sidecar_path(output, ".lock")?;
+
+        loop {
+            let file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)?;
+
+            if !acquire_lock(&file, lock, output)? {
+                return Ok(None);
+            }
+
+            // If the process which held the lock removed the lock file while we were waiting for
+            // it, our lock is on the removed file, which no longer excludes anyone; so we try
+            // again with the current lock file.
+            let current = match Handle::from_path(&path) {
+                Ok(current) => Some(current),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                Err(err) => return Err(err),
+            };
+
+            if current == Some(Handle::from_file(file.try_clone()?)?) {
+                return Ok(Some(Self { _file: file, path }));
+            }
+        }
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        // Removed while the lock is still held, so another process can't lock the file before
+        // it's removed. The lock itself is released when the file is closed afterwards.
+        // Windows doesn't allow removing a file which is still open, so the lock file is left in
+        // place there; removing it after it has been closed would remove the lock file another
+        // process may have locked in the meantime. A lock file which can't be removed otherwise
+        // is left in place as well: it's only a leftover, and is locked again by the next write.
+        if cfg!(not(windows)) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Returns whether the lock was acquired.
+fn acquire_lock(file: &File, lock: OutputLock, path: &Path) -> io::Result<bool> {
+    match lock {
+        OutputLock::Wait => file.lock_exclusive().map(|_| true),
+        OutputLock::Skip => match file.try_lock_exclusive() {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == fs2::lock_contended_error().kind() => {
+                eprintln!(
+                    "info: Skipped writing '{}', since it is locked by another process.",
+                    path.display()
+                );
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        },
+    }
+}
+
+/// The path of a hidden file next to the output, named after the output, e.g. `.<name>.lock`.
+fn sidecar_path(target: &Path, suffix: &str) -> io::Result<PathBuf> {
+    let name = target.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "output path has no file name")
+    })?;
+
+    let mut sidecar_name = std::ffi::OsString::from(".");
+    sidecar_name.push(name);
+    sidecar_name.push(suffix);
+
+    Ok(target.with_file_name(sidecar_name))
+}
+
+/// A file in the directory of the output, which replaces the output when persisted, and is
+/// removed otherwise.
+struct TemporaryFile {
+    file: File,
+    path: PathBuf,
+    target: PathBuf,
+    persisted: bool,
+}
+
+impl TemporaryFile {
+    fn create(target: &Path) -> io::Result<Self> {
+        // Unique per process, so concurrent invocations don't share a temporary file
+        let path = sidecar_path(target, &format!(".{}.tmp", std::process::id()))?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+
+        Ok(Self {
+            file,
+            path,
+            target: target.to_path_buf(),
+            persisted: false,
+        })
+    }
+
+    fn persist(&mut self) -> io::Result<()> {
+        self.file.sync_all()?;
+        fs::rename(&self.path, &self.target)?;
+        self.persisted = true;
+
+        Ok(())
+    }
+}
+
+impl Drop for TemporaryFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output_path(name: &str) -> PathBuf {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("target")
+            .join("output_writer");
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn entries_of(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect()
+    }
+
+    #[test]
+    fn atomic_output_appears_on_finish() {
+        let path = output_path("atomic_finish.bin");
+
+        let mut writer = OutputWriter::create(&path, OutputLock::Wait, true)
+            .unwrap()
+            .unwrap();
+        writer.write_all(b"image").unwrap();
+
+        assert!(!path.exists());
+
+        writer.finish().unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"image");
+    }
+
+    #[test]
+    fn atomic_output_replaces_existing() {
+        let path = output_path("atomic_replace.bin");
+        fs::write(&path, b"a larger previous image").unwrap();
+
+        let mut writer = OutputWriter::create(&path, OutputLock::Wait, true)
+            .unwrap()
+            .unwrap();
+        writer.write_all(b"image").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"a larger previous image");
+
+        writer.finish().unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"image");
+    }
+
+    #[test]
+    fn unfinished_atomic_output_is_discarded() {
+        let path = output_path("atomic_discard.bin");
+
+        let mut writer = OutputWriter::create(&path, OutputLock::Wait, true)
+            .unwrap()
+            .unwrap();
+        writer.write_all(b"partial").unwrap();
+        drop(writer);
+
+        // neither the temporary file nor, except on Windows, the lock file is kept
+        let lock_file = sidecar_path(&path, ".lock").unwrap();
+        assert!(!path.exists());
+        assert!(entries_of(path.parent().unwrap()).iter().all(|entry| {
+            !entry.to_string_lossy().contains("atomic_discard.bin")
+                || (cfg!(windows) && *entry == lock_file)
+        }));
+    }
+
+    #[test]
+    fn in_place_output() {
+        let path = output_path("in_place.bin");
+        fs::write(&path, b"a larger previous image").unwrap();
+
+        let mut writer = OutputWriter::create(&path, OutputLock::Wait, false)
+            .unwrap()
+            .unwrap();
+        writer.write_all(b"image").unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"image");
+    }
+
+    #[test]
+    fn skip_locked_output() {
+        let path = output_path("skip_locked.bin");
+        fs::write(&path, b"previous").unwrap();
+
+        let writer = OutputWriter::create(&path, OutputLock::Wait, true)
+            .unwrap()
+            .unwrap();
+
+        for atomic in [true, false].iter() {
+            assert!(OutputWriter::create(&path, OutputLock::Skip, *atomic)
+                .unwrap()
+                .is_none());
+        }
+
+        drop(writer);
+        assert_eq!(fs::read(&path).unwrap(), b"previous");
+    }
+
+    #[test]
+    fn skip_locked_new_output() {
+        let path = output_path("skip_locked_new.bin");
+
+        let mut writer = OutputWriter::create(&path, OutputLock::Wait, true)
+            .unwrap()
+            .unwrap();
+        assert!(OutputWriter::create(&path, OutputLock::Skip, true)
+            .unwrap()
+            .is_none());
+
+        writer.write_all(b"image").unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"image");
+        assert!(OutputWriter::create(&path, OutputLock::Skip, true)
+            .unwrap()
+            .is_some());
+    }
+
+    // On Windows, the lock file is left in place.
+    #[cfg(not(windows))]
+    #[test]
+    fn only_the_output_is_left() {
+        for atomic in [true, false].iter() {
+            let dir = output_path("only_output").with_extension("dir");
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("image.bin");
+
+            let mut writer = OutputWriter::create(&path, OutputLock::Wait, *atomic)
+                .unwrap()
+                .unwrap();
+            writer.write_all(b"image").unwrap();
+            writer.finish().unwrap();
+
+            assert_eq!(entries_of(&dir), vec![path]);
+        }
+    }
+
+    #[test]
+    fn waiting_process_locks_the_current_lock_file() {
+        let path = output_path("wait_for_removed_lock.bin");
+
+        let held = LockFile::acquire(&path, OutputLock::Wait).unwrap().unwrap();
+
+        let waiting = {
+            let path = path.clone();
+            std::thread::spawn(move || LockFile::acquire(&path, OutputLock::Wait).unwrap())
+        };
+
+        // the waiting process acquires its lock on the lock file once it has been removed
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        drop(held);
+        let acquired = waiting.join().unwrap().unwrap();
+
+        assert!(LockFile::acquire(&path, OutputLock::Skip)
+            .unwrap()
+            .is_none());
+
+        drop(acquired);
+        assert_eq!(
+            sidecar_path(&path, ".lock").unwrap().exists(),
+            cfg!(windows)
+        );
+    }
+}
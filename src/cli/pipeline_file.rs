@@ -0,0 +1,209 @@
+//! Pipeline files hold parsed image operations, so they can be stored, e.g. by a tool which
+//! composes them, and applied later with `--pipeline`, without parsing an image script.
+//!
+//! A pipeline file is written in JSON or TOML, which is chosen by its extension. It holds a single
+//! table with the image operations as `instructions`, e.g. in JSON:
+//! `{"instructions": [{"operation": {"resize": [10, 10]}}, {"operation": "invert"}]}`.
+
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use clap::ArgMatches;
+use serde::{Deserialize, Serialize};
+use sic_image_engine::engine::Instr;
+
+use crate::cli::app::arg_names::ARG_EXPORT_PIPELINE;
+use crate::cli::config::Config;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PipelineFormat {
+    Json,
+    Toml,
+}
+
+impl PipelineFormat {
+    pub fn from_path(path: &Path) -> anyhow::Result<PipelineFormat> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("json") => Ok(PipelineFormat::Json),
+            Some("toml") => Ok(PipelineFormat::Toml),
+            _ => bail!(
+                "Unable to determine the format of pipeline file '{}'; its extension should be 'json' or 'toml'.",
+                path.display()
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PipelineFile<T> {
+    instructions: T,
+}
+
+pub fn to_string(program: &[Instr], format: PipelineFormat) -> anyhow::Result<String> {
+    let file = PipelineFile {
+        instructions: program,
+    };
+
+    // the toml crate can't (de)serialize enum variants which hold a value, e.g. `resize`, so TOML
+    // is converted from and to the tables they're written as in JSON
+    let text = match format {
+        PipelineFormat::Json => serde_json::to_string_pretty(&file)?,
+        PipelineFormat::Toml => {
+            let mut value = serde_json::to_value(&file)?;
+            remove_nulls(&mut value);
+            // a TOML value writes the values of a table before its subtables, as TOML requires
+            toml::to_string(&toml::Value::try_from(value)?)?
+        }
+    };
+
+    Ok(text)
+}
+
+/// Removes the entries of objects without a value, such as inputs which weren't given, since TOML
+/// has no null value; such entries are read back as missing ones.
+fn remove_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(entries) => {
+            entries.retain(|_, value| !value.is_null());
+            entries.values_mut().for_each(remove_nulls);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+pub fn from_str(text: &str, format: PipelineFormat) -> anyhow::Result<Vec<Instr>> {
+    let file: PipelineFile<Vec<Instr>> = match format {
+        PipelineFormat::Json => serde_json::from_str(text)?,
+        PipelineFormat::Toml => {
+            let value = serde_json::to_value(toml::from_str::<toml::Value>(text)?)?;
+            serde_json::from_value(value)?
+        }
+    };
+
+    Ok(file.instructions)
+}
+
+pub fn read_pipeline_file(path: &Path) -> anyhow::Result<Vec<Instr>> {
+    let format = PipelineFormat::from_path(path)?;
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Unable to read pipeline file '{}'.", path.display()))?;
+
+    from_str(&text, format)
+        .with_context(|| format!("Unable to parse pipeline file '{}'.", path.display()))
+}
+
+/// Runs `--export-pipeline`, which writes the parsed image operations to a pipeline file, instead
+/// of applying them to an image.
+pub fn run_export_pipeline(matches: &ArgMatches, config: &Config) -> anyhow::Result<()> {
+    let path = Path::new(
+        matches
+            .value_of(ARG_EXPORT_PIPELINE)
+            .expect("--export-pipeline takes a value"),
+    );
+
+    let text = to_string(
+        &config.image_operations_program,
+        PipelineFormat::from_path(path)?,
+    )?;
+
+    std::fs::write(path, text)
+        .with_context(|| format!("Unable to write pipeline file '{}'.", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_image_engine::engine::{EnvItem, ItemName};
+    use sic_image_engine::wrapper::gravity::Gravity;
+    use sic_image_engine::ImgOp;
+
+    fn program() -> Vec<Instr> {
+        sic_parser::parse_script(
+            "set sampling-filter triangle; set resize.1 sampling-filter nearest; resize 10 20; \
+             resize 5 5; del sampling-filter; crop 0 0 50% $height/2; set gravity bottom-right; \
+             extend 40 40; invert; border 1 2 rgba(255, 0, 0, 255); crop-gravity center 5 5",
+        )
+        .unwrap()
+    }
+
+    #[parameterized(format = { PipelineFormat::Json, PipelineFormat::Toml })]
+    fn roundtrip(format: PipelineFormat) {
+        let program = program();
+        let text = to_string(&program, format).unwrap();
+
+        assert_eq!(from_str(&text, format).unwrap(), program);
+    }
+
+    #[test]
+    fn from_json() {
+        let text = r#"{
+            "instructions": [
+                {"operation": {"resize": [10, 10]}},
+                {"env-add": {"gravity": "top-left"}},
+                {"env-remove": "gravity"},
+                {"operation": "flip-horizontal"}
+            ]
+        }"#;
+
+        assert_eq!(
+            from_str(text, PipelineFormat::Json).unwrap(),
+            vec![
+                Instr::Operation(ImgOp::Resize((10, 10))),
+                Instr::EnvAdd(EnvItem::Gravity(Gravity::TopLeft)),
+                Instr::EnvRemove(ItemName::Gravity),
+                Instr::Operation(ImgOp::FlipHorizontal),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_toml() {
+        let text = r#"
+            [[instructions]]
+            operation = { resize = [10, 10] }
+
+            [[instructions]]
+            operation = "flip-horizontal"
+        "#;
+
+        assert_eq!(
+            from_str(text, PipelineFormat::Toml).unwrap(),
+            vec![
+                Instr::Operation(ImgOp::Resize((10, 10))),
+                Instr::Operation(ImgOp::FlipHorizontal),
+            ]
+        );
+    }
+
+    #[parameterized(
+        text = {
+            r#"{"instructions": [{"operation": {"resize": [10]}}]}"#,
+            r#"{"instructions": [{"operation": "sharpen-more"}]}"#,
+            r#"{"instructions": [{"operation": {"pad-to-ratio": {"ratio": "0:1"}}}]}"#,
+            r#"[{"operation": "invert"}]"#,
+        }
+    )]
+    fn from_json_invalid(text: &str) {
+        assert!(from_str(text, PipelineFormat::Json).is_err());
+    }
+
+    #[parameterized(
+        path = { "pipeline.json", "pipeline.JSON", "pipeline.toml", "pipeline", "pipeline.yaml" },
+        expected = {
+            Some(PipelineFormat::Json),
+            Some(PipelineFormat::Json),
+            Some(PipelineFormat::Toml),
+            None,
+            None,
+        }
+    )]
+    fn format_from_path(path: &str, expected: Option<PipelineFormat>) {
+        assert_eq!(PipelineFormat::from_path(Path::new(path)).ok(), expected);
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use anyhow::anyhow;
+use clap::ArgMatches;
+use sic_parser::errors::SicParserError;
+
+use crate::cli::app::arg_names::{ARG_SCRIPT_IN_PLACE, ARG_SCRIPT_PATH};
+
+/// Runs the `fmt-script` subcommand, which reprints an image operations script in canonical form,
+/// so scripts which are maintained by several people stay diffable.
+pub fn run_fmt_script(matches: &ArgMatches) -> anyhow::Result<()> {
+    rewrite_script(matches, sic_parser::formatter::format_script)
+}
+
+/// Runs the `migrate-script` subcommand, which converts an image operations script from the
+/// syntax of earlier versions to the current syntax.
+pub fn run_migrate_script(matches: &ArgMatches) -> anyhow::Result<()> {
+    rewrite_script(matches, sic_parser::migrate::migrate_script)
+}
+
+/// Reads the script file, rewrites it, and either prints the result or overwrites the file.
+fn rewrite_script<F>(matches: &ArgMatches, rewrite: F) -> anyhow::Result<()>
+where
+    F: Fn(&str, &Path) -> Result<String, SicParserError>,
+{
+    let path = Path::new(matches.value_of(ARG_SCRIPT_PATH).unwrap_or_default());
+
+    let script = std::fs::read_to_string(path)
+        .map_err(|err| anyhow!("unable to read script file: {}", err))?;
+    // the script files it includes are relative to its directory
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let rewritten = rewrite(&script, directory)?;
+
+    if matches.is_present(ARG_SCRIPT_IN_PLACE) {
+        // an unchanged script isn't written, so its modification time is kept
+        if rewritten != script {
+            std::fs::write(path, rewritten)
+                .map_err(|err| anyhow!("unable to write script file: {}", err))?;
+        }
+    } else {
+        print!("{}", rewritten);
+    }
+
+    Ok(())
+}
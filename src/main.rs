@@ -1,10 +1,22 @@
 #![deny(clippy::all)]
 
-use sic::cli::app::arg_names::{ARG_DEP_LICENSES, ARG_LICENSE};
-use sic::cli::app::build_app_config;
-use sic::cli::config::InputOutputMode;
+use sic::cli::app::arg_names::{
+    ARG_CHECK, ARG_DEP_LICENSES, ARG_DRY_RUN, ARG_EXPORT_PIPELINE, ARG_LICENSE,
+};
+use sic::cli::app::{
+    build_app_config, SUBCOMMAND_BENCH, SUBCOMMAND_FMT_SCRIPT, SUBCOMMAND_MIGRATE_SCRIPT,
+    SUBCOMMAND_OPS,
+};
+use sic::cli::bench::{run_bench, BenchSettings};
+use sic::cli::check::run_check;
+use sic::cli::config::{Config, InputOutputMode};
+use sic::cli::debug_bundle::DebugBundle;
+use sic::cli::dry_run::run_dry_run;
 use sic::cli::license::LicenseTexts;
+use sic::cli::ops::run_ops;
 use sic::cli::pipeline::{run_display_licenses, run_with_devices};
+use sic::cli::pipeline_file::run_export_pipeline;
+use sic::cli::script::{run_fmt_script, run_migrate_script};
 
 const LICENSE_SELF: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/LICENSE-MIT"));
 
@@ -19,17 +31,53 @@ fn main() -> anyhow::Result<()> {
     let app = sic::cli::app::create_app(VERSION, ABOUT, HELP_OPERATIONS_AVAILABLE);
     let matches = app.get_matches();
 
-    let license_display = matches.is_present(ARG_LICENSE) || matches.is_present(ARG_DEP_LICENSES);
+    // The subcommands have their own arguments, and don't use the regular configuration
+    if let Some(bench) = matches.subcommand_matches(SUBCOMMAND_BENCH) {
+        return run_bench(&BenchSettings::from_matches(bench)?);
+    }
+
+    if let Some(ops) = matches.subcommand_matches(SUBCOMMAND_OPS) {
+        return run_ops(ops);
+    }
+
+    if let Some(fmt_script) = matches.subcommand_matches(SUBCOMMAND_FMT_SCRIPT) {
+        return run_fmt_script(fmt_script);
+    }
+
+    if let Some(migrate_script) = matches.subcommand_matches(SUBCOMMAND_MIGRATE_SCRIPT) {
+        return run_migrate_script(migrate_script);
+    }
+
+    let debug_bundle = DebugBundle::from_matches(&matches);
 
-    let configuration = build_app_config(&matches)?;
+    let configuration = build_app_config(&matches)
+        .map_err(|err| DebugBundle::report(debug_bundle.as_ref(), None, err))?;
+
+    run(&matches, &configuration).map_err(|err| {
+        DebugBundle::report(
+            debug_bundle.as_ref(),
+            Some(&configuration.image_operations_program),
+            err,
+        )
+    })
+}
+
+fn run(matches: &clap::ArgMatches, configuration: &Config) -> anyhow::Result<()> {
+    let license_display = matches.is_present(ARG_LICENSE) || matches.is_present(ARG_DEP_LICENSES);
 
     if license_display {
         run_display_licenses(
-            &configuration,
+            configuration,
             &LicenseTexts::new(LICENSE_SELF, LICENSE_DEPS),
         )
+    } else if matches.is_present(ARG_CHECK) {
+        run_check(matches, configuration, &mut std::io::stdout())
+    } else if matches.is_present(ARG_DRY_RUN) {
+        run_dry_run(matches, configuration, &mut std::io::stdout())
+    } else if matches.is_present(ARG_EXPORT_PIPELINE) {
+        run_export_pipeline(matches, configuration)
     } else {
-        let io_device = InputOutputMode::try_from_matches(&matches)?;
-        run_with_devices(io_device, &configuration)
+        let io_device = InputOutputMode::try_from_matches(matches)?;
+        run_with_devices(io_device, configuration)
     }
 }
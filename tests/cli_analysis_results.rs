@@ -0,0 +1,188 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+/// Crops a template from the default input image, and returns its path.
+fn setup_template(name: &str, (x, y, width, height): (u32, u32, u32, u32)) -> String {
+    let image = sic_core::image::open(setup_input_path(DEFAULT_IN)).unwrap();
+    let path = setup_output_path(name);
+    image.crop_imm(x, y, width, height).save(&path).unwrap();
+
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn find_reports_text() {
+    let template = setup_template("analysis_find_template_text.png", (3, 2, 4, 3));
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("analysis_find_text.png")
+        .with_args(["--find", &template])
+        .spawn_child();
+    let output = process.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        format!(
+            "find '{}': x=3 y=2 width=4 height=3 score=1.0000\n",
+            template
+        )
+    );
+}
+
+#[test]
+fn find_reports_json() {
+    let template = setup_template("analysis_find_template_json.png", (1, 1, 3, 3));
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("analysis_find_json.png")
+        .with_args(["--analysis-format", "json"])
+        .with_args([
+            "--apply-operations",
+            &format!(
+                "find '{0}'; set region rect(4, 0, 4, 6); find '{0}'",
+                template
+            ),
+        ])
+        .spawn_child();
+    let output = process.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = results.as_array().unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["operation"], "find");
+    assert_eq!(results[0]["template"], template.as_str());
+    assert_eq!(
+        (results[0]["x"].as_u64(), results[0]["y"].as_u64()),
+        (Some(1), Some(1))
+    );
+    assert_eq!(results[0]["score"].as_f64(), Some(1.0));
+    // the second match lies within the region, but is reported in coordinates of the image
+    assert!(results[1]["x"].as_u64().unwrap() >= 4);
+}
+
+#[test]
+fn find_outlines_match() {
+    use sic_core::image::{GenericImageView, Rgba};
+
+    let template = setup_template("analysis_find_template_outline.png", (2, 1, 4, 4));
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("analysis_find_outline.png")
+        .with_args(["--find", &template, "rgba(255, 255, 255, 255)"])
+        .spawn_child();
+    let output = process.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let image = sic_core::image::open(setup_output_path("analysis_find_outline.png")).unwrap();
+    assert_eq!(image.get_pixel(2, 1), Rgba([255, 255, 255, 255]));
+    assert_eq!(image.get_pixel(5, 4), Rgba([255, 255, 255, 255]));
+}
+
+#[test]
+fn find_reports_on_stderr_if_image_is_written_to_stdout() {
+    let template = setup_template("analysis_find_template_stdout.png", (0, 0, 2, 2));
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .with_args(["--output-format", "png", "--find", &template])
+        .spawn_child();
+    let output = process.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    assert!(output.stdout.starts_with(b"\x89PNG"));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("x=0 y=0 width=2 height=2"));
+}
+
+#[test]
+fn find_template_larger_than_image() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("analysis_find_too_large.png")
+        .with_args([
+            "--find",
+            setup_input_path("unsplash_763569_cropped.jpg")
+                .to_str()
+                .unwrap(),
+        ])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
+
+#[test]
+fn contours_reports_text() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("analysis_contours_text.png")
+        .with_args(["--contours", "0"])
+        .spawn_child();
+    let output = process.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    // with a threshold of 0, the whole image is a single bright area
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        "contours threshold=0: contours=1 outer=1 holes=0 points=24\n"
+    );
+}
+
+#[test]
+fn contours_reports_json() {
+    use sic_core::image::{GenericImageView, Rgba};
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("analysis_contours_json.png")
+        .with_args(["--analysis-format", "json"])
+        .with_args(["--contours", "0", "rgba(255, 0, 255, 255)"])
+        .spawn_child();
+    let output = process.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let contours = results[0]["contours"].as_array().unwrap();
+
+    assert_eq!(results[0]["operation"], "contours");
+    assert_eq!(contours.len(), 1);
+    assert_eq!(contours[0]["kind"], "outer");
+    assert_eq!(contours[0]["points"][0], serde_json::json!([0, 0]));
+
+    let image = sic_core::image::open(setup_output_path("analysis_contours_json.png")).unwrap();
+    assert_eq!(image.get_pixel(7, 5), Rgba([255, 0, 255, 255]));
+    assert_ne!(image.get_pixel(3, 3), Rgba([255, 0, 255, 255]));
+}
+
+#[test]
+fn no_analysis_results_reported() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("analysis_none.png")
+        .with_args(["--analysis-format", "json", "--invert"])
+        .spawn_child();
+    let output = process.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn unknown_analysis_format() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("analysis_unknown_format.png")
+        .with_args(["--analysis-format", "yaml"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
@@ -0,0 +1,190 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use sic_core::image;
+use sic_core::image::{AnimationDecoder, GenericImageView};
+
+fn frames_of(path: std::path::PathBuf) -> Vec<image::Frame> {
+    let file = std::fs::File::open(path).unwrap();
+    let decoder = image::gif::GifDecoder::new(file).unwrap();
+    decoder.into_frames().collect_frames().unwrap()
+}
+
+#[test]
+fn gif_to_gif_keeps_all_frames() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("animation_resize.gif")
+        .with_args(["--resize", "10", "10"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let input = frames_of(setup_input_path("loop.gif"));
+    let output = frames_of(setup_output_path("animation_resize.gif"));
+
+    assert_eq!(output.len(), input.len());
+
+    for (input, output) in input.iter().zip(&output) {
+        assert_eq!(output.buffer().dimensions(), (10, 10));
+        assert_eq!(output.delay(), input.delay());
+    }
+}
+
+fn loops(path: std::path::PathBuf) -> bool {
+    let bytes = std::fs::read(path).unwrap();
+    bytes.windows(11).any(|window| window == b"NETSCAPE2.0")
+}
+
+#[test]
+fn gif_to_gif_keeps_the_repeat_count() {
+    for (input, output) in [
+        ("loop.gif", "animation_loop.gif"),
+        ("noloop.gif", "animation_noloop.gif"),
+    ]
+    .iter()
+    {
+        let mut process = SicTestCommandBuilder::new()
+            .input_from_resources(input)
+            .output_in_target(output)
+            .with_args(["--flip-horizontal"])
+            .spawn_child();
+        assert!(process.wait().unwrap().success());
+
+        assert_eq!(
+            loops(setup_output_path(output)),
+            loops(setup_input_path(input))
+        );
+    }
+
+    assert_not!(loops(setup_output_path("animation_noloop.gif")));
+}
+
+#[test]
+fn frames_are_processed_separately() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("animation_crop.gif")
+        .with_args(["--crop", "0", "0", "1", "1"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let input = frames_of(setup_input_path("loop.gif"));
+    let output = frames_of(setup_output_path("animation_crop.gif"));
+
+    let colors = |frames: &[image::Frame]| {
+        frames
+            .iter()
+            .map(|frame| *frame.buffer().get_pixel(0, 0))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(colors(&output), colors(&input));
+}
+
+#[test]
+fn apng_to_gif_keeps_all_frames() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("apng_sample.png")
+        .output_in_target("animation_from_apng.gif")
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = frames_of(setup_output_path("animation_from_apng.gif"));
+    assert_eq!(output.len(), 3);
+}
+
+#[test]
+fn selected_frame_is_still_image() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("animation_select_frame.gif")
+        .with_args(["--select-frame", "last"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = frames_of(setup_output_path("animation_select_frame.gif"));
+    assert_eq!(output.len(), 1);
+}
+
+#[test]
+fn other_output_formats_use_the_first_frame() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("animation_first_frame.png")
+        .with_args(["--resize", "10", "10"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = image::open(setup_output_path("animation_first_frame.png")).unwrap();
+    assert_eq!(output.dimensions(), (10, 10));
+}
+
+#[test]
+fn gif_colors_limit_the_palette() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("animation_gif_colors.gif")
+        .with_args(["--gif-colors", "4"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = frames_of(setup_output_path("animation_gif_colors.gif"));
+    let mut colors = output
+        .iter()
+        .flat_map(|frame| frame.buffer().pixels().copied())
+        .map(|pixel| pixel.0)
+        .collect::<Vec<_>>();
+    colors.sort_unstable();
+    colors.dedup();
+
+    assert_eq!(output.len(), frames_of(setup_input_path("loop.gif")).len());
+    assert!(colors.len() <= 4);
+}
+
+#[test]
+fn gif_local_palette_keeps_the_colors_of_each_frame() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("animation_gif_local_palette.gif")
+        .with_args(["--gif-palette", "local"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let input = frames_of(setup_input_path("loop.gif"));
+    let output = frames_of(setup_output_path("animation_gif_local_palette.gif"));
+
+    assert_eq!(output.len(), input.len());
+
+    for (input, output) in input.iter().zip(&output) {
+        assert_eq!(output.buffer(), input.buffer());
+    }
+}
+
+#[test]
+fn gif_colors_out_of_range() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("animation_gif_colors_out_of_range.gif")
+        .with_args(["--gif-colors", "257"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(!result.unwrap().success());
+}
+
+#[test]
+fn gif_unknown_palette() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("animation_gif_unknown_palette.gif")
+        .with_args(["--gif-palette", "adaptive"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(!result.unwrap().success());
+}
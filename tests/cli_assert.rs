@@ -0,0 +1,37 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+#[test]
+fn assert_holds() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("assert_holds.png")
+        .with_args(&["--assert", "width==8 && height==6 && has_alpha==false"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+}
+
+#[test]
+fn assert_does_not_hold() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("assert_does_not_hold.png")
+        .with_args(&["--assert", "width>=800"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
+
+#[test]
+fn assert_invalid_expression() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("assert_invalid_expression.png")
+        .with_args(&["--assert", "width >= wide"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
@@ -0,0 +1,54 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use sic_core::image;
+
+fn temporary_files_of(name: &str) -> Vec<std::path::PathBuf> {
+    let dir = setup_output_path(name).parent().unwrap().to_path_buf();
+
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let file_name = path.file_name().unwrap().to_string_lossy();
+            file_name.starts_with(&format!(".{}.", name)) && file_name.ends_with(".tmp")
+        })
+        .collect()
+}
+
+#[test]
+fn atomic_output() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("atomic_output.png")
+        .spawn_child();
+
+    assert!(process.wait().unwrap().success());
+    assert!(image::open(setup_output_path("atomic_output.png")).is_ok());
+    assert!(temporary_files_of("atomic_output.png").is_empty());
+}
+
+#[test]
+fn failed_atomic_output_leaves_no_temporary_file() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("atomic_output_failed.png")
+        .with_args(["--crop", "0", "0", "1000", "1000"])
+        .spawn_child();
+
+    assert_not!(process.wait().unwrap().success());
+    assert!(temporary_files_of("atomic_output_failed.png").is_empty());
+}
+
+#[test]
+fn no_atomic_output() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("no_atomic_output.png")
+        .with_args(["--no-atomic"])
+        .spawn_child();
+
+    assert!(process.wait().unwrap().success());
+    assert!(image::open(setup_output_path("no_atomic_output.png")).is_ok());
+}
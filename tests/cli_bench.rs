@@ -0,0 +1,67 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+#[test]
+fn bench_reports_stages() {
+    let input = setup_input_path(DEFAULT_IN);
+
+    let process = SicTestCommandBuilder::new()
+        .with_args([
+            "bench",
+            "--pipeline",
+            "invert; resize 4 4",
+            "--iterations",
+            "3",
+            input.to_str().unwrap(),
+        ])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert!(stdout.starts_with("3 iterations"));
+
+    for stage in ["decode", "operations", "encode", "total", "p50", "p99"].iter() {
+        assert!(stdout.contains(stage));
+    }
+}
+
+#[test]
+fn bench_output_format() {
+    let input = setup_input_path(DEFAULT_IN);
+
+    let process = SicTestCommandBuilder::new()
+        .with_args(["bench", "--output-format", "jpg", input.to_str().unwrap()])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert!(stdout.contains("(to jpg)"));
+}
+
+#[test]
+fn bench_invalid_arguments() {
+    let input = setup_input_path(DEFAULT_IN);
+    let input = input.to_str().unwrap();
+
+    let cases: &[&[&str]] = &[
+        &["bench", "--iterations", "0", input],
+        &["bench", "--iterations", "many", input],
+        &["bench", "--pipeline", "unknown-operation 1", input],
+        &["bench", "--output-format", "unknown-format", input],
+    ];
+
+    for args in cases {
+        let process = SicTestCommandBuilder::new()
+            .with_args(args.iter())
+            .spawn_child();
+
+        let ProcessOutput { success, .. } = output_of(process);
+        assert_not!(success);
+    }
+}
@@ -0,0 +1,85 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+#[test]
+fn check_valid_pipeline() {
+    let output = setup_output_path("check_valid_pipeline.png");
+    let _ = std::fs::remove_file(&output);
+    let diff = setup_input_path("palette_4x4.png");
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("check_valid_pipeline.png")
+        .with_args([
+            "--check",
+            "--apply-operations",
+            &format!("blur 1; diff '{}'", diff.display()),
+        ])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert_eq!(stdout, "Checked 2 image operation(s); no problems found.\n");
+
+    // nothing is written
+    assert_not!(output.exists());
+}
+
+#[test]
+fn check_cli_operations() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("check_cli_operations.png")
+        .with_args(["--check", "--blur", "1", "--flip-horizontal"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert_eq!(stdout, "Checked 2 image operation(s); no problems found.\n");
+}
+
+#[test]
+fn check_missing_referenced_file() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("check_missing_referenced_file.png")
+        .with_args([
+            "--check",
+            "--apply-operations",
+            "blur 1; diff 'missing.png'",
+        ])
+        .spawn_child();
+
+    let ProcessOutput { success, .. } = output_of(process);
+    assert_not!(success);
+}
+
+#[test]
+fn check_invalid_argument() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("check_invalid_argument.png")
+        .with_args(["--check", "--apply-operations", "blur one"])
+        .spawn_child();
+
+    let ProcessOutput { success, .. } = output_of(process);
+    assert_not!(success);
+}
+
+#[test]
+fn check_missing_input() {
+    let process = SicTestCommandBuilder::new()
+        .input("missing_input.png")
+        .output_in_target("check_missing_input.png")
+        .with_args(["--check", "--apply-operations", "blur 1"])
+        .spawn_child();
+
+    let ProcessOutput { success, .. } = output_of(process);
+    assert_not!(success);
+}
@@ -72,6 +72,9 @@ mod convert_to_x {
         "pgm",
         "ppm",
         "pam",
+        "webp",
+        "tiff",
+        "dds",
     }, expected_format = {
         image::ImageFormat::Bmp,
         image::ImageFormat::Farbfeld,
@@ -84,7 +87,9 @@ mod convert_to_x {
         image::ImageFormat::Pnm,
         image::ImageFormat::Pnm,
         image::ImageFormat::Pnm,
-
+        image::ImageFormat::WebP,
+        image::ImageFormat::Tiff,
+        image::ImageFormat::Dds,
     })]
     fn convert_to_x_by_extension(ext: &str, expected_format: image::ImageFormat) {
         let input_path = setup_input_path("palette_4x4.png");
@@ -142,6 +147,9 @@ mod convert_to_x_by_ff {
         "pgm",
         "ppm",
         "pam",
+        "webp",
+        "tiff",
+        "dds",
     }, expected_format = {
         image::ImageFormat::Bmp,
         image::ImageFormat::Farbfeld,
@@ -154,6 +162,9 @@ mod convert_to_x_by_ff {
         image::ImageFormat::Pnm,
         image::ImageFormat::Pnm,
         image::ImageFormat::Pnm,
+        image::ImageFormat::WebP,
+        image::ImageFormat::Tiff,
+        image::ImageFormat::Dds,
     })]
     fn convert_to_bmp_by_ff(which: &str, expected_format: image::ImageFormat) {
         let input_path = setup_input_path("palette_4x4.png");
@@ -329,3 +340,202 @@ fn convert_jpeg_quality_different() {
     clean_up_output_path(path_buf_str(&out1));
     clean_up_output_path(path_buf_str(&out2));
 }
+
+#[test]
+fn convert_webp_lossless_different() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let out1 = setup_output_path("out_03_lossy.webp");
+    let out2 = setup_output_path("out_03_lossless.webp");
+
+    for (output, lossless) in [(&out1, false), (&out2, true)].iter() {
+        let mut args = vec![
+            "sic",
+            "--webp-quality",
+            "50",
+            "--input",
+            path_buf_str(&our_input),
+            "--output",
+            path_buf_str(output),
+        ];
+
+        if *lossless {
+            args.push("--webp-lossless");
+        }
+
+        let matches = get_app("", "", "").get_matches_from(args);
+        run_with_devices(
+            InputOutputMode::try_from_matches(&matches).unwrap(),
+            &build_app_config(&matches).unwrap(),
+        )
+        .unwrap();
+    }
+
+    assert!(is_image_format(
+        path_buf_str(&out1),
+        image::ImageFormat::WebP
+    ));
+    assert!(is_image_format(
+        path_buf_str(&out2),
+        image::ImageFormat::WebP
+    ));
+    assert_ne!(
+        read_file_to_bytes(path_buf_str(&out1)),
+        read_file_to_bytes(path_buf_str(&out2))
+    );
+
+    clean_up_output_path(path_buf_str(&out1));
+    clean_up_output_path(path_buf_str(&out2));
+}
+
+#[test]
+fn convert_jpeg_progressive() {
+    let our_input = setup_input_path("rainbow_8x6.bmp");
+    let out1 = setup_output_path("out_07_baseline.jpg");
+    let out2 = setup_output_path("out_07_progressive.jpg");
+
+    for (output, progressive) in [(&out1, false), (&out2, true)].iter() {
+        let mut args = vec![
+            "sic",
+            "--input",
+            path_buf_str(&our_input),
+            "--output",
+            path_buf_str(output),
+        ];
+
+        if *progressive {
+            args.push("--jpeg-progressive");
+        }
+
+        let matches = get_app("", "", "").get_matches_from(args);
+        run_with_devices(
+            InputOutputMode::try_from_matches(&matches).unwrap(),
+            &build_app_config(&matches).unwrap(),
+        )
+        .unwrap();
+    }
+
+    // the start of frame marker of progressive JPEG images (SOF2)
+    let is_progressive = |bytes: Vec<u8>| bytes.windows(2).any(|marker| marker == [0xff, 0xc2]);
+
+    assert!(is_image_format(
+        path_buf_str(&out2),
+        image::ImageFormat::Jpeg
+    ));
+    assert!(!is_progressive(read_file_to_bytes(path_buf_str(&out1))));
+    assert!(is_progressive(read_file_to_bytes(path_buf_str(&out2))));
+
+    let decoded = image::open(&out2).unwrap();
+    assert_eq!(decoded.to_rgb().dimensions(), (8, 6));
+
+    clean_up_output_path(path_buf_str(&out1));
+    clean_up_output_path(path_buf_str(&out2));
+}
+
+#[test]
+fn convert_webp_quality_out_of_range() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let out = setup_output_path("out_04.webp");
+
+    let args = vec![
+        "sic",
+        "--webp-quality",
+        "101",
+        "--input",
+        path_buf_str(&our_input),
+        "--output",
+        path_buf_str(&out),
+    ];
+
+    let matches = get_app("", "", "").get_matches_from(args);
+    assert!(build_app_config(&matches).is_err());
+}
+
+#[parameterized(compression = { "bc1", "bc3" }, fourcc = { b"DXT1", b"DXT5" })]
+fn convert_dds_compression(compression: &str, fourcc: &[u8; 4]) {
+    let our_input = setup_input_path("palette_4x4.png");
+    let out = setup_output_path(&format!("out_06_{}.dds", compression));
+
+    let args = vec![
+        "sic",
+        "--dds-compression",
+        compression,
+        "--dds-mipmaps",
+        "--input",
+        path_buf_str(&our_input),
+        "--output",
+        path_buf_str(&out),
+    ];
+
+    let matches = get_app("", "", "").get_matches_from(args);
+    run_with_devices(
+        InputOutputMode::try_from_matches(&matches).unwrap(),
+        &build_app_config(&matches).unwrap(),
+    )
+    .unwrap();
+
+    let bytes = read_file_to_bytes(path_buf_str(&out));
+    // the pixel format, and the number of mipmaps: 4x4, 2x2 and 1x1
+    assert_eq!(&bytes[84..88], fourcc);
+    assert_eq!(bytes[28], 3);
+
+    let decoded = image::open(&out).unwrap().to_rgba();
+    assert_eq!(decoded.dimensions(), (4, 4));
+
+    clean_up_output_path(path_buf_str(&out));
+}
+
+#[test]
+fn convert_dds_unknown_compression() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let out = setup_output_path("out_06_unknown.dds");
+
+    let args = vec![
+        "sic",
+        "--dds-compression",
+        "bc7",
+        "--input",
+        path_buf_str(&our_input),
+        "--output",
+        path_buf_str(&out),
+    ];
+
+    assert!(get_app("", "", "").get_matches_from_safe(args).is_err());
+}
+
+#[parameterized(compression = { "lzw", "deflate", "packbits" })]
+fn convert_tiff_compression(compression: &str) {
+    let our_input = setup_input_path("rainbow_8x6.bmp");
+    let out1 = setup_output_path(&format!("out_05_{}_none.tiff", compression));
+    let out2 = setup_output_path(&format!("out_05_{}.tiff", compression));
+
+    for (output, method) in [(&out1, "none"), (&out2, compression)].iter() {
+        let args = vec![
+            "sic",
+            "--tiff-compression",
+            method,
+            "--input",
+            path_buf_str(&our_input),
+            "--output",
+            path_buf_str(output),
+        ];
+
+        let matches = get_app("", "", "").get_matches_from(args);
+        run_with_devices(
+            InputOutputMode::try_from_matches(&matches).unwrap(),
+            &build_app_config(&matches).unwrap(),
+        )
+        .unwrap();
+    }
+
+    assert!(is_image_format(
+        path_buf_str(&out2),
+        image::ImageFormat::Tiff
+    ));
+    assert_ne!(
+        read_file_to_bytes(path_buf_str(&out1)),
+        read_file_to_bytes(path_buf_str(&out2))
+    );
+
+    clean_up_output_path(path_buf_str(&out1));
+    clean_up_output_path(path_buf_str(&out2));
+}
@@ -0,0 +1,73 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use sic_core::image;
+
+#[test]
+fn create_blank_canvas() {
+    let mut process = SicTestCommandBuilder::new()
+        .output_in_target("create_blank_canvas.png")
+        .with_args(["--create", "5", "3"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = image::open(setup_output_path("create_blank_canvas.png"))
+        .unwrap()
+        .to_rgba();
+    assert_eq!(output.dimensions(), (5, 3));
+    assert!(output.pixels().all(|pixel| pixel.0 == [0, 0, 0, 0]));
+}
+
+#[test]
+fn create_gradient_on_canvas() {
+    let mut process = SicTestCommandBuilder::new()
+        .output_in_target("create_gradient_on_canvas.png")
+        .with_args(["--create", "8", "2"])
+        .with_args([
+            "--gradient",
+            "linear",
+            "rgba(0, 0, 0, 255)",
+            "rgba(255, 255, 255, 255)",
+        ])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = image::open(setup_output_path("create_gradient_on_canvas.png"))
+        .unwrap()
+        .to_rgba();
+    assert_eq!(output.dimensions(), (8, 2));
+
+    // opaque, and from dark on the left to light on the right
+    let row = (0..8).map(|x| output.get_pixel(x, 0).0).collect::<Vec<_>>();
+    assert!(row.iter().all(|pixel| pixel[3] == 255));
+    assert!(row.windows(2).all(|pair| pair[0][0] < pair[1][0]));
+}
+
+#[test]
+fn create_with_input() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("create_with_input.png")
+        .with_args(["--create", "5", "3"])
+        .spawn_child();
+
+    let ProcessOutput { success, .. } = output_of(process);
+    assert_not!(success);
+}
+
+#[test]
+fn create_empty_canvas() {
+    let process = SicTestCommandBuilder::new()
+        .output_in_target("create_empty_canvas.png")
+        .with_args(["--create", "5", "0"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stderr, ..
+    } = output_of(process);
+    assert_not!(success);
+    assert!(stderr.contains("--create is not valid"));
+}
@@ -0,0 +1,101 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use std::path::PathBuf;
+
+fn bundle_path(name: &str) -> PathBuf {
+    let path = setup_output_path(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn contains(haystack: &[u8], needle: &str) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle.as_bytes())
+}
+
+#[test]
+fn bundle_on_engine_failure() {
+    let path = bundle_path("debug_bundle_engine.zip");
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("debug_bundle_engine.png")
+        .with_args(["--crop", "0", "0", "100", "100"])
+        .with_args(["--debug-bundle", path.to_str().unwrap()])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+
+    let bundle = std::fs::read(&path).unwrap();
+    assert!(bundle.starts_with(b"PK\x03\x04"));
+    assert!(contains(&bundle, "error.txt"));
+    assert!(contains(&bundle, "arguments.json"));
+    assert!(contains(&bundle, "versions.json"));
+    assert!(contains(&bundle, "input.json"));
+    assert!(contains(&bundle, "pipeline.json"));
+    assert_not!(contains(&bundle, "input/"));
+}
+
+#[test]
+fn bundle_on_parser_failure_has_no_pipeline() {
+    let path = bundle_path("debug_bundle_parser.zip");
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("debug_bundle_parser.png")
+        .with_args(["--apply-operations", "blur;"])
+        .with_args(["--debug-bundle", path.to_str().unwrap()])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+
+    let bundle = std::fs::read(&path).unwrap();
+    assert!(contains(&bundle, "error.txt"));
+    assert_not!(contains(&bundle, "pipeline.json"));
+}
+
+#[test]
+fn bundle_includes_pixels_on_request() {
+    let path = bundle_path("debug_bundle_pixels.zip");
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("debug_bundle_pixels.png")
+        .with_args(["--crop", "0", "0", "100", "100"])
+        .with_args(["--debug-bundle", path.to_str().unwrap(), "--include-pixels"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+
+    let bundle = std::fs::read(&path).unwrap();
+    assert!(contains(&bundle, &format!("input/{}", DEFAULT_IN)));
+}
+
+#[test]
+fn no_bundle_on_success() {
+    let path = bundle_path("debug_bundle_success.zip");
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("debug_bundle_success.png")
+        .with_args(["--debug-bundle", path.to_str().unwrap()])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    assert_not!(path.exists());
+}
+
+#[test]
+fn include_pixels_requires_debug_bundle() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("debug_bundle_requires.png")
+        .with_args(["--include-pixels"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
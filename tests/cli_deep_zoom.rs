@@ -0,0 +1,100 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+fn clean(name: &str) {
+    let _ = std::fs::remove_dir_all(setup_output_path(&format!("{}_files", name)));
+}
+
+#[test]
+fn deep_zoom_default() {
+    clean("deep_zoom_default");
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("deep_zoom_default.dzi")
+        .with_args(["--deep-zoom"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let descriptor = std::fs::read_to_string(setup_output_path("deep_zoom_default.dzi")).unwrap();
+    assert!(descriptor.contains(r#"Format="png" Overlap="1" TileSize="254""#));
+    assert!(descriptor.contains(r#"<Size Width="8" Height="6"/>"#));
+
+    // 8 by 6 pixels: levels 0 (1x1) up to and including 3 (8x6), each a single tile
+    let tiles = setup_output_path("deep_zoom_default_files");
+    for (level, dimensions) in [(0, (1, 1)), (1, (2, 2)), (2, (4, 3)), (3, (8, 6))].iter() {
+        let tile = image::open(tiles.join(level.to_string()).join("0_0.png")).unwrap();
+        assert_eq!(tile.dimensions(), *dimensions);
+    }
+    assert_not!(tiles.join("4").exists());
+}
+
+#[test]
+fn deep_zoom_tile_size_and_overlap() {
+    clean("deep_zoom_tiles");
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("deep_zoom_tiles.dzi")
+        .with_args(["--deep-zoom", "--deep-zoom-tile-size", "4"])
+        .with_args(["--deep-zoom-overlap", "0"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let level = setup_output_path("deep_zoom_tiles_files").join("3");
+    let original = image::open(setup_input_path(DEFAULT_IN)).unwrap();
+
+    let last = image::open(level.join("1_1.png")).unwrap();
+    assert_eq!(last.dimensions(), (4, 2));
+    assert_eq!(last.get_pixel(0, 0), original.get_pixel(4, 4));
+    assert_not!(level.join("2_0.png").exists());
+}
+
+#[test]
+fn deep_zoom_json_descriptor_with_jpeg_tiles() {
+    clean("deep_zoom_json");
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("deep_zoom_json.json")
+        .with_args(["--deep-zoom", "--output-format", "jpeg"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let descriptor = std::fs::read_to_string(setup_output_path("deep_zoom_json.json")).unwrap();
+    assert!(descriptor.contains(r#""Format": "jpg""#));
+
+    let tile = setup_output_path("deep_zoom_json_files")
+        .join("3")
+        .join("0_0.jpg");
+    assert!(tile.exists());
+}
+
+#[test]
+fn deep_zoom_tile_size_requires_deep_zoom() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("deep_zoom_requires.png")
+        .with_args(["--deep-zoom-tile-size", "4"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
+
+#[test]
+fn deep_zoom_invalid_tile_size() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("deep_zoom_invalid.dzi")
+        .with_args(["--deep-zoom", "--deep-zoom-tile-size", "0"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
@@ -0,0 +1,70 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+#[test]
+fn dry_run_predicts_dimensions() {
+    let output = setup_output_path("dry_run_predicts_dimensions.png");
+    let _ = std::fs::remove_file(&output);
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("dry_run_predicts_dimensions.png")
+        .with_args([
+            "--dry-run",
+            "--apply-operations",
+            "set preserve-aspect-ratio true; resize 4 4; crop 0 0 50% $height; rotate90",
+        ])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert_eq!(
+        stdout,
+        "input: 8x6\n\
+         1. set preserve-aspect-ratio true\n\
+         2. resize 4 4\n   modifiers: preserve-aspect-ratio true\n   size: 4x3\n\
+         3. crop 0 0 2 3\n   size: 2x3\n\
+         4. rotate90\n   size: 3x2\n\
+         output: 3x2\n"
+    );
+
+    // nothing is written
+    assert_not!(output.exists());
+}
+
+#[test]
+fn dry_run_without_input() {
+    let process = SicTestCommandBuilder::new()
+        .with_args(["--dry-run", "--apply-operations", "blur 1; resize 10 20"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert_eq!(
+        stdout,
+        "input: unknown\n\
+         1. blur 1.0\n   size: unknown\n\
+         2. resize 10 20\n   size: 10x20\n\
+         output: 10x20\n"
+    );
+}
+
+#[test]
+fn dry_run_failing_operation() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .with_args(["--dry-run", "--apply-operations", "invert; crop 0 0 10 10"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stderr, ..
+    } = output_of(process);
+    assert_not!(success);
+    assert!(stderr.contains("Image operation 2 ('crop') would fail."));
+}
@@ -0,0 +1,56 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+fn dry_run(args: &[&str]) -> ProcessOutput {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .with_args(["--dry-run"])
+        .with_args(args)
+        .spawn_child();
+
+    output_of(process)
+}
+
+#[test]
+fn expand_env_substitutes_variables() {
+    std::env::set_var("SIC_TEST_EXPAND_ENV_WIDTH", "4");
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = dry_run(&[
+        "--expand-env",
+        "--apply-operations",
+        "resize ${SIC_TEST_EXPAND_ENV_WIDTH} 0",
+    ]);
+
+    assert!(success);
+    assert!(stdout.ends_with("output: 4x3\n"));
+}
+
+#[test]
+fn expand_env_missing_variable() {
+    let ProcessOutput {
+        success, stderr, ..
+    } = dry_run(&[
+        "--expand-env",
+        "--apply-operations",
+        "resize ${SIC_TEST_EXPAND_ENV_MISSING} 0",
+    ]);
+
+    assert_not!(success);
+    assert!(stderr.contains("environment variable 'SIC_TEST_EXPAND_ENV_MISSING' is not set"));
+}
+
+#[test]
+fn without_expand_env_variables_are_not_substituted() {
+    std::env::set_var("SIC_TEST_EXPAND_ENV_HEIGHT", "3");
+
+    let ProcessOutput { success, .. } = dry_run(&[
+        "--apply-operations",
+        "resize 4 ${SIC_TEST_EXPAND_ENV_HEIGHT}",
+    ]);
+
+    assert_not!(success);
+}
@@ -0,0 +1,49 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+#[test]
+fn explain_dir_writes_thumbnail_per_operation() {
+    let explain_dir = setup_output_path("explain_dir_per_operation");
+    let _ = std::fs::remove_dir_all(&explain_dir);
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("explain_dir_per_operation.png")
+        .with_args(&["--explain-dir", explain_dir.to_str().unwrap()])
+        .with_args(&[
+            "--apply-operations",
+            "blur 1; set sampling-filter nearest; resize 4 4; flip-horizontal",
+        ])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let mut files = std::fs::read_dir(&explain_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    files.sort();
+
+    assert_eq!(
+        files,
+        vec![
+            "00-input.png",
+            "01-blur.png",
+            "02-resize.png",
+            "03-flip-horizontal.png"
+        ]
+    );
+}
+
+#[test]
+fn explain_dir_conflicts_with_glob() {
+    let mut process = SicTestCommandBuilder::new()
+        .glob_input_from_resources("*.bmp")
+        .glob_output_in_target("explain_dir_glob")
+        .with_args(&["--explain-dir", "explain_dir_glob_thumbs"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
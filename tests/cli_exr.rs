@@ -0,0 +1,209 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use std::path::PathBuf;
+
+const HDR_IN: &str = "hdr_8x6.exr";
+
+fn convert(input: PathBuf, output: &str, args: &[&str]) -> bool {
+    SicTestCommandBuilder::new()
+        .input(input)
+        .output_in_target(output)
+        .with_args(args)
+        .spawn_child()
+        .wait()
+        .unwrap()
+        .success()
+}
+
+#[test]
+fn exr_to_exr_keeps_hdr_samples() {
+    assert!(convert(
+        setup_input_path(HDR_IN),
+        "exr_to_exr_keeps_hdr_samples.exr",
+        &[]
+    ));
+
+    let input = std::fs::read(setup_input_path(HDR_IN)).unwrap();
+    let output = std::fs::read(setup_output_path("exr_to_exr_keeps_hdr_samples.exr")).unwrap();
+    assert_eq!(input, output);
+}
+
+#[test]
+fn exr_to_png_is_clamped() {
+    assert!(convert(
+        setup_input_path(HDR_IN),
+        "exr_to_png_is_clamped.png",
+        &[]
+    ));
+
+    let output = sic_core::image::open(setup_output_path("exr_to_png_is_clamped.png")).unwrap();
+    let output = output.to_rgba();
+
+    // the red channel of the input image goes from 0.0 up to 7.0
+    assert_eq!(output.get_pixel(0, 0)[0], 0);
+    assert_eq!(output.get_pixel(1, 0)[0], 255);
+    assert_eq!(output.get_pixel(7, 0)[0], 255);
+}
+
+#[test]
+fn png_to_exr_and_back() {
+    assert!(convert(
+        setup_input_path(DEFAULT_IN),
+        "png_to_exr_and_back.exr",
+        &[]
+    ));
+    assert!(convert(
+        setup_output_path("png_to_exr_and_back.exr"),
+        "png_to_exr_and_back.png",
+        &[]
+    ));
+
+    let input = sic_core::image::open(setup_input_path(DEFAULT_IN)).unwrap();
+    let output = sic_core::image::open(setup_output_path("png_to_exr_and_back.png")).unwrap();
+    assert_eq!(input.to_rgba(), output.to_rgba());
+}
+
+#[test]
+fn exr_with_operation() {
+    assert!(convert(
+        setup_input_path(HDR_IN),
+        "exr_with_operation.exr",
+        &["--apply-operations", "flip-horizontal"]
+    ));
+    assert!(convert(
+        setup_output_path("exr_with_operation.exr"),
+        "exr_with_operation.png",
+        &[]
+    ));
+
+    let output = sic_core::image::open(setup_output_path("exr_with_operation.png")).unwrap();
+    assert_eq!(output.to_rgba().get_pixel(7, 0)[0], 0);
+}
+
+#[test]
+fn exr_with_geometric_operations_keeps_hdr_samples() {
+    assert!(convert(
+        setup_input_path(HDR_IN),
+        "exr_with_geometric_operations_keeps_hdr_samples.exr",
+        &["--apply-operations", "rotate180; flip-vertical"]
+    ));
+    assert!(convert(
+        setup_output_path("exr_with_geometric_operations_keeps_hdr_samples.exr"),
+        "exr_with_geometric_operations_keeps_hdr_samples.png",
+        &["--tonemap", "reinhard"]
+    ));
+
+    let output = sic_core::image::open(setup_output_path(
+        "exr_with_geometric_operations_keeps_hdr_samples.png",
+    ))
+    .unwrap();
+    let output = output.to_rgba();
+
+    // flipped horizontally, the highlights of the red channel are on the left, and not clipped
+    let red = (0..7)
+        .map(|x| output.get_pixel(x, 0)[0])
+        .collect::<Vec<_>>();
+    assert!(red.windows(2).all(|pair| pair[0] > pair[1]));
+    assert!(red[0] < 255);
+}
+
+#[test]
+fn exr_with_clamping_operation_warns() {
+    let output = SicTestCommandBuilder::new()
+        .input_from_resources(HDR_IN)
+        .output_in_target("exr_with_clamping_operation_warns.exr")
+        .with_args(["--blur", "1"])
+        .spawn_child()
+        .wait_with_output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("were clamped, since the 'blur' operation"));
+}
+
+#[test]
+fn exr_tonemap_keeps_highlights() {
+    assert!(convert(
+        setup_input_path(HDR_IN),
+        "exr_tonemap_keeps_highlights.png",
+        &["--tonemap", "reinhard"]
+    ));
+
+    let output =
+        sic_core::image::open(setup_output_path("exr_tonemap_keeps_highlights.png")).unwrap();
+    let output = output.to_rgba();
+
+    // unlike the clamped image, the highlights of the red channel still differ
+    let red = (1..8)
+        .map(|x| output.get_pixel(x, 0)[0])
+        .collect::<Vec<_>>();
+    assert!(red.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(red[6] < 255);
+}
+
+#[test]
+fn exr_tonemap_after_flip_keeps_highlights() {
+    assert!(convert(
+        setup_input_path(HDR_IN),
+        "exr_tonemap_after_flip_keeps_highlights.png",
+        &["--apply-operations", "flip-horizontal; tonemap reinhard"]
+    ));
+
+    let output = sic_core::image::open(setup_output_path(
+        "exr_tonemap_after_flip_keeps_highlights.png",
+    ))
+    .unwrap();
+    let output = output.to_rgba();
+
+    let red = (0..7)
+        .map(|x| output.get_pixel(x, 0)[0])
+        .collect::<Vec<_>>();
+    assert!(red.windows(2).all(|pair| pair[0] > pair[1]));
+    assert!(red[0] < 255);
+}
+
+#[test]
+fn exr_tonemap_after_clamping_operation() {
+    assert!(!convert(
+        setup_input_path(HDR_IN),
+        "exr_tonemap_after_clamping_operation.png",
+        &["--apply-operations", "blur 1; tonemap reinhard"]
+    ));
+}
+
+#[test]
+fn radiance_tonemap_to_png() {
+    assert!(convert(
+        setup_input_path("hdr_8x6.hdr"),
+        "radiance_tonemap_to_png.png",
+        &["--apply-operations", "tonemap aces -1"]
+    ));
+
+    let output = sic_core::image::open(setup_output_path("radiance_tonemap_to_png.png")).unwrap();
+    let output = output.to_rgba();
+
+    assert_eq!(output.dimensions(), (8, 6));
+    assert_eq!(output.get_pixel(0, 0)[0], 0);
+    assert!(output.get_pixel(3, 0)[0] < output.get_pixel(7, 0)[0]);
+}
+
+#[test]
+fn radiance_to_exr_keeps_hdr_samples() {
+    assert!(convert(
+        setup_input_path("hdr_8x6.hdr"),
+        "radiance_to_exr_keeps_hdr_samples.exr",
+        &[]
+    ));
+    assert!(convert(
+        setup_output_path("radiance_to_exr_keeps_hdr_samples.exr"),
+        "radiance_to_exr_keeps_hdr_samples.png",
+        &["--tonemap", "reinhard"]
+    ));
+
+    let output =
+        sic_core::image::open(setup_output_path("radiance_to_exr_keeps_hdr_samples.png")).unwrap();
+    assert!(output.to_rgba().get_pixel(7, 0)[0] < 255);
+}
@@ -0,0 +1,93 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use sic_core::image;
+
+#[test]
+fn mismatching_output_extension_warns() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("fix_extension_warns.png")
+        .with_args(&["--output-format", "jpeg"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stderr, ..
+    } = output_of(process);
+    assert!(success);
+    assert!(stderr.contains("--fix-extension"));
+    assert!(stderr.contains("output format 'jpeg'"));
+    assert!(setup_output_path("fix_extension_warns.png").exists());
+}
+
+#[test]
+fn matching_output_extension_does_not_warn() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("fix_extension_no_warning.jpeg")
+        .with_args(&["--output-format", "jpg"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stderr, ..
+    } = output_of(process);
+    assert!(success);
+    assert_not!(stderr.contains("--fix-extension"));
+}
+
+#[test]
+fn fix_extension_adjusts_output_path() {
+    let expected = setup_output_path("fix_extension_adjusts.jpg");
+    let _ = std::fs::remove_file(&expected);
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("fix_extension_adjusts.png")
+        .with_args(&["--output-format", "jpeg", "--fix-extension"])
+        .spawn_child();
+
+    let ProcessOutput { success, .. } = output_of(process);
+    assert!(success);
+    assert_not!(setup_output_path("fix_extension_adjusts.png").exists());
+    assert_eq!(
+        image::ImageFormat::from_path(&expected).unwrap(),
+        image::io::Reader::open(&expected)
+            .unwrap()
+            .with_guessed_format()
+            .unwrap()
+            .format()
+            .unwrap()
+    );
+}
+
+#[test]
+fn fix_extension_requires_output_format() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("fix_extension_requires.png")
+        .with_args(&["--fix-extension"])
+        .spawn_child();
+
+    let ProcessOutput { success, .. } = output_of(process);
+    assert_not!(success);
+}
+
+#[test]
+fn mismatching_input_extension_is_explained() {
+    let input = setup_output_path("fix_extension_not_a.png");
+    let mut contents = std::fs::read(setup_input_path("unsplash_763569_cropped.jpg")).unwrap();
+    contents.truncate(64);
+    std::fs::write(&input, contents).unwrap();
+
+    let process = SicTestCommandBuilder::new()
+        .input(&input)
+        .output_in_target("fix_extension_not_a_out.png")
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stderr, ..
+    } = output_of(process);
+    assert_not!(success);
+    assert!(stderr.contains("wrong extension"));
+}
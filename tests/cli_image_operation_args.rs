@@ -15,8 +15,57 @@ fn command(input: &str, output: &str, args: &str) -> Child {
         .spawn_child()
 }
 
+#[cfg(test)]
+mod alpha {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn alpha_add_remove() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_alpha_add_remove.png",
+            "--alpha-add --alpha-remove",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn alpha_premultiply_threshold() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_alpha_premultiply_threshold.png",
+            "--alpha-threshold 128 --alpha-premultiply",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn alpha_threshold_out_of_range() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_alpha_threshold_out_of_range.png",
+            "--alpha-threshold 256",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod auto_enhance {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn auto_enhance() {
+        let process = command(DEFAULT_IN, "cio_auto_enhance.png", "--auto-enhance");
+        assert!(output_of(process).success);
+    }
+}
+
 #[cfg(test)]
 mod blur {
+    use super::*;
     use crate::common::*;
 
     //    FIXME(image-rs/image#983): blur panics on option unwrap within image::imageops::sample::vertical_sample.
@@ -66,6 +115,160 @@ mod blur {
         assert!(result.is_ok());
         assert!(result.unwrap().success());
     }
+
+    #[test]
+    fn blur_with_box_algorithm() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_blur_box_algorithm.png",
+            "--blur-algorithm box --blur 4",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn blur_with_unknown_algorithm() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_blur_unknown_algorithm.png",
+            "--blur-algorithm median --blur 4",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod border {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn border() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_border.png",
+            "--border 4 rgba(255,255,255,255)",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn border_per_side() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_border_per_side.png",
+            "--border 1 2 3 4 rgba(255,0,0,128)",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn border_too_many_widths() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_border_too_many.png",
+            "--border 1 2 3 4 5 rgba(255,255,255,255)",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod box_blur {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn box_blur() {
+        let process = command(DEFAULT_IN, "cio_box_blur.png", "--box-blur 2");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn box_blur_negative_radius() {
+        let process = command(DEFAULT_IN, "cio_box_blur_neg.png", "--box-blur -2");
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod channel {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn channel_extract() {
+        let process = command(DEFAULT_IN, "cio_channel_extract.png", "--channel-extract g");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn channel_swap() {
+        let process = command(DEFAULT_IN, "cio_channel_swap.png", "--channel-swap r b");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn channel_swap_unknown_channel() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_channel_swap_unknown.png",
+            "--channel-swap r x",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod convolve {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn convolve() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_convolve.png",
+            "--convolve 3 0 -1 0 -1 5 -1 0 -1 0 --invert",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn convolve_incorrect_amount_of_weights() {
+        let process = command(DEFAULT_IN, "cio_convolve_count.png", "--convolve 3 1 1 1");
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn convolve_even_size() {
+        let process = command(DEFAULT_IN, "cio_convolve_even.png", "--convolve 2 1 1 1 1");
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn convolve_file() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_convolve_file.png",
+            &[
+                "--convolve-file",
+                setup_input_path("kernel/emboss_5x5.txt").to_str().unwrap(),
+            ]
+            .join(" "),
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn convolve_file_missing() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_convolve_file_missing.png",
+            "--convolve-file missing_kernel.txt",
+        );
+        assert_not!(output_of(process).success);
+    }
 }
 
 #[cfg(test)]
@@ -73,6 +276,46 @@ mod crop {
     use super::*;
     use crate::common::*;
 
+    #[test]
+    fn crop_by_percentage() {
+        use sic_core::image::GenericImageView;
+
+        let process = command(
+            DEFAULT_IN,
+            "cio_crop_percentage.png",
+            "--crop 25% 0% 75% 50%",
+        );
+        assert!(output_of(process).success);
+
+        let image = sic_core::image::open(setup_output_path("cio_crop_percentage.png")).unwrap();
+        assert_eq!(image.dimensions(), (4, 3));
+    }
+
+    #[test]
+    fn crop_by_variables() {
+        use sic_core::image::GenericImageView;
+
+        let process = SicTestCommandBuilder::new()
+            .input_from_resources(DEFAULT_IN)
+            .output_in_target("cio_crop_variables.png")
+            .with_args(["--apply-operations", "crop 0 0 $width $height/2"])
+            .spawn_child();
+        assert!(output_of(process).success);
+
+        let image = sic_core::image::open(setup_output_path("cio_crop_variables.png")).unwrap();
+        assert_eq!(image.dimensions(), (8, 3));
+    }
+
+    #[test]
+    fn crop_by_unknown_variable() {
+        let process = SicTestCommandBuilder::new()
+            .input_from_resources(DEFAULT_IN)
+            .output_in_target("cio_crop_unknown_variable.png")
+            .with_args(["--apply-operations", "crop 0 0 $depth 1"])
+            .spawn_child();
+        assert_not!(output_of(process).success);
+    }
+
     #[test]
     fn crop_simple() {
         let mut process = SicTestCommandBuilder::new()
@@ -171,6 +414,75 @@ mod crop {
     }
 }
 
+#[cfg(test)]
+mod crop_gravity {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn crop_center() {
+        let process = command(DEFAULT_IN, "cio_crop_center.png", "--crop-center 4 4");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn crop_gravity() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_crop_gravity.png",
+            "--crop-gravity bottom-right 4 4",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn crop_center_larger_than_image() {
+        let process = command(DEFAULT_IN, "cio_crop_center_large.png", "--crop-center 9 6");
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn crop_gravity_unknown_gravity() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_crop_gravity_unknown.png",
+            "--crop-gravity up 4 4",
+        );
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn crop_with_gravity_modifier() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_crop_gravity_modifier.png",
+            "--gravity south-east --crop 0 0 4 4",
+        );
+        assert!(output_of(process).success);
+
+        let process = command(
+            DEFAULT_IN,
+            "cio_crop_gravity_reference.png",
+            "--crop-gravity bottom-right 4 4",
+        );
+        assert!(output_of(process).success);
+
+        let modifier = sic_core::image::open(setup_output_path("cio_crop_gravity_modifier.png"));
+        let reference = sic_core::image::open(setup_output_path("cio_crop_gravity_reference.png"));
+        assert_eq!(modifier.unwrap().to_bytes(), reference.unwrap().to_bytes());
+    }
+
+    #[test]
+    fn crop_with_gravity_modifier_out_of_bounds() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_crop_gravity_modifier_oob.png",
+            "--gravity bottom-right --crop 6 0 10 4",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
 #[cfg(test)]
 mod diff {
     use super::*;
@@ -248,127 +560,1085 @@ mod filter3x3 {
 }
 
 #[cfg(test)]
-mod fliph {
+mod chroma_key {
     use super::*;
     use crate::common::*;
 
     #[test]
-    fn fliph() {
-        let mut process = command(DEFAULT_IN, "cio_fliph.png", "--flip-horizontal");
-        let result = process.wait();
-        assert!(result.is_ok());
-        assert!(result.unwrap().success());
+    fn chroma_key() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_chroma_key.png",
+            "--chroma-key rgba(255,0,0,255) 60 20",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn chroma_key_negative_tolerance() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_chroma_key_negative.png",
+            "--chroma-key rgba(255,0,0,255) -1 20",
+        );
+        assert_not!(output_of(process).success);
     }
 }
 
 #[cfg(test)]
-mod flipv {
+mod dither {
     use super::*;
     use crate::common::*;
 
     #[test]
-    fn flipv() {
-        let mut process = command(DEFAULT_IN, "cio_flipv.png", "--flip-vertical");
-        let result = process.wait();
-        assert!(result.is_ok());
-        assert!(result.unwrap().success());
+    fn dither() {
+        let process = command(DEFAULT_IN, "cio_dither.gif", "--dither 4");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn dither_too_many_colors() {
+        let process = command(DEFAULT_IN, "cio_dither_257.png", "--dither 257");
+        assert_not!(output_of(process).success);
     }
 }
 
 #[cfg(test)]
-mod grayscale {
+mod draw_histogram {
     use super::*;
     use crate::common::*;
 
     #[test]
-    fn grayscale() {
-        let mut process = command(DEFAULT_IN, "cio_gs.png", "--grayscale");
-        let result = process.wait();
-        assert!(result.is_ok());
-        assert!(result.unwrap().success());
+    fn draw_histogram() {
+        let process = command(DEFAULT_IN, "cio_draw_histogram.png", "--draw-histogram rgb");
+        assert!(output_of(process).success);
     }
-}
 
-#[cfg(test)]
-mod hue_rotate {
-    use super::*;
-    use crate::common::*;
+    #[test]
+    fn draw_histogram_default_channels() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_draw_histogram_default_channels.png",
+            "--draw-histogram",
+        );
+        assert!(output_of(process).success);
+    }
 
     #[test]
-    fn hue_rotate() {
-        let mut process = command(DEFAULT_IN, "cio_hr.png", "--hue-rotate -90");
-        let result = process.wait();
-        assert!(result.is_ok());
-        assert!(result.unwrap().success());
+    fn draw_histogram_overlay() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_draw_histogram_overlay.png",
+            "--draw-histogram luma overlay",
+        );
+        assert!(output_of(process).success);
     }
 
     #[test]
-    fn hue_rotate_not() {
-        let mut process = command(DEFAULT_IN, "cio_hr2.png", "--hue-rotate -p");
-        let result = process.wait();
-        assert!(result.is_ok());
-        assert_not!(result.unwrap().success());
+    fn draw_histogram_unknown_channels() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_draw_histogram_unknown_channels.png",
+            "--draw-histogram hsv",
+        );
+        assert_not!(output_of(process).success);
     }
 }
 
 #[cfg(test)]
-mod invert {
+mod duotone {
     use super::*;
     use crate::common::*;
 
     #[test]
-    fn invert() {
-        let mut process = command(DEFAULT_IN, "cio_invert.png", "--invert");
-        let result = process.wait();
-        assert!(result.is_ok());
-        assert!(result.unwrap().success());
+    fn duotone() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_duotone.png",
+            "--duotone rgba(0,0,128,255) rgba(255,200,0,255)",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn duotone_single_color() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_duotone_single.png",
+            "--duotone rgba(0,0,128,255)",
+        );
+        assert_not!(output_of(process).success);
     }
 }
 
 #[cfg(test)]
-mod overlay {
+mod equalize {
     use super::*;
     use crate::common::*;
 
     #[test]
-    fn overlay() {
-        let mut process = command(
-            DEFAULT_IN,
-            "cio_overlay.png",
-            &[
-                "--overlay",
-                setup_input_path("3x2_wbaaba.png").to_str().unwrap(),
-                "0",
-                "0",
-            ]
-            .join(" "),
-        );
-        let result = process.wait();
-        assert!(result.is_ok());
-        assert!(result.unwrap().success());
+    fn equalize() {
+        let process = command(DEFAULT_IN, "cio_equalize.png", "--equalize");
+        assert!(output_of(process).success);
     }
 }
 
 #[cfg(test)]
-mod resize {
+mod extend {
     use super::*;
     use crate::common::*;
 
     #[test]
-    fn resize() {
-        let mut process = command(DEFAULT_IN, "cio_resize1.png", "--resize 10 10");
-        let result = process.wait();
-        assert!(result.is_ok());
-        assert!(result.unwrap().success());
+    fn extend() {
+        let process = command(DEFAULT_IN, "cio_extend.png", "--extend 12 10");
+        assert!(output_of(process).success);
     }
 
     #[test]
-    fn resize_not() {
+    fn extend_with_gravity_and_color() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_extend_gravity_color.png",
+            "--extend 12 10 bottom-right rgba(255,255,255,255)",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn extend_with_color() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_extend_color.png",
+            "--extend 12 10 rgba(255,255,255,255)",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn extend_with_background_modifier() {
+        use sic_core::image::{GenericImageView, Rgba};
+
+        let process = command(
+            DEFAULT_IN,
+            "cio_extend_background.png",
+            "--background rgba(0,255,0,255) --extend 12 10 top-left",
+        );
+        assert!(output_of(process).success);
+
+        let image = sic_core::image::open(setup_output_path("cio_extend_background.png")).unwrap();
+        assert_eq!(image.get_pixel(11, 9), Rgba([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn extend_canvas_too_small() {
+        let process = command(DEFAULT_IN, "cio_extend_too_small.png", "--extend 4 4");
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod fisheye {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn fisheye_bulge() {
+        let process = command(DEFAULT_IN, "cio_fisheye_bulge.png", "--fisheye 0.5");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn fisheye_pinch() {
+        let process = command(DEFAULT_IN, "cio_fisheye_pinch.png", "--fisheye -0.5");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn fisheye_strength_out_of_range() {
+        let process = command(DEFAULT_IN, "cio_fisheye_out_of_range.png", "--fisheye 1.5");
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod flatten_checker {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn flatten_checker() {
+        let process = command(
+            "2x3_wrabaa.png",
+            "cio_flatten_checker.png",
+            "--flatten-checker 1",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn flatten_checker_zero_cell_size() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_flatten_checker_zero.png",
+            "--flatten-checker 0",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod fliph {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn fliph() {
+        let mut process = command(DEFAULT_IN, "cio_fliph.png", "--flip-horizontal");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod flipv {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn flipv() {
+        let mut process = command(DEFAULT_IN, "cio_flipv.png", "--flip-vertical");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod grayscale {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn grayscale() {
+        let mut process = command(DEFAULT_IN, "cio_gs.png", "--grayscale");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod grayscale_weighted {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn grayscale_weighted() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_gsw.png",
+            "--grayscale-weighted 0.2126 0.7152 0.0722",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn grayscale_weighted_negative_weight() {
+        let process = command(DEFAULT_IN, "cio_gsw_neg.png", "--grayscale-weighted -1 1 1");
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod gradient {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn gradient_linear_with_angle() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_gradient_linear.png",
+            "--gradient linear rgba(0,0,0,255) rgba(255,255,255,0) -45 --flip-horizontal",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn gradient_radial() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_gradient_radial.png",
+            "--gradient radial rgba(0,0,0,0) rgba(0,0,0,200)",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn gradient_missing_color() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_gradient_missing_color.png",
+            "--gradient linear rgba(0,0,0,255)",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod gradient_map {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn gradient_map() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_gradient_map.png",
+            "--gradient-map stop(0,rgba(0,0,0,255)) stop(0.5,rgba(255,0,0,255)) stop(1,rgba(255,255,0,255)) --flip-horizontal",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn gradient_map_single_stop() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_gradient_map_single.png",
+            "--gradient-map stop(0,rgba(0,0,0,255))",
+        );
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn gradient_map_stop_out_of_range() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_gradient_map_out_of_range.png",
+            "--gradient-map stop(0,rgba(0,0,0,255)) stop(2,rgba(255,255,255,255))",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod stack {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn hstack() {
+        let process = command(
+            "2x3_wrabaa.png",
+            "cio_hstack.png",
+            &[
+                "--hstack",
+                setup_input_path("3x2_wbaaba.png").to_str().unwrap(),
+            ]
+            .join(" "),
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn vstack() {
+        let process = command(
+            "2x3_wrabaa.png",
+            "cio_vstack.png",
+            &[
+                "--vstack",
+                setup_input_path("3x2_wbaaba.png").to_str().unwrap(),
+            ]
+            .join(" "),
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn hstack_invalid_path() {
+        let process = command(
+            "2x3_wrabaa.png",
+            "cio_hstack_invalid_path.png",
+            &["--hstack", setup_input_path("0:1.png").to_str().unwrap()].join(" "),
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod hue_rotate {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn hue_rotate() {
+        let mut process = command(DEFAULT_IN, "cio_hr.png", "--hue-rotate -90");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn hue_rotate_not() {
+        let mut process = command(DEFAULT_IN, "cio_hr2.png", "--hue-rotate -p");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod invert {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn invert() {
+        let mut process = command(DEFAULT_IN, "cio_invert.png", "--invert");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod local_statistics {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn local_mean() {
+        let process = command(DEFAULT_IN, "cio_local_mean.png", "--local-mean 1");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn local_variance() {
+        let process = command(DEFAULT_IN, "cio_local_variance.png", "--local-variance 1");
+        assert!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod lut {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn lut() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_lut.png",
+            &[
+                "--lut",
+                setup_input_path("lut/invert.cube").to_str().unwrap(),
+            ]
+            .join(" "),
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn lut_not_a_cube_file() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_lut_not_a_cube_file.png",
+            &[
+                "--lut",
+                setup_input_path("palette_4x4.png").to_str().unwrap(),
+            ]
+            .join(" "),
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod mirror {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn mirror() {
+        let process = command(DEFAULT_IN, "cio_mirror.png", "--mirror quad");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn mirror_unknown_mode() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_mirror_unknown_mode.png",
+            "--mirror diagonal",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod nine_slice {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn nine_slice() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_nine_slice.png",
+            "--nine-slice 1 1 1 1 20 10",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn nine_slice_with_sampling_filter() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_nine_slice_nearest.png",
+            "--sampling-filter nearest --nine-slice 2 1 2 1 5 5",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn nine_slice_insets_too_large() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_nine_slice_insets_too_large.png",
+            "--nine-slice 5 1 4 1 20 10",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod oil_paint {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn oil_paint() {
+        let process = command(DEFAULT_IN, "cio_oil_paint.png", "--oil-paint 2 16");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn oil_paint_invalid_levels() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_oil_paint_invalid_levels.png",
+            "--oil-paint 2 0",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod opacity {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn opacity() {
+        let process = command(DEFAULT_IN, "cio_opacity.png", "--opacity 0.5");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn opacity_out_of_range() {
+        let process = command(DEFAULT_IN, "cio_opacity_out_of_range.png", "--opacity 1.5");
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod overlay {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn overlay() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_overlay.png",
+            &[
+                "--overlay",
+                setup_input_path("3x2_wbaaba.png").to_str().unwrap(),
+                "0",
+                "0",
+            ]
+            .join(" "),
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn overlay_with_opacity_and_blend_mode() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_overlay_blend_mode.png",
+            &[
+                "--overlay",
+                setup_input_path("3x2_wbaaba.png").to_str().unwrap(),
+                "1",
+                "1",
+                "0.5",
+                "screen",
+            ]
+            .join(" "),
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn overlay_unknown_blend_mode() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_overlay_unknown_blend_mode.png",
+            &[
+                "--overlay",
+                setup_input_path("3x2_wbaaba.png").to_str().unwrap(),
+                "0",
+                "0",
+                "burn",
+            ]
+            .join(" "),
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod smart_crop {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn smart_crop() {
+        let process = command(DEFAULT_IN, "cio_smart_crop.png", "--smart-crop 4 4");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn smart_crop_larger_than_image() {
+        let process = command(DEFAULT_IN, "cio_smart_crop_large.png", "--smart-crop 8 7");
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod stamp {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn stamp() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_stamp.png",
+            &[
+                "--stamp",
+                setup_input_path("3x2_wbaaba.png").to_str().unwrap(),
+                "bottom-right",
+                "4",
+            ]
+            .join(" "),
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn stamp_unknown_gravity() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_stamp_unknown_gravity.png",
+            &[
+                "--stamp",
+                setup_input_path("3x2_wbaaba.png").to_str().unwrap(),
+                "up",
+                "4",
+            ]
+            .join(" "),
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod pad_to_ratio {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn pad_to_ratio() {
+        let process = command(DEFAULT_IN, "cio_pad_to_ratio.png", "--pad-to-ratio 16:9");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn pad_to_ratio_with_color() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_pad_to_ratio_color.png",
+            "--pad-to-ratio 1:1 rgba(255,255,255,255)",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn pad_to_ratio_invalid_ratio() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_pad_to_ratio_invalid.png",
+            "--pad-to-ratio 0:9",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod pixel_upscale {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn pixel_upscale() {
+        let process = command(DEFAULT_IN, "cio_pixel_upscale.png", "--pixel-upscale 4");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn pixel_upscale_scale2x() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_pixel_upscale_scale2x.png",
+            "--pixel-upscale 2 scale2x",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn pixel_upscale_unknown_algorithm() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_pixel_upscale_unknown_algorithm.png",
+            "--pixel-upscale 2 hqx",
+        );
+
+        let ProcessOutput {
+            success, stderr, ..
+        } = output_of(process);
+        assert_not!(success);
+        assert!(stderr.contains("pixel upscale algorithm 'hqx' not found"));
+    }
+
+    #[test]
+    fn pixel_upscale_invalid_factor() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_pixel_upscale_invalid_factor.png",
+            "--pixel-upscale 5",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod redact {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn redact_fill() {
+        use sic_core::image::{GenericImageView, Rgba};
+
+        let process = command(DEFAULT_IN, "cio_redact_fill.png", "--redact rect(2,1,3,2)");
+        assert!(output_of(process).success);
+
+        let image = sic_core::image::open(setup_output_path("cio_redact_fill.png")).unwrap();
+        assert_eq!(image.get_pixel(2, 1), Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(4, 2), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn redact_pixelate() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_redact_pixelate.png",
+            "--redact rect(0,0,8,6) pixelate --flip-horizontal",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn redact_outside_image() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_redact_outside_image.png",
+            "--redact rect(8,0,2,2)",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod radial_blur {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn radial_blur() {
+        let process = command(DEFAULT_IN, "cio_radial_blur.png", "--radial-blur 10 10 0.3");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn radial_blur_strength_out_of_range() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_radial_blur_out_of_range.png",
+            "--radial-blur 10 10 1.5",
+        );
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn radial_blur_negative_center() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_radial_blur_negative_center.png",
+            "--radial-blur -10 10 0.3",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod mask {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn mask() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_mask.png",
+            &[
+                "--mask",
+                setup_input_path("mask_8x6.png").to_str().unwrap(),
+                "--invert",
+            ]
+            .join(" "),
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn mask_of_other_size() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_mask_of_other_size.png",
+            &[
+                "--mask",
+                setup_input_path("palette_4x4.png").to_str().unwrap(),
+                "--invert",
+            ]
+            .join(" "),
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod region {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn region() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_region.png",
+            "--region rect(0,3,8,3) --blur 2 --invert",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn region_resize() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_region_resize.png",
+            "--region rect(0,3,8,3) --resize 4 4",
+        );
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod resize {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn resize() {
+        let mut process = command(DEFAULT_IN, "cio_resize1.png", "--resize 10 10");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn resize_by_percentage() {
+        use sic_core::image::GenericImageView;
+
+        let process = command(DEFAULT_IN, "cio_resize_percentage.png", "--resize 50% 150%");
+        assert!(output_of(process).success);
+
+        let image = sic_core::image::open(setup_output_path("cio_resize_percentage.png")).unwrap();
+        assert_eq!(image.dimensions(), (4, 9));
+    }
+
+    #[test]
+    fn resize_by_expressions() {
+        use sic_core::image::GenericImageView;
+
+        let process = SicTestCommandBuilder::new()
+            .input_from_resources(DEFAULT_IN)
+            .output_in_target("cio_resize_expressions.png")
+            .with_args(["--apply-operations", "resize ($width/2) min($height, 2)"])
+            .spawn_child();
+        assert!(output_of(process).success);
+
+        let image = sic_core::image::open(setup_output_path("cio_resize_expressions.png")).unwrap();
+        assert_eq!(image.dimensions(), (4, 2));
+    }
+
+    #[test]
+    fn resize_with_bindings() {
+        use sic_core::image::GenericImageView;
+
+        let process = SicTestCommandBuilder::new()
+            .input_from_resources(DEFAULT_IN)
+            .output_in_target("cio_resize_bindings.png")
+            .with_args(["--apply-operations", "let size = 3;\nresize $size $size"])
+            .spawn_child();
+        assert!(output_of(process).success);
+
+        let image = sic_core::image::open(setup_output_path("cio_resize_bindings.png")).unwrap();
+        assert_eq!(image.dimensions(), (3, 3));
+    }
+
+    #[test]
+    fn resize_with_undefined_binding() {
+        let process = SicTestCommandBuilder::new()
+            .input_from_resources(DEFAULT_IN)
+            .output_in_target("cio_resize_undefined_binding.png")
+            .with_args(["--apply-operations", "resize $size $size"])
+            .spawn_child();
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn resize_by_negative_percentage() {
+        let process = command(DEFAULT_IN, "cio_resize_negative.png", "--resize -50% 50%");
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn resize_not() {
         let mut process = command(DEFAULT_IN, "cio_resize2.png", "--resize 10 p");
         let result = process.wait();
         assert!(result.is_ok());
         assert_not!(result.unwrap().success());
     }
+
+    #[test]
+    fn resize_wildcard() {
+        use sic_core::image::GenericImageView;
+
+        let process = command(DEFAULT_IN, "cio_resize_wildcard.png", "--resize 10 0");
+        assert!(output_of(process).success);
+
+        let image = sic_core::image::open(setup_output_path("cio_resize_wildcard.png")).unwrap();
+        assert_eq!(image.dimensions().0, 10);
+    }
+
+    #[test]
+    fn resize_both_wildcards() {
+        let process = command(DEFAULT_IN, "cio_resize_wildcards.png", "--resize 0 0");
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn resize_with_custom_sampling_filters() {
+        for filter in &["box", "hermite", "mitchell"] {
+            let process = command(
+                DEFAULT_IN,
+                &format!("cio_resize_{}.png", filter),
+                &format!("--sampling-filter {} --resize 10 10", filter),
+            );
+            assert!(output_of(process).success);
+        }
+    }
+
+    #[test]
+    fn resize_with_sampling_filter_for_single_occurrence() {
+        let process = SicTestCommandBuilder::new()
+            .input_from_resources(DEFAULT_IN)
+            .output_in_target("cio_resize_occurrence_script.png")
+            .with_args([
+                "--apply-operations",
+                "set resize.1 sampling-filter nearest; resize 16 12; resize 5 4",
+            ])
+            .spawn_child();
+        assert!(output_of(process).success);
+
+        let process = command(
+            DEFAULT_IN,
+            "cio_resize_occurrence_args.png",
+            "--resize 16 12 --sampling-filter nearest --resize 5 4",
+        );
+        assert!(output_of(process).success);
+
+        let script = sic_core::image::open(setup_output_path("cio_resize_occurrence_script.png"));
+        let args = sic_core::image::open(setup_output_path("cio_resize_occurrence_args.png"));
+        assert_eq!(script.unwrap().to_bytes(), args.unwrap().to_bytes());
+    }
+
+    #[test]
+    fn resize_with_sampling_filter_for_unknown_operation() {
+        let process = SicTestCommandBuilder::new()
+            .input_from_resources(DEFAULT_IN)
+            .output_in_target("cio_resize_occurrence_unknown.png")
+            .with_args([
+                "--apply-operations",
+                "set resise.0 sampling-filter nearest; resize 16 12",
+            ])
+            .spawn_child();
+        assert_not!(output_of(process).success);
+    }
+
+    #[test]
+    fn resize_with_sampling_filter_for_unused_occurrence() {
+        let process = SicTestCommandBuilder::new()
+            .input_from_resources(DEFAULT_IN)
+            .output_in_target("cio_resize_occurrence_unused.png")
+            .with_args([
+                "--apply-operations",
+                "set resize.5 sampling-filter nearest; resize 2 2",
+            ])
+            .spawn_child();
+
+        let ProcessOutput {
+            success, stderr, ..
+        } = output_of(process);
+        assert_not!(success);
+        assert!(stderr.contains("resize.5"));
+    }
 }
 
 #[cfg(test)]
@@ -413,6 +1683,76 @@ mod rotate270 {
     }
 }
 
+#[cfg(test)]
+mod sharpen {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn sharpen_default_amount() {
+        let process = command(DEFAULT_IN, "cio_sharpen_default.png", "--sharpen");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn sharpen_amount() {
+        let process = command(DEFAULT_IN, "cio_sharpen.png", "--sharpen 0.5 --sharpen");
+        assert!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod tonemap {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn tonemap() {
+        let process = command(DEFAULT_IN, "cio_tonemap.png", "--tonemap reinhard");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn tonemap_with_exposure() {
+        let process = command(
+            DEFAULT_IN,
+            "cio_tonemap_exposure.png",
+            "--tonemap aces -1.5",
+        );
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn tonemap_unknown_operator() {
+        let process = command(DEFAULT_IN, "cio_tonemap_unknown.png", "--tonemap filmic");
+        assert_not!(output_of(process).success);
+    }
+}
+
+#[cfg(test)]
+mod trim {
+    use super::*;
+    use crate::common::*;
+
+    #[test]
+    fn trim() {
+        let process = command(DEFAULT_IN, "cio_trim.png", "--trim");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn trim_with_tolerance() {
+        let process = command(DEFAULT_IN, "cio_trim_tolerance.png", "--trim 10.5");
+        assert!(output_of(process).success);
+    }
+
+    #[test]
+    fn trim_negative_tolerance() {
+        let process = command(DEFAULT_IN, "cio_trim_negative.png", "--trim -1");
+        assert_not!(output_of(process).success);
+    }
+}
+
 #[cfg(test)]
 mod unsharpen {
     use super::*;
@@ -445,20 +1785,52 @@ mod mixed {
         let mut process = command(
             DEFAULT_IN,
             "img_op_arg_mixed_all.png",
-            "--blur 1 \
+            "--alpha-add \
+             --alpha-threshold 10 \
+             --alpha-premultiply \
+             --auto-enhance \
+             --blur 1 \
+             --border 1 rgba(0,0,0,255) \
              --brighten 2 \
+             --channel-swap r b \
+             --channel-extract g \
+             --chroma-key rgba(0,0,0,255) 10 5 \
+             --convolve 3 1 2 1 2 4 2 1 2 1 \
              --contrast 3 \
+             --crop-gravity top-left 6 5 \
+             --crop-center 4 4 \
              --crop 0 0 2 2 \
              --filter3x3 0 1 2 3 4 5 6 7 8 \
+             --fisheye 0.3 \
+             --dither 8 \
+             --duotone rgba(0,0,0,255) rgba(255,255,255,255) \
+             --flatten-checker 4 \
              --flip-horizontal \
              --flip-vertical \
+             --gradient-map stop(0,rgba(0,0,0,255)) stop(1,rgba(255,255,255,255)) \
              --grayscale \
+             --grayscale-weighted 0.299 0.587 0.114 \
              --hue-rotate -90 \
              --invert \
+             --mirror horizontal \
+             --nine-slice 1 1 1 1 12 12 \
+             --oil-paint 1 8 \
+             --opacity 0.8 \
+             --pad-to-ratio 4:3 rgba(0,0,0,255) \
+             --alpha-remove \
+             --equalize \
+             --extend 20 20 top rgba(0,0,0,255) \
+             --pixel-upscale 2 \
+             --radial-blur 5 5 0.2 \
              --resize 10 10 \
              --rotate90 \
              --rotate180 \
              --rotate270 \
+             --sharpen \
+             --sharpen 0.5 \
+             --smart-crop 8 8 \
+             --trim \
+             --trim 1 \
              --unsharpen 1.5 1",
         );
 
@@ -1,7 +1,7 @@
 #[macro_use]
 pub mod common;
 
-use common::{SicTestCommandBuilder, DEFAULT_IN};
+use common::{setup_output_path, SicTestCommandBuilder, DEFAULT_IN};
 
 #[test]
 fn script_from_file_ok() {
@@ -17,6 +17,119 @@ fn script_from_file_ok() {
     assert!(result.success());
 }
 
+#[test]
+fn apply_operations_file() {
+    let script_file = &[env!("CARGO_MANIFEST_DIR"), "/resources/script/emboss.sic"].concat();
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("cio_apply_operations_file.png")
+        .with_args(["--apply-operations-file", script_file])
+        .spawn_child();
+
+    let result = process.wait().unwrap();
+    assert!(result.success());
+}
+
+#[test]
+fn apply_operations_file_shorthand() {
+    use sic_core::image::GenericImageView;
+
+    let script = &[
+        "@",
+        env!("CARGO_MANIFEST_DIR"),
+        "/resources/script/emboss_resized.sic",
+    ]
+    .concat();
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("cio_apply_operations_file_shorthand.png")
+        .with_args(["--apply-operations", script])
+        .spawn_child();
+
+    let result = process.wait().unwrap();
+    assert!(result.success());
+
+    let image = sic_core::image::open(setup_output_path("cio_apply_operations_file_shorthand.png"))
+        .unwrap();
+    assert_eq!(image.dimensions(), (3, 3));
+}
+
+#[test]
+fn apply_operations_file_error_names_file() {
+    let script_file = setup_output_path("cio_invalid_script.sic");
+    std::fs::write(&script_file, "blur 1;\nblur;").unwrap();
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("cio_apply_operations_file_error.png")
+        .with_args(["--apply-operations-file", script_file.to_str().unwrap()])
+        .spawn_child();
+
+    let output = process.wait_with_output().unwrap();
+    assert_not!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("unable to parse script file"));
+    assert!(stderr.contains("cio_invalid_script.sic"));
+}
+
+#[test]
+fn apply_operations_file_error_points_at_argument() {
+    let script_file = setup_output_path("cio_invalid_argument_script.sic");
+    std::fs::write(&script_file, "blur 1;\nborder 1 rgba(300, 0, 0, 255);").unwrap();
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("cio_apply_operations_file_argument_error.png")
+        .with_args(["--apply-operations-file", script_file.to_str().unwrap()])
+        .spawn_child();
+
+    let output = process.wait_with_output().unwrap();
+    assert_not!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("argument '300' of operation 'border' at line 2, column 15"));
+    assert!(stderr.contains("2 | border 1 rgba(300, 0, 0, 255);\n  |               ^^^"));
+}
+
+#[test]
+fn script_from_file_with_include() {
+    use sic_core::image::GenericImageView;
+
+    let script_file = &[
+        env!("CARGO_MANIFEST_DIR"),
+        "/resources/script/emboss_resized.sic",
+    ]
+    .concat();
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("cio_script_from_file__include.png")
+        .with_args(["--operations-script", script_file])
+        .spawn_child();
+
+    let result = process.wait().unwrap();
+    assert!(result.success());
+
+    let image =
+        sic_core::image::open(setup_output_path("cio_script_from_file__include.png")).unwrap();
+    assert_eq!(image.dimensions(), (3, 3));
+}
+
+#[test]
+fn script_with_include_where_file_not_found() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("cio_script_from_file__include_not_found.png")
+        .with_args(["--apply-operations", "include '_.sic'; blur 1"])
+        .spawn_child();
+
+    let result = process.wait().unwrap();
+    assert_not!(result.success());
+}
+
 #[test]
 fn script_from_file_where_file_not_found() {
     let script = r#""_.sic""#;
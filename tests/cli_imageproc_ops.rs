@@ -14,9 +14,43 @@ mod tests {
     #[parameterized(
         ops = {
             r#"draw-text "example" coord(0,1) rgba(0,0,0,255) size(24) font("%font%");"#,
+            r#"draw-text "example" coord(4,4) rgba(255,255,255,255) size(24) font("%font%") outline(2, rgba(0,0,0,255)) shadow(2, 3, rgba(0,0,0,128)) spacing(1, 0);"#,
+            r#"draw-text "example" coord(4,40) rgba(255,255,255,160) size(32) font("%font%") angle(-30);"#,
+            r#"draw-text "a longer example which is wrapped\nand centered" coord(10,20) rgba(255,255,255,255) size(24) font("%font%") wrap(200) align('center');"#,
+            "adaptive-threshold 15;",
+            "set adaptive-threshold-method gaussian; adaptive-threshold 15;",
+            "clahe 64 2.0;",
+            "median 1;",
+            "noise salt-pepper 0.05 42;",
+            "adaptive-threshold 15; morph-open 1; morph-close 1; dilate 1; erode 1;",
+            "perspective 40 10 400 0 447 217 0 200;",
+            "adaptive-threshold 15; label-components 4;",
+            "adaptive-threshold 15; distance-transform;",
+            "draw-corners 40 rgba(0,255,0,255);",
+            "draw-rect rect(20, 20, 200, 80) rgba(255,0,0,255) stroke 4; draw-rect rect(40, 120, 300, 60) rgba(255,255,0,96) filled;",
+            "draw-line coord(10, 10) coord(400, 200) rgba(255,255,0,255) 3; draw-arrow coord(20, 200) coord(220, 60) rgba(255,0,0,255) 4;",
+            "draw-ellipse coord(200, 120) 150 60 rgba(255,0,0,255) stroke 3; draw-ellipse coord(100, 100) 40 40 rgba(0,0,255,96) filled;",
+            "draw-polygon coord(200, 20) coord(240, 220) coord(100, 90) coord(300, 90) coord(160, 220) rgba(0,255,0,160) filled;",
         },
         output_file = {
-            "imageproc_ops_draw_text_apply_operations"
+            "imageproc_ops_draw_text_apply_operations",
+            "imageproc_ops_draw_text_outline_shadow_spacing_apply_operations",
+            "imageproc_ops_draw_text_angle_apply_operations",
+            "imageproc_ops_draw_text_wrap_align_apply_operations",
+            "imageproc_ops_adaptive_threshold_apply_operations",
+            "imageproc_ops_adaptive_threshold_gaussian_apply_operations",
+            "imageproc_ops_clahe_apply_operations",
+            "imageproc_ops_median_apply_operations",
+            "imageproc_ops_noise_apply_operations",
+            "imageproc_ops_morphology_apply_operations",
+            "imageproc_ops_perspective_apply_operations",
+            "imageproc_ops_label_components_apply_operations",
+            "imageproc_ops_distance_transform_apply_operations",
+            "imageproc_ops_draw_corners_apply_operations",
+            "imageproc_ops_draw_rect_apply_operations",
+            "imageproc_ops_draw_line_arrow_apply_operations",
+            "imageproc_ops_draw_ellipse_apply_operations",
+            "imageproc_ops_draw_polygon_apply_operations",
         },
     )]
     fn check_imageproc_ops_with_script(ops: &str, output_file: &str) {
@@ -29,16 +63,14 @@ mod tests {
         let out = format!("{}.png", output_file);
 
         let ops = ops.replace("%font%", font_file);
-        let mut process = SicTestCommandBuilder::new()
+        let process = SicTestCommandBuilder::new()
             .input_from_resources("unsplash_763569_cropped.jpg")
             .output_in_target(&out)
             .with_args(&["--apply-operations", &ops])
             .with_feature("imageproc-ops")
             .spawn_child();
 
-        let result = process.wait();
-        assert!(result.is_ok());
-        assert!(result.unwrap().success());
+        assert!(output_of(process).success);
     }
 
     #[parameterized(
@@ -46,16 +78,115 @@ mod tests {
             &["--draw-text", "example", "coord(0,1)", "rgba(0,0,0,255)", "size(24)", "font('▲')"],
             &["--draw-text", "example", "coord(0,1)", "rgba(0,0,0,255)", "size(24)", "font(\"▲\")"],
             &["--draw-text", "example", "coord(0,1)", "rgba(0,0,0,255)", "size(24)", "font(\"▲\')"],
+            &["--adaptive-threshold-method", "gaussian", "--adaptive-threshold", "15"],
+            &["--adaptive-threshold", "16"],
+            &["--clahe", "64", "3"],
+            &["--clahe", "0", "3"],
+            &["--draw-text", "example", "coord(4,4)", "rgba(255,255,255,255)", "size(24)", "font('▲')", "outline(2, rgba(0,0,0,255))", "shadow(2, 3, rgba(0,0,0,128))", "--flip-horizontal"],
+            &["--draw-text", "example", "coord(4,4)", "rgba(255,255,255,255)", "size(24)", "font('▲')", "outline(0, rgba(0,0,0,255))"],
+            &["--median", "1"],
+            &["--median", "-1"],
+            &["--noise", "gaussian", "0.1", "--flip-horizontal"],
+            &["--noise", "gaussian", "0.1", "7"],
+            &["--noise", "gaussian", "2"],
+            &["--adaptive-threshold", "15", "--morph-open", "1", "--morph-close", "1"],
+            &["--dilate", "2", "--erode", "2"],
+            &["--erode", "-1"],
+            &["--perspective", "40", "10", "400", "0", "447", "217", "0", "200"],
+            &["--perspective", "-20", "0", "400", "-10", "447", "217", "0", "200", "--flip-horizontal"],
+            &["--perspective", "0", "0", "100", "0", "200", "0", "300", "0"],
+            &["--draw-rect", "rect(20, 20, 200, 80)", "rgba(255,0,0,255)", "stroke", "4", "--flip-horizontal"],
+            &["--draw-rect", "rect(400, 150, 200, 200)", "rgba(255,255,0,96)", "filled"],
+            &["--draw-rect", "rect(20, 20, 0, 80)", "rgba(255,0,0,255)"],
+            &["--draw-line", "coord(10, 10)", "coord(400, 200)", "rgba(255,255,0,255)", "3", "--draw-arrow", "coord(20, 200)", "coord(220, 60)", "rgba(255,0,0,255)", "4"],
+            &["--draw-arrow", "coord(20, 200)", "coord(20, 200)", "rgba(255,0,0,255)", "4"],
+            &["--draw-ellipse", "coord(200, 120)", "150", "60", "rgba(255,0,0,255)", "stroke", "3", "--flip-horizontal"],
+            &["--draw-ellipse", "coord(200, 120)", "0", "60", "rgba(255,0,0,255)"],
+            &["--draw-polygon", "coord(20, 20)", "coord(200, 40)", "coord(120, 200)", "coord(40, 160)", "rgba(255,0,0,255)", "stroke", "3", "--flip-horizontal"],
+            &["--draw-polygon", "coord(20, 20)", "coord(200, 40)", "rgba(255,0,0,255)"],
+            &["--draw-text", r"wrapped\nexample", "coord(4,4)", "rgba(255,255,255,255)", "size(24)", "font('▲')", "wrap(100)", "align('right')", "--flip-horizontal"],
+            &["--draw-text", "example", "coord(4,4)", "rgba(255,255,255,255)", "size(24)", "font('▲')", "wrap(0)"],
+            &["--adaptive-threshold", "15", "--label-components", "--flip-horizontal"],
+            &["--label-components", "6"],
+            &["--adaptive-threshold", "15", "--distance-transform", "--flip-horizontal"],
+            &["--draw-corners", "40", "--flip-horizontal"],
+            &["--draw-corners", "256"],
         },
         output_file = {
             "imageproc_ops_draw_text_cli_arg_0_ok",
             "imageproc_ops_draw_text_cli_arg_1_ok",
             "imageproc_ops_draw_text_cli_arg_2_err",
+            "imageproc_ops_adaptive_threshold_cli_arg_3_ok",
+            "imageproc_ops_adaptive_threshold_cli_arg_4_err",
+            "imageproc_ops_clahe_cli_arg_5_ok",
+            "imageproc_ops_clahe_cli_arg_6_err",
+            "imageproc_ops_draw_text_cli_arg_7_ok",
+            "imageproc_ops_draw_text_cli_arg_8_err",
+            "imageproc_ops_median_cli_arg_9_ok",
+            "imageproc_ops_median_cli_arg_10_err",
+            "imageproc_ops_noise_cli_arg_11_ok",
+            "imageproc_ops_noise_cli_arg_12_ok",
+            "imageproc_ops_noise_cli_arg_13_err",
+            "imageproc_ops_morphology_cli_arg_14_ok",
+            "imageproc_ops_morphology_cli_arg_15_ok",
+            "imageproc_ops_morphology_cli_arg_16_err",
+            "imageproc_ops_perspective_cli_arg_17_ok",
+            "imageproc_ops_perspective_cli_arg_18_ok",
+            "imageproc_ops_perspective_cli_arg_19_err",
+            "imageproc_ops_draw_rect_cli_arg_20_ok",
+            "imageproc_ops_draw_rect_cli_arg_21_ok",
+            "imageproc_ops_draw_rect_cli_arg_22_err",
+            "imageproc_ops_draw_line_cli_arg_23_ok",
+            "imageproc_ops_draw_arrow_cli_arg_24_err",
+            "imageproc_ops_draw_ellipse_cli_arg_25_ok",
+            "imageproc_ops_draw_ellipse_cli_arg_26_err",
+            "imageproc_ops_draw_polygon_cli_arg_27_ok",
+            "imageproc_ops_draw_polygon_cli_arg_28_err",
+            "imageproc_ops_draw_text_cli_arg_29_ok",
+            "imageproc_ops_draw_text_cli_arg_30_err",
+            "imageproc_ops_label_components_cli_arg_31_ok",
+            "imageproc_ops_label_components_cli_arg_32_err",
+            "imageproc_ops_distance_transform_cli_arg_33_ok",
+            "imageproc_ops_draw_corners_cli_arg_34_ok",
+            "imageproc_ops_draw_corners_cli_arg_35_err",
         },
         ok = {
             true,
             true,
             false,
+            true,
+            false,
+            true,
+            false,
+            true,
+            false,
+            true,
+            false,
+            true,
+            true,
+            false,
+            true,
+            true,
+            false,
+            true,
+            true,
+            false,
+            true,
+            true,
+            false,
+            true,
+            false,
+            true,
+            false,
+            true,
+            false,
+            true,
+            false,
+            true,
+            false,
+            true,
+            true,
+            false,
         }
     )]
     fn check_imageproc_ops_with_cli_args(ops: &[&str], output_file: &str, ok: bool) {
@@ -74,21 +205,21 @@ mod tests {
 
         let out = format!("{}.png", output_file);
 
-        let mut process = SicTestCommandBuilder::new()
+        let process = SicTestCommandBuilder::new()
             .input_from_resources("unsplash_763569_cropped.jpg")
             .output_in_target(&out)
             .with_args(&ops)
             .with_feature("imageproc-ops")
             .spawn_child();
 
-        let result = process.wait().unwrap();
+        let result = output_of(process);
 
         let _ = out;
 
         if ok {
-            assert!(result.success());
+            assert!(result.success);
         } else {
-            assert!(!result.success());
+            assert!(!result.success);
         }
     }
 }
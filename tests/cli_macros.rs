@@ -0,0 +1,95 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use sic_core::image::GenericImageView;
+
+fn macros_file() -> String {
+    setup_input_path("script/macros.sic")
+        .to_string_lossy()
+        .to_string()
+}
+
+#[test]
+fn macro_call_from_script() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("macro_call_from_script.png")
+        .with_args([
+            "--macros",
+            macros_file().as_str(),
+            "--apply-operations",
+            "mirrored_thumb(3)",
+        ])
+        .spawn_child();
+    assert!(process.wait().unwrap().success());
+
+    let image = sic_core::image::open(setup_output_path("macro_call_from_script.png")).unwrap();
+    assert_eq!(image.dimensions(), (3, 3));
+}
+
+#[test]
+fn macro_defined_in_script() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("macro_defined_in_script.png")
+        .with_args([
+            "--apply-operations",
+            "def thumb(w) { resize $w $w }\nthumb(2)",
+        ])
+        .spawn_child();
+    assert!(process.wait().unwrap().success());
+
+    let image = sic_core::image::open(setup_output_path("macro_defined_in_script.png")).unwrap();
+    assert_eq!(image.dimensions(), (2, 2));
+}
+
+#[test]
+fn macro_call_from_cli_operations() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("macro_call_from_cli_operations.png")
+        .with_args([
+            "--macros",
+            macros_file().as_str(),
+            "--flip-vertical",
+            "--macro",
+            "thumb(4)",
+            "--crop",
+            "0",
+            "0",
+            "2",
+            "3",
+        ])
+        .spawn_child();
+    assert!(process.wait().unwrap().success());
+
+    let image =
+        sic_core::image::open(setup_output_path("macro_call_from_cli_operations.png")).unwrap();
+    assert_eq!(image.dimensions(), (2, 3));
+}
+
+#[test]
+fn macro_call_without_macros_file() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("macro_call_without_macros_file.png")
+        .with_args(["--macro", "thumb(4)"])
+        .spawn_child();
+    assert_not!(process.wait().unwrap().success());
+}
+
+#[test]
+fn macro_call_undefined() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("macro_call_undefined.png")
+        .with_args([
+            "--macros",
+            macros_file().as_str(),
+            "--macro",
+            "undefined(4)",
+        ])
+        .spawn_child();
+    assert_not!(process.wait().unwrap().success());
+}
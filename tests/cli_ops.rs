@@ -0,0 +1,97 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+#[test]
+fn ops_list() {
+    let process = SicTestCommandBuilder::new()
+        .with_args(["ops", "list"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+
+    for syntax in [
+        "blur <sigma:fp>",
+        "invert",
+        "set sampling-filter <filter:value>",
+    ]
+    .iter()
+    {
+        assert!(stdout.lines().any(|line| line.starts_with(syntax)));
+    }
+}
+
+#[test]
+fn ops_list_json() {
+    let process = SicTestCommandBuilder::new()
+        .with_args(["ops", "list", "--json"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+
+    let operations: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let blur = operations
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|operation| operation["name"] == "blur")
+        .unwrap();
+
+    assert_eq!(blur["kind"], "operation");
+    assert_eq!(blur["arguments"][0]["type"], "fp");
+}
+
+#[test]
+fn ops_describe() {
+    let process = SicTestCommandBuilder::new()
+        .with_args(["ops", "describe", "opacity"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert!(stdout.starts_with("opacity (operation)"));
+    assert!(stdout.contains("0..=1"));
+}
+
+#[test]
+fn ops_describe_json() {
+    let process = SicTestCommandBuilder::new()
+        .with_args(["ops", "describe", "gradient-map", "--json"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+
+    let operation: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(operation["name"], "gradient-map");
+    assert_eq!(operation["variadic"], true);
+}
+
+#[test]
+fn ops_invalid_arguments() {
+    let cases: &[&[&str]] = &[
+        &["ops"],
+        &["ops", "describe"],
+        &["ops", "describe", "blurry"],
+    ];
+
+    for args in cases {
+        let process = SicTestCommandBuilder::new()
+            .with_args(args.iter())
+            .spawn_child();
+
+        let ProcessOutput { success, .. } = output_of(process);
+        assert_not!(success);
+    }
+}
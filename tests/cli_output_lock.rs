@@ -0,0 +1,119 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use fs2::FileExt;
+use sic_core::image;
+use std::fs::OpenOptions;
+use std::time::Duration;
+
+/// Locks the output as sic does: by its lock file, next to it.
+fn lock_output(name: &str, contents: Option<&[u8]>) -> std::fs::File {
+    if let Some(contents) = contents {
+        std::fs::write(setup_output_path(name), contents).unwrap();
+    }
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(setup_output_path(&format!(".{}.lock", name)))
+        .unwrap();
+    file.lock_exclusive().unwrap();
+    file
+}
+
+#[test]
+fn skip_locked_output() {
+    let contents = b"written by another process";
+    let lock = lock_output("output_lock_skip.png", Some(contents));
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("output_lock_skip.png")
+        .with_args(["--output-lock", "skip"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stderr, ..
+    } = output_of(process);
+    assert!(success);
+    assert!(stderr.contains("locked by another process"));
+    assert_eq!(
+        std::fs::read(setup_output_path("output_lock_skip.png")).unwrap(),
+        contents
+    );
+
+    lock.unlock().unwrap();
+}
+
+#[test]
+fn skip_locked_new_output() {
+    let _ = std::fs::remove_file(setup_output_path("output_lock_skip_new.png"));
+    let lock = lock_output("output_lock_skip_new.png", None);
+
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("output_lock_skip_new.png")
+        .with_args(["--output-lock", "skip"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stderr, ..
+    } = output_of(process);
+    assert!(success);
+    assert!(stderr.contains("locked by another process"));
+    assert_not!(setup_output_path("output_lock_skip_new.png").exists());
+
+    lock.unlock().unwrap();
+}
+
+#[test]
+fn wait_for_locked_output() {
+    let lock = lock_output(
+        "output_lock_wait.png",
+        Some(&[0xAA; 64 * 1024]), // larger than the output image, which should be truncated
+    );
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("output_lock_wait.png")
+        .spawn_child();
+
+    std::thread::sleep(Duration::from_millis(500));
+    assert!(process.try_wait().unwrap().is_none());
+
+    lock.unlock().unwrap();
+
+    let ProcessOutput { success, .. } = output_of(process);
+    assert!(success);
+    assert!(image::open(setup_output_path("output_lock_wait.png")).is_ok());
+}
+
+#[test]
+fn unlocked_output_is_written() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("output_lock_unlocked.png")
+        .with_args(["--output-lock", "skip"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stderr, ..
+    } = output_of(process);
+    assert!(success);
+    assert_not!(stderr.contains("locked by another process"));
+    assert!(image::open(setup_output_path("output_lock_unlocked.png")).is_ok());
+}
+
+#[test]
+fn invalid_output_lock() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("output_lock_invalid.png")
+        .with_args(["--output-lock", "never"])
+        .spawn_child();
+
+    let ProcessOutput { success, .. } = output_of(process);
+    assert_not!(success);
+}
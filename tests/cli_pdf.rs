@@ -0,0 +1,64 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+fn media_boxes(path: &str) -> Vec<String> {
+    let bytes = std::fs::read(setup_output_path(path)).unwrap();
+    let document = String::from_utf8_lossy(&bytes);
+
+    document
+        .match_indices("/MediaBox [")
+        .map(|(start, _)| {
+            let start = start + "/MediaBox [".len();
+            let end = start + document[start..].find(']').unwrap();
+            document[start..end].to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn image_to_pdf() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("rainbow_8x6.bmp")
+        .output_in_target("pdf_single_page.pdf")
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let bytes = std::fs::read(setup_output_path("pdf_single_page.pdf")).unwrap();
+    assert!(bytes.starts_with(b"%PDF-"));
+    assert_eq!(media_boxes("pdf_single_page.pdf"), vec!["0 0 8 6"]);
+}
+
+#[test]
+fn forced_pdf_output_format() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("rainbow_8x6.bmp")
+        .output_in_target("pdf_forced_format.out")
+        .with_args(["--output-format", "pdf", "--resize", "4", "3"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    assert_eq!(media_boxes("pdf_forced_format.out"), vec!["0 0 4 3"]);
+}
+
+#[test]
+fn glob_to_single_document() {
+    const OUT: &str = "globtest/pdf_pages.pdf";
+
+    let mut process = SicTestCommandBuilder::new()
+        .glob_input_from_resources("[23]x[23]_*.bmp")
+        .glob_output_in_target(OUT)
+        .with_args(["--output-format", "pdf"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    // pages are in the order of the input paths
+    assert_eq!(
+        media_boxes(OUT),
+        vec!["0 0 2 2", "0 0 2 3", "0 0 2 3", "0 0 3 2", "0 0 3 2"]
+    );
+}
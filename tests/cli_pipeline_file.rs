@@ -0,0 +1,96 @@
+#[macro_use]
+extern crate parameterized;
+
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+fn apply_script(script: &str, output: &str) -> bool {
+    SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target(output)
+        .with_args(["--apply-operations", script])
+        .spawn_child()
+        .wait()
+        .unwrap()
+        .success()
+}
+
+#[parameterized(extension = { "json", "toml" })]
+fn export_and_apply_pipeline(extension: &str) {
+    let script = "set sampling-filter nearest; resize 16 12; crop 0 0 50% $height/2; \
+                  border 1 rgba(255, 0, 0, 255); flip-horizontal";
+    let pipeline = setup_output_path(&format!("export_and_apply_pipeline.{}", extension));
+    let _ = std::fs::remove_file(&pipeline);
+
+    let exported = SicTestCommandBuilder::new()
+        .with_args(["--apply-operations", script, "--export-pipeline"])
+        .with_args([pipeline.as_os_str()])
+        .spawn_child()
+        .wait()
+        .unwrap();
+    assert!(exported.success());
+    assert!(pipeline.exists());
+
+    let applied = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target(format!("export_and_apply_pipeline_{}.png", extension))
+        .with_args(["--pipeline"])
+        .with_args([pipeline.as_os_str()])
+        .spawn_child()
+        .wait()
+        .unwrap();
+    assert!(applied.success());
+
+    let expected_output = format!("export_and_apply_pipeline_{}_expected.png", extension);
+    assert!(apply_script(script, &expected_output));
+
+    let applied = sic_core::image::open(setup_output_path(&format!(
+        "export_and_apply_pipeline_{}.png",
+        extension
+    )))
+    .unwrap();
+    let expected = sic_core::image::open(setup_output_path(&expected_output)).unwrap();
+    assert_eq!(applied.to_rgba(), expected.to_rgba());
+}
+
+#[test]
+fn pipeline_file_invalid() {
+    let pipeline = setup_output_path("pipeline_file_invalid.json");
+    std::fs::write(
+        &pipeline,
+        r#"{"instructions": [{"operation": {"resize": [10]}}]}"#,
+    )
+    .unwrap();
+
+    let output = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("pipeline_file_invalid.png")
+        .with_args(["--pipeline"])
+        .with_args([pipeline.as_os_str()])
+        .spawn_child()
+        .wait_with_output()
+        .unwrap();
+
+    assert_not!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("pipeline_file_invalid.json"));
+}
+
+#[test]
+fn pipeline_file_conflicts_with_script() {
+    let result = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("pipeline_file_conflicts_with_script.png")
+        .with_args([
+            "--pipeline",
+            "pipeline.json",
+            "--apply-operations",
+            "invert",
+        ])
+        .spawn_child()
+        .wait()
+        .unwrap();
+
+    assert_not!(result.success());
+}
@@ -0,0 +1,50 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+#[test]
+fn psd_to_png() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("rgba_4x3.psd")
+        .output_in_target("psd_to_png.png")
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = image::open(setup_output_path("psd_to_png.png")).unwrap();
+    assert_eq!(output.dimensions(), (4, 3));
+    assert_eq!(output.get_pixel(3, 0), image::Rgba([240, 0, 200, 128]));
+}
+
+#[test]
+fn psd_with_image_operations() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("rgba_4x3.psd")
+        .output_in_target("psd_flip_horizontal.png")
+        .with_args(["--flip-horizontal"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = image::open(setup_output_path("psd_flip_horizontal.png")).unwrap();
+    assert_eq!(output.get_pixel(0, 0), image::Rgba([240, 0, 200, 128]));
+}
+
+#[test]
+fn glob_input_includes_psd() {
+    const OUT: &str = "globtest/psd";
+
+    let mut process = SicTestCommandBuilder::new()
+        .glob_input_from_resources("*.psd")
+        .glob_output_in_target(OUT)
+        .with_args(["--output-format", "png"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = image::open(setup_output_path(OUT).join("rgba_4x3.png")).unwrap();
+    assert_eq!(output.dimensions(), (4, 3));
+}
@@ -0,0 +1,104 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use sic_core::image;
+use std::io::Write;
+use std::process::Stdio;
+
+#[test]
+fn write_raw_rgba8() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("raw_rgba8_out.rgba")
+        .with_args(["--output-format", "raw-rgba8"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let expected = image::open(setup_input_path(DEFAULT_IN)).unwrap().to_rgba();
+    let output = std::fs::read(setup_output_path("raw_rgba8_out.rgba")).unwrap();
+    assert_eq!(output, expected.into_raw());
+}
+
+#[test]
+fn write_raw_rgba8_with_raw_extension() {
+    let process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("raw_rgba8_out.raw")
+        .with_args(["--output-format", "raw-rgba8"])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stderr, ..
+    } = output_of(process);
+    assert!(success);
+    assert_not!(stderr.contains("--fix-extension"));
+
+    let expected = image::open(setup_input_path(DEFAULT_IN)).unwrap().to_rgba();
+    let output = std::fs::read(setup_output_path("raw_rgba8_out.raw")).unwrap();
+    assert_eq!(output, expected.into_raw());
+}
+
+#[test]
+fn read_raw_rgba8() {
+    let pixels = image::open(setup_input_path(DEFAULT_IN)).unwrap().to_rgba();
+    let input = setup_output_path("raw_rgba8_in.rgba");
+    std::fs::write(&input, pixels.as_raw()).unwrap();
+
+    let mut process = SicTestCommandBuilder::new()
+        .input(input)
+        .output_in_target("raw_rgba8_in.png")
+        .with_args(["--input-format", "raw-rgba8", "--input-size", "8x6"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let output = image::open(setup_output_path("raw_rgba8_in.png"))
+        .unwrap()
+        .to_rgba();
+    assert_eq!(output, pixels);
+}
+
+#[test]
+fn pipe_raw_rgba8() {
+    let pixels = image::open(setup_input_path(DEFAULT_IN)).unwrap().to_rgba();
+
+    let mut process = SicTestCommandBuilder::new()
+        .with_args(["--input-format", "raw-rgba8", "--input-size", "8x6"])
+        .with_args(["--output-format", "raw-rgba8", "--flip-horizontal"])
+        .spawn_child_with_stdin(Stdio::piped());
+    process
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(pixels.as_raw())
+        .unwrap();
+    let output = process.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let expected = image::imageops::flip_horizontal(&pixels);
+    assert_eq!(output.stdout, expected.into_raw());
+}
+
+#[test]
+fn raw_rgba8_size_mismatch() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("raw_rgba8_size_mismatch.png")
+        .with_args(["--input-format", "raw-rgba8", "--input-size", "8x6"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(!result.unwrap().success());
+}
+
+#[test]
+fn input_format_requires_input_size() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources(DEFAULT_IN)
+        .output_in_target("raw_rgba8_without_size.png")
+        .with_args(["--input-format", "raw-rgba8"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(!result.unwrap().success());
+}
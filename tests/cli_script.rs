@@ -0,0 +1,87 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+
+#[test]
+fn fmt_script() {
+    let process = SicTestCommandBuilder::new()
+        .with_args([
+            "fmt-script",
+            setup_input_path("script/emboss.sic").to_str().unwrap(),
+        ])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert_eq!(stdout, "filter3x3 -1 -1 0 | -1 1 1 | 0 1 1;\n");
+}
+
+#[test]
+fn fmt_script_in_place() {
+    let path = setup_output_path("fmt_script_in_place.sic");
+    std::fs::write(
+        &path,
+        "BLUR  01.50;set sampling-filter Nearest;resize 10 10",
+    )
+    .unwrap();
+
+    let process = SicTestCommandBuilder::new()
+        .with_args(["fmt-script", "--in-place", path.to_str().unwrap()])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert!(stdout.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "blur 1.5;\nset sampling-filter nearest;\nresize 10 10;\n"
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn fmt_script_invalid() {
+    let path = setup_output_path("fmt_script_invalid.sic");
+    std::fs::write(&path, "blur 1 2;").unwrap();
+
+    let process = SicTestCommandBuilder::new()
+        .with_args(["fmt-script", "--in-place", path.to_str().unwrap()])
+        .spawn_child();
+
+    let ProcessOutput { success, .. } = output_of(process);
+    assert!(!success);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "blur 1 2;");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn migrate_script() {
+    let path = setup_output_path("migrate_script.sic");
+    std::fs::write(
+        &path,
+        "fliph; resize 10 10\nset resize sampling_filter nearest;flip_vertical",
+    )
+    .unwrap();
+
+    let process = SicTestCommandBuilder::new()
+        .with_args(["migrate-script", path.to_str().unwrap()])
+        .spawn_child();
+
+    let ProcessOutput {
+        success, stdout, ..
+    } = output_of(process);
+    assert!(success);
+    assert_eq!(
+        stdout,
+        "flip-horizontal;\nresize 10 10;\nset sampling-filter nearest;\nflip-vertical;\n"
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,67 @@
+#[macro_use]
+pub mod common;
+
+use crate::common::*;
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+#[test]
+fn sprite_sheet_horizontal() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("sprite_sheet_horizontal.png")
+        .with_args(["--sprite-sheet"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let frame = image::open(setup_input_path("loop.gif")).unwrap();
+    let sheet = image::open(setup_output_path("sprite_sheet_horizontal.png")).unwrap();
+    assert_eq!(sheet.dimensions(), (frame.width() * 8, frame.height()));
+
+    let manifest = std::fs::read_to_string(setup_output_path("sprite_sheet_horizontal.json"));
+    assert!(manifest.unwrap().contains("\"frames\""));
+}
+
+#[test]
+fn sprite_sheet_grid_with_operations() {
+    let manifest_path = setup_output_path("sprite_sheet_grid_manifest.json");
+
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("sprite_sheet_grid.png")
+        .with_args(["--sprite-sheet", "--sprite-sheet-columns", "3"])
+        .with_args(["--sprite-sheet-manifest", manifest_path.to_str().unwrap()])
+        .with_args(["--resize", "10", "10"])
+        .spawn_child();
+    let result = process.wait();
+    assert!(result.unwrap().success());
+
+    let sheet = image::open(setup_output_path("sprite_sheet_grid.png")).unwrap();
+    assert_eq!(sheet.dimensions(), (30, 30));
+
+    let manifest = std::fs::read_to_string(manifest_path).unwrap();
+    assert!(manifest.contains("\"columns\": 3"));
+}
+
+#[test]
+fn sprite_sheet_columns_zero() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("sprite_sheet_columns_zero.png")
+        .with_args(["--sprite-sheet", "--sprite-sheet-columns", "0"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
+
+#[test]
+fn sprite_sheet_conflicts_with_select_frame() {
+    let mut process = SicTestCommandBuilder::new()
+        .input_from_resources("loop.gif")
+        .output_in_target("sprite_sheet_select_frame.png")
+        .with_args(["--sprite-sheet", "--select-frame", "1"])
+        .spawn_child();
+    let result = process.wait();
+    assert_not!(result.unwrap().success());
+}
@@ -88,6 +88,12 @@ impl SicTestCommandBuilder {
     }
 
     pub fn spawn_child(self) -> Child {
+        self.spawn_child_with_stdin(Stdio::inherit())
+    }
+
+    /// Like [spawn_child], but the stdin of the child process is given, e.g. to pipe an input
+    /// image to it.
+    pub fn spawn_child_with_stdin(self, stdin: Stdio) -> Child {
         let mut command = Command::new("cargo");
         command.arg("run");
 
@@ -101,6 +107,7 @@ impl SicTestCommandBuilder {
         command.args(self.commands);
 
         command
+            .stdin(stdin)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
@@ -143,6 +150,26 @@ pub fn setup_output_path(test_output_path: &str) -> PathBuf {
 
 pub const DEFAULT_IN: &str = "rainbow_8x6.bmp";
 
+/// What a finished sic process reported: whether it succeeded, and what it wrote to its stdout
+/// and stderr.
+pub struct ProcessOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Waits for the process to finish. Its stdout and stderr are read while waiting, so a process
+/// which writes more than fits in a pipe doesn't block.
+pub fn output_of(process: Child) -> ProcessOutput {
+    let output = process.wait_with_output().unwrap();
+
+    ProcessOutput {
+        success: output.status.success(),
+        stdout: String::from_utf8(output.stdout).unwrap(),
+        stderr: String::from_utf8(output.stderr).unwrap(),
+    }
+}
+
 #[allow(unused)]
 macro_rules! assert_not {
     ($e:expr) => {